@@ -7,6 +7,7 @@ use crate::lexer::position::{Position, Span};
 use crate::lexer::token::{FloatSuffix, IntBase, IntSuffix};
 
 pub mod dump;
+pub mod format;
 pub mod visit;
 
 #[derive(Clone, Debug)]
@@ -93,14 +94,47 @@ impl Ast {
     pub fn const0(&self) -> &Const {
         self.files.last().unwrap().elements[0].to_const().unwrap()
     }
+
+    #[cfg(test)]
+    pub fn alias0(&self) -> &Alias {
+        self.files.last().unwrap().elements[0].to_alias().unwrap()
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct File {
     pub path: String,
+    pub package: Option<Package>,
+    pub imports: Vec<Import>,
     pub elements: Vec<Elem>,
 }
 
+// `package a::b;` puts all declarations of the file into the namespace `a::b`,
+// files without it belong to the root namespace together with the stdlib
+#[derive(Clone, Debug)]
+pub struct Package {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+    pub path: Vec<Name>,
+}
+
+// `import a::b::C;` or `import a::b::{C, d};`
+#[derive(Clone, Debug)]
+pub struct Import {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+    pub path: Vec<Name>,
+    pub names: Vec<ImportName>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportName {
+    pub pos: Position,
+    pub name: Name,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub struct NodeId(pub usize);
 
@@ -121,6 +155,7 @@ pub enum Elem {
     ElemGlobal(Global),
     ElemConst(Const),
     ElemEnum(Enum),
+    ElemAlias(Alias),
 }
 
 impl Elem {
@@ -135,6 +170,22 @@ impl Elem {
             &ElemGlobal(ref g) => g.id,
             &ElemConst(ref c) => c.id,
             &ElemEnum(ref e) => e.id,
+            &ElemAlias(ref a) => a.id,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            &ElemFunction(ref fct) => fct.span,
+            &ElemClass(ref class) => class.span,
+            &ElemStruct(ref s) => s.span,
+            &ElemTrait(ref t) => t.span,
+            &ElemImpl(ref i) => i.span,
+            &ElemModule(ref m) => m.span,
+            &ElemGlobal(ref g) => g.span,
+            &ElemConst(ref c) => c.span,
+            &ElemEnum(ref e) => e.span,
+            &ElemAlias(ref a) => a.span,
         }
     }
 
@@ -200,6 +251,13 @@ impl Elem {
             _ => None,
         }
     }
+
+    pub fn to_alias(&self) -> Option<&Alias> {
+        match self {
+            &ElemAlias(ref alias) => Some(alias),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -208,6 +266,7 @@ pub struct Global {
     pub pos: Position,
     pub span: Span,
     pub name: Name,
+    pub is_pub: bool,
     pub reassignable: bool,
     pub data_type: Type,
     pub initializer: Option<Function>,
@@ -219,16 +278,29 @@ pub struct Const {
     pub pos: Position,
     pub span: Span,
     pub name: Name,
+    pub is_pub: bool,
     pub data_type: Type,
     pub expr: Box<Expr>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Alias {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+    pub name: Name,
+    pub is_pub: bool,
+    pub type_params: Option<Vec<TypeParam>>,
+    pub ty: Type,
+}
+
 #[derive(Clone, Debug)]
 pub struct Enum {
     pub id: NodeId,
     pub pos: Position,
     pub span: Span,
     pub name: Name,
+    pub is_pub: bool,
     pub type_params: Option<Vec<TypeParam>>,
     pub variants: Vec<EnumVariant>,
 }
@@ -248,6 +320,7 @@ pub struct Struct {
     pub pos: Position,
     pub span: Span,
     pub name: Name,
+    pub is_pub: bool,
     pub fields: Vec<StructField>,
 }
 
@@ -266,6 +339,7 @@ pub enum Type {
     TypeBasic(TypeBasicType),
     TypeTuple(TypeTupleType),
     TypeLambda(TypeLambdaType),
+    TypeNullable(TypeNullableType),
 }
 
 #[derive(Clone, Debug)]
@@ -294,6 +368,16 @@ pub struct TypeLambdaType {
     pub ret: Box<Type>,
 }
 
+// `T?`: a reference to `T` that may also be nil
+#[derive(Clone, Debug)]
+pub struct TypeNullableType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub ty: Box<Type>,
+}
+
 #[derive(Clone, Debug)]
 pub struct TypeBasicType {
     pub id: NodeId,
@@ -350,6 +434,10 @@ impl Type {
         })
     }
 
+    pub fn create_nullable(id: NodeId, pos: Position, span: Span, ty: Box<Type>) -> Type {
+        Type::TypeNullable(TypeNullableType { id, pos, span, ty })
+    }
+
     pub fn to_basic(&self) -> Option<&TypeBasicType> {
         match *self {
             Type::TypeBasic(ref val) => Some(val),
@@ -385,6 +473,13 @@ impl Type {
         }
     }
 
+    pub fn to_nullable(&self) -> Option<&TypeNullableType> {
+        match *self {
+            Type::TypeNullable(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
     #[cfg(test)]
     pub fn is_unit(&self) -> bool {
         match self {
@@ -411,6 +506,8 @@ impl Type {
 
                 format!("({}) -> {}", types.join(", "), ret)
             }
+
+            Type::TypeNullable(ref val) => format!("{}?", val.ty.to_string(interner)),
         }
     }
 
@@ -420,6 +517,7 @@ impl Type {
             Type::TypeBasic(ref val) => val.pos,
             Type::TypeTuple(ref val) => val.pos,
             Type::TypeLambda(ref val) => val.pos,
+            Type::TypeNullable(ref val) => val.pos,
         }
     }
 
@@ -429,6 +527,7 @@ impl Type {
             Type::TypeBasic(ref val) => val.id,
             Type::TypeTuple(ref val) => val.id,
             Type::TypeLambda(ref val) => val.id,
+            Type::TypeNullable(ref val) => val.id,
         }
     }
 }
@@ -451,6 +550,8 @@ pub struct Trait {
     pub name: Name,
     pub pos: Position,
     pub span: Span,
+    pub is_pub: bool,
+    pub type_params: Option<Vec<TypeParam>>,
     pub methods: Vec<Function>,
 }
 
@@ -463,6 +564,7 @@ pub struct Class {
     pub parent_class: Option<ParentClass>,
     pub has_open: bool,
     pub is_abstract: bool,
+    pub is_pub: bool,
     pub internal: bool,
     pub has_constructor: bool,
 
@@ -478,7 +580,9 @@ pub struct Module {
     pub id: NodeId,
     pub name: Name,
     pub pos: Position,
+    pub span: Span,
     pub parent_class: Option<ParentClass>,
+    pub is_pub: bool,
     pub internal: bool,
     pub has_constructor: bool,
 
@@ -664,6 +768,8 @@ pub enum Stmt {
     StmtContinue(StmtContinueType),
     StmtReturn(StmtReturnType),
     StmtFor(StmtForType),
+    StmtThrow(StmtThrowType),
+    StmtTry(StmtTryType),
 }
 
 impl Stmt {
@@ -752,6 +858,35 @@ impl Stmt {
         })
     }
 
+    pub fn create_throw(id: NodeId, pos: Position, span: Span, expr: Box<Expr>) -> Stmt {
+        Stmt::StmtThrow(StmtThrowType {
+            id,
+            pos,
+            span,
+
+            expr,
+        })
+    }
+
+    pub fn create_try(
+        id: NodeId,
+        pos: Position,
+        span: Span,
+        try_block: Box<Stmt>,
+        catch_blocks: Vec<CatchBlock>,
+        finally_block: Option<FinallyBlock>,
+    ) -> Stmt {
+        Stmt::StmtTry(StmtTryType {
+            id,
+            pos,
+            span,
+
+            try_block,
+            catch_blocks,
+            finally_block,
+        })
+    }
+
     pub fn id(&self) -> NodeId {
         match *self {
             Stmt::StmtVar(ref stmt) => stmt.id,
@@ -761,6 +896,8 @@ impl Stmt {
             Stmt::StmtBreak(ref stmt) => stmt.id,
             Stmt::StmtContinue(ref stmt) => stmt.id,
             Stmt::StmtReturn(ref stmt) => stmt.id,
+            Stmt::StmtThrow(ref stmt) => stmt.id,
+            Stmt::StmtTry(ref stmt) => stmt.id,
         }
     }
 
@@ -773,6 +910,8 @@ impl Stmt {
             Stmt::StmtBreak(ref stmt) => stmt.pos,
             Stmt::StmtContinue(ref stmt) => stmt.pos,
             Stmt::StmtReturn(ref stmt) => stmt.pos,
+            Stmt::StmtThrow(ref stmt) => stmt.pos,
+            Stmt::StmtTry(ref stmt) => stmt.pos,
         }
     }
 
@@ -785,6 +924,8 @@ impl Stmt {
             Stmt::StmtBreak(ref stmt) => stmt.span,
             Stmt::StmtContinue(ref stmt) => stmt.span,
             Stmt::StmtReturn(ref stmt) => stmt.span,
+            Stmt::StmtThrow(ref stmt) => stmt.span,
+            Stmt::StmtTry(ref stmt) => stmt.span,
         }
     }
 
@@ -885,6 +1026,34 @@ impl Stmt {
             _ => false,
         }
     }
+
+    pub fn to_throw(&self) -> Option<&StmtThrowType> {
+        match *self {
+            Stmt::StmtThrow(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_throw(&self) -> bool {
+        match *self {
+            Stmt::StmtThrow(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_try(&self) -> Option<&StmtTryType> {
+        match *self {
+            Stmt::StmtTry(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_try(&self) -> bool {
+        match *self {
+            Stmt::StmtTry(_) => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -953,6 +1122,69 @@ pub struct StmtContinueType {
     pub span: Span,
 }
 
+#[derive(Clone, Debug)]
+pub struct StmtThrowType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub expr: Box<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StmtTryType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub try_block: Box<Stmt>,
+    pub catch_blocks: Vec<CatchBlock>,
+    pub finally_block: Option<FinallyBlock>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CatchBlock {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub name: Name,
+    pub data_type: Type,
+    pub block: Box<Stmt>,
+}
+
+impl CatchBlock {
+    pub fn new(
+        id: NodeId,
+        pos: Position,
+        span: Span,
+        name: Name,
+        data_type: Type,
+        block: Box<Stmt>,
+    ) -> CatchBlock {
+        CatchBlock {
+            id,
+            pos,
+            span,
+
+            name,
+            data_type,
+            block,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FinallyBlock {
+    pub block: Box<Stmt>,
+}
+
+impl FinallyBlock {
+    pub fn new(block: Box<Stmt>) -> FinallyBlock {
+        FinallyBlock { block }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum UnOp {
     Plus,
@@ -1075,7 +1307,9 @@ pub enum Expr {
     ExprLambda(ExprLambdaType),
     ExprBlock(ExprBlockType),
     ExprIf(ExprIfType),
+    ExprMatch(ExprMatchType),
     ExprTuple(ExprTupleType),
+    ExprLitStruct(ExprLitStructType),
 }
 
 impl Expr {
@@ -1115,6 +1349,23 @@ impl Expr {
         })
     }
 
+    pub fn create_match(
+        id: NodeId,
+        pos: Position,
+        span: Span,
+        expr: Box<Expr>,
+        cases: Vec<MatchCaseType>,
+    ) -> Expr {
+        Expr::ExprMatch(ExprMatchType {
+            id,
+            pos,
+            span,
+
+            expr,
+            cases,
+        })
+    }
+
     pub fn create_un(id: NodeId, pos: Position, span: Span, op: UnOp, opnd: Box<Expr>) -> Expr {
         Expr::ExprUn(ExprUnType {
             id,
@@ -1347,31 +1598,32 @@ impl Expr {
         })
     }
 
-    pub fn create_lambda(
-        id: NodeId,
-        pos: Position,
-        span: Span,
-        params: Vec<Param>,
-        ret: Option<Box<Type>>,
-        block: Box<Stmt>,
-    ) -> Expr {
-        Expr::ExprLambda(ExprLambdaType {
+    pub fn create_lambda(id: NodeId, pos: Position, span: Span, fct: Function) -> Expr {
+        Expr::ExprLambda(ExprLambdaType { id, pos, span, fct })
+    }
+
+    pub fn create_tuple(id: NodeId, pos: Position, span: Span, values: Vec<Box<Expr>>) -> Expr {
+        Expr::ExprTuple(ExprTupleType {
             id,
             pos,
             span,
-
-            params,
-            ret,
-            block,
+            values,
         })
     }
 
-    pub fn create_tuple(id: NodeId, pos: Position, span: Span, values: Vec<Box<Expr>>) -> Expr {
-        Expr::ExprTuple(ExprTupleType {
+    pub fn create_lit_struct(
+        id: NodeId,
+        pos: Position,
+        span: Span,
+        path: Name,
+        args: Vec<StructArg>,
+    ) -> Expr {
+        Expr::ExprLitStruct(ExprLitStructType {
             id,
             pos,
             span,
-            values,
+            path,
+            args,
         })
     }
 
@@ -1648,6 +1900,20 @@ impl Expr {
         }
     }
 
+    pub fn to_lit_struct(&self) -> Option<&ExprLitStructType> {
+        match *self {
+            Expr::ExprLitStruct(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_lit_struct(&self) -> bool {
+        match *self {
+            Expr::ExprLitStruct(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn to_block(&self) -> Option<&ExprBlockType> {
         match *self {
             Expr::ExprBlock(ref val) => Some(val),
@@ -1676,10 +1942,25 @@ impl Expr {
         }
     }
 
+    pub fn to_match(&self) -> Option<&ExprMatchType> {
+        match *self {
+            Expr::ExprMatch(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_match(&self) -> bool {
+        match *self {
+            Expr::ExprMatch(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn needs_semicolon(&self) -> bool {
         match self {
             &Expr::ExprBlock(_) => false,
             &Expr::ExprIf(_) => false,
+            &Expr::ExprMatch(_) => false,
             _ => true,
         }
     }
@@ -1707,7 +1988,9 @@ impl Expr {
             Expr::ExprLambda(ref val) => val.pos,
             Expr::ExprBlock(ref val) => val.pos,
             Expr::ExprIf(ref val) => val.pos,
+            Expr::ExprMatch(ref val) => val.pos,
             Expr::ExprTuple(ref val) => val.pos,
+            Expr::ExprLitStruct(ref val) => val.pos,
         }
     }
 
@@ -1734,7 +2017,9 @@ impl Expr {
             Expr::ExprLambda(ref val) => val.span,
             Expr::ExprBlock(ref val) => val.span,
             Expr::ExprIf(ref val) => val.span,
+            Expr::ExprMatch(ref val) => val.span,
             Expr::ExprTuple(ref val) => val.span,
+            Expr::ExprLitStruct(ref val) => val.span,
         }
     }
 
@@ -1761,7 +2046,9 @@ impl Expr {
             Expr::ExprLambda(ref val) => val.id,
             Expr::ExprBlock(ref val) => val.id,
             Expr::ExprIf(ref val) => val.id,
+            Expr::ExprMatch(ref val) => val.id,
             Expr::ExprTuple(ref val) => val.id,
+            Expr::ExprLitStruct(ref val) => val.id,
         }
     }
 }
@@ -1777,6 +2064,83 @@ pub struct ExprIfType {
     pub else_block: Option<Box<Expr>>,
 }
 
+#[derive(Clone, Debug)]
+pub struct ExprMatchType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub expr: Box<Expr>,
+    pub cases: Vec<MatchCaseType>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchCaseType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub pattern: MatchPattern,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub enum MatchPattern {
+    Underscore(MatchPatternUnderscoreType),
+    Literal(Box<Expr>),
+    Variant(MatchPatternVariantType),
+}
+
+impl MatchPattern {
+    pub fn pos(&self) -> Position {
+        match *self {
+            MatchPattern::Underscore(ref val) => val.pos,
+            MatchPattern::Literal(ref val) => val.pos(),
+            MatchPattern::Variant(ref val) => val.pos,
+        }
+    }
+
+    pub fn to_variant(&self) -> Option<&MatchPatternVariantType> {
+        match *self {
+            MatchPattern::Variant(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_underscore(&self) -> bool {
+        match *self {
+            MatchPattern::Underscore(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchPatternUnderscoreType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchPatternVariantType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub path: Vec<Name>,
+    pub params: Option<Vec<MatchPatternParam>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchPatternParam {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub name: Option<Name>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ExprTupleType {
     pub id: NodeId,
@@ -1786,6 +2150,26 @@ pub struct ExprTupleType {
     pub values: Vec<Box<Expr>>,
 }
 
+#[derive(Clone, Debug)]
+pub struct ExprLitStructType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub path: Name,
+    pub args: Vec<StructArg>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StructArg {
+    pub id: NodeId,
+    pub pos: Position,
+    pub span: Span,
+
+    pub name: Name,
+    pub value: Box<Expr>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ExprConvType {
     pub id: NodeId,
@@ -1932,9 +2316,8 @@ pub struct ExprLambdaType {
     pub pos: Position,
     pub span: Span,
 
-    pub params: Vec<Param>,
-    pub ret: Option<Box<Type>>,
-    pub block: Box<Stmt>,
+    // the lambda body is represented as an anonymous function
+    pub fct: Function,
 }
 
 #[derive(Clone, Debug)]
@@ -1965,6 +2348,19 @@ impl ExprCallType {
     pub fn object_or_callee(&self) -> &Expr {
         self.object().unwrap_or(&self.callee)
     }
+
+    // receiver of a method call, for `Module::method()` this is the module
+    pub fn receiver(&self) -> Option<&Expr> {
+        let callee = match self.callee.to_type_param() {
+            Some(type_param) => &type_param.callee,
+            None => &self.callee,
+        };
+
+        match callee.to_path() {
+            Some(path) => Some(&path.lhs),
+            None => self.object(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -78,6 +78,7 @@ impl<'a> AstDumper<'a> {
                 ElemGlobal(ref global) => self.dump_global(global),
                 ElemConst(ref xconst) => self.dump_const(xconst),
                 ElemEnum(ref xenum) => self.dump_enum(xenum),
+                ElemAlias(ref alias) => self.dump_alias(alias),
             }
         }
     }
@@ -117,6 +118,18 @@ impl<'a> AstDumper<'a> {
         });
     }
 
+    fn dump_alias(&mut self, alias: &Alias) {
+        dump!(
+            self,
+            "type {} @ {} {}",
+            self.str(alias.name),
+            alias.pos,
+            alias.id
+        );
+
+        self.indent(|d| d.dump_type(&alias.ty));
+    }
+
     fn dump_enum(&mut self, xenum: &Enum) {
         dump!(
             self,
@@ -359,6 +372,8 @@ impl<'a> AstDumper<'a> {
             StmtVar(ref stmt) => self.dump_stmt_var(stmt),
             StmtWhile(ref stmt) => self.dump_stmt_while(stmt),
             StmtFor(ref stmt) => self.dump_stmt_for(stmt),
+            StmtThrow(ref stmt) => self.dump_stmt_throw(stmt),
+            StmtTry(ref stmt) => self.dump_stmt_try(stmt),
         }
     }
 
@@ -451,6 +466,45 @@ impl<'a> AstDumper<'a> {
         dump!(self, "continue @ {} {}", stmt.pos, stmt.id);
     }
 
+    fn dump_stmt_throw(&mut self, stmt: &StmtThrowType) {
+        dump!(self, "throw @ {} {}", stmt.pos, stmt.id);
+        self.indent(|d| {
+            d.dump_expr(&stmt.expr);
+        });
+    }
+
+    fn dump_stmt_try(&mut self, stmt: &StmtTryType) {
+        dump!(self, "try @ {} {}", stmt.pos, stmt.id);
+
+        self.indent(|d| {
+            dump!(d, "try");
+            d.indent(|d| {
+                d.dump_stmt(&stmt.try_block);
+            });
+
+            for catch in &stmt.catch_blocks {
+                dump!(
+                    d,
+                    "catch {} @ {} {}",
+                    d.str(catch.name),
+                    catch.pos,
+                    catch.id
+                );
+                d.indent(|d| {
+                    d.dump_type(&catch.data_type);
+                    d.dump_stmt(&catch.block);
+                });
+            }
+
+            if let Some(ref finally_block) = stmt.finally_block {
+                dump!(d, "finally");
+                d.indent(|d| {
+                    d.dump_stmt(&finally_block.block);
+                });
+            }
+        });
+    }
+
     fn dump_expr(&mut self, expr: &Expr) {
         match *expr {
             ExprUn(ref un) => self.dump_expr_un(un),
@@ -474,7 +528,9 @@ impl<'a> AstDumper<'a> {
             ExprLambda(ref expr) => self.dump_expr_lambda(expr),
             ExprBlock(ref expr) => self.dump_expr_block(expr),
             ExprIf(ref expr) => self.dump_expr_if(expr),
+            ExprMatch(ref expr) => self.dump_expr_match(expr),
             ExprTuple(ref expr) => self.dump_expr_tuple(expr),
+            ExprLitStruct(ref expr) => self.dump_expr_lit_struct(expr),
         }
     }
 
@@ -523,6 +579,53 @@ impl<'a> AstDumper<'a> {
         });
     }
 
+    fn dump_expr_match(&mut self, expr: &ExprMatchType) {
+        dump!(self, "match @ {} {}", expr.pos, expr.id);
+
+        self.indent(|d| {
+            d.indent(|d| {
+                d.dump_expr(&expr.expr);
+            });
+
+            for case in &expr.cases {
+                dump!(d, "case @ {} {}", case.pos, case.id);
+                d.indent(|d| {
+                    match case.pattern {
+                        MatchPattern::Underscore(ref pattern) => {
+                            dump!(d, "_ @ {} {}", pattern.pos, pattern.id);
+                        }
+
+                        MatchPattern::Literal(ref lit) => d.dump_expr(lit),
+
+                        MatchPattern::Variant(ref pattern) => {
+                            let path = pattern
+                                .path
+                                .iter()
+                                .map(|&name| d.str(name).to_string())
+                                .collect::<Vec<_>>()
+                                .join("::");
+                            dump!(d, "variant {} @ {} {}", path, pattern.pos, pattern.id);
+
+                            if let Some(ref params) = pattern.params {
+                                d.indent(|d| {
+                                    for param in params {
+                                        let name = match param.name {
+                                            Some(name) => d.str(name).to_string(),
+                                            None => "_".into(),
+                                        };
+                                        dump!(d, "param {} @ {} {}", name, param.pos, param.id);
+                                    }
+                                });
+                            }
+                        }
+                    }
+
+                    d.dump_expr(&case.value);
+                });
+            }
+        });
+    }
+
     fn dump_expr_conv(&mut self, expr: &ExprConvType) {
         self.indent(|d| d.dump_expr(&expr.object));
         let op = if expr.is { "is" } else { "as" };
@@ -611,7 +714,7 @@ impl<'a> AstDumper<'a> {
 
     fn dump_expr_lambda(&mut self, expr: &ExprLambdaType) {
         dump!(self, "lambda @ {} {}", expr.pos, expr.id);
-        self.indent(|d| d.dump_stmt(&expr.block));
+        self.indent(|d| d.dump_expr_block(expr.fct.block()));
     }
 
     fn dump_expr_tuple(&mut self, expr: &ExprTupleType) {
@@ -623,6 +726,22 @@ impl<'a> AstDumper<'a> {
         });
     }
 
+    fn dump_expr_lit_struct(&mut self, expr: &ExprLitStructType) {
+        dump!(
+            self,
+            "struct {} @ {} {}",
+            self.str(expr.path),
+            expr.pos,
+            expr.id
+        );
+        self.indent(|d| {
+            for arg in &expr.args {
+                dump!(d, "arg {} @ {} {}", d.str(arg.name), arg.pos, arg.id);
+                d.indent(|d| d.dump_expr(&arg.value));
+            }
+        });
+    }
+
     fn dump_expr_dot(&mut self, expr: &ExprDotType) {
         self.indent(|d| d.dump_expr(&expr.rhs));
         dump!(self, "dot @ {} {}", expr.pos, expr.id);
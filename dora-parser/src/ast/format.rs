@@ -0,0 +1,1362 @@
+use crate::ast::*;
+
+use crate::ast::visit::{walk_expr, Visitor};
+use crate::ast::Expr::*;
+use crate::ast::Stmt::*;
+use crate::ast::Type::*;
+use crate::interner::{Interner, Name};
+use crate::lexer::lossless::TokenStream;
+use crate::lexer::position::Span;
+use crate::lexer::reader::Reader;
+use crate::lexer::token::Trivia;
+
+const INDENT: &str = "    ";
+
+// Prints the file in canonical form: four spaces of indentation, one statement
+// or member per line, single spaces around binary operators and braces on the
+// same line. Literals are copied from the original source. Comments are kept
+// and printed on the line before the next statement or member following them,
+// comments at the end of a line stay there.
+pub fn format(file: &File, content: &str, interner: &Interner) -> String {
+    // the parser skips comments, they are taken from the trivia of the
+    // lossless token stream instead
+    let tokens = TokenStream::tokenize(Reader::from_string(&file.path, content))
+        .expect("file was already parsed without errors");
+    let comments = tokens.comments();
+
+    let mut formatter = AstFormatter {
+        content,
+        interner,
+        comments: &comments,
+        next_comment: 0,
+        last_end: 0,
+        body_start: true,
+        indent: 0,
+        line_start: true,
+        out: String::new(),
+    };
+
+    formatter.format_file(file);
+
+    let mut out = formatter.out;
+    let len = out.trim_end().len();
+    out.truncate(len);
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    out
+}
+
+struct AstFormatter<'a> {
+    content: &'a str,
+    interner: &'a Interner,
+
+    comments: &'a [Trivia],
+    next_comment: usize,
+
+    // end of the last printed element in the original source
+    last_end: u32,
+    // nothing was printed yet in the current body
+    body_start: bool,
+
+    indent: usize,
+    line_start: bool,
+    out: String,
+}
+
+// output and comment state, used to undo printing a construct on a single line
+struct State {
+    len: usize,
+    next_comment: usize,
+    last_end: u32,
+    body_start: bool,
+    line_start: bool,
+}
+
+enum Member<'a> {
+    Field(&'a Field),
+    Method(&'a Function),
+    Initializer(&'a Stmt),
+}
+
+impl<'a> Member<'a> {
+    fn span(&self) -> Span {
+        match *self {
+            Member::Field(field) => field.span,
+            Member::Method(method) => method.span,
+            Member::Initializer(stmt) => stmt.span(),
+        }
+    }
+}
+
+impl<'a> AstFormatter<'a> {
+    fn format_file(&mut self, file: &File) {
+        if let Some(ref package) = file.package {
+            self.leading_trivia(package.span.start(), false);
+            self.write("package ");
+            self.format_path(&package.path);
+            self.write(";");
+            self.trailing_comment(package.span.end());
+            self.newline();
+        }
+
+        for (idx, import) in file.imports.iter().enumerate() {
+            self.leading_trivia(import.span.start(), idx == 0 && file.package.is_some());
+            self.format_import(import);
+            self.trailing_comment(import.span.end());
+            self.newline();
+        }
+
+        // the first element is separated from the package and imports by a blank line
+        let has_header = file.package.is_some() || !file.imports.is_empty();
+        let mut last_short: Option<bool> = if has_header { Some(false) } else { None };
+
+        for el in &file.elements {
+            let short = match *el {
+                ElemGlobal(_) | ElemConst(_) | ElemAlias(_) => true,
+                _ => false,
+            };
+
+            // only globals, constants and type aliases may be grouped without a blank line
+            let blank = last_short.map_or(false, |last_short| !last_short || !short);
+            last_short = Some(short);
+
+            let span = el.span();
+            self.leading_trivia(span.start(), blank);
+
+            match *el {
+                ElemFunction(ref fct) => self.format_fct(fct),
+                ElemClass(ref cls) => self.format_class(cls),
+                ElemStruct(ref struc) => self.format_struct(struc),
+                ElemTrait(ref xtrait) => self.format_trait(xtrait),
+                ElemImpl(ref ximpl) => self.format_impl(ximpl),
+                ElemModule(ref module) => self.format_module(module),
+                ElemGlobal(ref global) => self.format_global(global),
+                ElemConst(ref xconst) => self.format_const(xconst),
+                ElemEnum(ref xenum) => self.format_enum(xenum),
+                ElemAlias(ref alias) => self.format_alias(alias),
+            }
+
+            self.trailing_comment(span.end());
+            self.newline();
+        }
+
+        self.leading_trivia(self.content.len() as u32, false);
+    }
+
+    fn format_import(&mut self, import: &Import) {
+        self.write("import ");
+        self.format_path(&import.path);
+        self.write("::");
+
+        if import.names.len() == 1 {
+            self.write_name(import.names[0].name);
+        } else {
+            self.write("{");
+            self.format_list(&import.names, |f, name| f.write_name(name.name));
+            self.write("}");
+        }
+
+        self.write(";");
+    }
+
+    fn format_path(&mut self, path: &[Name]) {
+        for (idx, &name) in path.iter().enumerate() {
+            if idx > 0 {
+                self.write("::");
+            }
+
+            self.write_name(name);
+        }
+    }
+
+    fn format_global(&mut self, global: &Global) {
+        self.format_annotations(&[(global.is_pub, "pub")]);
+        self.write(if global.reassignable { "var " } else { "let " });
+        self.write_name(global.name);
+        self.write(": ");
+        self.format_type(&global.data_type);
+
+        // the initial value is wrapped into an assignment in a generated function
+        let initializer = global
+            .initializer
+            .as_ref()
+            .and_then(|fct| fct.block().stmts.first())
+            .and_then(|stmt| stmt.to_expr())
+            .and_then(|stmt| stmt.expr.to_bin());
+
+        if let Some(assignment) = initializer {
+            self.write(" = ");
+            self.format_expr(&assignment.rhs);
+        }
+
+        self.write(";");
+    }
+
+    fn format_const(&mut self, xconst: &Const) {
+        self.format_annotations(&[(xconst.is_pub, "pub")]);
+        self.write("const ");
+        self.write_name(xconst.name);
+        self.write(": ");
+        self.format_type(&xconst.data_type);
+        self.write(" = ");
+        self.format_expr(&xconst.expr);
+        self.write(";");
+    }
+
+    fn format_alias(&mut self, alias: &Alias) {
+        self.format_annotations(&[(alias.is_pub, "pub")]);
+        self.write("type ");
+        self.write_name(alias.name);
+        self.format_type_params(&alias.type_params);
+        self.write(" = ");
+        self.format_type(&alias.ty);
+        self.write(";");
+    }
+
+    fn format_enum(&mut self, xenum: &Enum) {
+        self.format_annotations(&[(xenum.is_pub, "pub")]);
+        self.write("enum ");
+        self.write_name(xenum.name);
+        self.format_type_params(&xenum.type_params);
+        self.write(" ");
+
+        let variant_span = |variant: &EnumVariant| variant.span;
+
+        self.format_body(xenum.span, &xenum.variants, variant_span, |f, variant| {
+            f.write_name(variant.name);
+
+            if let Some(ref types) = variant.types {
+                f.write("(");
+                f.format_list(types, |f, ty| f.format_type(ty));
+                f.write(")");
+            }
+
+            f.write(",");
+        });
+    }
+
+    fn format_struct(&mut self, struc: &Struct) {
+        self.format_annotations(&[(struc.is_pub, "pub")]);
+        self.write("struct ");
+        self.write_name(struc.name);
+        self.write(" ");
+
+        let field_span = |field: &StructField| field.span;
+
+        self.format_body(struc.span, &struc.fields, field_span, |f, field| {
+            f.write_name(field.name);
+            f.write(": ");
+            f.format_type(&field.data_type);
+            f.write(",");
+        });
+    }
+
+    fn format_trait(&mut self, xtrait: &Trait) {
+        self.format_annotations(&[(xtrait.is_pub, "pub")]);
+        self.write("trait ");
+        self.write_name(xtrait.name);
+        self.format_type_params(&xtrait.type_params);
+        self.write(" ");
+
+        let method_span = |method: &Function| method.span;
+        self.format_body(xtrait.span, &xtrait.methods, method_span, Self::format_fct);
+    }
+
+    fn format_impl(&mut self, ximpl: &Impl) {
+        self.write("impl");
+        self.format_type_params(&ximpl.type_params);
+        self.write(" ");
+
+        if let Some(ref trait_type) = ximpl.trait_type {
+            self.format_type(trait_type);
+            self.write(" for ");
+        }
+
+        self.format_type(&ximpl.class_type);
+        self.write(" ");
+
+        let method_span = |method: &Function| method.span;
+        self.format_body(ximpl.span, &ximpl.methods, method_span, Self::format_fct);
+    }
+
+    fn format_class(&mut self, cls: &Class) {
+        let ctor = cls.constructor.as_ref();
+
+        self.format_annotations(&[
+            (cls.internal, "internal"),
+            (cls.is_pub, "pub"),
+            (cls.has_open, "open"),
+            (cls.is_abstract, "abstract"),
+            (ctor.map_or(false, |ctor| ctor.use_cannon), "cannon"),
+        ]);
+
+        self.write("class ");
+        self.write_name(cls.name);
+        self.format_type_params(&cls.type_params);
+
+        if let Some(ctor) = ctor.filter(|_| cls.has_constructor) {
+            self.write("(");
+            self.format_list(&ctor.params, |f, param| {
+                let field = cls
+                    .fields
+                    .iter()
+                    .find(|field| field.primary_ctor && field.name == param.name);
+
+                if let Some(field) = field {
+                    f.write(if field.reassignable { "var " } else { "let " });
+                }
+
+                f.format_param(param);
+            });
+            self.write(")");
+        }
+
+        self.format_parent_class(&cls.parent_class);
+
+        let mut members = Vec::new();
+
+        for field in cls.fields.iter().filter(|field| !field.primary_ctor) {
+            members.push(Member::Field(field));
+        }
+
+        for method in &cls.methods {
+            members.push(Member::Method(method));
+        }
+
+        // initializers were moved into the constructor, all other statements
+        // in there are generated and have no position in the source
+        if let Some(ctor) = ctor {
+            for stmt in &ctor.block().stmts {
+                if stmt.span().is_valid() {
+                    members.push(Member::Initializer(stmt));
+                }
+            }
+        }
+
+        self.format_members(cls.span, members);
+    }
+
+    fn format_module(&mut self, module: &Module) {
+        self.format_annotations(&[(module.internal, "internal"), (module.is_pub, "pub")]);
+        self.write("module ");
+        self.write_name(module.name);
+        self.format_parent_class(&module.parent_class);
+
+        let mut members = Vec::new();
+
+        for field in &module.fields {
+            members.push(Member::Field(field));
+        }
+
+        for method in &module.methods {
+            members.push(Member::Method(method));
+        }
+
+        // initializers were moved into the constructor, see classes
+        if let Some(ref ctor) = module.constructor {
+            for stmt in &ctor.block().stmts {
+                if stmt.span().is_valid() {
+                    members.push(Member::Initializer(stmt));
+                }
+            }
+        }
+
+        self.format_members(module.span, members);
+    }
+
+    fn format_parent_class(&mut self, parent_class: &Option<ParentClass>) {
+        if let Some(ref parent_class) = *parent_class {
+            self.write(": ");
+            self.write_name(parent_class.name);
+
+            if !parent_class.type_params.is_empty() {
+                self.write("[");
+                self.format_list(&parent_class.type_params, |f, ty| f.format_type(ty));
+                self.write("]");
+            }
+
+            if !parent_class.params.is_empty() {
+                self.format_args(&parent_class.params);
+            }
+        }
+    }
+
+    // the body of classes and modules is optional and left out when empty
+    fn format_members(&mut self, span: Span, mut members: Vec<Member>) {
+        if members.is_empty() && !self.has_comments(span) {
+            return;
+        }
+
+        members.sort_by_key(|member| member.span().start());
+        self.write(" ");
+
+        self.format_body(span, &members, Member::span, |f, member| match *member {
+            Member::Field(field) => f.format_field(field),
+            Member::Method(method) => f.format_fct(method),
+            Member::Initializer(stmt) => f.format_stmt(stmt),
+        });
+    }
+
+    fn format_field(&mut self, field: &Field) {
+        self.write(if field.reassignable { "var " } else { "let " });
+        self.write_name(field.name);
+        self.write(": ");
+        self.format_type(&field.data_type);
+
+        if let Some(ref expr) = field.expr {
+            self.write(" = ");
+            self.format_expr(expr);
+        }
+
+        self.write(";");
+    }
+
+    // prints the braces and every item on a line of its own, `span` is the span
+    // of the whole element and ends with the closing brace
+    fn format_body<T, S, F>(&mut self, span: Span, items: &[T], item_span: S, mut format_item: F)
+    where
+        S: Fn(&T) -> Span,
+        F: FnMut(&mut AstFormatter<'a>, &T),
+    {
+        if items.is_empty() && !self.has_comments(span) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indent += 1;
+        self.body_start = true;
+
+        for item in items {
+            let span = item_span(item);
+            self.leading_trivia(span.start(), false);
+            format_item(self, item);
+            self.trailing_comment(span.end());
+            self.newline();
+        }
+
+        self.leading_trivia(span.end() - 1, false);
+        self.indent -= 1;
+        self.write("}");
+        self.body_start = false;
+    }
+
+    fn format_fct(&mut self, fct: &Function) {
+        self.format_annotations(&[
+            (fct.internal, "internal"),
+            (fct.is_pub, "pub"),
+            (fct.is_static, "static"),
+            (fct.has_open, "open"),
+            (fct.is_abstract, "abstract"),
+            (fct.has_override, "override"),
+            (fct.has_final, "final"),
+            (fct.has_optimize, "optimize"),
+            (fct.has_optimize_immediately, "optimize_immediately"),
+            (fct.is_test, "test"),
+            (fct.use_cannon, "cannon"),
+        ]);
+
+        self.write("fun ");
+        self.write_name(fct.name);
+        self.format_type_params(&fct.type_params);
+
+        self.write("(");
+        self.format_list(&fct.params, |f, param| {
+            if param.reassignable {
+                f.write("var ");
+            }
+
+            f.format_param(param);
+        });
+        self.write(")");
+
+        if let Some(ref ty) = fct.return_type {
+            self.write(" -> ");
+            self.format_type(ty);
+        }
+
+        match fct.block {
+            None => self.write(";"),
+
+            // `fun f() = expr;` is kept in its short form
+            Some(ref block) if self.source_at(block.span.start()) != Some('{') => {
+                self.write(" = ");
+
+                if let Some(ref expr) = block.expr {
+                    self.format_expr(expr);
+                    self.write(";");
+                } else {
+                    self.format_stmt(&block.stmts[0]);
+                }
+            }
+
+            Some(ref block) => {
+                self.write(" ");
+                self.format_block(block);
+            }
+        }
+    }
+
+    fn format_param(&mut self, param: &Param) {
+        self.write_name(param.name);
+        self.write(": ");
+        self.format_type(&param.data_type);
+
+        if param.variadic {
+            self.write("...");
+        }
+    }
+
+    fn format_annotations(&mut self, annotations: &[(bool, &str)]) {
+        for &(present, name) in annotations {
+            if present {
+                self.write("@");
+                self.write(name);
+                self.write(" ");
+            }
+        }
+    }
+
+    fn format_type_params(&mut self, type_params: &Option<Vec<TypeParam>>) {
+        if let Some(ref type_params) = *type_params {
+            self.write("[");
+            self.format_list(type_params, |f, param| {
+                f.write_name(param.name);
+
+                for (idx, bound) in param.bounds.iter().enumerate() {
+                    f.write(if idx == 0 { ": " } else { " + " });
+                    f.format_type(bound);
+                }
+            });
+            self.write("]");
+        }
+    }
+
+    fn format_type(&mut self, ty: &Type) {
+        match *ty {
+            TypeSelf(_) => self.write("Self"),
+
+            TypeBasic(ref basic) => {
+                self.write_name(basic.name);
+
+                if !basic.params.is_empty() {
+                    self.write("[");
+                    self.format_list(&basic.params, |f, ty| f.format_type(ty));
+                    self.write("]");
+                }
+            }
+
+            TypeTuple(ref tuple) => {
+                self.write("(");
+                self.format_list(&tuple.subtypes, |f, ty| f.format_type(ty));
+                self.write(")");
+            }
+
+            TypeLambda(ref lambda) => {
+                self.write("(");
+                self.format_list(&lambda.params, |f, ty| f.format_type(ty));
+                self.write(") -> ");
+                self.format_type(&lambda.ret);
+            }
+
+            TypeNullable(ref nullable) => {
+                self.format_type(&nullable.ty);
+                self.write("?");
+            }
+        }
+    }
+
+    // prints the block over multiple lines
+    fn format_block(&mut self, block: &ExprBlockType) {
+        if block.stmts.is_empty() && block.expr.is_none() && !self.has_comments(block.span) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indent += 1;
+        self.body_start = true;
+
+        for stmt in &block.stmts {
+            let span = stmt.span();
+            self.leading_trivia(span.start(), false);
+            self.format_stmt(stmt);
+            self.trailing_comment(span.end());
+            self.newline();
+        }
+
+        if let Some(ref expr) = block.expr {
+            let span = expr.span();
+            self.leading_trivia(span.start(), false);
+            self.format_stmt_expr(expr);
+            self.trailing_comment(span.end());
+            self.newline();
+        }
+
+        self.leading_trivia(block.span.end() - 1, false);
+        self.indent -= 1;
+        self.write("}");
+        self.body_start = false;
+    }
+
+    fn format_block_stmt(&mut self, stmt: &Stmt) {
+        let block = stmt
+            .to_expr()
+            .and_then(|stmt| stmt.expr.to_block())
+            .expect("block expected");
+
+        self.format_block(block);
+    }
+
+    // blocks inside of expressions are printed on a single line when they are
+    // empty or consist of a single short statement
+    fn format_nested_block(&mut self, block: &ExprBlockType) {
+        let state = self.save();
+
+        if !self.format_inline_block(block) {
+            self.restore(state);
+            self.format_block(block);
+        }
+    }
+
+    fn format_inline_block(&mut self, block: &ExprBlockType) -> bool {
+        let start = self.out.len();
+        let count = block.stmts.len() + block.expr.iter().count();
+
+        if count > 1 || self.has_comments(block.span) {
+            return false;
+        }
+
+        if count == 0 {
+            self.write("{}");
+            return true;
+        }
+
+        self.write("{ ");
+
+        for stmt in &block.stmts {
+            self.format_stmt(stmt);
+        }
+
+        if let Some(ref expr) = block.expr {
+            self.format_expr(expr);
+        }
+
+        self.write(" }");
+
+        !self.out[start..].contains('\n')
+    }
+
+    fn format_stmt(&mut self, stmt: &Stmt) {
+        match *stmt {
+            StmtVar(ref var) => {
+                self.write(if var.reassignable { "var " } else { "let " });
+                self.write_name(var.name);
+
+                if let Some(ref ty) = var.data_type {
+                    self.write(": ");
+                    self.format_type(ty);
+                }
+
+                if let Some(ref expr) = var.expr {
+                    self.write(" = ");
+                    self.format_expr(expr);
+                }
+
+                self.write(";");
+            }
+
+            StmtWhile(ref stmt) => {
+                self.write("while ");
+                self.format_cond(&stmt.cond);
+                self.write(" ");
+                self.format_block_stmt(&stmt.block);
+            }
+
+            StmtFor(ref stmt) => {
+                self.write("for ");
+                self.write_name(stmt.name);
+                self.write(" in ");
+                self.format_cond(&stmt.expr);
+                self.write(" ");
+                self.format_block_stmt(&stmt.block);
+            }
+
+            StmtExpr(ref stmt) => {
+                self.format_stmt_expr(&stmt.expr);
+
+                if stmt.expr.needs_semicolon() {
+                    self.write(";");
+                }
+            }
+
+            StmtBreak(_) => self.write("break;"),
+            StmtContinue(_) => self.write("continue;"),
+
+            StmtReturn(ref stmt) => {
+                self.write("return");
+
+                if let Some(ref expr) = stmt.expr {
+                    self.write(" ");
+                    self.format_expr(expr);
+                }
+
+                self.write(";");
+            }
+
+            StmtThrow(ref stmt) => {
+                self.write("throw ");
+                self.format_expr(&stmt.expr);
+                self.write(";");
+            }
+
+            StmtTry(ref stmt) => {
+                self.write("try ");
+                self.format_block_stmt(&stmt.try_block);
+
+                for catch in &stmt.catch_blocks {
+                    self.write(" catch ");
+                    self.write_name(catch.name);
+                    self.write(": ");
+                    self.format_type(&catch.data_type);
+                    self.write(" ");
+                    self.format_block_stmt(&catch.block);
+                }
+
+                if let Some(ref finally_block) = stmt.finally_block {
+                    self.write(" finally ");
+                    self.format_block_stmt(&finally_block.block);
+                }
+            }
+        }
+    }
+
+    // expressions used as statements always span multiple lines
+    fn format_stmt_expr(&mut self, expr: &Expr) {
+        match *expr {
+            ExprBlock(ref block) => self.format_block(block),
+            ExprIf(ref expr) => self.format_if(expr),
+            _ => self.format_expr(expr),
+        }
+    }
+
+    fn format_if(&mut self, expr: &ExprIfType) {
+        self.write("if ");
+        self.format_cond(&expr.cond);
+        self.write(" ");
+        self.format_block(expr.then_block.to_block().expect("block expected"));
+
+        if let Some(ref else_block) = expr.else_block {
+            self.write(" else ");
+
+            match **else_block {
+                ExprIf(ref else_if) => self.format_if(else_if),
+                ExprBlock(ref block) => self.format_block(block),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    fn format_inline_if(&mut self, expr: &ExprIfType) -> bool {
+        self.write("if ");
+        self.format_cond(&expr.cond);
+        self.write(" ");
+
+        if !self.format_inline_block(expr.then_block.to_block().expect("block expected")) {
+            return false;
+        }
+
+        if let Some(ref else_block) = expr.else_block {
+            self.write(" else ");
+
+            match **else_block {
+                ExprIf(ref else_if) => self.format_inline_if(else_if),
+                ExprBlock(ref block) => self.format_inline_block(block),
+                _ => unreachable!(),
+            }
+        } else {
+            true
+        }
+    }
+
+    fn format_match(&mut self, expr: &ExprMatchType) {
+        self.write("match ");
+        self.format_cond(&expr.expr);
+        self.write(" ");
+
+        let case_span = |case: &MatchCaseType| case.span;
+
+        self.format_body(expr.span, &expr.cases, case_span, |f, case| {
+            f.format_pattern(&case.pattern);
+            f.write(" => ");
+            f.format_expr(&case.value);
+            f.write(",");
+        });
+    }
+
+    fn format_pattern(&mut self, pattern: &MatchPattern) {
+        match *pattern {
+            MatchPattern::Underscore(_) => self.write("_"),
+            MatchPattern::Literal(ref expr) => self.format_expr(expr),
+
+            MatchPattern::Variant(ref variant) => {
+                for (idx, &name) in variant.path.iter().enumerate() {
+                    if idx > 0 {
+                        self.write("::");
+                    }
+
+                    self.write_name(name);
+                }
+
+                if let Some(ref params) = variant.params {
+                    self.write("(");
+                    self.format_list(params, |f, param| match param.name {
+                        Some(name) => f.write_name(name),
+                        None => f.write("_"),
+                    });
+                    self.write(")");
+                }
+            }
+        }
+    }
+
+    fn format_expr(&mut self, expr: &Expr) {
+        match *expr {
+            ExprUn(ref expr) => {
+                self.write(expr.op.as_str());
+                self.format_operand(&expr.opnd, PREC_PRIMARY);
+            }
+
+            ExprBin(ref expr) => {
+                let prec = precedence(expr.op);
+
+                self.format_left_operand(&expr.lhs, prec);
+                self.write(" ");
+                self.write(expr.op.as_str());
+                self.write(" ");
+
+                // all binary operators are left-associative
+                self.format_operand(&expr.rhs, prec + 1);
+            }
+
+            ExprConv(ref expr) => {
+                self.format_left_operand(&expr.object, PREC_CONV);
+                self.write(if expr.is { " is " } else { " as " });
+                self.format_type(&expr.data_type);
+            }
+
+            ExprLitChar(ref lit) => self.write_source(lit.span),
+            ExprLitInt(ref lit) => self.write_source(lit.span),
+            ExprLitFloat(ref lit) => self.write_source(lit.span),
+            ExprLitStr(ref lit) => self.write_source(lit.span),
+            ExprLitBool(ref lit) => self.write(if lit.value { "true" } else { "false" }),
+
+            // the literal parts include the quotes and the `${` of the following
+            // expression, only the closing brace needs to be added
+            ExprTemplate(ref tmpl) => {
+                for (idx, part) in tmpl.parts.iter().enumerate() {
+                    if idx % 2 == 0 {
+                        self.write_source(part.span());
+                    } else {
+                        self.format_expr(part);
+                        self.write("}");
+                    }
+                }
+            }
+
+            ExprIdent(ref ident) => {
+                self.write_name(ident.name);
+
+                if let Some(ref type_params) = ident.type_params {
+                    self.write("[");
+                    self.format_list(type_params, |f, ty| f.format_type(ty));
+                    self.write("]");
+                }
+            }
+
+            ExprCall(ref call) => {
+                self.format_left_operand(&call.callee, PREC_PRIMARY);
+                self.format_args(&call.args);
+            }
+
+            ExprTypeParam(ref expr) => {
+                self.format_left_operand(&expr.callee, PREC_PRIMARY);
+                self.write("[");
+                self.format_list(&expr.args, |f, ty| f.format_type(ty));
+                self.write("]");
+            }
+
+            ExprPath(ref path) => {
+                self.format_left_operand(&path.lhs, PREC_PRIMARY);
+                self.write("::");
+                self.format_expr(&path.rhs);
+            }
+
+            ExprDot(ref dot) => {
+                // `x.0.1` would be read as `x` followed by the float `0.1`
+                let tuple_index = dot.lhs.to_dot().map_or(false, |lhs| lhs.rhs.is_lit_int());
+
+                if tuple_index {
+                    self.write("(");
+                    self.format_expr(&dot.lhs);
+                    self.write(")");
+                } else {
+                    self.format_left_operand(&dot.lhs, PREC_PRIMARY);
+                }
+
+                self.write(".");
+                self.format_expr(&dot.rhs);
+            }
+
+            ExprDelegation(_) => unreachable!("delegations are only generated"),
+
+            ExprSelf(_) => self.write("self"),
+            ExprSuper(_) => self.write("super"),
+            ExprNil(_) => self.write("nil"),
+
+            ExprLambda(ref lambda) => {
+                let fct = &lambda.fct;
+
+                self.write("|");
+                self.format_list(&fct.params, |f, param| {
+                    if param.reassignable {
+                        f.write("var ");
+                    }
+
+                    f.format_param(param);
+                });
+                self.write("| ");
+
+                if let Some(ref ty) = fct.return_type {
+                    self.write("-> ");
+                    self.format_type(ty);
+                    self.write(" ");
+                }
+
+                self.format_nested_block(fct.block());
+            }
+
+            ExprBlock(ref block) => self.format_nested_block(block),
+
+            ExprIf(ref expr) => {
+                let state = self.save();
+
+                if !self.format_inline_if(expr) {
+                    self.restore(state);
+                    self.format_if(expr);
+                }
+            }
+
+            ExprMatch(ref expr) => self.format_match(expr),
+
+            ExprTuple(ref tuple) => {
+                self.write("(");
+                self.format_list(&tuple.values, |f, value| f.format_expr(value));
+
+                // a single value needs the comma to be a tuple
+                if tuple.values.len() == 1 {
+                    self.write(",");
+                }
+
+                self.write(")");
+            }
+
+            ExprLitStruct(ref lit) => {
+                self.write_name(lit.path);
+
+                if lit.args.is_empty() {
+                    self.write(" {}");
+                } else {
+                    self.write(" { ");
+                    self.format_list(&lit.args, |f, arg| {
+                        f.write_name(arg.name);
+                        f.write(": ");
+                        f.format_expr(&arg.value);
+                    });
+                    self.write(" }");
+                }
+            }
+        }
+    }
+
+    // struct literals are not parsed in conditions, the braces would start the
+    // block instead. Such conditions are wrapped in parentheses.
+    fn format_cond(&mut self, cond: &Expr) {
+        let mut finder = StructLitFinder { found: false };
+        finder.visit_expr(cond);
+
+        if finder.found {
+            self.write("(");
+            self.format_expr(cond);
+            self.write(")");
+        } else {
+            self.format_expr(cond);
+        }
+    }
+
+    // adds parentheses when the parser would not reconstruct the expression
+    // otherwise. Blocks, ifs and matches at the start of a statement are parsed
+    // without any operator following them, so they are wrapped as well when
+    // used as the left operand.
+    fn format_left_operand(&mut self, expr: &Expr, min_prec: u32) {
+        let block_like = match *expr {
+            ExprBlock(_) | ExprIf(_) | ExprMatch(_) => true,
+            _ => false,
+        };
+
+        if block_like {
+            self.write("(");
+            self.format_expr(expr);
+            self.write(")");
+        } else {
+            self.format_operand(expr, min_prec);
+        }
+    }
+
+    fn format_operand(&mut self, expr: &Expr, min_prec: u32) {
+        if expr_precedence(expr) < min_prec {
+            self.write("(");
+            self.format_expr(expr);
+            self.write(")");
+        } else {
+            self.format_expr(expr);
+        }
+    }
+
+    fn format_args(&mut self, args: &[Box<Expr>]) {
+        self.write("(");
+        self.format_list(args, |f, arg| f.format_expr(arg));
+        self.write(")");
+    }
+
+    fn format_list<T, F>(&mut self, items: &[T], mut format_item: F)
+    where
+        F: FnMut(&mut AstFormatter<'a>, &T),
+    {
+        for (idx, item) in items.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+
+            format_item(self, item);
+        }
+    }
+
+    // prints the comments in front of `start` on lines of their own, a single
+    // blank line between elements of the original source is kept
+    fn leading_trivia(&mut self, start: u32, blank: bool) {
+        let mut blank = blank;
+
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            let span = comment.span;
+
+            if span.start() >= start {
+                break;
+            }
+
+            self.separate(span.start(), blank);
+            blank = false;
+
+            self.write_comment(span);
+            self.newline();
+            self.next_comment += 1;
+            self.last_end = span.end();
+        }
+
+        self.separate(start, blank);
+    }
+
+    fn separate(&mut self, start: u32, blank: bool) {
+        if !self.body_start && (blank || self.has_blank_line(start)) {
+            self.out.push('\n');
+        }
+
+        self.body_start = false;
+    }
+
+    fn has_blank_line(&self, start: u32) -> bool {
+        if self.last_end >= start {
+            return false;
+        }
+
+        let between = &self.content[self.last_end as usize..start as usize];
+        let lines: Vec<&str> = between.split('\n').collect();
+
+        // the first and last line are shared with the surrounding elements
+        lines.len() > 2
+            && lines[1..lines.len() - 1]
+                .iter()
+                .any(|line| line.trim().is_empty())
+    }
+
+    // comments following an element on the same line are printed after it
+    fn trailing_comment(&mut self, end: u32) {
+        self.last_end = end;
+
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            let span = comment.span;
+
+            if span.start() < self.last_end {
+                break;
+            }
+
+            let between = &self.content[self.last_end as usize..span.start() as usize];
+
+            if between.contains('\n') {
+                break;
+            }
+
+            self.write(" ");
+            self.write_comment(span);
+            self.next_comment += 1;
+            self.last_end = span.end();
+        }
+    }
+
+    fn has_comments(&self, span: Span) -> bool {
+        self.comments
+            .get(self.next_comment)
+            .map_or(false, |comment| comment.span.start() < span.end())
+    }
+
+    // lines of multi-line comments are printed unchanged
+    fn write_comment(&mut self, span: Span) {
+        let text = self.content[span.start() as usize..span.end() as usize].trim_end();
+
+        for (idx, line) in text.lines().enumerate() {
+            if idx == 0 {
+                self.write(line);
+            } else {
+                self.out.push('\n');
+                self.out.push_str(line.trim_end());
+            }
+        }
+    }
+
+    fn write_source(&mut self, span: Span) {
+        let text = &self.content[span.start() as usize..span.end() as usize];
+        self.write(text);
+    }
+
+    fn write_name(&mut self, name: Name) {
+        let name = self.interner.str(name);
+        self.write(&name);
+    }
+
+    fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        self.indent_pending_line();
+        self.out.push_str(text);
+    }
+
+    fn indent_pending_line(&mut self) {
+        if self.line_start {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+
+            self.line_start = false;
+        }
+    }
+
+    fn newline(&mut self) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        self.out.push('\n');
+        self.line_start = true;
+    }
+
+    fn source_at(&self, offset: u32) -> Option<char> {
+        self.content[offset as usize..].chars().next()
+    }
+
+    fn save(&self) -> State {
+        State {
+            len: self.out.len(),
+            next_comment: self.next_comment,
+            last_end: self.last_end,
+            body_start: self.body_start,
+            line_start: self.line_start,
+        }
+    }
+
+    fn restore(&mut self, state: State) {
+        self.out.truncate(state.len);
+        self.next_comment = state.next_comment;
+        self.last_end = state.last_end;
+        self.body_start = state.body_start;
+        self.line_start = state.line_start;
+    }
+}
+
+const PREC_CONV: u32 = 10;
+const PREC_UNARY: u32 = 11;
+const PREC_PRIMARY: u32 = 12;
+
+// binding strength of the operators as used by the parser
+fn precedence(op: BinOp) -> u32 {
+    match op {
+        BinOp::Or => 1,
+        BinOp::And => 2,
+        BinOp::Assign => 3,
+        BinOp::Cmp(CmpOp::Is) | BinOp::Cmp(CmpOp::IsNot) => 5,
+        BinOp::Cmp(_) => 4,
+        BinOp::BitOr | BinOp::BitAnd | BinOp::BitXor => 6,
+        BinOp::ShiftL | BinOp::ArithShiftR | BinOp::LogicalShiftR => 7,
+        BinOp::Add | BinOp::Sub => 8,
+        BinOp::Mul | BinOp::Div | BinOp::Mod => 9,
+    }
+}
+
+fn expr_precedence(expr: &Expr) -> u32 {
+    match *expr {
+        ExprBin(ref expr) => precedence(expr.op),
+        ExprConv(_) => PREC_CONV,
+        ExprUn(_) => PREC_UNARY,
+        _ => PREC_PRIMARY,
+    }
+}
+
+struct StructLitFinder {
+    found: bool,
+}
+
+impl<'a> Visitor<'a> for StructLitFinder {
+    fn visit_expr(&mut self, e: &'a Expr) {
+        if e.is_lit_struct() {
+            self.found = true;
+        } else {
+            walk_expr(self, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Ast;
+    use crate::interner::Interner;
+    use crate::lexer::reader::Reader;
+    use crate::parser::{NodeIdGenerator, Parser};
+
+    fn format(code: &str) -> String {
+        let id_generator = NodeIdGenerator::new();
+        let mut interner = Interner::new();
+        let mut ast = Ast::new();
+
+        let reader = Reader::from_string("<<code>>", code);
+        let parser = Parser::new(reader, &id_generator, &mut ast, &mut interner);
+        let (_, errors) = parser.parse();
+        assert!(errors.is_empty());
+
+        super::format(&ast.files[0], code, &interner)
+    }
+
+    fn assert_format(code: &str, expected: &str) {
+        assert_eq!(expected, format(code));
+        assert_eq!(expected, format(expected));
+    }
+
+    #[test]
+    fn format_spacing_and_braces() {
+        assert_format(
+            "fun f(a:Int,var b:Int...)->Int{let x=a*2+1;\nreturn x;}",
+            "fun f(a: Int, var b: Int...) -> Int {\n    let x = a * 2 + 1;\n    return x;\n}\n",
+        );
+
+        assert_format(
+            "fun f ( ) = 1 ;\nfun g() {  }",
+            "fun f() = 1;\n\nfun g() {}\n",
+        );
+    }
+
+    #[test]
+    fn format_nullable_types() {
+        assert_format(
+            "fun f(a:Foo?,b:Array[Foo?]?)->Foo?{a}",
+            "fun f(a: Foo?, b: Array[Foo?]?) -> Foo? {\n    a\n}\n",
+        );
+    }
+
+    #[test]
+    fn format_comments() {
+        assert_format(
+            "// header\nfun f() { // first\n  g(); // call\n\n\n  /* block */ h();\n  // end\n}\n",
+            "// header\nfun f() {\n    // first\n    g(); // call\n\n    /* block */\n    h();\n    // end\n}\n",
+        );
+
+        assert_format(
+            "fun f() {}\n/*\n * multi\n */\n\n\n// last\n",
+            "fun f() {}\n/*\n * multi\n */\n\n// last\n",
+        );
+    }
+
+    #[test]
+    fn format_parentheses() {
+        assert_format(
+            "fun f() { x = ((a + b)) * c - (d - e); y = -(a + b); z = (a as B).c; (x.0).1; }",
+            "fun f() {\n    x = (a + b) * c - (d - e);\n    y = -(a + b);\n    z = (a as B).c;\n    (x.0).1;\n}\n",
+        );
+
+        assert_format(
+            "fun f() { (if a { b } else { c }).d(); }",
+            "fun f() {\n    (if a { b } else { c }).d();\n}\n",
+        );
+    }
+
+    #[test]
+    fn format_package_and_imports() {
+        assert_format(
+            "package app ::util;import a::{B,c};\nimport a::{D};\n@pub fun f() {}",
+            "package app::util;\n\nimport a::{B, c};\nimport a::D;\n\n@pub fun f() {}\n",
+        );
+    }
+
+    #[test]
+    fn format_elements() {
+        assert_format(
+            "let a: Int = 1;\nconst B: Int = 2;\ntype M[V]=HashMap[Int,V];\nenum E { A, B(Int) }\nstruct S { a: Int }",
+            "let a: Int = 1;\nconst B: Int = 2;\ntype M[V] = HashMap[Int, V];\n\nenum E {\n    A,\n    B(Int),\n}\n\nstruct S {\n    a: Int,\n}\n",
+        );
+
+        assert_format(
+            "@open class Foo[T: A + B](let a: Int, b: T): Bar(a) { var c: Int = 1;\n@override fun f() {} b.g(); }",
+            "@open class Foo[T: A + B](let a: Int, b: T): Bar(a) {\n    var c: Int = 1;\n    @override fun f() {}\n    b.g();\n}\n",
+        );
+
+        assert_format(
+            "trait T { @static fun f(); }\nimpl[T] T for Foo[T] {}\nclass Baz",
+            "trait T {\n    @static fun f();\n}\n\nimpl[T] T for Foo[T] {}\n\nclass Baz\n",
+        );
+    }
+
+    #[test]
+    fn format_struct_literals() {
+        assert_format(
+            "fun f() { let x = Foo{a:1,b:Bar{}}; if (Foo { a: 1 }).a == 1 { g(); } }",
+            "fun f() {\n    let x = Foo { a: 1, b: Bar {} };\n    if (Foo { a: 1 }.a == 1) {\n        g();\n    }\n}\n",
+        );
+    }
+
+    #[test]
+    fn format_nested_blocks() {
+        assert_format(
+            "fun f() { let x = if a {1} else {2}; g(|x: Int| -> Int { x }); \
+             match y { A => 1, B(a, _) => { h(); } } }",
+            "fun f() {\n    let x = if a { 1 } else { 2 };\n    g(|x: Int| -> Int { x });\n    \
+             match y {\n        A => 1,\n        B(a, _) => { h(); },\n    }\n}\n",
+        );
+
+        assert_format(
+            "fun f() { g(|| { a(); b(); }); }",
+            "fun f() {\n    g(|| {\n        a();\n        b();\n    });\n}\n",
+        );
+    }
+}
@@ -45,6 +45,10 @@ pub trait Visitor<'v>: Sized {
         walk_enum(self, e);
     }
 
+    fn visit_alias(&mut self, a: &'v Alias) {
+        walk_alias(self, a);
+    }
+
     fn visit_struct_field(&mut self, f: &'v StructField) {
         walk_struct_field(self, f);
     }
@@ -100,6 +104,7 @@ pub fn walk_file<'v, V: Visitor<'v>>(v: &mut V, f: &'v File) {
             ElemGlobal(ref g) => v.visit_global(g),
             ElemConst(ref c) => v.visit_const(c),
             ElemEnum(ref e) => v.visit_enum(e),
+            ElemAlias(ref a) => v.visit_alias(a),
         }
     }
 }
@@ -161,6 +166,10 @@ pub fn walk_enum<'v, V: Visitor<'v>>(_v: &mut V, _e: &'v Enum) {
     // nothing to do
 }
 
+pub fn walk_alias<'v, V: Visitor<'v>>(v: &mut V, a: &'v Alias) {
+    v.visit_type(&a.ty);
+}
+
 pub fn walk_struct<'v, V: Visitor<'v>>(v: &mut V, s: &'v Struct) {
     for f in &s.fields {
         v.visit_struct_field(f);
@@ -216,6 +225,10 @@ pub fn walk_type<'v, V: Visitor<'v>>(v: &mut V, t: &'v Type) {
 
             v.visit_type(&fct.ret);
         }
+
+        TypeNullable(ref nullable) => {
+            v.visit_type(&nullable.ty);
+        }
     }
 }
 
@@ -253,6 +266,23 @@ pub fn walk_stmt<'v, V: Visitor<'v>>(v: &mut V, s: &'v Stmt) {
 
         StmtBreak(_) => {}
         StmtContinue(_) => {}
+
+        StmtThrow(ref value) => {
+            v.visit_expr(&value.expr);
+        }
+
+        StmtTry(ref value) => {
+            v.visit_stmt(&value.try_block);
+
+            for catch in &value.catch_blocks {
+                v.visit_type(&catch.data_type);
+                v.visit_stmt(&catch.block);
+            }
+
+            if let Some(ref finally_block) = value.finally_block {
+                v.visit_stmt(&finally_block.block);
+            }
+        }
     }
 }
 
@@ -304,16 +334,8 @@ pub fn walk_expr<'v, V: Visitor<'v>>(v: &mut V, e: &'v Expr) {
             v.visit_type(&value.data_type);
         }
 
-        ExprLambda(ref value) => {
-            for param in &value.params {
-                v.visit_type(&param.data_type);
-            }
-
-            if let Some(ref ret) = value.ret {
-                v.visit_type(ret);
-            }
-
-            v.visit_stmt(&value.block);
+        ExprLambda(_) => {
+            // the body of a lambda is checked as a function of its own
         }
 
         ExprBlock(ref value) => {
@@ -341,12 +363,30 @@ pub fn walk_expr<'v, V: Visitor<'v>>(v: &mut V, e: &'v Expr) {
             }
         }
 
+        ExprMatch(ref value) => {
+            v.visit_expr(&value.expr);
+
+            for case in &value.cases {
+                if let MatchPattern::Literal(ref lit) = case.pattern {
+                    v.visit_expr(lit);
+                }
+
+                v.visit_expr(&case.value);
+            }
+        }
+
         ExprTuple(ref value) => {
             for expr in &value.values {
                 v.visit_expr(expr);
             }
         }
 
+        ExprLitStruct(ref value) => {
+            for arg in &value.args {
+                v.visit_expr(&arg.value);
+            }
+        }
+
         ExprSuper(_) => {}
         ExprSelf(_) => {}
         ExprLitChar(_) => {}
@@ -1,6 +1,6 @@
 use crate::lexer::position::Position;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ParseError {
     // Lexer errors
     UnknownChar(char),
@@ -21,6 +21,10 @@ pub enum ParseError {
     NumberOverflow,
     UnclosedStringTemplate,
     ExpectedIdentifier(String),
+    CatchOrFinallyExpected,
+    ExpectedPattern(String),
+    MisplacedPackage,
+    MisplacedImport,
 }
 
 impl ParseError {
@@ -56,6 +60,39 @@ impl ParseError {
             ParseError::ExpectedIdentifier(ref tok) => {
                 format!("identifier expected but got {}.", tok)
             }
+            ParseError::CatchOrFinallyExpected => "`try` without `catch` or `finally`.".into(),
+            ParseError::ExpectedPattern(ref got) => format!("pattern expected but got {}.", got),
+            ParseError::MisplacedPackage => {
+                "package declaration needs to be the first element of the file.".into()
+            }
+            ParseError::MisplacedImport => {
+                "imports need to come before all other declarations of the file.".into()
+            }
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnknownChar(..) => "E0001",
+            ParseError::UnclosedComment => "E0002",
+            ParseError::UnclosedString => "E0003",
+            ParseError::UnclosedChar => "E0004",
+            ParseError::InvalidEscapeSequence(..) => "E0005",
+            ParseError::ExpectedTopLevelElement(..) => "E0006",
+            ParseError::UnknownAnnotation(..) => "E0007",
+            ParseError::RedundantAnnotation(..) => "E0008",
+            ParseError::MisplacedAnnotation(..) => "E0009",
+            ParseError::ExpectedToken(..) => "E0010",
+            ParseError::ExpectedType(..) => "E0011",
+            ParseError::MisplacedElse => "E0012",
+            ParseError::ExpectedFactor(..) => "E0013",
+            ParseError::NumberOverflow => "E0014",
+            ParseError::UnclosedStringTemplate => "E0015",
+            ParseError::ExpectedIdentifier(..) => "E0016",
+            ParseError::CatchOrFinallyExpected => "E0017",
+            ParseError::ExpectedPattern(..) => "E0018",
+            ParseError::MisplacedPackage => "E0019",
+            ParseError::MisplacedImport => "E0020",
         }
     }
 }
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
+use std::mem;
 
 use crate::error::{ParseError, ParseErrorAndPos};
 use crate::lexer::position::{Position, Span};
 use crate::lexer::reader::Reader;
-use crate::lexer::token::{FloatSuffix, IntBase, IntSuffix, Token, TokenKind};
+use crate::lexer::token::{FloatSuffix, IntBase, IntSuffix, Token, TokenKind, Trivia, TriviaKind};
 
+pub mod lossless;
 pub mod position;
 pub mod reader;
 pub mod token;
@@ -19,6 +21,9 @@ pub struct File {
 pub struct Lexer {
     reader: Reader,
     keywords: HashMap<&'static str, TokenKind>,
+
+    keep_trivia: bool,
+    trivia: Vec<Trivia>,
 }
 
 impl Lexer {
@@ -31,16 +36,36 @@ impl Lexer {
     pub fn new(reader: Reader) -> Lexer {
         let keywords = keywords_in_map();
 
-        Lexer { reader, keywords }
+        Lexer {
+            reader,
+            keywords,
+            keep_trivia: false,
+            trivia: Vec::new(),
+        }
+    }
+
+    // lexer that also records all whitespace and comments it skips, they can
+    // be retrieved with `take_trivia` after reading the following token.
+    pub fn with_trivia(reader: Reader) -> Lexer {
+        let mut lexer = Lexer::new(reader);
+        lexer.keep_trivia = true;
+        lexer
     }
 
     pub fn path(&self) -> &str {
         self.reader.path()
     }
 
+    // returns the whitespace and comments read since the last call
+    pub fn take_trivia(&mut self) -> Vec<Trivia> {
+        mem::replace(&mut self.trivia, Vec::new())
+    }
+
     pub fn read_token(&mut self) -> Result<Token, ParseErrorAndPos> {
         loop {
+            let start = self.reader.idx();
             self.skip_white();
+            self.add_trivia(TriviaKind::Whitespace, start);
 
             let pos = self.reader.pos();
             let idx = self.reader.idx();
@@ -54,8 +79,10 @@ impl Lexer {
                 return self.read_number();
             } else if self.is_comment_start() {
                 self.read_comment()?;
+                self.add_trivia(TriviaKind::LineComment, idx);
             } else if self.is_multi_comment_start() {
                 self.read_multi_comment()?;
+                self.add_trivia(TriviaKind::BlockComment, idx);
             } else if is_identifier_start(ch) {
                 return self.read_identifier();
             } else if is_quote(ch) {
@@ -66,6 +93,7 @@ impl Lexer {
                 return self.read_operator();
             } else {
                 let ch = ch.unwrap();
+                self.read_char();
 
                 return Err(ParseErrorAndPos::new(pos, ParseError::UnknownChar(ch)));
             }
@@ -78,6 +106,13 @@ impl Lexer {
         }
     }
 
+    fn add_trivia(&mut self, kind: TriviaKind, start: u32) {
+        if self.keep_trivia && self.reader.idx() > start {
+            let span = self.span_from(start);
+            self.trivia.push(Trivia { kind, span });
+        }
+    }
+
     fn read_comment(&mut self) -> Result<(), ParseErrorAndPos> {
         while !self.curr().is_none() && !is_newline(self.curr()) {
             self.read_char();
@@ -305,6 +340,9 @@ impl Lexer {
                     } else {
                         TokenKind::EqEq
                     }
+                } else if nch == '>' {
+                    self.read_char();
+                    TokenKind::FatArrow
                 } else {
                     TokenKind::Eq
                 }
@@ -358,6 +396,7 @@ impl Lexer {
                 }
             }
             '@' => TokenKind::At,
+            '?' => TokenKind::Question,
 
             _ => {
                 return Err(ParseErrorAndPos::new(pos, ParseError::UnknownChar(ch)));
@@ -536,7 +575,7 @@ fn is_char_quote(ch: Option<char>) -> bool {
 }
 
 fn is_operator(ch: Option<char>) -> bool {
-    ch.map(|ch| "^+-*/%&|,=!~;:.()[]{}<>@".contains(ch))
+    ch.map(|ch| "^+-*/%&|,=!~;:.()[]{}<>@?".contains(ch))
         .unwrap_or(false)
 }
 
@@ -566,6 +605,8 @@ fn keywords_in_map() -> HashMap<&'static str, TokenKind> {
     keywords.insert("trait", TokenKind::Trait);
     keywords.insert("impl", TokenKind::Impl);
     keywords.insert("module", TokenKind::Module);
+    keywords.insert("package", TokenKind::Package);
+    keywords.insert("import", TokenKind::Import);
 
     // "small" shapes
     keywords.insert("fun", TokenKind::Fun);
@@ -582,6 +623,11 @@ fn keywords_in_map() -> HashMap<&'static str, TokenKind> {
     keywords.insert("in", TokenKind::In);
     keywords.insert("break", TokenKind::Break);
     keywords.insert("continue", TokenKind::Continue);
+    keywords.insert("try", TokenKind::Try);
+    keywords.insert("catch", TokenKind::Catch);
+    keywords.insert("finally", TokenKind::Finally);
+    keywords.insert("throw", TokenKind::Throw);
+    keywords.insert("match", TokenKind::Match);
 
     // qualifiers
     keywords.insert("self", TokenKind::This);
@@ -1035,6 +1081,15 @@ mod tests {
         assert_tok(&mut reader, TokenKind::Return, 1, 16);
         assert_tok(&mut reader, TokenKind::Nil, 1, 23);
 
+        let mut reader = Lexer::from_str("try catch finally throw");
+        assert_tok(&mut reader, TokenKind::Try, 1, 1);
+        assert_tok(&mut reader, TokenKind::Catch, 1, 5);
+        assert_tok(&mut reader, TokenKind::Finally, 1, 11);
+        assert_tok(&mut reader, TokenKind::Throw, 1, 19);
+
+        let mut reader = Lexer::from_str("match");
+        assert_tok(&mut reader, TokenKind::Match, 1, 1);
+
         let mut reader = Lexer::from_str("type struct enum alias trait const");
         assert_tok(&mut reader, TokenKind::Type, 1, 1);
         assert_tok(&mut reader, TokenKind::Struct, 1, 6);
@@ -1048,6 +1103,11 @@ mod tests {
         assert_tok(&mut reader, TokenKind::In, 1, 5);
         assert_tok(&mut reader, TokenKind::Impl, 1, 8);
         assert_tok(&mut reader, TokenKind::CapitalThis, 1, 13);
+
+        let mut reader = Lexer::from_str("package import module");
+        assert_tok(&mut reader, TokenKind::Package, 1, 1);
+        assert_tok(&mut reader, TokenKind::Import, 1, 9);
+        assert_tok(&mut reader, TokenKind::Module, 1, 16);
     }
 
     #[test]
@@ -1082,6 +1142,16 @@ mod tests {
         let mut reader = Lexer::from_str("->");
         assert_tok(&mut reader, TokenKind::Arrow, 1, 1);
 
+        let mut reader = Lexer::from_str("A?]");
+        assert_tok(&mut reader, TokenKind::Identifier("A".into()), 1, 1);
+        assert_tok(&mut reader, TokenKind::Question, 1, 2);
+        assert_tok(&mut reader, TokenKind::RBracket, 1, 3);
+
+        let mut reader = Lexer::from_str("=>==>");
+        assert_tok(&mut reader, TokenKind::FatArrow, 1, 1);
+        assert_tok(&mut reader, TokenKind::EqEq, 1, 3);
+        assert_tok(&mut reader, TokenKind::Gt, 1, 5);
+
         let mut reader = Lexer::from_str(">><<>>>_::");
         assert_tok(&mut reader, TokenKind::GtGt, 1, 1);
         assert_tok(&mut reader, TokenKind::LtLt, 1, 3);
@@ -0,0 +1,170 @@
+use crate::error::ParseErrorAndPos;
+use crate::lexer::position::Span;
+use crate::lexer::reader::Reader;
+use crate::lexer::token::{Token, TokenKind, Trivia};
+use crate::lexer::{File, Lexer};
+
+// All tokens of a file including their leading trivia. Every byte of the file
+// is covered by exactly one token or trivia span, so concatenating them in
+// order yields the original source again.
+pub struct TokenStream {
+    pub file: File,
+    pub tokens: Vec<TriviaToken>,
+}
+
+// token together with the whitespace and comments in front of it, the End
+// token gets everything after the last token
+pub struct TriviaToken {
+    pub token: Token,
+    pub leading_trivia: Vec<Trivia>,
+}
+
+impl TokenStream {
+    pub fn tokenize(reader: Reader) -> Result<TokenStream, ParseErrorAndPos> {
+        let mut lexer = Lexer::with_trivia(reader);
+        let mut tokens = Vec::new();
+
+        // number of open braces for each string template we are currently in,
+        // the closing brace of a template expression continues the string
+        let mut templates: Vec<u32> = Vec::new();
+
+        loop {
+            let token = lexer.read_token()?;
+            let mut continue_string = false;
+
+            match token.kind {
+                TokenKind::StringExpr(_) => templates.push(0),
+                TokenKind::LBrace => {
+                    if let Some(depth) = templates.last_mut() {
+                        *depth += 1;
+                    }
+                }
+                TokenKind::RBrace => match templates.last_mut() {
+                    Some(0) => {
+                        templates.pop();
+                        continue_string = true;
+                    }
+                    Some(depth) => *depth -= 1,
+                    None => {}
+                },
+                _ => {}
+            }
+
+            let end = token.is_eof();
+            tokens.push(TriviaToken {
+                token,
+                leading_trivia: lexer.take_trivia(),
+            });
+
+            if continue_string {
+                let token = lexer.read_string_continuation()?;
+
+                if let TokenKind::StringExpr(_) = token.kind {
+                    templates.push(0);
+                }
+
+                tokens.push(TriviaToken {
+                    token,
+                    leading_trivia: Vec::new(),
+                });
+            }
+
+            if end {
+                break;
+            }
+        }
+
+        Ok(TokenStream {
+            file: lexer.file(),
+            tokens,
+        })
+    }
+
+    pub fn text(&self, span: Span) -> &str {
+        &self.file.content[span.start() as usize..span.end() as usize]
+    }
+
+    // all comments of the file in source order
+    pub fn comments(&self) -> Vec<Trivia> {
+        self.tokens
+            .iter()
+            .flat_map(|token| token.leading_trivia.iter())
+            .filter(|trivia| trivia.is_comment())
+            .cloned()
+            .collect()
+    }
+
+    // prints all tokens and trivia again, the result is identical to the source
+    pub fn to_source(&self) -> String {
+        let mut source = String::with_capacity(self.file.content.len());
+
+        for token in &self.tokens {
+            for trivia in &token.leading_trivia {
+                source.push_str(self.text(trivia.span));
+            }
+
+            source.push_str(self.text(token.token.span));
+        }
+
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::TriviaKind;
+
+    fn tokenize(code: &str) -> TokenStream {
+        TokenStream::tokenize(Reader::from_string("<<code>>", code)).unwrap()
+    }
+
+    fn assert_lossless(code: &str) {
+        assert_eq!(code, tokenize(code).to_source());
+    }
+
+    #[test]
+    fn test_trivia() {
+        let stream = tokenize("// a\nfun /* b */ f() {}  \n");
+        let fun = &stream.tokens[0];
+
+        assert_eq!(TokenKind::Fun, fun.token.kind);
+        let kinds: Vec<_> = fun.leading_trivia.iter().map(|t| t.kind).collect();
+        assert_eq!(vec![TriviaKind::LineComment, TriviaKind::Whitespace], kinds);
+        assert_eq!("// a", stream.text(fun.leading_trivia[0].span));
+
+        let f = &stream.tokens[1];
+        let kinds: Vec<_> = f.leading_trivia.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            vec![
+                TriviaKind::Whitespace,
+                TriviaKind::BlockComment,
+                TriviaKind::Whitespace
+            ],
+            kinds
+        );
+        assert_eq!("/* b */", stream.text(f.leading_trivia[1].span));
+
+        let end = stream.tokens.last().unwrap();
+        assert!(end.token.is_eof());
+        assert_eq!(1, end.leading_trivia.len());
+        assert_eq!("  \n", stream.text(end.leading_trivia[0].span));
+    }
+
+    #[test]
+    fn test_no_trivia_by_default() {
+        let mut lexer = Lexer::from_str("  // a\n  1");
+        lexer.read_token().unwrap();
+        assert!(lexer.take_trivia().is_empty());
+    }
+
+    #[test]
+    fn test_lossless() {
+        assert_lossless("");
+        assert_lossless("  \n\t");
+        assert_lossless("fun main() {\n\t// comment\n\tlet x = 1 /* inline */ + 2;\n}\n");
+        assert_lossless("let x = \"a${b}c${ { d } }e\";");
+        assert_lossless("let x = \"${\"${a}\"}\" + 'c';");
+        assert_lossless("class A(let x: Int) {\r\n    fun f() -> Int = 0x1_FL; /**/\r\n}");
+    }
+}
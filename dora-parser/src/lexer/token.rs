@@ -24,6 +24,8 @@ pub enum TokenKind {
     Trait,
     Impl,
     Module,
+    Package,
+    Import,
 
     // "small" shapes
     Fun,
@@ -40,6 +42,11 @@ pub enum TokenKind {
     In,
     Break,
     Continue,
+    Try,
+    Catch,
+    Finally,
+    Throw,
+    Match,
 
     // qualifiers
     This,
@@ -88,7 +95,9 @@ pub enum TokenKind {
     Colon,
     ColonColon,
     At,
+    Question,
     Arrow,
+    FatArrow,
 
     // brackets
     LParen,
@@ -134,6 +143,8 @@ impl TokenKind {
             TokenKind::Trait => "trait",
             TokenKind::Impl => "impl",
             TokenKind::Module => "module",
+            TokenKind::Package => "package",
+            TokenKind::Import => "import",
 
             // "small" shapes
             TokenKind::Fun => "fun",
@@ -150,6 +161,11 @@ impl TokenKind {
             TokenKind::In => "in",
             TokenKind::Break => "break",
             TokenKind::Continue => "continue",
+            TokenKind::Try => "try",
+            TokenKind::Catch => "catch",
+            TokenKind::Finally => "finally",
+            TokenKind::Throw => "throw",
+            TokenKind::Match => "match",
 
             // qualifiers
             TokenKind::This => "self",
@@ -198,7 +214,9 @@ impl TokenKind {
             TokenKind::Colon => ":",
             TokenKind::ColonColon => "::",
             TokenKind::At => "@",
+            TokenKind::Question => "?",
             TokenKind::Arrow => "->",
+            TokenKind::FatArrow => "=>",
 
             // brackets
             TokenKind::LParen => "(",
@@ -281,6 +299,25 @@ impl Token {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TriviaKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+}
+
+#[derive(Clone, Debug)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+impl Trivia {
+    pub fn is_comment(&self) -> bool {
+        self.kind != TriviaKind::Whitespace
+    }
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "{}", self.name())
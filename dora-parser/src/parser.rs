@@ -25,6 +25,7 @@ pub struct Parser<'a> {
     in_class_or_module: bool,
     parse_struct_lit: bool,
     last_end: Option<u32>,
+    errors: Vec<ParseErrorAndPos>,
 }
 
 type ExprResult = Result<Box<Expr>, ParseErrorAndPos>;
@@ -56,6 +57,7 @@ impl<'a> Parser<'a> {
             parse_struct_lit: true,
             ast,
             last_end: Some(0),
+            errors: Vec::new(),
         };
 
         parser
@@ -65,22 +67,34 @@ impl<'a> Parser<'a> {
         self.id_generator.next()
     }
 
-    pub fn parse(mut self) -> Result<LexerFile, ParseErrorAndPos> {
-        self.init()?;
-        let mut elements = vec![];
+    pub fn parse(mut self) -> (LexerFile, Vec<ParseErrorAndPos>) {
+        if let Err(error) = self.init() {
+            self.report_error(error);
+            self.skip_token();
+        }
+
+        let mut file = ast::File {
+            path: String::new(),
+            package: None,
+            imports: Vec::new(),
+            elements: Vec::new(),
+        };
 
         while !self.token.is_eof() {
-            self.parse_top_level_element(&mut elements)?;
+            let start = self.token.span.start();
+
+            if let Err(error) = self.parse_top_level_element(&mut file) {
+                self.report_error(error);
+                self.recover_top_level_element(start);
+            }
         }
 
-        let file = self.lexer.file();
+        let lexer_file = self.lexer.file();
+        file.path = lexer_file.name.clone();
 
-        self.ast.files.push(ast::File {
-            path: file.name.clone(),
-            elements,
-        });
+        self.ast.files.push(file);
 
-        Ok(file)
+        (lexer_file, self.errors)
     }
 
     fn init(&mut self) -> Result<(), ParseErrorAndPos> {
@@ -89,18 +103,45 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
-    fn parse_top_level_element(
-        &mut self,
-        elements: &mut Vec<Elem>,
-    ) -> Result<(), ParseErrorAndPos> {
+    fn parse_top_level_element(&mut self, file: &mut ast::File) -> Result<(), ParseErrorAndPos> {
         let modifiers = self.parse_annotations()?;
+        let elements = &mut file.elements;
 
         match self.token.kind {
+            TokenKind::Package => {
+                self.ban_modifiers(&modifiers)?;
+
+                if file.package.is_some() || !file.imports.is_empty() || !elements.is_empty() {
+                    return Err(ParseErrorAndPos::new(
+                        self.token.position,
+                        ParseError::MisplacedPackage,
+                    ));
+                }
+
+                let package = self.parse_package()?;
+                file.package = Some(package);
+            }
+
+            TokenKind::Import => {
+                self.ban_modifiers(&modifiers)?;
+
+                if !elements.is_empty() {
+                    return Err(ParseErrorAndPos::new(
+                        self.token.position,
+                        ParseError::MisplacedImport,
+                    ));
+                }
+
+                let import = self.parse_import()?;
+                file.imports.push(import);
+            }
+
             TokenKind::Fun => {
                 self.restrict_modifiers(
                     &modifiers,
                     &[
                         Modifier::Internal,
+                        Modifier::Pub,
                         Modifier::Optimize,
                         Modifier::OptimizeImmediately,
                         Modifier::Test,
@@ -118,6 +159,7 @@ impl<'a> Parser<'a> {
                         Modifier::Abstract,
                         Modifier::Open,
                         Modifier::Internal,
+                        Modifier::Pub,
                         Modifier::Cannon,
                     ],
                 )?;
@@ -126,14 +168,14 @@ impl<'a> Parser<'a> {
             }
 
             TokenKind::Struct => {
-                self.ban_modifiers(&modifiers)?;
-                let struc = self.parse_struct()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let struc = self.parse_struct(&modifiers)?;
                 elements.push(ElemStruct(struc))
             }
 
             TokenKind::Trait => {
-                self.ban_modifiers(&modifiers)?;
-                let xtrait = self.parse_trait()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let xtrait = self.parse_trait(&modifiers)?;
                 elements.push(ElemTrait(xtrait));
             }
 
@@ -144,28 +186,34 @@ impl<'a> Parser<'a> {
             }
 
             TokenKind::Module => {
-                self.ban_modifiers(&modifiers)?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
                 let module = self.parse_module(&modifiers)?;
                 elements.push(ElemModule(module));
             }
 
             TokenKind::Let | TokenKind::Var => {
-                self.ban_modifiers(&modifiers)?;
-                self.parse_global(elements)?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                self.parse_global(&modifiers, elements)?;
             }
 
             TokenKind::Const => {
-                self.ban_modifiers(&modifiers)?;
-                let xconst = self.parse_const()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let xconst = self.parse_const(&modifiers)?;
                 elements.push(ElemConst(xconst));
             }
 
             TokenKind::Enum => {
-                self.ban_modifiers(&modifiers)?;
-                let xenum = self.parse_enum()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let xenum = self.parse_enum(&modifiers)?;
                 elements.push(ElemEnum(xenum));
             }
 
+            TokenKind::Type => {
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let alias = self.parse_alias(&modifiers)?;
+                elements.push(ElemAlias(alias));
+            }
+
             _ => {
                 let msg = ParseError::ExpectedTopLevelElement(self.token.name());
                 return Err(ParseErrorAndPos::new(self.token.position, msg));
@@ -175,7 +223,87 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
-    fn parse_enum(&mut self) -> Result<Enum, ParseErrorAndPos> {
+    fn parse_package(&mut self) -> Result<Package, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Package)?.position;
+        let mut path = vec![self.expect_identifier()?];
+
+        while self.token.is(TokenKind::ColonColon) {
+            self.advance_token()?;
+            path.push(self.expect_identifier()?);
+        }
+
+        self.expect_semicolon()?;
+        let span = self.span_from(start);
+
+        Ok(Package {
+            id: self.generate_id(),
+            pos,
+            span,
+            path,
+        })
+    }
+
+    fn parse_import(&mut self) -> Result<Import, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Import)?.position;
+        let mut path = vec![self.expect_identifier()?];
+        let mut names = None;
+        let mut last_pos = pos;
+
+        self.expect_token(TokenKind::ColonColon)?;
+
+        loop {
+            if self.token.is(TokenKind::LBrace) {
+                self.advance_token()?;
+                names = Some(self.parse_list(TokenKind::Comma, TokenKind::RBrace, |p| {
+                    p.parse_import_name()
+                })?);
+                break;
+            }
+
+            last_pos = self.token.position;
+            path.push(self.expect_identifier()?);
+
+            if !self.token.is(TokenKind::ColonColon) {
+                break;
+            }
+
+            self.advance_token()?;
+        }
+
+        // `import a::b::C;` imports the last segment of the path
+        let names = match names {
+            Some(names) => names,
+            None => {
+                let name = path.pop().unwrap();
+                vec![ImportName {
+                    pos: last_pos,
+                    name,
+                }]
+            }
+        };
+
+        self.expect_semicolon()?;
+        let span = self.span_from(start);
+
+        Ok(Import {
+            id: self.generate_id(),
+            pos,
+            span,
+            path,
+            names,
+        })
+    }
+
+    fn parse_import_name(&mut self) -> Result<ImportName, ParseErrorAndPos> {
+        let pos = self.token.position;
+        let name = self.expect_identifier()?;
+
+        Ok(ImportName { pos, name })
+    }
+
+    fn parse_enum(&mut self, modifiers: &Modifiers) -> Result<Enum, ParseErrorAndPos> {
         let start = self.token.span.start();
         let pos = self.expect_token(TokenKind::Enum)?.position;
         let name = self.expect_identifier()?;
@@ -192,6 +320,7 @@ impl<'a> Parser<'a> {
             pos,
             span,
             name,
+            is_pub: modifiers.contains(Modifier::Pub),
             type_params,
             variants,
         })
@@ -220,7 +349,7 @@ impl<'a> Parser<'a> {
         })
     }
 
-    fn parse_const(&mut self) -> Result<Const, ParseErrorAndPos> {
+    fn parse_const(&mut self, modifiers: &Modifiers) -> Result<Const, ParseErrorAndPos> {
         let start = self.token.span.start();
         let pos = self.expect_token(TokenKind::Const)?.position;
         let name = self.expect_identifier()?;
@@ -236,11 +365,33 @@ impl<'a> Parser<'a> {
             pos,
             span,
             name,
+            is_pub: modifiers.contains(Modifier::Pub),
             data_type: ty,
             expr,
         })
     }
 
+    fn parse_alias(&mut self, modifiers: &Modifiers) -> Result<Alias, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Type)?.position;
+        let name = self.expect_identifier()?;
+        let type_params = self.parse_type_params()?;
+        self.expect_token(TokenKind::Eq)?;
+        let ty = self.parse_type()?;
+        self.expect_semicolon()?;
+        let span = self.span_from(start);
+
+        Ok(Alias {
+            id: self.generate_id(),
+            pos,
+            span,
+            name,
+            is_pub: modifiers.contains(Modifier::Pub),
+            type_params,
+            ty,
+        })
+    }
+
     fn parse_impl(&mut self) -> Result<Impl, ParseErrorAndPos> {
         let start = self.token.span.start();
         let pos = self.expect_token(TokenKind::Impl)?.position;
@@ -283,7 +434,11 @@ impl<'a> Parser<'a> {
         })
     }
 
-    fn parse_global(&mut self, elements: &mut Vec<Elem>) -> Result<(), ParseErrorAndPos> {
+    fn parse_global(
+        &mut self,
+        modifiers: &Modifiers,
+        elements: &mut Vec<Elem>,
+    ) -> Result<(), ParseErrorAndPos> {
         let start = self.token.span.start();
         let pos = self.token.position;
         let reassignable = self.token.is(TokenKind::Var);
@@ -309,6 +464,7 @@ impl<'a> Parser<'a> {
             name,
             pos,
             span,
+            is_pub: modifiers.contains(Modifier::Pub),
             data_type,
             reassignable,
             initializer: None,
@@ -324,10 +480,11 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
-    fn parse_trait(&mut self) -> Result<Trait, ParseErrorAndPos> {
+    fn parse_trait(&mut self, modifiers: &Modifiers) -> Result<Trait, ParseErrorAndPos> {
         let start = self.token.span.start();
         let pos = self.expect_token(TokenKind::Trait)?.position;
         let ident = self.expect_identifier()?;
+        let type_params = self.parse_type_params()?;
 
         self.expect_token(TokenKind::LBrace)?;
 
@@ -349,11 +506,13 @@ impl<'a> Parser<'a> {
             name: ident,
             pos,
             span,
+            is_pub: modifiers.contains(Modifier::Pub),
+            type_params,
             methods,
         })
     }
 
-    fn parse_struct(&mut self) -> Result<Struct, ParseErrorAndPos> {
+    fn parse_struct(&mut self, modifiers: &Modifiers) -> Result<Struct, ParseErrorAndPos> {
         let start = self.token.span.start();
         let pos = self.expect_token(TokenKind::Struct)?.position;
         let ident = self.expect_identifier()?;
@@ -369,6 +528,7 @@ impl<'a> Parser<'a> {
             name: ident,
             pos,
             span,
+            is_pub: modifiers.contains(Modifier::Pub),
             fields,
         })
     }
@@ -409,6 +569,7 @@ impl<'a> Parser<'a> {
             has_open,
             internal,
             is_abstract,
+            is_pub: modifiers.contains(Modifier::Pub),
             has_constructor: false,
             parent_class: None,
             constructor: None,
@@ -464,6 +625,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_module(&mut self, modifiers: &Modifiers) -> Result<Module, ParseErrorAndPos> {
+        let start = self.token.span.start();
         let internal = modifiers.contains(Modifier::Internal);
 
         let pos = self.expect_token(TokenKind::Module)?.position;
@@ -472,7 +634,9 @@ impl<'a> Parser<'a> {
             id: self.generate_id(),
             name: ident,
             pos: pos,
+            span: Span::invalid(),
             parent_class: None,
+            is_pub: modifiers.contains(Modifier::Pub),
             internal: internal,
             has_constructor: false,
             constructor: None,
@@ -499,6 +663,8 @@ impl<'a> Parser<'a> {
         };
 
         self.parse_module_body(&mut module)?;
+        module.span = self.span_from(start);
+        module.constructor = Some(self.generate_module_constructor(&mut module));
         self.in_class_or_module = false;
 
         Ok(module)
@@ -635,42 +801,53 @@ impl<'a> Parser<'a> {
 
         self.advance_token()?;
 
-        while !self.token.is(TokenKind::RBrace) {
-            let modifiers = self.parse_annotations()?;
+        while !self.token.is(TokenKind::RBrace) && !self.token.is_eof() {
+            let start = self.token.span.start();
 
-            match self.token.kind {
-                TokenKind::Fun => {
-                    let mods = &[
-                        Modifier::Abstract,
-                        Modifier::Internal,
-                        Modifier::Open,
-                        Modifier::Override,
-                        Modifier::Final,
-                        Modifier::Pub,
-                        Modifier::Static,
-                        Modifier::Cannon,
-                    ];
-                    self.restrict_modifiers(&modifiers, mods)?;
+            if let Err(error) = self.parse_class_member(cls) {
+                self.report_error(error);
+                self.recover_class_member(start);
+            }
+        }
 
-                    let fct = self.parse_function(&modifiers)?;
-                    cls.methods.push(fct);
-                }
+        self.expect_token(TokenKind::RBrace)?;
+        Ok(())
+    }
+
+    fn parse_class_member(&mut self, cls: &mut Class) -> Result<(), ParseErrorAndPos> {
+        let modifiers = self.parse_annotations()?;
 
-                TokenKind::Var | TokenKind::Let => {
-                    self.ban_modifiers(&modifiers)?;
+        match self.token.kind {
+            TokenKind::Fun => {
+                let mods = &[
+                    Modifier::Abstract,
+                    Modifier::Internal,
+                    Modifier::Open,
+                    Modifier::Override,
+                    Modifier::Final,
+                    Modifier::Pub,
+                    Modifier::Static,
+                    Modifier::Cannon,
+                ];
+                self.restrict_modifiers(&modifiers, mods)?;
 
-                    let field = self.parse_field()?;
-                    cls.fields.push(field);
-                }
+                let fct = self.parse_function(&modifiers)?;
+                cls.methods.push(fct);
+            }
 
-                _ => {
-                    let initializer = self.parse_statement()?;
-                    cls.initializers.push(initializer);
-                }
+            TokenKind::Var | TokenKind::Let => {
+                self.ban_modifiers(&modifiers)?;
+
+                let field = self.parse_field()?;
+                cls.fields.push(field);
+            }
+
+            _ => {
+                let initializer = self.parse_statement()?;
+                cls.initializers.push(initializer);
             }
         }
 
-        self.advance_token()?;
         Ok(())
     }
 
@@ -681,41 +858,52 @@ impl<'a> Parser<'a> {
 
         self.advance_token()?;
 
-        while !self.token.is(TokenKind::RBrace) {
-            let modifiers = self.parse_annotations()?;
+        while !self.token.is(TokenKind::RBrace) && !self.token.is_eof() {
+            let start = self.token.span.start();
 
-            match self.token.kind {
-                TokenKind::Fun => {
-                    let mods = &[
-                        Modifier::Abstract,
-                        Modifier::Internal,
-                        Modifier::Open,
-                        Modifier::Override,
-                        Modifier::Final,
-                        Modifier::Pub,
-                        Modifier::Static,
-                    ];
-                    self.restrict_modifiers(&modifiers, mods)?;
+            if let Err(error) = self.parse_module_member(module) {
+                self.report_error(error);
+                self.recover_class_member(start);
+            }
+        }
 
-                    let fct = self.parse_function(&modifiers)?;
-                    module.methods.push(fct);
-                }
+        self.expect_token(TokenKind::RBrace)?;
+        Ok(())
+    }
 
-                TokenKind::Var | TokenKind::Let => {
-                    self.ban_modifiers(&modifiers)?;
+    fn parse_module_member(&mut self, module: &mut Module) -> Result<(), ParseErrorAndPos> {
+        let modifiers = self.parse_annotations()?;
 
-                    let field = self.parse_field()?;
-                    module.fields.push(field);
-                }
+        match self.token.kind {
+            TokenKind::Fun => {
+                let mods = &[
+                    Modifier::Abstract,
+                    Modifier::Internal,
+                    Modifier::Open,
+                    Modifier::Override,
+                    Modifier::Final,
+                    Modifier::Pub,
+                    Modifier::Static,
+                ];
+                self.restrict_modifiers(&modifiers, mods)?;
 
-                _ => {
-                    let initializer = self.parse_statement()?;
-                    module.initializers.push(initializer);
-                }
+                let fct = self.parse_function(&modifiers)?;
+                module.methods.push(fct);
+            }
+
+            TokenKind::Var | TokenKind::Let => {
+                self.ban_modifiers(&modifiers)?;
+
+                let field = self.parse_field()?;
+                module.fields.push(field);
+            }
+
+            _ => {
+                let initializer = self.parse_statement()?;
+                module.initializers.push(initializer);
             }
         }
 
-        self.advance_token()?;
         Ok(())
     }
 
@@ -1025,13 +1213,20 @@ impl<'a> Parser<'a> {
                 };
 
                 let span = self.span_from(start);
-                Ok(Type::create_basic(
-                    self.generate_id(),
-                    pos,
-                    span,
-                    name,
-                    params,
-                ))
+                let ty = Type::create_basic(self.generate_id(), pos, span, name, params);
+
+                if self.token.is(TokenKind::Question) {
+                    self.advance_token()?;
+                    let span = self.span_from(start);
+                    Ok(Type::create_nullable(
+                        self.generate_id(),
+                        pos,
+                        span,
+                        Box::new(ty),
+                    ))
+                } else {
+                    Ok(ty)
+                }
             }
 
             TokenKind::LParen => {
@@ -1160,11 +1355,11 @@ impl<'a> Parser<'a> {
         let mut expr = None;
 
         while !self.token.is(TokenKind::RBrace) && !self.token.is_eof() {
-            let stmt_or_expr = self.parse_statement_or_expression()?;
+            let stmt_start = self.token.span.start();
 
-            match stmt_or_expr {
-                StmtOrExpr::Stmt(stmt) => stmts.push(stmt),
-                StmtOrExpr::Expr(curr_expr) => {
+            match self.parse_statement_or_expression() {
+                Ok(StmtOrExpr::Stmt(stmt)) => stmts.push(stmt),
+                Ok(StmtOrExpr::Expr(curr_expr)) => {
                     if curr_expr.needs_semicolon() {
                         expr = Some(curr_expr);
                         break;
@@ -1179,6 +1374,10 @@ impl<'a> Parser<'a> {
                         expr = Some(curr_expr);
                     }
                 }
+                Err(error) => {
+                    self.report_error(error);
+                    self.recover_statement(stmt_start);
+                }
             }
         }
 
@@ -1201,6 +1400,8 @@ impl<'a> Parser<'a> {
             TokenKind::Break => Ok(StmtOrExpr::Stmt(self.parse_break()?)),
             TokenKind::Continue => Ok(StmtOrExpr::Stmt(self.parse_continue()?)),
             TokenKind::Return => Ok(StmtOrExpr::Stmt(self.parse_return()?)),
+            TokenKind::Throw => Ok(StmtOrExpr::Stmt(self.parse_throw()?)),
+            TokenKind::Try => Ok(StmtOrExpr::Stmt(self.parse_try()?)),
             TokenKind::Else => Err(ParseErrorAndPos::new(
                 self.token.position,
                 ParseError::MisplacedElse,
@@ -1258,6 +1459,160 @@ impl<'a> Parser<'a> {
         )))
     }
 
+    fn parse_match(&mut self) -> ExprResult {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Match)?.position;
+
+        let expr = self.parse_expression_no_struct_lit()?;
+        let mut cases = Vec::new();
+
+        self.expect_token(TokenKind::LBrace)?;
+
+        while !self.token.is(TokenKind::RBrace) && !self.token.is_eof() {
+            let case = self.parse_match_case()?;
+            let needs_comma = case.value.needs_semicolon();
+            cases.push(case);
+
+            if self.token.is(TokenKind::Comma) {
+                self.advance_token()?;
+            } else if needs_comma && !self.token.is(TokenKind::RBrace) {
+                self.expect_token(TokenKind::Comma)?;
+            }
+        }
+
+        self.expect_token(TokenKind::RBrace)?;
+        let span = self.span_from(start);
+
+        Ok(Box::new(Expr::create_match(
+            self.generate_id(),
+            pos,
+            span,
+            expr,
+            cases,
+        )))
+    }
+
+    fn parse_match_case(&mut self) -> Result<MatchCaseType, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.token.position;
+        let pattern = self.parse_match_pattern()?;
+        self.expect_token(TokenKind::FatArrow)?;
+        let value = self.parse_expression()?;
+        let span = self.span_from(start);
+
+        Ok(MatchCaseType {
+            id: self.generate_id(),
+            pos,
+            span,
+
+            pattern,
+            value,
+        })
+    }
+
+    fn parse_match_pattern(&mut self) -> Result<MatchPattern, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.token.position;
+
+        match self.token.kind {
+            TokenKind::Underscore => {
+                self.advance_token()?;
+                let span = self.span_from(start);
+
+                Ok(MatchPattern::Underscore(MatchPatternUnderscoreType {
+                    id: self.generate_id(),
+                    pos,
+                    span,
+                }))
+            }
+
+            TokenKind::LitChar(_) => Ok(MatchPattern::Literal(self.parse_lit_char()?)),
+            TokenKind::LitInt(_, _, _) => Ok(MatchPattern::Literal(self.parse_lit_int()?)),
+            TokenKind::True | TokenKind::False => {
+                Ok(MatchPattern::Literal(self.parse_bool_literal()?))
+            }
+
+            TokenKind::Sub => {
+                self.advance_token()?;
+
+                let opnd = match self.token.kind {
+                    TokenKind::LitInt(_, _, _) => self.parse_lit_int()?,
+                    _ => {
+                        return Err(ParseErrorAndPos::new(
+                            self.token.position,
+                            ParseError::ExpectedPattern(self.token.name()),
+                        ))
+                    }
+                };
+                let span = self.span_from(start);
+
+                Ok(MatchPattern::Literal(Box::new(Expr::create_un(
+                    self.generate_id(),
+                    pos,
+                    span,
+                    UnOp::Neg,
+                    opnd,
+                ))))
+            }
+
+            TokenKind::Identifier(_) => {
+                let mut path = vec![self.expect_identifier()?];
+
+                while self.token.is(TokenKind::ColonColon) {
+                    self.advance_token()?;
+                    path.push(self.expect_identifier()?);
+                }
+
+                let params = if self.token.is(TokenKind::LParen) {
+                    self.advance_token()?;
+                    Some(self.parse_list(TokenKind::Comma, TokenKind::RParen, |p| {
+                        p.parse_match_pattern_param()
+                    })?)
+                } else {
+                    None
+                };
+
+                let span = self.span_from(start);
+
+                Ok(MatchPattern::Variant(MatchPatternVariantType {
+                    id: self.generate_id(),
+                    pos,
+                    span,
+
+                    path,
+                    params,
+                }))
+            }
+
+            _ => Err(ParseErrorAndPos::new(
+                pos,
+                ParseError::ExpectedPattern(self.token.name()),
+            )),
+        }
+    }
+
+    fn parse_match_pattern_param(&mut self) -> Result<MatchPatternParam, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.token.position;
+
+        let name = if self.token.is(TokenKind::Underscore) {
+            self.advance_token()?;
+            None
+        } else {
+            Some(self.expect_identifier()?)
+        };
+
+        let span = self.span_from(start);
+
+        Ok(MatchPatternParam {
+            id: self.generate_id(),
+            pos,
+            span,
+
+            name,
+        })
+    }
+
     fn parse_for(&mut self) -> StmtResult {
         let start = self.token.span.start();
         let pos = self.expect_token(TokenKind::For)?.position;
@@ -1308,32 +1663,104 @@ impl<'a> Parser<'a> {
         self.expect_semicolon()?;
         let span = self.span_from(start);
 
-        Ok(Box::new(Stmt::create_continue(
+        Ok(Box::new(Stmt::create_continue(
+            self.generate_id(),
+            pos,
+            span,
+        )))
+    }
+
+    fn parse_return(&mut self) -> StmtResult {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Return)?.position;
+        let expr = if self.token.is(TokenKind::Semicolon) {
+            None
+        } else {
+            let expr = self.parse_expression()?;
+            Some(expr)
+        };
+
+        self.expect_semicolon()?;
+        let span = self.span_from(start);
+
+        Ok(Box::new(Stmt::create_return(
+            self.generate_id(),
+            pos,
+            span,
+            expr,
+        )))
+    }
+
+    fn parse_throw(&mut self) -> StmtResult {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Throw)?.position;
+        let expr = self.parse_expression()?;
+        self.expect_semicolon()?;
+        let span = self.span_from(start);
+
+        Ok(Box::new(Stmt::create_throw(
+            self.generate_id(),
+            pos,
+            span,
+            expr,
+        )))
+    }
+
+    fn parse_try(&mut self) -> StmtResult {
+        let start = self.token.span.start();
+        let pos = self.expect_token(TokenKind::Try)?.position;
+        let try_block = self.parse_block_stmt()?;
+        let mut catch_blocks = Vec::new();
+
+        while self.token.is(TokenKind::Catch) {
+            catch_blocks.push(self.parse_catch()?);
+        }
+
+        let finally_block = if self.token.is(TokenKind::Finally) {
+            self.advance_token()?;
+            let block = self.parse_block_stmt()?;
+
+            Some(FinallyBlock::new(block))
+        } else {
+            None
+        };
+
+        if catch_blocks.is_empty() && finally_block.is_none() {
+            return Err(ParseErrorAndPos::new(
+                self.token.position,
+                ParseError::CatchOrFinallyExpected,
+            ));
+        }
+
+        let span = self.span_from(start);
+
+        Ok(Box::new(Stmt::create_try(
             self.generate_id(),
             pos,
             span,
+            try_block,
+            catch_blocks,
+            finally_block,
         )))
     }
 
-    fn parse_return(&mut self) -> StmtResult {
+    fn parse_catch(&mut self) -> Result<CatchBlock, ParseErrorAndPos> {
         let start = self.token.span.start();
-        let pos = self.expect_token(TokenKind::Return)?.position;
-        let expr = if self.token.is(TokenKind::Semicolon) {
-            None
-        } else {
-            let expr = self.parse_expression()?;
-            Some(expr)
-        };
-
-        self.expect_semicolon()?;
+        let pos = self.expect_token(TokenKind::Catch)?.position;
+        let name = self.expect_identifier()?;
+        self.expect_token(TokenKind::Colon)?;
+        let data_type = self.parse_type()?;
+        let block = self.parse_block_stmt()?;
         let span = self.span_from(start);
 
-        Ok(Box::new(Stmt::create_return(
+        Ok(CatchBlock::new(
             self.generate_id(),
             pos,
             span,
-            expr,
-        )))
+            name,
+            data_type,
+            block,
+        ))
     }
 
     fn parse_expression(&mut self) -> ExprResult {
@@ -1351,6 +1778,7 @@ impl<'a> Parser<'a> {
         let result = match self.token.kind {
             TokenKind::LBrace => self.parse_block(),
             TokenKind::If => self.parse_if(),
+            TokenKind::Match => self.parse_match(),
             _ => self.parse_binary(0),
         };
 
@@ -1560,6 +1988,7 @@ impl<'a> Parser<'a> {
             TokenKind::LParen => self.parse_parentheses(),
             TokenKind::LBrace => self.parse_block(),
             TokenKind::If => self.parse_if(),
+            TokenKind::Match => self.parse_match(),
             TokenKind::LitChar(_) => self.parse_lit_char(),
             TokenKind::LitInt(_, _, _) => self.parse_lit_int(),
             TokenKind::LitFloat(_, _) => self.parse_lit_float(),
@@ -1583,6 +2012,10 @@ impl<'a> Parser<'a> {
         let span = self.token.span;
         let name = self.expect_identifier()?;
 
+        if self.parse_struct_lit && self.token.is(TokenKind::LBrace) {
+            return self.parse_lit_struct(pos, span.start(), name);
+        }
+
         Ok(Box::new(Expr::create_ident(
             self.generate_id(),
             pos,
@@ -1592,6 +2025,40 @@ impl<'a> Parser<'a> {
         )))
     }
 
+    fn parse_lit_struct(&mut self, pos: Position, start: u32, path: Name) -> ExprResult {
+        self.expect_token(TokenKind::LBrace)?;
+        let args = self.parse_list(TokenKind::Comma, TokenKind::RBrace, |p| {
+            p.parse_struct_arg()
+        })?;
+        let span = self.span_from(start);
+
+        Ok(Box::new(Expr::create_lit_struct(
+            self.generate_id(),
+            pos,
+            span,
+            path,
+            args,
+        )))
+    }
+
+    fn parse_struct_arg(&mut self) -> Result<StructArg, ParseErrorAndPos> {
+        let start = self.token.span.start();
+        let pos = self.token.position;
+        let name = self.expect_identifier()?;
+
+        self.expect_token(TokenKind::Colon)?;
+        let value = self.parse_expression()?;
+        let span = self.span_from(start);
+
+        Ok(StructArg {
+            id: self.generate_id(),
+            pos,
+            span,
+            name,
+            value,
+        })
+    }
+
     fn parse_parentheses(&mut self) -> ExprResult {
         let pos = self.token.position;
         let start = self.token.span.start();
@@ -1840,21 +2307,46 @@ impl<'a> Parser<'a> {
 
         let ret = if self.token.is(TokenKind::Arrow) {
             self.advance_token()?;
-            Some(Box::new(self.parse_type()?))
+            Some(self.parse_type()?)
         } else {
             None
         };
 
-        let block = self.parse_block_stmt()?;
+        let block = match *self.parse_block()? {
+            Expr::ExprBlock(block) => block,
+            _ => unreachable!(),
+        };
         let span = self.span_from(start);
 
+        let fct = Function {
+            id: self.generate_id(),
+            name: self.interner.intern("<lambda>"),
+            pos: tok.position,
+            span,
+            method: false,
+            has_open: false,
+            has_override: false,
+            has_final: false,
+            has_optimize: false,
+            has_optimize_immediately: false,
+            is_pub: false,
+            is_static: false,
+            internal: false,
+            is_abstract: false,
+            is_constructor: false,
+            is_test: false,
+            use_cannon: false,
+            params,
+            return_type: ret,
+            block: Some(Box::new(block)),
+            type_params: None,
+        };
+
         Ok(Box::new(Expr::create_lambda(
             self.generate_id(),
             tok.position,
             span,
-            params,
-            ret,
-            block,
+            fct,
         )))
     }
 
@@ -1890,6 +2382,131 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // Skips tokens until the start of the next top-level element. Braces are
+    // balanced so that elements nested in a broken body are not picked up.
+    fn recover_top_level_element(&mut self, start: u32) {
+        self.skip_if_not_advanced(start);
+        let mut depth = 0;
+
+        loop {
+            match self.token.kind {
+                TokenKind::End => break,
+                TokenKind::Fun
+                | TokenKind::Class
+                | TokenKind::Struct
+                | TokenKind::Trait
+                | TokenKind::Impl
+                | TokenKind::Module
+                | TokenKind::Package
+                | TokenKind::Import
+                | TokenKind::Let
+                | TokenKind::Var
+                | TokenKind::Const
+                | TokenKind::Enum
+                | TokenKind::Type
+                | TokenKind::At
+                    if depth == 0 =>
+                {
+                    break
+                }
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace if depth > 0 => depth -= 1,
+                _ => {}
+            }
+
+            self.skip_token();
+        }
+    }
+
+    // Skips tokens until the start of the next class or module member, stops
+    // before the `}` that closes the body.
+    fn recover_class_member(&mut self, start: u32) {
+        self.skip_if_not_advanced(start);
+        let mut depth = 0;
+
+        loop {
+            match self.token.kind {
+                TokenKind::End => break,
+                TokenKind::Semicolon if depth == 0 => {
+                    self.skip_token();
+                    break;
+                }
+                TokenKind::Fun | TokenKind::Let | TokenKind::Var | TokenKind::At if depth == 0 => {
+                    break
+                }
+                TokenKind::RBrace if depth == 0 => break,
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace => depth -= 1,
+                _ => {}
+            }
+
+            self.skip_token();
+        }
+    }
+
+    // Skips tokens until the end of the current statement, stops before the
+    // `}` that closes the block.
+    fn recover_statement(&mut self, start: u32) {
+        self.skip_if_not_advanced(start);
+        let mut depth = 0;
+
+        loop {
+            match self.token.kind {
+                TokenKind::End => break,
+                TokenKind::Semicolon if depth == 0 => {
+                    self.skip_token();
+                    break;
+                }
+                TokenKind::Let
+                | TokenKind::Var
+                | TokenKind::While
+                | TokenKind::For
+                | TokenKind::Return
+                | TokenKind::Break
+                | TokenKind::Continue
+                | TokenKind::Throw
+                | TokenKind::Try
+                    if depth == 0 =>
+                {
+                    break
+                }
+                TokenKind::RBrace if depth == 0 => break,
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace => depth -= 1,
+                _ => {}
+            }
+
+            self.skip_token();
+        }
+    }
+
+    fn report_error(&mut self, error: ParseErrorAndPos) {
+        // nested recovery points can run into the same error, e.g. a missing `}` at the end of file
+        if let Some(last) = self.errors.last() {
+            if last.pos == error.pos {
+                return;
+            }
+        }
+
+        self.errors.push(error);
+    }
+
+    fn skip_if_not_advanced(&mut self, start: u32) {
+        if self.token.span.start() == start && !self.token.is_eof() {
+            self.skip_token();
+        }
+    }
+
+    // Advances to the next token, lexer errors are recorded and skipped.
+    fn skip_token(&mut self) {
+        loop {
+            match self.advance_token() {
+                Ok(_) => break,
+                Err(error) => self.report_error(error),
+            }
+        }
+    }
+
     fn advance_token(&mut self) -> Result<Token, ParseErrorAndPos> {
         let token = self.lexer.read_token()?;
         Ok(self.advance_token_with(token))
@@ -1928,11 +2545,46 @@ impl<'a> Parser<'a> {
         cls: &mut Class,
         ctor_params: Vec<ConstructorParam>,
         use_cannon: bool,
+    ) -> Function {
+        let initializers = mem::replace(&mut cls.initializers, Vec::new());
+
+        self.build_constructor(
+            cls.name,
+            &cls.parent_class,
+            &ctor_params,
+            &cls.fields,
+            initializers,
+            use_cannon,
+        )
+    }
+
+    // the constructor of a module initializes the module object on first use
+    fn generate_module_constructor(&mut self, module: &mut Module) -> Function {
+        let initializers = mem::replace(&mut module.initializers, Vec::new());
+
+        self.build_constructor(
+            module.name,
+            &module.parent_class,
+            &[],
+            &module.fields,
+            initializers,
+            false,
+        )
+    }
+
+    fn build_constructor(
+        &mut self,
+        name: Name,
+        parent_class: &Option<ParentClass>,
+        ctor_params: &[ConstructorParam],
+        fields: &[Field],
+        initializers: Vec<Box<Stmt>>,
+        use_cannon: bool,
     ) -> Function {
         let builder = Builder::new(self.id_generator);
         let mut block = builder.build_block();
 
-        if let Some(ref parent_class) = cls.parent_class {
+        if let Some(ref parent_class) = *parent_class {
             let expr = Expr::create_delegation(
                 self.generate_id(),
                 parent_class.pos,
@@ -1952,7 +2604,7 @@ impl<'a> Parser<'a> {
             block.add_expr(ass);
         }
 
-        for field in cls.fields.iter().filter(|field| field.expr.is_some()) {
+        for field in fields.iter().filter(|field| field.expr.is_some()) {
             let this = builder.build_this();
             let lhs = builder.build_dot(this, builder.build_ident(field.name));
             let ass = builder.build_initializer_assign(lhs, field.expr.as_ref().unwrap().clone());
@@ -1960,11 +2612,11 @@ impl<'a> Parser<'a> {
             block.add_expr(ass);
         }
 
-        block.add_stmts(mem::replace(&mut cls.initializers, Vec::new()));
+        block.add_stmts(initializers);
 
-        let mut fct = builder.build_fct(cls.name);
+        let mut fct = builder.build_fct(name);
 
-        for field in &ctor_params {
+        for field in ctor_params {
             fct.add_param(field.name, field.data_type.clone());
         }
 
@@ -2009,7 +2661,7 @@ mod tests {
     use crate::ast::*;
     use crate::interner::*;
 
-    use crate::error::ParseError;
+    use crate::error::{ParseError, ParseErrorAndPos};
     use crate::lexer::position::Position;
     use crate::lexer::reader::Reader;
     use crate::parser::{NodeIdGenerator, Parser};
@@ -2102,28 +2754,42 @@ mod tests {
         let mut ast = Ast::new();
 
         let reader = Reader::from_string("<<code>>", code);
-        Parser::new(reader, &id_generator, &mut ast, &mut interner)
-            .parse()
-            .unwrap();
+        let (_, errors) = Parser::new(reader, &id_generator, &mut ast, &mut interner).parse();
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
 
         (ast, interner)
     }
 
-    fn parse_err(code: &'static str, msg: ParseError, line: u32, col: u32) {
+    fn parse_errs(code: &'static str) -> (Ast, Vec<ParseErrorAndPos>) {
         let id_generator = NodeIdGenerator::new();
         let mut interner = Interner::new();
         let mut ast = Ast::new();
 
         let reader = Reader::from_string("<<code>>", code);
-        let err = Parser::new(reader, &id_generator, &mut ast, &mut interner)
-            .parse()
-            .unwrap_err();
+        let (_, errors) = Parser::new(reader, &id_generator, &mut ast, &mut interner).parse();
+
+        (ast, errors)
+    }
+
+    fn parse_err(code: &'static str, msg: ParseError, line: u32, col: u32) {
+        let (_, errors) = parse_errs(code);
+        let err = errors.first().expect("parse error expected");
 
         assert_eq!(msg, err.error);
         assert_eq!(line, err.pos.line);
         assert_eq!(col, err.pos.column);
     }
 
+    fn assert_errs(errors: &[ParseErrorAndPos], expected: &[(ParseError, u32, u32)]) {
+        assert_eq!(expected.len(), errors.len(), "errors: {:?}", errors);
+
+        for (err, (msg, line, col)) in errors.iter().zip(expected) {
+            assert_eq!(*msg, err.error);
+            assert_eq!(*line, err.pos.line);
+            assert_eq!(*col, err.pos.column);
+        }
+    }
+
     #[test]
     fn parse_ident() {
         let (expr, interner) = parse_expr("a");
@@ -2826,6 +3492,101 @@ mod tests {
         assert!(ret.expr.is_none());
     }
 
+    #[test]
+    fn parse_throw() {
+        let stmt = parse_stmt("throw 1;");
+        let throw = stmt.to_throw().unwrap();
+
+        assert_eq!(1, throw.expr.to_lit_int().unwrap().value);
+    }
+
+    #[test]
+    fn parse_try_catch() {
+        let stmt = parse_stmt("try { 1; } catch e: Str { 2; }");
+        let try_stmt = stmt.to_try().unwrap();
+
+        assert_eq!(1, try_stmt.catch_blocks.len());
+        assert!(try_stmt.finally_block.is_none());
+
+        let catch = &try_stmt.catch_blocks[0];
+        assert!(catch.data_type.to_basic().is_some());
+    }
+
+    #[test]
+    fn parse_try_multiple_catch_finally() {
+        let stmt = parse_stmt("try { 1; } catch e: Str { 2; } catch f: Foo { 3; } finally { 4; }");
+        let try_stmt = stmt.to_try().unwrap();
+
+        assert_eq!(2, try_stmt.catch_blocks.len());
+        assert!(try_stmt.finally_block.is_some());
+    }
+
+    #[test]
+    fn parse_match() {
+        let (expr, interner) = parse_expr("match x { Foo::A(a, _) => a, Foo::B => { 1 } _ => 2 }");
+        let match_expr = expr.to_match().unwrap();
+
+        assert!(match_expr.expr.is_ident());
+        assert_eq!(3, match_expr.cases.len());
+
+        let variant = match_expr.cases[0].pattern.to_variant().unwrap();
+        assert_eq!(2, variant.path.len());
+        assert_eq!("A", *interner.str(variant.path[1]));
+        let params = variant.params.as_ref().unwrap();
+        assert_eq!(2, params.len());
+        assert_eq!("a", *interner.str(params[0].name.unwrap()));
+        assert!(params[1].name.is_none());
+
+        let variant = match_expr.cases[1].pattern.to_variant().unwrap();
+        assert!(variant.params.is_none());
+        assert!(match_expr.cases[1].value.is_block());
+
+        assert!(match_expr.cases[2].pattern.is_underscore());
+    }
+
+    #[test]
+    fn parse_match_literals() {
+        let (expr, _) = parse_expr("match x { 1 => 2, -1 => 3, }");
+        let match_expr = expr.to_match().unwrap();
+
+        assert_eq!(2, match_expr.cases.len());
+
+        match match_expr.cases[1].pattern {
+            MatchPattern::Literal(ref lit) => assert!(lit.is_un()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn parse_match_errors() {
+        err_expr(
+            "match x { 1 => 2 3 => 4 }",
+            ParseError::ExpectedToken(",".into(), "3".into()),
+            1,
+            18,
+        );
+        err_expr(
+            "match x { + => 2 }",
+            ParseError::ExpectedPattern("+".into()),
+            1,
+            11,
+        );
+    }
+
+    #[test]
+    fn parse_try_finally() {
+        let stmt = parse_stmt("try { 1; } finally { 2; }");
+        let try_stmt = stmt.to_try().unwrap();
+
+        assert!(try_stmt.catch_blocks.is_empty());
+        assert!(try_stmt.finally_block.is_some());
+    }
+
+    #[test]
+    fn parse_try_without_catch() {
+        err_stmt("try { 1; }", ParseError::CatchOrFinallyExpected, 1, 11);
+    }
+
     #[test]
     fn parse_else() {
         err_stmt("else", ParseError::MisplacedElse, 1, 1);
@@ -2851,6 +3612,19 @@ mod tests {
         assert_eq!("B", *interner.str(basic.params[1].to_basic().unwrap().name));
     }
 
+    #[test]
+    fn parse_type_nullable() {
+        let (ty, interner) = parse_type("Foo[A?]?");
+        let nullable = ty.to_nullable().unwrap();
+        let basic = nullable.ty.to_basic().unwrap();
+
+        assert_eq!("Foo", *interner.str(basic.name));
+        assert_eq!(1, basic.params.len());
+
+        let param = basic.params[0].to_nullable().unwrap();
+        assert_eq!("A", *interner.str(param.ty.to_basic().unwrap().name));
+    }
+
     #[test]
     fn parse_type_fct_no_params() {
         let (ty, _) = parse_type("() -> ()");
@@ -3093,6 +3867,22 @@ mod tests {
         assert_eq!(1, module.methods.len());
     }
 
+    #[test]
+    fn parse_module_constructor() {
+        let (prog, _) = parse("module Foo : Bar(1) { let x: Int = 23; var y: Int; y = x; }");
+        let module = prog.mod0();
+        let ctor = module.constructor.as_ref().unwrap();
+
+        assert!(ctor.is_constructor);
+        assert!(module.initializers.is_empty());
+
+        let stmts = &ctor.block().stmts;
+        assert_eq!(3, stmts.len());
+        assert!(stmts[0].to_expr().unwrap().expr.is_delegation());
+        assert!(stmts[1].to_expr().unwrap().expr.is_bin());
+        assert!(stmts[2].span().is_valid());
+    }
+
     #[test]
     fn parse_method_invocation() {
         let (expr, _) = parse_expr("a.foo()");
@@ -3267,6 +4057,39 @@ mod tests {
         assert_eq!("fb", *interner.str(f2.name));
     }
 
+    #[test]
+    fn parse_lit_struct() {
+        let (expr, interner) = parse_expr("Foo { a: 1, b: x + 2 }");
+        let lit = expr.to_lit_struct().unwrap();
+
+        assert_eq!("Foo", *interner.str(lit.path));
+        assert_eq!(2, lit.args.len());
+        assert_eq!("a", *interner.str(lit.args[0].name));
+        assert!(lit.args[0].value.is_lit_int());
+        assert_eq!("b", *interner.str(lit.args[1].name));
+        assert!(lit.args[1].value.is_bin());
+
+        let (expr, _) = parse_expr("Foo {}");
+        assert!(expr.to_lit_struct().unwrap().args.is_empty());
+
+        let (expr, _) = parse_expr("Foo { a: 1 }.a");
+        assert!(expr.to_dot().unwrap().lhs.is_lit_struct());
+    }
+
+    #[test]
+    fn parse_lit_struct_in_cond() {
+        let (expr, _) = parse_expr("if x { }");
+        assert!(expr.to_if().unwrap().cond.is_ident());
+
+        let (expr, _) = parse_expr("if (Foo { a: 1 }).a == 1 { }");
+        let bin = expr.to_if().unwrap().cond.to_bin().unwrap();
+        assert!(bin.lhs.to_dot().unwrap().lhs.is_lit_struct());
+
+        let (expr, _) = parse_expr("if f(Foo { a: 1 }) { }");
+        let call = expr.to_if().unwrap().cond.to_call().unwrap();
+        assert!(call.args[0].is_lit_struct());
+    }
+
     #[test]
     fn parse_struct_lit_while() {
         let stmt = parse_stmt("while i < n { }");
@@ -3369,6 +4192,34 @@ mod tests {
         assert_eq!(true, xtrait.methods[0].is_static);
     }
 
+    #[test]
+    fn parse_generic_trait() {
+        let (prog, interner) = parse("trait Foo[T] { fun get() -> T; }");
+        let xtrait = prog.trait0();
+
+        assert_eq!("Foo", *interner.str(xtrait.name));
+        let type_params = xtrait.type_params.as_ref().unwrap();
+        assert_eq!(1, type_params.len());
+        assert_eq!("T", *interner.str(type_params[0].name));
+        assert_eq!(1, xtrait.methods.len());
+    }
+
+    #[test]
+    fn parse_generic_impl() {
+        let (prog, interner) = parse("impl[T] Foo[T] for A[T] {}");
+        let ximpl = prog.impl0();
+
+        assert_eq!(1, ximpl.type_params.as_ref().unwrap().len());
+
+        let trait_type = ximpl.trait_type.as_ref().unwrap().to_basic().unwrap();
+        assert_eq!("Foo", *interner.str(trait_type.name));
+        assert_eq!(1, trait_type.params.len());
+
+        let class_type = ximpl.class_type.to_basic().unwrap();
+        assert_eq!("A", *interner.str(class_type.name));
+        assert_eq!(1, class_type.params.len());
+    }
+
     #[test]
     fn parse_empty_impl() {
         let (prog, interner) = parse("impl Foo for A {}");
@@ -3501,6 +4352,87 @@ mod tests {
         assert_eq!("x", *interner.str(xconst.name));
     }
 
+    #[test]
+    fn parse_alias() {
+        let (prog, interner) = parse("type Map[V] = HashMap[Int, V];");
+        let alias = prog.alias0();
+
+        assert_eq!("Map", *interner.str(alias.name));
+        assert_eq!(1, alias.type_params.as_ref().unwrap().len());
+        let ty = alias.ty.to_basic().unwrap();
+        assert_eq!("HashMap", *interner.str(ty.name));
+        assert_eq!(2, ty.params.len());
+
+        let (prog, interner) = parse("type Pair = (Int, Str);");
+        let alias = prog.alias0();
+
+        assert!(alias.type_params.is_none());
+        assert_eq!("(Int, Str)", alias.ty.to_string(&interner));
+    }
+
+    #[test]
+    fn parse_package_and_imports() {
+        let (prog, interner) =
+            parse("package app::util;\nimport std::io::File;\nimport a::{B, c};\nfun f() {}");
+        let file = prog.files.last().unwrap();
+
+        let package = file.package.as_ref().unwrap();
+        let path: Vec<_> = package
+            .path
+            .iter()
+            .map(|&n| interner.str(n).to_string())
+            .collect();
+        assert_eq!(vec!["app", "util"], path);
+
+        assert_eq!(2, file.imports.len());
+        let import = &file.imports[0];
+        assert_eq!(2, import.path.len());
+        assert_eq!(1, import.names.len());
+        assert_eq!("File", *interner.str(import.names[0].name));
+        assert_eq!(Position::new(2, 17), import.names[0].pos);
+
+        let import = &file.imports[1];
+        assert_eq!(1, import.path.len());
+        assert_eq!("B", *interner.str(import.names[0].name));
+        assert_eq!("c", *interner.str(import.names[1].name));
+        assert_eq!(1, file.elements.len());
+    }
+
+    #[test]
+    fn parse_misplaced_package_and_imports() {
+        parse_err("fun f() {}\npackage a;", ParseError::MisplacedPackage, 2, 1);
+        parse_err(
+            "import a::B;\npackage a;",
+            ParseError::MisplacedPackage,
+            2,
+            1,
+        );
+        parse_err(
+            "fun f() {}\nimport a::B;",
+            ParseError::MisplacedImport,
+            2,
+            1,
+        );
+        parse_err(
+            "import a;",
+            ParseError::ExpectedToken("::".into(), ";".into()),
+            1,
+            9,
+        );
+    }
+
+    #[test]
+    fn parse_pub_elements() {
+        let (prog, _) = parse("@pub class A");
+        assert!(prog.cls0().is_pub);
+
+        let (prog, _) = parse("@pub struct A { a: Int }");
+        assert!(prog.struct0().is_pub);
+
+        let (prog, _) = parse("enum A { B }");
+        assert!(!prog.enum0().is_pub);
+    }
+
     #[test]
     fn parse_generic_with_bound() {
         let (prog, _) = parse("class A[T: Foo]");
@@ -3542,14 +4474,14 @@ mod tests {
         let (expr, _) = parse_expr("|| {}");
         let lambda = expr.to_lambda().unwrap();
 
-        assert!(lambda.ret.is_none());
+        assert!(lambda.fct.return_type.is_none());
     }
 
     #[test]
     fn parse_lambda_no_params_unit_as_return_value() {
         let (expr, _) = parse_expr("|| -> () {}");
         let lambda = expr.to_lambda().unwrap();
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
 
         assert!(ret.is_unit());
     }
@@ -3558,7 +4490,7 @@ mod tests {
     fn parse_lambda_no_params_with_return_value() {
         let (expr, interner) = parse_expr("|| -> A {}");
         let lambda = expr.to_lambda().unwrap();
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
         let basic = ret.to_basic().unwrap();
 
         assert_eq!("A", *interner.str(basic.name));
@@ -3569,14 +4501,14 @@ mod tests {
         let (expr, interner) = parse_expr("|a: A| -> B {}");
         let lambda = expr.to_lambda().unwrap();
 
-        assert_eq!(1, lambda.params.len());
+        assert_eq!(1, lambda.fct.params.len());
 
-        let param = &lambda.params[0];
+        let param = &lambda.fct.params[0];
         assert_eq!("a", *interner.str(param.name));
         let basic = param.data_type.to_basic().unwrap();
         assert_eq!("A", *interner.str(basic.name));
 
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
         let basic = ret.to_basic().unwrap();
 
         assert_eq!("B", *interner.str(basic.name));
@@ -3587,19 +4519,19 @@ mod tests {
         let (expr, interner) = parse_expr("|a: A, b: B| -> C {}");
         let lambda = expr.to_lambda().unwrap();
 
-        assert_eq!(2, lambda.params.len());
+        assert_eq!(2, lambda.fct.params.len());
 
-        let param = &lambda.params[0];
+        let param = &lambda.fct.params[0];
         assert_eq!("a", *interner.str(param.name));
         let basic = param.data_type.to_basic().unwrap();
         assert_eq!("A", *interner.str(basic.name));
 
-        let param = &lambda.params[1];
+        let param = &lambda.fct.params[1];
         assert_eq!("b", *interner.str(param.name));
         let basic = param.data_type.to_basic().unwrap();
         assert_eq!("B", *interner.str(basic.name));
 
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
         let basic = ret.to_basic().unwrap();
 
         assert_eq!("C", *interner.str(basic.name));
@@ -3685,4 +4617,69 @@ mod tests {
         assert!(xenum.variants[0].types.is_none());
         assert_eq!(xenum.variants[1].types.as_ref().unwrap().len(), 1);
     }
+
+    #[test]
+    fn parse_recover_statements() {
+        let (ast, errors) = parse_errs("fun f() { let x = ; let y = 1 +; g(); }");
+        assert_errs(
+            &errors,
+            &[
+                (ParseError::ExpectedFactor(";".into()), 1, 19),
+                (ParseError::ExpectedFactor(";".into()), 1, 32),
+            ],
+        );
+
+        let fct = ast.fct0();
+        assert_eq!(1, fct.block().stmts.len());
+    }
+
+    #[test]
+    fn parse_recover_top_level_elements() {
+        let (ast, errors) = parse_errs("fun f(a: ) {}\nfun g() {}\nfun h(: Int) {}");
+        assert_errs(
+            &errors,
+            &[
+                (ParseError::ExpectedType(")".into()), 1, 10),
+                (ParseError::ExpectedIdentifier(":".into()), 3, 7),
+            ],
+        );
+
+        assert_eq!(1, ast.files[0].elements.len());
+    }
+
+    #[test]
+    fn parse_recover_class_members() {
+        let (ast, errors) = parse_errs("class A { fun f(: Int) {} var x: Int; let ; fun g() {} }");
+        assert_errs(
+            &errors,
+            &[
+                (ParseError::ExpectedIdentifier(":".into()), 1, 17),
+                (ParseError::ExpectedIdentifier(";".into()), 1, 43),
+            ],
+        );
+
+        let cls = ast.cls0();
+        assert_eq!(1, cls.fields.len());
+        assert_eq!(1, cls.methods.len());
+    }
+
+    #[test]
+    fn parse_recover_missing_rbrace() {
+        let (_, errors) = parse_errs("fun f() { 1; ");
+        assert_errs(
+            &errors,
+            &[(
+                ParseError::ExpectedToken("}".into(), "<<EOF>>".into()),
+                1,
+                14,
+            )],
+        );
+    }
+
+    #[test]
+    fn parse_recover_unknown_char() {
+        let (ast, errors) = parse_errs("fun f() { let x = 1 $ 2; }\nfun g() {}");
+        assert_errs(&errors, &[(ParseError::UnknownChar('$'), 1, 21)]);
+        assert_eq!(2, ast.files[0].elements.len());
+    }
 }
@@ -58,7 +58,7 @@ impl<'ast> InternalArg<'ast> {
 
 #[derive(Clone, Debug)]
 struct CallSite<'ast> {
-    callee: FctId,
+    callee: Option<FctId>, // None for invocation of lambdas
     cls_type_params: TypeList,
     fct_type_params: TypeList,
     args: Vec<InternalArg<'ast>>,
@@ -219,9 +219,9 @@ impl ManagedStackFrame {
                 continue;
             }
 
-            if data.ty.reference_type() {
+            if data.ty.reference_type(vm) {
                 offsets.push(data.offset);
-            } else if let Some(tuple_id) = data.ty.tuple_id() {
+            } else if let Some(tuple_id) = data.ty.layout_tuple_id(vm) {
                 let tuples = vm.tuples.lock();
                 let tuple_references = tuples.get_tuple(tuple_id).references();
                 for &offset in tuple_references {
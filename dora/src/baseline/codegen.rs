@@ -13,7 +13,7 @@ use crate::baseline::{
 };
 use crate::compiler::asm::BaselineAssembler;
 use crate::compiler::codegen::{ensure_native_stub, should_emit_debug, AllocationSize, AnyReg};
-use crate::compiler::fct::{Code, GcPoint, JitDescriptor};
+use crate::compiler::fct::{CatchType, Code, GcPoint, JitDescriptor};
 use crate::compiler::native_stub::{NativeFct, NativeFctDescriptor};
 use crate::cpu::{
     next_param_offset, FReg, Mem, Reg, FREG_PARAMS, FREG_RESULT, FREG_TMP1, PARAM_OFFSET,
@@ -23,12 +23,16 @@ use crate::gc::Address;
 use crate::masm::*;
 use crate::mem;
 use crate::object::{offset_of_array_data, Header, Str};
-use crate::semck::specialize::{replace_type_param, specialize_class_ty, specialize_for_call_type};
+use crate::semck::specialize::{
+    replace_type_param, specialize_class_ty, specialize_enum_id_params, specialize_for_call_type,
+    specialize_lambda, specialize_module, specialize_struct_id_params,
+};
 use crate::size::InstanceSize;
 use crate::ty::{BuiltinType, MachineMode, TypeList, TypeParamId};
 use crate::vm::{
-    CallType, ClassDefId, ConstId, Fct, FctId, FctKind, FctSrc, FieldId, IdentType, Intrinsic,
-    TraitId, Trap, TupleId, VarId, VM,
+    find_field_in_struct, CallType, ClassDef, ClassDefId, ConstId, EnumDefId, EnumLayout, Fct,
+    FctId, FctKind, FctParent, FctSrc, FieldId, GlobalId, IdentType, Intrinsic, StructFieldDef,
+    StructFieldId, TraitId, Trap, TupleId, VarId, VM,
 };
 use crate::vtable::{VTable, DISPLAY_SIZE};
 
@@ -49,6 +53,9 @@ pub(super) fn generate<'a, 'ast: 'a>(
         lbl_break: None,
         lbl_continue: None,
 
+        active_tries: Vec::new(),
+        loop_try_depth: 0,
+
         stacksize_offset: 0,
         managed_stack: ManagedStackFrame::new(),
         var_to_slot: HashMap::new(),
@@ -71,6 +78,13 @@ struct AstCodeGen<'a, 'ast: 'a> {
     lbl_break: Option<Label>,
     lbl_continue: Option<Label>,
 
+    // try statements surrounding the current position, innermost last
+    active_tries: Vec<ActiveTry<'ast>>,
+
+    // number of try statements active when entering the innermost loop,
+    // break and continue leave all try statements above that depth
+    loop_try_depth: usize,
+
     managed_stack: ManagedStackFrame,
     stacksize_offset: usize,
 
@@ -99,6 +113,14 @@ where
         self.store_register_params_on_stack();
         self.emit_stack_guard();
 
+        if self.fct.is_lambda() {
+            self.load_captured_vars();
+        }
+
+        if self.fct.is_module_constructor() {
+            self.allocate_module_object();
+        }
+
         let always_returns = self.src.always_returns;
 
         {
@@ -114,7 +136,7 @@ where
                 self.emit_expr(value, dest);
 
                 if !always_returns {
-                    if let Some(tuple_id) = return_type.tuple_id() {
+                    if let Some(tuple_id) = return_type.layout_tuple_id(self.vm) {
                         self.asm.load_mem(
                             MachineMode::Ptr,
                             REG_TMP1.into(),
@@ -158,7 +180,7 @@ where
 
         let return_type = self.specialize_type(self.fct.return_type);
 
-        if return_type.is_tuple() {
+        if return_type.layout_tuple_id(self.vm).is_some() {
             let slot = self.managed_stack.add_scope(BuiltinType::Ptr, self.vm);
             self.asm.store_mem(
                 MachineMode::Ptr,
@@ -171,7 +193,7 @@ where
 
         if self.fct.has_self() {
             let var = self.src.var_self();
-            let mode = var.ty.mode();
+            let mode = var.ty.mode(self.vm);
 
             self.asm.emit_comment("store param self".into());
 
@@ -184,8 +206,16 @@ where
             let slot_param = self.managed_stack.add_scope(var.ty, self.vm);
             assert!(self.var_to_slot.insert(var.id, slot_param).is_none());
 
-            self.asm
-                .store_mem(mode, Mem::Local(slot_param.offset()), dest);
+            if self.fct.is_module_constructor() {
+                // the module object is allocated after the stack guard,
+                // the slot must not contain garbage until then
+                self.asm.load_nil(REG_TMP1);
+                self.asm
+                    .store_mem(mode, Mem::Local(slot_param.offset()), REG_TMP1.into());
+            } else {
+                self.asm
+                    .store_mem(mode, Mem::Local(slot_param.offset()), dest);
+            }
 
             if mode.is_float() {
                 freg_idx += 1;
@@ -202,7 +232,7 @@ where
 
             if ty.is_unit() {
                 // nothing
-            } else if let Some(tuple_id) = ty.tuple_id() {
+            } else if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
                 let slot_param = self.managed_stack.add_scope(ty, self.vm);
                 assert!(self.var_to_slot.insert(varid, slot_param).is_none());
 
@@ -270,6 +300,81 @@ where
         }
     }
 
+    // the module object is stored in the module's global before the
+    // initializers run, so that they can already refer to the module
+    fn allocate_module_object(&mut self) {
+        let module_id = match self.fct.parent {
+            FctParent::Module(module_id) => module_id,
+            _ => unreachable!(),
+        };
+
+        let cls_def_id = specialize_module(self.vm, module_id);
+        let global_id = self.vm.modules.idx(module_id).read().global_id;
+
+        let slot = self.emit_allocation(self.fct.pos, ArrayLength::Fixed(0), cls_def_id);
+        let self_offset = self.var_offset(self.src.var_self().id);
+
+        self.asm.load_mem(
+            MachineMode::Ptr,
+            REG_RESULT.into(),
+            Mem::Local(slot.offset()),
+        );
+        self.asm
+            .store_mem(MachineMode::Ptr, Mem::Local(self_offset), REG_RESULT.into());
+        self.managed_stack.free_temp(slot, self.vm);
+
+        self.emit_store_global(global_id, REG_RESULT.into());
+    }
+
+    // captured variables are copied from the closure object into
+    // local variables of the lambda
+    fn load_captured_vars(&mut self) {
+        let cls_def_id = specialize_lambda(
+            self.vm,
+            self.fct,
+            self.cls_type_params.clone(),
+            self.fct_type_params.clone(),
+        );
+        let fields = self.vm.class_defs.idx(cls_def_id).read().fields.clone();
+        let closure_offset = self.var_offset(self.src.var_self().id);
+        let src = self.src;
+
+        for (capture, field) in src.captures.iter().zip(&fields) {
+            let ty = field.ty;
+
+            if ty.is_unit() {
+                continue;
+            }
+
+            let slot_var = self.managed_stack.add_scope(ty, self.vm);
+            assert!(self.var_to_slot.insert(capture.inner, slot_var).is_none());
+
+            {
+                let name = self.vm.interner.str(capture.name);
+                self.asm.emit_comment(format!("load captured var {}", name));
+            }
+
+            self.asm.load_mem(
+                MachineMode::Ptr,
+                REG_TMP1.into(),
+                Mem::Local(closure_offset),
+            );
+
+            if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
+                self.copy_tuple(
+                    tuple_id,
+                    RegOrOffset::Offset(slot_var.offset()),
+                    RegOrOffset::RegWithOffset(REG_TMP1, field.offset),
+                );
+            } else {
+                let reg = result_reg_ty(ty).any_reg();
+                self.asm
+                    .load_mem(ty.mode(self.vm), reg, Mem::Base(REG_TMP1, field.offset));
+                self.asm.var_store(slot_var.offset(), ty, reg);
+            }
+        }
+    }
+
     fn emit_prolog(&mut self) {
         self.stacksize_offset = self.asm.prolog();
     }
@@ -292,12 +397,26 @@ where
         if let Some(ref expr) = s.expr {
             let dest = self.emit_expr_result_reg(expr);
 
-            if return_type.is_tuple() {
+            if return_type.layout_tuple_id(self.vm).is_some() {
                 temp = Some(dest.stack_slot());
+            } else if !return_type.is_unit() && self.has_active_finally() {
+                // finally-blocks would overwrite the result register
+                let slot = self.managed_stack.add_temp(return_type, self.vm);
+                self.asm
+                    .var_store(slot.offset(), return_type, dest.any_reg());
+                let exited = self.emit_finally_blocks(0);
+                self.asm
+                    .var_load(slot.offset(), return_type, dest.any_reg());
+                self.managed_stack.free_temp(slot, self.vm);
+                self.emit_epilog();
+                self.reenter_tries(exited);
+                return;
             }
         }
 
-        if let Some(tuple_id) = return_type.tuple_id() {
+        let exited = self.emit_finally_blocks(0);
+
+        if let Some(tuple_id) = return_type.layout_tuple_id(self.vm) {
             self.asm.load_mem(
                 MachineMode::Ptr,
                 REG_TMP1.into(),
@@ -316,6 +435,169 @@ where
         }
 
         self.emit_epilog();
+        self.reenter_tries(exited);
+    }
+
+    fn emit_stmt_throw(&mut self, s: &'ast StmtThrowType) {
+        self.emit_expr(&s.expr, REG_RESULT.into());
+        self.asm.test_if_nil_bailout(s.pos, REG_RESULT, Trap::NIL);
+
+        let gcpoint = self.create_gcpoint();
+        self.asm.throw(REG_RESULT, s.pos, gcpoint);
+    }
+
+    fn emit_stmt_try(&mut self, s: &'ast StmtTryType) {
+        let lbl_end = self.asm.create_label();
+        let finally_block = s.finally_block.as_ref().map(|finally| &*finally.block);
+
+        let try_ranges = self.emit_try_region(finally_block, |this| {
+            this.visit_stmt(&s.try_block);
+        });
+
+        if let Some(finally_block) = finally_block {
+            self.visit_stmt(finally_block);
+        }
+
+        self.asm.jump(lbl_end);
+
+        let mut catch_ranges = Vec::new();
+
+        for catch in &s.catch_blocks {
+            let var = *self.src.map_vars.get(catch.id).unwrap();
+            let ty = self.var_ty(var);
+            let cls_def_id = specialize_class_ty(self.vm, ty);
+
+            self.asm.emit_slow_paths_inline();
+            let catch_start = self.asm.pos();
+
+            for &range in &try_ranges {
+                self.asm
+                    .emit_exception_handler(range, catch_start, CatchType::Class(cls_def_id));
+            }
+
+            let ranges = self.emit_try_region(finally_block, |this| {
+                this.managed_stack.push_scope();
+
+                let gcpoint = this.create_gcpoint();
+                this.asm.catch_exception(REG_RESULT, catch.pos, gcpoint);
+
+                let slot_var = this.managed_stack.add_scope(ty, this.vm);
+                this.var_to_slot.insert(var, slot_var);
+                this.asm
+                    .var_store(this.var_offset(var), ty, REG_RESULT.into());
+
+                this.visit_stmt(&catch.block);
+                this.managed_stack.pop_scope(this.vm);
+            });
+
+            if let Some(finally_block) = finally_block {
+                catch_ranges.extend(ranges);
+                self.visit_stmt(finally_block);
+            }
+
+            self.asm.jump(lbl_end);
+        }
+
+        if let Some(finally_block) = finally_block {
+            // exceptions not handled by any catch-block: run finally-block
+            // and rethrow exception afterwards
+            self.asm.emit_slow_paths_inline();
+            let finally_start = self.asm.pos();
+
+            for &range in try_ranges.iter().chain(catch_ranges.iter()) {
+                self.asm
+                    .emit_exception_handler(range, finally_start, CatchType::Any);
+            }
+
+            self.managed_stack.push_scope();
+
+            let gcpoint = self.create_gcpoint();
+            self.asm.catch_exception(REG_RESULT, s.pos, gcpoint);
+
+            let slot = self.managed_stack.add_scope(BuiltinType::Ptr, self.vm);
+            self.asm
+                .var_store(slot.offset(), BuiltinType::Ptr, REG_RESULT.into());
+
+            self.visit_stmt(finally_block);
+
+            self.asm
+                .var_load(slot.offset(), BuiltinType::Ptr, REG_RESULT.into());
+            let gcpoint = self.create_gcpoint();
+            self.asm.throw(REG_RESULT, s.pos, gcpoint);
+
+            self.managed_stack.pop_scope(self.vm);
+        }
+
+        self.asm.bind_label(lbl_end);
+    }
+
+    // Emits code covered by the handlers of a try statement and returns all
+    // code ranges that need to be registered for these handlers.
+    fn emit_try_region<F>(&mut self, finally_block: Option<&'ast Stmt>, f: F) -> Vec<(usize, usize)>
+    where
+        F: FnOnce(&mut AstCodeGen<'a, 'ast>),
+    {
+        // pending bailouts belong to code in front of the try-block
+        self.asm.emit_slow_paths_inline();
+
+        self.active_tries.push(ActiveTry {
+            finally_block,
+            ranges: Vec::new(),
+            start: self.asm.pos(),
+        });
+
+        f(self);
+
+        let mut active_try = self.active_tries.pop().unwrap();
+        self.close_try_range(&mut active_try);
+
+        active_try.ranges
+    }
+
+    fn close_try_range(&mut self, active_try: &mut ActiveTry<'ast>) {
+        self.asm.emit_slow_paths_inline();
+        let end = self.asm.pos();
+
+        if active_try.start < end {
+            active_try.ranges.push((active_try.start, end));
+        }
+    }
+
+    fn has_active_finally(&self) -> bool {
+        self.active_tries
+            .iter()
+            .any(|active_try| active_try.finally_block.is_some())
+    }
+
+    // Leaves all try statements above the given depth: emits their
+    // finally-blocks inline, the emitted code is not covered by their handlers.
+    fn emit_finally_blocks(&mut self, depth: usize) -> Vec<ActiveTry<'ast>> {
+        let mut exited = Vec::new();
+
+        while self.active_tries.len() > depth {
+            let mut active_try = self.active_tries.pop().unwrap();
+            self.close_try_range(&mut active_try);
+
+            if let Some(finally_block) = active_try.finally_block {
+                self.visit_stmt(finally_block);
+            }
+
+            exited.push(active_try);
+        }
+
+        exited
+    }
+
+    // Code after return, break or continue is again covered by the handlers
+    // of the exited try statements.
+    fn reenter_tries(&mut self, exited: Vec<ActiveTry<'ast>>) {
+        self.asm.emit_slow_paths_inline();
+        let start = self.asm.pos();
+
+        for mut active_try in exited.into_iter().rev() {
+            active_try.start = start;
+            self.active_tries.push(active_try);
+        }
     }
 
     fn emit_stmt_while(&mut self, s: &'ast StmtWhileType) {
@@ -427,7 +709,7 @@ where
 
         if var_ty.is_unit() {
             // nothing to do
-        } else if let Some(tuple_id) = var_ty.tuple_id() {
+        } else if let Some(tuple_id) = var_ty.layout_tuple_id(self.vm) {
             let element_size = self.vm.tuples.lock().get_tuple(tuple_id).size();
             self.asm
                 .array_address(REG_TMP1, REG_RESULT, REG_TMP1, element_size);
@@ -440,9 +722,9 @@ where
         } else {
             let dest = result_reg_ty(var_ty).any_reg();
             self.asm
-                .load_array_elem(var_ty.mode(), dest, REG_RESULT, REG_TMP1);
+                .load_array_elem(var_ty.mode(self.vm), dest, REG_RESULT, REG_TMP1);
             self.asm
-                .store_mem(var_ty.mode(), Mem::Local(var_slot.offset()), dest);
+                .store_mem(var_ty.mode(self.vm), Mem::Local(var_slot.offset()), dest);
         }
 
         self.save_label_state(lbl_end, lbl_start, |this| {
@@ -480,7 +762,7 @@ where
         self.managed_stack.push_scope();
 
         // emit: <iterator> = obj.makeIterator()
-        let object_type = self.ty(stmt.expr.id());
+        let object_type = self.specialize_type(for_type_info.make_iterator_type);
         let ctype = CallType::Method(object_type, for_type_info.make_iterator, TypeList::empty());
         let args = vec![Arg::Expr(&stmt.expr)];
         let make_iterator = self.build_call_site(&ctype, for_type_info.make_iterator, args);
@@ -528,8 +810,17 @@ where
         let slot_var = self.managed_stack.add_scope(var_ty, self.vm);
         assert!(self.var_to_slot.insert(for_var_id, slot_var).is_none());
 
-        self.asm
-            .var_store(slot_var.offset(), var_ty, dest.any_reg());
+        if let Some(tuple_id) = var_ty.layout_tuple_id(self.vm) {
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Offset(slot_var.offset()),
+                RegOrOffset::Offset(dest.stack_offset()),
+            );
+            self.free_expr_store(dest);
+        } else if !var_ty.is_unit() {
+            self.asm
+                .var_store(slot_var.offset(), var_ty, dest.any_reg());
+        }
 
         self.save_label_state(lbl_end, lbl_start, |this| {
             // execute while body, then jump back to condition
@@ -554,28 +845,38 @@ where
     {
         let old_lbl_break = self.lbl_break;
         let old_lbl_continue = self.lbl_continue;
+        let old_loop_try_depth = self.loop_try_depth;
 
         self.lbl_break = Some(lbl_break);
         self.lbl_continue = Some(lbl_continue);
+        self.loop_try_depth = self.active_tries.len();
 
         f(self);
 
         self.lbl_break = old_lbl_break;
         self.lbl_continue = old_lbl_continue;
+        self.loop_try_depth = old_loop_try_depth;
     }
 
     fn emit_stmt_break(&mut self, _: &'ast StmtBreakType) {
+        let exited = self.emit_finally_blocks(self.loop_try_depth);
+
         // now jump out of loop
         let lbl_break = self.lbl_break.unwrap();
         self.asm.jump(lbl_break);
+
+        self.reenter_tries(exited);
     }
 
     fn emit_stmt_continue(&mut self, _: &'ast StmtContinueType) {
+        let exited = self.emit_finally_blocks(self.loop_try_depth);
         self.emit_stack_guard();
 
         // now jump to start of loop
         let lbl_continue = self.lbl_continue.unwrap();
         self.asm.jump(lbl_continue);
+
+        self.reenter_tries(exited);
     }
 
     fn emit_stmt_expr(&mut self, s: &'ast StmtExprType) {
@@ -598,10 +899,12 @@ where
         }
 
         let slot_var = self.managed_stack.add_scope(ty, self.vm);
-        assert!(self.var_to_slot.insert(var, slot_var).is_none());
+        // finally-blocks are emitted multiple times, so variables declared
+        // in there are also defined more than once
+        self.var_to_slot.insert(var, slot_var);
 
         if let Some(value) = value {
-            if let Some(tuple_id) = ty.tuple_id() {
+            if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
                 self.copy_tuple(
                     tuple_id,
                     RegOrOffset::Offset(self.var_offset(var)),
@@ -614,7 +917,7 @@ where
                     .var_store(self.var_offset(var), ty, value.any_reg());
             }
             self.free_expr_store(value);
-        } else if ty.reference_type() {
+        } else if ty.reference_type(self.vm) {
             // uninitialized variables which reference objects need to be initialized to null
             // otherwise the GC can't know if the stored value is a valid pointer
             self.asm.load_nil(REG_RESULT);
@@ -634,7 +937,7 @@ where
             .to_owned();
 
         for (&subtype, &subtype_offset) in subtypes.iter().zip(&offsets) {
-            if let Some(tuple_id) = subtype.tuple_id() {
+            if let Some(tuple_id) = subtype.layout_tuple_id(self.vm) {
                 let src = match src {
                     RegOrOffset::Reg(reg) => RegOrOffset::RegWithOffset(reg, subtype_offset),
                     RegOrOffset::RegWithOffset(reg, tuple_offset) => {
@@ -658,7 +961,7 @@ where
                 // nothing
             } else {
                 let tmp = result_reg_ty(subtype);
-                let mode = subtype.mode();
+                let mode = subtype.mode(self.vm);
                 let src = match src {
                     RegOrOffset::Reg(reg) => Mem::Base(reg, subtype_offset),
                     RegOrOffset::RegWithOffset(reg, tuple_offset) => {
@@ -695,10 +998,8 @@ where
     }
 
     fn emit_call_site_old(&mut self, call_site: &CallSite<'ast>, pos: Position) -> ExprStore {
-        let callee = self.vm.fcts.idx(call_site.callee);
-        let callee = callee.read();
-        let return_type = self.specialize_type(callee.return_type);
-        let dest = result_reg_ty(return_type);
+        let return_type = self.specialize_type(call_site.return_type);
+        let dest = self.alloc_expr_store(return_type);
 
         self.emit_call_site(call_site, pos, dest);
 
@@ -720,15 +1021,18 @@ where
             ExprPath(ref expr) => self.emit_path(expr, dest),
             ExprDelegation(ref expr) => self.emit_delegation(expr, dest),
             ExprDot(ref expr) => self.emit_dot(expr, dest),
+            ExprSelf(ref expr) if self.fct.is_lambda() => self.emit_captured_self(expr, dest),
             ExprSelf(_) => self.emit_self(dest),
             ExprSuper(_) => self.emit_self(dest),
             ExprNil(_) => self.emit_nil(dest.reg()),
             ExprConv(ref expr) => self.emit_conv(expr, dest.reg()),
             ExprTemplate(ref expr) => self.emit_template(expr, dest.reg()),
-            ExprLambda(_) => unimplemented!(),
+            ExprLambda(ref expr) => self.emit_lambda(expr, dest.reg()),
             ExprBlock(ref expr) => self.emit_block(expr, dest),
             ExprIf(ref expr) => self.emit_if(expr, dest),
+            ExprMatch(ref expr) => self.emit_match(expr, dest),
             ExprTuple(ref expr) => self.emit_tuple(expr, dest),
+            ExprLitStruct(ref expr) => self.emit_lit_struct(expr, dest),
         }
     }
 
@@ -748,7 +1052,7 @@ where
             let slot = self.managed_stack.add_temp(ty, self.vm);
             slots.push(slot);
 
-            if let Some(tuple_id) = ty.tuple_id() {
+            if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
                 self.copy_tuple(
                     tuple_id,
                     RegOrOffset::Offset(slot.offset),
@@ -775,7 +1079,7 @@ where
         for (value, (slot, &offset)) in e.values.iter().zip(slots.iter().zip(&offsets)) {
             let ty = self.ty(value.id());
 
-            if let Some(tuple_id) = ty.tuple_id() {
+            if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
                 self.copy_tuple(
                     tuple_id,
                     RegOrOffset::Offset(tuple_offset + offset),
@@ -784,7 +1088,7 @@ where
             } else if ty.is_unit() {
                 // do nothing
             } else {
-                let mode = ty.mode();
+                let mode = ty.mode(self.vm);
                 let temp = result_reg_ty(ty);
                 self.asm
                     .load_mem(mode, temp.any_reg(), Mem::Local(slot.offset));
@@ -801,6 +1105,47 @@ where
         self.managed_stack.mark_initialized(slot.var);
     }
 
+    fn emit_lit_struct(&mut self, e: &'ast ExprLitStructType, dest: ExprStore) {
+        let ty = self.ty(e.id);
+        let struct_id = match ty {
+            BuiltinType::Struct(struct_id, _) => struct_id,
+            _ => unreachable!(),
+        };
+
+        // the arguments are evaluated in source order into temporaries first,
+        // the destination is not visible to the GC before it is complete
+        let mut values = Vec::new();
+
+        for arg in &e.args {
+            let arg_ty = self.ty(arg.value.id());
+            let value = self.emit_expr_result_reg(&arg.value);
+
+            let slot = self.managed_stack.add_temp(arg_ty, self.vm);
+            self.store_value(arg_ty, RegOrOffset::Offset(slot.offset), value);
+            self.free_expr_store(value);
+
+            let (field_id, _) = find_field_in_struct(self.vm, struct_id, arg.name).unwrap();
+            values.push((field_id, slot));
+        }
+
+        let struct_offset = dest.stack_offset();
+
+        for (field_id, slot) in values {
+            let field = self.struct_field_def(ty, field_id);
+
+            self.copy_value(
+                field.ty,
+                RegOrOffset::Offset(struct_offset + field.offset),
+                RegOrOffset::Offset(slot.offset),
+            );
+
+            self.managed_stack.free_temp(slot, self.vm);
+        }
+
+        let slot = dest.stack_slot();
+        self.managed_stack.mark_initialized(slot.var);
+    }
+
     fn emit_if(&mut self, e: &'ast ExprIfType, dest: ExprStore) {
         let lbl_end = self.asm.create_label();
         let lbl_else = if let Some(_) = e.else_block {
@@ -825,6 +1170,317 @@ where
         self.asm.bind_label(lbl_end);
     }
 
+    fn emit_match(&mut self, e: &'ast ExprMatchType, dest: ExprStore) {
+        let lbl_end = self.asm.create_label();
+
+        let ty = self.ty(e.expr.id());
+        let mode = ty.mode(self.vm);
+
+        self.managed_stack.push_scope();
+
+        let value_slot = self.managed_stack.add_scope(ty, self.vm);
+        self.emit_expr(&e.expr, REG_RESULT.into());
+        self.asm
+            .store_mem(mode, Mem::Local(value_slot.offset()), REG_RESULT.into());
+
+        for case in &e.cases {
+            let lbl_next = self.asm.create_label();
+
+            self.managed_stack.push_scope();
+
+            match case.pattern {
+                MatchPattern::Underscore(_) => {}
+
+                MatchPattern::Literal(ref lit) => {
+                    self.emit_expr(lit, REG_RESULT.into());
+                    self.asm
+                        .load_mem(mode, REG_TMP1.into(), Mem::Local(value_slot.offset()));
+                    self.asm.cmp_reg(mode, REG_TMP1, REG_RESULT);
+                    self.asm.jump_if(CondCode::NotEqual, lbl_next);
+                }
+
+                MatchPattern::Variant(ref pattern) => {
+                    self.emit_match_variant(pattern, ty, value_slot, lbl_next);
+                }
+            }
+
+            self.emit_expr(&case.value, dest);
+            self.managed_stack.pop_scope(self.vm);
+
+            self.asm.jump(lbl_end);
+            self.asm.bind_label(lbl_next);
+        }
+
+        self.asm.bind_label(lbl_end);
+        self.managed_stack.pop_scope(self.vm);
+    }
+
+    fn emit_match_variant(
+        &mut self,
+        pattern: &'ast MatchPatternVariantType,
+        ty: BuiltinType,
+        value_slot: ManagedStackSlot,
+        lbl_next: Label,
+    ) {
+        let value = match self.src.map_idents.get(pattern.id) {
+            Some(&IdentType::EnumValue(_, value)) => value,
+            _ => unreachable!(),
+        };
+
+        let enum_def_id = self.specialize_enum_ty(ty);
+        let enum_def = self.vm.enum_defs.idx(enum_def_id);
+        let (layout, cls_def_id) = {
+            let enum_def = enum_def.read();
+            (
+                enum_def.layout,
+                enum_def.variants[value as usize].cls_def_id,
+            )
+        };
+
+        match layout {
+            EnumLayout::Int => {
+                self.asm.load_mem(
+                    MachineMode::Int32,
+                    REG_RESULT.into(),
+                    Mem::Local(value_slot.offset()),
+                );
+                self.asm
+                    .cmp_reg_imm(MachineMode::Int32, REG_RESULT, value as i32);
+                self.asm.jump_if(CondCode::NotEqual, lbl_next);
+            }
+
+            EnumLayout::Tagged => {
+                let cls = self.vm.class_defs.idx(cls_def_id.unwrap());
+                let fields = cls.read().fields.clone();
+
+                self.asm.load_mem(
+                    MachineMode::Ptr,
+                    REG_RESULT.into(),
+                    Mem::Local(value_slot.offset()),
+                );
+                self.asm.load_mem(
+                    MachineMode::Int32,
+                    REG_RESULT.into(),
+                    Mem::Base(REG_RESULT, fields[0].offset),
+                );
+                self.asm
+                    .cmp_reg_imm(MachineMode::Int32, REG_RESULT, value as i32);
+                self.asm.jump_if(CondCode::NotEqual, lbl_next);
+
+                let params = match pattern.params {
+                    Some(ref params) => params,
+                    None => return,
+                };
+
+                for (param, field) in params.iter().zip(&fields[1..]) {
+                    if param.name.is_none() || field.ty.is_unit() {
+                        continue;
+                    }
+
+                    let var = *self.src.map_vars.get(param.id).unwrap();
+                    let slot = self.managed_stack.add_scope(field.ty, self.vm);
+                    self.var_to_slot.insert(var, slot);
+
+                    self.asm.load_mem(
+                        MachineMode::Ptr,
+                        REG_TMP1.into(),
+                        Mem::Local(value_slot.offset()),
+                    );
+
+                    if let Some(tuple_id) = field.ty.layout_tuple_id(self.vm) {
+                        self.copy_tuple(
+                            tuple_id,
+                            RegOrOffset::Offset(slot.offset()),
+                            RegOrOffset::RegWithOffset(REG_TMP1, field.offset),
+                        );
+                    } else {
+                        let reg: AnyReg = if field.ty.is_float() {
+                            FREG_RESULT.into()
+                        } else {
+                            REG_RESULT.into()
+                        };
+
+                        self.asm.load_mem(
+                            field.ty.mode(self.vm),
+                            reg,
+                            Mem::Base(REG_TMP1, field.offset),
+                        );
+                        self.asm.var_store(slot.offset(), field.ty, reg);
+                    }
+                }
+            }
+
+            EnumLayout::Ptr => unreachable!(),
+        }
+    }
+
+    fn emit_enum_variant(
+        &mut self,
+        pos: Position,
+        ty: BuiltinType,
+        value: u32,
+        args: &'ast [Box<Expr>],
+        dest: ExprStore,
+    ) {
+        let enum_def_id = self.specialize_enum_ty(ty);
+        let enum_def = self.vm.enum_defs.idx(enum_def_id);
+        let (layout, cls_def_id) = {
+            let enum_def = enum_def.read();
+            (
+                enum_def.layout,
+                enum_def.variants[value as usize].cls_def_id,
+            )
+        };
+
+        match layout {
+            EnumLayout::Int => {
+                self.asm
+                    .load_int_const(MachineMode::Int32, dest.reg(), value as i64);
+                return;
+            }
+
+            EnumLayout::Tagged => {}
+            EnumLayout::Ptr => unreachable!(),
+        }
+
+        let cls_def_id = cls_def_id.unwrap();
+        let fields = self.vm.class_defs.idx(cls_def_id).read().fields.clone();
+        let object_slot = self.emit_allocation(pos, ArrayLength::Fixed(0), cls_def_id);
+
+        // the tag is always stored in the first field of the variant
+        self.asm.load_mem(
+            MachineMode::Ptr,
+            REG_TMP1.into(),
+            Mem::Local(object_slot.offset()),
+        );
+        self.asm
+            .load_int_const(MachineMode::Int32, REG_TMP2, value as i64);
+        self.asm.store_mem(
+            MachineMode::Int32,
+            Mem::Base(REG_TMP1, fields[0].offset),
+            REG_TMP2.into(),
+        );
+
+        for (arg, field) in args.iter().zip(&fields[1..]) {
+            let value = self.emit_expr_result_reg(arg);
+            self.asm.load_mem(
+                MachineMode::Ptr,
+                REG_TMP1.into(),
+                Mem::Local(object_slot.offset()),
+            );
+
+            let needs_write_barrier = if let Some(tuple_id) = field.ty.layout_tuple_id(self.vm) {
+                self.copy_tuple(
+                    tuple_id,
+                    RegOrOffset::RegWithOffset(REG_TMP1, field.offset),
+                    RegOrOffset::Offset(value.stack_offset()),
+                );
+
+                self.vm
+                    .tuples
+                    .lock()
+                    .get_tuple(tuple_id)
+                    .contains_references()
+            } else if field.ty.is_unit() {
+                false
+            } else {
+                self.asm.store_mem(
+                    field.ty.mode(self.vm),
+                    Mem::Base(REG_TMP1, field.offset),
+                    value.any_reg(),
+                );
+
+                field.ty.reference_type(self.vm)
+            };
+
+            if self.vm.gc.needs_write_barrier() && needs_write_barrier {
+                let card_table_offset = self.vm.gc.card_table_offset();
+                self.asm.emit_barrier(REG_TMP1, card_table_offset);
+            }
+
+            self.free_expr_store(value);
+        }
+
+        self.asm.load_mem(
+            MachineMode::Ptr,
+            dest.reg().into(),
+            Mem::Local(object_slot.offset()),
+        );
+        self.managed_stack.free_temp(object_slot, self.vm);
+    }
+
+    fn emit_lambda(&mut self, e: &'ast ExprLambdaType, dest: Reg) {
+        let lambda_id = *self.src.map_lambdas.get(e.id).unwrap();
+        let lambda = self.vm.fcts.idx(lambda_id);
+        let lambda = lambda.read();
+
+        // lambdas are specialized with the type params of the enclosing function
+        let cls_def_id = specialize_lambda(
+            self.vm,
+            &*lambda,
+            self.cls_type_params.clone(),
+            self.fct_type_params.clone(),
+        );
+        let fields = self.vm.class_defs.idx(cls_def_id).read().fields.clone();
+        let captures = lambda.src().read().captures.clone();
+
+        let object_slot = self.emit_allocation(e.pos, ArrayLength::Fixed(0), cls_def_id);
+
+        for (capture, field) in captures.iter().zip(&fields) {
+            let ty = field.ty;
+
+            {
+                let name = self.vm.interner.str(capture.name);
+                self.asm.emit_comment(format!("capture var {}", name));
+            }
+
+            let needs_write_barrier = if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
+                self.asm.load_mem(
+                    MachineMode::Ptr,
+                    REG_TMP1.into(),
+                    Mem::Local(object_slot.offset()),
+                );
+                self.copy_tuple(
+                    tuple_id,
+                    RegOrOffset::RegWithOffset(REG_TMP1, field.offset),
+                    RegOrOffset::Offset(self.var_offset(capture.outer)),
+                );
+
+                self.vm
+                    .tuples
+                    .lock()
+                    .get_tuple(tuple_id)
+                    .contains_references()
+            } else if ty.is_unit() {
+                false
+            } else {
+                let reg = result_reg_ty(ty).any_reg();
+                self.asm.var_load(self.var_offset(capture.outer), ty, reg);
+                self.asm.load_mem(
+                    MachineMode::Ptr,
+                    REG_TMP1.into(),
+                    Mem::Local(object_slot.offset()),
+                );
+                self.asm
+                    .store_mem(ty.mode(self.vm), Mem::Base(REG_TMP1, field.offset), reg);
+
+                ty.reference_type(self.vm)
+            };
+
+            if self.vm.gc.needs_write_barrier() && needs_write_barrier {
+                let card_table_offset = self.vm.gc.card_table_offset();
+                self.asm.emit_barrier(REG_TMP1, card_table_offset);
+            }
+        }
+
+        self.asm.load_mem(
+            MachineMode::Ptr,
+            dest.into(),
+            Mem::Local(object_slot.offset()),
+        );
+        self.managed_stack.free_temp(object_slot, self.vm);
+    }
+
     fn emit_block(&mut self, block: &'ast ExprBlockType, dest: ExprStore) {
         self.managed_stack.push_scope();
 
@@ -1083,7 +1739,10 @@ where
             return Some(intrinsic);
         }
 
-        let fid = call_type.fct_id().unwrap();
+        let fid = match call_type.fct_id() {
+            Some(fid) => fid,
+            None => return None,
+        };
 
         // the function we compile right now is never an intrinsic
         if self.fct.id == fid {
@@ -1099,66 +1758,266 @@ where
         }
     }
 
-    fn emit_self(&mut self, dest: ExprStore) {
-        let var = self.src.var_self();
+    // self was captured from the enclosing method
+    fn emit_captured_self(&mut self, e: &'ast ExprSelfType, dest: ExprStore) {
+        let var_id = self.src.map_idents.get(e.id).unwrap().var_id();
+        let ty = self.var_ty(var_id);
+
+        self.asm.emit_comment("load captured self".into());
+        self.asm
+            .var_load(self.var_offset(var_id), ty, dest.any_reg());
+    }
+
+    fn emit_self(&mut self, dest: ExprStore) {
+        let var = self.src.var_self();
+
+        self.asm.emit_comment("load self".into());
+
+        let offset = self.var_offset(var.id);
+        self.asm
+            .load_mem(var.ty.mode(self.vm), dest.any_reg(), Mem::Local(offset));
+    }
+
+    fn emit_nil(&mut self, dest: Reg) {
+        self.asm.load_nil(dest);
+    }
+
+    fn emit_dot(&mut self, expr: &'ast ExprDotType, dest: ExprStore) {
+        let object_ty = self.ty(expr.lhs.id());
+
+        if let Some(tuple_id) = object_ty.tuple_id() {
+            self.emit_dot_tuple(expr, tuple_id, dest);
+            return;
+        }
+
+        if let Some(&IdentType::StructField(ty, field_id)) = self.src.map_idents.get(expr.id) {
+            let field = self.struct_field_def(ty, field_id);
+            self.emit_value_element(&expr.lhs, field.ty, field.offset, dest);
+            return;
+        }
+
+        let (ty, field) = {
+            let ident_type = self.src.map_idents.get(expr.id).unwrap();
+
+            match ident_type {
+                &IdentType::Field(ty, field) => (ty, field),
+                _ => unreachable!(),
+            }
+        };
+
+        let ty = self.specialize_type(ty);
+
+        self.emit_expr(&expr.lhs, REG_TMP1.into());
+        self.emit_field_access(expr.pos, ty, field, REG_TMP1, dest);
+    }
+
+    fn emit_dot_tuple(&mut self, expr: &'ast ExprDotType, tuple_id: TupleId, dest: ExprStore) {
+        let idx = expr.rhs.to_lit_int().unwrap().value as usize;
+        let (ty, offset) = self.vm.tuples.lock().get_at(tuple_id, idx);
+
+        self.emit_value_element(&expr.lhs, ty, offset, dest);
+    }
+
+    // loads the element at `offset` of a tuple or struct value
+    fn emit_value_element(
+        &mut self,
+        object: &'ast Expr,
+        ty: BuiltinType,
+        offset: i32,
+        dest: ExprStore,
+    ) {
+        let value = self.emit_expr_result_reg(object);
+
+        if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Offset(dest.stack_offset()),
+                RegOrOffset::Offset(value.stack_offset() + offset),
+            );
+        } else if ty.is_unit() {
+            assert!(dest.is_none());
+        } else {
+            self.asm.load_mem(
+                ty.mode(self.vm),
+                dest.any_reg(),
+                Mem::Local(value.stack_offset() + offset),
+            );
+        }
+
+        self.free_expr_store(value);
+    }
+
+    fn struct_field_def(&self, ty: BuiltinType, field_id: StructFieldId) -> StructFieldDef {
+        let (struct_id, list_id) = match self.specialize_type(ty) {
+            BuiltinType::Struct(struct_id, list_id) => (struct_id, list_id),
+            _ => unreachable!(),
+        };
+
+        let params = self.vm.lists.lock().get(list_id);
+        let struct_def_id = specialize_struct_id_params(self.vm, struct_id, params);
+        let struct_def = self.vm.struct_defs.idx(struct_def_id);
+        let struct_def = struct_def.lock();
+
+        struct_def.fields[field_id.idx()].clone()
+    }
+
+    // finds the variable, global or class field a struct value is stored in,
+    // together with the offset of the value in there
+    fn struct_place(&self, e: &'ast Expr) -> (StructPlace<'ast>, i32) {
+        match *e {
+            ExprIdent(ref ident) => match self.src.map_idents.get(ident.id) {
+                Some(&IdentType::Var(var_id)) => (StructPlace::Var(var_id), 0),
+                Some(&IdentType::Global(global_id)) => (StructPlace::Global(global_id), 0),
+                _ => unreachable!(),
+            },
+
+            ExprDot(ref dot) => match self.src.map_idents.get(dot.id) {
+                Some(&IdentType::StructField(ty, field_id)) => {
+                    let (place, offset) = self.struct_place(&dot.lhs);
+                    let field = self.struct_field_def(ty, field_id);
+
+                    (place, offset + field.offset)
+                }
+
+                Some(&IdentType::Field(cls_ty, field_id)) => {
+                    (StructPlace::Field(&dot.lhs, cls_ty, field_id), 0)
+                }
+
+                _ => unreachable!(),
+            },
+
+            _ => unreachable!(),
+        }
+    }
+
+    fn emit_assign_struct_field(
+        &mut self,
+        e: &'ast ExprBinType,
+        struct_ty: BuiltinType,
+        field_id: StructFieldId,
+    ) {
+        let dot = e.lhs.to_dot().unwrap();
+        let (place, offset) = self.struct_place(&dot.lhs);
+        let field = self.struct_field_def(struct_ty, field_id);
+        let offset = offset + field.offset;
+
+        match place {
+            StructPlace::Var(var_id) => {
+                let value = self.emit_expr_result_reg(&e.rhs);
+                let var_offset = self.var_offset(var_id);
+
+                {
+                    let var = &self.src.vars[var_id];
+                    let name = self.vm.interner.str(var.name);
+                    self.asm
+                        .emit_comment(format!("store field of var {}", name));
+                }
+
+                self.store_value(field.ty, RegOrOffset::Offset(var_offset + offset), value);
+                self.free_expr_store(value);
+            }
+
+            StructPlace::Global(global_id) => {
+                let glob = self.vm.globals.idx(global_id);
+                let glob = glob.read();
+
+                // the rest of the struct needs to be initialized first
+                if glob.needs_initialization() {
+                    let fid = glob.initializer.unwrap();
+                    let ptr = self.ptr_for_fct_id(fid, TypeList::empty(), TypeList::empty());
+                    let gcpoint = self.create_gcpoint();
+                    self.asm.ensure_global(&*glob, fid, ptr, glob.pos, gcpoint);
+                }
 
-        self.asm.emit_comment("load self".into());
+                let value = self.emit_expr_result_reg(&e.rhs);
 
-        let offset = self.var_offset(var.id);
-        self.asm
-            .load_mem(var.ty.mode(), dest.any_reg(), Mem::Local(offset));
-    }
+                let disp = self.asm.add_addr(glob.address_value.to_ptr());
+                let pos = self.asm.pos() as i32;
+                let name = self.vm.interner.str(glob.name);
+                self.asm
+                    .emit_comment(format!("store field of global {}", name));
+                self.asm.load_constpool(REG_TMP1, disp + pos);
 
-    fn emit_nil(&mut self, dest: Reg) {
-        self.asm.load_nil(dest);
-    }
+                self.store_value(
+                    field.ty,
+                    RegOrOffset::RegWithOffset(REG_TMP1, offset),
+                    value,
+                );
+                self.free_expr_store(value);
+            }
 
-    fn emit_dot(&mut self, expr: &'ast ExprDotType, dest: ExprStore) {
-        let object_ty = self.ty(expr.lhs.id());
+            StructPlace::Field(object, cls_ty, cls_field_id) => {
+                let cls_ty = self.specialize_type(cls_ty);
+                let cls_def_id = specialize_class_ty(self.vm, cls_ty);
+                let cls = self.vm.class_defs.idx(cls_def_id);
+                let field_offset = cls.read().fields[cls_field_id.idx()].offset;
 
-        if let Some(tuple_id) = object_ty.tuple_id() {
-            self.emit_dot_tuple(expr, tuple_id, dest);
-            return;
-        }
+                self.emit_expr(object, REG_RESULT.into());
 
-        let (ty, field) = {
-            let ident_type = self.src.map_idents.get(expr.id).unwrap();
+                let object_slot = self.add_temp_node(object);
+                self.asm.store_mem(
+                    MachineMode::Ptr,
+                    Mem::Local(object_slot.offset()),
+                    REG_RESULT.into(),
+                );
 
-            match ident_type {
-                &IdentType::Field(ty, field) => (ty, field),
-                _ => unreachable!(),
-            }
-        };
+                let value = self.emit_expr_result_reg(&e.rhs);
+                self.asm.load_mem(
+                    MachineMode::Ptr,
+                    REG_TMP1.into(),
+                    Mem::Local(object_slot.offset()),
+                );
 
-        let ty = self.specialize_type(ty);
+                self.asm
+                    .emit_comment("store field of struct in object".into());
+                self.asm.test_if_nil_bailout(e.pos, REG_TMP1, Trap::NIL);
 
-        self.emit_expr(&expr.lhs, REG_TMP1.into());
-        self.emit_field_access(expr.pos, ty, field, REG_TMP1, dest);
-    }
+                let needs_write_barrier = self.store_value(
+                    field.ty,
+                    RegOrOffset::RegWithOffset(REG_TMP1, field_offset + offset),
+                    value,
+                );
 
-    fn emit_dot_tuple(&mut self, expr: &'ast ExprDotType, tuple_id: TupleId, dest: ExprStore) {
-        let tuple = self.emit_expr_result_reg(&expr.lhs);
+                if self.vm.gc.needs_write_barrier() && needs_write_barrier {
+                    let card_table_offset = self.vm.gc.card_table_offset();
+                    self.asm.emit_barrier(REG_TMP1, card_table_offset);
+                }
 
-        let idx = expr.rhs.to_lit_int().unwrap().value as usize;
-        let (ty, offset) = self.vm.tuples.lock().get_at(tuple_id, idx);
+                self.free_expr_store(value);
+                self.managed_stack.free_temp(object_slot, self.vm);
+            }
+        }
+    }
 
-        if let Some(tuple_id) = ty.tuple_id() {
-            self.copy_tuple(
-                tuple_id,
-                RegOrOffset::Offset(dest.stack_offset()),
-                RegOrOffset::Offset(tuple.stack_offset() + offset),
-            );
+    // stores the evaluated value to `dest`, returns whether references were stored
+    fn store_value(&mut self, ty: BuiltinType, dest: RegOrOffset, value: ExprStore) -> bool {
+        if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
+            self.copy_tuple(tuple_id, dest, RegOrOffset::Offset(value.stack_offset()));
+
+            self.vm
+                .tuples
+                .lock()
+                .get_tuple(tuple_id)
+                .contains_references()
         } else if ty.is_unit() {
-            assert!(dest.is_none());
+            false
         } else {
-            self.asm.load_mem(
-                ty.mode(),
-                dest.any_reg(),
-                Mem::Local(tuple.stack_offset() + offset),
-            );
+            self.asm
+                .store_mem(ty.mode(self.vm), dest.mem(), value.any_reg());
+
+            ty.reference_type(self.vm)
         }
+    }
 
-        self.free_expr_store(tuple);
+    fn copy_value(&mut self, ty: BuiltinType, dest: RegOrOffset, src: RegOrOffset) {
+        if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
+            self.copy_tuple(tuple_id, dest, src);
+        } else if !ty.is_unit() {
+            let mode = ty.mode(self.vm);
+            let tmp = result_reg_ty(ty).any_reg();
+            self.asm.load_mem(mode, tmp, src.mem());
+            self.asm.store_mem(mode, dest.mem(), tmp);
+        }
     }
 
     fn emit_field_access(
@@ -1176,11 +2035,7 @@ where
 
         {
             let cname = cls.name(self.vm);
-            let class_id = cls.cls_id.expect("no corresponding class");
-            let class = self.vm.classes.idx(class_id);
-            let class = class.read();
-            let field = &class.fields[fieldid];
-            let fname = self.vm.interner.str(field.name);
+            let fname = self.field_name(&*cls, fieldid);
 
             self.asm
                 .emit_comment(format!("load field {}.{}", cname, fname));
@@ -1188,7 +2043,7 @@ where
 
         self.asm.test_if_nil_bailout(pos, src, Trap::NIL);
 
-        if let Some(tuple_id) = field.ty.tuple_id() {
+        if let Some(tuple_id) = field.ty.layout_tuple_id(self.vm) {
             self.copy_tuple(
                 tuple_id,
                 RegOrOffset::Offset(dest.stack_offset()),
@@ -1198,13 +2053,28 @@ where
             assert!(dest.is_none());
         } else {
             self.asm.load_mem(
-                field.ty.mode(),
+                field.ty.mode(self.vm),
                 dest.any_reg(),
                 Mem::Base(src, field.offset),
             );
         }
     }
 
+    fn field_name(&self, cls: &ClassDef, field_id: FieldId) -> String {
+        let name = if let Some(module_id) = cls.module {
+            let module = self.vm.modules.idx(module_id);
+            let module = module.read();
+            module.fields[field_id].name
+        } else {
+            let cls_id = cls.cls_id.expect("no corresponding class");
+            let class = self.vm.classes.idx(cls_id);
+            let class = class.read();
+            class.fields[field_id].name
+        };
+
+        self.vm.interner.str(name).to_string()
+    }
+
     fn emit_lit_char(&mut self, lit: &'ast ExprLitCharType, dest: Reg) {
         self.asm
             .load_int_const(MachineMode::Int32, dest, lit.value as i64);
@@ -1280,7 +2150,7 @@ where
                         self.asm.emit_comment(format!("load var {}", name));
                     }
 
-                    if let Some(tuple_id) = ty.tuple_id() {
+                    if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
                         self.copy_tuple(
                             tuple_id,
                             RegOrOffset::Offset(dest.stack_offset()),
@@ -1294,45 +2164,11 @@ where
             }
 
             &IdentType::Global(gid) => {
-                let glob = self.vm.globals.idx(gid);
-                let glob = glob.read();
-
-                if glob.ty.is_unit() {
-                    assert!(dest.is_none());
-                } else {
-                    if glob.needs_initialization() {
-                        let fid = glob.initializer.unwrap();
-                        let ptr = self.ptr_for_fct_id(fid, TypeList::empty(), TypeList::empty());
-                        let gcpoint = self.create_gcpoint();
-                        self.asm.ensure_global(&*glob, fid, ptr, glob.pos, gcpoint);
-                    }
-
-                    let disp = self.asm.add_addr(glob.address_value.to_ptr());
-                    let pos = self.asm.pos() as i32;
-
-                    let name = self.vm.interner.str(glob.name);
-                    self.asm.emit_comment(format!("load global {}", name));
-
-                    self.asm.load_constpool(REG_TMP1, disp + pos);
-
-                    if let Some(tuple_id) = glob.ty.tuple_id() {
-                        self.copy_tuple(
-                            tuple_id,
-                            RegOrOffset::Offset(dest.stack_offset()),
-                            RegOrOffset::Reg(REG_TMP1),
-                        );
-                    } else {
-                        self.asm
-                            .load_mem(glob.ty.mode(), dest.any_reg(), Mem::Base(REG_TMP1, 0));
-                    }
-                }
+                self.emit_load_global(gid, dest);
             }
 
-            &IdentType::Field(_, _) => unreachable!(),
-
-            &IdentType::Struct(_) => {
-                unimplemented!();
-            }
+            &IdentType::Field(_, _) | &IdentType::StructField(_, _) => unreachable!(),
+            &IdentType::Struct(_) => unreachable!(),
 
             &IdentType::Const(const_id) => {
                 self.emit_const(const_id, dest.any_reg());
@@ -1341,7 +2177,12 @@ where
             &IdentType::Enum(_) | &IdentType::EnumValue(_, _) => unreachable!(),
             &IdentType::Fct(_) | &IdentType::FctType(_, _) => unreachable!(),
             &IdentType::Class(_) | &IdentType::ClassType(_, _) => unreachable!(),
-            &IdentType::Module(_) => unreachable!(),
+            &IdentType::Module(module_id) => {
+                // the module object lives in a hidden global
+                let global_id = self.vm.modules.idx(module_id).read().global_id;
+                self.emit_load_global(global_id, dest);
+            }
+
             &IdentType::Method(_, _) | &IdentType::MethodType(_, _, _) => unreachable!(),
             &IdentType::TypeParam(_) | &IdentType::TypeParamStaticMethod(_, _) => unreachable!(),
             &IdentType::StaticMethod(_, _) | &IdentType::StaticMethodType(_, _, _) => {
@@ -1350,6 +2191,81 @@ where
         }
     }
 
+    fn emit_load_global(&mut self, gid: GlobalId, dest: ExprStore) {
+        let glob = self.vm.globals.idx(gid);
+        let glob = glob.read();
+
+        if glob.ty.is_unit() {
+            assert!(dest.is_none());
+            return;
+        }
+
+        if glob.needs_initialization() {
+            let fid = glob.initializer.unwrap();
+            let ptr = self.ptr_for_fct_id(fid, TypeList::empty(), TypeList::empty());
+            let gcpoint = self.create_gcpoint();
+            self.asm.ensure_global(&*glob, fid, ptr, glob.pos, gcpoint);
+        }
+
+        let disp = self.asm.add_addr(glob.address_value.to_ptr());
+        let pos = self.asm.pos() as i32;
+
+        let name = self.vm.interner.str(glob.name);
+        self.asm.emit_comment(format!("load global {}", name));
+
+        self.asm.load_constpool(REG_TMP1, disp + pos);
+
+        if let Some(tuple_id) = glob.ty.layout_tuple_id(self.vm) {
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Offset(dest.stack_offset()),
+                RegOrOffset::Reg(REG_TMP1),
+            );
+        } else {
+            self.asm.load_mem(
+                glob.ty.mode(self.vm),
+                dest.any_reg(),
+                Mem::Base(REG_TMP1, 0),
+            );
+        }
+    }
+
+    fn emit_store_global(&mut self, gid: GlobalId, value: ExprStore) {
+        let glob = self.vm.globals.idx(gid);
+        let glob = glob.read();
+        let ty = glob.ty;
+
+        if ty.is_unit() {
+            return;
+        }
+
+        let disp = self.asm.add_addr(glob.address_value.to_ptr());
+        let pos = self.asm.pos() as i32;
+        let name = self.vm.interner.str(glob.name);
+        self.asm.emit_comment(format!("store global {}", name));
+        self.asm.load_constpool(REG_TMP1, disp + pos);
+
+        if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Reg(REG_TMP1),
+                RegOrOffset::Offset(value.stack_offset()),
+            );
+        } else {
+            self.asm
+                .store_mem(ty.mode(self.vm), Mem::Base(REG_TMP1, 0), value.any_reg());
+        }
+
+        if glob.needs_initialization() {
+            let disp = self.asm.add_addr(glob.address_init.to_ptr());
+            let pos = self.asm.pos() as i32;
+            self.asm.load_constpool(REG_RESULT, disp + pos);
+            self.asm.load_int_const(MachineMode::Int8, REG_TMP1, 1);
+            self.asm
+                .store_mem(MachineMode::Int8, Mem::Base(REG_RESULT, 0), REG_TMP1.into());
+        }
+    }
+
     fn emit_const(&mut self, const_id: ConstId, dest: AnyReg) {
         let xconst = self.vm.consts.idx(const_id);
         let xconst = xconst.lock();
@@ -1374,12 +2290,12 @@ where
 
             BuiltinType::UInt8 | BuiltinType::Int | BuiltinType::Int64 => {
                 self.asm
-                    .load_int_const(ty.mode(), dest.reg(), xconst.value.to_int());
+                    .load_int_const(ty.mode(self.vm), dest.reg(), xconst.value.to_int());
             }
 
             BuiltinType::Float | BuiltinType::Double => {
                 self.asm
-                    .load_float_const(ty.mode(), dest.freg(), xconst.value.to_float());
+                    .load_float_const(ty.mode(self.vm), dest.freg(), xconst.value.to_float());
             }
 
             _ => unimplemented!(),
@@ -1573,7 +2489,7 @@ where
 
                     let offset = self.var_offset(varid);
 
-                    if let Some(tuple_id) = ty.tuple_id() {
+                    if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
                         self.copy_tuple(
                             tuple_id,
                             RegOrOffset::Offset(offset),
@@ -1588,43 +2504,8 @@ where
             }
 
             &IdentType::Global(gid) => {
-                let glob = self.vm.globals.idx(gid);
-                let glob = glob.read();
-                let ty = glob.ty;
-
                 let value = self.emit_expr_result_reg(&e.rhs);
-
-                if !ty.is_unit() {
-                    let disp = self.asm.add_addr(glob.address_value.to_ptr());
-                    let pos = self.asm.pos() as i32;
-                    let name = self.vm.interner.str(glob.name);
-                    self.asm.emit_comment(format!("store global {}", name));
-                    self.asm.load_constpool(REG_TMP1, disp + pos);
-
-                    if let Some(tuple_id) = ty.tuple_id() {
-                        self.copy_tuple(
-                            tuple_id,
-                            RegOrOffset::Reg(REG_TMP1),
-                            RegOrOffset::Offset(value.stack_offset()),
-                        );
-                    } else {
-                        self.asm
-                            .store_mem(ty.mode(), Mem::Base(REG_TMP1, 0), value.any_reg());
-                    }
-
-                    if glob.needs_initialization() {
-                        let disp = self.asm.add_addr(glob.address_init.to_ptr());
-                        let pos = self.asm.pos() as i32;
-                        self.asm.load_constpool(REG_RESULT, disp + pos);
-                        self.asm.load_int_const(MachineMode::Int8, REG_TMP1, 1);
-                        self.asm.store_mem(
-                            MachineMode::Int8,
-                            Mem::Base(REG_RESULT, 0),
-                            REG_TMP1.into(),
-                        );
-                    }
-                }
-
+                self.emit_store_global(gid, value);
                 self.free_expr_store(value);
             }
 
@@ -1639,6 +2520,10 @@ where
                     self.emit_expr(&expr_field.lhs, REG_RESULT.into());
 
                     &expr_field.lhs
+                } else if let Some(expr_path) = e.lhs.to_path() {
+                    self.emit_expr(&expr_path.lhs, REG_RESULT.into());
+
+                    &expr_path.lhs
                 } else {
                     self.emit_self(REG_RESULT.into());
 
@@ -1661,18 +2546,15 @@ where
 
                 {
                     let cname = cls.name(self.vm);
-                    let class_id = cls.cls_id.expect("no corresponding class");
-                    let class = self.vm.classes.idx(class_id);
-                    let class = class.read();
-                    let field = &class.fields[fieldid];
-                    let fname = self.vm.interner.str(field.name);
+                    let fname = self.field_name(&*cls, fieldid);
                     self.asm
                         .emit_comment(format!("store field {}.{}", cname, fname));
                 }
 
                 self.asm.test_if_nil_bailout(e.pos, REG_TMP1, Trap::NIL);
 
-                let needs_write_barrier = if let Some(tuple_id) = field.ty.tuple_id() {
+                let needs_write_barrier = if let Some(tuple_id) = field.ty.layout_tuple_id(self.vm)
+                {
                     self.copy_tuple(
                         tuple_id,
                         RegOrOffset::RegWithOffset(REG_TMP1, field.offset),
@@ -1688,12 +2570,12 @@ where
                     false
                 } else {
                     self.asm.store_mem(
-                        field.ty.mode(),
+                        field.ty.mode(self.vm),
                         Mem::Base(REG_TMP1, field.offset),
                         value.any_reg(),
                     );
 
-                    field.ty.reference_type()
+                    field.ty.reference_type(self.vm)
                 };
 
                 if self.vm.gc.needs_write_barrier() && needs_write_barrier {
@@ -1705,11 +2587,14 @@ where
                 self.managed_stack.free_temp(object_slot, self.vm);
             }
 
-            &IdentType::Struct(_) => {
-                unimplemented!();
+            &IdentType::StructField(ty, field_id) => {
+                self.emit_assign_struct_field(e, ty, field_id);
             }
 
-            &IdentType::Const(_) | &IdentType::Enum(_) | &IdentType::EnumValue(_, _) => {
+            &IdentType::Const(_)
+            | &IdentType::Enum(_)
+            | &IdentType::EnumValue(_, _)
+            | &IdentType::Struct(_) => {
                 unreachable!();
             }
 
@@ -1772,10 +2657,10 @@ where
             BuiltinType::Nil => MachineMode::Ptr,
             BuiltinType::Float => MachineMode::Int32,
             BuiltinType::Double => MachineMode::Int64,
-            _ => builtin_type.mode(),
+            _ => builtin_type.mode(self.vm),
         };
         let slot = if builtin_type.is_float() {
-            let src_mode = builtin_type.mode();
+            let src_mode = builtin_type.mode(self.vm);
 
             self.emit_expr(&e.lhs, FREG_RESULT.into());
             self.asm
@@ -1901,8 +2786,15 @@ where
 
         match ident_type {
             &IdentType::EnumValue(_, value) => {
-                self.asm
-                    .load_int_const(MachineMode::Int32, dest.reg(), value as i64);
+                let ty = self.ty(e.id);
+                self.emit_enum_variant(e.pos, ty, value, &[], dest);
+            }
+
+            &IdentType::Field(ty, field) => {
+                let ty = self.specialize_type(ty);
+
+                self.emit_expr(&e.lhs, REG_TMP1.into());
+                self.emit_field_access(e.pos, ty, field, REG_TMP1, dest);
             }
 
             _ => unreachable!(),
@@ -1910,6 +2802,12 @@ where
     }
 
     fn emit_call(&mut self, e: &'ast ExprCallType, dest: ExprStore) {
+        if let Some(&IdentType::EnumValue(_, value)) = self.src.map_idents.get(e.callee.id()) {
+            let ty = self.ty(e.id);
+            self.emit_enum_variant(e.pos, ty, value, &e.args, dest);
+            return;
+        }
+
         let call_type = self.src.map_calls.get(e.id).unwrap().clone();
 
         if let Some(intrinsic) = self.get_intrinsic(e.id) {
@@ -1918,7 +2816,7 @@ where
             if call_type.is_expr() {
                 args.push(&e.callee);
             } else if call_type.is_method() {
-                args.push(e.object().unwrap());
+                args.push(e.receiver().unwrap());
             }
 
             for arg in &e.args {
@@ -1926,6 +2824,12 @@ where
             }
 
             self.emit_call_intrinsic(e.id, e.pos, &args, intrinsic, dest);
+        } else if let CallType::Lambda(lambda_ty) = *call_type {
+            let mut args = e.args.iter().map(|arg| &**arg).collect::<Vec<_>>();
+            args.insert(0, &e.callee);
+
+            let call_site = self.build_call_site_lambda(lambda_ty, args);
+            self.emit_call_site(&call_site, e.pos, dest);
         } else {
             let mut args = e.args.iter().map(|arg| Arg::Expr(arg)).collect::<Vec<_>>();
 
@@ -1943,7 +2847,7 @@ where
                 }
 
                 CallType::Method(_, fct_id, _) => {
-                    let object = e.object().unwrap();
+                    let object = e.receiver().unwrap();
                     args.insert(0, Arg::Expr(object));
 
                     let fct = self.vm.fcts.idx(fct_id);
@@ -2008,8 +2912,14 @@ where
                     impl_fct_id.expect("no impl_fct_id found")
                 }
 
-                CallType::Trait(_, _) => unimplemented!(),
-                CallType::Intrinsic(_) => unreachable!(),
+                CallType::Trait(_, fct_id, _) => {
+                    let object = e.receiver().unwrap();
+                    args.insert(0, Arg::Expr(object));
+
+                    fct_id
+                }
+
+                CallType::Lambda(_) | CallType::Intrinsic(_) => unreachable!(),
             };
 
             let call_site = self.build_call_site_id(e.id, args, Some(callee_id));
@@ -2353,9 +3263,10 @@ where
             | BuiltinType::UInt8
             | BuiltinType::Int
             | BuiltinType::Int64
-            | BuiltinType::Char => self.asm.load_int_const(ty.mode(), dest.reg(), 0),
+            | BuiltinType::Char => self.asm.load_int_const(ty.mode(self.vm), dest.reg(), 0),
             BuiltinType::Float | BuiltinType::Double => {
-                self.asm.load_float_const(ty.mode(), dest.freg(), 0.0)
+                self.asm
+                    .load_float_const(ty.mode(self.vm), dest.freg(), 0.0)
             }
             _ => self.asm.load_nil(dest.reg()),
         }
@@ -2401,12 +3312,12 @@ where
 
         let slot_value = if element_type.is_unit() {
             None
-        } else if element_type.is_tuple() {
+        } else if element_type.layout_tuple_id(self.vm).is_some() {
             Some(value.stack_slot())
         } else {
             let slot_value = self.add_temp_node(rhs);
             self.asm.store_mem(
-                element_type.mode(),
+                element_type.mode(self.vm),
                 Mem::Local(slot_value.offset()),
                 value.any_reg(),
             );
@@ -2435,7 +3346,7 @@ where
 
         if element_type.is_unit() {
             // nothing
-        } else if let Some(tuple_id) = element_type.tuple_id() {
+        } else if let Some(tuple_id) = element_type.layout_tuple_id(self.vm) {
             let element_size = self.vm.tuples.lock().get_tuple(tuple_id).size();
             let slot_value = slot_value.unwrap();
             self.asm
@@ -2467,7 +3378,7 @@ where
         } else {
             let slot_value = slot_value.unwrap();
             let value = result_reg_ty(element_type);
-            let mode = element_type.mode();
+            let mode = element_type.mode(self.vm);
 
             self.asm
                 .load_mem(mode, value.any_reg(), Mem::Local(slot_value.offset()));
@@ -2478,7 +3389,7 @@ where
                 value.any_reg(),
             );
 
-            if self.vm.gc.needs_write_barrier() && element_type.reference_type() {
+            if self.vm.gc.needs_write_barrier() && element_type.reference_type(self.vm) {
                 let card_table_offset = self.vm.gc.card_table_offset();
                 let scratch = self.asm.get_scratch();
                 self.asm.lea(
@@ -2529,7 +3440,7 @@ where
 
         if element_type.is_unit() {
             assert!(dest.is_none());
-        } else if let Some(tuple_id) = element_type.tuple_id() {
+        } else if let Some(tuple_id) = element_type.layout_tuple_id(self.vm) {
             let element_size = self.vm.tuples.lock().get_tuple(tuple_id).size();
             self.asm
                 .array_address(REG_TMP1, REG_RESULT, REG_TMP1, element_size);
@@ -2543,9 +3454,9 @@ where
             let res = result_reg_ty(element_type).any_reg();
             let dest = dest.any_reg();
             self.asm
-                .load_array_elem(element_type.mode(), res, REG_RESULT, REG_TMP1);
+                .load_array_elem(element_type.mode(self.vm), res, REG_RESULT, REG_TMP1);
             if dest != res {
-                self.asm.copy(element_type.mode(), dest, res);
+                self.asm.copy(element_type.mode(self.vm), dest, res);
             }
         }
 
@@ -2717,7 +3628,7 @@ where
         op: Option<BinOp>,
         pos: Position,
     ) {
-        let mode = self.ty(lhs.id()).mode();
+        let mode = self.ty(lhs.id()).mode(self.vm);
 
         let (lhs_reg, rhs_reg) = if mode.is_float() {
             (FREG_RESULT.into(), FREG_TMP1.into())
@@ -2914,9 +3825,8 @@ where
         let mut temps: Vec<SlotOrOffset> = Vec::new();
         let mut alloc_cls_id: Option<ClassDefId> = None;
 
-        let fid = csite.callee;
-        let fct = self.vm.fcts.idx(fid);
-        let fct = fct.read();
+        let fct = csite.callee.map(|fid| self.vm.fcts.idx(fid));
+        let fct = fct.as_ref().map(|fct| fct.read());
 
         for (idx, arg) in csite.args.iter().enumerate() {
             let slot_or_offset = match *arg {
@@ -2930,23 +3840,25 @@ where
                     // no check necessary for:
                     //   super calls (guaranteed to not be nil) and
                     //   dynamic dispatch (implicit check when loading fctptr from vtable)
-                    if idx == 0
-                        && fct.has_self()
-                        && check_for_nil(ty)
-                        && !csite.super_call
-                        && !fct.is_virtual()
-                    {
+                    //   lambdas (dispatched through vtable of closure object)
+                    //   trait objects (checked before dispatching)
+                    let needs_nil_check = match fct {
+                        Some(ref fct) => fct.has_self() && !fct.is_virtual() && !fct.in_trait(),
+                        None => false,
+                    };
+
+                    if idx == 0 && needs_nil_check && check_for_nil(ty) && !csite.super_call {
                         self.asm.test_if_nil_bailout(pos, dest.reg(), Trap::NIL);
                     }
 
-                    if ty.is_tuple() {
+                    if ty.layout_tuple_id(self.vm).is_some() {
                         SlotOrOffset::Slot(dest.stack_slot())
                     } else if ty.is_unit() {
                         SlotOrOffset::Uninitialized
                     } else {
                         let slot = self.add_temp_arg(arg);
                         self.asm.store_mem(
-                            arg.ty().mode(),
+                            arg.ty().mode(self.vm),
                             Mem::Local(slot.offset()),
                             dest.any_reg(),
                         );
@@ -2959,14 +3871,14 @@ where
                     let dest = self.alloc_expr_store(ty);
                     self.emit_expr(ast, dest);
 
-                    if ty.is_tuple() {
+                    if ty.layout_tuple_id(self.vm).is_some() {
                         SlotOrOffset::Slot(dest.stack_slot())
                     } else if ty.is_unit() {
                         SlotOrOffset::Uninitialized
                     } else {
                         let slot = self.add_temp_arg(arg);
                         self.asm.store_mem(
-                            arg.ty().mode(),
+                            arg.ty().mode(self.vm),
                             Mem::Local(slot.offset()),
                             dest.any_reg(),
                         );
@@ -3042,7 +3954,7 @@ where
 
         let return_type = self.specialize_type(csite.return_type);
 
-        if return_type.is_tuple() {
+        if return_type.layout_tuple_id(self.vm).is_some() {
             self.asm
                 .lea(REG_PARAMS[reg_idx], Mem::Local(dest.stack_offset()));
             reg_idx += 1;
@@ -3068,7 +3980,7 @@ where
                     variadic_slot.unwrap(),
                     temps[idx].clone(),
                 );
-            } else if ty.is_tuple() {
+            } else if ty.layout_tuple_id(self.vm).is_some() {
                 if reg_idx < REG_PARAMS.len() {
                     let reg = REG_PARAMS[reg_idx];
                     self.asm.lea(reg, Mem::Local(offset));
@@ -3085,7 +3997,7 @@ where
                     sp_offset += 8;
                 }
             } else if ty.is_float() {
-                let mode = ty.mode();
+                let mode = ty.mode(self.vm);
 
                 if freg_idx < FREG_PARAMS.len() {
                     let freg = FREG_PARAMS[freg_idx];
@@ -3101,7 +4013,7 @@ where
                     sp_offset += 8;
                 }
             } else {
-                let mode = ty.mode();
+                let mode = ty.mode(self.vm);
 
                 if reg_idx < REG_PARAMS.len() {
                     let reg = REG_PARAMS[reg_idx];
@@ -3158,13 +4070,35 @@ where
             .iter()
             .all(|ty| !ty.contains_type_param(self.vm)));
 
-        let (result, result_type): (AnyReg, BuiltinType) = if return_type.is_tuple() {
-            (REG_RESULT.into(), BuiltinType::Unit)
-        } else if return_type.is_unit() {
-            (REG_RESULT.into(), BuiltinType::Unit)
+        // tuples are returned through a pointer passed as first argument
+        let self_index = if return_type.layout_tuple_id(self.vm).is_some() {
+            1
         } else {
-            (dest.any_reg(), return_type)
+            0
+        };
+
+        let (result, result_type): (AnyReg, BuiltinType) =
+            if return_type.layout_tuple_id(self.vm).is_some() {
+                (REG_RESULT.into(), BuiltinType::Unit)
+            } else if return_type.is_unit() {
+                (REG_RESULT.into(), BuiltinType::Unit)
+            } else {
+                (dest.any_reg(), return_type)
+            };
+
+        let fct = match fct {
+            Some(fct) => fct,
+            None => {
+                self.asm.emit_comment("call lambda".into());
+                let gcpoint = self.create_gcpoint();
+                self.asm
+                    .indirect_call(0, pos, gcpoint, result_type, self_index, result);
+
+                self.finish_call_site(csite, temps, variadic_slot, argsize, dest);
+                return;
+            }
         };
+        let fid = fct.id;
 
         if csite.super_call {
             let ptr = self.ptr_for_fct_id(fid, cls_type_params.clone(), fct_type_params.clone());
@@ -3186,13 +4120,19 @@ where
             let name = fct.full_name(self.vm);
             self.asm.emit_comment(format!("call virtual {}", name));
             let gcpoint = self.create_gcpoint();
-            let cls_type_params = csite.args[0].ty().type_params(self.vm);
-            self.asm.indirect_call(
-                vtable_index,
+            self.asm
+                .indirect_call(vtable_index, pos, gcpoint, result_type, self_index, result);
+        } else if fct.in_trait() {
+            let name = fct.full_name(self.vm);
+            self.asm.emit_comment(format!("call trait object {}", name));
+            let gcpoint = self.create_gcpoint();
+            self.asm.trait_call(
+                fid,
+                fct_type_params,
                 pos,
                 gcpoint,
                 result_type,
-                cls_type_params,
+                self_index,
                 result,
             );
         } else {
@@ -3212,11 +4152,22 @@ where
             );
         }
 
+        self.finish_call_site(csite, temps, variadic_slot, argsize, dest);
+    }
+
+    fn finish_call_site(
+        &mut self,
+        csite: &CallSite<'ast>,
+        temps: Vec<SlotOrOffset>,
+        variadic_slot: Option<ManagedStackSlot>,
+        argsize: i32,
+        dest: ExprStore,
+    ) {
         if csite.args.len() > 0 {
             if let InternalArg::SelfieNew(ty) = csite.args[0] {
                 let temp = &temps[0];
                 self.asm
-                    .load_mem(ty.mode(), dest.any_reg(), Mem::Local(temp.offset()));
+                    .load_mem(ty.mode(self.vm), dest.any_reg(), Mem::Local(temp.offset()));
             }
         }
 
@@ -3230,7 +4181,7 @@ where
             self.managed_stack.free_temp(variadic_slot, self.vm);
         }
 
-        if csite.return_type.is_tuple() {
+        if csite.return_type.layout_tuple_id(self.vm).is_some() {
             self.managed_stack.mark_initialized(dest.stack_slot().var);
         }
 
@@ -3254,7 +4205,7 @@ where
         let header_size = Header::size() + mem::ptr_width();
         let array_offset = header_size + array_idx as i32 * ty.size(self.vm);
 
-        let needs_write_barrier = if let Some(tuple_id) = ty.tuple_id() {
+        let needs_write_barrier = if let Some(tuple_id) = ty.layout_tuple_id(self.vm) {
             self.copy_tuple(
                 tuple_id,
                 RegOrOffset::RegWithOffset(REG_TMP1, array_offset),
@@ -3269,19 +4220,19 @@ where
         } else if ty.is_unit() {
             false
         } else if ty.is_float() {
-            let mode = ty.mode();
+            let mode = ty.mode(self.vm);
             self.asm
                 .load_mem(mode, FREG_TMP1.into(), Mem::Local(arg.offset()));
             self.asm
                 .store_mem(mode, Mem::Base(REG_TMP1, array_offset), FREG_TMP1.into());
             false
         } else {
-            let mode = ty.mode();
+            let mode = ty.mode(self.vm);
             self.asm
                 .load_mem(mode, REG_TMP2.into(), Mem::Local(arg.offset()));
             self.asm
                 .store_mem(mode, Mem::Base(REG_TMP1, array_offset), REG_TMP2.into());
-            ty.reference_type()
+            ty.reference_type(self.vm)
         };
 
         if self.vm.gc.needs_write_barrier() && needs_write_barrier {
@@ -3457,6 +4408,17 @@ where
         temp_slot
     }
 
+    fn specialize_enum_ty(&self, ty: BuiltinType) -> EnumDefId {
+        match ty {
+            BuiltinType::Enum(enum_id, list_id) => {
+                let params = self.vm.lists.lock().get(list_id);
+                specialize_enum_id_params(self.vm, enum_id, params)
+            }
+
+            _ => unreachable!(),
+        }
+    }
+
     fn specialize_type(&self, ty: BuiltinType) -> BuiltinType {
         replace_type_param(
             self.vm,
@@ -3523,7 +4485,7 @@ where
         let (cls_type_params, fct_type_params) = self.determine_call_type_params(&*call_type);
 
         CallSite {
-            callee: callee_id,
+            callee: Some(callee_id),
             args,
             variadic_array,
             cls_type_params,
@@ -3533,6 +4495,44 @@ where
         }
     }
 
+    // the closure object is passed as first argument to the lambda
+    fn build_call_site_lambda(
+        &mut self,
+        lambda_ty: BuiltinType,
+        args: Vec<&'ast Expr>,
+    ) -> CallSite<'ast> {
+        let lambda_ty = self.specialize_type(lambda_ty);
+        let lambda = self
+            .vm
+            .lambda_types
+            .lock()
+            .get(lambda_ty.lambda_id().unwrap());
+
+        let args = args
+            .into_iter()
+            .enumerate()
+            .map(|(ind, arg)| {
+                let ty = if ind == 0 {
+                    lambda_ty
+                } else {
+                    lambda.params()[ind - 1]
+                };
+
+                InternalArg::Expr(arg, ty)
+            })
+            .collect::<Vec<_>>();
+
+        CallSite {
+            callee: None,
+            args,
+            variadic_array: None,
+            cls_type_params: TypeList::empty(),
+            fct_type_params: TypeList::empty(),
+            super_call: false,
+            return_type: lambda.ret(),
+        }
+    }
+
     fn build_call_site_id(
         &mut self,
         id: NodeId,
@@ -3654,14 +4654,17 @@ where
                 fct_type_params = TypeList::empty();
             }
 
-            CallType::Trait(_, _) => unimplemented!(),
+            CallType::Trait(_, _, ref type_params) => {
+                cls_type_params = TypeList::empty();
+                fct_type_params = type_params.clone();
+            }
 
             CallType::TraitStatic(_, _, _) => {
                 cls_type_params = TypeList::empty();
                 fct_type_params = TypeList::empty();
             }
 
-            CallType::Intrinsic(_) => unreachable!(),
+            CallType::Lambda(_) | CallType::Intrinsic(_) => unreachable!(),
         }
 
         (cls_type_params, fct_type_params)
@@ -3678,7 +4681,7 @@ where
     }
 
     fn alloc_expr_store(&mut self, ty: BuiltinType) -> ExprStore {
-        if ty.is_tuple() {
+        if ty.layout_tuple_id(self.vm).is_some() {
             let slot = self.managed_stack.add_temp_uninitialized(ty, self.vm);
             ExprStore::Stack(slot)
         } else if ty.is_unit() {
@@ -3710,6 +4713,8 @@ impl<'a, 'ast> visit::Visitor<'ast> for AstCodeGen<'a, 'ast> {
             StmtBreak(ref stmt) => self.emit_stmt_break(stmt),
             StmtContinue(ref stmt) => self.emit_stmt_continue(stmt),
             StmtVar(ref stmt) => self.emit_stmt_var(stmt),
+            StmtThrow(ref stmt) => self.emit_stmt_throw(stmt),
+            StmtTry(ref stmt) => self.emit_stmt_try(stmt),
         }
     }
 
@@ -3718,6 +4723,16 @@ impl<'a, 'ast> visit::Visitor<'ast> for AstCodeGen<'a, 'ast> {
     }
 }
 
+struct ActiveTry<'ast> {
+    finally_block: Option<&'ast Stmt>,
+
+    // code ranges already closed, e.g. by return/break/continue
+    ranges: Vec<(usize, usize)>,
+
+    // start of currently open code range
+    start: usize,
+}
+
 #[derive(Clone)]
 enum ArrayLength {
     Fixed(i32),
@@ -3747,12 +4762,29 @@ impl ArrayLength {
     }
 }
 
+#[derive(Copy, Clone)]
 enum RegOrOffset {
     Reg(Reg),
     RegWithOffset(Reg, i32),
     Offset(i32),
 }
 
+impl RegOrOffset {
+    fn mem(self) -> Mem {
+        match self {
+            RegOrOffset::Reg(reg) => Mem::Base(reg, 0),
+            RegOrOffset::RegWithOffset(reg, offset) => Mem::Base(reg, offset),
+            RegOrOffset::Offset(offset) => Mem::Local(offset),
+        }
+    }
+}
+
+enum StructPlace<'ast> {
+    Var(VarId),
+    Global(GlobalId),
+    Field(&'ast Expr, BuiltinType, FieldId),
+}
+
 fn result_reg_mode(mode: MachineMode) -> ExprStore {
     if mode.is_float() {
         ExprStore::FloatReg(FREG_RESULT)
@@ -3787,12 +4819,14 @@ fn check_for_nil(ty: BuiltinType) -> bool {
         | BuiltinType::Enum(_, _) => false,
         BuiltinType::Nil | BuiltinType::Ptr => true,
         BuiltinType::Class(_, _) => true,
+        BuiltinType::Nullable(_, _) => true,
         BuiltinType::Struct(_, _) => false,
-        BuiltinType::Trait(_) => false,
+        BuiltinType::Trait(_) => true,
         BuiltinType::Module(_) => false,
         BuiltinType::This => unreachable!(),
         BuiltinType::ClassTypeParam(_, _) => unreachable!(),
         BuiltinType::FctTypeParam(_, _) => unreachable!(),
+        BuiltinType::TraitTypeParam(_, _) => unreachable!(),
         BuiltinType::Lambda(_) => true,
         BuiltinType::Tuple(_) => false,
     }
@@ -1,7 +1,7 @@
 use std::mem;
 use std::ptr;
 
-use crate::bytecode::{self, BytecodeFunction, ConstPoolEntry, ConstPoolOpcode};
+use crate::bytecode::{self, BytecodeFunction, BytecodeType, ConstPoolEntry, ConstPoolOpcode};
 use crate::compiler::codegen::should_emit_bytecode;
 use crate::compiler::fct::{Code, JitDescriptor};
 use crate::gc::Address;
@@ -95,12 +95,28 @@ fn allocate_registers_array(vm: &VM, fct: &BytecodeFunction) -> Ref<IntArray> {
     let mut array = int_array_alloc_heap(vm, fct.registers().len());
 
     for (idx, &ty) in fct.registers().iter().enumerate() {
-        array.set_at(idx, ty as u32 as i32);
+        array.set_at(idx, encode_bytecode_type(ty));
     }
 
     array
 }
 
+// keep in sync with BC_TYPE_* in dora-boots/bytecode_opcode.dora
+fn encode_bytecode_type(ty: BytecodeType) -> i32 {
+    match ty {
+        BytecodeType::Bool => 0,
+        BytecodeType::UInt8 => 1,
+        BytecodeType::Char => 2,
+        BytecodeType::Int => 3,
+        BytecodeType::Int32 => 4,
+        BytecodeType::Int64 => 5,
+        BytecodeType::Float => 6,
+        BytecodeType::Double => 7,
+        BytecodeType::Ptr => 8,
+        BytecodeType::Tuple(_) => 9,
+    }
+}
+
 fn allocate_constpool_array(vm: &VM, fct: &BytecodeFunction) -> Ref<ByteArray> {
     use byteorder::{LittleEndian, WriteBytesExt};
     let mut buffer = Vec::new();
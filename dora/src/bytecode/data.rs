@@ -1,8 +1,8 @@
 use std::fmt;
 
-use crate::cpu::STACK_FRAME_ALIGNMENT;
-use crate::mem::{align_i32, ptr_width};
+use crate::compiler::fct::CatchType;
 use crate::ty::{BuiltinType, MachineMode};
+use crate::vm::{TupleId, VM};
 use dora_parser::lexer::position::Position;
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -29,20 +29,20 @@ pub enum BytecodeType {
     Float,
     Double,
     Ptr,
+    Tuple(TupleId),
 }
 
 impl BytecodeType {
-    pub fn size(&self) -> i32 {
-        match self {
-            BytecodeType::Bool => 1,
-            BytecodeType::UInt8 => 1,
-            BytecodeType::Char => 4,
-            BytecodeType::Int => 4,
-            BytecodeType::Int32 => 4,
-            BytecodeType::Int64 => 8,
-            BytecodeType::Float => 4,
-            BytecodeType::Double => 8,
-            BytecodeType::Ptr => ptr_width(),
+    // enums are either stored as Int or as a pointer to the variant object,
+    // depending on their layout, structs share the layout of the tuple of
+    // their field types
+    pub fn from_ty(vm: &VM, ty: BuiltinType) -> BytecodeType {
+        if ty.reference_type(vm) {
+            BytecodeType::Ptr
+        } else if let Some(tuple_id) = ty.layout_tuple_id(vm) {
+            BytecodeType::Tuple(tuple_id)
+        } else {
+            ty.into()
         }
     }
 
@@ -57,6 +57,7 @@ impl BytecodeType {
             BytecodeType::Float => MachineMode::Float32,
             BytecodeType::Double => MachineMode::Float64,
             BytecodeType::Ptr => MachineMode::Ptr,
+            BytecodeType::Tuple(_) => unreachable!(),
         }
     }
 
@@ -66,6 +67,13 @@ impl BytecodeType {
             _ => false,
         }
     }
+
+    pub fn tuple_id(&self) -> Option<TupleId> {
+        match self {
+            BytecodeType::Tuple(tuple_id) => Some(*tuple_id),
+            _ => None,
+        }
+    }
 }
 
 impl From<BuiltinType> for BytecodeType {
@@ -80,6 +88,10 @@ impl From<BuiltinType> for BytecodeType {
             BuiltinType::Float => BytecodeType::Float,
             BuiltinType::Double => BytecodeType::Double,
             BuiltinType::Class(_, _) => BytecodeType::Ptr,
+            BuiltinType::Nullable(_, _) => BytecodeType::Ptr,
+            BuiltinType::Module(_) => BytecodeType::Ptr,
+            BuiltinType::Lambda(_) => BytecodeType::Ptr,
+            BuiltinType::Trait(_) => BytecodeType::Ptr,
             BuiltinType::Enum(_, _) => BytecodeType::Int,
             _ => panic!("BuiltinType {:?} cannot converted to BytecodeType", ty),
         }
@@ -98,6 +110,7 @@ impl From<BytecodeType> for BuiltinType {
             BytecodeType::Float => BuiltinType::Float,
             BytecodeType::Double => BuiltinType::Double,
             BytecodeType::Ptr => BuiltinType::Ptr,
+            BytecodeType::Tuple(tuple_id) => BuiltinType::Tuple(tuple_id),
         }
     }
 }
@@ -215,6 +228,7 @@ pub enum BytecodeOpcode {
     LoadFieldFloat,
     LoadFieldDouble,
     LoadFieldPtr,
+    LoadFieldTuple,
 
     StoreFieldBool,
     StoreFieldUInt8,
@@ -224,6 +238,7 @@ pub enum BytecodeOpcode {
     StoreFieldFloat,
     StoreFieldDouble,
     StoreFieldPtr,
+    StoreFieldTuple,
 
     LoadGlobalBool,
     LoadGlobalUInt8,
@@ -233,6 +248,7 @@ pub enum BytecodeOpcode {
     LoadGlobalFloat,
     LoadGlobalDouble,
     LoadGlobalPtr,
+    LoadGlobalTuple,
 
     StoreGlobalBool,
     StoreGlobalUInt8,
@@ -242,6 +258,7 @@ pub enum BytecodeOpcode {
     StoreGlobalFloat,
     StoreGlobalDouble,
     StoreGlobalPtr,
+    StoreGlobalTuple,
 
     PushRegister,
 
@@ -315,6 +332,9 @@ pub enum BytecodeOpcode {
 
     Assert,
 
+    Throw,
+    LoadException,
+
     // Backward jump
     JumpLoop,
 
@@ -335,6 +355,7 @@ pub enum BytecodeOpcode {
     InvokeDirectFloat,
     InvokeDirectDouble,
     InvokeDirectPtr,
+    InvokeDirectTuple,
 
     InvokeVirtualVoid,
     InvokeVirtualBool,
@@ -345,6 +366,7 @@ pub enum BytecodeOpcode {
     InvokeVirtualFloat,
     InvokeVirtualDouble,
     InvokeVirtualPtr,
+    InvokeVirtualTuple,
 
     InvokeStaticVoid,
     InvokeStaticBool,
@@ -355,6 +377,7 @@ pub enum BytecodeOpcode {
     InvokeStaticFloat,
     InvokeStaticDouble,
     InvokeStaticPtr,
+    InvokeStaticTuple,
 
     NewObject,
     NewArray,
@@ -391,6 +414,29 @@ pub enum BytecodeOpcode {
     RetFloat,
     RetDouble,
     RetPtr,
+    RetTuple,
+
+    InvokeLambdaVoid,
+    InvokeLambdaBool,
+    InvokeLambdaUInt8,
+    InvokeLambdaChar,
+    InvokeLambdaInt,
+    InvokeLambdaInt64,
+    InvokeLambdaFloat,
+    InvokeLambdaDouble,
+    InvokeLambdaPtr,
+    InvokeLambdaTuple,
+
+    InvokeTraitVoid,
+    InvokeTraitBool,
+    InvokeTraitUInt8,
+    InvokeTraitChar,
+    InvokeTraitInt,
+    InvokeTraitInt64,
+    InvokeTraitFloat,
+    InvokeTraitDouble,
+    InvokeTraitPtr,
+    InvokeTraitTuple,
 }
 
 impl BytecodeOpcode {
@@ -410,6 +456,7 @@ impl BytecodeOpcode {
             | BytecodeOpcode::LoadFieldFloat
             | BytecodeOpcode::LoadFieldDouble
             | BytecodeOpcode::LoadFieldPtr
+            | BytecodeOpcode::LoadFieldTuple
             | BytecodeOpcode::StoreFieldBool
             | BytecodeOpcode::StoreFieldUInt8
             | BytecodeOpcode::StoreFieldChar
@@ -418,6 +465,7 @@ impl BytecodeOpcode {
             | BytecodeOpcode::StoreFieldFloat
             | BytecodeOpcode::StoreFieldDouble
             | BytecodeOpcode::StoreFieldPtr
+            | BytecodeOpcode::StoreFieldTuple
             | BytecodeOpcode::InvokeDirectVoid
             | BytecodeOpcode::InvokeDirectBool
             | BytecodeOpcode::InvokeDirectUInt8
@@ -427,6 +475,7 @@ impl BytecodeOpcode {
             | BytecodeOpcode::InvokeDirectFloat
             | BytecodeOpcode::InvokeDirectDouble
             | BytecodeOpcode::InvokeDirectPtr
+            | BytecodeOpcode::InvokeDirectTuple
             | BytecodeOpcode::InvokeVirtualVoid
             | BytecodeOpcode::InvokeVirtualBool
             | BytecodeOpcode::InvokeVirtualUInt8
@@ -436,6 +485,27 @@ impl BytecodeOpcode {
             | BytecodeOpcode::InvokeVirtualFloat
             | BytecodeOpcode::InvokeVirtualDouble
             | BytecodeOpcode::InvokeVirtualPtr
+            | BytecodeOpcode::InvokeVirtualTuple
+            | BytecodeOpcode::InvokeLambdaVoid
+            | BytecodeOpcode::InvokeLambdaBool
+            | BytecodeOpcode::InvokeLambdaUInt8
+            | BytecodeOpcode::InvokeLambdaChar
+            | BytecodeOpcode::InvokeLambdaInt
+            | BytecodeOpcode::InvokeLambdaInt64
+            | BytecodeOpcode::InvokeLambdaFloat
+            | BytecodeOpcode::InvokeLambdaDouble
+            | BytecodeOpcode::InvokeLambdaPtr
+            | BytecodeOpcode::InvokeLambdaTuple
+            | BytecodeOpcode::InvokeTraitVoid
+            | BytecodeOpcode::InvokeTraitBool
+            | BytecodeOpcode::InvokeTraitUInt8
+            | BytecodeOpcode::InvokeTraitChar
+            | BytecodeOpcode::InvokeTraitInt
+            | BytecodeOpcode::InvokeTraitInt64
+            | BytecodeOpcode::InvokeTraitFloat
+            | BytecodeOpcode::InvokeTraitDouble
+            | BytecodeOpcode::InvokeTraitPtr
+            | BytecodeOpcode::InvokeTraitTuple
             | BytecodeOpcode::InvokeStaticVoid
             | BytecodeOpcode::InvokeStaticBool
             | BytecodeOpcode::InvokeStaticUInt8
@@ -445,6 +515,7 @@ impl BytecodeOpcode {
             | BytecodeOpcode::InvokeStaticFloat
             | BytecodeOpcode::InvokeStaticDouble
             | BytecodeOpcode::InvokeStaticPtr
+            | BytecodeOpcode::InvokeStaticTuple
             | BytecodeOpcode::NewObject
             | BytecodeOpcode::NewArray
             | BytecodeOpcode::NilCheck
@@ -466,7 +537,9 @@ impl BytecodeOpcode {
             | BytecodeOpcode::StoreArrayFloat
             | BytecodeOpcode::StoreArrayDouble
             | BytecodeOpcode::StoreArrayPtr
-            | BytecodeOpcode::Assert => true,
+            | BytecodeOpcode::Assert
+            | BytecodeOpcode::Throw
+            | BytecodeOpcode::LoadException => true,
             _ => false,
         }
     }
@@ -507,10 +580,9 @@ pub struct BytecodeFunction {
     code: Vec<u8>,
     registers: Vec<BytecodeType>,
     const_pool: Vec<ConstPoolEntry>,
-    offset: Vec<i32>,
-    stacksize: i32,
     arguments: u32,
     positions: Vec<(u32, Position)>,
+    exception_handlers: Vec<ExceptionHandler>,
 }
 
 impl BytecodeFunction {
@@ -520,16 +592,15 @@ impl BytecodeFunction {
         registers: Vec<BytecodeType>,
         arguments: u32,
         positions: Vec<(u32, Position)>,
+        exception_handlers: Vec<ExceptionHandler>,
     ) -> BytecodeFunction {
-        let (offset, stacksize) = determine_offsets(&registers);
         BytecodeFunction {
             code,
             const_pool,
             registers,
-            offset,
-            stacksize,
             arguments,
             positions,
+            exception_handlers,
         }
     }
     pub fn code(&self) -> &[u8] {
@@ -544,16 +615,12 @@ impl BytecodeFunction {
         &self.positions
     }
 
-    pub fn register_type(&self, register: Register) -> BytecodeType {
-        *self.registers.get(register.0).expect("register not found")
-    }
-
-    pub fn register_offset(&self, register: Register) -> i32 {
-        *self.offset.get(register.0).expect("offset not found")
+    pub fn exception_handlers(&self) -> &[ExceptionHandler] {
+        &self.exception_handlers
     }
 
-    pub fn stacksize(&self) -> i32 {
-        self.stacksize
+    pub fn register_type(&self, register: Register) -> BytecodeType {
+        *self.registers.get(register.0).expect("register not found")
     }
 
     pub fn arguments(&self) -> u32 {
@@ -578,17 +645,16 @@ impl BytecodeFunction {
     }
 }
 
-fn determine_offsets(registers: &Vec<BytecodeType>) -> (Vec<i32>, i32) {
-    let mut offset: Vec<i32> = vec![0; registers.len()];
-    let mut stacksize: i32 = 0;
-    for (index, ty) in registers.iter().enumerate() {
-        stacksize = align_i32(stacksize + ty.size(), ty.size());
-        offset[index] = -stacksize;
-    }
-
-    stacksize = align_i32(stacksize, STACK_FRAME_ALIGNMENT as i32);
-
-    (offset, stacksize)
+// Bytecode counterpart of the handlers in compiler::fct, ordered from
+// innermost to outermost. The range covers all instructions in
+// `try_start..try_end`, `catch` is the offset of the LoadException
+// instruction that starts the handler.
+#[derive(Copy, Clone)]
+pub struct ExceptionHandler {
+    pub try_start: BytecodeOffset,
+    pub try_end: BytecodeOffset,
+    pub catch: BytecodeOffset,
+    pub catch_type: CatchType,
 }
 
 #[derive(FromPrimitive, ToPrimitive)]
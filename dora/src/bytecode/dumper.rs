@@ -3,7 +3,7 @@ use std::io;
 use crate::bytecode::{
     read, BytecodeFunction, BytecodeOffset, BytecodeVisitor, ConstPoolIdx, Register,
 };
-use crate::vm::{ClassDefId, FctDefId, FieldId, GlobalId};
+use crate::vm::{ClassDefId, FctDefId, FieldId, GlobalId, TupleId};
 
 pub fn dump(bc: &BytecodeFunction) {
     let mut stdout = io::stdout();
@@ -20,6 +20,16 @@ pub fn dump(bc: &BytecodeFunction) {
     }
 
     println!("");
+
+    for handler in bc.exception_handlers() {
+        println!(
+            "Handler: #{}-#{} => #{} {:?}",
+            handler.try_start.to_u32(),
+            handler.try_end.to_u32(),
+            handler.catch.to_u32(),
+            handler.catch_type
+        );
+    }
 }
 
 struct BytecodeDumper<'a> {
@@ -49,6 +59,31 @@ impl<'a> BytecodeDumper<'a> {
         writeln!(self.w, " {}, {}, {}", r1, r2, cls_id.to_usize()).expect("write! failed");
     }
 
+    fn emit_reg2_tuple(&mut self, name: &str, r1: Register, r2: Register, tuple_id: TupleId) {
+        self.emit_start(name);
+        writeln!(self.w, " {}, {}, {}", r1, r2, tuple_id.to_usize()).expect("write! failed");
+    }
+
+    fn emit_reg2_tuple_element(
+        &mut self,
+        name: &str,
+        r1: Register,
+        r2: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        self.emit_start(name);
+        writeln!(
+            self.w,
+            " {}, {}, {}:{}",
+            r1,
+            r2,
+            tuple_id.to_usize(),
+            element
+        )
+        .expect("write! failed");
+    }
+
     fn emit_reg1(&mut self, name: &str, r1: Register) {
         self.emit_start(name);
         writeln!(self.w, " {}", r1).expect("write! failed");
@@ -115,6 +150,16 @@ impl<'a> BytecodeDumper<'a> {
         writeln!(self.w, " {}, {}, {}", r1, fid.to_usize(), cnt).expect("write! failed");
     }
 
+    fn emit_lambda_void(&mut self, name: &str, cnt: u32) {
+        self.emit_start(name);
+        writeln!(self.w, " {}", cnt).expect("write! failed");
+    }
+
+    fn emit_lambda(&mut self, name: &str, r1: Register, cnt: u32) {
+        self.emit_start(name);
+        writeln!(self.w, " {}, {}", r1, cnt).expect("write! failed");
+    }
+
     fn emit_new(&mut self, name: &str, r1: Register, cls: ClassDefId) {
         self.emit_start(name);
         writeln!(self.w, " {}, {}", r1, cls.to_usize()).expect("write! failed");
@@ -376,6 +421,28 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_mov_ptr(&mut self, dest: Register, src: Register) {
         self.emit_reg2("MovPtr", dest, src);
     }
+    fn visit_mov_tuple(&mut self, dest: Register, src: Register, tuple_id: TupleId) {
+        self.emit_reg2_tuple("MovTuple", dest, src, tuple_id);
+    }
+
+    fn visit_load_tuple_element(
+        &mut self,
+        dest: Register,
+        src: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        self.emit_reg2_tuple_element("LoadTupleElement", dest, src, tuple_id, element);
+    }
+    fn visit_store_tuple_element(
+        &mut self,
+        src: Register,
+        dest: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        self.emit_reg2_tuple_element("StoreTupleElement", src, dest, tuple_id, element);
+    }
 
     fn visit_load_field_bool(
         &mut self,
@@ -449,6 +516,15 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     ) {
         self.emit_field("LoadFieldPtr", dest, obj, cls, field);
     }
+    fn visit_load_field_tuple(
+        &mut self,
+        dest: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit_field("LoadFieldTuple", dest, obj, cls, field);
+    }
 
     fn visit_store_field_bool(
         &mut self,
@@ -522,6 +598,15 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     ) {
         self.emit_field("StoreFieldPtr", src, obj, cls, field);
     }
+    fn visit_store_field_tuple(
+        &mut self,
+        src: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit_field("StoreFieldTuple", src, obj, cls, field);
+    }
 
     fn visit_load_global_bool(&mut self, dest: Register, glob: GlobalId) {
         self.emit_global("LoadGlobalBool", dest, glob);
@@ -547,6 +632,9 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_load_global_ptr(&mut self, dest: Register, glob: GlobalId) {
         self.emit_global("LoadGlobalPtr", dest, glob);
     }
+    fn visit_load_global_tuple(&mut self, dest: Register, glob: GlobalId) {
+        self.emit_global("LoadGlobalTuple", dest, glob);
+    }
 
     fn visit_store_global_bool(&mut self, src: Register, glob: GlobalId) {
         self.emit_global("StoreGlobalBool", src, glob);
@@ -572,6 +660,9 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_store_global_ptr(&mut self, src: Register, glob: GlobalId) {
         self.emit_global("StoreGlobalPtr", src, glob);
     }
+    fn visit_store_global_tuple(&mut self, src: Register, glob: GlobalId) {
+        self.emit_global("StoreGlobalTuple", src, glob);
+    }
 
     fn visit_push_register(&mut self, src: Register) {
         self.emit_reg1("PushRegister", src)
@@ -764,6 +855,14 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
         self.emit_reg1("Assert", value);
     }
 
+    fn visit_throw(&mut self, exception: Register) {
+        self.emit_reg1("Throw", exception);
+    }
+
+    fn visit_load_exception(&mut self, dest: Register) {
+        self.emit_reg1("LoadException", dest);
+    }
+
     fn visit_jump_if_false(&mut self, opnd: Register, offset: u32) {
         self.emit_reg1_u32("JumpIfFalse", opnd, offset);
     }
@@ -813,6 +912,9 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_invoke_direct_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit_fct("InvokeDirectPtr", dest, fctdef, count);
     }
+    fn visit_invoke_direct_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeDirectTuple", dest, fctdef, count);
+    }
 
     fn visit_invoke_virtual_void(&mut self, fctdef: FctDefId, count: u32) {
         self.emit_fct_void("InvokeVirtualVoid", fctdef, count);
@@ -841,6 +943,9 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_invoke_virtual_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit_fct("InvokeVirtualPtr", dest, fctdef, count);
     }
+    fn visit_invoke_virtual_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeVirtualTuple", dest, fctdef, count);
+    }
 
     fn visit_invoke_static_void(&mut self, fctdef: FctDefId, count: u32) {
         self.emit_fct_void("InvokeStaticVoid", fctdef, count);
@@ -869,6 +974,71 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_invoke_static_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit_fct("InvokeStaticPtr", dest, fctdef, count);
     }
+    fn visit_invoke_static_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeStaticTuple", dest, fctdef, count);
+    }
+
+    fn visit_invoke_lambda_void(&mut self, count: u32) {
+        self.emit_lambda_void("InvokeLambdaVoid", count);
+    }
+    fn visit_invoke_lambda_bool(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaBool", dest, count);
+    }
+    fn visit_invoke_lambda_uint8(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaUInt8", dest, count);
+    }
+    fn visit_invoke_lambda_char(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaChar", dest, count);
+    }
+    fn visit_invoke_lambda_int(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaInt", dest, count);
+    }
+    fn visit_invoke_lambda_int64(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaInt64", dest, count);
+    }
+    fn visit_invoke_lambda_float(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaFloat", dest, count);
+    }
+    fn visit_invoke_lambda_double(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaDouble", dest, count);
+    }
+    fn visit_invoke_lambda_ptr(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaPtr", dest, count);
+    }
+    fn visit_invoke_lambda_tuple(&mut self, dest: Register, count: u32) {
+        self.emit_lambda("InvokeLambdaTuple", dest, count);
+    }
+
+    fn visit_invoke_trait_void(&mut self, fctdef: FctDefId, count: u32) {
+        self.emit_fct_void("InvokeTraitVoid", fctdef, count);
+    }
+    fn visit_invoke_trait_bool(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitBool", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_uint8(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitUInt8", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_char(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitChar", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_int(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitInt", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_int64(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitInt64", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_float(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitFloat", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_double(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitDouble", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitPtr", dest, fctdef, count);
+    }
+    fn visit_invoke_trait_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_fct("InvokeTraitTuple", dest, fctdef, count);
+    }
 
     fn visit_new_object(&mut self, dest: Register, cls: ClassDefId) {
         self.emit_new("NewObject", dest, cls);
@@ -965,4 +1135,7 @@ impl<'a> BytecodeVisitor for BytecodeDumper<'a> {
     fn visit_ret_ptr(&mut self, opnd: Register) {
         self.emit_reg1("RetPtr", opnd);
     }
+    fn visit_ret_tuple(&mut self, opnd: Register) {
+        self.emit_reg1("RetTuple", opnd);
+    }
 }
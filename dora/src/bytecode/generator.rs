@@ -5,27 +5,52 @@ use dora_parser::ast::Expr::*;
 use dora_parser::ast::Stmt::*;
 use dora_parser::ast::*;
 
-use crate::bytecode::{BytecodeFunction, BytecodeType, BytecodeWriter, Label, Register};
-use crate::semck::specialize::{specialize_class_ty, specialize_type};
-use crate::semck::{expr_always_returns, expr_block_always_returns};
+use crate::bytecode::{
+    BytecodeFunction, BytecodeOffset, BytecodeType, BytecodeWriter, Label, Register,
+};
+use crate::compiler::fct::CatchType;
+use crate::semck::specialize::{
+    specialize_class_ty, specialize_enum_id_params, specialize_for_call_type, specialize_lambda,
+    specialize_module, specialize_type,
+};
+use crate::semck::{always_returns, expr_always_returns, expr_block_always_returns};
 use crate::size::InstanceSize;
 use crate::ty::{BuiltinType, TypeList, TypeParamId};
 use crate::vm::{
-    CallType, ConstId, Fct, FctDef, FctDefId, FctId, FctKind, FctSrc, GlobalId, IdentType,
-    Intrinsic, TraitId, VarId, VM,
+    find_field_in_struct, CallType, ClassDefId, ConstId, EnumDefId, EnumLayout, Fct, FctDef,
+    FctDefId, FctId, FctKind, FctParent, FctSrc, FieldId, GlobalId, IdentType, Intrinsic,
+    StructFieldId, TraitId, TupleId, VarId, VM,
 };
 
 pub struct LoopLabels {
     cond: Label,
     end: Label,
+
+    // number of try statements active when entering the loop,
+    // break and continue leave all try statements above that depth
+    try_depth: usize,
 }
 
 impl LoopLabels {
-    fn new(cond: Label, end: Label) -> LoopLabels {
-        LoopLabels { cond, end }
+    fn new(cond: Label, end: Label, try_depth: usize) -> LoopLabels {
+        LoopLabels {
+            cond,
+            end,
+            try_depth,
+        }
     }
 }
 
+struct ActiveTry<'ast> {
+    finally_block: Option<&'ast Stmt>,
+
+    // ranges already closed, e.g. by return/break/continue
+    ranges: Vec<(BytecodeOffset, BytecodeOffset)>,
+
+    // start of currently open range
+    start: BytecodeOffset,
+}
+
 pub fn generate_fct<'ast>(
     vm: &VM<'ast>,
     id: FctId,
@@ -58,6 +83,7 @@ pub fn generate<'ast>(
 
         gen: BytecodeWriter::new(),
         loops: Vec::new(),
+        active_tries: Vec::new(),
         var_registers: HashMap::new(),
     };
     ast_bytecode_generator.generate()
@@ -74,6 +100,10 @@ struct AstBytecodeGen<'a, 'ast: 'a> {
 
     gen: BytecodeWriter,
     loops: Vec<LoopLabels>,
+
+    // try statements surrounding the current position, innermost last
+    active_tries: Vec<ActiveTry<'ast>>,
+
     var_registers: HashMap<VarId, Register>,
 }
 
@@ -85,9 +115,15 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             let var_self = self.src.var_self();
             let var_ty = self.specialize_type(var_self.ty);
             let var_id = var_self.id;
-            let reg = self.gen.add_register(var_ty.into());
+            let reg = self
+                .gen
+                .add_register(BytecodeType::from_ty(self.vm, var_ty));
             self.var_registers.insert(var_id, reg);
-            arguments += 1;
+
+            // module constructors are invoked without arguments
+            if !self.fct.is_module_constructor() {
+                arguments += 1;
+            }
         }
 
         for param in &self.ast.params {
@@ -97,7 +133,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             if ty.is_unit() {
                 // no register needed for unit
             } else {
-                let ty: BytecodeType = ty.into();
+                let ty: BytecodeType = BytecodeType::from_ty(self.vm, ty);
                 let reg = self.gen.add_register(ty);
                 self.var_registers.insert(var_id, reg);
                 arguments += 1;
@@ -106,6 +142,16 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
         self.gen.set_arguments(arguments);
 
+        if self.fct.is_lambda() {
+            let closure_reg = self.var_reg(self.src.var_self().id);
+            self.load_captured_vars(closure_reg);
+        }
+
+        if self.fct.is_module_constructor() {
+            let self_reg = self.var_reg(self.src.var_self().id);
+            self.allocate_module_object(self_reg);
+        }
+
         if let Some(ref block) = self.ast.block {
             for stmt in &block.stmts {
                 self.visit_stmt(stmt);
@@ -129,7 +175,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         self.gen.generate()
     }
 
-    fn visit_stmt(&mut self, stmt: &Stmt) {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
         match *stmt {
             StmtReturn(ref ret) => self.visit_stmt_return(ret),
             StmtBreak(ref stmt) => self.visit_stmt_break(stmt),
@@ -138,21 +184,31 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             StmtVar(ref stmt) => self.visit_stmt_var(stmt),
             StmtWhile(ref stmt) => self.visit_stmt_while(stmt),
             StmtFor(ref stmt) => self.visit_stmt_for(stmt),
+            StmtThrow(ref stmt) => self.visit_stmt_throw(stmt),
+            StmtTry(ref stmt) => self.visit_stmt_try(stmt),
         }
     }
 
-    fn visit_stmt_for(&mut self, stmt: &StmtForType) {
+    fn visit_stmt_for(&mut self, stmt: &'ast StmtForType) {
         let for_type_info = self.src.map_fors.get(stmt.id).unwrap().clone();
 
         // Emit: <obj> = <expr> (for <var> in <expr> { ... })
         let object_reg = self.visit_expr(&stmt.expr, DataDest::Alloc);
         self.gen.emit_push_register(object_reg);
 
+        let object_ty = self.specialize_type(for_type_info.make_iterator_type);
+        let iterator_ty = self.specialize_type(for_type_info.iterator_type);
+
         // Emit: <iterator> = <obj>.makeIterator();
         let iterator_reg = self.gen.add_register(BytecodeType::Ptr);
         self.gen.emit_invoke_direct_ptr(
             iterator_reg,
-            FctDef::fct_id(self.vm, for_type_info.make_iterator),
+            FctDef::fct_id_types(
+                self.vm,
+                for_type_info.make_iterator,
+                object_ty.type_params(self.vm),
+                TypeList::empty(),
+            ),
             1,
         );
 
@@ -165,7 +221,12 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let cond_reg = self.gen.add_register(BytecodeType::Bool);
         self.gen.emit_invoke_direct_ptr(
             cond_reg,
-            FctDef::fct_id(self.vm, for_type_info.has_next),
+            FctDef::fct_id_types(
+                self.vm,
+                for_type_info.has_next,
+                iterator_ty.type_params(self.vm),
+                TypeList::empty(),
+            ),
             1,
         );
         self.gen.emit_jump_if_false(cond_reg, lbl_end);
@@ -174,7 +235,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let var_id = *self.src.map_vars.get(stmt.id).unwrap();
         let var_ty = self.var_ty(var_id);
 
-        let ty: BytecodeType = var_ty.into();
+        let ty: BytecodeType = BytecodeType::from_ty(self.vm, var_ty);
         let var_reg = self.gen.add_register(ty);
         self.var_registers.insert(var_id, var_reg);
 
@@ -183,11 +244,17 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         self.emit_invoke_direct(
             var_ty,
             var_reg,
-            FctDef::fct_id(self.vm, for_type_info.next),
+            FctDef::fct_id_types(
+                self.vm,
+                for_type_info.next,
+                iterator_ty.type_params(self.vm),
+                TypeList::empty(),
+            ),
             1,
         );
 
-        self.loops.push(LoopLabels::new(lbl_cond, lbl_end));
+        self.loops
+            .push(LoopLabels::new(lbl_cond, lbl_end, self.active_tries.len()));
         self.visit_stmt(&stmt.block);
         self.loops.pop().unwrap();
 
@@ -195,7 +262,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         self.gen.bind_label(lbl_end);
     }
 
-    fn visit_stmt_var(&mut self, stmt: &StmtVarType) {
+    fn visit_stmt_var(&mut self, stmt: &'ast StmtVarType) {
         let var_id = *self.src.map_vars.get(stmt.id).unwrap();
         let ty = self.var_ty(var_id);
 
@@ -204,7 +271,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         } else if let Some(_tuple_id) = ty.tuple_id() {
             unimplemented!();
         } else {
-            let ty: BytecodeType = ty.into();
+            let ty: BytecodeType = BytecodeType::from_ty(self.vm, ty);
             let var_reg = self.gen.add_register(ty);
 
             self.var_registers.insert(var_id, var_reg);
@@ -217,29 +284,199 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
     }
 
-    fn visit_stmt_while(&mut self, stmt: &StmtWhileType) {
+    fn visit_stmt_while(&mut self, stmt: &'ast StmtWhileType) {
         let cond_lbl = self.gen.define_label();
         let end_lbl = self.gen.create_label();
         let cond_reg = self.visit_expr(&stmt.cond, DataDest::Alloc);
         self.gen.emit_jump_if_false(cond_reg, end_lbl);
-        self.loops.push(LoopLabels::new(cond_lbl, end_lbl));
+        self.loops
+            .push(LoopLabels::new(cond_lbl, end_lbl, self.active_tries.len()));
         self.visit_stmt(&stmt.block);
         self.loops.pop().unwrap();
         self.gen.emit_jump_loop(cond_lbl);
         self.gen.bind_label(end_lbl);
     }
 
-    fn visit_stmt_expr(&mut self, stmt: &StmtExprType) {
+    fn visit_stmt_throw(&mut self, stmt: &'ast StmtThrowType) {
+        let exception_reg = self.visit_expr(&stmt.expr, DataDest::Alloc);
+        self.gen.set_position(stmt.pos);
+        self.gen.emit_throw(exception_reg);
+    }
+
+    fn visit_stmt_try(&mut self, stmt: &'ast StmtTryType) {
+        let lbl_end = self.gen.create_label();
+        let finally_block = stmt.finally_block.as_ref().map(|finally| &*finally.block);
+        let finally_returns = finally_block.map_or(false, |block| always_returns(block));
+
+        let try_ranges = self.emit_try_region(finally_block, |this| {
+            this.visit_stmt(&stmt.try_block);
+        });
+
+        if let Some(finally_block) = finally_block {
+            self.visit_stmt(finally_block);
+        }
+
+        if !always_returns(&stmt.try_block) && !finally_returns {
+            self.gen.emit_jump(lbl_end);
+        }
+
+        let mut catch_ranges = Vec::new();
+
+        for catch in &stmt.catch_blocks {
+            let var_id = *self.src.map_vars.get(catch.id).unwrap();
+            let ty = self.var_ty(var_id);
+            let cls_def_id = specialize_class_ty(self.vm, ty);
+
+            let catch_start = self.gen.offset();
+
+            for &range in &try_ranges {
+                self.gen
+                    .add_exception_handler(range, catch_start, CatchType::Class(cls_def_id));
+            }
+
+            let ranges = self.emit_try_region(finally_block, |this| {
+                let var_reg = this.gen.add_register(BytecodeType::Ptr);
+                this.var_registers.insert(var_id, var_reg);
+                this.gen.set_position(catch.pos);
+                this.gen.emit_load_exception(var_reg);
+
+                this.visit_stmt(&catch.block);
+            });
+
+            if let Some(finally_block) = finally_block {
+                catch_ranges.extend(ranges);
+                self.visit_stmt(finally_block);
+            }
+
+            if !always_returns(&catch.block) && !finally_returns {
+                self.gen.emit_jump(lbl_end);
+            }
+        }
+
+        if let Some(finally_block) = finally_block {
+            // exceptions not handled by any catch-block: run finally-block
+            // and rethrow exception afterwards
+            let finally_start = self.gen.offset();
+
+            for &range in try_ranges.iter().chain(catch_ranges.iter()) {
+                self.gen
+                    .add_exception_handler(range, finally_start, CatchType::Any);
+            }
+
+            let exception_reg = self.gen.add_register(BytecodeType::Ptr);
+            self.gen.set_position(stmt.pos);
+            self.gen.emit_load_exception(exception_reg);
+
+            self.visit_stmt(finally_block);
+
+            if !finally_returns {
+                self.gen.set_position(stmt.pos);
+                self.gen.emit_throw(exception_reg);
+            }
+        }
+
+        self.gen.bind_label(lbl_end);
+    }
+
+    // Emits code covered by the handlers of a try statement and returns all
+    // bytecode ranges that need to be registered for these handlers.
+    fn emit_try_region<F>(
+        &mut self,
+        finally_block: Option<&'ast Stmt>,
+        f: F,
+    ) -> Vec<(BytecodeOffset, BytecodeOffset)>
+    where
+        F: FnOnce(&mut AstBytecodeGen<'a, 'ast>),
+    {
+        self.active_tries.push(ActiveTry {
+            finally_block,
+            ranges: Vec::new(),
+            start: self.gen.offset(),
+        });
+
+        f(self);
+
+        let mut active_try = self.active_tries.pop().unwrap();
+        self.close_try_range(&mut active_try);
+
+        active_try.ranges
+    }
+
+    fn close_try_range(&mut self, active_try: &mut ActiveTry<'ast>) {
+        let end = self.gen.offset();
+
+        if active_try.start < end {
+            active_try.ranges.push((active_try.start, end));
+        }
+    }
+
+    fn has_active_finally(&self) -> bool {
+        self.active_tries
+            .iter()
+            .any(|active_try| active_try.finally_block.is_some())
+    }
+
+    // Leaves all try statements above the given depth: emits their
+    // finally-blocks inline, the emitted code is not covered by their handlers.
+    fn emit_finally_blocks(&mut self, depth: usize) -> Vec<ActiveTry<'ast>> {
+        let mut exited = Vec::new();
+
+        while self.active_tries.len() > depth {
+            let mut active_try = self.active_tries.pop().unwrap();
+            self.close_try_range(&mut active_try);
+
+            if let Some(finally_block) = active_try.finally_block {
+                self.visit_stmt(finally_block);
+            }
+
+            exited.push(active_try);
+        }
+
+        exited
+    }
+
+    // Code after return, break or continue is again covered by the handlers
+    // of the exited try statements.
+    fn reenter_tries(&mut self, exited: Vec<ActiveTry<'ast>>) {
+        let start = self.gen.offset();
+
+        for mut active_try in exited.into_iter().rev() {
+            active_try.start = start;
+            self.active_tries.push(active_try);
+        }
+    }
+
+    fn visit_stmt_expr(&mut self, stmt: &'ast StmtExprType) {
         self.visit_expr(&stmt.expr, DataDest::Effect);
     }
 
-    fn visit_stmt_return(&mut self, ret: &StmtReturnType) {
-        if let Some(ref expr) = ret.expr {
+    fn visit_stmt_return(&mut self, ret: &'ast StmtReturnType) {
+        let result_reg = if let Some(ref expr) = ret.expr {
             let result_reg = self.visit_expr(expr, DataDest::Alloc);
+            let ret_ty = self.specialize_type(self.fct.return_type);
+
+            if !ret_ty.is_unit() && self.has_active_finally() {
+                // finally-blocks could modify the variable that is returned
+                let ty: BytecodeType = BytecodeType::from_ty(self.vm, ret_ty);
+                let copy_reg = self.gen.add_register(ty);
+                self.emit_mov(ty, copy_reg, result_reg);
+                Some(copy_reg)
+            } else {
+                Some(result_reg)
+            }
+        } else {
+            None
+        };
+
+        let exited = self.emit_finally_blocks(0);
+
+        if let Some(result_reg) = result_reg {
             self.emit_ret_value(result_reg);
         } else {
             self.gen.emit_ret_void();
         }
+
+        self.reenter_tries(exited);
     }
 
     fn emit_ret_value(&mut self, result_reg: Register) {
@@ -250,7 +487,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             return;
         }
 
-        let return_type: BytecodeType = ret_ty.into();
+        let return_type: BytecodeType = BytecodeType::from_ty(self.vm, ret_ty);
 
         match return_type {
             BytecodeType::Bool => self.gen.emit_ret_bool(result_reg),
@@ -262,26 +499,34 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Float => self.gen.emit_ret_float(result_reg),
             BytecodeType::Double => self.gen.emit_ret_double(result_reg),
             BytecodeType::Ptr => self.gen.emit_ret_ptr(result_reg),
+            BytecodeType::Tuple(_) => self.gen.emit_ret_tuple(result_reg),
         }
     }
 
-    fn visit_stmt_break(&mut self, _stmt: &StmtBreakType) {
+    fn visit_stmt_break(&mut self, _stmt: &'ast StmtBreakType) {
         let end = self.loops.last().unwrap().end;
+        let try_depth = self.loops.last().unwrap().try_depth;
+        let exited = self.emit_finally_blocks(try_depth);
         self.gen.emit_jump(end);
+        self.reenter_tries(exited);
     }
 
-    fn visit_stmt_continue(&mut self, _stmt: &StmtContinueType) {
+    fn visit_stmt_continue(&mut self, _stmt: &'ast StmtContinueType) {
         let cond = self.loops.last().unwrap().cond;
+        let try_depth = self.loops.last().unwrap().try_depth;
+        let exited = self.emit_finally_blocks(try_depth);
         self.gen.emit_jump_loop(cond);
+        self.reenter_tries(exited);
     }
 
-    fn visit_expr(&mut self, expr: &Expr, dest: DataDest) -> Register {
+    fn visit_expr(&mut self, expr: &'ast Expr, dest: DataDest) -> Register {
         match *expr {
             ExprUn(ref un) => self.visit_expr_un(un, dest),
             ExprBin(ref bin) => self.visit_expr_bin(bin, dest),
             ExprDot(ref field) => self.visit_expr_dot(field, dest),
             ExprBlock(ref block) => self.visit_expr_block(block, dest),
             ExprIf(ref expr) => self.visit_expr_if(expr, dest),
+            ExprMatch(ref expr) => self.visit_expr_match(expr, dest),
             ExprTemplate(ref template) => self.visit_expr_template(template, dest),
             ExprTypeParam(_) => unreachable!(),
             ExprPath(ref path) => self.visit_expr_path(path, dest),
@@ -293,16 +538,18 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             ExprIdent(ref ident) => self.visit_expr_ident(ident, dest),
             ExprCall(ref call) => self.visit_expr_call(call, dest),
             ExprDelegation(ref call) => self.visit_expr_delegation(call, dest),
+            ExprSelf(ref expr) if self.fct.is_lambda() => self.visit_expr_captured_self(expr, dest),
             ExprSelf(_) => self.visit_expr_self(dest),
             ExprSuper(_) => self.visit_expr_self(dest),
             ExprConv(ref conv) => self.visit_expr_conv(conv, dest),
             ExprNil(ref nil) => self.visit_expr_nil(nil, dest),
             ExprTuple(ref tuple) => self.visit_expr_tuple(tuple, dest),
-            ExprLambda(_) => unimplemented!(),
+            ExprLitStruct(ref expr) => self.visit_expr_lit_struct(expr, dest),
+            ExprLambda(ref expr) => self.visit_expr_lambda(expr, dest),
         }
     }
 
-    fn visit_expr_template(&mut self, expr: &ExprTemplateType, dest: DataDest) -> Register {
+    fn visit_expr_template(&mut self, expr: &'ast ExprTemplateType, dest: DataDest) -> Register {
         let buffer_register = self.ensure_register(dest, BytecodeType::Ptr);
         self.gen.set_position(expr.pos);
 
@@ -335,7 +582,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                         .find_trait_method(self.vm, self.vm.vips.stringable_trait, name, false)
                         .expect("toString() method not found");
 
-                    if ty.reference_type() {
+                    if ty.reference_type(self.vm) {
                         self.gen.emit_invoke_direct_ptr(
                             part_register,
                             FctDef::fct_id(self.vm, to_string_id),
@@ -368,21 +615,24 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         buffer_register
     }
 
-    fn visit_expr_path(&mut self, expr: &ExprPathType, dest: DataDest) -> Register {
+    fn visit_expr_path(&mut self, expr: &'ast ExprPathType, dest: DataDest) -> Register {
         let ident_type = self.src.map_idents.get(expr.id).unwrap();
 
         match ident_type {
             &IdentType::EnumValue(_, value) => {
-                let dest = self.ensure_register(dest, BytecodeType::Int);
-                self.gen.emit_const_int(dest, value as i32);
-                dest
+                let ty = self.ty(expr.id);
+                self.emit_enum_variant(ty, value, &[], expr.pos, dest)
+            }
+
+            &IdentType::Field(cls_ty, field_id) => {
+                self.visit_field_load(&expr.lhs, cls_ty, field_id, expr.pos, dest)
             }
 
             _ => unreachable!(),
         }
     }
 
-    fn visit_expr_conv(&mut self, expr: &ExprConvType, dest: DataDest) -> Register {
+    fn visit_expr_conv(&mut self, expr: &'ast ExprConvType, dest: DataDest) -> Register {
         let conv = *self.src.map_convs.get(expr.id).unwrap();
         let ty = self.specialize_type(conv.check_type);
         let cls_def_id = specialize_class_ty(self.vm, ty);
@@ -403,17 +653,209 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             };
 
             let object = self.visit_expr(&expr.object, dest);
-            self.gen.emit_checked_cast(object, cls_def_id);
+
+            // casts to a supertype, e.g. of a variable narrowed by an `is` check,
+            // always succeed
+            if !conv.valid {
+                self.gen.emit_checked_cast(object, cls_def_id);
+            }
+
             object
         }
     }
 
-    fn visit_expr_if(&mut self, expr: &ExprIfType, dest: DataDest) -> Register {
+    fn visit_expr_match(&mut self, expr: &'ast ExprMatchType, dest: DataDest) -> Register {
+        let ty = self.ty(expr.id);
+        let dest = if ty.is_unit() {
+            Register::invalid()
+        } else {
+            self.ensure_register(dest, BytecodeType::from_ty(self.vm, ty))
+        };
+
+        let expr_ty = self.ty(expr.expr.id());
+        let value_reg = self.visit_expr(&expr.expr, DataDest::Alloc);
+        let end_lbl = self.gen.create_label();
+
+        for case in &expr.cases {
+            let next_lbl = self.gen.create_label();
+
+            match case.pattern {
+                MatchPattern::Underscore(_) => {}
+
+                MatchPattern::Literal(ref lit) => {
+                    let lit_reg = self.visit_expr(lit, DataDest::Alloc);
+                    let cond_reg = self.gen.add_register(BytecodeType::Bool);
+
+                    match expr_ty {
+                        BuiltinType::Bool => {
+                            self.gen.emit_test_eq_bool(cond_reg, value_reg, lit_reg)
+                        }
+                        BuiltinType::UInt8 => {
+                            self.gen.emit_test_eq_uint8(cond_reg, value_reg, lit_reg)
+                        }
+                        BuiltinType::Char => {
+                            self.gen.emit_test_eq_char(cond_reg, value_reg, lit_reg)
+                        }
+                        BuiltinType::Int => self.gen.emit_test_eq_int(cond_reg, value_reg, lit_reg),
+                        BuiltinType::Int64 => {
+                            self.gen.emit_test_eq_int64(cond_reg, value_reg, lit_reg)
+                        }
+                        _ => unreachable!(),
+                    }
+
+                    self.gen.emit_jump_if_false(cond_reg, next_lbl);
+                }
+
+                MatchPattern::Variant(ref pattern) => {
+                    let value = match self.src.map_idents.get(pattern.id) {
+                        Some(&IdentType::EnumValue(_, value)) => value,
+                        _ => unreachable!(),
+                    };
+
+                    self.emit_match_variant(pattern, expr_ty, value, value_reg, next_lbl);
+                }
+            }
+
+            self.visit_expr(&case.value, DataDest::Reg(dest));
+
+            if !expr_always_returns(&case.value) {
+                self.gen.emit_jump(end_lbl);
+            }
+
+            self.gen.bind_label(next_lbl);
+        }
+
+        self.gen.bind_label(end_lbl);
+
+        dest
+    }
+
+    fn emit_match_variant(
+        &mut self,
+        pattern: &'ast MatchPatternVariantType,
+        ty: BuiltinType,
+        value: u32,
+        value_reg: Register,
+        next_lbl: Label,
+    ) {
+        let enum_def_id = self.specialize_enum_ty(ty);
+        let enum_def = self.vm.enum_defs.idx(enum_def_id);
+        let (layout, cls_def_id) = {
+            let enum_def = enum_def.read();
+            (
+                enum_def.layout,
+                enum_def.variants[value as usize].cls_def_id,
+            )
+        };
+
+        let variant_reg = self.gen.add_register(BytecodeType::Int);
+        self.gen.emit_const_int(variant_reg, value as i32);
+        let cond_reg = self.gen.add_register(BytecodeType::Bool);
+
+        match layout {
+            EnumLayout::Int => {
+                self.gen.emit_test_eq_enum(cond_reg, value_reg, variant_reg);
+                self.gen.emit_jump_if_false(cond_reg, next_lbl);
+            }
+
+            EnumLayout::Tagged => {
+                let cls_def_id = cls_def_id.unwrap();
+                let fields = self.vm.class_defs.idx(cls_def_id).read().fields.clone();
+
+                // the tag is always stored in the first field of the variant
+                let tag_reg = self.gen.add_register(BytecodeType::Int);
+                self.gen
+                    .emit_load_field_int(tag_reg, value_reg, cls_def_id, FieldId::from(0));
+                self.gen.emit_test_eq_int(cond_reg, tag_reg, variant_reg);
+                self.gen.emit_jump_if_false(cond_reg, next_lbl);
+
+                let params = match pattern.params {
+                    Some(ref params) => params,
+                    None => return,
+                };
+
+                for (idx, (param, field)) in params.iter().zip(&fields[1..]).enumerate() {
+                    if param.name.is_none() || field.ty.is_unit() {
+                        continue;
+                    }
+
+                    let var_id = *self.src.map_vars.get(param.id).unwrap();
+                    let ty = BytecodeType::from_ty(self.vm, field.ty);
+                    let reg = self.gen.add_register(ty);
+                    self.var_registers.insert(var_id, reg);
+                    self.emit_load_field(ty, reg, value_reg, cls_def_id, (idx + 1).into());
+                }
+            }
+
+            EnumLayout::Ptr => unreachable!(),
+        }
+    }
+
+    fn emit_enum_variant(
+        &mut self,
+        ty: BuiltinType,
+        value: u32,
+        args: &'ast [Box<Expr>],
+        pos: Position,
+        dest: DataDest,
+    ) -> Register {
+        let enum_def_id = self.specialize_enum_ty(ty);
+        let enum_def = self.vm.enum_defs.idx(enum_def_id);
+        let (layout, cls_def_id) = {
+            let enum_def = enum_def.read();
+            (
+                enum_def.layout,
+                enum_def.variants[value as usize].cls_def_id,
+            )
+        };
+
+        match layout {
+            EnumLayout::Int => {
+                let dest = self.ensure_register(dest, BytecodeType::Int);
+                self.gen.emit_const_int(dest, value as i32);
+                return dest;
+            }
+
+            EnumLayout::Tagged => {}
+            EnumLayout::Ptr => unreachable!(),
+        }
+
+        let cls_def_id = cls_def_id.unwrap();
+        let fields = self.vm.class_defs.idx(cls_def_id).read().fields.clone();
+
+        let arg_regs = args
+            .iter()
+            .map(|arg| self.visit_expr(arg, DataDest::Alloc))
+            .collect::<Vec<_>>();
+
+        let object_reg = self.ensure_register(dest, BytecodeType::Ptr);
+        self.gen.set_position(pos);
+        self.gen.emit_new_object(object_reg, cls_def_id);
+
+        // the tag is always stored in the first field of the variant
+        let tag_reg = self.gen.add_register(BytecodeType::Int);
+        self.gen.emit_const_int(tag_reg, value as i32);
+        self.gen
+            .emit_store_field_int(tag_reg, object_reg, cls_def_id, FieldId::from(0));
+
+        for (idx, (arg_reg, field)) in arg_regs.into_iter().zip(&fields[1..]).enumerate() {
+            if field.ty.is_unit() {
+                continue;
+            }
+
+            let ty = BytecodeType::from_ty(self.vm, field.ty);
+            self.emit_store_field(ty, arg_reg, object_reg, cls_def_id, (idx + 1).into());
+        }
+
+        object_reg
+    }
+
+    fn visit_expr_if(&mut self, expr: &'ast ExprIfType, dest: DataDest) -> Register {
         let ty = self.ty(expr.id);
         let dest = if ty.is_unit() {
             Register::invalid()
         } else {
-            self.ensure_register(dest, ty.into())
+            self.ensure_register(dest, BytecodeType::from_ty(self.vm, ty))
         };
 
         if let Some(ref else_block) = expr.else_block {
@@ -443,7 +885,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         dest
     }
 
-    fn visit_expr_block(&mut self, block: &ExprBlockType, dest: DataDest) -> Register {
+    fn visit_expr_block(&mut self, block: &'ast ExprBlockType, dest: DataDest) -> Register {
         for stmt in &block.stmts {
             self.visit_stmt(stmt);
         }
@@ -455,7 +897,11 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
     }
 
-    fn visit_expr_dot(&mut self, expr: &ExprDotType, dest: DataDest) -> Register {
+    fn visit_expr_dot(&mut self, expr: &'ast ExprDotType, dest: DataDest) -> Register {
+        if let Some(&IdentType::StructField(ty, field_id)) = self.src.map_idents.get(expr.id) {
+            return self.visit_struct_field_load(&expr.lhs, ty, field_id, dest);
+        }
+
         let (cls_ty, field_id) = {
             let ident_type = self.src.map_idents.get(expr.id).unwrap();
 
@@ -465,6 +911,17 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             }
         };
 
+        self.visit_field_load(&expr.lhs, cls_ty, field_id, expr.pos, dest)
+    }
+
+    fn visit_field_load(
+        &mut self,
+        object: &'ast Expr,
+        cls_ty: BuiltinType,
+        field_id: FieldId,
+        pos: Position,
+        dest: DataDest,
+    ) -> Register {
         let cls_ty = self.specialize_type(cls_ty);
         let cls_def_id = specialize_class_ty(self.vm, cls_ty);
 
@@ -478,18 +935,22 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
         if field_ty.is_unit() {
             assert!(dest.is_unit());
-            let obj = self.visit_expr(&expr.lhs, DataDest::Alloc);
-            self.gen.set_position(expr.pos);
-            self.gen.emit_nil_check(obj);
+            let obj = self.visit_expr(object, DataDest::Alloc);
+
+            if !self.is_non_nil(object) {
+                self.gen.set_position(pos);
+                self.gen.emit_nil_check(obj);
+            }
+
             return Register::invalid();
         }
 
-        let field_bc_ty: BytecodeType = field_ty.into();
+        let field_bc_ty: BytecodeType = BytecodeType::from_ty(self.vm, field_ty);
 
         let dest = self.ensure_register(dest, field_bc_ty);
-        let obj = self.visit_expr(&expr.lhs, DataDest::Alloc);
+        let obj = self.visit_expr(object, DataDest::Alloc);
 
-        self.gen.set_position(expr.pos);
+        self.gen.set_position(pos);
 
         match field_bc_ty {
             BytecodeType::UInt8 => self
@@ -519,25 +980,73 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Ptr => self
                 .gen
                 .emit_load_field_ptr(dest, obj, cls_def_id, field_id),
+            BytecodeType::Tuple(_) => self
+                .gen
+                .emit_load_field_tuple(dest, obj, cls_def_id, field_id),
+        }
+
+        dest
+    }
+
+    fn visit_struct_field_load(
+        &mut self,
+        object: &'ast Expr,
+        struct_ty: BuiltinType,
+        field_id: StructFieldId,
+        dest: DataDest,
+    ) -> Register {
+        let (tuple_id, field_ty) = self.struct_field_ty(struct_ty, field_id);
+        let obj = self.visit_expr(object, DataDest::Alloc);
+
+        if field_ty.is_unit() || dest.is_effect() {
+            return Register::invalid();
         }
 
+        let field_bc_ty: BytecodeType = BytecodeType::from_ty(self.vm, field_ty);
+        let dest = self.ensure_register(dest, field_bc_ty);
+
+        self.gen
+            .emit_load_tuple_element(dest, obj, tuple_id, field_id.idx() as u32);
+
         dest
     }
 
-    fn visit_expr_assert(&mut self, expr: &ExprCallType, dest: DataDest) {
+    // returns the tuple layout of the struct type and the type of the field
+    fn struct_field_ty(
+        &self,
+        struct_ty: BuiltinType,
+        field_id: StructFieldId,
+    ) -> (TupleId, BuiltinType) {
+        let struct_ty = self.specialize_type(struct_ty);
+        let tuple_id = struct_ty.layout_tuple_id(self.vm).unwrap();
+        let (field_ty, _) = self.vm.tuples.lock().get_at(tuple_id, field_id.idx());
+
+        (tuple_id, field_ty)
+    }
+
+    fn visit_expr_assert(&mut self, expr: &'ast ExprCallType, dest: DataDest) {
         assert!(dest.is_unit());
         let assert_reg = self.visit_expr(&*expr.args[0], DataDest::Alloc);
         self.gen.set_position(expr.pos);
         self.gen.emit_assert(assert_reg);
     }
 
-    fn visit_expr_call(&mut self, expr: &ExprCallType, dest: DataDest) -> Register {
+    fn visit_expr_call(&mut self, expr: &'ast ExprCallType, dest: DataDest) -> Register {
+        if let Some(&IdentType::EnumValue(_, value)) = self.src.map_idents.get(expr.callee.id()) {
+            let ty = self.ty(expr.id);
+            return self.emit_enum_variant(ty, value, &expr.args, expr.pos, dest);
+        }
+
         if let Some(info) = self.get_intrinsic(expr.id) {
             return self.emit_intrinsic_call(expr, info, dest);
         }
 
         let call_type = self.src.map_calls.get(expr.id).unwrap().clone();
 
+        if let CallType::Lambda(lambda_ty) = *call_type {
+            return self.visit_expr_call_lambda(expr, lambda_ty, dest);
+        }
+
         // Find method that is called
         let callee_id = self.determine_callee(&call_type);
 
@@ -557,7 +1066,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let return_reg = if return_type.is_unit() {
             Register::invalid()
         } else {
-            self.ensure_register(dest, return_type.into())
+            self.ensure_register(dest, BytecodeType::from_ty(self.vm, return_type))
         };
 
         // Evaluate object/self argument
@@ -599,6 +1108,72 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         self.emit_call_result(&call_type, dest, return_reg, object_argument)
     }
 
+    fn visit_expr_call_lambda(
+        &mut self,
+        expr: &'ast ExprCallType,
+        lambda_ty: BuiltinType,
+        dest: DataDest,
+    ) -> Register {
+        let lambda_ty = self.specialize_type(lambda_ty);
+        let lambda_id = lambda_ty.lambda_id().unwrap();
+        let (params, return_type) = {
+            let lambdas = self.vm.lambda_types.lock();
+            let lambda = lambdas.get(lambda_id);
+            (lambda.params().to_vec(), lambda.ret())
+        };
+
+        let return_type = if dest.is_effect() {
+            BuiltinType::Unit
+        } else {
+            return_type
+        };
+
+        let return_reg = if return_type.is_unit() {
+            Register::invalid()
+        } else {
+            self.ensure_register(dest, BytecodeType::from_ty(self.vm, return_type))
+        };
+
+        // the closure object is passed as first argument
+        let closure_reg = self.visit_expr(&expr.callee, DataDest::Alloc);
+        let mut arguments = vec![closure_reg];
+
+        for (arg, &ty) in expr.args.iter().zip(&params) {
+            if ty.is_unit() {
+                self.visit_expr(arg, DataDest::Effect);
+            } else {
+                arguments.push(self.visit_expr(arg, DataDest::Alloc));
+            }
+        }
+
+        let num_args = arguments.len();
+
+        for reg in arguments {
+            self.gen.emit_push_register(reg);
+        }
+
+        self.gen.set_position(expr.pos);
+
+        if return_type.is_unit() {
+            self.gen.emit_invoke_lambda_void(num_args);
+        } else {
+            match BytecodeType::from_ty(self.vm, return_type) {
+                BytecodeType::Bool => self.gen.emit_invoke_lambda_bool(return_reg, num_args),
+                BytecodeType::UInt8 => self.gen.emit_invoke_lambda_uint8(return_reg, num_args),
+                BytecodeType::Char => self.gen.emit_invoke_lambda_char(return_reg, num_args),
+                BytecodeType::Int => self.gen.emit_invoke_lambda_int(return_reg, num_args),
+                BytecodeType::Int32 => self.gen.emit_invoke_lambda_int(return_reg, num_args),
+                BytecodeType::Int64 => self.gen.emit_invoke_lambda_int64(return_reg, num_args),
+                BytecodeType::Float => self.gen.emit_invoke_lambda_float(return_reg, num_args),
+                BytecodeType::Double => self.gen.emit_invoke_lambda_double(return_reg, num_args),
+                BytecodeType::Ptr => self.gen.emit_invoke_lambda_ptr(return_reg, num_args),
+                BytecodeType::Tuple(_) => self.gen.emit_invoke_lambda_tuple(return_reg, num_args),
+            }
+        }
+
+        return_reg
+    }
+
     fn determine_callee(&mut self, call_type: &CallType) -> FctId {
         match *call_type {
             CallType::Method(_, fct_id, _) => {
@@ -678,7 +1253,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let arg_bytecode_types = arg_types
             .iter()
             .filter(|ty| !ty.is_unit())
-            .map(|&ty| ty.into())
+            .map(|&ty| BytecodeType::from_ty(self.vm, ty))
             .collect::<Vec<BytecodeType>>();
 
         (arg_types, arg_bytecode_types, return_type)
@@ -686,14 +1261,14 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_call_object_argument(
         &mut self,
-        expr: &ExprCallType,
+        expr: &'ast ExprCallType,
         call_type: &CallType,
         return_reg: Register,
         dest: DataDest,
     ) -> Option<Register> {
         match *call_type {
-            CallType::Method(_, _, _) => {
-                let obj_expr = expr.object().expect("method target required");
+            CallType::Method(_, _, _) | CallType::Trait(_, _, _) => {
+                let obj_expr = expr.receiver().expect("method target required");
                 let reg = self.visit_expr(obj_expr, DataDest::Alloc);
 
                 Some(reg)
@@ -713,14 +1288,17 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_call_arguments(
         &mut self,
-        expr: &ExprCallType,
+        expr: &'ast ExprCallType,
         call_type: &CallType,
         arg_types: &[BuiltinType],
     ) -> Vec<Register> {
         let mut registers = Vec::new();
 
         let arg_start_offset = match *call_type {
-            CallType::CtorNew(_, _) | CallType::Method(_, _, _) | CallType::Expr(_, _) => 1,
+            CallType::CtorNew(_, _)
+            | CallType::Method(_, _, _)
+            | CallType::Expr(_, _)
+            | CallType::Trait(_, _, _) => 1,
             _ => 0,
         };
 
@@ -778,7 +1356,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_call_inst(
         &mut self,
-        expr: &ExprCallType,
+        expr: &'ast ExprCallType,
         fct: &Fct,
         call_type: &CallType,
         arg_bytecode_types: &[BytecodeType],
@@ -823,11 +1401,13 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                     self.emit_invoke_direct(return_type, return_reg, fct_def_id, num_args);
                 }
             }
-            CallType::Trait(_, _) => unimplemented!(),
+            CallType::Trait(_, _, _) => {
+                self.emit_invoke_trait(return_type, return_reg, fct_def_id, num_args);
+            }
             CallType::TraitStatic(_, _, _) => {
                 self.emit_invoke_static(return_type, return_reg, fct_def_id, num_args);
             }
-            CallType::Intrinsic(_) => unreachable!(),
+            CallType::Intrinsic(_) | CallType::Lambda(_) => unreachable!(),
         }
     }
 
@@ -859,6 +1439,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Int32 => self.gen.emit_mov_int(dest, src),
             BytecodeType::Int64 => self.gen.emit_mov_int64(dest, src),
             BytecodeType::Ptr => self.gen.emit_mov_ptr(dest, src),
+            BytecodeType::Tuple(tuple_id) => self.gen.emit_mov_tuple(dest, src, tuple_id),
         }
     }
 
@@ -872,7 +1453,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         if return_type.is_unit() {
             self.gen.emit_invoke_virtual_void(callee_id, num_args);
         } else {
-            let return_type: BytecodeType = return_type.into();
+            let return_type: BytecodeType = BytecodeType::from_ty(self.vm, return_type);
 
             match return_type.into() {
                 BytecodeType::Bool => self
@@ -902,6 +1483,56 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 BytecodeType::Ptr => self
                     .gen
                     .emit_invoke_virtual_ptr(return_reg, callee_id, num_args),
+                BytecodeType::Tuple(_) => self
+                    .gen
+                    .emit_invoke_virtual_tuple(return_reg, callee_id, num_args),
+            }
+        }
+    }
+
+    fn emit_invoke_trait(
+        &mut self,
+        return_type: BuiltinType,
+        return_reg: Register,
+        callee_id: FctDefId,
+        num_args: usize,
+    ) {
+        if return_type.is_unit() {
+            self.gen.emit_invoke_trait_void(callee_id, num_args);
+        } else {
+            let return_type: BytecodeType = BytecodeType::from_ty(self.vm, return_type);
+
+            match return_type.into() {
+                BytecodeType::Bool => self
+                    .gen
+                    .emit_invoke_trait_bool(return_reg, callee_id, num_args),
+                BytecodeType::UInt8 => self
+                    .gen
+                    .emit_invoke_trait_uint8(return_reg, callee_id, num_args),
+                BytecodeType::Char => self
+                    .gen
+                    .emit_invoke_trait_char(return_reg, callee_id, num_args),
+                BytecodeType::Int => self
+                    .gen
+                    .emit_invoke_trait_int(return_reg, callee_id, num_args),
+                BytecodeType::Int32 => self
+                    .gen
+                    .emit_invoke_trait_int(return_reg, callee_id, num_args),
+                BytecodeType::Int64 => self
+                    .gen
+                    .emit_invoke_trait_int64(return_reg, callee_id, num_args),
+                BytecodeType::Float => self
+                    .gen
+                    .emit_invoke_trait_float(return_reg, callee_id, num_args),
+                BytecodeType::Double => self
+                    .gen
+                    .emit_invoke_trait_double(return_reg, callee_id, num_args),
+                BytecodeType::Ptr => self
+                    .gen
+                    .emit_invoke_trait_ptr(return_reg, callee_id, num_args),
+                BytecodeType::Tuple(_) => self
+                    .gen
+                    .emit_invoke_trait_tuple(return_reg, callee_id, num_args),
             }
         }
     }
@@ -916,7 +1547,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         if return_type.is_unit() {
             self.gen.emit_invoke_direct_void(callee_id, num_args);
         } else {
-            let return_type: BytecodeType = return_type.into();
+            let return_type: BytecodeType = BytecodeType::from_ty(self.vm, return_type);
 
             match return_type.into() {
                 BytecodeType::Bool => self
@@ -946,6 +1577,9 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 BytecodeType::Ptr => self
                     .gen
                     .emit_invoke_direct_ptr(return_reg, callee_id, num_args),
+                BytecodeType::Tuple(_) => self
+                    .gen
+                    .emit_invoke_direct_tuple(return_reg, callee_id, num_args),
             }
         }
     }
@@ -960,7 +1594,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         if return_type.is_unit() {
             self.gen.emit_invoke_static_void(callee_id, num_args);
         } else {
-            let return_type: BytecodeType = return_type.into();
+            let return_type: BytecodeType = BytecodeType::from_ty(self.vm, return_type);
 
             match return_type.into() {
                 BytecodeType::Bool => self
@@ -990,11 +1624,18 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 BytecodeType::Ptr => self
                     .gen
                     .emit_invoke_static_ptr(return_reg, callee_id, num_args),
+                BytecodeType::Tuple(_) => self
+                    .gen
+                    .emit_invoke_static_tuple(return_reg, callee_id, num_args),
             }
         }
     }
 
-    fn visit_expr_delegation(&mut self, expr: &ExprDelegationType, dest: DataDest) -> Register {
+    fn visit_expr_delegation(
+        &mut self,
+        expr: &'ast ExprDelegationType,
+        dest: DataDest,
+    ) -> Register {
         assert!(dest.is_unit());
         let call_type = self.src.map_calls.get(expr.id).unwrap().clone();
         let fct_id = call_type.fct_id().unwrap();
@@ -1009,7 +1650,9 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let arg_types = callee
             .params_with_self()
             .iter()
-            .map(|&arg| self.specialize_type_for_call(&call_type, arg).into())
+            .map(|&arg| {
+                BytecodeType::from_ty(self.vm, self.specialize_type_for_call(&call_type, arg))
+            })
             .collect::<Vec<BytecodeType>>();
         let num_args = arg_types.len();
 
@@ -1053,6 +1696,136 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         dest
     }
 
+    fn visit_expr_captured_self(&mut self, expr: &ExprSelfType, dest: DataDest) -> Register {
+        match *self.src.map_idents.get(expr.id).unwrap() {
+            IdentType::Var(var_id) => self.visit_expr_ident_var(var_id, dest),
+            _ => unreachable!(),
+        }
+    }
+
+    fn visit_expr_lambda(&mut self, expr: &'ast ExprLambdaType, dest: DataDest) -> Register {
+        let lambda_id = *self.src.map_lambdas.get(expr.id).unwrap();
+        let lambda = self.vm.fcts.idx(lambda_id);
+        let lambda = lambda.read();
+
+        // lambdas are specialized with the type params of the enclosing function
+        let cls_def_id = specialize_lambda(
+            self.vm,
+            &*lambda,
+            self.cls_type_params.clone(),
+            self.fct_type_params.clone(),
+        );
+        let captures = lambda.src().read().captures.clone();
+
+        let closure_reg = self.ensure_register(dest, BytecodeType::Ptr);
+
+        self.gen.set_position(expr.pos);
+        self.gen.emit_new_object(closure_reg, cls_def_id);
+
+        for (idx, capture) in captures.iter().enumerate() {
+            let ty = self.var_ty(capture.outer);
+
+            if ty.is_unit() {
+                continue;
+            }
+
+            let src = self.var_reg(capture.outer);
+            self.emit_store_field(
+                BytecodeType::from_ty(self.vm, ty),
+                src,
+                closure_reg,
+                cls_def_id,
+                idx.into(),
+            );
+        }
+
+        closure_reg
+    }
+
+    // the module object is stored in the module's global before the
+    // initializers run, so that they can already refer to the module
+    fn allocate_module_object(&mut self, self_reg: Register) {
+        let module_id = match self.fct.parent {
+            FctParent::Module(module_id) => module_id,
+            _ => unreachable!(),
+        };
+
+        let cls_def_id = specialize_module(self.vm, module_id);
+        let global_id = self.vm.modules.idx(module_id).read().global_id;
+
+        self.gen.set_position(self.fct.pos);
+        self.gen.emit_new_object(self_reg, cls_def_id);
+        self.gen.emit_store_global_ptr(self_reg, global_id);
+    }
+
+    fn load_captured_vars(&mut self, closure_reg: Register) {
+        let cls_def_id = specialize_lambda(
+            self.vm,
+            self.fct,
+            self.cls_type_params.clone(),
+            self.fct_type_params.clone(),
+        );
+
+        self.gen.set_position(self.ast.pos);
+
+        for (idx, capture) in self.src.captures.iter().enumerate() {
+            let ty = self.var_ty(capture.inner);
+
+            if ty.is_unit() {
+                continue;
+            }
+
+            let ty: BytecodeType = BytecodeType::from_ty(self.vm, ty);
+            let reg = self.gen.add_register(ty);
+            self.var_registers.insert(capture.inner, reg);
+            self.emit_load_field(ty, reg, closure_reg, cls_def_id, idx.into());
+        }
+    }
+
+    fn emit_load_field(
+        &mut self,
+        ty: BytecodeType,
+        dest: Register,
+        obj: Register,
+        cls_id: ClassDefId,
+        field_id: FieldId,
+    ) {
+        match ty {
+            BytecodeType::UInt8 => self.gen.emit_load_field_uint8(dest, obj, cls_id, field_id),
+            BytecodeType::Bool => self.gen.emit_load_field_bool(dest, obj, cls_id, field_id),
+            BytecodeType::Char => self.gen.emit_load_field_char(dest, obj, cls_id, field_id),
+            BytecodeType::Int => self.gen.emit_load_field_int(dest, obj, cls_id, field_id),
+            BytecodeType::Int32 => self.gen.emit_load_field_int(dest, obj, cls_id, field_id),
+            BytecodeType::Int64 => self.gen.emit_load_field_int64(dest, obj, cls_id, field_id),
+            BytecodeType::Float => self.gen.emit_load_field_float(dest, obj, cls_id, field_id),
+            BytecodeType::Double => self.gen.emit_load_field_double(dest, obj, cls_id, field_id),
+            BytecodeType::Ptr => self.gen.emit_load_field_ptr(dest, obj, cls_id, field_id),
+            BytecodeType::Tuple(_) => self.gen.emit_load_field_tuple(dest, obj, cls_id, field_id),
+        }
+    }
+
+    fn emit_store_field(
+        &mut self,
+        ty: BytecodeType,
+        src: Register,
+        obj: Register,
+        cls_id: ClassDefId,
+        field_id: FieldId,
+    ) {
+        match ty {
+            BytecodeType::UInt8 => self.gen.emit_store_field_uint8(src, obj, cls_id, field_id),
+            BytecodeType::Bool => self.gen.emit_store_field_bool(src, obj, cls_id, field_id),
+            BytecodeType::Char => self.gen.emit_store_field_char(src, obj, cls_id, field_id),
+            BytecodeType::Int => self.gen.emit_store_field_int(src, obj, cls_id, field_id),
+            BytecodeType::Int32 => self.gen.emit_store_field_int(src, obj, cls_id, field_id),
+            BytecodeType::Int64 => self.gen.emit_store_field_int64(src, obj, cls_id, field_id),
+            BytecodeType::Float => self.gen.emit_store_field_float(src, obj, cls_id, field_id),
+            BytecodeType::Double => self.gen.emit_store_field_double(src, obj, cls_id, field_id),
+            BytecodeType::Ptr => self.gen.emit_store_field_ptr(src, obj, cls_id, field_id),
+            BytecodeType::Tuple(_) => self.gen.emit_store_field_tuple(src, obj, cls_id, field_id),
+        }
+    }
+
     fn visit_expr_self(&mut self, dest: DataDest) -> Register {
         if dest.is_effect() {
             return Register::invalid();
@@ -1066,7 +1839,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
 
         let dest = dest.reg();
-        let ty: BytecodeType = self.src.var_self().ty.into();
+        let ty: BytecodeType = BytecodeType::from_ty(self.vm, self.src.var_self().ty);
 
         match ty {
             BytecodeType::Bool => self.gen.emit_mov_bool(dest, var_reg),
@@ -1078,6 +1851,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Int32 => self.gen.emit_mov_int(dest, var_reg),
             BytecodeType::Int64 => self.gen.emit_mov_int64(dest, var_reg),
             BytecodeType::Ptr => self.gen.emit_mov_ptr(dest, var_reg),
+            BytecodeType::Tuple(tuple_id) => self.gen.emit_mov_tuple(dest, var_reg, tuple_id),
         }
         dest
     }
@@ -1197,7 +1971,45 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         dest
     }
 
-    fn visit_expr_tuple(&mut self, e: &ExprTupleType, dest: DataDest) -> Register {
+    fn visit_expr_lit_struct(&mut self, e: &'ast ExprLitStructType, dest: DataDest) -> Register {
+        let ty = self.ty(e.id);
+        let struct_id = match ty {
+            BuiltinType::Struct(struct_id, _) => struct_id,
+            _ => unreachable!(),
+        };
+        let tuple_id = ty.layout_tuple_id(self.vm).unwrap();
+
+        // all arguments are evaluated before the first element is stored,
+        // the arguments might still read the destination
+        let mut values = Vec::new();
+
+        for arg in &e.args {
+            let (field_id, _) = find_field_in_struct(self.vm, struct_id, arg.name).unwrap();
+            let (arg_ty, _) = self.vm.tuples.lock().get_at(tuple_id, field_id.idx());
+
+            if arg_ty.is_unit() {
+                self.visit_expr(&arg.value, DataDest::Effect);
+            } else {
+                let value = self.visit_expr(&arg.value, DataDest::Alloc);
+                values.push((field_id, value));
+            }
+        }
+
+        if dest.is_effect() {
+            return Register::invalid();
+        }
+
+        let dest = self.ensure_register(dest, BytecodeType::Tuple(tuple_id));
+
+        for (field_id, value) in values {
+            self.gen
+                .emit_store_tuple_element(value, dest, tuple_id, field_id.idx() as u32);
+        }
+
+        dest
+    }
+
+    fn visit_expr_tuple(&mut self, e: &'ast ExprTupleType, dest: DataDest) -> Register {
         if e.values.is_empty() {
             assert!(dest.is_unit());
             return Register::invalid();
@@ -1206,7 +2018,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         unimplemented!();
     }
 
-    fn visit_expr_un(&mut self, expr: &ExprUnType, dest: DataDest) -> Register {
+    fn visit_expr_un(&mut self, expr: &'ast ExprUnType, dest: DataDest) -> Register {
         if expr.op == UnOp::Neg && expr.opnd.is_lit_int() {
             self.visit_expr_lit_int(expr.opnd.to_lit_int().unwrap(), dest, true)
         } else if let Some(intrinsic) = self.get_intrinsic(expr.id) {
@@ -1216,7 +2028,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
     }
 
-    fn visit_expr_bin(&mut self, expr: &ExprBinType, dest: DataDest) -> Register {
+    fn visit_expr_bin(&mut self, expr: &'ast ExprBinType, dest: DataDest) -> Register {
         if expr.op.is_any_assign() {
             self.visit_expr_assign(expr, dest)
         } else if expr.op == BinOp::Cmp(CmpOp::Is) || expr.op == BinOp::Cmp(CmpOp::IsNot) {
@@ -1232,8 +2044,8 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
     }
 
-    fn visit_expr_bin_method(&mut self, expr: &ExprBinType, dest: DataDest) -> Register {
-        let lhs_type: BytecodeType = self.ty(expr.lhs.id()).into();
+    fn visit_expr_bin_method(&mut self, expr: &'ast ExprBinType, dest: DataDest) -> Register {
+        let lhs_type: BytecodeType = BytecodeType::from_ty(self.vm, self.ty(expr.lhs.id()));
 
         let lhs = self.visit_expr(&expr.lhs, DataDest::Alloc);
         let rhs = self.visit_expr(&expr.rhs, DataDest::Alloc);
@@ -1248,7 +2060,8 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let function_return_type: BuiltinType =
             self.specialize_type_for_call(call_type, fct.return_type);
 
-        let function_return_type_bc: BytecodeType = function_return_type.into();
+        let function_return_type_bc: BytecodeType =
+            BytecodeType::from_ty(self.vm, function_return_type);
 
         let return_type = match expr.op {
             BinOp::Cmp(_) => BytecodeType::Bool,
@@ -1302,7 +2115,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_intrinsic_call(
         &mut self,
-        expr: &ExprCallType,
+        expr: &'ast ExprCallType,
         info: IntrinsicInfo,
         dest: DataDest,
     ) -> Register {
@@ -1343,7 +2156,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                         return Register::invalid();
                     }
 
-                    let ty: BytecodeType = ty.into();
+                    let ty: BytecodeType = BytecodeType::from_ty(self.vm, ty);
                     let dest = self.ensure_register(dest, ty);
 
                     match ty {
@@ -1356,6 +2169,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                         BytecodeType::Float => self.gen.emit_const_float(dest, 0.0),
                         BytecodeType::Double => self.gen.emit_const_double(dest, 0.0),
                         BytecodeType::Ptr => self.gen.emit_const_nil(dest),
+                        BytecodeType::Tuple(_) => unimplemented!(),
                     }
 
                     dest
@@ -1368,8 +2182,8 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_bin_intrinsic<F>(
         &mut self,
-        lhs: &Expr,
-        rhs: &Expr,
+        lhs: &'ast Expr,
+        rhs: &'ast Expr,
         dest: DataDest,
         return_type: BytecodeType,
         fct: F,
@@ -1395,7 +2209,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_un_intrinsic<F>(
         &mut self,
-        opnd: &Expr,
+        opnd: &'ast Expr,
         dest: DataDest,
         return_type: BytecodeType,
         fct: F,
@@ -1417,7 +2231,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         dest
     }
 
-    fn emit_bin_is(&mut self, expr: &ExprBinType, dest: DataDest) -> Register {
+    fn emit_bin_is(&mut self, expr: &'ast ExprBinType, dest: DataDest) -> Register {
         if dest.is_effect() {
             self.visit_expr(&expr.lhs, dest);
             self.visit_expr(&expr.rhs, dest);
@@ -1437,7 +2251,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         dest
     }
 
-    fn emit_bin_or(&mut self, expr: &ExprBinType, dest: DataDest) -> Register {
+    fn emit_bin_or(&mut self, expr: &'ast ExprBinType, dest: DataDest) -> Register {
         if dest.is_effect() {
             let end_lbl = self.gen.create_label();
             let dest = self.gen.add_register(BytecodeType::Bool);
@@ -1461,7 +2275,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
     }
 
-    fn emit_bin_and(&mut self, expr: &ExprBinType, dest: DataDest) -> Register {
+    fn emit_bin_and(&mut self, expr: &'ast ExprBinType, dest: DataDest) -> Register {
         if dest.is_effect() {
             let end_lbl = self.gen.create_label();
             let dest = self.gen.add_register(BytecodeType::Bool);
@@ -1487,9 +2301,9 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_intrinsic_array_set(
         &mut self,
-        arr: &Expr,
-        idx: &Expr,
-        src: &Expr,
+        arr: &'ast Expr,
+        idx: &'ast Expr,
+        src: &'ast Expr,
         pos: Position,
         dest: DataDest,
     ) -> Register {
@@ -1499,7 +2313,11 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let ty = self.specialize_type(ty);
         let ty = ty.type_params(self.vm);
         let ty = ty[0];
-        let ty: Option<BytecodeType> = if ty.is_unit() { None } else { Some(ty.into()) };
+        let ty: Option<BytecodeType> = if ty.is_unit() {
+            None
+        } else {
+            Some(BytecodeType::from_ty(self.vm, ty))
+        };
 
         let arr = self.visit_expr(arr, DataDest::Alloc);
         let idx = self.visit_expr(idx, DataDest::Alloc);
@@ -1524,6 +2342,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Float => self.gen.emit_store_array_float(src, arr, idx),
             BytecodeType::Double => self.gen.emit_store_array_double(src, arr, idx),
             BytecodeType::Ptr => self.gen.emit_store_array_ptr(src, arr, idx),
+            BytecodeType::Tuple(_) => unimplemented!(),
         }
 
         Register::invalid()
@@ -1531,7 +2350,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_intrinsic_un(
         &mut self,
-        opnd: &Expr,
+        opnd: &'ast Expr,
         info: IntrinsicInfo,
         pos: Position,
         dest: DataDest,
@@ -1542,8 +2361,12 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             match intrinsic {
                 Intrinsic::GenericArrayLen | Intrinsic::StrLen => {
                     let src = self.visit_expr(opnd, DataDest::Alloc);
-                    self.gen.set_position(pos);
-                    self.gen.emit_nil_check(src);
+
+                    if !self.is_non_nil(opnd) {
+                        self.gen.set_position(pos);
+                        self.gen.emit_nil_check(src);
+                    }
+
                     return Register::invalid();
                 }
 
@@ -1670,8 +2493,8 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
 
     fn emit_intrinsic_bin(
         &mut self,
-        lhs: &Expr,
-        rhs: &Expr,
+        lhs: &'ast Expr,
+        rhs: &'ast Expr,
         info: IntrinsicInfo,
         op: Option<BinOp>,
         pos: Position,
@@ -1694,7 +2517,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                             assert!(dest.is_unit());
                             None
                         } else {
-                            Some(ty.into())
+                            Some(BytecodeType::from_ty(self.vm, ty))
                         }
                     };
 
@@ -1723,6 +2546,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                     BytecodeType::Float => self.gen.emit_load_array_float(dest, arr, idx),
                     BytecodeType::Double => self.gen.emit_load_array_double(dest, arr, idx),
                     BytecodeType::Ptr => self.gen.emit_load_array_ptr(dest, arr, idx),
+                    BytecodeType::Tuple(_) => unimplemented!(),
                 }
                 return dest;
             }
@@ -1893,7 +2717,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         dest
     }
 
-    fn visit_expr_assign(&mut self, expr: &ExprBinType, dest: DataDest) -> Register {
+    fn visit_expr_assign(&mut self, expr: &'ast ExprBinType, dest: DataDest) -> Register {
         assert!(dest.is_unit());
 
         if expr.lhs.is_ident() {
@@ -1905,7 +2729,13 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             }
         } else {
             match *expr.lhs {
-                ExprDot(ref dot) => self.visit_expr_assign_dot(expr, dot),
+                ExprDot(ref dot) => match self.src.map_idents.get(dot.id) {
+                    Some(&IdentType::StructField(ty, field_id)) => {
+                        self.visit_expr_assign_struct_field(expr, ty, field_id)
+                    }
+                    _ => self.visit_expr_assign_field(expr, dot.id, &dot.lhs),
+                },
+                ExprPath(ref path) => self.visit_expr_assign_field(expr, path.id, &path.lhs),
                 ExprCall(ref call) => self.visit_expr_assign_call(expr, call),
                 _ => unreachable!(),
             };
@@ -1914,7 +2744,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         Register::invalid()
     }
 
-    fn visit_expr_assign_call(&mut self, expr: &ExprBinType, call_expr: &ExprCallType) {
+    fn visit_expr_assign_call(&mut self, expr: &'ast ExprBinType, call_expr: &'ast ExprCallType) {
         let object = &call_expr.callee;
         let index = &call_expr.args[0];
         let value = &expr.rhs;
@@ -1950,9 +2780,10 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
     }
 
-    fn visit_expr_assign_dot(&mut self, expr: &ExprBinType, dot: &ExprDotType) {
+    // assigns the field of an object, e.g. `obj.field = value` or `Module::field = value`
+    fn visit_expr_assign_field(&mut self, expr: &'ast ExprBinType, id: NodeId, object: &'ast Expr) {
         let (class, field_id) = {
-            let ident_type = self.src.map_idents.get(dot.id).unwrap();
+            let ident_type = self.src.map_idents.get(id).unwrap();
             match ident_type {
                 &IdentType::Field(class, field) => (class, field),
                 _ => unreachable!(),
@@ -1966,16 +2797,19 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let ty: Option<BytecodeType> = if field.ty.is_unit() {
             None
         } else {
-            Some(field.ty.into())
+            Some(BytecodeType::from_ty(self.vm, field.ty))
         };
 
-        let obj = self.visit_expr(&dot.lhs, DataDest::Alloc);
+        let obj = self.visit_expr(object, DataDest::Alloc);
         let src = self.visit_expr(&expr.rhs, DataDest::Alloc);
 
         self.gen.set_position(expr.pos);
 
         if ty.is_none() {
-            self.gen.emit_nil_check(obj);
+            if !self.is_non_nil(object) {
+                self.gen.emit_nil_check(obj);
+            }
+
             return;
         }
 
@@ -1989,10 +2823,134 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Float => self.gen.emit_store_field_float(src, obj, cls_id, field_id),
             BytecodeType::Double => self.gen.emit_store_field_double(src, obj, cls_id, field_id),
             BytecodeType::Ptr => self.gen.emit_store_field_ptr(src, obj, cls_id, field_id),
+            BytecodeType::Tuple(_) => self.gen.emit_store_field_tuple(src, obj, cls_id, field_id),
+        }
+    }
+
+    // assigns the field of a struct value, e.g. `a.b.c = value`: the struct is read
+    // from the variable, global or object field it lives in, updated and written back
+    fn visit_expr_assign_struct_field(
+        &mut self,
+        expr: &'ast ExprBinType,
+        struct_ty: BuiltinType,
+        field_id: StructFieldId,
+    ) {
+        let (tuple_id, field_ty) = self.struct_field_ty(struct_ty, field_id);
+        let mut path = vec![(tuple_id, field_id.idx() as u32)];
+        let mut object = &expr.lhs.to_dot().unwrap().lhs;
+
+        while let Some(dot) = object.to_dot() {
+            match self.src.map_idents.get(dot.id) {
+                Some(&IdentType::StructField(ty, field_id)) => {
+                    let (tuple_id, _) = self.struct_field_ty(ty, field_id);
+                    path.push((tuple_id, field_id.idx() as u32));
+                    object = &dot.lhs;
+                }
+
+                _ => break,
+            }
+        }
+
+        path.reverse();
+
+        let place = match **object {
+            ExprIdent(ref ident) => match self.src.map_idents.get(ident.id) {
+                Some(&IdentType::Var(var_id)) => StructPlace::Var(self.var_reg(var_id)),
+                Some(&IdentType::Global(gid)) => StructPlace::Global(gid),
+                _ => unreachable!(),
+            },
+
+            ExprDot(ref dot) => self.struct_place_field(dot.id, &dot.lhs),
+            ExprPath(ref path) => self.struct_place_field(path.id, &path.lhs),
+            _ => unreachable!(),
+        };
+
+        if field_ty.is_unit() {
+            self.visit_expr(&expr.rhs, DataDest::Effect);
+
+            if let StructPlace::Field(obj, _, _) = place {
+                self.gen.set_position(expr.pos);
+                self.gen.emit_nil_check(obj);
+            }
+
+            return;
+        }
+
+        let src = self.visit_expr(&expr.rhs, DataDest::Alloc);
+
+        let (root_tuple_id, _) = path[0];
+        let root = match place {
+            StructPlace::Var(var_reg) => var_reg,
+
+            StructPlace::Global(gid) => {
+                let root = self.gen.add_register(BytecodeType::Tuple(root_tuple_id));
+                self.gen.emit_load_global_tuple(root, gid);
+                root
+            }
+
+            StructPlace::Field(obj, cls_id, field_id) => {
+                let root = self.gen.add_register(BytecodeType::Tuple(root_tuple_id));
+                self.gen.set_position(expr.pos);
+                self.gen.emit_load_field_tuple(root, obj, cls_id, field_id);
+                root
+            }
+        };
+
+        // load the nested struct values down to the one containing the field
+        let mut values = vec![root];
+
+        for idx in 1..path.len() {
+            let (tuple_id, _) = path[idx];
+            let (parent_tuple_id, parent_element) = path[idx - 1];
+            let value = self.gen.add_register(BytecodeType::Tuple(tuple_id));
+            self.gen.emit_load_tuple_element(
+                value,
+                values[idx - 1],
+                parent_tuple_id,
+                parent_element,
+            );
+            values.push(value);
+        }
+
+        let (tuple_id, element) = *path.last().unwrap();
+        self.gen
+            .emit_store_tuple_element(src, *values.last().unwrap(), tuple_id, element);
+
+        // and write the updated values back
+        for idx in (1..path.len()).rev() {
+            let (parent_tuple_id, parent_element) = path[idx - 1];
+            self.gen.emit_store_tuple_element(
+                values[idx],
+                values[idx - 1],
+                parent_tuple_id,
+                parent_element,
+            );
         }
+
+        match place {
+            StructPlace::Var(_) => {}
+            StructPlace::Global(gid) => self.gen.emit_store_global_tuple(root, gid),
+            StructPlace::Field(obj, cls_id, field_id) => {
+                self.gen.set_position(expr.pos);
+                self.gen.emit_store_field_tuple(root, obj, cls_id, field_id);
+            }
+        }
+    }
+
+    fn struct_place_field(&mut self, id: NodeId, object: &'ast Expr) -> StructPlace {
+        let (cls_ty, field_id) = match self.src.map_idents.get(id) {
+            Some(&IdentType::Field(cls_ty, field_id)) => (cls_ty, field_id),
+            _ => unreachable!(),
+        };
+
+        let cls_ty = self.specialize_type(cls_ty);
+        let cls_id = specialize_class_ty(self.vm, cls_ty);
+        let obj = self.visit_expr(object, DataDest::Alloc);
+
+        StructPlace::Field(obj, cls_id, field_id)
     }
 
-    fn visit_expr_assign_var(&mut self, expr: &ExprBinType, var_id: VarId) {
+    fn visit_expr_assign_var(&mut self, expr: &'ast ExprBinType, var_id: VarId) {
         let ty = self.var_ty(var_id);
 
         let dest = if ty.is_unit() {
@@ -2005,7 +2963,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         self.visit_expr(&expr.rhs, dest);
     }
 
-    fn visit_expr_assign_global(&mut self, expr: &ExprBinType, gid: GlobalId) {
+    fn visit_expr_assign_global(&mut self, expr: &'ast ExprBinType, gid: GlobalId) {
         let glob = self.vm.globals.idx(gid);
         let glob = glob.read();
 
@@ -2018,7 +2976,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let src = self.visit_expr(&expr.rhs, dest);
 
         if !glob.ty.is_unit() {
-            let ty: BytecodeType = glob.ty.into();
+            let ty: BytecodeType = BytecodeType::from_ty(self.vm, glob.ty);
             match ty {
                 BytecodeType::Bool => self.gen.emit_store_global_bool(src, gid),
                 BytecodeType::UInt8 => self.gen.emit_store_global_uint8(src, gid),
@@ -2029,25 +2987,32 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 BytecodeType::Float => self.gen.emit_store_global_float(src, gid),
                 BytecodeType::Double => self.gen.emit_store_global_double(src, gid),
                 BytecodeType::Ptr => self.gen.emit_store_global_ptr(src, gid),
+                BytecodeType::Tuple(_) => self.gen.emit_store_global_tuple(src, gid),
             }
         }
     }
 
-    fn visit_expr_ident(&mut self, ident: &ExprIdentType, dest: DataDest) -> Register {
+    fn visit_expr_ident(&mut self, ident: &'ast ExprIdentType, dest: DataDest) -> Register {
         let ident_type = self.src.map_idents.get(ident.id).unwrap();
 
         match ident_type {
             &IdentType::Var(varid) => self.visit_expr_ident_var(varid, dest),
             &IdentType::Global(gid) => self.visit_expr_ident_global(gid, dest),
 
-            &IdentType::Field(_, _) => unimplemented!(),
-            &IdentType::Struct(_) => unimplemented!(),
+            &IdentType::Field(_, _) | &IdentType::StructField(_, _) => unreachable!(),
+            &IdentType::Struct(_) => unreachable!(),
             &IdentType::Const(cid) => self.visit_expr_ident_const(cid, dest),
 
             &IdentType::Enum(_) | &IdentType::EnumValue(_, _) => unreachable!(),
             &IdentType::Fct(_) | &IdentType::FctType(_, _) => unreachable!(),
             &IdentType::Class(_) | &IdentType::ClassType(_, _) => unreachable!(),
-            &IdentType::Module(_) => unreachable!(),
+
+            &IdentType::Module(module_id) => {
+                // the module object lives in a hidden global
+                let global_id = self.vm.modules.idx(module_id).read().global_id;
+                self.visit_expr_ident_global(global_id, dest)
+            }
+
             &IdentType::TypeParam(_) | &IdentType::TypeParamStaticMethod(_, _) => unreachable!(),
             &IdentType::Method(_, _) | &IdentType::MethodType(_, _, _) => unreachable!(),
             &IdentType::StaticMethod(_, _) | &IdentType::StaticMethodType(_, _, _) => {
@@ -2065,7 +3030,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         let xconst = xconst.lock();
         let ty = xconst.ty;
 
-        let dest = self.ensure_register(dest, ty.into());
+        let dest = self.ensure_register(dest, BytecodeType::from_ty(self.vm, ty));
 
         match ty {
             BuiltinType::Bool => {
@@ -2124,7 +3089,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             return Register::invalid();
         }
 
-        let ty: BytecodeType = glob.ty.into();
+        let ty: BytecodeType = BytecodeType::from_ty(self.vm, glob.ty);
         let dest = self.ensure_register(dest, ty);
 
         match ty {
@@ -2137,6 +3102,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             BytecodeType::Float => self.gen.emit_load_global_float(dest, gid),
             BytecodeType::Double => self.gen.emit_load_global_double(dest, gid),
             BytecodeType::Ptr => self.gen.emit_load_global_ptr(dest, gid),
+            BytecodeType::Tuple(_) => self.gen.emit_load_global_tuple(dest, gid),
         }
 
         dest
@@ -2155,7 +3121,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         }
 
         let var_reg = self.var_reg(var_id);
-        let ty: BytecodeType = self.specialize_type(ty).into();
+        let ty: BytecodeType = BytecodeType::from_ty(self.vm, self.specialize_type(ty));
 
         if dest.is_alloc() {
             return var_reg;
@@ -2174,6 +3140,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 BytecodeType::Float => self.gen.emit_mov_float(dest, var_reg),
                 BytecodeType::Double => self.gen.emit_mov_double(dest, var_reg),
                 BytecodeType::Ptr => self.gen.emit_mov_ptr(dest, var_reg),
+                BytecodeType::Tuple(tuple_id) => self.gen.emit_mov_tuple(dest, var_reg, tuple_id),
             }
         }
 
@@ -2248,14 +3215,17 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 fct_type_params = TypeList::empty();
             }
 
-            CallType::Trait(_, _) => unimplemented!(),
+            CallType::Trait(_, _, ref type_params) => {
+                cls_type_params = TypeList::empty();
+                fct_type_params = type_params.clone();
+            }
 
             CallType::TraitStatic(_, _, _) => {
                 cls_type_params = TypeList::empty();
                 fct_type_params = TypeList::empty();
             }
 
-            CallType::Intrinsic(_) => unreachable!(),
+            CallType::Intrinsic(_) | CallType::Lambda(_) => unreachable!(),
         }
 
         (cls_type_params, fct_type_params)
@@ -2302,11 +3272,11 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
                 specialize_type(self.vm, ty, &type_params, &TypeList::empty())
             }
 
-            CallType::Trait(_, _) => unimplemented!(),
+            CallType::Trait(..) => specialize_for_call_type(call_type, ty, self.vm),
             CallType::TraitStatic(_, _, _) => {
                 specialize_type(self.vm, ty, &TypeList::empty(), &TypeList::empty())
             }
-            CallType::Intrinsic(_) => unreachable!(),
+            CallType::Intrinsic(_) | CallType::Lambda(_) => unreachable!(),
         };
 
         self.specialize_type(ty)
@@ -2316,11 +3286,28 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
         specialize_type(self.vm, ty, self.cls_type_params, self.fct_type_params)
     }
 
+    fn specialize_enum_ty(&self, ty: BuiltinType) -> EnumDefId {
+        match ty {
+            BuiltinType::Enum(enum_id, list_id) => {
+                let params = self.vm.lists.lock().get(list_id);
+                specialize_enum_id_params(self.vm, enum_id, params)
+            }
+
+            _ => unreachable!(),
+        }
+    }
+
     fn ty(&self, id: NodeId) -> BuiltinType {
         let ty = self.src.ty(id);
         self.specialize_type(ty)
     }
 
+    // `self` and variables guarded by an `is` check or a nil comparison
+    // cannot be nil
+    fn is_non_nil(&self, expr: &Expr) -> bool {
+        expr.is_this() || self.src.map_smart_casts.get(expr.id()).is_some()
+    }
+
     fn var_ty(&self, id: VarId) -> BuiltinType {
         let ty = self.src.vars[id].ty;
         self.specialize_type(ty)
@@ -2333,7 +3320,7 @@ impl<'a, 'ast> AstBytecodeGen<'a, 'ast> {
             return Some(intrinsic.into());
         }
 
-        let fid = call_type.fct_id().unwrap();
+        let fid = call_type.fct_id()?;
 
         // the function we compile right now is never an intrinsic
         if self.fct.id == fid {
@@ -2373,6 +3360,14 @@ impl From<Intrinsic> for IntrinsicInfo {
     }
 }
 
+// the variable, global or object field a struct value is stored in
+#[derive(Copy, Clone, Debug)]
+enum StructPlace {
+    Var(Register),
+    Global(GlobalId),
+    Field(Register, ClassDefId, FieldId),
+}
+
 #[derive(Copy, Clone, Debug)]
 enum DataDest {
     // Do not store result. Only interested in side-effects of
@@ -3031,6 +3031,60 @@ fn gen_assert() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn gen_throw() {
+    let result = code("fun f(a: Exception) { throw a; }");
+    let expected = vec![Throw(r(0)), RetVoid];
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn gen_try_catch() {
+    gen_fct(
+        "fun f(a: Exception) { try { throw a; } catch x: Exception { } }",
+        |_, code, fct| {
+            let expected = vec![Throw(r(0)), LoadException(r(1)), Jump(3), RetVoid];
+            assert_eq!(expected, code);
+
+            let handlers = fct.exception_handlers();
+            assert_eq!(1, handlers.len());
+            assert_eq!(0, handlers[0].try_start.to_u32());
+            assert_eq!(2, handlers[0].try_end.to_u32());
+            assert_eq!(2, handlers[0].catch.to_u32());
+        },
+    );
+}
+
+#[test]
+fn gen_try_finally_return() {
+    gen_fct(
+        "
+            fun f() -> Int { try { return 1; } finally { g(); } }
+            fun g() { }
+            ",
+        |vm, code, fct| {
+            let fct_id = vm.fct_def_by_name("g").expect("g not found");
+            let expected = vec![
+                ConstInt(r(0), 1),
+                MovInt(r(1), r(0)),
+                InvokeStaticVoid(fct_id, 0),
+                RetInt(r(1)),
+                InvokeStaticVoid(fct_id, 0),
+                LoadException(r(2)),
+                InvokeStaticVoid(fct_id, 0),
+                Throw(r(2)),
+            ];
+            assert_eq!(expected, code);
+
+            // finally-block and return are not covered by the handler
+            let handlers = fct.exception_handlers();
+            assert_eq!(1, handlers.len());
+            assert_eq!(0, handlers[0].try_start.to_u32());
+            assert!(handlers[0].try_end.to_u32() < handlers[0].catch.to_u32());
+        },
+    );
+}
+
 #[test]
 fn gen_position_assert() {
     let result = position("fun f() { assert(true); }");
@@ -3174,6 +3228,25 @@ fn gen_checked_cast() {
     );
 }
 
+#[test]
+fn gen_smart_cast() {
+    gen(
+        "@open class A class B: A
+        fun f(a: A) -> B { if a is B { return a as B; } else { return nil; } }",
+        |vm, code| {
+            let cls_id = vm.cls_def_by_name("B");
+            let expected = vec![
+                InstanceOf(r(1), r(0), cls_id),
+                JumpIfFalse(r(1), 3),
+                RetPtr(r(0)),
+                ConstNil(r(2)),
+                RetPtr(r(2)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
 #[test]
 fn gen_checked_cast_effect() {
     gen(
@@ -3195,6 +3268,91 @@ fn gen_enum_value() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn gen_match_enum() {
+    let result = code(
+        "enum MyEnum { A, B } fun f(x: MyEnum) -> Int { match x { MyEnum::A => 1, MyEnum::B => 2 } }",
+    );
+    let expected = vec![
+        ConstInt(r(2), 0),
+        TestEqEnum(r(3), r(0), r(2)),
+        JumpIfFalse(r(3), 5),
+        ConstInt(r(1), 1),
+        Jump(10),
+        ConstInt(r(4), 1),
+        TestEqEnum(r(5), r(0), r(4)),
+        JumpIfFalse(r(5), 10),
+        ConstInt(r(1), 2),
+        Jump(10),
+        RetInt(r(1)),
+    ];
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn gen_enum_variant_with_payload() {
+    gen(
+        "enum MyEnum { A(Int), B } fun f() -> MyEnum { MyEnum::A(1) }",
+        |vm, code| {
+            let cls: ClassDefId = (vm.class_defs.lock().len() - 2).into();
+            let expected = vec![
+                ConstInt(r(0), 1),
+                NewObject(r(1), cls),
+                ConstInt(r(2), 0),
+                StoreFieldInt(r(2), r(1), cls, 0.into()),
+                StoreFieldInt(r(0), r(1), cls, 1.into()),
+                RetPtr(r(1)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_match_enum_with_payload() {
+    gen(
+        "enum MyEnum { A(Int), B }
+        fun f(x: MyEnum) -> Int { match x { MyEnum::A(v) => v, MyEnum::B => 2 } }",
+        |vm, code| {
+            let cls_a: ClassDefId = (vm.class_defs.lock().len() - 2).into();
+            let cls_b: ClassDefId = (vm.class_defs.lock().len() - 1).into();
+            let expected = vec![
+                ConstInt(r(2), 0),
+                LoadFieldInt(r(4), r(0), cls_a, 0.into()),
+                TestEqInt(r(3), r(4), r(2)),
+                JumpIfFalse(r(3), 7),
+                LoadFieldInt(r(5), r(0), cls_a, 1.into()),
+                MovInt(r(1), r(5)),
+                Jump(13),
+                ConstInt(r(6), 1),
+                LoadFieldInt(r(8), r(0), cls_b, 0.into()),
+                TestEqInt(r(7), r(8), r(6)),
+                JumpIfFalse(r(7), 13),
+                ConstInt(r(1), 2),
+                Jump(13),
+                RetInt(r(1)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_match_literal() {
+    let result = code("fun f(x: Int) -> Int { match x { 1 => 2, _ => 3 } }");
+    let expected = vec![
+        ConstInt(r(2), 1),
+        TestEqInt(r(3), r(0), r(2)),
+        JumpIfFalse(r(3), 5),
+        ConstInt(r(1), 2),
+        Jump(7),
+        ConstInt(r(1), 3),
+        Jump(7),
+        RetInt(r(1)),
+    ];
+    assert_eq!(expected, result);
+}
+
 #[test]
 fn gen_string_length() {
     let result = code("fun f(x: String) -> Int { x.length() }");
@@ -3498,6 +3656,152 @@ fn gen_tuple_var() {
     });
 }
 
+#[test]
+fn gen_struct_lit() {
+    gen(
+        "struct Foo { a: Int, b: Int } fun f(x: Int) -> Foo { Foo { b: 2, a: x } }",
+        |vm, code| {
+            let tuple_id = ensure_tuple(vm, vec![BuiltinType::Int, BuiltinType::Int]);
+            let expected = vec![
+                ConstInt(r(1), 2),
+                StoreTupleElement(r(1), r(2), tuple_id, 1),
+                StoreTupleElement(r(0), r(2), tuple_id, 0),
+                RetTuple(r(2)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_struct_field_load() {
+    gen(
+        "struct Foo { a: Int, b: Int } fun f(x: Foo) -> Int { x.b }",
+        |vm, code| {
+            let tuple_id = ensure_tuple(vm, vec![BuiltinType::Int, BuiltinType::Int]);
+            let expected = vec![LoadTupleElement(r(1), r(0), tuple_id, 1), RetInt(r(1))];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_struct_field_store() {
+    gen(
+        "struct Foo { a: Int, b: Int } fun f(x: Foo) { var y = x; y.a = 3; }",
+        |vm, code| {
+            let tuple_id = ensure_tuple(vm, vec![BuiltinType::Int, BuiltinType::Int]);
+            let expected = vec![
+                MovTuple(r(1), r(0), tuple_id),
+                ConstInt(r(2), 3),
+                StoreTupleElement(r(2), r(1), tuple_id, 0),
+                RetVoid,
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_struct_field_store_in_class_field() {
+    gen(
+        "struct Foo { a: Int, b: Int } class Bar(var foo: Foo) fun f(x: Bar) { x.foo.a = 1; }",
+        |vm, code| {
+            let tuple_id = ensure_tuple(vm, vec![BuiltinType::Int, BuiltinType::Int]);
+            let (cls, field) = vm.field_by_name("Bar", "foo");
+            let expected = vec![
+                ConstInt(r(1), 1),
+                LoadFieldTuple(r(2), r(0), cls, field),
+                StoreTupleElement(r(1), r(2), tuple_id, 0),
+                StoreFieldTuple(r(2), r(0), cls, field),
+                RetVoid,
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_struct_call() {
+    gen(
+        "
+            struct Foo { a: Int, b: Int }
+            fun f(x: Foo) -> Foo { g(x) }
+            fun g(x: Foo) -> Foo { x }
+            ",
+        |vm, code| {
+            let fct_id = vm.fct_def_by_name("g").expect("g not found");
+            let expected = vec![
+                PushRegister(r(0)),
+                InvokeStaticTuple(r(1), fct_id, 1),
+                RetTuple(r(1)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_lambda_call() {
+    let result = code("fun f(x: (Int) -> Int) -> Int { x(1) }");
+    let expected = vec![
+        ConstInt(r(2), 1),
+        PushRegister(r(0)),
+        PushRegister(r(2)),
+        InvokeLambdaInt(r(1), 2),
+        RetInt(r(1)),
+    ];
+    assert_eq!(expected, result);
+
+    let result = code("fun f(x: () -> ()) { x(); }");
+    let expected = vec![PushRegister(r(0)), InvokeLambdaVoid(1), RetVoid];
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn gen_trait_object_call() {
+    gen(
+        "
+            trait Foo { fun foo(a: Int) -> Int; fun bar(); }
+            fun f(x: Foo) -> Int { x.bar(); return x.foo(1); }
+            ",
+        |vm, code| {
+            let foo_id = vm
+                .trait_method_def_by_name("Foo", "foo")
+                .expect("foo not found");
+            let bar_id = vm
+                .trait_method_def_by_name("Foo", "bar")
+                .expect("bar not found");
+            let expected = vec![
+                PushRegister(r(0)),
+                InvokeTraitVoid(bar_id, 1),
+                ConstInt(r(2), 1),
+                PushRegister(r(0)),
+                PushRegister(r(2)),
+                InvokeTraitInt(r(1), foo_id, 2),
+                RetInt(r(1)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
+#[test]
+fn gen_lambda_captures() {
+    gen(
+        "fun f(a: Int) -> () -> Int { || -> Int { a } }",
+        |vm, code| {
+            let cls: ClassDefId = (vm.class_defs.lock().len() - 1).into();
+            let expected = vec![
+                NewObject(r(1), cls),
+                StoreFieldInt(r(0), r(1), cls, 0.into()),
+                RetPtr(r(1)),
+            ];
+            assert_eq!(expected, code);
+        },
+    );
+}
+
 fn p(line: u32, column: u32) -> Position {
     Position { line, column }
 }
@@ -3611,6 +3915,7 @@ pub enum Bytecode {
     LoadFieldFloat(Register, Register, ClassDefId, FieldId),
     LoadFieldDouble(Register, Register, ClassDefId, FieldId),
     LoadFieldPtr(Register, Register, ClassDefId, FieldId),
+    LoadFieldTuple(Register, Register, ClassDefId, FieldId),
 
     StoreFieldBool(Register, Register, ClassDefId, FieldId),
     StoreFieldUInt8(Register, Register, ClassDefId, FieldId),
@@ -3620,6 +3925,7 @@ pub enum Bytecode {
     StoreFieldFloat(Register, Register, ClassDefId, FieldId),
     StoreFieldDouble(Register, Register, ClassDefId, FieldId),
     StoreFieldPtr(Register, Register, ClassDefId, FieldId),
+    StoreFieldTuple(Register, Register, ClassDefId, FieldId),
 
     LoadGlobalBool(Register, GlobalId),
     LoadGlobalUInt8(Register, GlobalId),
@@ -3629,6 +3935,7 @@ pub enum Bytecode {
     LoadGlobalFloat(Register, GlobalId),
     LoadGlobalDouble(Register, GlobalId),
     LoadGlobalPtr(Register, GlobalId),
+    LoadGlobalTuple(Register, GlobalId),
 
     StoreGlobalBool(Register, GlobalId),
     StoreGlobalUInt8(Register, GlobalId),
@@ -3638,6 +3945,7 @@ pub enum Bytecode {
     StoreGlobalFloat(Register, GlobalId),
     StoreGlobalDouble(Register, GlobalId),
     StoreGlobalPtr(Register, GlobalId),
+    StoreGlobalTuple(Register, GlobalId),
 
     PushRegister(Register),
 
@@ -3711,6 +4019,9 @@ pub enum Bytecode {
 
     Assert(Register),
 
+    Throw(Register),
+    LoadException(Register),
+
     JumpLoop(usize),
     Jump(usize),
     JumpIfFalse(Register, usize),
@@ -3725,6 +4036,7 @@ pub enum Bytecode {
     InvokeDirectFloat(Register, FctDefId, u32),
     InvokeDirectDouble(Register, FctDefId, u32),
     InvokeDirectPtr(Register, FctDefId, u32),
+    InvokeDirectTuple(Register, FctDefId, u32),
 
     InvokeVirtualVoid(FctDefId, u32),
     InvokeVirtualBool(Register, FctDefId, u32),
@@ -3735,6 +4047,7 @@ pub enum Bytecode {
     InvokeVirtualFloat(Register, FctDefId, u32),
     InvokeVirtualDouble(Register, FctDefId, u32),
     InvokeVirtualPtr(Register, FctDefId, u32),
+    InvokeVirtualTuple(Register, FctDefId, u32),
 
     InvokeStaticVoid(FctDefId, u32),
     InvokeStaticBool(Register, FctDefId, u32),
@@ -3745,6 +4058,29 @@ pub enum Bytecode {
     InvokeStaticFloat(Register, FctDefId, u32),
     InvokeStaticDouble(Register, FctDefId, u32),
     InvokeStaticPtr(Register, FctDefId, u32),
+    InvokeStaticTuple(Register, FctDefId, u32),
+
+    InvokeLambdaVoid(u32),
+    InvokeLambdaBool(Register, u32),
+    InvokeLambdaUInt8(Register, u32),
+    InvokeLambdaChar(Register, u32),
+    InvokeLambdaInt(Register, u32),
+    InvokeLambdaInt64(Register, u32),
+    InvokeLambdaFloat(Register, u32),
+    InvokeLambdaDouble(Register, u32),
+    InvokeLambdaPtr(Register, u32),
+    InvokeLambdaTuple(Register, u32),
+
+    InvokeTraitVoid(FctDefId, u32),
+    InvokeTraitBool(Register, FctDefId, u32),
+    InvokeTraitUInt8(Register, FctDefId, u32),
+    InvokeTraitChar(Register, FctDefId, u32),
+    InvokeTraitInt(Register, FctDefId, u32),
+    InvokeTraitInt64(Register, FctDefId, u32),
+    InvokeTraitFloat(Register, FctDefId, u32),
+    InvokeTraitDouble(Register, FctDefId, u32),
+    InvokeTraitPtr(Register, FctDefId, u32),
+    InvokeTraitTuple(Register, FctDefId, u32),
 
     NewObject(Register, ClassDefId),
     NewArray(Register, ClassDefId, Register),
@@ -3781,6 +4117,7 @@ pub enum Bytecode {
     RetFloat(Register),
     RetDouble(Register),
     RetPtr(Register),
+    RetTuple(Register),
 }
 
 fn build(bc: &BytecodeFunction) -> Vec<Bytecode> {
@@ -4100,12 +4437,12 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
 
     fn visit_load_tuple_element(
         &mut self,
-        src: Register,
         dest: Register,
+        src: Register,
         tuple_id: TupleId,
         element: u32,
     ) {
-        self.emit(Bytecode::LoadTupleElement(src, dest, tuple_id, element));
+        self.emit(Bytecode::LoadTupleElement(dest, src, tuple_id, element));
     }
 
     fn visit_load_field_bool(
@@ -4180,6 +4517,15 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     ) {
         self.emit(Bytecode::LoadFieldPtr(dest, obj, cls, field));
     }
+    fn visit_load_field_tuple(
+        &mut self,
+        dest: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit(Bytecode::LoadFieldTuple(dest, obj, cls, field));
+    }
 
     fn visit_store_field_bool(
         &mut self,
@@ -4253,6 +4599,15 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     ) {
         self.emit(Bytecode::StoreFieldPtr(src, obj, cls, field));
     }
+    fn visit_store_field_tuple(
+        &mut self,
+        src: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit(Bytecode::StoreFieldTuple(src, obj, cls, field));
+    }
 
     fn visit_load_global_bool(&mut self, dest: Register, glob: GlobalId) {
         self.emit(Bytecode::LoadGlobalBool(dest, glob));
@@ -4278,6 +4633,9 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     fn visit_load_global_ptr(&mut self, dest: Register, glob: GlobalId) {
         self.emit(Bytecode::LoadGlobalPtr(dest, glob));
     }
+    fn visit_load_global_tuple(&mut self, dest: Register, glob: GlobalId) {
+        self.emit(Bytecode::LoadGlobalTuple(dest, glob));
+    }
 
     fn visit_store_global_bool(&mut self, src: Register, glob: GlobalId) {
         self.emit(Bytecode::StoreGlobalBool(src, glob));
@@ -4303,6 +4661,9 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     fn visit_store_global_ptr(&mut self, src: Register, glob: GlobalId) {
         self.emit(Bytecode::StoreGlobalPtr(src, glob));
     }
+    fn visit_store_global_tuple(&mut self, src: Register, glob: GlobalId) {
+        self.emit(Bytecode::StoreGlobalTuple(src, glob));
+    }
 
     fn visit_push_register(&mut self, src: Register) {
         self.emit(Bytecode::PushRegister(src));
@@ -4511,6 +4872,14 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
         self.emit(Bytecode::Assert(value));
     }
 
+    fn visit_throw(&mut self, exception: Register) {
+        self.emit(Bytecode::Throw(exception));
+    }
+
+    fn visit_load_exception(&mut self, dest: Register) {
+        self.emit(Bytecode::LoadException(dest));
+    }
+
     fn visit_jump_if_false(&mut self, opnd: Register, offset: u32) {
         let offset = BytecodeOffset(self.pc.to_u32() + offset);
         self.jumps.push((self.next_idx - 1, offset));
@@ -4571,6 +4940,9 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     fn visit_invoke_direct_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit(Bytecode::InvokeDirectPtr(dest, fctdef, count));
     }
+    fn visit_invoke_direct_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeDirectTuple(dest, fctdef, count));
+    }
 
     fn visit_invoke_virtual_void(&mut self, fctdef: FctDefId, count: u32) {
         self.emit(Bytecode::InvokeVirtualVoid(fctdef, count));
@@ -4599,6 +4971,9 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     fn visit_invoke_virtual_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit(Bytecode::InvokeVirtualPtr(dest, fctdef, count));
     }
+    fn visit_invoke_virtual_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeVirtualTuple(dest, fctdef, count));
+    }
 
     fn visit_invoke_static_void(&mut self, fctdef: FctDefId, count: u32) {
         self.emit(Bytecode::InvokeStaticVoid(fctdef, count));
@@ -4627,6 +5002,71 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     fn visit_invoke_static_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit(Bytecode::InvokeStaticPtr(dest, fctdef, count));
     }
+    fn visit_invoke_static_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeStaticTuple(dest, fctdef, count));
+    }
+
+    fn visit_invoke_lambda_void(&mut self, count: u32) {
+        self.emit(Bytecode::InvokeLambdaVoid(count));
+    }
+    fn visit_invoke_lambda_bool(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaBool(dest, count));
+    }
+    fn visit_invoke_lambda_uint8(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaUInt8(dest, count));
+    }
+    fn visit_invoke_lambda_char(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaChar(dest, count));
+    }
+    fn visit_invoke_lambda_int(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaInt(dest, count));
+    }
+    fn visit_invoke_lambda_int64(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaInt64(dest, count));
+    }
+    fn visit_invoke_lambda_float(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaFloat(dest, count));
+    }
+    fn visit_invoke_lambda_double(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaDouble(dest, count));
+    }
+    fn visit_invoke_lambda_ptr(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaPtr(dest, count));
+    }
+    fn visit_invoke_lambda_tuple(&mut self, dest: Register, count: u32) {
+        self.emit(Bytecode::InvokeLambdaTuple(dest, count));
+    }
+
+    fn visit_invoke_trait_void(&mut self, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitVoid(fctdef, count));
+    }
+    fn visit_invoke_trait_bool(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitBool(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_uint8(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitUInt8(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_char(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitChar(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_int(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitInt(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_int64(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitInt64(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_float(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitFloat(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_double(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitDouble(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitPtr(dest, fctdef, count));
+    }
+    fn visit_invoke_trait_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit(Bytecode::InvokeTraitTuple(dest, fctdef, count));
+    }
 
     fn visit_new_object(&mut self, dest: Register, cls: ClassDefId) {
         self.emit(Bytecode::NewObject(dest, cls));
@@ -4723,4 +5163,7 @@ impl<'a> BytecodeVisitor for BytecodeArrayBuilder<'a> {
     fn visit_ret_ptr(&mut self, opnd: Register) {
         self.emit(Bytecode::RetPtr(opnd));
     }
+    fn visit_ret_tuple(&mut self, opnd: Register) {
+        self.emit(Bytecode::RetTuple(opnd));
+    }
 }
@@ -547,6 +547,13 @@ where
                 let field = self.read_field(wide);
                 self.visitor.visit_load_field_ptr(dest, obj, cls, field);
             }
+            BytecodeOpcode::LoadFieldTuple => {
+                let dest = self.read_register(wide);
+                let obj = self.read_register(wide);
+                let cls = self.read_class(wide);
+                let field = self.read_field(wide);
+                self.visitor.visit_load_field_tuple(dest, obj, cls, field);
+            }
 
             BytecodeOpcode::StoreFieldBool => {
                 let src = self.read_register(wide);
@@ -604,6 +611,13 @@ where
                 let field = self.read_field(wide);
                 self.visitor.visit_store_field_ptr(src, obj, cls, field);
             }
+            BytecodeOpcode::StoreFieldTuple => {
+                let src = self.read_register(wide);
+                let obj = self.read_register(wide);
+                let cls = self.read_class(wide);
+                let field = self.read_field(wide);
+                self.visitor.visit_store_field_tuple(src, obj, cls, field);
+            }
 
             BytecodeOpcode::LoadGlobalBool => {
                 let dest = self.read_register(wide);
@@ -645,6 +659,11 @@ where
                 let glob = self.read_global(wide);
                 self.visitor.visit_load_global_ptr(dest, glob);
             }
+            BytecodeOpcode::LoadGlobalTuple => {
+                let dest = self.read_register(wide);
+                let glob = self.read_global(wide);
+                self.visitor.visit_load_global_tuple(dest, glob);
+            }
 
             BytecodeOpcode::StoreGlobalBool => {
                 let dest = self.read_register(wide);
@@ -686,6 +705,11 @@ where
                 let glob = self.read_global(wide);
                 self.visitor.visit_store_global_ptr(dest, glob);
             }
+            BytecodeOpcode::StoreGlobalTuple => {
+                let dest = self.read_register(wide);
+                let glob = self.read_global(wide);
+                self.visitor.visit_store_global_tuple(dest, glob);
+            }
 
             BytecodeOpcode::PushRegister => {
                 let src = self.read_register(wide);
@@ -1023,6 +1047,16 @@ where
                 self.visitor.visit_assert(value);
             }
 
+            BytecodeOpcode::Throw => {
+                let exception = self.read_register(wide);
+                self.visitor.visit_throw(exception);
+            }
+
+            BytecodeOpcode::LoadException => {
+                let dest = self.read_register(wide);
+                self.visitor.visit_load_exception(dest);
+            }
+
             BytecodeOpcode::JumpLoop => {
                 let offset = self.read_offset(wide);
                 self.visitor.visit_jump_loop(offset);
@@ -1109,6 +1143,12 @@ where
                 let count = self.read_index(wide);
                 self.visitor.visit_invoke_direct_ptr(dest, fct, count);
             }
+            BytecodeOpcode::InvokeDirectTuple => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_direct_tuple(dest, fct, count);
+            }
 
             BytecodeOpcode::InvokeVirtualVoid => {
                 let fct = self.read_fct(wide);
@@ -1163,6 +1203,12 @@ where
                 let count = self.read_index(wide);
                 self.visitor.visit_invoke_virtual_ptr(dest, fct, count);
             }
+            BytecodeOpcode::InvokeVirtualTuple => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_virtual_tuple(dest, fct, count);
+            }
 
             BytecodeOpcode::InvokeStaticVoid => {
                 let fct = self.read_fct(wide);
@@ -1217,6 +1263,122 @@ where
                 let count = self.read_index(wide);
                 self.visitor.visit_invoke_static_ptr(dest, fct, count);
             }
+            BytecodeOpcode::InvokeStaticTuple => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_static_tuple(dest, fct, count);
+            }
+
+            BytecodeOpcode::InvokeLambdaVoid => {
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_void(count);
+            }
+            BytecodeOpcode::InvokeLambdaBool => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_bool(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaUInt8 => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_uint8(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaChar => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_char(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaInt => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_int(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaInt64 => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_int64(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaFloat => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_float(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaDouble => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_double(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaPtr => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_ptr(dest, count);
+            }
+            BytecodeOpcode::InvokeLambdaTuple => {
+                let dest = self.read_register(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_lambda_tuple(dest, count);
+            }
+
+            BytecodeOpcode::InvokeTraitVoid => {
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_void(fct, count);
+            }
+            BytecodeOpcode::InvokeTraitBool => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_bool(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitUInt8 => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_uint8(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitChar => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_char(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitInt => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_int(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitInt64 => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_int64(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitFloat => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_float(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitDouble => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_double(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitPtr => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_ptr(dest, fct, count);
+            }
+            BytecodeOpcode::InvokeTraitTuple => {
+                let dest = self.read_register(wide);
+                let fct = self.read_fct(wide);
+                let count = self.read_index(wide);
+                self.visitor.visit_invoke_trait_tuple(dest, fct, count);
+            }
 
             BytecodeOpcode::NewObject => {
                 let dest = self.read_register(wide);
@@ -1379,6 +1541,10 @@ where
                 let opnd = self.read_register(wide);
                 self.visitor.visit_ret_ptr(opnd);
             }
+            BytecodeOpcode::RetTuple => {
+                let opnd = self.read_register(wide);
+                self.visitor.visit_ret_tuple(opnd);
+            }
         }
     }
 
@@ -1795,6 +1961,15 @@ pub trait BytecodeVisitor {
     ) {
         unimplemented!();
     }
+    fn visit_load_field_tuple(
+        &mut self,
+        _dest: Register,
+        _obj: Register,
+        _cls: ClassDefId,
+        _field: FieldId,
+    ) {
+        unimplemented!();
+    }
 
     fn visit_store_field_bool(
         &mut self,
@@ -1868,6 +2043,15 @@ pub trait BytecodeVisitor {
     ) {
         unimplemented!();
     }
+    fn visit_store_field_tuple(
+        &mut self,
+        _src: Register,
+        _obj: Register,
+        _cls: ClassDefId,
+        _field: FieldId,
+    ) {
+        unimplemented!();
+    }
 
     fn visit_load_global_bool(&mut self, _dest: Register, _glob: GlobalId) {
         unimplemented!();
@@ -1893,6 +2077,9 @@ pub trait BytecodeVisitor {
     fn visit_load_global_ptr(&mut self, _dest: Register, _glob: GlobalId) {
         unimplemented!();
     }
+    fn visit_load_global_tuple(&mut self, _dest: Register, _glob: GlobalId) {
+        unimplemented!();
+    }
 
     fn visit_store_global_bool(&mut self, _src: Register, _glob: GlobalId) {
         unimplemented!();
@@ -1918,6 +2105,9 @@ pub trait BytecodeVisitor {
     fn visit_store_global_ptr(&mut self, _src: Register, _glob: GlobalId) {
         unimplemented!();
     }
+    fn visit_store_global_tuple(&mut self, _src: Register, _glob: GlobalId) {
+        unimplemented!();
+    }
 
     fn visit_push_register(&mut self, _src: Register) {
         unimplemented!();
@@ -2105,6 +2295,14 @@ pub trait BytecodeVisitor {
         unimplemented!();
     }
 
+    fn visit_throw(&mut self, _exception: Register) {
+        unimplemented!();
+    }
+
+    fn visit_load_exception(&mut self, _dest: Register) {
+        unimplemented!();
+    }
+
     fn visit_jump_if_false(&mut self, _opnd: Register, _offset: u32) {
         unimplemented!();
     }
@@ -2154,6 +2352,9 @@ pub trait BytecodeVisitor {
     fn visit_invoke_direct_ptr(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
         unimplemented!();
     }
+    fn visit_invoke_direct_tuple(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
 
     fn visit_invoke_virtual_void(&mut self, _fctdef: FctDefId, _count: u32) {
         unimplemented!();
@@ -2182,6 +2383,9 @@ pub trait BytecodeVisitor {
     fn visit_invoke_virtual_ptr(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
         unimplemented!();
     }
+    fn visit_invoke_virtual_tuple(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
 
     fn visit_invoke_static_void(&mut self, _fctdef: FctDefId, _count: u32) {
         unimplemented!();
@@ -2210,6 +2414,71 @@ pub trait BytecodeVisitor {
     fn visit_invoke_static_ptr(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
         unimplemented!();
     }
+    fn visit_invoke_static_tuple(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+
+    fn visit_invoke_lambda_void(&mut self, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_bool(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_uint8(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_char(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_int(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_int64(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_float(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_double(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_ptr(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_lambda_tuple(&mut self, _dest: Register, _count: u32) {
+        unimplemented!();
+    }
+
+    fn visit_invoke_trait_void(&mut self, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_bool(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_uint8(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_char(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_int(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_int64(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_float(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_double(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_ptr(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
+    fn visit_invoke_trait_tuple(&mut self, _dest: Register, _fctdef: FctDefId, _count: u32) {
+        unimplemented!();
+    }
 
     fn visit_new_object(&mut self, _dest: Register, _cls: ClassDefId) {
         unimplemented!();
@@ -2306,4 +2575,7 @@ pub trait BytecodeVisitor {
     fn visit_ret_ptr(&mut self, _opnd: Register) {
         unimplemented!();
     }
+    fn visit_ret_tuple(&mut self, _opnd: Register) {
+        unimplemented!();
+    }
 }
@@ -4,8 +4,9 @@ use std::mem;
 
 use crate::bytecode::{
     BytecodeFunction, BytecodeOffset, BytecodeOpcode, BytecodeType, ConstPoolEntry, ConstPoolIdx,
-    Register,
+    ExceptionHandler, Register,
 };
+use crate::compiler::fct::CatchType;
 use crate::vm::{ClassDefId, FctDefId, FieldId, GlobalId, TupleId};
 
 use dora_parser::lexer::position::Position;
@@ -26,6 +27,8 @@ pub struct BytecodeWriter {
 
     positions: Vec<(u32, Position)>,
     position: Option<Position>,
+
+    exception_handlers: Vec<ExceptionHandler>,
 }
 
 impl BytecodeWriter {
@@ -43,6 +46,8 @@ impl BytecodeWriter {
 
             positions: Vec::new(),
             position: None,
+
+            exception_handlers: Vec::new(),
         }
     }
 
@@ -84,10 +89,24 @@ impl BytecodeWriter {
         self.label_offsets[lbl.0]
     }
 
-    fn offset(&self) -> BytecodeOffset {
+    pub fn offset(&self) -> BytecodeOffset {
         BytecodeOffset(self.code.len() as u32)
     }
 
+    pub fn add_exception_handler(
+        &mut self,
+        range: (BytecodeOffset, BytecodeOffset),
+        catch: BytecodeOffset,
+        catch_type: CatchType,
+    ) {
+        self.exception_handlers.push(ExceptionHandler {
+            try_start: range.0,
+            try_end: range.1,
+            catch,
+            catch_type,
+        });
+    }
+
     pub fn set_arguments(&mut self, arguments: u32) {
         self.arguments = arguments;
     }
@@ -228,6 +247,16 @@ impl BytecodeWriter {
         self.emit_access_field(BytecodeOpcode::LoadFieldPtr, dest, obj, cls, field);
     }
 
+    pub fn emit_load_field_tuple(
+        &mut self,
+        dest: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit_access_field(BytecodeOpcode::LoadFieldTuple, dest, obj, cls, field);
+    }
+
     pub fn emit_store_field_bool(
         &mut self,
         src: Register,
@@ -308,6 +337,16 @@ impl BytecodeWriter {
         self.emit_access_field(BytecodeOpcode::StoreFieldPtr, src, obj, cls, field);
     }
 
+    pub fn emit_store_field_tuple(
+        &mut self,
+        src: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit_access_field(BytecodeOpcode::StoreFieldTuple, src, obj, cls, field);
+    }
+
     pub fn emit_const_nil(&mut self, dest: Register) {
         self.emit_reg1(BytecodeOpcode::ConstNil, dest);
     }
@@ -637,7 +676,7 @@ impl BytecodeWriter {
     }
 
     pub fn emit_mov_tuple(&mut self, dest: Register, src: Register, tuple_id: TupleId) {
-        self.emit_reg2_tuple(BytecodeOpcode::MovPtr, dest, src, tuple_id);
+        self.emit_reg2_tuple(BytecodeOpcode::MovTuple, dest, src, tuple_id);
     }
 
     pub fn emit_load_tuple_element(
@@ -664,9 +703,9 @@ impl BytecodeWriter {
         element: u32,
     ) {
         self.emit_access_tuple(
-            BytecodeOpcode::LoadTupleElement,
-            dest,
+            BytecodeOpcode::StoreTupleElement,
             src,
+            dest,
             tuple_id,
             element,
         );
@@ -704,6 +743,10 @@ impl BytecodeWriter {
         self.emit_reg1(BytecodeOpcode::RetPtr, src);
     }
 
+    pub fn emit_ret_tuple(&mut self, src: Register) {
+        self.emit_reg1(BytecodeOpcode::RetTuple, src);
+    }
+
     pub fn emit_ret_void(&mut self) {
         self.emit_op(BytecodeOpcode::RetVoid);
     }
@@ -880,6 +923,14 @@ impl BytecodeWriter {
         self.emit_reg1(BytecodeOpcode::Assert, value);
     }
 
+    pub fn emit_throw(&mut self, exception: Register) {
+        self.emit_reg1(BytecodeOpcode::Throw, exception);
+    }
+
+    pub fn emit_load_exception(&mut self, dest: Register) {
+        self.emit_reg1(BytecodeOpcode::LoadException, dest);
+    }
+
     pub fn emit_load_global_bool(&mut self, dest: Register, gid: GlobalId) {
         self.emit_load_global(BytecodeOpcode::LoadGlobalBool, dest, gid);
     }
@@ -912,6 +963,10 @@ impl BytecodeWriter {
         self.emit_load_global(BytecodeOpcode::LoadGlobalPtr, dest, gid);
     }
 
+    pub fn emit_load_global_tuple(&mut self, dest: Register, gid: GlobalId) {
+        self.emit_load_global(BytecodeOpcode::LoadGlobalTuple, dest, gid);
+    }
+
     pub fn emit_store_global_bool(&mut self, src: Register, gid: GlobalId) {
         self.emit_store_global(BytecodeOpcode::StoreGlobalBool, src, gid);
     }
@@ -944,6 +999,10 @@ impl BytecodeWriter {
         self.emit_store_global(BytecodeOpcode::StoreGlobalPtr, src, gid);
     }
 
+    pub fn emit_store_global_tuple(&mut self, src: Register, gid: GlobalId) {
+        self.emit_store_global(BytecodeOpcode::StoreGlobalTuple, src, gid);
+    }
+
     pub fn emit_push_register(&mut self, src: Register) {
         self.emit_reg1(BytecodeOpcode::PushRegister, src);
     }
@@ -984,6 +1043,10 @@ impl BytecodeWriter {
         self.emit_fct(BytecodeOpcode::InvokeDirectPtr, dest, fid, num);
     }
 
+    pub fn emit_invoke_direct_tuple(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeDirectTuple, dest, fid, num);
+    }
+
     pub fn emit_invoke_virtual_void(&mut self, fid: FctDefId, num: usize) {
         self.emit_fct_void(BytecodeOpcode::InvokeVirtualVoid, fid, num);
     }
@@ -1020,6 +1083,10 @@ impl BytecodeWriter {
         self.emit_fct(BytecodeOpcode::InvokeVirtualPtr, dest, fid, num);
     }
 
+    pub fn emit_invoke_virtual_tuple(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeVirtualTuple, dest, fid, num);
+    }
+
     pub fn emit_invoke_static_void(&mut self, fid: FctDefId, num: usize) {
         self.emit_fct_void(BytecodeOpcode::InvokeStaticVoid, fid, num);
     }
@@ -1056,6 +1123,90 @@ impl BytecodeWriter {
         self.emit_fct(BytecodeOpcode::InvokeStaticPtr, dest, fid, num);
     }
 
+    pub fn emit_invoke_static_tuple(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeStaticTuple, dest, fid, num);
+    }
+
+    pub fn emit_invoke_lambda_void(&mut self, num: usize) {
+        self.emit_lambda_void(BytecodeOpcode::InvokeLambdaVoid, num);
+    }
+
+    pub fn emit_invoke_lambda_bool(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaBool, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_uint8(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaUInt8, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_char(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaChar, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_int(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaInt, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_int64(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaInt64, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_float(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaFloat, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_double(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaDouble, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_ptr(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaPtr, dest, num);
+    }
+
+    pub fn emit_invoke_lambda_tuple(&mut self, dest: Register, num: usize) {
+        self.emit_lambda(BytecodeOpcode::InvokeLambdaTuple, dest, num);
+    }
+
+    pub fn emit_invoke_trait_void(&mut self, fid: FctDefId, num: usize) {
+        self.emit_fct_void(BytecodeOpcode::InvokeTraitVoid, fid, num);
+    }
+
+    pub fn emit_invoke_trait_bool(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitBool, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_uint8(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitUInt8, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_char(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitChar, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_int(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitInt, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_int64(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitInt64, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_float(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitFloat, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_double(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitDouble, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_ptr(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitPtr, dest, fid, num);
+    }
+
+    pub fn emit_invoke_trait_tuple(&mut self, dest: Register, fid: FctDefId, num: usize) {
+        self.emit_fct(BytecodeOpcode::InvokeTraitTuple, dest, fid, num);
+    }
+
     pub fn emit_new_object(&mut self, dest: Register, cls_id: ClassDefId) {
         self.emit_new(BytecodeOpcode::NewObject, dest, cls_id);
     }
@@ -1133,6 +1284,7 @@ impl BytecodeWriter {
             self.registers,
             self.arguments,
             self.positions,
+            self.exception_handlers,
         )
     }
 
@@ -1271,6 +1423,16 @@ impl BytecodeWriter {
         self.emit_values(inst, &values);
     }
 
+    fn emit_lambda_void(&mut self, inst: BytecodeOpcode, cnt: usize) {
+        let values = [cnt as u32];
+        self.emit_values(inst, &values);
+    }
+
+    fn emit_lambda(&mut self, inst: BytecodeOpcode, r1: Register, cnt: usize) {
+        let values = [r1.to_usize() as u32, cnt as u32];
+        self.emit_values(inst, &values);
+    }
+
     fn emit_access_field(
         &mut self,
         inst: BytecodeOpcode,
@@ -1,5 +1,6 @@
 use dora_parser::ast::*;
 use std::collections::hash_map::HashMap;
+use std::collections::HashSet;
 
 use crate::bytecode::{
     self, BytecodeFunction, BytecodeOffset, BytecodeType, BytecodeVisitor, ConstPoolIdx, Register,
@@ -20,8 +21,8 @@ use crate::semck::specialize::specialize_type;
 use crate::size::InstanceSize;
 use crate::ty::{BuiltinType, MachineMode, TypeList};
 use crate::vm::{
-    ClassDefId, Fct, FctDef, FctDefId, FctId, FctKind, FctSrc, FieldId, GlobalId, Intrinsic, Trap,
-    VM,
+    ClassDef, ClassDefId, Fct, FctDef, FctDefId, FctId, FctKind, FctSrc, FieldId, GlobalId,
+    Intrinsic, Trap, TupleId, VM,
 };
 use crate::vtable::{VTable, DISPLAY_SIZE};
 
@@ -68,10 +69,21 @@ pub struct CannonCodeGen<'a, 'ast: 'a> {
 
     offset_to_address: HashMap<BytecodeOffset, usize>,
 
+    // start and end offsets of try ranges, pending slow paths and bailouts
+    // are emitted in front of these instructions
+    try_boundaries: HashSet<BytecodeOffset>,
+
     forward_jumps: Vec<ForwardJump>,
     current_offset: BytecodeOffset,
     argument_stack: Vec<Register>,
 
+    // stack slot of each register and the size of the whole frame
+    offset: Vec<i32>,
+    stacksize: i32,
+
+    // slot for the address a struct return value is copied to
+    return_address_offset: Option<i32>,
+
     references: Vec<i32>,
 }
 
@@ -111,9 +123,13 @@ where
             cls_type_params,
             fct_type_params,
             offset_to_address: HashMap::new(),
+            try_boundaries: HashSet::new(),
             forward_jumps: Vec::new(),
             current_offset: BytecodeOffset(0),
             argument_stack: Vec::new(),
+            offset: Vec::new(),
+            stacksize: 0,
+            return_address_offset: None,
             references: Vec::new(),
         }
     }
@@ -123,28 +139,102 @@ where
             self.asm.debug();
         }
 
+        self.determine_offsets();
+
         self.emit_prolog();
         self.store_params_on_stack();
+        self.clear_ptr_registers();
         self.emit_stack_guard();
 
+        for handler in self.bytecode.exception_handlers() {
+            self.try_boundaries.insert(handler.try_start);
+            self.try_boundaries.insert(handler.try_end);
+        }
+
         bytecode::read(self.bytecode.code(), &mut self);
 
         self.resolve_forward_jumps();
+        self.emit_exception_handlers();
 
-        let jit_fct = self.asm.jit(
-            self.bytecode.stacksize(),
-            JitDescriptor::DoraFct(self.fct.id),
-        );
+        let jit_fct = self
+            .asm
+            .jit(self.stacksize, JitDescriptor::DoraFct(self.fct.id));
 
         jit_fct
     }
 
+    fn determine_offsets(&mut self) {
+        let mut stacksize: i32 = 0;
+
+        for &ty in self.bytecode.registers() {
+            let (size, align) = match ty {
+                BytecodeType::Tuple(tuple_id) => {
+                    let tuples = self.vm.tuples.lock();
+                    let tuple = tuples.get_tuple(tuple_id);
+                    (tuple.size(), tuple.align())
+                }
+                _ => (ty.mode().size(), ty.mode().size()),
+            };
+
+            stacksize = mem::align_i32(stacksize + size, align);
+            self.offset.push(-stacksize);
+        }
+
+        if self.return_tuple_id().is_some() {
+            stacksize = mem::align_i32(stacksize + mem::ptr_width(), mem::ptr_width());
+            self.return_address_offset = Some(-stacksize);
+        }
+
+        self.stacksize = mem::align_i32(stacksize, STACK_FRAME_ALIGNMENT as i32);
+
+        // every register holding references is visible to the GC for the
+        // whole function, see clear_ptr_registers
+        for (idx, &ty) in self.bytecode.registers().iter().enumerate() {
+            let offset = self.offset[idx];
+
+            match ty {
+                BytecodeType::Ptr => self.references.push(offset),
+                BytecodeType::Tuple(tuple_id) => {
+                    let tuples = self.vm.tuples.lock();
+                    let tuple = tuples.get_tuple(tuple_id);
+
+                    for &ref_offset in tuple.references() {
+                        self.references.push(offset + ref_offset);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn return_tuple_id(&self) -> Option<TupleId> {
+        let return_type = self.specialize_type(self.fct.return_type);
+
+        if return_type.is_unit() {
+            None
+        } else {
+            BytecodeType::from_ty(self.vm, return_type).tuple_id()
+        }
+    }
+
     fn store_params_on_stack(&mut self) {
+        if self.fct.is_module_constructor() {
+            // the module object is allocated by the constructor itself
+            return;
+        }
+
         let mut reg_idx = 0;
         let mut freg_idx = 0;
         let mut sp_offset = 16;
         let mut idx = 0;
 
+        if let Some(offset) = self.return_address_offset {
+            // address of the struct return value is passed before self
+            self.asm
+                .store_mem(MachineMode::Ptr, Mem::Local(offset), REG_PARAMS[0].into());
+            reg_idx += 1;
+        }
+
         for &param_ty in self.fct.params_with_self() {
             let param_ty = self.specialize_type(param_ty);
 
@@ -153,9 +243,36 @@ where
             }
 
             let dest = Register(idx);
-            assert_eq!(self.bytecode.register_type(dest), param_ty.into());
+            let bytecode_type = BytecodeType::from_ty(self.vm, param_ty);
+            assert_eq!(self.bytecode.register_type(dest), bytecode_type);
+
+            if let BytecodeType::Tuple(tuple_id) = bytecode_type {
+                // structs are passed as pointer to a copy owned by the caller
+                if reg_idx < REG_PARAMS.len() {
+                    self.asm.copy(
+                        MachineMode::Ptr,
+                        REG_TMP1.into(),
+                        REG_PARAMS[reg_idx].into(),
+                    );
+                    reg_idx += 1;
+                } else {
+                    self.asm
+                        .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(sp_offset));
+                    sp_offset += 8;
+                }
+
+                let offset = self.determine_register_offset(dest);
+                self.copy_tuple(
+                    tuple_id,
+                    RegOrOffset::Offset(offset),
+                    RegOrOffset::Reg(REG_TMP1),
+                );
+
+                idx += 1;
+                continue;
+            }
 
-            let mode = param_ty.mode();
+            let mode = param_ty.mode(self.vm);
 
             let register = if mode.is_float() {
                 if freg_idx < FREG_PARAMS.len() {
@@ -195,8 +312,49 @@ where
         }
     }
 
+    // All references in registers are part of every GC point, registers
+    // that were not assigned yet must not contain garbage.
+    fn clear_ptr_registers(&mut self) {
+        // the module object is only stored after its allocation
+        let arguments = if self.fct.is_module_constructor() {
+            0
+        } else {
+            self.bytecode.arguments() as usize
+        };
+        self.asm.load_int_const(MachineMode::Ptr, REG_TMP1, 0);
+
+        for (idx, &ty) in self.bytecode.registers().iter().enumerate().skip(arguments) {
+            let offset = self.determine_register_offset(Register(idx));
+
+            match ty {
+                BytecodeType::Ptr => {
+                    self.asm
+                        .store_mem(MachineMode::Ptr, Mem::Local(offset), REG_TMP1.into());
+                }
+                BytecodeType::Tuple(tuple_id) => {
+                    let references = self
+                        .vm
+                        .tuples
+                        .lock()
+                        .get_tuple(tuple_id)
+                        .references()
+                        .to_vec();
+
+                    for ref_offset in references {
+                        self.asm.store_mem(
+                            MachineMode::Ptr,
+                            Mem::Local(offset + ref_offset),
+                            REG_TMP1.into(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     fn emit_prolog(&mut self) {
-        self.asm.prolog_size(self.bytecode.stacksize());
+        self.asm.prolog_size(self.stacksize);
     }
 
     fn emit_stack_guard(&mut self) {
@@ -224,7 +382,7 @@ where
     }
 
     fn determine_register_offset(&mut self, reg: Register) -> i32 {
-        self.bytecode.register_offset(reg)
+        self.offset[reg.0]
     }
 
     fn emit_add_int(&mut self, dest: Register, lhs: Register, rhs: Register) {
@@ -918,6 +1076,120 @@ where
         self.emit_store_register(reg.into(), dest);
     }
 
+    // copies a tuple value element by element, uses REG_TMP2 and FREG_TMP1
+    fn copy_tuple(&mut self, tuple_id: TupleId, dest: RegOrOffset, src: RegOrOffset) {
+        let subtypes = self.vm.tuples.lock().get(tuple_id);
+        let offsets = self
+            .vm
+            .tuples
+            .lock()
+            .get_tuple(tuple_id)
+            .offsets()
+            .to_owned();
+
+        for (&subtype, &subtype_offset) in subtypes.iter().zip(&offsets) {
+            if let Some(tuple_id) = subtype.layout_tuple_id(self.vm) {
+                self.copy_tuple(
+                    tuple_id,
+                    dest.offset(subtype_offset),
+                    src.offset(subtype_offset),
+                );
+            } else if !subtype.is_unit() {
+                let mode = subtype.mode(self.vm);
+                let tmp: AnyReg = if mode.is_float() {
+                    FREG_TMP1.into()
+                } else {
+                    REG_TMP2.into()
+                };
+
+                self.asm
+                    .load_mem(mode, tmp, src.offset(subtype_offset).mem());
+                self.asm
+                    .store_mem(mode, dest.offset(subtype_offset).mem(), tmp);
+            }
+        }
+    }
+
+    fn emit_mov_tuple(&mut self, dest: Register, src: Register, tuple_id: TupleId) {
+        assert_eq!(
+            self.bytecode.register_type(src),
+            BytecodeType::Tuple(tuple_id)
+        );
+        assert_eq!(
+            self.bytecode.register_type(dest),
+            BytecodeType::Tuple(tuple_id)
+        );
+
+        let dest_offset = self.determine_register_offset(dest);
+        let src_offset = self.determine_register_offset(src);
+
+        self.copy_tuple(
+            tuple_id,
+            RegOrOffset::Offset(dest_offset),
+            RegOrOffset::Offset(src_offset),
+        );
+    }
+
+    fn emit_load_tuple_element(
+        &mut self,
+        dest: Register,
+        src: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        assert_eq!(
+            self.bytecode.register_type(src),
+            BytecodeType::Tuple(tuple_id)
+        );
+
+        let (_, element_offset) = self.vm.tuples.lock().get_at(tuple_id, element as usize);
+        let offset = self.determine_register_offset(src) + element_offset;
+        let dest_type = self.bytecode.register_type(dest);
+
+        if let BytecodeType::Tuple(element_tuple_id) = dest_type {
+            let dest_offset = self.determine_register_offset(dest);
+            self.copy_tuple(
+                element_tuple_id,
+                RegOrOffset::Offset(dest_offset),
+                RegOrOffset::Offset(offset),
+            );
+        } else {
+            let reg = result_reg(dest_type);
+            self.asm.load_mem(dest_type.mode(), reg, Mem::Local(offset));
+            self.emit_store_register(reg, dest);
+        }
+    }
+
+    fn emit_store_tuple_element(
+        &mut self,
+        src: Register,
+        dest: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        assert_eq!(
+            self.bytecode.register_type(dest),
+            BytecodeType::Tuple(tuple_id)
+        );
+
+        let (_, element_offset) = self.vm.tuples.lock().get_at(tuple_id, element as usize);
+        let offset = self.determine_register_offset(dest) + element_offset;
+        let src_type = self.bytecode.register_type(src);
+
+        if let BytecodeType::Tuple(element_tuple_id) = src_type {
+            let src_offset = self.determine_register_offset(src);
+            self.copy_tuple(
+                element_tuple_id,
+                RegOrOffset::Offset(offset),
+                RegOrOffset::Offset(src_offset),
+            );
+        } else {
+            let reg = result_reg(src_type);
+            self.emit_load_register(src, reg);
+            self.asm.store_mem(src_type.mode(), Mem::Local(offset), reg);
+        }
+    }
+
     fn emit_load_field(
         &mut self,
         dest: Register,
@@ -931,16 +1203,14 @@ where
         let cls = cls.read();
         let field = &cls.fields[field_id.idx()];
 
-        assert_eq!(self.bytecode.register_type(dest), field.ty.into());
+        assert_eq!(
+            self.bytecode.register_type(dest),
+            BytecodeType::from_ty(self.vm, field.ty)
+        );
 
         {
             let cname = cls.name(self.vm);
-
-            let cls_id = cls.cls_id.expect("no corresponding class");
-            let class = self.vm.classes.idx(cls_id);
-            let class = class.read();
-            let field = &class.fields[field_id.idx()];
-            let fname = self.vm.interner.str(field.name);
+            let fname = self.field_name(&*cls, field_id);
 
             self.asm
                 .emit_comment(format!("load field {}.{}", cname, fname));
@@ -952,17 +1222,74 @@ where
         self.emit_load_register(obj, obj_reg.into());
 
         let bytecode_type = self.bytecode.register_type(dest);
+        let pos = self.bytecode.offset_position(self.current_offset.to_u32());
+
+        if let BytecodeType::Tuple(tuple_id) = bytecode_type {
+            self.asm.test_if_nil_bailout(pos, obj_reg, Trap::NIL);
+
+            let dest_offset = self.determine_register_offset(dest);
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Offset(dest_offset),
+                RegOrOffset::RegWithOffset(obj_reg, field.offset),
+            );
+            return;
+        }
 
         let dest_reg = result_reg(bytecode_type);
-        let pos = self.bytecode.offset_position(self.current_offset.to_u32());
 
         self.asm.test_if_nil_bailout(pos, obj_reg, Trap::NIL);
-        self.asm
-            .load_mem(field.ty.mode(), dest_reg, Mem::Base(obj_reg, field.offset));
+        self.asm.load_mem(
+            field.ty.mode(self.vm),
+            dest_reg,
+            Mem::Base(obj_reg, field.offset),
+        );
 
         self.emit_store_register(dest_reg.into(), dest)
     }
 
+    fn field_name(&self, cls: &ClassDef, field_id: FieldId) -> String {
+        if let Some(fct_def_id) = cls.lambda {
+            // fields of closure objects are the captured variables
+            let fct_id = self.vm.fct_defs.idx(fct_def_id).read().fct_id;
+
+            // the src of the function we compile right now is already locked
+            let name = if fct_id == self.fct.id {
+                self.src.captures[field_id.idx()].name
+            } else {
+                let fct = self.vm.fcts.idx(fct_id);
+                let fct = fct.read();
+                let src = fct.src();
+                let src = src.read();
+                src.captures[field_id.idx()].name
+            };
+
+            return self.vm.interner.str(name).to_string();
+        }
+
+        if let Some(module_id) = cls.module {
+            let module = self.vm.modules.idx(module_id);
+            let module = module.read();
+            let field = &module.fields[field_id.idx()];
+            return self.vm.interner.str(field.name).to_string();
+        }
+
+        let cls_id = match cls.cls_id {
+            Some(cls_id) => cls_id,
+            None => {
+                // classes of enum variants: the tag is followed by the payload
+                return match field_id.idx() {
+                    0 => "tag".to_string(),
+                    idx => format!("{}", idx - 1),
+                };
+            }
+        };
+        let class = self.vm.classes.idx(cls_id);
+        let class = class.read();
+        let field = &class.fields[field_id.idx()];
+        self.vm.interner.str(field.name).to_string()
+    }
+
     fn emit_store_field(
         &mut self,
         src: Register,
@@ -976,16 +1303,14 @@ where
         let cls = cls.read();
         let field = &cls.fields[field_id.idx()];
 
-        assert_eq!(self.bytecode.register_type(src), field.ty.into());
+        assert_eq!(
+            self.bytecode.register_type(src),
+            BytecodeType::from_ty(self.vm, field.ty)
+        );
 
         {
             let cname = cls.name(self.vm);
-
-            let cls_id = cls.cls_id.expect("no corresponding class");
-            let class = self.vm.classes.idx(cls_id);
-            let class = class.read();
-            let field = &class.fields[field_id.idx()];
-            let fname = self.vm.interner.str(field.name);
+            let fname = self.field_name(&*cls, field_id);
 
             self.asm
                 .emit_comment(format!("store field {}.{}", cname, fname));
@@ -993,6 +1318,35 @@ where
 
         let bytecode_type = self.bytecode.register_type(src);
 
+        if let BytecodeType::Tuple(tuple_id) = bytecode_type {
+            let obj_reg = REG_TMP1;
+            self.emit_load_register(obj, obj_reg.into());
+
+            let pos = self.bytecode.offset_position(self.current_offset.to_u32());
+            self.asm.test_if_nil_bailout(pos, obj_reg, Trap::NIL);
+
+            let src_offset = self.determine_register_offset(src);
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::RegWithOffset(obj_reg, field.offset),
+                RegOrOffset::Offset(src_offset),
+            );
+
+            let contains_references = self
+                .vm
+                .tuples
+                .lock()
+                .get_tuple(tuple_id)
+                .contains_references();
+
+            if self.vm.gc.needs_write_barrier() && contains_references {
+                let card_table_offset = self.vm.gc.card_table_offset();
+                self.asm.emit_barrier(obj_reg, card_table_offset);
+            }
+
+            return;
+        }
+
         let value = result_reg(bytecode_type);
 
         self.emit_load_register(src, value.into());
@@ -1002,13 +1356,16 @@ where
         let obj_reg = REG_TMP1;
         self.emit_load_register(obj, obj_reg.into());
 
-        let write_barrier = self.vm.gc.needs_write_barrier() && field.ty.reference_type();
+        let write_barrier = self.vm.gc.needs_write_barrier() && field.ty.reference_type(self.vm);
         let card_table_offset = self.vm.gc.card_table_offset();
         let pos = self.bytecode.offset_position(self.current_offset.to_u32());
 
         self.asm.test_if_nil_bailout(pos, obj_reg, Trap::NIL);
-        self.asm
-            .store_mem(field.ty.mode(), Mem::Base(obj_reg, field.offset), value);
+        self.asm.store_mem(
+            field.ty.mode(self.vm),
+            Mem::Base(obj_reg, field.offset),
+            value,
+        );
 
         if write_barrier {
             self.asm.emit_barrier(obj_reg, card_table_offset);
@@ -1019,7 +1376,17 @@ where
         let glob = self.vm.globals.idx(global_id);
         let glob = glob.read();
 
-        assert_eq!(self.bytecode.register_type(dest), glob.ty.into());
+        assert_eq!(
+            self.bytecode.register_type(dest),
+            BytecodeType::from_ty(self.vm, glob.ty)
+        );
+
+        if glob.needs_initialization() {
+            let fid = glob.initializer.unwrap();
+            let ptr = self.ptr_for_fct_id(fid, TypeList::empty(), TypeList::empty());
+            let gcpoint = GcPoint::from_offsets(self.references.clone());
+            self.asm.ensure_global(&*glob, fid, ptr, glob.pos, gcpoint);
+        }
 
         let disp = self.asm.add_addr(glob.address_value.to_ptr());
         let pos = self.asm.pos() as i32;
@@ -1030,10 +1397,20 @@ where
 
         let bytecode_type = self.bytecode.register_type(dest);
 
+        if let BytecodeType::Tuple(tuple_id) = bytecode_type {
+            let dest_offset = self.determine_register_offset(dest);
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Offset(dest_offset),
+                RegOrOffset::Reg(REG_TMP1),
+            );
+            return;
+        }
+
         let reg = result_reg(bytecode_type);
 
         self.asm
-            .load_mem(glob.ty.mode(), reg, Mem::Base(REG_TMP1, 0));
+            .load_mem(glob.ty.mode(self.vm), reg, Mem::Base(REG_TMP1, 0));
 
         self.emit_store_register(reg, dest);
     }
@@ -1042,7 +1419,10 @@ where
         let glob = self.vm.globals.idx(global_id);
         let glob = glob.read();
 
-        assert_eq!(self.bytecode.register_type(src), glob.ty.into());
+        assert_eq!(
+            self.bytecode.register_type(src),
+            BytecodeType::from_ty(self.vm, glob.ty)
+        );
 
         let disp = self.asm.add_addr(glob.address_value.to_ptr());
         let pos = self.asm.pos() as i32;
@@ -1053,12 +1433,30 @@ where
 
         let bytecode_type = self.bytecode.register_type(src);
 
-        let reg = result_reg(bytecode_type);
+        if let BytecodeType::Tuple(tuple_id) = bytecode_type {
+            let src_offset = self.determine_register_offset(src);
+            self.copy_tuple(
+                tuple_id,
+                RegOrOffset::Reg(REG_TMP1),
+                RegOrOffset::Offset(src_offset),
+            );
+        } else {
+            let reg = result_reg(bytecode_type);
 
-        self.emit_load_register(src, reg);
+            self.emit_load_register(src, reg);
 
-        self.asm
-            .store_mem(glob.ty.mode(), Mem::Base(REG_TMP1, 0), reg);
+            self.asm
+                .store_mem(glob.ty.mode(self.vm), Mem::Base(REG_TMP1, 0), reg);
+        }
+
+        if glob.needs_initialization() {
+            let disp = self.asm.add_addr(glob.address_init.to_ptr());
+            let pos = self.asm.pos() as i32;
+            self.asm.load_constpool(REG_RESULT, disp + pos);
+            self.asm.load_int_const(MachineMode::Int8, REG_TMP1, 1);
+            self.asm
+                .store_mem(MachineMode::Int8, Mem::Base(REG_RESULT, 0), REG_TMP1.into());
+        }
     }
 
     fn emit_const_nil(&mut self, dest: Register) {
@@ -1200,6 +1598,26 @@ where
         }
     }
 
+    fn emit_return_tuple(&mut self, src: Register) {
+        let tuple_id = self.bytecode.register_type(src).tuple_id().unwrap();
+        let return_address_offset = self.return_address_offset.unwrap();
+
+        self.asm.load_mem(
+            MachineMode::Ptr,
+            REG_TMP1.into(),
+            Mem::Local(return_address_offset),
+        );
+
+        let src_offset = self.determine_register_offset(src);
+        self.copy_tuple(
+            tuple_id,
+            RegOrOffset::Reg(REG_TMP1),
+            RegOrOffset::Offset(src_offset),
+        );
+
+        self.emit_epilog();
+    }
+
     fn emit_return_generic(&mut self, src: Register) {
         let bytecode_type = self.bytecode.register_type(src);
 
@@ -1220,6 +1638,25 @@ where
         }
     }
 
+    fn emit_exception_handlers(&mut self) {
+        for handler in self.bytecode.exception_handlers() {
+            let try_start = self.offset_to_address[&handler.try_start];
+            let try_end = self.offset_to_address[&handler.try_end];
+            let catch = self.offset_to_address[&handler.catch];
+
+            self.asm
+                .emit_exception_handler((try_start, try_end), catch, handler.catch_type);
+        }
+    }
+
+    fn emit_load_exception(&mut self, dest: Register) {
+        assert_eq!(self.bytecode.register_type(dest), BytecodeType::Ptr);
+        let position = self.bytecode.offset_position(self.current_offset.to_u32());
+        let gcpoint = GcPoint::from_offsets(self.references.clone());
+        self.asm.catch_exception(REG_RESULT, position, gcpoint);
+        self.emit_store_register(REG_RESULT.into(), dest);
+    }
+
     fn emit_new_object(&mut self, dest: Register, class_def_id: ClassDefId) {
         assert_eq!(self.bytecode.register_type(dest), BytecodeType::Ptr);
 
@@ -1275,9 +1712,6 @@ where
             }
             _ => unreachable!(),
         }
-
-        let ref_offset = self.determine_register_offset(dest);
-        self.references.push(ref_offset);
     }
 
     fn emit_new_array(&mut self, dest: Register, class_def_id: ClassDefId, length: Register) {
@@ -1365,9 +1799,6 @@ where
             InstanceSize::UnitArray => {}
             _ => unreachable!(),
         }
-
-        let ref_offset = self.determine_register_offset(dest);
-        self.references.push(ref_offset);
     }
 
     fn emit_array_initialization(&mut self, object_start: Reg, array_length: Reg, size: i32) {
@@ -1523,19 +1954,10 @@ where
     }
 
     fn emit_invoke_virtual_generic(&mut self, dest: Register, fct_id: FctDefId, num: u32) {
-        let bytecode_type = self.bytecode.register_type(dest);
-
-        let reg = self.emit_invoke_virtual(fct_id, num, Some(bytecode_type));
-
-        self.emit_store_register(reg, dest);
+        self.emit_invoke_virtual(fct_id, num, Some(dest));
     }
 
-    fn emit_invoke_virtual(
-        &mut self,
-        fct_def_id: FctDefId,
-        num: u32,
-        bytecode_type: Option<BytecodeType>,
-    ) -> AnyReg {
+    fn emit_invoke_virtual(&mut self, fct_def_id: FctDefId, num: u32, dest: Option<Register>) {
         assert!(num > 0);
 
         assert_eq!(self.argument_stack.len() as u32, num);
@@ -1555,27 +1977,113 @@ where
         let fct = self.vm.fcts.idx(fct_id);
         let fct = fct.read();
 
-        let argsize = self.emit_invoke_arguments(arguments, num);
-
-        // handling of class and function type parameters has to implemented
-        let cls_type_params = fct_def.cls_type_params.clone();
+        let argsize = self.emit_invoke_arguments(dest, arguments);
 
         let name = fct.full_name(self.vm);
         self.asm.emit_comment(format!("call virtual {}", name));
         let vtable_index = fct.vtable_index.unwrap();
         let gcpoint = GcPoint::from_offsets(self.references.clone());
 
-        let (reg, ty) = match bytecode_type {
-            Some(bytecode_type) => (result_reg(bytecode_type), bytecode_type.into()),
-            None => (REG_RESULT.into(), BuiltinType::Unit),
-        };
+        let (reg, ty) = self.call_result(dest);
 
+        let self_index = if self.returns_tuple(dest) { 1 } else { 0 };
         self.asm
-            .indirect_call(vtable_index, position, gcpoint, ty, cls_type_params, reg);
+            .indirect_call(vtable_index, position, gcpoint, ty, self_index, reg);
+
+        self.asm.decrease_stack_frame(argsize);
+
+        self.store_call_result(reg, dest);
+    }
+
+    fn emit_invoke_lambda_void(&mut self, num: u32) {
+        self.emit_invoke_lambda(num, None);
+    }
+
+    fn emit_invoke_lambda_generic(&mut self, dest: Register, num: u32) {
+        self.emit_invoke_lambda(num, Some(dest));
+    }
+
+    fn emit_invoke_lambda(&mut self, num: u32, dest: Option<Register>) {
+        assert!(num > 0);
+
+        assert_eq!(self.argument_stack.len() as u32, num);
+        let arguments = self.argument_stack.drain(..).collect::<Vec<_>>();
+        let closure_register = arguments[0];
+
+        let bytecode_type_closure = self.bytecode.register_type(closure_register);
+        let position = self.bytecode.offset_position(self.current_offset.to_u32());
+        assert_eq!(bytecode_type_closure, BytecodeType::Ptr);
+
+        self.emit_load_register(closure_register, REG_RESULT.into());
+
+        self.asm
+            .test_if_nil_bailout(position, REG_RESULT, Trap::NIL);
+
+        let argsize = self.emit_invoke_arguments(dest, arguments);
+
+        // the lambda body is the only entry in the vtable of the closure class
+        self.asm.emit_comment("call lambda".into());
+        let gcpoint = GcPoint::from_offsets(self.references.clone());
+
+        let (reg, ty) = self.call_result(dest);
+
+        let self_index = if self.returns_tuple(dest) { 1 } else { 0 };
+        self.asm
+            .indirect_call(0, position, gcpoint, ty, self_index, reg);
+
+        self.asm.decrease_stack_frame(argsize);
+
+        self.store_call_result(reg, dest);
+    }
+
+    fn emit_invoke_trait_void(&mut self, fct_id: FctDefId, num: u32) {
+        self.emit_invoke_trait(fct_id, num, None);
+    }
+
+    fn emit_invoke_trait_generic(&mut self, dest: Register, fct_id: FctDefId, num: u32) {
+        self.emit_invoke_trait(fct_id, num, Some(dest));
+    }
+
+    fn emit_invoke_trait(&mut self, fct_def_id: FctDefId, num: u32, dest: Option<Register>) {
+        assert!(num > 0);
+
+        assert_eq!(self.argument_stack.len() as u32, num);
+        let arguments = self.argument_stack.drain(..).collect::<Vec<_>>();
+        let self_register = arguments[0];
+
+        let bytecode_type_self = self.bytecode.register_type(self_register);
+        let position = self.bytecode.offset_position(self.current_offset.to_u32());
+        assert_eq!(bytecode_type_self, BytecodeType::Ptr);
+
+        let fct_def = self.vm.fct_defs.idx(fct_def_id);
+        let fct_def = fct_def.read();
+
+        let fct_id = fct_def.fct_id;
+        let fct = self.vm.fcts.idx(fct_id);
+        let fct = fct.read();
+
+        let argsize = self.emit_invoke_arguments(dest, arguments);
+
+        let name = fct.full_name(self.vm);
+        self.asm.emit_comment(format!("call trait object {}", name));
+        let gcpoint = GcPoint::from_offsets(self.references.clone());
+
+        let (reg, ty) = self.call_result(dest);
+
+        let self_index = if self.returns_tuple(dest) { 1 } else { 0 };
+        self.asm.trait_call(
+            fct_id,
+            fct_def.fct_type_params.clone(),
+            position,
+            gcpoint,
+            ty,
+            self_index,
+            reg,
+        );
 
         self.asm.decrease_stack_frame(argsize);
 
-        reg
+        self.store_call_result(reg, dest);
     }
 
     fn emit_invoke_direct_void(&mut self, fct_def_id: FctDefId, num: u32) {
@@ -1583,19 +2091,10 @@ where
     }
 
     fn emit_invoke_direct_generic(&mut self, dest: Register, fct_id: FctDefId, num: u32) {
-        let bytecode_type = self.bytecode.register_type(dest);
-
-        let reg = self.emit_invoke_direct(fct_id, num, Some(bytecode_type));
-
-        self.emit_store_register(reg, dest);
+        self.emit_invoke_direct(fct_id, num, Some(dest));
     }
 
-    fn emit_invoke_direct(
-        &mut self,
-        fct_def_id: FctDefId,
-        num: u32,
-        bytecode_type: Option<BytecodeType>,
-    ) -> AnyReg {
+    fn emit_invoke_direct(&mut self, fct_def_id: FctDefId, num: u32, dest: Option<Register>) {
         assert!(num > 0);
 
         assert_eq!(self.argument_stack.len() as u32, num);
@@ -1620,7 +2119,7 @@ where
 
         assert!(fct.type_params.is_empty());
 
-        let argsize = self.emit_invoke_arguments(arguments, num);
+        let argsize = self.emit_invoke_arguments(dest, arguments);
 
         let cls_type_params = fct_def.cls_type_params.clone();
         let fct_type_params = fct_def.fct_type_params.clone();
@@ -1630,10 +2129,7 @@ where
         let ptr = self.ptr_for_fct_id(fct_id, cls_type_params.clone(), fct_type_params.clone());
         let gcpoint = GcPoint::from_offsets(self.references.clone());
 
-        let (reg, ty) = match bytecode_type {
-            Some(bytecode_type) => (result_reg(bytecode_type), bytecode_type.into()),
-            None => (REG_RESULT.into(), BuiltinType::Unit),
-        };
+        let (reg, ty) = self.call_result(dest);
         self.asm.direct_call(
             fct_id,
             ptr.to_ptr(),
@@ -1647,7 +2143,7 @@ where
 
         self.asm.decrease_stack_frame(argsize);
 
-        reg
+        self.store_call_result(reg, dest);
     }
 
     fn emit_invoke_static_void(&mut self, fct_id: FctDefId, num: u32) {
@@ -1655,19 +2151,10 @@ where
     }
 
     fn emit_invoke_static_generic(&mut self, dest: Register, fct_id: FctDefId, num: u32) {
-        let bytecode_type = self.bytecode.register_type(dest);
-
-        let reg = self.emit_invoke_static(fct_id, num, Some(bytecode_type));
-
-        self.emit_store_register(reg, dest);
+        self.emit_invoke_static(fct_id, num, Some(dest));
     }
 
-    fn emit_invoke_static(
-        &mut self,
-        fct_def_id: FctDefId,
-        num: u32,
-        bytecode_type: Option<BytecodeType>,
-    ) -> AnyReg {
+    fn emit_invoke_static(&mut self, fct_def_id: FctDefId, num: u32, dest: Option<Register>) {
         let fct_def = self.vm.fct_defs.idx(fct_def_id);
         let fct_def = fct_def.read();
 
@@ -1676,13 +2163,15 @@ where
         let fct = fct.read();
 
         if let FctKind::Builtin(intrinsic) = fct.kind {
-            return self.emit_invoke_intrinsic(&*fct, &*fct_def, intrinsic, num);
+            let reg = self.emit_invoke_intrinsic(&*fct, &*fct_def, intrinsic, num);
+            self.store_call_result(reg, dest);
+            return;
         }
 
         assert_eq!(self.argument_stack.len() as u32, num);
         let arguments = self.argument_stack.drain(..).collect::<Vec<_>>();
 
-        let argsize = self.emit_invoke_arguments(arguments, num);
+        let argsize = self.emit_invoke_arguments(dest, arguments);
 
         let cls_type_params = fct_def.cls_type_params.clone();
         let fct_type_params = fct_def.fct_type_params.clone();
@@ -1694,10 +2183,7 @@ where
         let gcpoint = GcPoint::from_offsets(self.references.clone());
         let position = self.bytecode.offset_position(self.current_offset.to_u32());
 
-        let (reg, ty) = match bytecode_type {
-            Some(bytecode_type) => (result_reg(bytecode_type), bytecode_type.into()),
-            None => (REG_RESULT.into(), BuiltinType::Unit),
-        };
+        let (reg, ty) = self.call_result(dest);
         self.asm.direct_call(
             fct_id,
             ptr.to_ptr(),
@@ -1711,7 +2197,7 @@ where
 
         self.asm.decrease_stack_frame(argsize);
 
-        reg
+        self.store_call_result(reg, dest);
     }
 
     fn emit_invoke_intrinsic(
@@ -1820,12 +2306,8 @@ where
         }
     }
 
-    fn emit_invoke_arguments(&mut self, arguments: Vec<Register>, num: u32) -> i32 {
-        if num == 0 {
-            return 0;
-        }
-
-        let argsize = self.determine_argsize(&arguments);
+    fn emit_invoke_arguments(&mut self, dest: Option<Register>, arguments: Vec<Register>) -> i32 {
+        let argsize = self.determine_argsize(dest, &arguments);
 
         self.asm.increase_stack_frame(argsize);
 
@@ -1833,9 +2315,35 @@ where
         let mut freg_idx = 0;
         let mut sp_offset = 0;
 
+        if self.returns_tuple(dest) {
+            // the callee copies the struct return value to this address
+            let offset = self.determine_register_offset(dest.unwrap());
+            self.asm.lea(REG_PARAMS[0], Mem::Local(offset));
+            reg_idx += 1;
+        }
+
         for src in arguments {
             let bytecode_type = self.bytecode.register_type(src);
-            let offset = self.bytecode.register_offset(src);
+            let offset = self.determine_register_offset(src);
+
+            if bytecode_type.tuple_id().is_some() {
+                // structs are passed as pointer, the callee copies the value
+                if reg_idx < REG_PARAMS.len() {
+                    self.asm.lea(REG_PARAMS[reg_idx], Mem::Local(offset));
+                    reg_idx += 1;
+                } else {
+                    self.asm.lea(REG_TMP1, Mem::Local(offset));
+                    self.asm.store_mem(
+                        MachineMode::Ptr,
+                        Mem::Base(REG_SP, sp_offset),
+                        REG_TMP1.into(),
+                    );
+                    sp_offset += 8;
+                }
+
+                continue;
+            }
+
             let mode = bytecode_type.mode();
 
             match bytecode_type {
@@ -1877,8 +2385,8 @@ where
         argsize
     }
 
-    fn determine_argsize(&mut self, arguments: &Vec<Register>) -> i32 {
-        let mut reg_idx = 0;
+    fn determine_argsize(&mut self, dest: Option<Register>, arguments: &Vec<Register>) -> i32 {
+        let mut reg_idx = if self.returns_tuple(dest) { 1 } else { 0 };
         let mut freg_idx = 0;
         let mut argsize = 0;
 
@@ -1906,6 +2414,34 @@ where
         mem::align_i32(argsize, STACK_FRAME_ALIGNMENT as i32)
     }
 
+    // structs are not returned in a register, the caller passes the address
+    // of the destination as hidden first argument
+    fn returns_tuple(&self, dest: Option<Register>) -> bool {
+        match dest {
+            Some(dest) => self.bytecode.register_type(dest).tuple_id().is_some(),
+            None => false,
+        }
+    }
+
+    fn call_result(&self, dest: Option<Register>) -> (AnyReg, BuiltinType) {
+        match dest {
+            Some(dest) if !self.returns_tuple(Some(dest)) => {
+                let bytecode_type = self.bytecode.register_type(dest);
+                (result_reg(bytecode_type), bytecode_type.into())
+            }
+            _ => (REG_RESULT.into(), BuiltinType::Unit),
+        }
+    }
+
+    fn store_call_result(&mut self, reg: AnyReg, dest: Option<Register>) {
+        match dest {
+            Some(dest) if !self.returns_tuple(Some(dest)) => {
+                self.emit_store_register(reg, dest);
+            }
+            _ => {}
+        }
+    }
+
     fn ptr_for_fct_id(
         &mut self,
         fid: FctId,
@@ -1951,6 +2487,11 @@ where
 
 impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_instruction(&mut self, offset: BytecodeOffset) {
+        if self.try_boundaries.contains(&offset) {
+            // traps need to return into the try range to be caught
+            self.asm.emit_slow_paths_inline();
+        }
+
         self.offset_to_address.insert(offset, self.asm.pos());
         self.current_offset = offset;
     }
@@ -2199,6 +2740,28 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_mov_ptr(&mut self, dest: Register, src: Register) {
         self.emit_mov_generic(dest, src);
     }
+    fn visit_mov_tuple(&mut self, dest: Register, src: Register, tuple_id: TupleId) {
+        self.emit_mov_tuple(dest, src, tuple_id);
+    }
+
+    fn visit_load_tuple_element(
+        &mut self,
+        dest: Register,
+        src: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        self.emit_load_tuple_element(dest, src, tuple_id, element);
+    }
+    fn visit_store_tuple_element(
+        &mut self,
+        src: Register,
+        dest: Register,
+        tuple_id: TupleId,
+        element: u32,
+    ) {
+        self.emit_store_tuple_element(src, dest, tuple_id, element);
+    }
 
     fn visit_load_field_bool(
         &mut self,
@@ -2272,6 +2835,15 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     ) {
         self.emit_load_field(dest, obj, cls, field);
     }
+    fn visit_load_field_tuple(
+        &mut self,
+        dest: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit_load_field(dest, obj, cls, field);
+    }
 
     fn visit_store_field_bool(
         &mut self,
@@ -2345,6 +2917,15 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     ) {
         self.emit_store_field(src, obj, cls, field);
     }
+    fn visit_store_field_tuple(
+        &mut self,
+        src: Register,
+        obj: Register,
+        cls: ClassDefId,
+        field: FieldId,
+    ) {
+        self.emit_store_field(src, obj, cls, field);
+    }
 
     fn visit_load_global_bool(&mut self, dest: Register, glob: GlobalId) {
         self.emit_load_global(dest, glob);
@@ -2370,6 +2951,9 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_load_global_ptr(&mut self, dest: Register, glob: GlobalId) {
         self.emit_load_global(dest, glob);
     }
+    fn visit_load_global_tuple(&mut self, dest: Register, glob: GlobalId) {
+        self.emit_load_global(dest, glob);
+    }
 
     fn visit_store_global_bool(&mut self, src: Register, glob: GlobalId) {
         self.emit_store_global(src, glob);
@@ -2395,6 +2979,9 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_store_global_ptr(&mut self, src: Register, glob: GlobalId) {
         self.emit_store_global(src, glob);
     }
+    fn visit_store_global_tuple(&mut self, src: Register, glob: GlobalId) {
+        self.emit_store_global(src, glob);
+    }
 
     fn visit_push_register(&mut self, src: Register) {
         self.argument_stack.push(src);
@@ -2623,6 +3210,21 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
         self.asm.assert(REG_RESULT, position);
     }
 
+    fn visit_throw(&mut self, exception: Register) {
+        assert_eq!(self.bytecode.register_type(exception), BytecodeType::Ptr);
+        let position = self.bytecode.offset_position(self.current_offset.to_u32());
+        self.emit_load_register(exception, REG_RESULT.into());
+
+        self.asm
+            .test_if_nil_bailout(position, REG_RESULT, Trap::NIL);
+        let gcpoint = GcPoint::from_offsets(self.references.clone());
+        self.asm.throw(REG_RESULT, position, gcpoint);
+    }
+
+    fn visit_load_exception(&mut self, dest: Register) {
+        self.emit_load_exception(dest);
+    }
+
     fn visit_jump_if_false(&mut self, opnd: Register, offset: u32) {
         let target = BytecodeOffset(self.current_offset.to_u32() + offset);
         self.emit_jump_if(opnd, target, false);
@@ -2692,6 +3294,9 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_invoke_direct_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit_invoke_direct_generic(dest, fctdef, count);
     }
+    fn visit_invoke_direct_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_direct_generic(dest, fctdef, count);
+    }
 
     fn visit_invoke_virtual_void(&mut self, fctdef: FctDefId, count: u32) {
         self.emit_invoke_virtual_void(fctdef, count);
@@ -2720,6 +3325,9 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_invoke_virtual_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit_invoke_virtual_generic(dest, fctdef, count);
     }
+    fn visit_invoke_virtual_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_virtual_generic(dest, fctdef, count);
+    }
 
     fn visit_invoke_static_void(&mut self, fctdef: FctDefId, count: u32) {
         self.emit_invoke_static_void(fctdef, count)
@@ -2748,6 +3356,71 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_invoke_static_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
         self.emit_invoke_static_generic(dest, fctdef, count);
     }
+    fn visit_invoke_static_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_static_generic(dest, fctdef, count);
+    }
+
+    fn visit_invoke_lambda_void(&mut self, count: u32) {
+        self.emit_invoke_lambda_void(count);
+    }
+    fn visit_invoke_lambda_bool(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_uint8(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_char(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_int(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_int64(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_float(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_double(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_ptr(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+    fn visit_invoke_lambda_tuple(&mut self, dest: Register, count: u32) {
+        self.emit_invoke_lambda_generic(dest, count);
+    }
+
+    fn visit_invoke_trait_void(&mut self, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_void(fctdef, count);
+    }
+    fn visit_invoke_trait_bool(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_uint8(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_char(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_int(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_int64(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_float(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_double(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_ptr(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
+    fn visit_invoke_trait_tuple(&mut self, dest: Register, fctdef: FctDefId, count: u32) {
+        self.emit_invoke_trait_generic(dest, fctdef, count);
+    }
 
     fn visit_new_object(&mut self, dest: Register, cls: ClassDefId) {
         self.emit_new_object(dest, cls)
@@ -2844,6 +3517,36 @@ impl<'a, 'ast: 'a> BytecodeVisitor for CannonCodeGen<'a, 'ast> {
     fn visit_ret_ptr(&mut self, opnd: Register) {
         self.emit_return_generic(opnd);
     }
+    fn visit_ret_tuple(&mut self, opnd: Register) {
+        self.emit_return_tuple(opnd);
+    }
+}
+
+#[derive(Copy, Clone)]
+enum RegOrOffset {
+    Reg(Reg),
+    RegWithOffset(Reg, i32),
+    Offset(i32),
+}
+
+impl RegOrOffset {
+    fn offset(self, offset: i32) -> RegOrOffset {
+        match self {
+            RegOrOffset::Reg(reg) => RegOrOffset::RegWithOffset(reg, offset),
+            RegOrOffset::RegWithOffset(reg, base_offset) => {
+                RegOrOffset::RegWithOffset(reg, base_offset + offset)
+            }
+            RegOrOffset::Offset(base_offset) => RegOrOffset::Offset(base_offset + offset),
+        }
+    }
+
+    fn mem(self) -> Mem {
+        match self {
+            RegOrOffset::Reg(reg) => Mem::Base(reg, 0),
+            RegOrOffset::RegWithOffset(reg, offset) => Mem::Base(reg, offset),
+            RegOrOffset::Offset(offset) => Mem::Local(offset),
+        }
+    }
 }
 
 fn result_reg(bytecode_type: BytecodeType) -> AnyReg {
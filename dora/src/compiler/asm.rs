@@ -3,7 +3,7 @@ use std::mem;
 use dora_parser::lexer::position::Position;
 
 use crate::compiler::codegen::{ensure_native_stub, AllocationSize, AnyReg};
-use crate::compiler::fct::{Code, GcPoint, JitDescriptor};
+use crate::compiler::fct::{CatchType, Code, GcPoint, JitDescriptor};
 use crate::compiler::native_stub::{NativeFct, NativeFctDescriptor};
 use crate::cpu::{
     FReg, Mem, Reg, FREG_RESULT, REG_PARAMS, REG_RESULT, REG_THREAD, REG_TMP1, REG_TMP2,
@@ -186,6 +186,44 @@ where
         self.masm.emit_bailout_inplace(trap, pos)
     }
 
+    // Emits all slow paths and bailouts collected so far at the current
+    // position, traps raised in there are then covered by the enclosing
+    // try-block.
+    pub fn emit_slow_paths_inline(&mut self) {
+        if !self.slow_paths.is_empty() {
+            let lbl_end = self.masm.create_label();
+            self.masm.jump(lbl_end);
+            self.slow_paths();
+            self.masm.bind_label(lbl_end);
+        }
+
+        self.masm.emit_bailouts_inline();
+    }
+
+    pub fn emit_exception_handler(
+        &mut self,
+        span: (usize, usize),
+        catch: usize,
+        catch_type: CatchType,
+    ) {
+        self.masm.emit_exception_handler(span, catch, catch_type);
+    }
+
+    pub fn throw(&mut self, exception: Reg, pos: Position, gcpoint: GcPoint) {
+        self.masm
+            .copy_reg(MachineMode::Ptr, REG_PARAMS[0], exception);
+        self.masm.raw_call(self.vm.throw_stub().to_ptr());
+        self.masm.emit_gcpoint(gcpoint);
+        self.masm.emit_position(pos);
+    }
+
+    pub fn catch_exception(&mut self, dest: Reg, pos: Position, gcpoint: GcPoint) {
+        self.masm.raw_call(self.vm.catch_stub().to_ptr());
+        self.masm.emit_gcpoint(gcpoint);
+        self.masm.emit_position(pos);
+        self.masm.copy_reg(MachineMode::Ptr, dest, REG_RESULT);
+    }
+
     pub fn get_scratch(&self) -> ScratchReg {
         self.masm.get_scratch()
     }
@@ -443,11 +481,11 @@ where
     }
 
     pub fn var_store(&mut self, offset: i32, ty: BuiltinType, src: AnyReg) {
-        self.masm.store_mem(ty.mode(), Mem::Local(offset), src);
+        self.masm.store_mem(ty.mode(self.vm), Mem::Local(offset), src);
     }
 
     pub fn var_load(&mut self, offset: i32, ty: BuiltinType, dest: AnyReg) {
-        self.masm.load_mem(ty.mode(), dest, Mem::Local(offset));
+        self.masm.load_mem(ty.mode(self.vm), dest, Mem::Local(offset));
     }
 
     pub fn jit(mut self, stacksize: i32, desc: JitDescriptor) -> Code {
@@ -491,10 +529,34 @@ where
         pos: Position,
         gcpoint: GcPoint,
         return_type: BuiltinType,
-        cls_type_params: TypeList,
+        self_index: usize,
+        dest: AnyReg,
+    ) {
+        self.masm.indirect_call(pos, index, self_index);
+        self.call_epilog(pos, return_type, dest, gcpoint);
+    }
+
+    pub fn trait_call(
+        &mut self,
+        fct_id: FctId,
+        fct_tps: TypeList,
+        pos: Position,
+        gcpoint: GcPoint,
+        return_type: BuiltinType,
+        self_index: usize,
         dest: AnyReg,
     ) {
-        self.masm.indirect_call(pos, index, cls_type_params);
+        let (trait_id, index) = {
+            let fct = self.vm.fcts.idx(fct_id);
+            let fct = fct.read();
+            let trait_id = fct.trait_id();
+            let xtrait = self.vm.traits[trait_id].read();
+
+            (trait_id, xtrait.method_index(fct_id) as u32)
+        };
+
+        self.masm
+            .trait_call(pos, trait_id, index, self_index, fct_tps);
         self.call_epilog(pos, return_type, dest, gcpoint);
     }
 
@@ -512,13 +574,13 @@ where
         match dest {
             AnyReg::Reg(dest) => {
                 if dest != REG_RESULT {
-                    self.masm.copy_reg(ty.mode(), dest, REG_RESULT);
+                    self.masm.copy_reg(ty.mode(self.vm), dest, REG_RESULT);
                 }
             }
 
             AnyReg::FReg(dest) => {
                 if dest != FREG_RESULT {
-                    self.masm.copy_freg(ty.mode(), dest, FREG_RESULT);
+                    self.masm.copy_freg(ty.mode(self.vm), dest, FREG_RESULT);
                 }
             }
         }
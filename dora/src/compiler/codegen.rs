@@ -4,7 +4,7 @@ use crate::baseline;
 use crate::boots;
 use crate::cannon;
 use crate::compiler::JitFct;
-use crate::compiler::{native_stub, CodeDescriptor, NativeFct};
+use crate::compiler::{native_stub, CodeDescriptor, NativeFct, NativeFctDescriptor};
 use crate::cpu::{FReg, Reg, FREG_RESULT, REG_RESULT};
 use crate::disassembler;
 use crate::driver::cmd::{AsmSyntax, CompilerName};
@@ -12,7 +12,8 @@ use crate::gc::Address;
 use crate::masm::*;
 use crate::mem;
 use crate::os;
-use crate::ty::{MachineMode, TypeList};
+use crate::stdlib;
+use crate::ty::{BuiltinType, MachineMode, TypeList};
 use crate::vm::VM;
 use crate::vm::{Fct, FctId, FctSrc};
 
@@ -227,6 +228,12 @@ pub enum AllocationSize {
     Dynamic(Reg),
 }
 
+fn is_capability_denied(vm: &VM, fct_id: FctId) -> bool {
+    let fct = vm.fcts.idx(fct_id);
+    let fct = fct.read();
+    vm.denied_capability(&*fct).is_some()
+}
+
 pub fn ensure_native_stub(vm: &VM, fct_id: Option<FctId>, internal_fct: NativeFct) -> Address {
     let mut native_stubs = vm.native_stubs.lock();
     let ptr = internal_fct.ptr;
@@ -235,6 +242,20 @@ pub fn ensure_native_stub(vm: &VM, fct_id: Option<FctId>, internal_fct: NativeFc
         let jit_fct = vm.jit_fcts.idx(jit_fct_id);
         jit_fct.instruction_start()
     } else {
+        let internal_fct = match fct_id {
+            // the call was not rejected by semck (e.g. from within the stdlib),
+            // so fail at runtime instead. the stub is still generated and cached
+            // per native, so that stack traces name the denied native.
+            Some(fct_id) if is_capability_denied(vm, fct_id) => NativeFct {
+                ptr: Address::from_ptr(stdlib::capability_denied as *const u8),
+                args: &[],
+                return_type: BuiltinType::Unit,
+                desc: NativeFctDescriptor::NativeStub(fct_id),
+            },
+
+            _ => internal_fct,
+        };
+
         let dbg = if let Some(fct_id) = fct_id {
             let fct = vm.fcts.idx(fct_id);
             let fct = fct.read();
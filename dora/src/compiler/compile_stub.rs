@@ -11,11 +11,12 @@ use crate::gc::Address;
 use crate::masm::MacroAssembler;
 use crate::mem;
 use crate::object::Obj;
+use crate::semck::specialize::replace_type_param;
 use crate::stack::DoraToNativeInfo;
 use crate::threads::ThreadLocalData;
 use crate::ty::{MachineMode, TypeList};
-use crate::vm::FctId;
 use crate::vm::{get_vm, VM};
+use crate::vm::{ClassDef, ClassId, FctId, TraitId};
 
 // This code generates the compiler stub, there should only be one instance
 // of this function be used in Dora. It is necessary for lazy compilation, where
@@ -122,11 +123,8 @@ where
             CCALL_REG_PARAMS[0].into(),
             Mem::Base(REG_FP, mem::ptr_width()),
         );
-        self.masm.load_mem(
-            MachineMode::Ptr,
-            CCALL_REG_PARAMS[1].into(),
-            Mem::Base(REG_SP, offset_params),
-        );
+        self.masm
+            .lea(CCALL_REG_PARAMS[1], Mem::Base(REG_SP, offset_params));
         self.masm.raw_call(compile_request as *const u8);
 
         self.masm.load_mem(
@@ -191,7 +189,7 @@ where
     }
 }
 
-fn compile_request(ra: usize, receiver: Address) -> Address {
+fn compile_request(ra: usize, params: Address) -> Address {
     let vm = get_vm();
 
     let lazy_compilation_site = {
@@ -219,27 +217,113 @@ fn compile_request(ra: usize, receiver: Address) -> Address {
             patch_fct_call(vm, ra, fct_id, cls_tps, fct_tps, disp)
         }
 
-        LazyCompilationSite::VirtCompile(vtable_index, ref cls_tps, ref fct_tps) => {
-            patch_vtable_call(vm, receiver, vtable_index, cls_tps, fct_tps)
+        LazyCompilationSite::VirtCompile(vtable_index, self_index, ref fct_tps) => {
+            // argument registers were saved to the stack by the stub
+            let receiver = unsafe { *params.add_ptr(self_index).to_ptr::<Address>() };
+            patch_vtable_call(vm, receiver, vtable_index, fct_tps)
+        }
+
+        LazyCompilationSite::TraitCompile(trait_id, index, self_index, ref fct_tps) => {
+            let receiver = unsafe { *params.add_ptr(self_index).to_ptr::<Address>() };
+            patch_itable_call(vm, receiver, trait_id, index, fct_tps)
         }
     }
 }
 
-fn patch_vtable_call(
+// trait objects are plain object references, the method implementing the
+// trait method is looked up in the impls of the receiver's class and its
+// super classes and then stored in the itable of the receiver's class.
+// Generic methods are never stored, the itable has only one entry for all
+// their type params.
+fn patch_itable_call(
     vm: &VM,
     receiver: Address,
-    vtable_index: u32,
-    cls_tps: &TypeList,
+    trait_id: TraitId,
+    index: u32,
     fct_tps: &TypeList,
 ) -> Address {
     let obj = unsafe { &mut *receiver.to_mut_ptr::<Obj>() };
     let vtable = obj.header().vtbl();
-    let cls_id = vtable.class().cls_id.expect("no corresponding class");
+    let trait_fct_id = vm.traits[trait_id].read().methods[index as usize];
+    let fct_ptr = trait_impl_ptr(vm, vtable.class(), trait_fct_id, fct_tps);
+
+    if fct_tps.is_empty() {
+        vtable.set_itable_entry(trait_id.to_usize(), index as usize, fct_ptr.to_usize());
+    }
+
+    fct_ptr
+}
+
+fn trait_impl_ptr(vm: &VM, cls_def: &ClassDef, trait_fct_id: FctId, fct_tps: &TypeList) -> Address {
+    let (mut cls_id, mut cls_tps) = receiver_class(vm, cls_def);
+
+    loop {
+        if let Some(fct_id) = find_trait_impl(vm, cls_id, trait_fct_id) {
+            return compiler::generate(vm, fct_id, &cls_tps, fct_tps);
+        }
+
+        let cls = vm.classes.idx(cls_id);
+        let cls = cls.read();
+        let parent_class = cls.parent_class.expect("no impl for trait method");
+        let parent_class = replace_type_param(vm, parent_class, &cls_tps, &TypeList::empty(), None);
+
+        cls_id = parent_class.cls_id(vm).expect("no class");
+        cls_tps = parent_class.type_params(vm);
+    }
+}
+
+fn find_trait_impl(vm: &VM, cls_id: ClassId, trait_fct_id: FctId) -> Option<FctId> {
     let cls = vm.classes.idx(cls_id);
     let cls = cls.read();
 
-    let fct_id = cls.virtual_fcts[vtable_index as usize];
-    let fct_ptr = compiler::generate(vm, fct_id, cls_tps, fct_tps);
+    for &impl_id in &cls.impls {
+        let ximpl = vm.impls[impl_id].read();
+
+        for &method_id in &ximpl.methods {
+            let method = vm.fcts.idx(method_id);
+            let method = method.read();
+
+            if method.impl_for == Some(trait_fct_id) {
+                return Some(method_id);
+            }
+        }
+    }
+
+    None
+}
+
+fn patch_vtable_call(vm: &VM, receiver: Address, vtable_index: u32, fct_tps: &TypeList) -> Address {
+    let obj = unsafe { &mut *receiver.to_mut_ptr::<Obj>() };
+    let vtable = obj.header().vtbl();
+
+    let fct_ptr = if let Some(fct_def_id) = vtable.class().lambda {
+        // closure objects know the type params of their lambda
+        let (fct_id, cls_tps, fct_tps) = {
+            let fct_def = vm.fct_defs.idx(fct_def_id);
+            let fct_def = fct_def.read();
+
+            (
+                fct_def.fct_id,
+                fct_def.cls_type_params.clone(),
+                fct_def.fct_type_params.clone(),
+            )
+        };
+
+        compiler::generate(vm, fct_id, &cls_tps, &fct_tps)
+    } else {
+        let (cls_id, cls_tps) = receiver_class(vm, vtable.class());
+        let fct_id = {
+            let cls = vm.classes.idx(cls_id);
+            let cls = cls.read();
+            cls.virtual_fcts[vtable_index as usize]
+        };
+
+        // the receiver's static type might be a super class with different type params,
+        // derive the type params of the method's class from the receiver's dynamic class
+        let cls_tps = owner_type_params(vm, cls_id, cls_tps, fct_id);
+
+        compiler::generate(vm, fct_id, &cls_tps, fct_tps)
+    };
 
     let methodtable = vtable.table_mut();
     methodtable[vtable_index as usize] = fct_ptr.to_usize();
@@ -247,6 +331,42 @@ fn patch_vtable_call(
     fct_ptr
 }
 
+// module objects have no class of their own, their virtual methods are
+// the ones of the super class
+fn receiver_class(vm: &VM, cls_def: &ClassDef) -> (ClassId, TypeList) {
+    if let Some(cls_id) = cls_def.cls_id {
+        return (cls_id, cls_def.type_params.clone());
+    }
+
+    let parent_id = cls_def.parent_id.expect("no corresponding class");
+    let parent = vm.class_defs.idx(parent_id);
+    let parent = parent.read();
+
+    receiver_class(vm, &*parent)
+}
+
+fn owner_type_params(
+    vm: &VM,
+    mut cls_id: ClassId,
+    mut type_params: TypeList,
+    fct_id: FctId,
+) -> TypeList {
+    let owner_id = vm.fcts.idx(fct_id).read().cls_id();
+
+    while cls_id != owner_id {
+        let cls = vm.classes.idx(cls_id);
+        let cls = cls.read();
+        let parent_class = cls.parent_class.expect("method not in class hierarchy");
+        let parent_class =
+            replace_type_param(vm, parent_class, &type_params, &TypeList::empty(), None);
+
+        cls_id = parent_class.cls_id(vm).expect("no class");
+        type_params = parent_class.type_params(vm);
+    }
+
+    type_params
+}
+
 fn patch_fct_call(
     vm: &VM,
     ra: usize,
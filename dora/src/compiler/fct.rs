@@ -8,8 +8,8 @@ use crate::dseg::DSeg;
 use crate::gc::Address;
 use crate::ty::TypeList;
 use crate::utils::GrowableVec;
-use crate::vm::FctId;
 use crate::vm::VM;
+use crate::vm::{ClassDefId, FctId, TraitId};
 
 use dora_parser::Position;
 
@@ -120,6 +120,13 @@ impl JitFct {
             &JitFct::Uncompiled => unreachable!(),
         }
     }
+
+    pub fn exception_handlers(&self) -> &[ExceptionHandler] {
+        match self {
+            &JitFct::Compiled(ref base) => base.exception_handlers(),
+            &JitFct::Uncompiled => unreachable!(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -127,6 +134,8 @@ pub enum JitDescriptor {
     DoraFct(FctId),
     CompileStub,
     TrapStub,
+    ThrowStub,
+    CatchStub,
     AllocStub,
     VerifyStub,
     NativeStub(FctId),
@@ -149,6 +158,7 @@ pub struct Code {
     gcpoints: GcPoints,
     comments: Comments,
     positions: PositionTable,
+    exception_handlers: Vec<ExceptionHandler>,
 }
 
 impl Code {
@@ -164,6 +174,7 @@ impl Code {
             0,
             Comments::new(),
             PositionTable::new(),
+            Vec::new(),
             desc,
         )
     }
@@ -177,6 +188,7 @@ impl Code {
         framesize: i32,
         comments: Comments,
         positions: PositionTable,
+        exception_handlers: Vec<ExceptionHandler>,
         desc: JitDescriptor,
     ) -> Code {
         let size = dseg.size() as usize + buffer.len();
@@ -211,6 +223,7 @@ impl Code {
             instruction_start,
             instruction_end,
             positions,
+            exception_handlers,
             desc,
         }
     }
@@ -258,6 +271,10 @@ impl Code {
     pub fn lazy_for_offset(&self, offset: u32) -> Option<&LazyCompilationSite> {
         self.lazy_compilation.get(offset)
     }
+
+    pub fn exception_handlers(&self) -> &[ExceptionHandler] {
+        &self.exception_handlers
+    }
 }
 
 impl fmt::Debug for Code {
@@ -430,8 +447,33 @@ impl LazyCompilationData {
     }
 }
 
+// Handlers are ordered from innermost to outermost, the first handler
+// that covers the return address and accepts the exception wins.
+#[derive(Clone, Debug)]
+pub struct ExceptionHandler {
+    pub try_start: u32,
+    pub try_end: u32,
+    pub catch: u32,
+    pub catch_type: CatchType,
+}
+
+impl ExceptionHandler {
+    // `offset` is always a return address, so a call that is the last
+    // instruction in the try-block ends exactly at `try_end`.
+    pub fn covers(&self, offset: u32) -> bool {
+        self.try_start < offset && offset <= self.try_end
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum CatchType {
+    Any,
+    Class(ClassDefId),
+}
+
 #[derive(Clone, Debug)]
 pub enum LazyCompilationSite {
     Compile(FctId, i32, TypeList, TypeList),
-    VirtCompile(u32, TypeList, TypeList),
+    VirtCompile(u32, usize, TypeList),
+    TraitCompile(TraitId, u32, usize, TypeList),
 }
@@ -32,6 +32,8 @@ impl CodeMap {
                 }
                 &CodeDescriptor::CompileStub => println!("compile_stub"),
                 &CodeDescriptor::TrapStub => println!("trap_stub"),
+                &CodeDescriptor::ThrowStub => println!("throw_stub"),
+                &CodeDescriptor::CatchStub => println!("catch_stub"),
                 &CodeDescriptor::AllocStub => println!("alloc_stub"),
                 &CodeDescriptor::VerifyStub => println!("verify_stub"),
                 &CodeDescriptor::NativeStub(jit_fct_id) => {
@@ -66,6 +68,8 @@ pub enum CodeDescriptor {
     DoraFct(JitFctId),
     CompileStub,
     TrapStub,
+    ThrowStub,
+    CatchStub,
     AllocStub,
     VerifyStub,
     NativeStub(JitFctId),
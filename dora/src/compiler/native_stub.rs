@@ -43,6 +43,8 @@ pub enum NativeFctDescriptor {
     AllocStub,
     VerifyStub,
     TrapStub,
+    ThrowStub,
+    CatchStub,
     GuardCheckStub,
 }
 
@@ -71,6 +73,8 @@ pub fn generate<'a, 'ast: 'a>(vm: &'a VM<'ast>, fct: NativeFct, dbg: bool) -> Ji
     let code_desc = match fct_desc {
         NativeFctDescriptor::NativeStub(_) => CodeDescriptor::NativeStub(jit_fct_id),
         NativeFctDescriptor::TrapStub => CodeDescriptor::TrapStub,
+        NativeFctDescriptor::ThrowStub => CodeDescriptor::ThrowStub,
+        NativeFctDescriptor::CatchStub => CodeDescriptor::CatchStub,
         NativeFctDescriptor::VerifyStub => CodeDescriptor::VerifyStub,
         NativeFctDescriptor::AllocStub => CodeDescriptor::AllocStub,
         NativeFctDescriptor::GuardCheckStub => CodeDescriptor::GuardCheckStub,
@@ -97,7 +101,7 @@ where
         let save_return = self.fct.return_type != BuiltinType::Unit;
         let dtn_size = size_of::<DoraToNativeInfo>() as i32;
 
-        let (stack_args, temporaries, temporaries_desc, args_desc) = analyze(self.fct.args);
+        let (stack_args, temporaries, temporaries_desc, args_desc) = analyze(self.vm, self.fct.args);
 
         let offset_args = 0;
         let offset_temporaries = offset_args + stack_args as i32 * mem::ptr_width();
@@ -233,7 +237,7 @@ where
 
         if !self.fct.return_type.is_unit() {
             self.masm
-                .fix_result(REG_RESULT, self.fct.return_type.mode());
+                .fix_result(REG_RESULT, self.fct.return_type.mode(self.vm));
         }
 
         self.masm.load_mem(
@@ -248,7 +252,17 @@ where
             REG_TMP1.into(),
         );
 
-        self.masm.epilog();
+        match self.fct.desc {
+            // only returns when an exception handler was found, continue there
+            NativeFctDescriptor::TrapStub | NativeFctDescriptor::ThrowStub => {
+                self.masm.resume_exception();
+            }
+
+            _ => {
+                self.masm.epilog();
+            }
+        }
+
         self.masm.nop();
 
         let desc = match self.fct.desc {
@@ -256,6 +270,8 @@ where
             NativeFctDescriptor::AllocStub => JitDescriptor::AllocStub,
             NativeFctDescriptor::VerifyStub => JitDescriptor::VerifyStub,
             NativeFctDescriptor::TrapStub => JitDescriptor::TrapStub,
+            NativeFctDescriptor::ThrowStub => JitDescriptor::ThrowStub,
+            NativeFctDescriptor::CatchStub => JitDescriptor::CatchStub,
             NativeFctDescriptor::GuardCheckStub => JitDescriptor::GuardCheckStub,
         };
 
@@ -264,6 +280,7 @@ where
 }
 
 fn analyze(
+    vm: &VM,
     args: &[BuiltinType],
 ) -> (
     u32,
@@ -285,7 +302,7 @@ fn analyze(
         if ty.is_float() {
             let source = if freg_idx < FREG_PARAMS.len() {
                 save_temporaries.push(TemporaryStore::FloatRegister(
-                    ty.mode(),
+                    ty.mode(vm),
                     FREG_PARAMS[freg_idx],
                     temporaries,
                 ));
@@ -298,10 +315,10 @@ fn analyze(
 
             let destination = if freg_idx < CCALL_FREG_PARAMS.len() {
                 // argument still fits into register
-                ArgumentDestination::FloatRegister(ty.mode(), CCALL_FREG_PARAMS[freg_idx])
+                ArgumentDestination::FloatRegister(ty.mode(vm), CCALL_FREG_PARAMS[freg_idx])
             } else {
                 stack_args += 1;
-                ArgumentDestination::Offset(ty.mode(), stack_args - 1)
+                ArgumentDestination::Offset(ty.mode(vm), stack_args - 1)
             };
 
             load_params.push((source, destination));
@@ -309,7 +326,7 @@ fn analyze(
         } else {
             let source = if reg_idx < REG_PARAMS.len() {
                 save_temporaries.push(TemporaryStore::Register(
-                    ty.mode(),
+                    ty.mode(vm),
                     REG_PARAMS[reg_idx],
                     temporaries,
                 ));
@@ -326,18 +343,18 @@ fn analyze(
                     stack_args += 1;
                 }
 
-                if ty.reference_type() {
+                if ty.reference_type(vm) {
                     ArgumentDestination::HandleRegister(CCALL_REG_PARAMS[reg_idx])
                 } else {
-                    ArgumentDestination::Register(ty.mode(), CCALL_REG_PARAMS[reg_idx])
+                    ArgumentDestination::Register(ty.mode(vm), CCALL_REG_PARAMS[reg_idx])
                 }
             } else {
                 stack_args += 1;
 
-                if ty.reference_type() {
+                if ty.reference_type(vm) {
                     ArgumentDestination::HandleOffset(stack_args - 1)
                 } else {
-                    ArgumentDestination::Offset(ty.mode(), stack_args - 1)
+                    ArgumentDestination::Offset(ty.mode(vm), stack_args - 1)
                 }
             };
 
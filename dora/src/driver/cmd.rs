@@ -7,7 +7,9 @@ use crate::gc::M;
 use docopt::Docopt;
 use rustc_serialize;
 
+use crate::error::lint::{Lint, LintLevel, LintSet};
 use crate::gc::{DEFAULT_CODE_SPACE_LIMIT, DEFAULT_PERM_SPACE_LIMIT};
+use crate::vm::{Capability, CapabilitySet};
 
 pub fn parse() -> Args {
     Docopt::new(USAGE)
@@ -17,13 +19,21 @@ pub fn parse() -> Args {
 
 // Write the Docopt usage string.
 static USAGE: &'static str = "
-Usage: dora test [options] <file>
+Usage: dora test [options] [<file>]
+       dora check [options] [<file>]
+       dora run [options] [--] [<argument>...]
+       dora fmt [options] <file>
        dora [options] <file> [--] [<argument>...]
+       dora --explain=<code>
        dora (--version | --help)
 
+Without <file> `test` and `check` use the project in the working directory, `run`
+always does. A project is a directory with a `dora.toml` manifest.
+
 Options:
     -h, --help              Shows this text.
     --version               Shows version.
+    --explain=<code>        Explains the error with the given code, e.g. E0103.
     --emit-ast              Emits AST to stdout.
     --emit-llvm             Emits initial LLVM IR to stdout.
     --emit-asm=<fct>        Emits assembly code to stdout.
@@ -35,7 +45,10 @@ Options:
     --emit-debug-compile    Emits debug instruction at beginning of compile stub.
     --emit-debug-entry      Emits debug instruction at beginning of entry stub.
     --omit-bounds-check     Omit array index out of bounds checks.
-    --check                 Only type check given program.
+    --null-safety           Class types are non-nullable unless written as `T?`.
+    --check                 Only type check given program. With `fmt` only check
+                            whether files are formatted without changing them.
+    --error-format=<fmt>    Format of reported errors. Possible values: human (default), json.
     --asm-syntax TYPE       Emits assembly with Intel or AT&T syntax.
                             Allowed values: intel, att.
     --enable-perf           Enable dump for perf.
@@ -69,6 +82,12 @@ Options:
 
     --stdlib=<path>         Load standard library from the given path.
     --boots=<path>          Load boots source from the given path.
+
+    --allow=<lints>         Silence the given lints (comma-separated).
+    --warn=<lints>          Report the given lints as warnings (comma-separated).
+    --deny=<names>          Deny capabilities to the program or report lints as errors (comma-separated).
+                            Capabilities: process, threads, filesystem, network, reflection.
+                            Lints: unused-variable, unused-function, unreachable-code, unused-result.
 ";
 
 #[derive(Debug, RustcDecodable)]
@@ -84,7 +103,9 @@ pub struct Args {
     pub flag_emit_stubs: bool,
     pub flag_enable_perf: bool,
     pub flag_omit_bounds_check: bool,
+    pub flag_null_safety: bool,
     pub flag_version: bool,
+    pub flag_explain: Option<String>,
     pub flag_emit_debug: Option<String>,
     pub flag_emit_debug_native: bool,
     pub flag_emit_debug_compile: bool,
@@ -110,13 +131,20 @@ pub struct Args {
     pub flag_code_size: Option<MemSize>,
     pub flag_perm_size: Option<MemSize>,
     pub flag_check: bool,
+    pub flag_error_format: Option<ErrorFormat>,
     pub flag_disable_tlab: bool,
     pub flag_disable_barrier: bool,
     pub flag_stdlib: Option<String>,
     pub flag_boots: Option<String>,
     pub flag_test_filter: Option<String>,
+    pub flag_allow: Option<LintSet>,
+    pub flag_warn: Option<LintSet>,
+    pub flag_deny: Option<DenyList>,
 
     pub cmd_test: bool,
+    pub cmd_check: bool,
+    pub cmd_run: bool,
+    pub cmd_fmt: bool,
 }
 
 impl Args {
@@ -172,6 +200,38 @@ impl Args {
     pub fn compiler(&self) -> CompilerName {
         self.flag_compiler.unwrap_or(CompilerName::Baseline)
     }
+
+    pub fn denied_capabilities(&self) -> CapabilitySet {
+        self.flag_deny
+            .map(|deny| deny.capabilities)
+            .unwrap_or_default()
+    }
+
+    pub fn lint_level(&self, lint: Lint) -> LintLevel {
+        let contains = |set: Option<LintSet>| set.map_or(false, |set| set.contains(lint));
+
+        if self
+            .flag_deny
+            .map_or(false, |deny| deny.lints.contains(lint))
+        {
+            LintLevel::Deny
+        } else if contains(self.flag_warn) {
+            LintLevel::Warn
+        } else if contains(self.flag_allow) {
+            LintLevel::Allow
+        } else {
+            lint.default_level()
+        }
+    }
+
+    // `dora check` and `--check` stop after the semantic checks
+    pub fn check_only(&self) -> bool {
+        self.flag_check || self.cmd_check
+    }
+
+    pub fn error_format(&self) -> ErrorFormat {
+        self.flag_error_format.unwrap_or(ErrorFormat::Human)
+    }
 }
 
 impl Default for Args {
@@ -192,7 +252,9 @@ impl Default for Args {
             flag_emit_debug_entry: false,
             flag_enable_perf: false,
             flag_omit_bounds_check: false,
+            flag_null_safety: false,
             flag_version: false,
+            flag_explain: None,
             flag_asm_syntax: None,
             flag_gc_events: false,
             flag_gc_stress: false,
@@ -214,13 +276,20 @@ impl Default for Args {
             flag_code_size: None,
             flag_perm_size: None,
             flag_check: false,
+            flag_error_format: None,
             flag_disable_tlab: false,
             flag_disable_barrier: false,
             flag_stdlib: None,
             flag_boots: None,
             flag_test_filter: None,
+            flag_allow: None,
+            flag_warn: None,
+            flag_deny: None,
 
             cmd_test: false,
+            cmd_check: false,
+            cmd_run: false,
+            cmd_fmt: false,
         }
     }
 }
@@ -248,6 +317,41 @@ pub enum AsmSyntax {
     Att,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RustcDecodable)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+// `--deny` takes both capabilities and lints
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DenyList {
+    pub capabilities: CapabilitySet,
+    pub lints: LintSet,
+}
+
+impl DenyList {
+    pub fn parse(value: &str) -> Result<DenyList, String> {
+        let mut deny = DenyList::default();
+
+        for name in value.split(',').map(|name| name.trim()) {
+            if name.is_empty() {
+                continue;
+            }
+
+            if let Some(cap) = Capability::from_name(name) {
+                deny.capabilities.insert(cap);
+            } else if let Some(lint) = Lint::from_name(name) {
+                deny.lints.insert(lint);
+            } else {
+                return Err(format!("unknown capability or lint `{}`", name));
+            }
+        }
+
+        Ok(deny)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct MemSize(usize);
 
@@ -283,3 +387,17 @@ impl rustc_serialize::Decodable for MemSize {
         }
     }
 }
+
+impl rustc_serialize::Decodable for DenyList {
+    fn decode<D: rustc_serialize::Decoder>(d: &mut D) -> Result<DenyList, D::Error> {
+        let names = d.read_str()?;
+        DenyList::parse(&names).map_err(|msg| d.error(&msg))
+    }
+}
+
+impl rustc_serialize::Decodable for LintSet {
+    fn decode<D: rustc_serialize::Decoder>(d: &mut D) -> Result<LintSet, D::Error> {
+        let lints = d.read_str()?;
+        LintSet::parse(&lints).map_err(|msg| d.error(&msg))
+    }
+}
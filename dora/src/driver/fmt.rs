@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::driver::cmd::{self, ErrorFormat};
+use crate::driver::start::{parse_file, print_diagnostics_summary};
+use crate::vm::VM;
+use dora_parser::ast::{self, Ast};
+
+// Formats the given file or all files in the given directory. With `--check`
+// files are not changed but reported when they differ from their formatted
+// version.
+pub fn format(args: cmd::Args) -> i32 {
+    let arg_file = args.arg_file.clone();
+    let path = Path::new(&arg_file);
+    let mut files = Vec::new();
+
+    if arg_file == "-" || path.is_file() {
+        files.push(arg_file.clone());
+    } else if path.is_dir() {
+        collect_files(path, &mut files);
+        files.sort();
+    } else {
+        println!("file or directory `{}` does not exist.", &arg_file);
+        return 1;
+    }
+
+    let mut ast = Ast::new();
+    let empty = Ast::new();
+    let mut vm = VM::new(args, &empty);
+
+    for file in &files {
+        if let Err(code) = parse_file(file, &mut vm, &mut ast) {
+            return code;
+        }
+    }
+
+    // only files without syntax errors can be formatted
+    if vm.diag.lock().has_errors() {
+        vm.diag.lock().dump(&vm);
+
+        if vm.args.error_format() == ErrorFormat::Human {
+            print_diagnostics_summary(&vm);
+        }
+
+        return 1;
+    }
+
+    let mut unformatted = 0;
+
+    for (ast_file, file) in ast.files.iter().zip(&vm.files) {
+        let formatted = ast::format::format(ast_file, &file.content, &vm.interner);
+
+        if arg_file == "-" && !vm.args.flag_check {
+            print!("{}", formatted);
+        } else if formatted == file.content {
+            continue;
+        } else if vm.args.flag_check {
+            println!("{} is not formatted.", &file.name);
+            unformatted += 1;
+        } else if fs::write(&file.name, formatted).is_err() {
+            println!("unable to write file `{}`", &file.name);
+            return 1;
+        }
+    }
+
+    if unformatted > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+pub fn collect_files(dir: &Path, files: &mut Vec<String>) {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|e| e.path())
+            .collect(),
+        Err(_) => return,
+    };
+
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.extension().map_or(false, |ext| ext == "dora") {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_NAME: &str = "dora.toml";
+
+// The `dora.toml` of a package, e.g.:
+//
+//     [package]
+//     name = "app"
+//     main = "src/main.dora"
+//     sources = ["src"]
+//     tests = ["tests"]
+//
+//     [dependencies]
+//     geo = { path = "../geo" }
+//
+// All paths are relative to the directory of the manifest. Only this small
+// subset of TOML is understood.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    pub dir: PathBuf,
+    pub name: String,
+    pub main: Option<PathBuf>,
+    pub sources: Vec<PathBuf>,
+    pub tests: Vec<PathBuf>,
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+// returns the project directory if the given file or directory is a manifest or
+// a directory with a manifest, an empty path stands for the working directory
+pub fn find_project(path: &str) -> Option<PathBuf> {
+    let path = if path.is_empty() {
+        Path::new(".")
+    } else {
+        Path::new(path)
+    };
+
+    if path.is_dir() && path.join(MANIFEST_NAME).is_file() {
+        Some(path.to_path_buf())
+    } else if path.is_file() && path.file_name().map_or(false, |name| name == MANIFEST_NAME) {
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        if dir.as_os_str().is_empty() {
+            Some(PathBuf::from("."))
+        } else {
+            Some(dir.to_path_buf())
+        }
+    } else {
+        None
+    }
+}
+
+// loads the manifest in the given directory together with the manifests of all
+// its (transitive) dependencies, dependencies come before the packages that
+// use them and the project itself is the last element
+pub fn load_project(dir: &Path) -> Result<Vec<Manifest>, String> {
+    let mut packages = Vec::new();
+    let mut loaded = HashSet::new();
+    let mut active = Vec::new();
+
+    load_package(dir, None, &mut packages, &mut loaded, &mut active)?;
+
+    Ok(packages)
+}
+
+fn load_package(
+    dir: &Path,
+    expected_name: Option<&str>,
+    packages: &mut Vec<Manifest>,
+    loaded: &mut HashSet<PathBuf>,
+    active: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let canonical = fs::canonicalize(dir)
+        .map_err(|_| format!("package directory `{}` does not exist.", dir.display()))?;
+
+    if active.contains(&canonical) {
+        return Err(format!(
+            "dependency cycle detected at package `{}`.",
+            dir.display()
+        ));
+    }
+
+    if !loaded.insert(canonical.clone()) {
+        return Ok(());
+    }
+
+    let manifest = Manifest::load(dir)?;
+
+    if let Some(expected_name) = expected_name {
+        if manifest.name != expected_name {
+            return Err(format!(
+                "{}: package is named `{}` but the dependency is `{}`.",
+                dir.join(MANIFEST_NAME).display(),
+                manifest.name,
+                expected_name
+            ));
+        }
+    }
+
+    active.push(canonical);
+
+    for dependency in &manifest.dependencies {
+        load_package(
+            &dependency.path,
+            Some(&dependency.name),
+            packages,
+            loaded,
+            active,
+        )?;
+    }
+
+    active.pop();
+    packages.push(manifest);
+
+    Ok(())
+}
+
+impl Manifest {
+    pub fn load(dir: &Path) -> Result<Manifest, String> {
+        let path = dir.join(MANIFEST_NAME);
+
+        let content = fs::read_to_string(&path)
+            .map_err(|_| format!("unable to read file `{}`", path.display()))?;
+
+        Manifest::parse(dir, &content)
+            .map_err(|(line, msg)| format!("{}:{}: {}", path.display(), line, msg))
+    }
+
+    // parses the manifest content, errors contain the line number
+    pub fn parse(dir: &Path, content: &str) -> Result<Manifest, (usize, String)> {
+        let mut name = None;
+        let mut main = None;
+        let mut sources = None;
+        let mut tests = None;
+        let mut dependencies = Vec::new();
+        let mut section = None;
+
+        for (idx, line) in content.lines().enumerate() {
+            let lineno = idx + 1;
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                if !line.ends_with(']') {
+                    return Err((lineno, "expected `]`.".into()));
+                }
+
+                let name = line[1..line.len() - 1].trim();
+
+                section = match name {
+                    "package" => Some(Section::Package),
+                    "dependencies" => Some(Section::Dependencies),
+                    _ => return Err((lineno, format!("unknown section `{}`.", name))),
+                };
+
+                continue;
+            }
+
+            let eq = match line.find('=') {
+                Some(eq) => eq,
+                None => return Err((lineno, "expected `key = value`.".into())),
+            };
+
+            let key = line[..eq].trim();
+            let value = Value::parse(line[eq + 1..].trim()).map_err(|msg| (lineno, msg))?;
+
+            match section {
+                Some(Section::Package) => match key {
+                    "name" => name = Some(value.into_str(key).map_err(|msg| (lineno, msg))?),
+                    "main" => {
+                        let file = value.into_str(key).map_err(|msg| (lineno, msg))?;
+                        main = Some(dir.join(file));
+                    }
+                    "sources" => {
+                        let dirs = value.into_list(key).map_err(|msg| (lineno, msg))?;
+                        sources = Some(dirs.iter().map(|d| dir.join(d)).collect());
+                    }
+                    "tests" => {
+                        let dirs = value.into_list(key).map_err(|msg| (lineno, msg))?;
+                        tests = Some(dirs.iter().map(|d| dir.join(d)).collect());
+                    }
+                    _ => return Err((lineno, format!("unknown key `{}`.", key))),
+                },
+
+                Some(Section::Dependencies) => {
+                    let path = value.into_path(key).map_err(|msg| (lineno, msg))?;
+
+                    dependencies.push(Dependency {
+                        name: key.to_string(),
+                        path: dir.join(path),
+                    });
+                }
+
+                None => return Err((lineno, format!("key `{}` outside of section.", key))),
+            }
+        }
+
+        let name = match name {
+            Some(name) => name,
+            None => return Err((1, "missing `name` in section `[package]`.".into())),
+        };
+
+        Ok(Manifest {
+            dir: dir.to_path_buf(),
+            name,
+            main,
+            sources: sources.unwrap_or_else(|| vec![dir.join("src")]),
+            tests: tests.unwrap_or_default(),
+            dependencies,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Section {
+    Package,
+    Dependencies,
+}
+
+#[derive(Debug)]
+enum Value {
+    Str(String),
+    List(Vec<String>),
+    Table(Vec<(String, String)>),
+}
+
+impl Value {
+    // values are a string, a list of strings or an inline table of strings
+    fn parse(value: &str) -> Result<Value, String> {
+        if value.starts_with('[') {
+            let inner = enclosed(value, ']')?;
+            let mut items = Vec::new();
+
+            for item in split_items(inner) {
+                items.push(parse_str(item)?);
+            }
+
+            Ok(Value::List(items))
+        } else if value.starts_with('{') {
+            let inner = enclosed(value, '}')?;
+            let mut entries = Vec::new();
+
+            for entry in split_items(inner) {
+                let eq = match entry.find('=') {
+                    Some(eq) => eq,
+                    None => return Err("expected `key = value`.".into()),
+                };
+
+                let key = entry[..eq].trim().to_string();
+                let value = parse_str(entry[eq + 1..].trim())?;
+                entries.push((key, value));
+            }
+
+            Ok(Value::Table(entries))
+        } else {
+            Ok(Value::Str(parse_str(value)?))
+        }
+    }
+
+    fn into_str(self, key: &str) -> Result<String, String> {
+        match self {
+            Value::Str(value) => Ok(value),
+            _ => Err(format!("`{}` expects a string.", key)),
+        }
+    }
+
+    fn into_list(self, key: &str) -> Result<Vec<String>, String> {
+        match self {
+            Value::List(values) => Ok(values),
+            _ => Err(format!("`{}` expects a list of strings.", key)),
+        }
+    }
+
+    // dependencies are given as `name = { path = "..." }`
+    fn into_path(self, key: &str) -> Result<String, String> {
+        if let Value::Table(entries) = self {
+            if entries.len() == 1 && entries[0].0 == "path" {
+                return Ok(entries.into_iter().next().unwrap().1);
+            }
+        }
+
+        Err(format!(
+            "dependency `{}` expects `{{ path = \"...\" }}`.",
+            key
+        ))
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+
+    for (idx, ch) in line.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+fn enclosed(value: &str, close: char) -> Result<&str, String> {
+    if value.len() >= 2 && value.ends_with(close) {
+        Ok(&value[1..value.len() - 1])
+    } else {
+        Err(format!("expected `{}`.", close))
+    }
+}
+
+// splits at commas, a trailing comma is allowed
+fn split_items(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn parse_str(value: &str) -> Result<String, String> {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let value = &value[1..value.len() - 1];
+
+        if value.contains('"') || value.contains('\\') {
+            Err("escape sequences are not supported.".into())
+        } else {
+            Ok(value.to_string())
+        }
+    } else {
+        Err(format!("expected string but got `{}`.", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Manifest, (usize, String)> {
+        Manifest::parse(Path::new("app"), content)
+    }
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = parse(
+            "# application\n\
+             [package]\n\
+             name = \"app\" # the name\n\
+             main = \"src/main.dora\"\n\
+             sources = [\"src\", \"gen\",]\n\
+             tests = [\"tests\"]\n\
+             \n\
+             [dependencies]\n\
+             geo = { path = \"../geo\" }\n",
+        )
+        .unwrap();
+
+        assert_eq!("app", manifest.name);
+        assert_eq!(Some(PathBuf::from("app/src/main.dora")), manifest.main);
+        assert_eq!(
+            vec![PathBuf::from("app/src"), PathBuf::from("app/gen")],
+            manifest.sources
+        );
+        assert_eq!(vec![PathBuf::from("app/tests")], manifest.tests);
+        assert_eq!(
+            vec![Dependency {
+                name: "geo".into(),
+                path: PathBuf::from("app/../geo"),
+            }],
+            manifest.dependencies
+        );
+    }
+
+    #[test]
+    fn parse_manifest_defaults() {
+        let manifest = parse("[package]\nname = \"lib\"").unwrap();
+
+        assert_eq!(None, manifest.main);
+        assert_eq!(vec![PathBuf::from("app/src")], manifest.sources);
+        assert!(manifest.tests.is_empty());
+        assert!(manifest.dependencies.is_empty());
+    }
+
+    #[test]
+    fn parse_manifest_errors() {
+        assert_eq!(1, parse("[package]").unwrap_err().0);
+        assert_eq!(1, parse("name = \"app\"").unwrap_err().0);
+        assert_eq!(1, parse("[workspace]").unwrap_err().0);
+        assert_eq!(2, parse("[package]\nversion = \"1\"").unwrap_err().0);
+        assert_eq!(2, parse("[package]\nsources = \"src\"").unwrap_err().0);
+        assert_eq!(2, parse("[package]\nname = app").unwrap_err().0);
+        assert_eq!(
+            4,
+            parse("[package]\nname = \"app\"\n[dependencies]\ngeo = \"../geo\"")
+                .unwrap_err()
+                .0
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use std::fs;
 use std::path::Path;
 
+use crate::error;
 use crate::error::msg::SemError;
 use crate::vm::VM;
-use crate::vm::{Fct, FctId};
+use crate::vm::{Fct, FctId, FileId};
 use dora_parser::ast::{self, Ast};
 
-use crate::driver::cmd;
+use crate::driver::cmd::{self, ErrorFormat};
+use crate::driver::fmt;
+use crate::driver::manifest::{self, Manifest};
 use crate::object;
 use crate::timer::Timer;
 use dora_parser::lexer::reader::Reader;
@@ -25,18 +28,31 @@ pub fn start(content: Option<&str>) -> i32 {
         cmd::parse()
     };
 
+    start_with_args(args, content)
+}
+
+pub fn start_with_args(args: cmd::Args, content: Option<&str>) -> i32 {
     if args.flag_version {
         println!("dora v0.01b");
         return 0;
     }
 
+    if let Some(ref code) = args.flag_explain {
+        return explain(code);
+    }
+
+    if args.cmd_fmt {
+        return fmt::format(args);
+    }
+
     let mut ast = Ast::new();
     let empty = Ast::new();
     let mut vm = VM::new(args, &empty);
 
-    if let Err(code) = parse_all_files(&mut vm, &mut ast, content) {
-        return code;
-    }
+    let entry = match parse_all_files(&mut vm, &mut ast, content) {
+        Ok(entry) => entry,
+        Err(code) => return code,
+    };
 
     vm.ast = &ast;
 
@@ -51,29 +67,48 @@ pub fn start(content: Option<&str>) -> i32 {
     let main = if vm.args.cmd_test {
         None
     } else {
-        find_main(&vm)
+        match entry {
+            MainEntry::AnyFile => find_main(&vm, None),
+            MainEntry::File(file) => find_main(&vm, Some(file)),
+            MainEntry::Missing => None,
+        }
     };
 
-    if vm.diag.lock().has_errors() {
+    let has_errors = vm.diag.lock().has_errors();
+
+    if has_errors || vm.diag.lock().has_warnings() {
         vm.diag.lock().dump(&vm);
-        let no_errors = vm.diag.lock().errors().len();
 
-        if no_errors == 1 {
-            eprintln!("{} error found.", no_errors);
-        } else {
-            eprintln!("{} errors found.", no_errors);
+        // keep the json output free of anything but diagnostics
+        if vm.args.error_format() == ErrorFormat::Human {
+            print_diagnostics_summary(&vm);
         }
+    }
 
+    // warnings do not prevent running the program
+    if has_errors {
         return 1;
     }
 
     if !vm.args.cmd_test && main.is_none() {
-        println!("error: no `main` function found in the program");
+        if let MainEntry::Missing = entry {
+            // a library can be checked without a main entry
+            if vm.args.check_only() {
+                return 0;
+            }
+
+            eprintln!("error: no `main` entry given in `dora.toml`");
+        } else if vm.args.error_format() == ErrorFormat::Json {
+            eprintln!("{}", SemError::MainNotFound.to_json_without_location());
+        } else {
+            println!("error: no `main` function found in the program");
+        }
+
         return 1;
     }
 
     // if --check given, stop after type/semantic check
-    if vm.args.flag_check {
+    if vm.args.check_only() {
         return 0;
     }
 
@@ -98,7 +133,46 @@ pub fn start(content: Option<&str>) -> i32 {
     code
 }
 
-fn parse_all_files(vm: &mut VM, ast: &mut Ast, content: Option<&str>) -> Result<(), i32> {
+pub fn print_diagnostics_summary(vm: &VM) {
+    let diag = vm.diag.lock();
+    let no_warnings = diag.warnings().len();
+    let no_errors = diag.errors().len();
+
+    if no_warnings > 0 {
+        eprintln!(
+            "{} {} emitted.",
+            no_warnings,
+            plural(no_warnings, "warning")
+        );
+    }
+
+    if no_errors > 0 {
+        eprintln!("{} {} found.", no_errors, plural(no_errors, "error"));
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.into()
+    } else {
+        format!("{}s", word)
+    }
+}
+
+// where the `main` function is looked up
+#[derive(Copy, Clone)]
+enum MainEntry {
+    // a file or directory given on the command line
+    AnyFile,
+
+    // the main entry of the project manifest
+    File(FileId),
+
+    // a project without main entry, it can only be checked or tested
+    Missing,
+}
+
+fn parse_all_files(vm: &mut VM, ast: &mut Ast, content: Option<&str>) -> Result<MainEntry, i32> {
     let fuzzing = content.is_some();
 
     let stdlib_dir = vm.args.flag_stdlib.clone();
@@ -115,20 +189,146 @@ fn parse_all_files(vm: &mut VM, ast: &mut Ast, content: Option<&str>) -> Result<
         parse_dir(&boots, vm, ast)?;
     }
 
+    vm.trusted_files = vm.files.len();
+
     if fuzzing {
-        return parse_str(content.unwrap(), vm, ast);
+        parse_str(content.unwrap(), vm, ast)?;
+        return Ok(MainEntry::AnyFile);
     }
 
     let arg_file = vm.args.arg_file.clone();
+
+    // `run`, `test` and `check` without file use the working directory
+    if arg_file.is_empty() {
+        return match manifest::find_project(&arg_file) {
+            Some(dir) => parse_project(&dir, vm, ast),
+            None => {
+                println!(
+                    "no `{}` found in working directory.",
+                    manifest::MANIFEST_NAME
+                );
+                Err(1)
+            }
+        };
+    }
+
+    if let Some(dir) = manifest::find_project(&arg_file) {
+        return parse_project(&dir, vm, ast);
+    }
+
     let path = Path::new(&arg_file);
 
     if path.is_file() {
-        parse_file(&arg_file, vm, ast)
+        parse_file(&arg_file, vm, ast)?;
     } else if path.is_dir() {
-        parse_dir(&arg_file, vm, ast)
+        parse_dir(&arg_file, vm, ast)?;
     } else {
         println!("file or directory `{}` does not exist.", &arg_file);
-        Err(1)
+        return Err(1);
+    }
+
+    Ok(MainEntry::AnyFile)
+}
+
+// parses the source roots of the project and all its dependencies, test roots
+// are only parsed for the project itself and only with `dora test`
+fn parse_project(dir: &Path, vm: &mut VM, ast: &mut Ast) -> Result<MainEntry, i32> {
+    let packages = match manifest::load_project(dir) {
+        Ok(packages) => packages,
+        Err(msg) => {
+            println!("{}", msg);
+            return Err(1);
+        }
+    };
+
+    let project = packages.last().expect("project missing");
+
+    for package in &packages {
+        for root in &package.sources {
+            parse_root(root, vm, ast)?;
+        }
+    }
+
+    if vm.args.cmd_test {
+        for root in &project.tests {
+            parse_root(root, vm, ast)?;
+        }
+    }
+
+    match project.main {
+        Some(ref main) => find_main_file(project, main, vm, ast).map(MainEntry::File),
+        None => Ok(MainEntry::Missing),
+    }
+}
+
+// parses all files in the given directory and its subdirectories
+fn parse_root(root: &Path, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
+    if !root.is_dir() {
+        println!("directory `{}` does not exist.", root.display());
+        return Err(1);
+    }
+
+    let mut files = Vec::new();
+    fmt::collect_files(root, &mut files);
+
+    for file in files {
+        if should_file_be_parsed(Path::new(&file)) {
+            parse_file(&file, vm, ast)?;
+        }
+    }
+
+    Ok(())
+}
+
+// the main entry is usually part of a source root, otherwise it is parsed on
+// its own
+fn find_main_file(
+    project: &Manifest,
+    main: &Path,
+    vm: &mut VM,
+    ast: &mut Ast,
+) -> Result<FileId, i32> {
+    let canonical = match fs::canonicalize(main) {
+        Ok(path) => path,
+        Err(_) => {
+            println!(
+                "main entry `{}` of package `{}` does not exist.",
+                main.display(),
+                project.name
+            );
+            return Err(1);
+        }
+    };
+
+    let parsed = vm
+        .files
+        .iter()
+        .position(|file| fs::canonicalize(&file.name).map_or(false, |path| path == canonical));
+
+    let idx = match parsed {
+        Some(idx) => idx,
+        None => {
+            parse_file(main.to_str().unwrap(), vm, ast)?;
+            vm.files.len() - 1
+        }
+    };
+
+    Ok((idx as u32).into())
+}
+
+fn explain(code: &str) -> i32 {
+    let code = code.to_uppercase();
+
+    match error::explain::explain(&code) {
+        Some(text) => {
+            println!("{}\n\n{}", code, text);
+            0
+        }
+
+        None => {
+            println!("error code `{}` does not exist.", code);
+            1
+        }
     }
 }
 
@@ -242,7 +442,7 @@ fn parse_dir(dirname: &str, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
     }
 }
 
-fn should_file_be_parsed(path: &Path) -> bool {
+pub fn should_file_be_parsed(path: &Path) -> bool {
     if !path.is_file() {
         return false;
     }
@@ -262,7 +462,7 @@ fn should_file_be_parsed(path: &Path) -> bool {
     }
 }
 
-fn parse_file(filename: &str, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
+pub fn parse_file(filename: &str, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
     let reader = if filename == "-" {
         match Reader::from_input() {
             Ok(reader) => reader,
@@ -290,13 +490,17 @@ const STDLIB: &[(&str, &str)] = &include!(concat!(env!("OUT_DIR"), "/dora_stdlib
 
 pub fn parse_bundled_stdlib(vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
     for (filename, content) in STDLIB {
-        parse_bundled_stdlib_file(filename, content, vm, ast)?;
+        parse_named_str(filename, content, vm, ast)?;
     }
 
     Ok(())
 }
 
-fn parse_bundled_stdlib_file(
+pub fn parse_str(file: &str, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
+    parse_named_str("<<code>>", file, vm, ast)
+}
+
+pub fn parse_named_str(
     filename: &str,
     content: &str,
     vm: &mut VM,
@@ -306,39 +510,37 @@ fn parse_bundled_stdlib_file(
     parse_reader(reader, vm, ast)
 }
 
-fn parse_str(file: &str, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
-    let reader = Reader::from_string("<<code>>", file);
-    parse_reader(reader, vm, ast)
-}
-
 fn parse_reader(reader: Reader, vm: &mut VM, ast: &mut Ast) -> Result<(), i32> {
-    let filename: String = reader.path().into();
     let parser = Parser::new(reader, &vm.id_generator, ast, &mut vm.interner);
+    let (file, errors) = parser.parse();
 
-    match parser.parse() {
-        Ok(file) => {
-            vm.files.push(file);
-            assert_eq!(ast.files.len(), vm.files.len());
-            Ok(())
-        }
-
-        Err(error) => {
-            println!(
-                "error in {} at {}: {}",
-                filename,
-                error.pos,
-                error.error.message()
-            );
-            println!("error during parsing.");
+    let file_id: FileId = (vm.files.len() as u32).into();
+    vm.files.push(file);
+    assert_eq!(ast.files.len(), vm.files.len());
 
-            Err(1)
-        }
+    // parse errors are reported together with the errors of the semantic checks
+    for error in errors {
+        vm.diag
+            .lock()
+            .report(file_id, error.pos, SemError::ParseError(error.error));
     }
+
+    Ok(())
 }
 
-fn find_main<'ast>(vm: &VM<'ast>) -> Option<FctId> {
+fn find_main<'ast>(vm: &VM<'ast>, main_file: Option<FileId>) -> Option<FctId> {
     let name = vm.interner.intern("main");
-    let fctid = match vm.sym.lock().get_fct(name) {
+
+    // main can be declared in the namespace of any of the program's files,
+    // unless the manifest names the main entry
+    let fctid = match main_file {
+        Some(file) => vm.with_file_scope(file, || vm.sym.lock().get_fct(name)),
+        None => (vm.trusted_files..vm.files.len())
+            .map(|idx| FileId::from(idx as u32))
+            .find_map(|file| vm.with_file_scope(file, || vm.sym.lock().get_fct(name))),
+    };
+
+    let fctid = match fctid {
         Some(id) => id,
         None => {
             return None;
@@ -1,2 +1,5 @@
 pub mod diag;
+pub mod explain;
+pub mod lint;
 pub mod msg;
+pub mod suggest;
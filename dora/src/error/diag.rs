@@ -1,32 +1,89 @@
-use crate::error::msg::{SemError, SemErrorAndPos};
+use crate::driver::cmd::ErrorFormat;
+use crate::error::msg::{SemError, SemErrorAndPos, SemNote, Severity};
 use crate::vm::{FileId, VM};
 
-use dora_parser::lexer::position::Position;
+use dora_parser::lexer::position::{Position, Span};
 
 pub struct Diagnostic {
     errors: Vec<SemErrorAndPos>,
+    warnings: Vec<SemErrorAndPos>,
 }
 
 impl Diagnostic {
     pub fn new() -> Diagnostic {
-        Diagnostic { errors: Vec::new() }
+        Diagnostic {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
     }
 
     pub fn errors(&self) -> &[SemErrorAndPos] {
         &self.errors
     }
 
+    pub fn warnings(&self) -> &[SemErrorAndPos] {
+        &self.warnings
+    }
+
     pub fn report(&mut self, file: FileId, pos: Position, msg: SemError) {
         self.errors.push(SemErrorAndPos::new(file, pos, msg));
     }
 
+    pub fn report_span(&mut self, file: FileId, pos: Position, span: Span, msg: SemError) {
+        let mut error = SemErrorAndPos::new(file, pos, msg);
+        error.span = Some(span);
+        self.errors.push(error);
+    }
+
+    pub fn report_with_notes(
+        &mut self,
+        file: FileId,
+        pos: Position,
+        msg: SemError,
+        notes: Vec<SemNote>,
+    ) {
+        let mut error = SemErrorAndPos::new(file, pos, msg);
+        error.notes = notes;
+        self.errors.push(error);
+    }
+
+    // lints are reported as warnings or as errors when denied
+    pub fn report_lint(
+        &mut self,
+        file: FileId,
+        pos: Position,
+        span: Option<Span>,
+        severity: Severity,
+        msg: SemError,
+    ) {
+        let mut error = SemErrorAndPos::new(file, pos, msg);
+        error.span = span;
+        error.severity = severity;
+
+        match severity {
+            Severity::Error => self.errors.push(error),
+            Severity::Warning => self.warnings.push(error),
+        }
+    }
+
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
 
+    pub fn has_sem_errors(&self) -> bool {
+        self.errors.iter().any(|err| !err.msg.is_parse_error())
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
     pub fn dump(&self, vm: &VM) {
-        for err in &self.errors {
-            eprintln!("{}", &err.message(vm));
+        for err in self.warnings.iter().chain(&self.errors) {
+            match vm.args.error_format() {
+                ErrorFormat::Human => eprintln!("{}", &err.render(vm)),
+                ErrorFormat::Json => eprintln!("{}", &err.to_json(vm)),
+            }
         }
     }
 }
@@ -0,0 +1,773 @@
+// Long-form explanations of the error codes, printed by `dora --explain <code>`.
+// Codes E0001-E0099 are parse errors, codes from E0100 on are semantic errors.
+// Codes are stable: never reuse or renumber a code, only append new ones.
+
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|&&(name, _)| name == code)
+        .map(|&(_, text)| text)
+}
+
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "E0001",
+        "The source contains a character that is not part of Dora's syntax, e.g. `$` or `#` outside of a string or comment.\n\nRemove the character or move it into a string or char literal.",
+    ),
+    (
+        "E0002",
+        "A block comment started with `/*` is never closed with `*/`.\n\nAdd the missing `*/` at the end of the comment.",
+    ),
+    (
+        "E0003",
+        "A string literal is missing its closing `\"`.\n\nAdd the closing quote. Use `\\\"` to put a quote inside a string.",
+    ),
+    (
+        "E0004",
+        "A char literal is missing its closing `'` or contains more than one character.\n\nA char literal holds exactly one character, e.g. `'a'` or `'\\n'`. Use a string for longer text.",
+    ),
+    (
+        "E0005",
+        "A backslash in a string or char literal is followed by a character that is not a known escape.\n\nSupported escapes are `\\\\`, `\\n`, `\\t`, `\\r`, `\\0`, `\\\"`, `\\'` and `\\$`.",
+    ),
+    (
+        "E0006",
+        "Only declarations are allowed at the top level of a file: `fun`, `class`, `struct`, `trait`, `impl`, `module`, `enum`, `type`, `const`, `let` and `var`.\n\nStatements and expressions have to be placed inside a function body.",
+    ),
+    (
+        "E0007",
+        "The annotation after `@` is not known.\n\nCheck the spelling, valid annotations are for example `@open`, `@override`, `@abstract`, `@static` and `@test`.",
+    ),
+    (
+        "E0008",
+        "The same annotation is given more than once for a declaration.\n\nRemove the duplicate annotation.",
+    ),
+    (
+        "E0009",
+        "The annotation is not allowed on this kind of declaration, e.g. `@override` on a top-level function.\n\nRemove the annotation or move it to a declaration that supports it.",
+    ),
+    (
+        "E0010",
+        "The parser expected a specific token, e.g. a `;` at the end of a statement or a closing `)`, but found something else.\n\nInsert the expected token. The error position often points just after the actual mistake.",
+    ),
+    (
+        "E0011",
+        "A type was expected, e.g. after `:` in a parameter or variable declaration or after `->` in a function signature.\n\nWrite a type like `Int`, `String`, `Array[Int]` or `(Int) -> Bool`.",
+    ),
+    (
+        "E0012",
+        "An `else` appears without a preceding `if`.\n\nCheck that the `if` block is not closed too early and that there is no `;` between the `if` block and `else`.",
+    ),
+    (
+        "E0013",
+        "An expression was expected, but the parser found a token that cannot start an expression, e.g. `let x = ;`.\n\nComplete the expression or remove the dangling operator.",
+    ),
+    (
+        "E0014",
+        "The number literal is too large to be represented.\n\nUse a smaller literal or a wider type suffix like `L` for `Long`.",
+    ),
+    (
+        "E0015",
+        "A `${` inside a string template is never closed with `}`.\n\nClose the embedded expression with `}` before the end of the string.",
+    ),
+    (
+        "E0016",
+        "A name was expected here, e.g. the name of a function, class, parameter or variable.\n\nNames start with a letter or `_`. Keywords like `class` or `fun` cannot be used as names.",
+    ),
+    (
+        "E0017",
+        "A `try` block needs at least one `catch` block or a `finally` block.\n\nAdd `catch e: Exception { ... }` or `finally { ... }` after the `try` block.",
+    ),
+    (
+        "E0018",
+        "A pattern was expected in a `match` arm.\n\nPatterns are literals, enum variants like `Foo::Bar(x)` or the wildcard `_`.",
+    ),
+    (
+        "E0019",
+        "A `package` declaration appears after other declarations or twice in the same file.\n\nA file has at most one `package a::b;` and it has to come first, before all imports and declarations.",
+    ),
+    (
+        "E0020",
+        "An `import` appears after a declaration.\n\nMove all imports to the start of the file, directly after the `package` declaration.",
+    ),
+    (
+        "E0100",
+        "The program uses a language feature that is not implemented yet.\n\nRewrite the code without this feature.",
+    ),
+    (
+        "E0101",
+        "The class is not defined.\n\nCheck the spelling and make sure the class is declared in one of the compiled files.",
+    ),
+    (
+        "E0102",
+        "The type is not defined.\n\nCheck the spelling. Types are classes, structs, traits, enums or type parameters that are in scope.",
+    ),
+    (
+        "E0103",
+        "No variable, parameter, function, global or constant with this name is in scope.\n\nCheck the spelling and that the variable is declared before it is used. Fields of the current class have to be accessed with `self.name`.",
+    ),
+    (
+        "E0104",
+        "The struct is not defined.\n\nCheck the spelling and make sure the struct is declared.",
+    ),
+    (
+        "E0105",
+        "No function with this name is defined.\n\nCheck the spelling and make sure the function is declared at the top level or in the module used for the call.",
+    ),
+    (
+        "E0106",
+        "The type has no field with this name.\n\nCheck the spelling of the field and the type of the receiver expression.",
+    ),
+    (
+        "E0107",
+        "The class has no method with this name that accepts the given argument types.\n\nCheck the method name, the number of arguments and their types.",
+    ),
+    (
+        "E0108",
+        "The enum has no variant with this name.\n\nCheck the spelling of the variant.",
+    ),
+    (
+        "E0109",
+        "More than one method matches the call, so the call is ambiguous.\n\nThis usually happens when several traits implemented for the class define a method with the same name.",
+    ),
+    (
+        "E0110",
+        "Only the last parameter of a function can be variadic (`x: Int...`).\n\nMove the variadic parameter to the end of the parameter list.",
+    ),
+    (
+        "E0111",
+        "None of the trait bounds of the type parameter defines a method with this name and these argument types.\n\nAdd a trait bound that provides the method, e.g. `T: Equals`.",
+    ),
+    (
+        "E0112",
+        "Several trait bounds of the type parameter define a method matching this call, so the call is ambiguous.",
+    ),
+    (
+        "E0113",
+        "Several trait bounds of the type parameter define a static method matching this call, so the call is ambiguous.",
+    ),
+    (
+        "E0114",
+        "None of the trait bounds of the type parameter defines a static method with this name.\n\nAdd a trait bound that provides the static method.",
+    ),
+    (
+        "E0115",
+        "The class has no static method with this name that accepts the given argument types.\n\nCheck the name and the arguments. Instance methods have to be called on an object.",
+    ),
+    (
+        "E0116",
+        "The class has no constructor that accepts the given argument types.\n\nCheck the number and types of the arguments against the class declaration.",
+    ),
+    (
+        "E0117",
+        "A method with the same name is already defined for this type. Dora does not support overloading methods by parameter types.\n\nRename one of the methods.",
+    ),
+    (
+        "E0118",
+        "`nil` can only be used for reference types like classes, not for value types like `Int` or `Bool`.",
+    ),
+    (
+        "E0119",
+        "The name is already defined in this scope.\n\nRename one of the definitions.",
+    ),
+    (
+        "E0120",
+        "The declaration uses the name of an existing function.\n\nRename one of them.",
+    ),
+    (
+        "E0121",
+        "The declaration uses the name of a parameter of the same function.\n\nRename one of them.",
+    ),
+    (
+        "E0122",
+        "The declaration uses the name of an existing class.\n\nRename one of them.",
+    ),
+    (
+        "E0123",
+        "The declaration uses the name of an existing class, whose constructor is already defined under that name.\n\nRename one of them.",
+    ),
+    (
+        "E0124",
+        "The declaration uses the name of an existing struct.\n\nRename one of them.",
+    ),
+    (
+        "E0125",
+        "The declaration uses the name of an existing struct, whose constructor is already defined under that name.\n\nRename one of them.",
+    ),
+    (
+        "E0126",
+        "The declaration uses the name of an existing trait.\n\nRename one of them.",
+    ),
+    (
+        "E0127",
+        "A field with this name already exists in the class, module or struct.\n\nRename one of the fields.",
+    ),
+    (
+        "E0128",
+        "The declaration uses the name of an existing global variable.\n\nRename one of them.",
+    ),
+    (
+        "E0129",
+        "The declaration uses the name of an existing constant.\n\nRename one of them.",
+    ),
+    (
+        "E0130",
+        "The declaration uses the name of an existing module.\n\nRename one of them.",
+    ),
+    (
+        "E0131",
+        "The declaration uses the name of an existing enum.\n\nRename one of them.",
+    ),
+    (
+        "E0132",
+        "The enum declares the same variant twice.\n\nRemove or rename the duplicate variant.",
+    ),
+    (
+        "E0133",
+        "The left-hand side of the assignment cannot be assigned to.\n\nOnly variables, fields and array elements can be assigned.",
+    ),
+    (
+        "E0134",
+        "An enum needs at least one variant.",
+    ),
+    (
+        "E0135",
+        "The type of the variable cannot be determined.\n\nGive the variable a type annotation (`let x: Int;`) or initialize it with an expression.",
+    ),
+    (
+        "E0136",
+        "The argument types of the call do not match the parameter types of the function.\n\nCheck the number, order and types of the arguments.",
+    ),
+    (
+        "E0137",
+        "The condition of a `while` loop has to be of type `Bool`.\n\nCompare the value explicitly, e.g. `while x != 0 { ... }`.",
+    ),
+    (
+        "E0138",
+        "The condition of an `if` has to be of type `Bool`.\n\nCompare the value explicitly, e.g. `if x != 0 { ... }`.",
+    ),
+    (
+        "E0139",
+        "The returned value does not match the return type declared in the function signature.\n\nChange the returned expression or the declared return type.",
+    ),
+    (
+        "E0140",
+        "The left-hand side of the assignment is not something that can be assigned to.\n\nOnly variables, fields and array elements can be assigned.",
+    ),
+    (
+        "E0141",
+        "The type of the assigned value does not match the declared type of the variable.\n\nConvert the value or change the variable's type.",
+    ),
+    (
+        "E0142",
+        "The type of the assigned value does not match the declared type of the field.\n\nConvert the value or change the field's type.",
+    ),
+    (
+        "E0143",
+        "The unary operator is not defined for values of this type, e.g. `!` on an `Int`.",
+    ),
+    (
+        "E0144",
+        "The binary operator is not defined for these operand types.\n\nBoth operands usually need the same type, convert one of them explicitly, e.g. with `toLong()`.",
+    ),
+    (
+        "E0145",
+        "A `const` needs a value that can be computed at compile time, e.g. a literal.",
+    ),
+    (
+        "E0146",
+        "`break` and `continue` can only be used inside `while` and `for` loops.",
+    ),
+    (
+        "E0147",
+        "The function declares a return type, but not every code path ends with a `return` or a value.\n\nAdd a `return` at the end of the function or make the last expression of the body produce the value.",
+    ),
+    (
+        "E0148",
+        "The program has no `main` function.\n\nAdd `fun main() { ... }` to one of the files.",
+    ),
+    (
+        "E0149",
+        "`main` must not take parameters and has to return either nothing or `Int`.\n\nUse `fun main() { ... }` or `fun main() -> Int { ... }`.",
+    ),
+    (
+        "E0150",
+        "`self` refers to the current object and is therefore only available inside methods.",
+    ),
+    (
+        "E0151",
+        "The type `Self` is only available inside traits.",
+    ),
+    (
+        "E0152",
+        "`super` is only available in methods of classes that have a parent class.",
+    ),
+    (
+        "E0153",
+        "`super` can only be used to call a method of the parent class, e.g. `super.toString()`.",
+    ),
+    (
+        "E0154",
+        "Only reference types like classes can be used here, value types like `Int` or `Bool` are not allowed.",
+    ),
+    (
+        "E0155",
+        "A `let` binding cannot be assigned later, so it has to be initialized where it is declared.\n\nAdd an initializer or use `var` instead.",
+    ),
+    (
+        "E0156",
+        "A `let` binding cannot be changed after initialization.\n\nDeclare it with `var` if the value needs to change.",
+    ),
+    (
+        "E0157",
+        "A function name cannot be assigned a new value.",
+    ),
+    (
+        "E0158",
+        "A class name cannot be assigned a new value.",
+    ),
+    (
+        "E0159",
+        "A type parameter cannot be assigned a value.",
+    ),
+    (
+        "E0160",
+        "A function name was used as a value. Functions need to be called with `()`.",
+    ),
+    (
+        "E0161",
+        "A class name was used as a value. Create an instance by calling the class like a function, e.g. `Foo()`.",
+    ),
+    (
+        "E0162",
+        "A module name was used as a value. Access the module's members with `Module::member()`.",
+    ),
+    (
+        "E0163",
+        "A type parameter was used as a value. Type parameters can only be used as types.",
+    ),
+    (
+        "E0164",
+        "An enum name was used as a value. Use one of its variants, e.g. `Color::Red`.",
+    ),
+    (
+        "E0165",
+        "A type parameter cannot be called. Call a static method of one of its trait bounds instead, e.g. `T::default()`.",
+    ),
+    (
+        "E0166",
+        "The class cannot be used as a parent class.\n\nMark the parent class with `@open` to allow subclasses. Built-in types cannot be subclassed.",
+    ),
+    (
+        "E0167",
+        "The class hierarchy contains a cycle, e.g. `A` extends `B` and `B` extends `A`.",
+    ),
+    (
+        "E0168",
+        "The method is marked with `@override`, but the parent class has no method with that name to override.\n\nRemove the annotation or check the method name.",
+    ),
+    (
+        "E0169",
+        "The method is marked with `@open`, but the class itself does not allow subclasses.\n\nMark the class `@open` or remove the annotation.",
+    ),
+    (
+        "E0170",
+        "The method overrides a method of the parent class and therefore has to be marked with `@override`.",
+    ),
+    (
+        "E0171",
+        "The method of the parent class cannot be overridden.\n\nMark it with `@open` in the parent class.",
+    ),
+    (
+        "E0172",
+        "The two types are not compatible with each other.",
+    ),
+    (
+        "E0173",
+        "An overriding method needs the same return type as the method it overrides.",
+    ),
+    (
+        "E0174",
+        "An overriding method needs the same parameter types as the method it overrides.",
+    ),
+    (
+        "E0175",
+        "A declaration is marked `@internal`, but the VM does not provide an implementation for it. This can only happen in the standard library.",
+    ),
+    (
+        "E0176",
+        "A block comment started with `/*` is never closed with `*/`.",
+    ),
+    (
+        "E0177",
+        "The source contains a character that is not part of Dora's syntax.",
+    ),
+    (
+        "E0178",
+        "A char literal is missing its closing `'`.",
+    ),
+    (
+        "E0179",
+        "A string literal is missing its closing `\"`.",
+    ),
+    (
+        "E0180",
+        "The literal does not fit into its type.\n\nUse a smaller value or a wider type, e.g. the suffix `L` for `Long`.",
+    ),
+    (
+        "E0181",
+        "A class name was expected here.",
+    ),
+    (
+        "E0182",
+        "An expression was expected here.",
+    ),
+    (
+        "E0183",
+        "The parser expected a different token here.",
+    ),
+    (
+        "E0184",
+        "Only declarations are allowed at the top level of a file.",
+    ),
+    (
+        "E0185",
+        "A trait name was expected here, e.g. in an `impl Trait for Class` block or a trait bound.",
+    ),
+    (
+        "E0186",
+        "A type was expected here.",
+    ),
+    (
+        "E0187",
+        "A name was expected here.",
+    ),
+    (
+        "E0188",
+        "Values embedded into a string template with `${...}` need to implement the trait `Stringable`.\n\nImplement `Stringable` for the type or convert the value to a `String` first.",
+    ),
+    (
+        "E0189",
+        "A name was expected here.",
+    ),
+    (
+        "E0190",
+        "An `else` appears without a preceding `if`.",
+    ),
+    (
+        "E0191",
+        "A source file could not be read.\n\nCheck that the file exists and is readable.",
+    ),
+    (
+        "E0192",
+        "Only fields, methods and initializer statements are allowed in a class body.",
+    ),
+    (
+        "E0193",
+        "The annotation is not allowed on this kind of declaration.",
+    ),
+    (
+        "E0194",
+        "The same annotation is given more than once.",
+    ),
+    (
+        "E0195",
+        "The annotation is not known.",
+    ),
+    (
+        "E0196",
+        "A backslash in a string or char literal is followed by an unknown escape character.",
+    ),
+    (
+        "E0197",
+        "The function needs a body. Only `@internal` functions, abstract methods and trait methods may omit it.",
+    ),
+    (
+        "E0198",
+        "A function call was expected here.",
+    ),
+    (
+        "E0199",
+        "A constructor delegation has to call either `self(...)` or `super(...)`.",
+    ),
+    (
+        "E0200",
+        "A class with a primary constructor passes arguments to its parent class in the class header, e.g. `class B(x: Int): A(x)`, and not with `super(...)`.",
+    ),
+    (
+        "E0201",
+        "The class has no parent class, so there is nothing to delegate to with `super`.",
+    ),
+    (
+        "E0202",
+        "A struct cannot contain itself as a field, directly or indirectly, since its size would be infinite.\n\nUse a class for recursive data.",
+    ),
+    (
+        "E0204",
+        "The type is generic and needs type arguments, e.g. `Array[Int]`.",
+    ),
+    (
+        "E0205",
+        "Two type parameters of the same declaration have the same name.\n\nRename one of them.",
+    ),
+    (
+        "E0206",
+        "The `impl` block defines a static method that the trait does not declare.\n\nRemove the method or add it to the trait.",
+    ),
+    (
+        "E0207",
+        "The `impl` block defines a method that the trait does not declare.\n\nRemove the method or add it to the trait.",
+    ),
+    (
+        "E0208",
+        "The trait declares a static method that the `impl` block does not define.\n\nAdd the missing static method to the `impl` block.",
+    ),
+    (
+        "E0209",
+        "The trait declares a method that the `impl` block does not define.\n\nAdd the missing method to the `impl` block.",
+    ),
+    (
+        "E0210",
+        "The number of type arguments does not match the number of type parameters of the declaration.",
+    ),
+    (
+        "E0211",
+        "A class was expected here.",
+    ),
+    (
+        "E0212",
+        "Only classes can be used as type arguments here.",
+    ),
+    (
+        "E0213",
+        "Constants cannot be assigned a new value.\n\nUse a global `var` if the value needs to change.",
+    ),
+    (
+        "E0214",
+        "A type parameter bound needs to be a class or a trait.",
+    ),
+    (
+        "E0215",
+        "The type is not generic and cannot be used with type arguments.",
+    ),
+    (
+        "E0216",
+        "The same trait is listed twice in the bounds of a type parameter.\n\nRemove the duplicate bound.",
+    ),
+    (
+        "E0217",
+        "The type argument does not implement a trait that the type parameter requires.\n\nImplement the trait for the type with `impl Trait for Type { ... }`.",
+    ),
+    (
+        "E0218",
+        "Abstract methods are only allowed in classes marked `@abstract`.",
+    ),
+    (
+        "E0219",
+        "An abstract method cannot have a body.\n\nRemove the body or the `@abstract` annotation.",
+    ),
+    (
+        "E0220",
+        "Abstract classes cannot be instantiated. Instantiate a subclass that implements all abstract methods instead.",
+    ),
+    (
+        "E0221",
+        "The class does not override an abstract method of its parent class.\n\nImplement the method with `@override` or mark the class `@abstract`.",
+    ),
+    (
+        "E0222",
+        "Static methods cannot be overridden, so `@open`, `@override` and `@final` are not allowed on them.",
+    ),
+    (
+        "E0223",
+        "Global variables cannot have an initializer yet.\n\nAssign the value at the beginning of `main` instead.",
+    ),
+    (
+        "E0224",
+        "A `for` loop needs a value that has a `makeIterator()` method or implements `Iterator`.",
+    ),
+    (
+        "E0225",
+        "The value returned by `makeIterator()` needs to implement the trait `Iterator`.",
+    ),
+    (
+        "E0226",
+        "The struct has no field with this name.",
+    ),
+    (
+        "E0227",
+        "All fields of a struct need to be initialized in a struct literal.",
+    ),
+    (
+        "E0228",
+        "The left-hand side of `::` needs to be a class, module or enum.",
+    ),
+    (
+        "E0229",
+        "Type arguments can only be given to classes and functions.",
+    ),
+    (
+        "E0230",
+        "The name of a static method is expected after `::`.",
+    ),
+    (
+        "E0231",
+        "When an `if` is used as an expression, both branches need to produce values of the same type.",
+    ),
+    (
+        "E0232",
+        "A field or method name is expected after `.`.",
+    ),
+    (
+        "E0233",
+        "A tuple element is accessed with a number, e.g. `tuple.0`.",
+    ),
+    (
+        "E0234",
+        "The tuple has fewer elements than the index requires. Indices start at 0.",
+    ),
+    (
+        "E0235",
+        "Only objects of classes that inherit from `Stacktrace` (e.g. `Exception`) can be thrown or caught.",
+    ),
+    (
+        "E0236",
+        "The enum is not defined.\n\nCheck the spelling of the enum name.",
+    ),
+    (
+        "E0237",
+        "The arguments do not match the payload types declared for the enum variant.",
+    ),
+    (
+        "E0238",
+        "`match` only supports enums, integers, chars, booleans and strings.",
+    ),
+    (
+        "E0239",
+        "The pattern has a different type than the value that is matched.",
+    ),
+    (
+        "E0240",
+        "The pattern binds a different number of values than the enum variant carries.",
+    ),
+    (
+        "E0241",
+        "All arms of a `match` expression need to produce values of the same type.",
+    ),
+    (
+        "E0242",
+        "A `match` needs to cover every possible value.\n\nAdd arms for the missing variants or a wildcard arm `_ => ...`.",
+    ),
+    (
+        "E0243",
+        "The function needs a capability that was denied with `--deny`. Calls are rejected wherever they appear in the program, including code that is never executed.\n\nRun the program without denying the capability or remove the call.",
+    ),
+    (
+        "E0244",
+        "Lambdas capture variables by value, so a captured variable cannot be assigned inside the lambda.\n\nStore the value in an object field if it needs to change.",
+    ),
+    (
+        "E0245",
+        "The arguments of the call do not match the parameter types of the lambda.",
+    ),
+    (
+        "E0246",
+        "A local variable is declared but never used.\n\nRemove the variable or prefix its name with `_`. This is a warning of the lint `unused-variable`.",
+    ),
+    (
+        "E0247",
+        "A parameter is never used in the function body.\n\nRemove the parameter or prefix its name with `_`. This is a warning of the lint `unused-variable`.",
+    ),
+    (
+        "E0248",
+        "A function or non-`pub` method is never called.\n\nRemove the function or call it. This is a warning of the lint `unused-function`.",
+    ),
+    (
+        "E0249",
+        "The code follows a `return`, `break`, `continue` or `throw` and is never executed.\n\nRemove the code. This is a warning of the lint `unreachable-code`.",
+    ),
+    (
+        "E0250",
+        "An expression statement computes a value that is thrown away.\n\nStore the value in a variable or remove the statement. This is a warning of the lint `unused-result`, which is only reported with `--warn=unused-result`.",
+    ),
+    (
+        "E0251",
+        "The declaration uses the name of an existing type alias.\n\nRename one of them.",
+    ),
+    (
+        "E0252",
+        "The type alias expands to a type that contains the alias itself, e.g. `type A = Array[A];` or `type A = B; type B = A;`.\n\nAliases are only names for existing types and cannot define recursive types. Use a class for those.",
+    ),
+    (
+        "E0253",
+        "A type parameter of a type alias has trait bounds, e.g. `type Map[K: Hash] = HashMap[K, Int];`.\n\nRemove the bounds, they are checked on the aliased type when the alias is used.",
+    ),
+    (
+        "E0254",
+        "A field is given more than once in a struct literal, e.g. `Foo { a: 1, a: 2 }`.",
+    ),
+    (
+        "E0255",
+        "A struct name was used as a value. Create a value with a struct literal, e.g. `Foo { a: 1 }`.",
+    ),
+    (
+        "E0256",
+        "The path of an `import` does not name a namespace.\n\nNamespaces are declared with `package a::b;` at the start of a file. Check the spelling and that the file is part of the compiled program.",
+    ),
+    (
+        "E0257",
+        "The imported name is not declared in the namespace.\n\nCheck the spelling and that the declaration is in a file with the same `package` declaration.",
+    ),
+    (
+        "E0258",
+        "Only declarations marked `@pub` can be imported from another namespace.\n\nAdd `@pub` to the declaration, e.g. `@pub class Foo`.",
+    ),
+    (
+        "E0259",
+        "The declaration is `@internal`, its implementation is provided by the VM. Only the stdlib and boots files may import it.",
+    ),
+    (
+        "E0260",
+        "The imported name is already used by a declaration of the file's namespace or by another import.\n\nRemove one of the imports or rename the declaration.",
+    ),
+    (
+        "E0261",
+        "A `var` declared without initializer is read on a path where it was not assigned yet.\n\nInitialize the variable where it is declared or assign it in every branch before the read.",
+    ),
+    (
+        "E0262",
+        "A `let` field without initializer has to be assigned exactly once by the constructor, on every path through the class body.\n\nAssign the field in every branch or add an initializer to its declaration. With `--null-safety` the constructor also has to assign every `var` field of a non-nullable class type, since it would be nil otherwise.",
+    ),
+    (
+        "E0263",
+        "Only class types can be made nullable with `?`, values of other types can never be nil.",
+    ),
+    (
+        "E0264",
+        "With `--null-safety` a value of a nullable type `T?` might be nil, so its fields and methods cannot be used directly.\n\nStore the value in a `let` binding and compare it with nil first, e.g. `if x !== nil { x.foo(); }`, or check its class with `is`.",
+    ),
+    (
+        "E0265",
+        "With `--null-safety` the elements of `Array[T](len)` start out as nil, which a non-nullable class type `T` does not allow. This also applies to a type param `T` since it can be instantiated with a class.\n\nUse `arrayFill[T](len, value)` to create the array with an initial element or, for a class, make the element type nullable with `Array[T?](len)`.",
+    ),
+    (
+        "E0266",
+        "Type arguments of generic functions and constructors can be left out when they follow from the arguments of the call or from the type the result is assigned to, e.g. `let a: Array[Int] = arrayEmpty();`.\n\nIf a type param occurs neither in the parameters nor in the expected type, pass the type arguments explicitly: `arrayEmpty[Int]()`.",
+    ),
+    (
+        "E0267",
+        "A trait can be used as a type, its values are objects of any class implementing the trait. A method taking `Self` cannot be called on such a value: the argument would need to be an instance of the receiver's class, which is only known at runtime.\n\nCall the method on a value of the class or through a type param bound like `T: Trait` instead.",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::explain;
+
+    #[test]
+    fn explain_codes() {
+        assert!(explain("E0001").is_some());
+        assert!(explain("E0139").is_some());
+        assert!(explain("E9999").is_none());
+    }
+}
@@ -0,0 +1,106 @@
+// lints are semantic checks that report warnings instead of errors, their
+// level can be changed with `--allow`, `--warn` and `--deny=<lint,...>`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedFunction,
+    UnreachableCode,
+    UnusedResult,
+}
+
+impl Lint {
+    pub fn all() -> &'static [Lint] {
+        &[
+            Lint::UnusedVariable,
+            Lint::UnusedFunction,
+            Lint::UnreachableCode,
+            Lint::UnusedResult,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedFunction => "unused-function",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::UnusedResult => "unused-result",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::all().iter().find(|lint| lint.name() == name).cloned()
+    }
+
+    pub fn default_level(self) -> LintLevel {
+        match self {
+            // too noisy for code that calls methods for their side effects
+            Lint::UnusedResult => LintLevel::Allow,
+            _ => LintLevel::Warn,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LintSet(u8);
+
+impl LintSet {
+    pub fn empty() -> LintSet {
+        LintSet(0)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, lint: Lint) {
+        self.0 |= lint.bit();
+    }
+
+    pub fn contains(self, lint: Lint) -> bool {
+        self.0 & lint.bit() != 0
+    }
+
+    pub fn parse(value: &str) -> Result<LintSet, String> {
+        let mut set = LintSet::empty();
+
+        for name in value.split(',').map(|name| name.trim()) {
+            if name.is_empty() {
+                continue;
+            }
+
+            match Lint::from_name(name) {
+                Some(lint) => set.insert(lint),
+                None => return Err(format!("unknown lint `{}`", name)),
+            }
+        }
+
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lints() {
+        let set = LintSet::parse("unused-variable,unreachable-code").unwrap();
+        assert!(set.contains(Lint::UnusedVariable));
+        assert!(set.contains(Lint::UnreachableCode));
+        assert!(!set.contains(Lint::UnusedResult));
+
+        assert!(LintSet::parse("").unwrap().is_empty());
+        assert!(LintSet::parse("unused-variable,foo").is_err());
+    }
+}
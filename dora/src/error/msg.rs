@@ -1,5 +1,10 @@
+use rustc_serialize::json;
+use std::cmp::{max, min};
+
 use crate::vm::{FileId, VM};
-use dora_parser::lexer::position::Position;
+use dora_parser::error::ParseError;
+use dora_parser::lexer::position::{Position, Span};
+use dora_parser::lexer::File;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum SemError {
@@ -106,7 +111,6 @@ pub enum SemError {
     NoSuperDelegationWithPrimaryCtor(String),
     NoSuperClass(String),
     RecursiveStructure,
-    TraitMethodWithBody,
     TypeParamsExpected,
     TypeParamNameNotUnique(String),
     StaticMethodNotInTrait(String, String, Vec<String>),
@@ -138,6 +142,40 @@ pub enum SemError {
     NameExpected,
     IndexExpected,
     IllegalTupleIndex(u64, String),
+    ExceptionTypeExpected(String),
+    UnknownEnum(String),
+    EnumArgsIncompatible(String, String, Vec<String>, Vec<String>),
+    MatchTypeUnsupported(String),
+    MatchPatternTypeMismatch(String, String),
+    MatchPatternWrongNumberOfParams(String, usize, usize),
+    MatchBranchTypesIncompatible(String, String),
+    MatchNotExhaustive(Vec<String>),
+    CapabilityDenied(String, String),
+    CapturedVarReassigned(String),
+    LambdaArgsIncompatible(String, Vec<String>),
+    UnusedVariable(String),
+    UnusedParam(String),
+    UnusedFunction(String),
+    UnreachableCode,
+    UnusedResult(String),
+    ShadowAlias(String),
+    RecursiveAlias(String),
+    AliasTypeParamBound,
+    StructFieldInitializedTwice(String, String),
+    StructUsedAsIdentifier,
+    UnknownNamespace(String),
+    UnknownImport(String, String),
+    ImportNotPub(String, String),
+    ImportInternal(String, String),
+    ImportConflict(String),
+    UninitializedVar(String),
+    FieldNotInitialized(String, String),
+    NullableNotClass(String),
+    NullableReceiver(String),
+    ArrayWithoutElements(String),
+    TypeParamNotInferred(String, String),
+    TraitObjectSelfParam(String, String),
+    ParseError(ParseError),
 }
 
 impl SemError {
@@ -371,9 +409,6 @@ impl SemError {
                 format!("class `{}` does not have super class.", name)
             }
             SemError::RecursiveStructure => "recursive structure is not allowed.".into(),
-            SemError::TraitMethodWithBody => {
-                "trait method is not allowed to have definition".into()
-            }
             SemError::TypeParamsExpected => "type params expected.".into(),
             SemError::TypeParamNameNotUnique(ref name) => {
                 format!("type param `{}` name already used.", name)
@@ -468,6 +503,345 @@ impl SemError {
             SemError::IllegalTupleIndex(idx, ref ty) => {
                 format!("illegal index `{}` for type `{}`", idx, ty)
             }
+            SemError::ExceptionTypeExpected(ref ty) => format!(
+                "type `{}` is not an exception, subclass of `Stacktrace` expected.",
+                ty
+            ),
+            SemError::UnknownEnum(ref name) => format!("unknown enum `{}`.", name),
+            SemError::EnumArgsIncompatible(ref xenum, ref variant, ref def, ref expr) => {
+                let def = def.join(", ");
+                let expr = expr.join(", ");
+
+                format!(
+                    "enum variant `{}::{}({})` cannot be created as `{}::{}({})`",
+                    xenum, variant, def, xenum, variant, expr
+                )
+            }
+            SemError::MatchTypeUnsupported(ref ty) => {
+                format!("cannot match on value of type `{}`.", ty)
+            }
+            SemError::MatchPatternTypeMismatch(ref expected, ref pattern) => format!(
+                "pattern of type `{}` cannot match value of type `{}`.",
+                pattern, expected
+            ),
+            SemError::MatchPatternWrongNumberOfParams(ref variant, expected, got) => format!(
+                "pattern for variant `{}` expects {} parameter(s) but got {}.",
+                variant, expected, got
+            ),
+            SemError::MatchBranchTypesIncompatible(ref expected, ref got) => format!(
+                "match arms have incompatible types `{}` and `{}`.",
+                expected, got
+            ),
+            SemError::MatchNotExhaustive(ref missing) => {
+                let missing = missing.join(", ");
+                format!("match is not exhaustive, missing pattern(s) {}.", missing)
+            }
+            SemError::CapabilityDenied(ref name, ref cap) => format!(
+                "`{}` requires capability `{}`, which was denied. Every call site is checked, even if it is never reached.",
+                name, cap
+            ),
+            SemError::CapturedVarReassigned(ref name) => format!(
+                "captured variable `{}` cannot be reassigned.",
+                name
+            ),
+            SemError::LambdaArgsIncompatible(ref lambda, ref args) => {
+                let args = args.join(", ");
+                format!(
+                    "lambda of type `{}` cannot be called with `({})`.",
+                    lambda, args
+                )
+            }
+            SemError::UnusedVariable(ref name) => format!("unused variable `{}`.", name),
+            SemError::UnusedParam(ref name) => format!("unused parameter `{}`.", name),
+            SemError::UnusedFunction(ref name) => format!("function `{}` is never used.", name),
+            SemError::UnreachableCode => "unreachable code.".into(),
+            SemError::UnusedResult(ref ty) => format!("unused result of type `{}`.", ty),
+            SemError::ShadowAlias(ref name) => format!("can not shadow type alias `{}`.", name),
+            SemError::RecursiveAlias(ref name) => {
+                format!("type alias `{}` refers to itself.", name)
+            }
+            SemError::AliasTypeParamBound => "type alias parameters cannot have bounds.".into(),
+            SemError::StructFieldInitializedTwice(ref struc, ref field) => format!(
+                "field `{}` in struct `{}` initialized more than once.",
+                field, struc
+            ),
+            SemError::StructUsedAsIdentifier => "struct cannot be used as identifier.".into(),
+            SemError::UnknownNamespace(ref name) => format!("namespace `{}` does not exist.", name),
+            SemError::UnknownImport(ref namespace, ref name) => {
+                format!("namespace `{}` has no declaration `{}`.", namespace, name)
+            }
+            SemError::ImportNotPub(ref namespace, ref name) => {
+                format!("`{}` in namespace `{}` is not public.", name, namespace)
+            }
+            SemError::ImportInternal(ref namespace, ref name) => format!(
+                "`{}` in namespace `{}` is internal and can only be imported by the stdlib.",
+                name, namespace
+            ),
+            SemError::ImportConflict(ref name) => {
+                format!("import of `{}` conflicts with another declaration.", name)
+            }
+            SemError::UninitializedVar(ref name) => {
+                format!("use of possibly uninitialized variable `{}`.", name)
+            }
+            SemError::FieldNotInitialized(ref cls, ref field) => format!(
+                "field `{}` in class `{}` is not initialized on every path through the constructor.",
+                field, cls
+            ),
+            SemError::NullableNotClass(ref name) => {
+                format!("type `{}` cannot be nullable, only class types can.", name)
+            }
+            SemError::NullableReceiver(ref name) => format!(
+                "value of type `{}` might be nil, compare it with nil before using it.",
+                name
+            ),
+            SemError::ArrayWithoutElements(ref name) => format!(
+                "array of non-nullable `{}` needs a fill value, use `arrayFill`.",
+                name
+            ),
+            SemError::TypeParamNotInferred(ref tp, ref name) => format!(
+                "cannot infer type param `{}` of `{}`, specify the type arguments explicitly.",
+                tp, name
+            ),
+            SemError::TraitObjectSelfParam(ref xtrait, ref name) => format!(
+                "method `{}` of trait `{}` takes `Self` and cannot be called on a trait object.",
+                name, xtrait
+            ),
+            SemError::ParseError(ref error) => error.message(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match *self {
+            SemError::Unimplemented => "E0100",
+            SemError::UnknownClass(..) => "E0101",
+            SemError::UnknownType(..) => "E0102",
+            SemError::UnknownIdentifier(..) => "E0103",
+            SemError::UnknownStruct(..) => "E0104",
+            SemError::UnknownFunction(..) => "E0105",
+            SemError::UnknownField(..) => "E0106",
+            SemError::UnknownMethod(..) => "E0107",
+            SemError::UnknownEnumValue(..) => "E0108",
+            SemError::MultipleCandidatesForMethod(..) => "E0109",
+            SemError::VariadicParameterNeedsToBeLast => "E0110",
+            SemError::UnknownMethodForTypeParam(..) => "E0111",
+            SemError::MultipleCandidatesForTypeParam(..) => "E0112",
+            SemError::MultipleCandidatesForStaticMethodWithTypeParam => "E0113",
+            SemError::UnknownStaticMethodWithTypeParam => "E0114",
+            SemError::UnknownStaticMethod(..) => "E0115",
+            SemError::UnknownCtor(..) => "E0116",
+            SemError::MethodExists(..) => "E0117",
+            SemError::IncompatibleWithNil(..) => "E0118",
+            SemError::IdentifierExists(..) => "E0119",
+            SemError::ShadowFunction(..) => "E0120",
+            SemError::ShadowParam(..) => "E0121",
+            SemError::ShadowClass(..) => "E0122",
+            SemError::ShadowClassConstructor(..) => "E0123",
+            SemError::ShadowStruct(..) => "E0124",
+            SemError::ShadowStructConstructor(..) => "E0125",
+            SemError::ShadowTrait(..) => "E0126",
+            SemError::ShadowField(..) => "E0127",
+            SemError::ShadowGlobal(..) => "E0128",
+            SemError::ShadowConst(..) => "E0129",
+            SemError::ShadowModule(..) => "E0130",
+            SemError::ShadowEnum(..) => "E0131",
+            SemError::ShadowEnumValue(..) => "E0132",
+            SemError::InvalidLhsAssignment => "E0133",
+            SemError::NoEnumValue => "E0134",
+            SemError::VarNeedsTypeInfo(..) => "E0135",
+            SemError::ParamTypesIncompatible(..) => "E0136",
+            SemError::WhileCondType(..) => "E0137",
+            SemError::IfCondType(..) => "E0138",
+            SemError::ReturnType(..) => "E0139",
+            SemError::LvalueExpected => "E0140",
+            SemError::AssignType(..) => "E0141",
+            SemError::AssignField(..) => "E0142",
+            SemError::UnOpType(..) => "E0143",
+            SemError::BinOpType(..) => "E0144",
+            SemError::ConstValueExpected => "E0145",
+            SemError::OutsideLoop => "E0146",
+            SemError::NoReturnValue => "E0147",
+            SemError::MainNotFound => "E0148",
+            SemError::WrongMainDefinition => "E0149",
+            SemError::ThisUnavailable => "E0150",
+            SemError::SelfTypeUnavailable => "E0151",
+            SemError::SuperUnavailable => "E0152",
+            SemError::SuperNeedsMethodCall => "E0153",
+            SemError::ReferenceTypeExpected(..) => "E0154",
+            SemError::LetMissingInitialization => "E0155",
+            SemError::LetReassigned => "E0156",
+            SemError::FctReassigned => "E0157",
+            SemError::ClassReassigned => "E0158",
+            SemError::TypeParamReassigned => "E0159",
+            SemError::FctUsedAsIdentifier => "E0160",
+            SemError::ClsUsedAsIdentifier => "E0161",
+            SemError::ModuleUsedAsIdentifier => "E0162",
+            SemError::TypeParamUsedAsIdentifier => "E0163",
+            SemError::EnumUsedAsIdentifier => "E0164",
+            SemError::TypeParamUsedAsCallee => "E0165",
+            SemError::UnderivableType(..) => "E0166",
+            SemError::CycleInHierarchy => "E0167",
+            SemError::SuperfluousOverride(..) => "E0168",
+            SemError::SuperfluousOpen(..) => "E0169",
+            SemError::MissingOverride(..) => "E0170",
+            SemError::MethodNotOverridable(..) => "E0171",
+            SemError::TypesIncompatible(..) => "E0172",
+            SemError::ReturnTypeMismatch(..) => "E0173",
+            SemError::OverrideMismatch => "E0174",
+            SemError::UnresolvedInternal => "E0175",
+            SemError::UnclosedComment => "E0176",
+            SemError::UnknownChar(..) => "E0177",
+            SemError::UnclosedChar => "E0178",
+            SemError::UnclosedString => "E0179",
+            SemError::NumberOverflow(..) => "E0180",
+            SemError::ExpectedClass(..) => "E0181",
+            SemError::ExpectedFactor(..) => "E0182",
+            SemError::ExpectedToken(..) => "E0183",
+            SemError::ExpectedTopLevelElement(..) => "E0184",
+            SemError::ExpectedTrait(..) => "E0185",
+            SemError::ExpectedType(..) => "E0186",
+            SemError::ExpectedIdentifier(..) => "E0187",
+            SemError::ExpectedStringable(..) => "E0188",
+            SemError::ExpectedSomeIdentifier => "E0189",
+            SemError::MisplacedElse => "E0190",
+            SemError::IoError => "E0191",
+            SemError::ExpectedClassElement(..) => "E0192",
+            SemError::MisplacedAnnotation(..) => "E0193",
+            SemError::RedundantAnnotation(..) => "E0194",
+            SemError::UnknownAnnotation(..) => "E0195",
+            SemError::InvalidEscapeSequence(..) => "E0196",
+            SemError::MissingFctBody => "E0197",
+            SemError::FctCallExpected => "E0198",
+            SemError::ThisOrSuperExpected(..) => "E0199",
+            SemError::NoSuperDelegationWithPrimaryCtor(..) => "E0200",
+            SemError::NoSuperClass(..) => "E0201",
+            SemError::RecursiveStructure => "E0202",
+            SemError::TypeParamsExpected => "E0204",
+            SemError::TypeParamNameNotUnique(..) => "E0205",
+            SemError::StaticMethodNotInTrait(..) => "E0206",
+            SemError::MethodNotInTrait(..) => "E0207",
+            SemError::StaticMethodMissingFromTrait(..) => "E0208",
+            SemError::MethodMissingFromTrait(..) => "E0209",
+            SemError::WrongNumberTypeParams(..) => "E0210",
+            SemError::ClassExpected => "E0211",
+            SemError::ClassExpectedAsTypeParam => "E0212",
+            SemError::AssignmentToConst => "E0213",
+            SemError::BoundExpected => "E0214",
+            SemError::NoTypeParamsExpected => "E0215",
+            SemError::DuplicateTraitBound => "E0216",
+            SemError::TraitBoundNotSatisfied(..) => "E0217",
+            SemError::AbstractMethodNotInAbstractClass => "E0218",
+            SemError::AbstractMethodWithImplementation => "E0219",
+            SemError::NewAbstractClass => "E0220",
+            SemError::MissingAbstractOverride(..) => "E0221",
+            SemError::ModifierNotAllowedForStaticMethod(..) => "E0222",
+            SemError::GlobalInitializerNotSupported => "E0223",
+            SemError::MakeIteratorNotFound(..) => "E0224",
+            SemError::MakeIteratorReturnType(..) => "E0225",
+            SemError::UnknownStructField(..) => "E0226",
+            SemError::StructFieldNotInitialized(..) => "E0227",
+            SemError::InvalidLeftSideOfSeparator => "E0228",
+            SemError::InvalidUseOfTypeParams => "E0229",
+            SemError::NameOfStaticMethodExpected => "E0230",
+            SemError::IfBranchTypesIncompatible(..) => "E0231",
+            SemError::NameExpected => "E0232",
+            SemError::IndexExpected => "E0233",
+            SemError::IllegalTupleIndex(..) => "E0234",
+            SemError::ExceptionTypeExpected(..) => "E0235",
+            SemError::UnknownEnum(..) => "E0236",
+            SemError::EnumArgsIncompatible(..) => "E0237",
+            SemError::MatchTypeUnsupported(..) => "E0238",
+            SemError::MatchPatternTypeMismatch(..) => "E0239",
+            SemError::MatchPatternWrongNumberOfParams(..) => "E0240",
+            SemError::MatchBranchTypesIncompatible(..) => "E0241",
+            SemError::MatchNotExhaustive(..) => "E0242",
+            SemError::CapabilityDenied(..) => "E0243",
+            SemError::CapturedVarReassigned(..) => "E0244",
+            SemError::LambdaArgsIncompatible(..) => "E0245",
+            SemError::UnusedVariable(..) => "E0246",
+            SemError::UnusedParam(..) => "E0247",
+            SemError::UnusedFunction(..) => "E0248",
+            SemError::UnreachableCode => "E0249",
+            SemError::UnusedResult(..) => "E0250",
+            SemError::ShadowAlias(..) => "E0251",
+            SemError::RecursiveAlias(..) => "E0252",
+            SemError::AliasTypeParamBound => "E0253",
+            SemError::StructFieldInitializedTwice(..) => "E0254",
+            SemError::StructUsedAsIdentifier => "E0255",
+            SemError::UnknownNamespace(..) => "E0256",
+            SemError::UnknownImport(..) => "E0257",
+            SemError::ImportNotPub(..) => "E0258",
+            SemError::ImportInternal(..) => "E0259",
+            SemError::ImportConflict(..) => "E0260",
+            SemError::UninitializedVar(..) => "E0261",
+            SemError::FieldNotInitialized(..) => "E0262",
+            SemError::NullableNotClass(..) => "E0263",
+            SemError::NullableReceiver(..) => "E0264",
+            SemError::ArrayWithoutElements(..) => "E0265",
+            SemError::TypeParamNotInferred(..) => "E0266",
+            SemError::TraitObjectSelfParam(..) => "E0267",
+            SemError::ParseError(ref error) => error.code(),
+        }
+    }
+
+    pub fn is_parse_error(&self) -> bool {
+        match *self {
+            SemError::ParseError(_) => true,
+            _ => false,
+        }
+    }
+
+    // for errors that concern the whole program (e.g. a missing `main`)
+    pub fn to_json_without_location(&self) -> String {
+        let diagnostic = JsonDiagnostic {
+            file: None,
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+            severity: Severity::Error.name().into(),
+            code: self.code().into(),
+            message: self.message(),
+            notes: Vec::new(),
+        };
+
+        json::encode(&diagnostic).expect("encoding diagnostic failed")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SemNote {
+    pub location: Option<(FileId, Position)>,
+    pub msg: String,
+}
+
+impl SemNote {
+    pub fn new(file: FileId, pos: Position, msg: String) -> SemNote {
+        SemNote {
+            location: Some((file, pos)),
+            msg,
+        }
+    }
+
+    // notes without location are printed without source snippet
+    pub fn help(msg: String) -> SemNote {
+        SemNote {
+            location: None,
+            msg,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
         }
     }
 }
@@ -476,21 +850,302 @@ impl SemError {
 pub struct SemErrorAndPos {
     pub file: FileId,
     pub pos: Position,
+    pub span: Option<Span>,
+    pub severity: Severity,
     pub msg: SemError,
+    pub notes: Vec<SemNote>,
 }
 
 impl SemErrorAndPos {
     pub fn new(file: FileId, pos: Position, msg: SemError) -> SemErrorAndPos {
-        SemErrorAndPos { file, pos, msg }
+        SemErrorAndPos {
+            file,
+            pos,
+            span: None,
+            severity: Severity::Error,
+            msg,
+            notes: Vec::new(),
+        }
     }
 
     pub fn message(&self, vm: &VM) -> String {
         let file = vm.file(self.file);
         format!(
-            "error in {} at {}: {}",
+            "{} in {} at {}: {}",
+            self.severity.name(),
             file.name,
             self.pos,
             self.msg.message()
         )
     }
+
+    // start and (exclusive) end of the source range marked by this diagnostic,
+    // both on the line of the diagnostic's position
+    pub fn range(&self, vm: &VM) -> (Position, Position) {
+        let file = vm.file(self.file);
+        let (col, len) = marked_range(file, self.pos, self.span);
+        let line = self.pos.line;
+
+        (
+            Position::new(line, col as u32 + 1),
+            Position::new(line, (col + len) as u32 + 1),
+        )
+    }
+
+    pub fn to_json(&self, vm: &VM) -> String {
+        let file = vm.file(self.file);
+        let (start, end) = self.range(vm);
+
+        let diagnostic = JsonDiagnostic {
+            file: Some(file.name.clone()),
+            line: Some(start.line),
+            column: Some(start.column),
+            end_line: Some(end.line),
+            end_column: Some(end.column),
+            severity: self.severity.name().into(),
+            code: self.msg.code().into(),
+            message: self.msg.message(),
+            notes: self
+                .notes
+                .iter()
+                .map(|note| JsonNote {
+                    file: note.location.map(|(file, _)| vm.file(file).name.clone()),
+                    line: note.location.map(|(_, pos)| pos.line),
+                    column: note.location.map(|(_, pos)| pos.column),
+                    message: note.msg.clone(),
+                })
+                .collect(),
+        };
+
+        json::encode(&diagnostic).expect("encoding diagnostic failed")
+    }
+
+    pub fn render(&self, vm: &VM) -> String {
+        let max_line = self
+            .notes
+            .iter()
+            .filter_map(|note| note.location.map(|(_, pos)| pos.line))
+            .fold(self.pos.line, max);
+        let width = max_line.to_string().len();
+
+        let mut out = format!(
+            "{}[{}]: {}\n",
+            self.severity.name(),
+            self.msg.code(),
+            self.msg.message()
+        );
+        render_snippet(
+            &mut out,
+            vm.file(self.file),
+            self.pos,
+            self.span,
+            '^',
+            width,
+        );
+
+        for note in &self.notes {
+            match note.location {
+                Some((file, pos)) => {
+                    out.push_str(&format!("note: {}\n", note.msg));
+                    render_snippet(&mut out, vm.file(file), pos, None, '-', width);
+                }
+
+                None => {
+                    out.push_str(&format!("{} = note: {}\n", " ".repeat(width), note.msg));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+// Prints the source line of `pos` and marks the code of the span on that line. Without
+// a span the identifier or character at `pos` is marked.
+fn render_snippet(
+    out: &mut String,
+    file: &File,
+    pos: Position,
+    span: Option<Span>,
+    marker: char,
+    width: usize,
+) {
+    let gutter = " ".repeat(width);
+    let line = source_line(file, pos.line);
+
+    let (col, len) = marked_range(file, pos, span);
+
+    out.push_str(&format!("{}--> {}:{}\n", gutter, file.name, pos));
+    out.push_str(&format!("{} |\n", gutter));
+    out.push_str(&format!("{:>2$} | {}\n", pos.line, line, width));
+    out.push_str(&format!(
+        "{} | {}{}\n",
+        gutter,
+        " ".repeat(col),
+        marker.to_string().repeat(len)
+    ));
+}
+
+// Start column (zero-based) and number of columns marked on the line of `pos`,
+// at least one. The position of an expression is not necessarily its start (e.g.
+// the operator of a binary expression), so a span on the same line takes precedence.
+fn marked_range(file: &File, pos: Position, span: Option<Span>) -> (usize, usize) {
+    let line = source_line(file, pos.line);
+    let mut col = pos.column as usize - 1;
+
+    let len = match span.filter(|span| span.is_valid()) {
+        Some(span) => {
+            let start = span.start() as usize;
+            let prefix = file.content.get(..start).unwrap_or_default();
+
+            if prefix.matches('\n').count() + 1 == pos.line as usize {
+                let line_start = prefix.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+                col = expand_tabs(&prefix[line_start..]).chars().count();
+            }
+
+            file.content
+                .get(start..span.end() as usize)
+                .and_then(|text| text.lines().next())
+                .map(|text| text.chars().count())
+                .unwrap_or(1)
+        }
+
+        None => line
+            .chars()
+            .skip(col)
+            .take_while(|&ch| ch.is_alphanumeric() || ch == '_')
+            .count(),
+    };
+
+    let rest = line.chars().count().saturating_sub(col);
+
+    (col, min(len, rest).max(1))
+}
+
+fn source_line(file: &File, line: u32) -> String {
+    file.content
+        .lines()
+        .nth(line as usize - 1)
+        .map(expand_tabs)
+        .unwrap_or_default()
+}
+
+// Columns in positions count tabs up to the next multiple of the reader's tab width.
+fn expand_tabs(line: &str) -> String {
+    let mut result = String::new();
+    let mut col = 0;
+
+    for ch in line.chars() {
+        if ch == '\t' {
+            let spaces = TAB_WIDTH - col % TAB_WIDTH;
+            result.push_str(&" ".repeat(spaces));
+            col += spaces;
+        } else {
+            result.push(ch);
+            col += 1;
+        }
+    }
+
+    result
+}
+
+const TAB_WIDTH: usize = 4;
+
+#[derive(RustcEncodable)]
+struct JsonDiagnostic {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: String,
+    code: String,
+    message: String,
+    notes: Vec<JsonNote>,
+}
+
+#[derive(RustcEncodable)]
+struct JsonNote {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SemError;
+    use crate::test;
+
+    #[test]
+    fn render_error_with_span() {
+        test::parse_with_errors("fun f() -> Int {\n\treturn false;\n}", |vm| {
+            let diag = vm.diag.lock();
+            let error = &diag.errors()[0];
+
+            assert_eq!(
+                "error[E0139]: `return` expects value of type `Int` but got `Bool`.\n\
+                 \x20--> <<code>>:2:5\n  |\n2 |     return false;\n  |     ^^^^^^^^^^^^^\n",
+                error.render(vm)
+            );
+        });
+    }
+
+    #[test]
+    fn error_to_json() {
+        test::parse_with_errors("fun f() -> Int { return false; }", |vm| {
+            let diag = vm.diag.lock();
+            let error = &diag.errors()[0];
+
+            assert_eq!(
+                "{\"file\":\"<<code>>\",\"line\":1,\"column\":18,\"end_line\":1,\
+                 \"end_column\":31,\"severity\":\"error\",\"code\":\"E0139\",\
+                 \"message\":\"`return` expects value of type `Int` but got `Bool`.\",\
+                 \"notes\":[]}",
+                error.to_json(vm)
+            );
+        });
+    }
+
+    #[test]
+    fn error_to_json_with_tab() {
+        test::parse_with_errors("fun f() -> Int {\n\treturn false;\n}", |vm| {
+            let diag = vm.diag.lock();
+            let error = &diag.errors()[0];
+
+            assert_eq!(
+                "{\"file\":\"<<code>>\",\"line\":2,\"column\":5,\"end_line\":2,\
+                 \"end_column\":18,\"severity\":\"error\",\"code\":\"E0139\",\
+                 \"message\":\"`return` expects value of type `Int` but got `Bool`.\",\
+                 \"notes\":[]}",
+                error.to_json(vm)
+            );
+        });
+    }
+
+    #[test]
+    fn error_to_json_without_location() {
+        assert_eq!(
+            "{\"file\":null,\"line\":null,\"column\":null,\"end_line\":null,\
+             \"end_column\":null,\"severity\":\"error\",\"code\":\"E0148\",\
+             \"message\":\"no `main` function found in the program\",\"notes\":[]}",
+            SemError::MainNotFound.to_json_without_location()
+        );
+    }
+
+    #[test]
+    fn render_error_with_note() {
+        test::parse_with_errors("class Foo\n\n\n\n\n\n\n\n\nclass Foo", |vm| {
+            let diag = vm.diag.lock();
+            let error = &diag.errors()[0];
+
+            assert_eq!(
+                "error[E0122]: can not shadow class `Foo`.\n\
+                 \x20 --> <<code>>:10:1\n   |\n10 | class Foo\n   | ^^^^^\n\
+                 note: previous definition here\n\
+                 \x20 --> <<code>>:1:1\n   |\n 1 | class Foo\n   | -----\n",
+                error.render(vm)
+            );
+        });
+    }
 }
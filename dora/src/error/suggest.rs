@@ -0,0 +1,117 @@
+use std::cmp::{max, min};
+
+use crate::error::msg::SemNote;
+use crate::vm::VM;
+use dora_parser::interner::Name;
+
+// Returns a "did you mean?" note for the candidate closest to the unknown name,
+// or no note at all when none of the candidates is similar enough.
+pub fn did_you_mean<I>(vm: &VM, name: Name, candidates: I) -> Vec<SemNote>
+where
+    I: IntoIterator<Item = Name>,
+{
+    let name = vm.interner.str(name);
+    let candidates = candidates
+        .into_iter()
+        .map(|candidate| vm.interner.str(candidate).to_string());
+
+    match similar_name(&name, candidates) {
+        Some(candidate) => vec![SemNote::help(format!("did you mean `{}`?", candidate))],
+        None => Vec::new(),
+    }
+}
+
+pub fn similar_name<I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    // distances are measured in half edits
+    let max_distance = 2 * max(name.chars().count() / 3, 1);
+
+    // ties are broken by name to keep suggestions deterministic
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate != name)
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+// Edit distance in half edits where swapping two adjacent characters counts as a
+// single edit and a change in case only counts half an edit, to prefer e.g.
+// `String` over `Strong` for `string`.
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let lhs: Vec<char> = lhs.chars().collect();
+    let rhs: Vec<char> = rhs.chars().collect();
+
+    let mut prev_prev = vec![0; rhs.len() + 1];
+    let mut prev: Vec<usize> = (0..=rhs.len()).map(|idx| idx * 2).collect();
+    let mut curr = vec![0; rhs.len() + 1];
+
+    for (i, &l) in lhs.iter().enumerate() {
+        curr[0] = (i + 1) * 2;
+
+        for (j, &r) in rhs.iter().enumerate() {
+            let cost = if l == r {
+                0
+            } else if l.to_lowercase().eq(r.to_lowercase()) {
+                1
+            } else {
+                2
+            };
+
+            let mut distance = min(min(prev[j + 1] + 2, curr[j] + 2), prev[j] + cost);
+
+            if i > 0 && j > 0 && l == rhs[j - 1] && lhs[i - 1] == r {
+                distance = min(distance, prev_prev[j - 1] + 2);
+            }
+
+            curr[j + 1] = distance;
+        }
+
+        std::mem::swap(&mut prev_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[rhs.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("foo", "foo"));
+        assert_eq!(2, edit_distance("foo", "fooo"));
+        assert_eq!(2, edit_distance("foo", "fo"));
+        assert_eq!(2, edit_distance("foo", "fob"));
+        assert_eq!(2, edit_distance("cuont", "count"));
+        assert_eq!(1, edit_distance("string", "String"));
+        assert_eq!(6, edit_distance("abc", "xyz"));
+        assert_eq!(6, edit_distance("", "abc"));
+    }
+
+    #[test]
+    fn test_similar_name() {
+        assert_eq!(
+            Some("length".into()),
+            similar_name("lenght", names(&["length", "size", "get"]))
+        );
+        assert_eq!(
+            Some("String".into()),
+            similar_name("string", names(&["Strong", "String"]))
+        );
+        assert_eq!(None, similar_name("foo", names(&["bar", "baz"])));
+        assert_eq!(None, similar_name("foo", names(&["foo"])));
+        assert_eq!(
+            Some("bar".into()),
+            similar_name("baz", names(&["bat", "bar"]))
+        );
+    }
+}
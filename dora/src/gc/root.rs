@@ -11,6 +11,7 @@ pub fn get_rootset(vm: &VM, threads: &[Arc<DoraThread>]) -> Vec<Slot> {
 
     determine_rootset_from_stack(&mut rootset, vm, threads);
     determine_rootset_from_handles(&mut rootset, threads);
+    determine_rootset_from_exceptions(&mut rootset, threads);
 
     determine_rootset_from_globals(&mut rootset, vm);
 
@@ -26,18 +27,29 @@ fn determine_rootset_from_handles(rootset: &mut Vec<Slot>, threads: &[Arc<DoraTh
     }
 }
 
+fn determine_rootset_from_exceptions(rootset: &mut Vec<Slot>, threads: &[Arc<DoraThread>]) {
+    for thread in threads {
+        let exception = thread.exception.lock();
+
+        if let Some(address) = exception.as_ref().and_then(|e| e.object_address()) {
+            let slot = Slot::at(Address::from_ptr(address));
+            rootset.push(slot);
+        }
+    }
+}
+
 fn determine_rootset_from_globals(rootset: &mut Vec<Slot>, vm: &VM) {
     for glob in vm.globals.iter() {
         let glob = glob.read();
 
-        if glob.ty.reference_type() {
+        if glob.ty.reference_type(vm) {
             let slot = Slot::at(glob.address_value);
             rootset.push(slot);
-        } else if let Some(tuple_id) = glob.ty.tuple_id() {
+        } else if let Some(tuple_id) = glob.ty.layout_tuple_id(vm) {
             let tuples = vm.tuples.lock();
             let tuple = tuples.get_tuple(tuple_id);
 
-            for &offset in tuple.offsets() {
+            for &offset in tuple.references() {
                 let slot_address = glob.address_value.offset(offset as usize);
                 let slot = Slot::at(slot_address);
                 rootset.push(slot);
@@ -117,6 +129,7 @@ fn determine_rootset(rootset: &mut Vec<Slot>, vm: &VM, fp: usize, pc: usize) ->
         }
 
         Some(CodeDescriptor::AllocStub) => true,
+        Some(CodeDescriptor::CatchStub) => true,
         Some(CodeDescriptor::DoraStub) => false,
         Some(CodeDescriptor::GuardCheckStub) => true,
         Some(CodeDescriptor::CompileStub) => true,
@@ -31,6 +31,7 @@ mod dseg;
 mod error;
 mod gc;
 mod handle;
+mod lsp;
 mod masm;
 mod mem;
 mod object;
@@ -52,12 +53,31 @@ mod vtable;
 #[cfg(test)]
 mod test;
 
+pub use crate::vm::Capability;
+
 #[cfg(not(test))]
 pub fn run_content(content: &str) -> i32 {
     driver::start(Some(content))
 }
 
+// runs the given program, calls to natives of denied capabilities
+// are rejected at compile time or fail at runtime
+#[cfg(not(test))]
+pub fn run_content_restricted(content: &str, deny: &[Capability]) -> i32 {
+    let mut args: driver::cmd::Args = Default::default();
+    args.flag_deny = Some(driver::cmd::DenyList {
+        capabilities: deny.into(),
+        ..Default::default()
+    });
+    driver::start_with_args(args, Some(content))
+}
+
 #[cfg(not(test))]
 pub fn run() -> i32 {
     driver::start(None)
 }
+
+#[cfg(not(test))]
+pub fn run_lsp() -> i32 {
+    lsp::start()
+}
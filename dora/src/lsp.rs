@@ -0,0 +1,319 @@
+use rustc_serialize::json::Json;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use crate::lsp::analysis::{Document, DocumentAnalysis};
+use crate::lsp::location::LspPosition;
+use crate::lsp::rpc::{object, Message};
+
+mod analysis;
+mod location;
+mod rpc;
+
+// Language server speaking JSON-RPC over stdin and stdout. Open documents are
+// checked on every change and their diagnostics published, additionally hover,
+// go-to-definition and document symbols are supported. Requests are answered
+// from the results of the last check.
+pub fn start() -> i32 {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    let mut input = stdin.lock();
+    let mut server = Server::new(stdout.lock());
+
+    match server.run(&mut input) {
+        Ok(code) => code,
+
+        Err(error) => {
+            eprintln!("dora-lsp: {}", error);
+            1
+        }
+    }
+}
+
+struct Server<W: Write> {
+    output: W,
+    documents: HashMap<String, String>,
+    results: HashMap<String, DocumentAnalysis>,
+    shutdown: bool,
+}
+
+impl<W: Write> Server<W> {
+    fn new(output: W) -> Server<W> {
+        Server {
+            output,
+            documents: HashMap::new(),
+            results: HashMap::new(),
+            shutdown: false,
+        }
+    }
+
+    fn run<R: BufRead>(&mut self, input: &mut R) -> io::Result<i32> {
+        while let Some(message) = rpc::read_message(input)? {
+            match message {
+                Message::Request { id, method, params } => {
+                    let response = self.handle_request(id, &method, &params);
+                    rpc::write_message(&mut self.output, &response)?;
+                }
+
+                Message::Notification { method, params } => {
+                    if method == "exit" {
+                        return Ok(self.exit_code());
+                    }
+
+                    self.handle_notification(&method, &params)?;
+                }
+
+                Message::Response => {}
+            }
+        }
+
+        Ok(self.exit_code())
+    }
+
+    // the client needs to ask for a shutdown before exiting
+    fn exit_code(&self) -> i32 {
+        if self.shutdown {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn handle_request(&mut self, id: Json, method: &str, params: &Json) -> Json {
+        if self.shutdown {
+            return rpc::error_response(id, rpc::INVALID_REQUEST, "server is shutting down");
+        }
+
+        let result = match method {
+            "initialize" => Some(initialize_result()),
+
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Json::Null)
+            }
+
+            "textDocument/hover" => self
+                .document_position(params)
+                .map(|(results, pos)| results.hover(pos)),
+
+            "textDocument/definition" => self
+                .document_position(params)
+                .map(|(results, pos)| results.definition(pos)),
+
+            "textDocument/documentSymbol" => self
+                .document(params)
+                .map(|results| results.document_symbols()),
+
+            _ => {
+                let msg = format!("unknown method `{}`", method);
+                return rpc::error_response(id, rpc::METHOD_NOT_FOUND, &msg);
+            }
+        };
+
+        match result {
+            Some(result) => rpc::response(id, result),
+            None => rpc::error_response(id, rpc::INVALID_PARAMS, "unknown document"),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Json) -> io::Result<()> {
+        let uri = match params.find_path(&["textDocument", "uri"]) {
+            Some(uri) => uri.as_string().unwrap_or_default().to_string(),
+            None => return Ok(()),
+        };
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params.find_path(&["textDocument", "text"]);
+                let text = text.and_then(|text| text.as_string()).unwrap_or_default();
+                self.documents.insert(uri.clone(), text.into());
+                self.check(&uri)
+            }
+
+            // documents are always synchronized in full
+            "textDocument/didChange" => {
+                let changes = params.find("contentChanges").and_then(|c| c.as_array());
+                let text = changes
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.find("text"))
+                    .and_then(|text| text.as_string());
+
+                if let Some(text) = text {
+                    self.documents.insert(uri.clone(), text.into());
+                }
+
+                self.check(&uri)
+            }
+
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                self.results.remove(&uri);
+                self.send_diagnostics(&uri, Json::Array(Vec::new()))?;
+
+                // the other documents of the project now use the file on disk
+                if analysis::find_project_dir(&uri).is_some() {
+                    self.check(&uri)
+                } else {
+                    Ok(())
+                }
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    // checks the document together with the open documents of its project
+    // and publishes the diagnostics of all of them
+    fn check(&mut self, uri: &str) -> io::Result<()> {
+        let project = analysis::find_project_dir(uri);
+
+        let uris: Vec<String> = match project {
+            Some(ref dir) => self
+                .documents
+                .keys()
+                .filter(|other| analysis::find_project_dir(other).as_ref() == Some(dir))
+                .cloned()
+                .collect(),
+
+            None if self.documents.contains_key(uri) => vec![uri.to_string()],
+            None => Vec::new(),
+        };
+
+        if uris.is_empty() {
+            return Ok(());
+        }
+
+        let results = {
+            let docs: Vec<Document> = uris
+                .iter()
+                .map(|uri| Document {
+                    uri: uri.as_str(),
+                    text: self.documents[uri].as_str(),
+                })
+                .collect();
+
+            analysis::analyze(&docs, project.as_ref().map(|dir| dir.as_path()))
+        };
+
+        for (uri, results) in uris.into_iter().zip(results) {
+            let diagnostics = results.diagnostics();
+            self.results.insert(uri.clone(), results);
+            self.send_diagnostics(&uri, diagnostics)?;
+        }
+
+        Ok(())
+    }
+
+    fn send_diagnostics(&mut self, uri: &str, diagnostics: Json) -> io::Result<()> {
+        let params = object(vec![
+            ("uri", Json::String(uri.into())),
+            ("diagnostics", diagnostics),
+        ]);
+
+        let notification = rpc::notification("textDocument/publishDiagnostics", params);
+        rpc::write_message(&mut self.output, &notification)
+    }
+
+    fn document(&self, params: &Json) -> Option<&DocumentAnalysis> {
+        let uri = params.find_path(&["textDocument", "uri"])?.as_string()?;
+        self.results.get(uri)
+    }
+
+    fn document_position(&self, params: &Json) -> Option<(&DocumentAnalysis, LspPosition)> {
+        let doc = self.document(params)?;
+        let line = params.find_path(&["position", "line"])?.as_u64()?;
+        let character = params.find_path(&["position", "character"])?.as_u64()?;
+
+        Some((doc, LspPosition::new(line as u32, character as u32)))
+    }
+}
+
+fn initialize_result() -> Json {
+    let capabilities = object(vec![
+        // full text of the document on every change
+        ("textDocumentSync", Json::U64(1)),
+        ("hoverProvider", Json::Boolean(true)),
+        ("definitionProvider", Json::Boolean(true)),
+        ("documentSymbolProvider", Json::Boolean(true)),
+    ]);
+
+    let server_info = object(vec![
+        ("name", Json::String("dora-lsp".into())),
+        ("version", Json::String(env!("CARGO_PKG_VERSION").into())),
+    ]);
+
+    object(vec![
+        ("capabilities", capabilities),
+        ("serverInfo", server_info),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(json: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{}", json.len(), json)
+    }
+
+    fn responses(output: &[u8]) -> Vec<Json> {
+        let mut output = output;
+        let mut messages = Vec::new();
+
+        while let Some(message) = rpc_message(&mut output) {
+            messages.push(message);
+        }
+
+        messages
+    }
+
+    fn rpc_message(input: &mut &[u8]) -> Option<Json> {
+        let content = String::from_utf8_lossy(input).into_owned();
+        let start = content.find("\r\n\r\n")? + 4;
+        let length: usize = content["Content-Length: ".len()..start - 4].parse().ok()?;
+        let json = Json::from_str(&content[start..start + length]).ok()?;
+        *input = &input[start + length..];
+
+        Some(json)
+    }
+
+    #[test]
+    fn server_session() {
+        let input = [
+            message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#),
+            message(
+                r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///tmp/a.dora","languageId":"dora","version":1,"text":"fun main() { x; }"}}}"#,
+            ),
+            message(r#"{"jsonrpc":"2.0","id":2,"method":"foo"}"#),
+            message(r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#),
+            message(r#"{"jsonrpc":"2.0","method":"exit"}"#),
+        ]
+        .concat();
+
+        let mut output = Vec::new();
+        let code = Server::new(&mut output).run(&mut input.as_bytes()).unwrap();
+        assert_eq!(0, code);
+
+        let responses = responses(&output);
+        assert_eq!(4, responses.len());
+
+        assert!(responses[0]
+            .find_path(&["result", "capabilities", "hoverProvider"])
+            .is_some());
+
+        let diagnostics = responses[1]
+            .find_path(&["params", "diagnostics"])
+            .and_then(|diagnostics| diagnostics.as_array())
+            .unwrap();
+        assert_eq!(1, diagnostics.len());
+
+        assert_eq!(
+            Some(rpc::METHOD_NOT_FOUND),
+            responses[2]
+                .find_path(&["error", "code"])
+                .and_then(|code| code.as_i64())
+        );
+        assert_eq!(Some(&Json::Null), responses[3].find("result"));
+    }
+}
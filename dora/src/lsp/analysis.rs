@@ -0,0 +1,1137 @@
+use rustc_serialize::json::Json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::driver::fmt::collect_files;
+use crate::driver::manifest;
+use crate::driver::start::{parse_bundled_stdlib, parse_named_str, should_file_be_parsed};
+use crate::error::msg::{SemErrorAndPos, Severity};
+use crate::lsp::location::{
+    lsp_to_offset, offset_of_position, offset_to_lsp, path_to_uri, uri_to_path, LspPosition,
+};
+use crate::lsp::rpc::object;
+use crate::semck;
+use crate::sym::TypeSym;
+use crate::ty::BuiltinType;
+use crate::vm::{CallType, Fct, FctId, FctParent, FctSrc, FileId, IdentType, VM};
+use dora_parser::ast::visit::{self, Visitor};
+use dora_parser::ast::Expr::*;
+use dora_parser::ast::Stmt::*;
+use dora_parser::ast::{self, Ast, Elem, Expr, MatchPattern, NodeId, Stmt, Type};
+use dora_parser::interner::Name;
+use dora_parser::lexer::position::{Position, Span};
+
+pub struct Document<'a> {
+    pub uri: &'a str,
+    pub text: &'a str,
+}
+
+// The results of checking a document. They are computed once after each
+// change, requests only look them up.
+pub struct DocumentAnalysis {
+    content: String,
+    diagnostics: Vec<Json>,
+    symbols: Vec<Json>,
+    targets: Vec<Target>,
+}
+
+impl DocumentAnalysis {
+    pub fn diagnostics(&self) -> Json {
+        Json::Array(self.diagnostics.clone())
+    }
+
+    pub fn hover(&self, pos: LspPosition) -> Json {
+        self.target(pos)
+            .map(|target| target.hover.clone())
+            .unwrap_or(Json::Null)
+    }
+
+    pub fn definition(&self, pos: LspPosition) -> Json {
+        self.target(pos)
+            .map(|target| target.definition.clone())
+            .unwrap_or(Json::Null)
+    }
+
+    pub fn document_symbols(&self) -> Json {
+        Json::Array(self.symbols.clone())
+    }
+
+    fn target(&self, pos: LspPosition) -> Option<&Target> {
+        let offset = lsp_to_offset(&self.content, pos);
+
+        self.targets
+            .iter()
+            .find(|target| target.start <= offset && offset < target.end)
+    }
+}
+
+// hover and go-to-definition of the token between `start` and `end`
+struct Target {
+    start: usize,
+    end: usize,
+    hover: Json,
+    definition: Json,
+}
+
+// the directory of the project (a directory with a `dora.toml`) the document
+// belongs to
+pub fn find_project_dir(uri: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(uri_to_path(uri));
+
+    path.ancestors()
+        .skip(1)
+        .filter_map(|dir| dir.to_str())
+        .filter_map(manifest::find_project)
+        .next()
+}
+
+// Checks the documents together with the stdlib and returns the results in
+// the order of the documents. With a project directory all files of its
+// source and test roots and of its dependencies are checked as well, so that
+// imports between them resolve. Open documents replace their files on disk.
+//
+// The stdlib is parsed for each check, but only the bodies of the other files
+// are checked. Panics of the compiler are not caught, release builds abort on
+// panic and the client needs to restart the server.
+pub fn analyze(docs: &[Document], project: Option<&Path>) -> Vec<DocumentAnalysis> {
+    let mut ast = Ast::new();
+    let empty = Ast::new();
+    let mut vm = VM::new(Default::default(), &empty);
+
+    parse_bundled_stdlib(&mut vm, &mut ast).expect("failed parsing stdlib");
+    vm.trusted_files = vm.files.len();
+    vm.check_trusted_bodies = false;
+
+    let mut doc_files = Vec::new();
+    let mut parsed: Vec<PathBuf> = Vec::new();
+
+    for doc in docs {
+        let path = uri_to_path(doc.uri);
+        doc_files.push(ast.files.len());
+        parse_named_str(&path, doc.text, &mut vm, &mut ast).expect("failed parsing document");
+
+        if let Ok(path) = fs::canonicalize(&path) {
+            parsed.push(path);
+        }
+    }
+
+    if let Some(project) = project {
+        for file in project_files(project) {
+            let canonical = match fs::canonicalize(&file) {
+                Ok(path) => path,
+                Err(_) => continue,
+            };
+
+            if parsed.contains(&canonical) {
+                continue;
+            }
+
+            // files that can't be read are skipped, stdout belongs to the protocol
+            if let Ok(content) = fs::read_to_string(&file) {
+                parse_named_str(&file, &content, &mut vm, &mut ast).expect("failed parsing file");
+                parsed.push(canonical);
+            }
+        }
+    }
+
+    vm.ast = &ast;
+    semck::check(&mut vm);
+
+    docs.iter()
+        .zip(doc_files)
+        .map(|(doc, idx)| {
+            let analysis = Analysis::new(&vm, doc, (idx as u32).into(), &ast.files[idx]);
+            analysis.results()
+        })
+        .collect()
+}
+
+// the files of the source roots of the project and its dependencies, the test
+// roots and the main entry of the project
+fn project_files(dir: &Path) -> Vec<String> {
+    let packages = match manifest::load_project(dir) {
+        Ok(packages) => packages,
+        Err(_) => return Vec::new(),
+    };
+
+    let project = packages.last().expect("project missing");
+    let mut files = Vec::new();
+
+    for package in &packages {
+        for root in &package.sources {
+            collect_files(root, &mut files);
+        }
+    }
+
+    for root in &project.tests {
+        collect_files(root, &mut files);
+    }
+
+    if let Some(ref main) = project.main {
+        files.push(main.to_string_lossy().into_owned());
+    }
+
+    files.retain(|file| should_file_be_parsed(Path::new(file)));
+    files
+}
+
+// what hover and go-to-definition show for the name under the cursor
+struct Symbol {
+    signature: String,
+    definition: Option<(FileId, Position, Name)>,
+}
+
+struct Analysis<'a, 'ast: 'a> {
+    vm: &'a VM<'ast>,
+    uri: &'a str,
+    file: FileId,
+    ast: &'a ast::File,
+    content: &'a str,
+    fcts: Vec<FctId>, // functions with a body in the document
+}
+
+impl<'a, 'ast> Analysis<'a, 'ast> {
+    fn new(
+        vm: &'a VM<'ast>,
+        doc: &Document<'a>,
+        file: FileId,
+        ast: &'a ast::File,
+    ) -> Analysis<'a, 'ast> {
+        let mut fcts = Vec::new();
+
+        for fct in vm.fcts.iter() {
+            let fct = fct.read();
+
+            if fct.file == file && fct.is_src() {
+                fcts.push(fct.id);
+            }
+        }
+
+        Analysis {
+            vm,
+            uri: doc.uri,
+            file,
+            ast,
+            content: doc.text,
+            fcts,
+        }
+    }
+
+    fn results(&self) -> DocumentAnalysis {
+        DocumentAnalysis {
+            content: self.content.into(),
+            diagnostics: self.diagnostics(),
+            symbols: self.document_symbols(),
+            targets: self.targets(),
+        }
+    }
+
+    fn diagnostics(&self) -> Vec<Json> {
+        let diag = self.vm.diag.lock();
+
+        diag.errors()
+            .iter()
+            .chain(diag.warnings())
+            .filter(|error| error.file == self.file)
+            .map(|error| self.diagnostic(error))
+            .collect()
+    }
+
+    fn diagnostic(&self, error: &SemErrorAndPos) -> Json {
+        let (start, end) = error.range(self.vm);
+        let mut message = error.msg.message();
+        let mut related = Vec::new();
+
+        for note in &error.notes {
+            let location = note
+                .location
+                .and_then(|(file, pos)| self.location(file, pos, None));
+
+            match location {
+                Some(location) => related.push(object(vec![
+                    ("location", location),
+                    ("message", Json::String(note.msg.clone())),
+                ])),
+
+                None => {
+                    message.push('\n');
+                    message.push_str(&note.msg);
+                }
+            }
+        }
+
+        let severity = match error.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+        };
+
+        let start = offset_of_position(self.content, start);
+        let end = offset_of_position(self.content, end);
+
+        object(vec![
+            ("range", range(self.content, start, end)),
+            ("severity", Json::U64(severity)),
+            ("code", Json::String(error.msg.code().into())),
+            ("source", Json::String("dora".into())),
+            ("message", Json::String(message)),
+            ("relatedInformation", Json::Array(related)),
+        ])
+    }
+
+    // hover and go-to-definition are looked up for every token of the document
+    fn targets(&self) -> Vec<Target> {
+        token_ranges(self.content)
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let symbol = self.symbol_at(start)?;
+                let value = format!("```dora\n{}\n```", symbol.signature);
+                let contents = object(vec![
+                    ("kind", Json::String("markdown".into())),
+                    ("value", Json::String(value)),
+                ]);
+
+                let definition = symbol
+                    .definition
+                    .and_then(|(file, pos, name)| self.location(file, pos, Some(name)))
+                    .unwrap_or(Json::Null);
+
+                Some(Target {
+                    start,
+                    end,
+                    hover: object(vec![("contents", contents)]),
+                    definition,
+                })
+            })
+            .collect()
+    }
+
+    fn symbol_at(&self, offset: usize) -> Option<Symbol> {
+        let fct_id = self.fct_at(offset)?;
+        let fct = self.vm.fcts.idx(fct_id);
+        let fct = fct.read();
+        let src = fct.src().read();
+
+        let mut finder = NodeFinder::new(offset);
+        finder.visit_fct(fct.ast);
+
+        // the innermost expression with a known meaning wins
+        for expr in finder.exprs.iter().rev() {
+            if let Some(call_type) = src.map_calls.get(expr.id()) {
+                if let Some(fct_id) = called_fct(call_type) {
+                    return Some(self.fct_symbol(fct_id));
+                }
+            }
+
+            if let Some(ident_type) = src.map_idents.get(expr.id()) {
+                if let Some(symbol) = self.ident_symbol(&fct, &src, ident_type) {
+                    return Some(symbol);
+                }
+            }
+
+            if let Some(&ty) = src.map_tys.get(expr.id()) {
+                if !ty.is_error() {
+                    return Some(Symbol {
+                        signature: ty.name(self.vm),
+                        definition: None,
+                    });
+                }
+            }
+        }
+
+        if let Some(ty) = finder.ty {
+            return self.type_symbol(ty.name);
+        }
+
+        for decl in &finder.decls {
+            match self.name_at(decl.pos, decl.name) {
+                Some((start, end)) if start <= offset && offset < end => {
+                    let var = src.vars.iter().find(|var| var.node_id == decl.id)?;
+                    return Some(self.var_symbol(var.name, var.ty, var.reassignable, decl.pos));
+                }
+
+                _ => {}
+            }
+        }
+
+        let ast = fct.ast;
+        let before_body = ast
+            .block
+            .as_ref()
+            .map(|block| offset < block.span.start() as usize)
+            .unwrap_or(true);
+
+        if before_body && !fct.is_lambda() {
+            return Some(self.fct_symbol(fct_id));
+        }
+
+        None
+    }
+
+    // the innermost function whose source contains the offset, lambdas are
+    // functions of their own
+    fn fct_at(&self, offset: usize) -> Option<FctId> {
+        let mut result: Option<(FctId, u32)> = None;
+
+        for &fct_id in &self.fcts {
+            let fct = self.vm.fcts.idx(fct_id);
+            let fct = fct.read();
+
+            if !contains(fct.ast.span, offset) {
+                continue;
+            }
+
+            let size = fct.ast.span.count();
+
+            if result.map(|(_, smallest)| size < smallest).unwrap_or(true) {
+                result = Some((fct.id, size));
+            }
+        }
+
+        result.map(|(fct_id, _)| fct_id)
+    }
+
+    fn ident_symbol(&self, fct: &Fct, src: &FctSrc, ident_type: &IdentType) -> Option<Symbol> {
+        let vm = self.vm;
+
+        let symbol = match *ident_type {
+            IdentType::Var(var_id) => {
+                let var = &src.vars[var_id];
+                let pos = self.find_decl(fct, var.node_id)?;
+                self.var_symbol(var.name, var.ty, var.reassignable, pos)
+            }
+
+            IdentType::Global(global_id) => {
+                let global = vm.globals.idx(global_id);
+                let global = global.read();
+                let keyword = if global.reassignable { "var" } else { "let" };
+
+                Symbol {
+                    signature: format!(
+                        "{} {}: {}",
+                        keyword,
+                        vm.interner.str(global.name),
+                        global.ty.name(vm)
+                    ),
+                    definition: Some((global.file, global.pos, global.name)),
+                }
+            }
+
+            IdentType::Field(ty, field_id) if ty.is_module() => {
+                let module = vm.modules.idx(ty.module_id()?);
+                let module = module.read();
+                let field = &module.fields[field_id];
+                let keyword = if field.reassignable { "var" } else { "let" };
+
+                Symbol {
+                    signature: format!(
+                        "{} {}::{}: {}",
+                        keyword,
+                        vm.interner.str(module.name),
+                        vm.interner.str(field.name),
+                        field.ty.name(vm)
+                    ),
+                    definition: Some((module.file, field.pos, field.name)),
+                }
+            }
+
+            IdentType::Field(ty, field_id) => {
+                let cls_id = ty.cls_id(vm)?;
+                let cls = vm.classes.idx(cls_id);
+                let cls = cls.read();
+                let field = &cls.fields[field_id];
+                let keyword = if field.reassignable { "var" } else { "let" };
+
+                Symbol {
+                    signature: format!(
+                        "{} {}.{}: {}",
+                        keyword,
+                        vm.interner.str(cls.name),
+                        vm.interner.str(field.name),
+                        field.ty.name(vm)
+                    ),
+                    definition: Some((cls.file, field.pos, field.name)),
+                }
+            }
+
+            IdentType::Const(const_id) => {
+                let xconst = vm.consts.idx(const_id);
+                let xconst = xconst.lock();
+
+                Symbol {
+                    signature: format!(
+                        "const {}: {}",
+                        vm.interner.str(xconst.name),
+                        xconst.ty.name(vm)
+                    ),
+                    definition: Some((xconst.file, xconst.pos, xconst.name)),
+                }
+            }
+
+            IdentType::Fct(fct_id) | IdentType::FctType(fct_id, _) => self.fct_symbol(fct_id),
+
+            IdentType::Struct(_)
+            | IdentType::Class(_)
+            | IdentType::ClassType(_, _)
+            | IdentType::Module(_)
+            | IdentType::Enum(_)
+            | IdentType::EnumValue(_, _) => self.named_symbol(ident_type)?,
+
+            _ => return None,
+        };
+
+        Some(symbol)
+    }
+
+    fn named_symbol(&self, ident_type: &IdentType) -> Option<Symbol> {
+        let vm = self.vm;
+
+        let (signature, file, pos, name) = match *ident_type {
+            IdentType::Struct(struct_id) => {
+                let xstruct = vm.structs.idx(struct_id);
+                let xstruct = xstruct.lock();
+                let name = vm.interner.str(xstruct.name).to_string();
+                (
+                    format!("struct {}", name),
+                    xstruct.file,
+                    xstruct.pos,
+                    xstruct.name,
+                )
+            }
+
+            IdentType::Class(cls_id) | IdentType::ClassType(cls_id, _) => {
+                let cls = vm.classes.idx(cls_id);
+                let cls = cls.read();
+                let name = vm.interner.str(cls.name).to_string();
+                (format!("class {}", name), cls.file, cls.pos, cls.name)
+            }
+
+            IdentType::Module(module_id) => {
+                let module = vm.modules.idx(module_id);
+                let module = module.read();
+                let name = vm.interner.str(module.name).to_string();
+                (
+                    format!("module {}", name),
+                    module.file,
+                    module.pos,
+                    module.name,
+                )
+            }
+
+            IdentType::Enum(enum_id) => {
+                let xenum = vm.enums[enum_id].read();
+                let name = vm.interner.str(xenum.name).to_string();
+                (format!("enum {}", name), xenum.file, xenum.pos, xenum.name)
+            }
+
+            IdentType::EnumValue(enum_id, value) => {
+                let xenum = vm.enums[enum_id].read();
+                let variant = xenum
+                    .name_to_value
+                    .iter()
+                    .find(|&(_, &idx)| idx == value)
+                    .map(|(&name, _)| name)?;
+                let signature = format!(
+                    "{}::{}",
+                    vm.interner.str(xenum.name),
+                    vm.interner.str(variant)
+                );
+                (signature, xenum.file, xenum.pos, xenum.name)
+            }
+
+            _ => return None,
+        };
+
+        Some(Symbol {
+            signature,
+            definition: Some((file, pos, name)),
+        })
+    }
+
+    fn type_symbol(&self, name: Name) -> Option<Symbol> {
+        let vm = self.vm;
+        let sym = vm.with_file_scope(self.file, || vm.sym.lock().get_type(name))?;
+
+        let ident_type = match sym {
+            TypeSym::SymClass(cls_id) => IdentType::Class(cls_id),
+            TypeSym::SymStruct(struct_id) => IdentType::Struct(struct_id),
+            TypeSym::SymEnum(enum_id) => IdentType::Enum(enum_id),
+
+            TypeSym::SymTrait(trait_id) => {
+                let xtrait = self.vm.traits[trait_id].read();
+                let name = self.vm.interner.str(xtrait.name).to_string();
+
+                return Some(Symbol {
+                    signature: format!("trait {}", name),
+                    definition: Some((xtrait.file, xtrait.pos, xtrait.name)),
+                });
+            }
+
+            TypeSym::SymAlias(alias_id) => {
+                let alias = &self.vm.aliases[alias_id];
+                let name = self.vm.interner.str(alias.name).to_string();
+
+                return Some(Symbol {
+                    signature: format!("type {}", name),
+                    definition: Some((alias.file, alias.pos, alias.name)),
+                });
+            }
+
+            _ => return None,
+        };
+
+        self.named_symbol(&ident_type)
+    }
+
+    fn fct_symbol(&self, fct_id: FctId) -> Symbol {
+        let fct = self.vm.fcts.idx(fct_id);
+        let fct = fct.read();
+
+        Symbol {
+            signature: format!("fun {}", fct.full_name(self.vm)),
+            definition: Some((fct.file, fct.pos, fct.name)),
+        }
+    }
+
+    fn var_symbol(&self, name: Name, ty: BuiltinType, reassignable: bool, pos: Position) -> Symbol {
+        let keyword = if reassignable { "var" } else { "let" };
+
+        Symbol {
+            signature: format!(
+                "{} {}: {}",
+                keyword,
+                self.vm.interner.str(name),
+                ty.name(self.vm)
+            ),
+            definition: Some((self.file, pos, name)),
+        }
+    }
+
+    // position of the declaration of a variable, variables used in lambdas
+    // might be declared in one of the enclosing functions
+    fn find_decl(&self, fct: &Fct, id: NodeId) -> Option<Position> {
+        let mut finder = NodeFinder::new(usize::max_value());
+        finder.visit_fct(fct.ast);
+
+        if let Some(decl) = finder.decls.iter().find(|decl| decl.id == id) {
+            return Some(decl.pos);
+        }
+
+        match fct.parent {
+            FctParent::Function(parent_id) => {
+                let parent = self.vm.fcts.idx(parent_id);
+                let parent = parent.read();
+                self.find_decl(&parent, id)
+            }
+
+            _ => None,
+        }
+    }
+
+    // location of the name declared at the given position, the position of a
+    // declaration is usually the position of its keyword
+    fn location(&self, file: FileId, pos: Position, name: Option<Name>) -> Option<Json> {
+        let uri = if file == self.file {
+            self.uri.to_string()
+        } else {
+            let path = Path::new(&self.vm.file(file).name);
+
+            if !path.is_file() {
+                return None;
+            }
+
+            path_to_uri(&path.canonicalize().ok()?)
+        };
+
+        let content = &self.vm.file(file).content;
+        let start = offset_of_position(content, pos);
+        let (start, end) = match name {
+            Some(name) => {
+                name_range(content, start, &self.vm.interner.str(name)).unwrap_or((start, start))
+            }
+            None => (start, start),
+        };
+
+        Some(object(vec![
+            ("uri", Json::String(uri)),
+            ("range", range(content, start, end)),
+        ]))
+    }
+
+    fn name_at(&self, pos: Position, name: Name) -> Option<(usize, usize)> {
+        let start = offset_of_position(self.content, pos);
+        name_range(self.content, start, &self.vm.interner.str(name))
+    }
+
+    fn document_symbols(&self) -> Vec<Json> {
+        self.ast
+            .elements
+            .iter()
+            .filter_map(|elem| self.elem_symbol(elem))
+            .collect()
+    }
+
+    fn elem_symbol(&self, elem: &Elem) -> Option<Json> {
+        let symbol = match *elem {
+            Elem::ElemFunction(ref fct) => self.fct_doc_symbol(fct),
+
+            Elem::ElemClass(ref cls) => {
+                let mut children: Vec<Json> = cls
+                    .fields
+                    .iter()
+                    .map(|f| self.doc_symbol(f.name, FIELD, f.pos, f.span, Vec::new()))
+                    .collect();
+                children.extend(cls.methods.iter().map(|m| self.fct_doc_symbol(m)));
+
+                self.doc_symbol(cls.name, CLASS, cls.pos, cls.span, children)
+            }
+
+            Elem::ElemStruct(ref xstruct) => {
+                let children = xstruct
+                    .fields
+                    .iter()
+                    .map(|f| self.doc_symbol(f.name, FIELD, f.pos, f.span, Vec::new()))
+                    .collect();
+
+                self.doc_symbol(xstruct.name, STRUCT, xstruct.pos, xstruct.span, children)
+            }
+
+            Elem::ElemTrait(ref xtrait) => {
+                let children = xtrait
+                    .methods
+                    .iter()
+                    .map(|m| self.fct_doc_symbol(m))
+                    .collect();
+
+                self.doc_symbol(xtrait.name, INTERFACE, xtrait.pos, xtrait.span, children)
+            }
+
+            Elem::ElemImpl(ref ximpl) => {
+                let interner = &self.vm.interner;
+                let class_name = ximpl.class_type.to_string(interner);
+                let name = match ximpl.trait_type {
+                    Some(ref trait_type) => {
+                        format!("impl {} for {}", trait_type.to_string(interner), class_name)
+                    }
+                    None => format!("impl {}", class_name),
+                };
+
+                let children = ximpl
+                    .methods
+                    .iter()
+                    .map(|m| self.fct_doc_symbol(m))
+                    .collect();
+                let start = offset_of_position(self.content, ximpl.pos);
+                let (start, end) = span_range(ximpl.span).unwrap_or((start, start));
+
+                symbol_json(
+                    name,
+                    OBJECT,
+                    range(self.content, start, end),
+                    range(self.content, start, start),
+                    children,
+                )
+            }
+
+            Elem::ElemModule(ref module) => {
+                let mut children: Vec<Json> = module
+                    .fields
+                    .iter()
+                    .map(|f| self.doc_symbol(f.name, FIELD, f.pos, f.span, Vec::new()))
+                    .collect();
+                children.extend(module.methods.iter().map(|m| self.fct_doc_symbol(m)));
+
+                self.doc_symbol(module.name, MODULE, module.pos, Span::invalid(), children)
+            }
+
+            Elem::ElemGlobal(ref global) => {
+                self.doc_symbol(global.name, VARIABLE, global.pos, global.span, Vec::new())
+            }
+
+            Elem::ElemConst(ref xconst) => {
+                self.doc_symbol(xconst.name, CONSTANT, xconst.pos, xconst.span, Vec::new())
+            }
+
+            Elem::ElemEnum(ref xenum) => {
+                let children = xenum
+                    .variants
+                    .iter()
+                    .map(|v| self.doc_symbol(v.name, ENUM_MEMBER, v.pos, v.span, Vec::new()))
+                    .collect();
+
+                self.doc_symbol(xenum.name, ENUM, xenum.pos, xenum.span, children)
+            }
+
+            Elem::ElemAlias(ref alias) => self.doc_symbol(
+                alias.name,
+                TYPE_PARAMETER,
+                alias.pos,
+                alias.span,
+                Vec::new(),
+            ),
+        };
+
+        Some(symbol)
+    }
+
+    fn fct_doc_symbol(&self, fct: &ast::Function) -> Json {
+        let kind = if fct.method { METHOD } else { FUNCTION };
+        self.doc_symbol(fct.name, kind, fct.pos, fct.span, Vec::new())
+    }
+
+    fn doc_symbol(
+        &self,
+        name: Name,
+        kind: u64,
+        pos: Position,
+        span: Span,
+        children: Vec<Json>,
+    ) -> Json {
+        let name_str = self.vm.interner.str(name).to_string();
+        let start = offset_of_position(self.content, pos);
+        let (name_start, name_end) = self.name_at(pos, name).unwrap_or((start, start));
+
+        // the selection range needs to be contained in the full range
+        let (start, end) = match span_range(span) {
+            Some((start, end)) if start <= name_start && name_end <= end => (start, end),
+            _ => (name_start, name_end),
+        };
+
+        symbol_json(
+            name_str,
+            kind,
+            range(self.content, start, end),
+            range(self.content, name_start, name_end),
+            children,
+        )
+    }
+}
+
+// kinds of document symbols defined by the protocol
+const MODULE: u64 = 2;
+const CLASS: u64 = 5;
+const METHOD: u64 = 6;
+const FIELD: u64 = 8;
+const ENUM: u64 = 10;
+const INTERFACE: u64 = 11;
+const FUNCTION: u64 = 12;
+const VARIABLE: u64 = 13;
+const CONSTANT: u64 = 14;
+const OBJECT: u64 = 19;
+const ENUM_MEMBER: u64 = 22;
+const STRUCT: u64 = 23;
+// the protocol has no kind for type aliases
+const TYPE_PARAMETER: u64 = 26;
+
+fn symbol_json(
+    name: String,
+    kind: u64,
+    range: Json,
+    selection_range: Json,
+    children: Vec<Json>,
+) -> Json {
+    object(vec![
+        ("name", Json::String(name)),
+        ("kind", Json::U64(kind)),
+        ("range", range),
+        ("selectionRange", selection_range),
+        ("children", Json::Array(children)),
+    ])
+}
+
+fn range(content: &str, start: usize, end: usize) -> Json {
+    object(vec![
+        ("start", position(offset_to_lsp(content, start))),
+        ("end", position(offset_to_lsp(content, end))),
+    ])
+}
+
+fn position(pos: LspPosition) -> Json {
+    object(vec![
+        ("line", Json::U64(pos.line as u64)),
+        ("character", Json::U64(pos.character as u64)),
+    ])
+}
+
+fn span_range(span: Span) -> Option<(usize, usize)> {
+    if span.is_valid() {
+        Some((span.start() as usize, span.end() as usize))
+    } else {
+        None
+    }
+}
+
+fn contains(span: Span, offset: usize) -> bool {
+    span_range(span)
+        .map(|(start, end)| start <= offset && offset < end)
+        .unwrap_or(false)
+}
+
+// names, numbers and all other characters but whitespace on their own
+fn token_ranges(content: &str) -> Vec<(usize, usize)> {
+    let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut ranges = Vec::new();
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((start, ch)) = chars.next() {
+        if ch.is_whitespace() {
+            continue;
+        }
+
+        let mut end = start + ch.len_utf8();
+
+        if is_ident(ch) {
+            while let Some(&(idx, next)) = chars.peek() {
+                if !is_ident(next) {
+                    break;
+                }
+
+                end = idx + next.len_utf8();
+                chars.next();
+            }
+        }
+
+        ranges.push((start, end));
+    }
+
+    ranges
+}
+
+// range of the first occurrence of the name on the line starting at `start`
+fn name_range(content: &str, start: usize, name: &str) -> Option<(usize, usize)> {
+    let line = &content[start..];
+    let line = &line[..line.find('\n').unwrap_or_else(|| line.len())];
+    let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+
+    for (idx, _) in line.match_indices(name) {
+        let before = line[..idx].chars().next_back();
+        let after = line[idx + name.len()..].chars().next();
+
+        if !before.map(is_ident).unwrap_or(false) && !after.map(is_ident).unwrap_or(false) {
+            return Some((start + idx, start + idx + name.len()));
+        }
+    }
+
+    None
+}
+
+fn called_fct(call_type: &CallType) -> Option<FctId> {
+    match *call_type {
+        CallType::Fct(fct_id, _, _)
+        | CallType::Method(_, fct_id, _)
+        | CallType::CtorNew(_, fct_id)
+        | CallType::Ctor(_, fct_id)
+        | CallType::Expr(_, fct_id)
+        | CallType::Trait(_, fct_id, _)
+        | CallType::TraitStatic(_, _, fct_id) => Some(fct_id),
+
+        CallType::Lambda(_) | CallType::Intrinsic(_) => None,
+    }
+}
+
+struct Decl {
+    id: NodeId,
+    name: Name,
+    pos: Position,
+}
+
+// collects the expressions containing the offset (outermost first), the type
+// at the offset and all declarations of a function
+struct NodeFinder<'ast> {
+    offset: usize,
+    exprs: Vec<&'ast Expr>,
+    ty: Option<&'ast ast::TypeBasicType>,
+    decls: Vec<Decl>,
+}
+
+impl<'ast> NodeFinder<'ast> {
+    fn new(offset: usize) -> NodeFinder<'ast> {
+        NodeFinder {
+            offset,
+            exprs: Vec::new(),
+            ty: None,
+            decls: Vec::new(),
+        }
+    }
+
+    fn add_decl(&mut self, id: NodeId, name: Name, pos: Position) {
+        self.decls.push(Decl { id, name, pos });
+    }
+}
+
+impl<'ast> Visitor<'ast> for NodeFinder<'ast> {
+    fn visit_param(&mut self, p: &'ast ast::Param) {
+        self.add_decl(p.id, p.name, p.pos);
+        visit::walk_param(self, p);
+    }
+
+    fn visit_type(&mut self, t: &'ast Type) {
+        if let Type::TypeBasic(ref basic) = *t {
+            if contains(basic.span, self.offset) {
+                self.ty = Some(basic);
+            }
+        }
+
+        visit::walk_type(self, t);
+    }
+
+    fn visit_stmt(&mut self, s: &'ast Stmt) {
+        match *s {
+            StmtVar(ref stmt) => self.add_decl(stmt.id, stmt.name, stmt.pos),
+            StmtFor(ref stmt) => self.add_decl(stmt.id, stmt.name, stmt.pos),
+
+            StmtTry(ref stmt) => {
+                for catch in &stmt.catch_blocks {
+                    self.add_decl(catch.id, catch.name, catch.pos);
+                }
+            }
+
+            _ => {}
+        }
+
+        visit::walk_stmt(self, s);
+    }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        if contains(e.span(), self.offset) {
+            self.exprs.push(e);
+        }
+
+        if let ExprMatch(ref expr) = *e {
+            for case in &expr.cases {
+                if let MatchPattern::Variant(ref pattern) = case.pattern {
+                    for param in pattern.params.iter().flatten() {
+                        if let Some(name) = param.name {
+                            self.add_decl(param.id, name, param.pos);
+                        }
+                    }
+                }
+            }
+        }
+
+        visit::walk_expr(self, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "file:///tmp/main.dora";
+
+    fn check(text: &str) -> DocumentAnalysis {
+        analyze(&[Document { uri: URI, text }], None).remove(0)
+    }
+
+    fn hover_text(text: &str, line: u32, character: u32) -> Option<String> {
+        let hover = check(text).hover(LspPosition::new(line, character));
+        hover
+            .find_path(&["contents", "value"])
+            .and_then(|value| value.as_string())
+            .map(|value| value.into())
+    }
+
+    fn definition_start(text: &str, line: u32, character: u32) -> Option<(u64, u64)> {
+        let location = check(text).definition(LspPosition::new(line, character));
+        let start = location.find_path(&["range", "start"])?;
+
+        Some((
+            start.find("line")?.as_u64()?,
+            start.find("character")?.as_u64()?,
+        ))
+    }
+
+    #[test]
+    fn publish_diagnostics() {
+        let diagnostics = check("fun main() {\n  let x: Int = \"a\";\n}").diagnostics();
+        let diagnostics = diagnostics.as_array().unwrap();
+
+        assert_eq!(1, diagnostics.len());
+        let diagnostic = &diagnostics[0];
+        assert_eq!(Some(1), diagnostic.find("severity").unwrap().as_u64());
+        assert_eq!(
+            Some(1),
+            diagnostic
+                .find_path(&["range", "start", "line"])
+                .unwrap()
+                .as_u64()
+        );
+    }
+
+    #[test]
+    fn hover_names() {
+        let code = "fun main() {\n  let x = foo(1);\n  x;\n}\nfun foo(a: Int) -> Int { a }";
+
+        assert_eq!(
+            Some("```dora\nlet x: Int\n```".into()),
+            hover_text(code, 2, 2)
+        );
+        assert_eq!(
+            Some("```dora\nfun foo(Int) -> Int\n```".into()),
+            hover_text(code, 1, 11)
+        );
+        assert_eq!(Some("```dora\nInt\n```".into()), hover_text(code, 1, 14));
+        assert_eq!(
+            Some("```dora\nlet x: Int\n```".into()),
+            hover_text(code, 1, 6)
+        );
+    }
+
+    #[test]
+    fn hover_alias() {
+        let code = "type Number = Int;\nfun main() {\n  let x: Number = 1;\n}";
+
+        assert_eq!(
+            Some("```dora\ntype Number\n```".into()),
+            hover_text(code, 2, 10)
+        );
+        assert_eq!(Some((0, 5)), definition_start(code, 2, 10));
+    }
+
+    #[test]
+    fn goto_definition() {
+        let code = "class Foo(let value: Int)\nfun main() {\n  let foo = Foo(1);\n  foo.value;\n}";
+
+        assert_eq!(Some((2, 6)), definition_start(code, 3, 3));
+        assert_eq!(Some((0, 14)), definition_start(code, 3, 7));
+        assert_eq!(Some((0, 6)), definition_start(code, 2, 13));
+    }
+
+    #[test]
+    fn list_document_symbols() {
+        let symbols = check("class Foo {\n  var a: Int = 0;\n  fun bar() {}\n}\nfun main() {}")
+            .document_symbols();
+        let symbols = symbols.as_array().unwrap();
+
+        let names: Vec<&str> = symbols
+            .iter()
+            .map(|symbol| symbol.find("name").unwrap().as_string().unwrap())
+            .collect();
+        assert_eq!(vec!["Foo", "main"], names);
+
+        let children = symbols[0].find("children").unwrap().as_array().unwrap();
+        assert_eq!(2, children.len());
+        assert_eq!(Some(FIELD), children[0].find("kind").unwrap().as_u64());
+        assert_eq!(Some(METHOD), children[1].find("kind").unwrap().as_u64());
+    }
+
+    #[test]
+    fn check_project_together() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/project/app")
+            .canonicalize()
+            .unwrap();
+        let main = dir.join("src/main.dora");
+        let uri = path_to_uri(&main);
+        let text = fs::read_to_string(&main).unwrap();
+
+        // `geo` is only known together with the dependency of the project
+        let doc = Document {
+            uri: &uri,
+            text: &text,
+        };
+        let results = analyze(&[doc], Some(&dir));
+
+        assert_eq!(Some(dir), find_project_dir(&uri));
+        assert_eq!(Json::Array(Vec::new()), results[0].diagnostics());
+    }
+}
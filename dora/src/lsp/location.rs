@@ -0,0 +1,173 @@
+use std::path::Path;
+
+use dora_parser::lexer::position::Position;
+
+// the parser expands tabs to the next multiple of 4 when computing columns
+const TABWIDTH: u32 = 4;
+
+// Position in a document as used by the protocol: zero-based line and the
+// zero-based offset in UTF-16 code units on that line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LspPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+impl LspPosition {
+    pub fn new(line: u32, character: u32) -> LspPosition {
+        LspPosition { line, character }
+    }
+}
+
+// byte offset of the given parser position, positions past the end of a line
+// are clamped to the end of that line
+pub fn offset_of_position(content: &str, pos: Position) -> usize {
+    let line_start = line_start(content, pos.line - 1);
+    let mut column = 1;
+
+    for (idx, ch) in content[line_start..].char_indices() {
+        if column >= pos.column || ch == '\n' {
+            return line_start + idx;
+        }
+
+        column = match ch {
+            '\t' => 1 + TABWIDTH * ((column - 1) / TABWIDTH + 1),
+            _ => column + 1,
+        };
+    }
+
+    content.len()
+}
+
+pub fn offset_to_lsp(content: &str, offset: usize) -> LspPosition {
+    let offset = offset.min(content.len());
+    let prefix = &content[..offset];
+
+    let line = prefix.matches('\n').count() as u32;
+    let line_start = prefix.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    let character = prefix[line_start..]
+        .chars()
+        .map(|ch| ch.len_utf16() as u32)
+        .sum();
+
+    LspPosition { line, character }
+}
+
+pub fn lsp_to_offset(content: &str, pos: LspPosition) -> usize {
+    let line_start = line_start(content, pos.line);
+    let mut character = 0;
+
+    for (idx, ch) in content[line_start..].char_indices() {
+        if character >= pos.character || ch == '\n' {
+            return line_start + idx;
+        }
+
+        character += ch.len_utf16() as u32;
+    }
+
+    content.len()
+}
+
+pub fn position_to_lsp(content: &str, pos: Position) -> LspPosition {
+    offset_to_lsp(content, offset_of_position(content, pos))
+}
+
+// byte offset of the start of the given zero-based line
+fn line_start(content: &str, line: u32) -> usize {
+    if line == 0 {
+        return 0;
+    }
+
+    content
+        .match_indices('\n')
+        .nth(line as usize - 1)
+        .map(|(idx, _)| idx + 1)
+        .unwrap_or_else(|| content.len())
+}
+
+pub fn uri_to_path(uri: &str) -> String {
+    match uri.find("://") {
+        Some(idx) if uri.starts_with("file:") => percent_decode(&uri[idx + 3..]),
+        _ => uri.into(),
+    }
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let decoded = if bytes[idx] == b'%' {
+            value
+                .get(idx + 1..idx + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+
+        match decoded {
+            Some(byte) => {
+                result.push(byte);
+                idx += 3;
+            }
+
+            None => {
+                result.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_positions() {
+        let content = "fun f() {\n\tlet x = 1;\n}\n";
+
+        assert_eq!(0, offset_of_position(content, Position::new(1, 1)));
+        assert_eq!(4, offset_of_position(content, Position::new(1, 5)));
+        assert_eq!(15, offset_of_position(content, Position::new(2, 9)));
+        assert_eq!(
+            LspPosition::new(1, 5),
+            position_to_lsp(content, Position::new(2, 9))
+        );
+        assert_eq!(15, lsp_to_offset(content, LspPosition::new(1, 5)));
+        assert_eq!(21, lsp_to_offset(content, LspPosition::new(1, 100)));
+        assert_eq!(LspPosition::new(2, 0), offset_to_lsp(content, 22));
+    }
+
+    #[test]
+    fn convert_uris() {
+        assert_eq!("/tmp/a b.dora", uri_to_path("file:///tmp/a%20b.dora"));
+        assert_eq!(
+            "file:///tmp/a%20b.dora",
+            path_to_uri(Path::new("/tmp/a b.dora"))
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use rustc_serialize::json::{Json, Object};
+use std::io::{self, BufRead, Write};
+
+// error codes defined by JSON-RPC and the language server protocol
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+pub enum Message {
+    Request {
+        id: Json,
+        method: String,
+        params: Json,
+    },
+
+    Notification {
+        method: String,
+        params: Json,
+    },
+
+    // responses to requests of the server, the server never sends any
+    Response,
+}
+
+impl Message {
+    fn from_json(json: Json) -> io::Result<Message> {
+        let method = json.find("method").and_then(|m| m.as_string());
+        let params = json.find("params").cloned().unwrap_or(Json::Null);
+
+        match (json.find("id"), method) {
+            (Some(id), Some(method)) => Ok(Message::Request {
+                id: id.clone(),
+                method: method.into(),
+                params,
+            }),
+
+            (None, Some(method)) => Ok(Message::Notification {
+                method: method.into(),
+                params,
+            }),
+
+            (Some(_), None) => Ok(Message::Response),
+
+            (None, None) => Err(invalid_data("message without id and method")),
+        }
+    }
+}
+
+// reads the next message, each message is preceded by a header with its length:
+// `Content-Length: <bytes>\r\n\r\n`. Returns `None` at the end of input.
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Message>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            break;
+        }
+
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim();
+        let value = parts.next().unwrap_or("").trim();
+
+        if name.eq_ignore_ascii_case("Content-Length") {
+            let length = value
+                .parse::<usize>()
+                .map_err(|_| invalid_data("invalid Content-Length"))?;
+            content_length = Some(length);
+        }
+    }
+
+    let content_length = content_length.ok_or_else(|| invalid_data("missing Content-Length"))?;
+    let mut content = vec![0; content_length];
+    input.read_exact(&mut content)?;
+
+    let content = String::from_utf8(content).map_err(|_| invalid_data("invalid UTF-8"))?;
+    let json = Json::from_str(&content).map_err(|_| invalid_data("invalid JSON"))?;
+
+    Message::from_json(json).map(Some)
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Json) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+pub fn response(id: Json, result: Json) -> Json {
+    object(vec![
+        ("jsonrpc", Json::String("2.0".into())),
+        ("id", id),
+        ("result", result),
+    ])
+}
+
+pub fn error_response(id: Json, code: i64, message: &str) -> Json {
+    let error = object(vec![
+        ("code", Json::I64(code)),
+        ("message", Json::String(message.into())),
+    ]);
+
+    object(vec![
+        ("jsonrpc", Json::String("2.0".into())),
+        ("id", id),
+        ("error", error),
+    ])
+}
+
+pub fn notification(method: &str, params: Json) -> Json {
+    object(vec![
+        ("jsonrpc", Json::String("2.0".into())),
+        ("method", Json::String(method.into())),
+        ("params", params),
+    ])
+}
+
+pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    let mut object = Object::new();
+
+    for (name, value) in fields {
+        object.insert(name.into(), value);
+    }
+
+    Json::Object(object)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_and_write_messages() {
+        let mut input: &[u8] = b"Content-Length: 44\r\n\r\n\
+            {\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"shutdown\"}";
+
+        match read_message(&mut input).unwrap() {
+            Some(Message::Request { id, method, params }) => {
+                assert_eq!(Json::U64(1), id);
+                assert_eq!("shutdown", method);
+                assert_eq!(Json::Null, params);
+            }
+
+            _ => panic!("request expected"),
+        }
+
+        assert!(read_message(&mut input).unwrap().is_none());
+
+        let mut output = Vec::new();
+        write_message(&mut output, &response(Json::U64(1), Json::Null)).unwrap();
+        assert_eq!(
+            "Content-Length: 38\r\n\r\n{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":null}",
+            String::from_utf8(output).unwrap()
+        );
+    }
+}
@@ -0,0 +1,7 @@
+#[cfg(not(test))]
+use std::process::exit;
+
+#[cfg(not(test))]
+fn main() {
+    exit(dora::run_lsp());
+}
@@ -5,13 +5,14 @@ use std::rc::Rc;
 use crate::asm::Assembler;
 use crate::compiler::codegen::AnyReg;
 use crate::compiler::fct::{
-    Code, Comments, GcPoint, GcPoints, JitDescriptor, LazyCompilationData, LazyCompilationSite,
-    PositionTable,
+    CatchType, Code, Comments, ExceptionHandler, GcPoint, GcPoints, JitDescriptor,
+    LazyCompilationData, LazyCompilationSite, PositionTable,
 };
-use crate::cpu::{Mem, Reg, SCRATCH};
+use crate::cpu::{Mem, Reg, REG_FP, REG_THREAD, REG_TMP1, SCRATCH};
 use crate::dseg::DSeg;
 use crate::mem;
 use crate::object::Header;
+use crate::threads::ThreadLocalData;
 use crate::ty::MachineMode;
 use crate::vm::{Trap, VM};
 use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
@@ -39,6 +40,7 @@ pub struct MacroAssembler {
     gcpoints: GcPoints,
     comments: Comments,
     positions: PositionTable,
+    exception_handlers: Vec<ExceptionHandler>,
     scratch_registers: ScratchRegisters,
 }
 
@@ -54,6 +56,7 @@ impl MacroAssembler {
             gcpoints: GcPoints::new(),
             comments: Comments::new(),
             positions: PositionTable::new(),
+            exception_handlers: Vec::new(),
             scratch_registers: ScratchRegisters::new(),
         }
     }
@@ -74,6 +77,7 @@ impl MacroAssembler {
             stacksize,
             self.comments,
             self.positions,
+            self.exception_handlers,
             desc,
         )
     }
@@ -108,6 +112,65 @@ impl MacroAssembler {
         self.fix_forward_jumps();
     }
 
+    // Emits all bailouts collected so far right here instead of at the end of
+    // the function. Needed at the end of try-blocks: the return address of the
+    // trap needs to be inside the try-block for the exception handler to apply.
+    pub fn emit_bailouts_inline(&mut self) {
+        if self.bailouts.is_empty() {
+            return;
+        }
+
+        let lbl_end = self.create_label();
+        self.jump(lbl_end);
+
+        let bailouts = self.bailouts.drain(0..).collect::<Vec<_>>();
+
+        for bailout in &bailouts {
+            let (lbl, trap, pos) = *bailout;
+
+            self.bind_label(lbl);
+            self.trap(trap, pos);
+        }
+
+        self.bind_label(lbl_end);
+    }
+
+    pub fn emit_exception_handler(
+        &mut self,
+        span: (usize, usize),
+        catch: usize,
+        catch_type: CatchType,
+    ) {
+        self.exception_handlers.push(ExceptionHandler {
+            try_start: span.0 as u32,
+            try_end: span.1 as u32,
+            catch: catch as u32,
+            catch_type,
+        });
+    }
+
+    // Continues execution at the exception handler the unwinder stored in the
+    // thread local data. Used by the throw and trap stubs.
+    pub fn resume_exception(&mut self) {
+        self.load_mem(
+            MachineMode::Ptr,
+            REG_TMP1.into(),
+            Mem::Base(REG_THREAD, ThreadLocalData::resume_sp_offset()),
+        );
+        self.set_sp(REG_TMP1);
+        self.load_mem(
+            MachineMode::Ptr,
+            REG_FP.into(),
+            Mem::Base(REG_THREAD, ThreadLocalData::resume_fp_offset()),
+        );
+        self.load_mem(
+            MachineMode::Ptr,
+            REG_TMP1.into(),
+            Mem::Base(REG_THREAD, ThreadLocalData::resume_pc_offset()),
+        );
+        self.jump_reg(REG_TMP1);
+    }
+
     pub fn add_addr(&mut self, ptr: *const u8) -> i32 {
         self.dseg.add_addr(ptr)
     }
@@ -13,7 +13,7 @@ use crate::mem::ptr_width;
 use crate::object::{offset_of_array_data, offset_of_array_length, Header};
 use crate::threads::ThreadLocalData;
 use crate::ty::{MachineMode, TypeList};
-use crate::vm::{get_vm, FctId, Trap};
+use crate::vm::{get_vm, FctId, TraitId, Trap};
 use crate::vtable::VTable;
 
 impl MacroAssembler {
@@ -179,8 +179,8 @@ impl MacroAssembler {
         self.emit_u32(asm::blr(*scratch));
     }
 
-    pub fn indirect_call(&mut self, pos: Position, index: u32, cls_type_params: TypeList) {
-        let obj = REG_PARAMS[0];
+    pub fn indirect_call(&mut self, pos: Position, index: u32, self_index: usize) {
+        let obj = REG_PARAMS[self_index];
 
         self.test_if_nil_bailout(pos, obj, Trap::NIL);
 
@@ -205,11 +205,60 @@ impl MacroAssembler {
         self.emit_u32(asm::blr(*scratch));
         self.emit_lazy_compilation_site(LazyCompilationSite::VirtCompile(
             index,
-            cls_type_params,
+            self_index,
             TypeList::empty(),
         ));
     }
 
+    pub fn trait_call(
+        &mut self,
+        pos: Position,
+        trait_id: TraitId,
+        index: u32,
+        self_index: usize,
+        fct_tps: TypeList,
+    ) {
+        let obj = REG_PARAMS[self_index];
+
+        self.test_if_nil_bailout(pos, obj, Trap::NIL);
+
+        // need to use scratch register instead of REG_RESULT for calculations
+        // since REG_RESULT (x0) is also the first parameter
+        let scratch = self.get_scratch();
+
+        // scratch = [obj] (load vtable)
+        self.load_mem(MachineMode::Ptr, (*scratch).into(), Mem::Base(obj, 0));
+
+        // load itable
+        self.load_mem(
+            MachineMode::Ptr,
+            scratch.reg().into(),
+            Mem::Base(*scratch, VTable::offset_of_itable()),
+        );
+
+        // load method table of trait
+        let disp = (trait_id.to_usize() as i32) * ptr_width();
+        self.load_mem(
+            MachineMode::Ptr,
+            scratch.reg().into(),
+            Mem::Base(*scratch, disp),
+        );
+
+        // load method table entry into scratch
+        let disp = (index as i32) * ptr_width();
+        self.load_mem(
+            MachineMode::Ptr,
+            scratch.reg().into(),
+            Mem::Base(*scratch, disp),
+        );
+
+        // call *scratch
+        self.emit_u32(asm::blr(*scratch));
+        self.emit_lazy_compilation_site(LazyCompilationSite::TraitCompile(
+            trait_id, index, self_index, fct_tps,
+        ));
+    }
+
     pub fn load_array_elem(&mut self, mode: MachineMode, dest: AnyReg, array: Reg, index: Reg) {
         self.load_mem(
             mode,
@@ -13,7 +13,7 @@ use crate::mem::{fits_i32, ptr_width};
 use crate::object::{offset_of_array_data, offset_of_array_length, Header};
 use crate::threads::ThreadLocalData;
 use crate::ty::{MachineMode, TypeList};
-use crate::vm::{get_vm, FctId, Trap};
+use crate::vm::{get_vm, FctId, TraitId, Trap};
 use crate::vtable::VTable;
 
 impl MacroAssembler {
@@ -117,8 +117,8 @@ impl MacroAssembler {
         self.call_reg(REG_RESULT);
     }
 
-    pub fn indirect_call(&mut self, pos: Position, index: u32, cls_type_params: TypeList) {
-        let obj = REG_PARAMS[0];
+    pub fn indirect_call(&mut self, pos: Position, index: u32, self_index: usize) {
+        let obj = REG_PARAMS[self_index];
 
         self.test_if_nil_bailout(pos, obj, Trap::NIL);
 
@@ -139,11 +139,56 @@ impl MacroAssembler {
         self.call_reg(REG_RESULT);
         self.emit_lazy_compilation_site(LazyCompilationSite::VirtCompile(
             index,
-            cls_type_params,
+            self_index,
             TypeList::empty(),
         ));
     }
 
+    pub fn trait_call(
+        &mut self,
+        pos: Position,
+        trait_id: TraitId,
+        index: u32,
+        self_index: usize,
+        fct_tps: TypeList,
+    ) {
+        let obj = REG_PARAMS[self_index];
+
+        self.test_if_nil_bailout(pos, obj, Trap::NIL);
+
+        // REG_RESULT = [obj] (load vtable)
+        self.load_mem(MachineMode::Ptr, REG_RESULT.into(), Mem::Base(obj, 0));
+
+        // load itable
+        self.load_mem(
+            MachineMode::Ptr,
+            REG_RESULT.into(),
+            Mem::Base(REG_RESULT, VTable::offset_of_itable()),
+        );
+
+        // load method table of trait
+        let disp = (trait_id.to_usize() as i32) * ptr_width();
+        self.load_mem(
+            MachineMode::Ptr,
+            REG_RESULT.into(),
+            Mem::Base(REG_RESULT, disp),
+        );
+
+        // load method table entry
+        let disp = (index as i32) * ptr_width();
+        self.load_mem(
+            MachineMode::Ptr,
+            REG_RESULT.into(),
+            Mem::Base(REG_RESULT, disp),
+        );
+
+        // call *REG_RESULT
+        self.call_reg(REG_RESULT);
+        self.emit_lazy_compilation_site(LazyCompilationSite::TraitCompile(
+            trait_id, index, self_index, fct_tps,
+        ));
+    }
+
     pub fn load_array_elem(&mut self, mode: MachineMode, dest: AnyReg, array: Reg, index: Reg) {
         self.load_mem(
             mode,
@@ -841,7 +841,7 @@ pub fn write_ref(vm: &VM, obj: Ref<Obj>, cls_id: ClassDefId, fid: FieldId, value
     let cls_def = cls_def.read();
     let field = &cls_def.fields[fid.idx()];
     let slot = obj.address().offset(field.offset as usize);
-    assert!(field.ty.reference_type());
+    assert!(field.ty.reference_type(vm));
 
     unsafe {
         *slot.to_mut_ptr::<Address>() = value.address();
@@ -866,6 +866,13 @@ pub struct Stacktrace {
     pub elements: Ref<Obj>,
 }
 
+pub struct Exception {
+    pub header: Header,
+    pub backtrace: Ref<IntArray>,
+    pub elements: Ref<Obj>,
+    pub message: Ref<Str>,
+}
+
 pub struct StacktraceElement {
     pub header: Header,
     pub name: Ref<Str>,
@@ -1,15 +1,21 @@
 use crate::error::msg::SemError;
+use crate::error::suggest::did_you_mean;
 use crate::mem;
 use crate::sym::TypeSym::{
-    SymClass, SymClassTypeParam, SymEnum, SymFctTypeParam, SymStruct, SymTrait,
+    SymAlias, SymClass, SymClassTypeParam, SymEnum, SymFctTypeParam, SymStruct, SymTrait,
+    SymTraitTypeParam,
 };
 use crate::ty::{BuiltinType, TypeList};
 use crate::typeck;
-use crate::vm::{ensure_tuple, FileId, NodeMap, VM};
-use dora_parser::ast::Type::{TypeBasic, TypeLambda, TypeSelf, TypeTuple};
-use dora_parser::ast::{Expr, ExprBlockType, Stmt, Type};
+use crate::vm::{ensure_tuple, layout_deferred_tuples, AliasId, FileId, NodeMap, VM};
+use dora_parser::ast::Type::{TypeBasic, TypeLambda, TypeNullable, TypeSelf, TypeTuple};
+use dora_parser::ast::{Expr, ExprBlockType, Stmt, Type, TypeBasicType};
+use dora_parser::interner::Name;
+use dora_parser::lexer::position::Position;
 
 mod abstractck;
+mod aliasck;
+mod capabilityck;
 mod clsdefck;
 mod constdefck;
 mod enumck;
@@ -20,6 +26,9 @@ mod globaldef;
 mod globaldefck;
 mod implck;
 mod impldefck;
+mod importck;
+mod initck;
+mod lintck;
 mod moduledefck;
 mod nameck;
 pub(crate) mod prelude;
@@ -30,9 +39,10 @@ mod superck;
 mod traitdefck;
 pub mod typeparamck;
 
+// parse errors do not stop the checks, the partial AST is still checked
 macro_rules! return_on_error {
     ($vm: ident) => {{
-        if $vm.diag.lock().has_errors() {
+        if $vm.diag.lock().has_sem_errors() {
             return;
         }
     }};
@@ -65,12 +75,20 @@ pub fn check<'ast>(vm: &mut VM<'ast>) {
     );
     return_on_error!(vm);
 
+    // resolve imports, names of other namespaces are only visible afterwards
+    importck::check(vm);
+    return_on_error!(vm);
+
     // define internal classes
     prelude::internal_classes(vm);
 
     // find all trait implementations for classes
     impldefck::check(vm, &vm.ast, &map_impl_defs);
 
+    // checks the aliased types of type aliases
+    aliasck::check(vm);
+    return_on_error!(vm);
+
     // checks class/struct/trait definitions/bodies
     clsdefck::check(vm, &vm.ast, &map_cls_defs);
     moduledefck::check(vm, &vm.ast, &map_module_defs);
@@ -82,6 +100,12 @@ pub fn check<'ast>(vm: &mut VM<'ast>) {
     extensiondefck::check(vm, &vm.ast, &map_extension_defs);
     return_on_error!(vm);
 
+    // impls get their own copy of trait methods with a default body
+    specialize::specialize_trait_defaults(vm);
+
+    // fields of all structs are known, so the size of tuples can be determined
+    layout_deferred_tuples(vm);
+
     // check super class definition of classes
     clsdefck::check_super_definition(vm, &vm.ast, &map_cls_defs);
     return_on_error!(vm);
@@ -114,9 +138,18 @@ pub fn check<'ast>(vm: &mut VM<'ast>) {
     // are break and continue used in the right places?
     flowck::check(vm);
 
+    // are variables and `let` fields assigned before they are used?
+    initck::check(vm);
+
     // checks if function has a return value
     returnck::check(vm);
 
+    // rejects calls to natives whose capability was denied
+    capabilityck::check(vm);
+
+    // reports warnings for unused variables and functions and unreachable code
+    lintck::check(vm);
+
     // add size of super classes to field offsets
     superck::check(vm);
     return_on_error!(vm);
@@ -211,11 +244,59 @@ fn init_global_addresses<'ast>(vm: &VM<'ast>) {
 }
 
 pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<BuiltinType> {
+    vm.with_file_scope(file, || read_type_in(vm, file, t, None))
+}
+
+// Type arguments of the type aliases that are expanded at the moment, the
+// innermost alias comes first.
+struct AliasExpansion<'a> {
+    alias_id: AliasId,
+    type_params: &'a [BuiltinType],
+    outer: Option<&'a AliasExpansion<'a>>,
+}
+
+impl<'a> AliasExpansion<'a> {
+    fn type_param(&self, vm: &VM, name: Name) -> Option<BuiltinType> {
+        let alias = &vm.aliases[self.alias_id];
+        let idx = alias.type_params.iter().position(|&param| param == name)?;
+
+        Some(self.type_params[idx])
+    }
+
+    fn contains(&self, alias_id: AliasId) -> bool {
+        self.alias_id == alias_id || self.outer.map_or(false, |outer| outer.contains(alias_id))
+    }
+}
+
+fn read_type_in<'ast>(
+    vm: &VM<'ast>,
+    file: FileId,
+    t: &'ast Type,
+    expansion: Option<&AliasExpansion>,
+) -> Option<BuiltinType> {
     match *t {
         TypeSelf(_) => Some(BuiltinType::This),
 
         TypeBasic(ref basic) => {
-            let sym = vm.sym.lock().get_type(basic.name);
+            // names in the aliased type refer to the alias parameters or to
+            // top-level declarations, but never to the scope the alias is used in
+            let sym = match expansion {
+                Some(expansion) => {
+                    if let Some(ty) = expansion.type_param(vm, basic.name) {
+                        if !basic.params.is_empty() {
+                            let msg = SemError::NoTypeParamsExpected;
+                            vm.diag.lock().report(file, basic.pos, msg);
+                        }
+
+                        return Some(ty);
+                    }
+
+                    vm.sym.lock().get_global_type(basic.name)
+                }
+
+                None => vm.sym.lock().get_type(basic.name),
+            };
+
             if let Some(sym) = sym {
                 match sym {
                     SymClass(cls_id) => {
@@ -223,7 +304,7 @@ pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<Bui
                             let mut type_params = Vec::new();
 
                             for param in &basic.params {
-                                let param = read_type(vm, file, param);
+                                let param = read_type_in(vm, file, param, expansion);
 
                                 if let Some(param) = param {
                                     type_params.push(param);
@@ -280,7 +361,11 @@ pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<Bui
                     }
 
                     SymTrait(trait_id) => {
-                        if basic.params.len() > 0 {
+                        if !vm.traits[trait_id].read().type_params.is_empty() {
+                            // We don't support generic traits as types or bounds yet.
+                            let msg = SemError::Unimplemented;
+                            vm.diag.lock().report(file, basic.pos, msg);
+                        } else if !basic.params.is_empty() {
                             let msg = SemError::NoTypeParamsExpected;
                             vm.diag.lock().report(file, basic.pos, msg);
                         }
@@ -325,11 +410,22 @@ pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<Bui
 
                         return Some(BuiltinType::FctTypeParam(fct_id, type_param_id));
                     }
+
+                    SymTraitTypeParam(trait_id, type_param_id) => {
+                        if !basic.params.is_empty() {
+                            let msg = SemError::NoTypeParamsExpected;
+                            vm.diag.lock().report(file, basic.pos, msg);
+                        }
+
+                        return Some(BuiltinType::TraitTypeParam(trait_id, type_param_id));
+                    }
+
+                    SymAlias(alias_id) => {
+                        return read_alias(vm, file, basic, alias_id, expansion);
+                    }
                 }
             } else {
-                let name = vm.interner.str(basic.name).to_string();
-                let msg = SemError::UnknownType(name);
-                vm.diag.lock().report(file, basic.pos, msg);
+                report_unknown_type(vm, file, basic.pos, basic.name);
             }
 
             None
@@ -342,7 +438,7 @@ pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<Bui
                 let mut subtypes = Vec::new();
 
                 for subtype in &tuple.subtypes {
-                    if let Some(ty) = read_type(vm, file, subtype) {
+                    if let Some(ty) = read_type_in(vm, file, subtype, expansion) {
                         subtypes.push(ty);
                     } else {
                         return None;
@@ -358,14 +454,14 @@ pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<Bui
             let mut params = vec![];
 
             for param in &lambda.params {
-                if let Some(p) = read_type(vm, file, param) {
+                if let Some(p) = read_type_in(vm, file, param, expansion) {
                     params.push(p);
                 } else {
                     return None;
                 }
             }
 
-            let ret = if let Some(ret) = read_type(vm, file, &lambda.ret) {
+            let ret = if let Some(ret) = read_type_in(vm, file, &lambda.ret, expansion) {
                 ret
             } else {
                 return None;
@@ -376,7 +472,86 @@ pub fn read_type<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> Option<Bui
 
             Some(ty)
         }
+
+        TypeNullable(ref nullable) => {
+            let ty = read_type_in(vm, file, &nullable.ty, expansion)?;
+
+            match ty {
+                BuiltinType::Class(cls_id, list_id) if vm.args.flag_null_safety => {
+                    Some(BuiltinType::Nullable(cls_id, list_id))
+                }
+
+                // without --null-safety every class type allows nil anyway
+                BuiltinType::Class(_, _) | BuiltinType::Nullable(_, _) | BuiltinType::Error => {
+                    Some(ty)
+                }
+
+                _ => {
+                    let msg = SemError::NullableNotClass(ty.name(vm));
+                    vm.diag.lock().report(file, nullable.pos, msg);
+
+                    None
+                }
+            }
+        }
+    }
+}
+
+fn read_alias<'ast>(
+    vm: &VM<'ast>,
+    file: FileId,
+    basic: &'ast TypeBasicType,
+    alias_id: AliasId,
+    expansion: Option<&AliasExpansion>,
+) -> Option<BuiltinType> {
+    let alias = &vm.aliases[alias_id];
+
+    if expansion.map_or(false, |expansion| expansion.contains(alias_id)) {
+        let name = vm.interner.str(alias.name).to_string();
+        let msg = SemError::RecursiveAlias(name);
+        vm.diag.lock().report(file, basic.pos, msg);
+        return None;
     }
+
+    if alias.type_params.len() != basic.params.len() {
+        let msg = SemError::WrongNumberTypeParams(alias.type_params.len(), basic.params.len());
+        vm.diag.lock().report(file, basic.pos, msg);
+        return None;
+    }
+
+    let mut type_params = Vec::new();
+
+    for param in &basic.params {
+        type_params.push(read_type_in(vm, file, param, expansion)?);
+    }
+
+    let expansion = AliasExpansion {
+        alias_id,
+        type_params: &type_params,
+        outer: expansion,
+    };
+
+    // the aliased type is resolved in the file of the alias declaration
+    vm.with_file_scope(alias.file, || {
+        read_type_in(vm, alias.file, &alias.ast.ty, Some(&expansion))
+    })
+}
+
+// reads the aliased type once with all type parameters unknown, this reports
+// errors in the alias definition even if the alias is never used
+pub fn read_alias_definition(vm: &VM, alias_id: AliasId) -> Option<BuiltinType> {
+    let alias = &vm.aliases[alias_id];
+    let type_params = vec![BuiltinType::Error; alias.type_params.len()];
+
+    let expansion = AliasExpansion {
+        alias_id,
+        type_params: &type_params,
+        outer: None,
+    };
+
+    vm.with_file_scope(alias.file, || {
+        read_type_in(vm, alias.file, &alias.ast.ty, Some(&expansion))
+    })
 }
 
 pub fn read_type_unchecked<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) -> BuiltinType {
@@ -384,7 +559,7 @@ pub fn read_type_unchecked<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) ->
         TypeSelf(_) => BuiltinType::This,
 
         TypeBasic(ref basic) => {
-            let sym = vm.sym.lock().get_type(basic.name);
+            let sym = vm.with_file_scope(file, || vm.sym.lock().get_type(basic.name));
             if let Some(sym) = sym {
                 match sym {
                     SymClass(cls_id) => {
@@ -405,7 +580,11 @@ pub fn read_type_unchecked<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) ->
                     }
 
                     SymTrait(trait_id) => {
-                        if basic.params.len() > 0 {
+                        if !vm.traits[trait_id].read().type_params.is_empty() {
+                            // We don't support generic traits as types or bounds yet.
+                            let msg = SemError::Unimplemented;
+                            vm.diag.lock().report(file, basic.pos, msg);
+                        } else if !basic.params.is_empty() {
                             let msg = SemError::NoTypeParamsExpected;
                             vm.diag.lock().report(file, basic.pos, msg);
                         }
@@ -450,11 +629,20 @@ pub fn read_type_unchecked<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) ->
 
                         BuiltinType::FctTypeParam(fct_id, type_param_id)
                     }
+
+                    SymTraitTypeParam(trait_id, type_param_id) => {
+                        if !basic.params.is_empty() {
+                            let msg = SemError::NoTypeParamsExpected;
+                            vm.diag.lock().report(file, basic.pos, msg);
+                        }
+
+                        BuiltinType::TraitTypeParam(trait_id, type_param_id)
+                    }
+
+                    SymAlias(_) => read_type(vm, file, t).unwrap_or(BuiltinType::Error),
                 }
             } else {
-                let name = vm.interner.str(basic.name).to_string();
-                let msg = SemError::UnknownType(name);
-                vm.diag.lock().report(file, basic.pos, msg);
+                report_unknown_type(vm, file, basic.pos, basic.name);
 
                 BuiltinType::Error
             }
@@ -478,9 +666,25 @@ pub fn read_type_unchecked<'ast>(vm: &VM<'ast>, file: FileId, t: &'ast Type) ->
         TypeLambda(_) => {
             unimplemented!();
         }
+
+        TypeNullable(ref nullable) => match read_type_unchecked(vm, file, &nullable.ty) {
+            BuiltinType::Class(cls_id, list_id) if vm.args.flag_null_safety => {
+                BuiltinType::Nullable(cls_id, list_id)
+            }
+
+            ty => ty,
+        },
     }
 }
 
+fn report_unknown_type(vm: &VM, file: FileId, pos: Position, name: Name) {
+    let names = vm.with_file_scope(file, || vm.sym.lock().type_names());
+    let notes = did_you_mean(vm, name, names);
+    let name = vm.interner.str(name).to_string();
+    let msg = SemError::UnknownType(name);
+    vm.diag.lock().report_with_notes(file, pos, msg, notes);
+}
+
 pub fn always_returns(s: &Stmt) -> bool {
     returnck::returns_value(s).is_ok()
 }
@@ -495,6 +699,7 @@ pub fn expr_block_always_returns(e: &ExprBlockType) -> bool {
 
 #[cfg(test)]
 pub mod tests {
+    use crate::driver::cmd::Args;
     use crate::error::msg::SemError;
     use crate::test;
     use crate::vm::VM;
@@ -548,6 +753,85 @@ pub mod tests {
         });
     }
 
+    fn null_safety() -> Args {
+        let mut args: Args = Default::default();
+        args.flag_null_safety = true;
+        args
+    }
+
+    // like `ok` and `err` but with --null-safety, this also checks the stdlib
+    pub fn ok_null_safety(code: &'static str) {
+        test::parse_with_args(code, null_safety(), |vm| {
+            let diag = vm.diag.lock();
+            let errors = diag.errors();
+
+            for e in errors {
+                println!("{}", e.message(vm));
+            }
+
+            assert!(!diag.has_errors());
+        });
+    }
+
+    pub fn err_null_safety(code: &'static str, pos: Position, msg: SemError) {
+        test::parse_with_args(code, null_safety(), |vm| {
+            let diag = vm.diag.lock();
+            let errors = diag.errors();
+
+            println!("errors = {:?}", errors);
+
+            assert_eq!(1, errors.len(), "found {} errors instead", errors.len());
+            assert_eq!(pos, errors[0].pos);
+            assert_eq!(msg, errors[0].msg);
+        });
+    }
+
+    pub fn ok_files(codes: &[&'static str]) {
+        test::parse_files_with_errors(codes, |vm| {
+            let diag = vm.diag.lock();
+            let errors = diag.errors();
+
+            for e in errors {
+                println!("{}", e.message(vm));
+            }
+
+            assert!(!diag.has_errors());
+        });
+    }
+
+    pub fn err_files(codes: &[&'static str], pos: Position, msg: SemError) {
+        test::parse_files_with_errors(codes, |vm| {
+            let diag = vm.diag.lock();
+            let errors = diag.errors();
+
+            println!("errors = {:?}", errors);
+
+            assert_eq!(1, errors.len(), "found {} errors instead", errors.len());
+            assert_eq!(pos, errors[0].pos);
+            assert_eq!(msg, errors[0].msg);
+        });
+    }
+
+    pub fn err_with_note(code: &'static str, pos: Position, msg: SemError, note: &str) {
+        test::parse_with_errors(code, |vm| {
+            let diag = vm.diag.lock();
+            let errors = diag.errors();
+
+            println!("errors = {:?}", errors);
+
+            assert_eq!(1, errors.len(), "found {} errors instead", errors.len());
+            assert_eq!(pos, errors[0].pos);
+            assert_eq!(msg, errors[0].msg);
+
+            let notes = errors[0]
+                .notes
+                .iter()
+                .map(|note| note.msg.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(vec![note], notes);
+        });
+    }
+
     pub fn errors(code: &'static str, vec: &[(Position, SemError)]) {
         test::parse_with_errors(code, |vm| {
             let diag = vm.diag.lock();
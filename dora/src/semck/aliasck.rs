@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use crate::error::msg::SemError;
+use crate::semck;
+use crate::vm::VM;
+
+pub fn check<'ast>(vm: &VM<'ast>) {
+    for alias in &vm.aliases {
+        let mut names = HashSet::new();
+
+        for type_param in alias.ast.type_params.iter().flatten() {
+            if !names.insert(type_param.name) {
+                let name = vm.interner.str(type_param.name).to_string();
+                let msg = SemError::TypeParamNameNotUnique(name);
+                vm.diag.lock().report(alias.file, type_param.pos, msg);
+            }
+
+            if !type_param.bounds.is_empty() {
+                let msg = SemError::AliasTypeParamBound;
+                vm.diag.lock().report(alias.file, type_param.pos, msg);
+            }
+        }
+
+        semck::read_alias_definition(vm, alias.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::msg::SemError;
+    use crate::semck::tests::*;
+
+    #[test]
+    fn alias_as_type() {
+        ok("type Number = Int; fun f(x: Number) -> Int { return x; }");
+        ok("type Number = Int; fun f() -> Number { let x: Int = 1; return x; }");
+        ok("type Pair = (Int, Bool); fun f() -> Pair { return (1, true); }");
+        ok("type Callback = (Int) -> Bool; fun f(cb: Callback) -> Bool { return cb(1); }");
+        ok("fun f(x: Number) -> Int { return x; } type Number = Int;");
+        ok("class Foo type Bar = Foo; fun f(x: Bar) -> Foo { return x; }");
+
+        err(
+            "type Number = Int; fun f(x: Number) -> Bool { return x; }",
+            pos(1, 47),
+            SemError::ReturnType("Bool".into(), "Int".into()),
+        );
+    }
+
+    #[test]
+    fn generic_alias() {
+        ok("type Arr[T] = Array[T]; fun f(x: Arr[Int]) -> Array[Int] { return x; }");
+        ok("type Table[V] = HashMap[Int, V];
+            fun f() -> Table[String] { return HashMap[Int, String](); }");
+        ok(
+            "type Nested[T] = Array[Array[T]]; type Matrix = Nested[Double];
+            fun f(x: Matrix) -> Array[Array[Double]] { return x; }",
+        );
+
+        err(
+            "type Arr[T] = Array[T]; fun f(x: Arr[Int]) -> Int { return x; }",
+            pos(1, 53),
+            SemError::ReturnType("Int".into(), "Array[Int]".into()),
+        );
+        err(
+            "type Arr[T] = Array[T]; fun f(x: Arr) {}",
+            pos(1, 34),
+            SemError::WrongNumberTypeParams(1, 0),
+        );
+        err(
+            "type Number = Int; fun f(x: Number[Int]) {}",
+            pos(1, 29),
+            SemError::WrongNumberTypeParams(0, 1),
+        );
+    }
+
+    #[test]
+    fn alias_names_are_resolved_at_definition() {
+        // `Foo` in the aliased type is the class and not the type param of `f`
+        ok("class Foo type Bar = Foo; fun f[Foo](x: Bar) { g(x); } fun g(x: Foo) {}");
+        err(
+            "type Foo = T; fun f[T](x: T) {}",
+            pos(1, 12),
+            SemError::UnknownType("T".into()),
+        );
+    }
+
+    #[test]
+    fn recursive_alias() {
+        err(
+            "type Foo = Array[Foo];",
+            pos(1, 18),
+            SemError::RecursiveAlias("Foo".into()),
+        );
+        errors(
+            "type A = B; type B = A;",
+            &[
+                (pos(1, 22), SemError::RecursiveAlias("A".into())),
+                (pos(1, 10), SemError::RecursiveAlias("B".into())),
+            ],
+        );
+    }
+
+    #[test]
+    fn alias_type_params() {
+        err(
+            "type Foo[T, T] = Array[T];",
+            pos(1, 13),
+            SemError::TypeParamNameNotUnique("T".into()),
+        );
+        err(
+            "trait Bar {} type Foo[T: Bar] = Array[T];",
+            pos(1, 23),
+            SemError::AliasTypeParamBound,
+        );
+        err(
+            "type Foo[T] = Array[T[Int]];",
+            pos(1, 21),
+            SemError::NoTypeParamsExpected,
+        );
+    }
+}
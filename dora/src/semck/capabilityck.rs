@@ -0,0 +1,145 @@
+use crate::error::msg::SemError;
+use crate::vm::{Fct, FctSrc, VM};
+
+use dora_parser::ast::visit::*;
+use dora_parser::ast::*;
+
+pub fn check<'ast>(vm: &VM<'ast>) {
+    if vm.args.denied_capabilities().is_empty() {
+        return;
+    }
+
+    for fct in vm.fcts.iter() {
+        let fct = fct.read();
+
+        if !fct.is_src() || vm.is_trusted_file(fct.file) {
+            continue;
+        }
+
+        let src = fct.src();
+        let src = src.read();
+        let ast = fct.ast;
+
+        let mut capabilityck = CapabilityCheck {
+            vm,
+            fct: &fct,
+            src: &src,
+        };
+
+        capabilityck.visit_fct(ast);
+    }
+}
+
+struct CapabilityCheck<'a, 'ast: 'a> {
+    vm: &'a VM<'ast>,
+    fct: &'a Fct<'ast>,
+    src: &'a FctSrc,
+}
+
+impl<'a, 'ast> CapabilityCheck<'a, 'ast> {
+    fn check_call(&mut self, e: &'ast Expr) {
+        let callee_id = match self.src.map_calls.get(e.id()) {
+            Some(call_type) => call_type.fct_id(),
+            None => None,
+        };
+
+        let callee_id = match callee_id {
+            Some(callee_id) if callee_id != self.fct.id => callee_id,
+            _ => return,
+        };
+
+        let callee = self.vm.fcts.idx(callee_id);
+        let callee = callee.read();
+
+        if let Some(cap) = self.vm.denied_capability(&*callee) {
+            let name = callee.full_name(self.vm);
+            let msg = SemError::CapabilityDenied(name, cap.name().into());
+            self.vm.diag.lock().report(self.fct.file, e.pos(), msg);
+        }
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for CapabilityCheck<'a, 'ast> {
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        self.check_call(e);
+        visit::walk_expr(self, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::driver::cmd::{Args, DenyList};
+    use crate::error::msg::SemError;
+    use crate::semck::tests::*;
+    use crate::test;
+
+    fn deny(caps: &str) -> Args {
+        let mut args: Args = Default::default();
+        args.flag_deny = Some(DenyList::parse(caps).unwrap());
+        args
+    }
+
+    fn err_denied(code: &'static str, caps: &str, expected: &[(u32, u32, SemError)]) {
+        test::parse_with_args(code, deny(caps), |vm| {
+            let diag = vm.diag.lock();
+            let errors = diag.errors();
+
+            println!("errors = {:?}", errors);
+            assert_eq!(expected.len(), errors.len());
+
+            for (error, &(line, col, ref msg)) in errors.iter().zip(expected) {
+                assert_eq!(pos(line, col), error.pos);
+                assert_eq!(*msg, error.msg);
+            }
+        });
+    }
+
+    #[test]
+    fn capabilityck_allowed_by_default() {
+        ok("fun f() { exit(1); sleep(1); call(\"f\"); }");
+        err_denied("fun f() { exit(1); }", "threads,network", &[]);
+    }
+
+    #[test]
+    fn capabilityck_denied_fct() {
+        err_denied(
+            "fun f() { exit(1); }",
+            "process",
+            &[(
+                1,
+                15,
+                SemError::CapabilityDenied("exit(Int)".into(), "process".into()),
+            )],
+        );
+
+        err_denied(
+            "fun f() { sleep(1); call(\"f\"); }",
+            "threads,reflection",
+            &[
+                (
+                    1,
+                    16,
+                    SemError::CapabilityDenied("sleep(Int)".into(), "threads".into()),
+                ),
+                (
+                    1,
+                    25,
+                    SemError::CapabilityDenied("call(String)".into(), "reflection".into()),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn capabilityck_denied_method() {
+        err_denied(
+            "fun f(t: Thread) { t.start(); }",
+            "threads",
+            &[(
+                1,
+                27,
+                SemError::CapabilityDenied("Thread.start()".into(), "threads".into()),
+            )],
+        );
+    }
+}
@@ -2,6 +2,7 @@ use parking_lot::RwLock;
 use std::collections::{HashMap, HashSet};
 
 use crate::error::msg::SemError;
+use crate::error::suggest::did_you_mean;
 use crate::semck;
 use crate::semck::typeparamck;
 use crate::sym::{SymLevel, TermSym, TypeSym};
@@ -50,6 +51,7 @@ impl<'x, 'ast> ClsDefCheck<'x, 'ast> {
         let field = Field {
             id: fid,
             name,
+            pos,
             ty,
             offset: 0,
             reassignable,
@@ -150,11 +152,22 @@ impl<'x, 'ast> ClsDefCheck<'x, 'ast> {
             }
 
             _ => {
+                let candidates = {
+                    let sym = self.vm.sym.lock();
+                    sym.type_names()
+                        .into_iter()
+                        .filter(|&name| sym.get_class(name).is_some())
+                        .collect::<Vec<_>>()
+                };
+
+                let notes = did_you_mean(self.vm, parent_class.name, candidates);
                 let msg = SemError::UnknownClass(name);
-                self.vm
-                    .diag
-                    .lock()
-                    .report(self.file_id.into(), parent_class.pos, msg);
+                self.vm.diag.lock().report_with_notes(
+                    self.file_id.into(),
+                    parent_class.pos,
+                    msg,
+                    notes,
+                );
             }
         }
     }
@@ -204,7 +217,9 @@ impl<'x, 'ast> ClsDefCheck<'x, 'ast> {
 
 impl<'x, 'ast> Visitor<'ast> for ClsDefCheck<'x, 'ast> {
     fn visit_file(&mut self, f: &'ast ast::File) {
+        let scope = self.vm.enter_file_scope(self.file_id.into());
         visit::walk_file(self, f);
+        self.vm.leave_file_scope(scope);
         self.file_id += 1;
     }
 
@@ -234,15 +249,9 @@ impl<'x, 'ast> Visitor<'ast> for ClsDefCheck<'x, 'ast> {
     fn visit_field(&mut self, f: &'ast ast::Field) {
         let ty = semck::read_type(self.vm, self.file_id.into(), &f.data_type)
             .unwrap_or(BuiltinType::Unit);
+        // `let` fields without initializer need to be assigned by the class
+        // body, this is checked by initck
         self.add_field(f.pos, f.name, ty, f.reassignable);
-
-        if !f.reassignable && !f.primary_ctor && f.expr.is_none() {
-            self.vm.diag.lock().report(
-                self.file_id.into(),
-                f.pos,
-                SemError::LetMissingInitialization,
-            );
-        }
     }
 
     fn visit_ctor(&mut self, f: &'ast ast::Function) {
@@ -273,6 +282,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsDefCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: f.is_constructor,
             vtable_index: None,
@@ -328,6 +338,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsDefCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: false,
             vtable_index: None,
@@ -391,7 +402,9 @@ impl<'x, 'ast> ClsSuperDefinitionCheck<'x, 'ast> {
 
 impl<'x, 'ast> Visitor<'ast> for ClsSuperDefinitionCheck<'x, 'ast> {
     fn visit_file(&mut self, f: &'ast ast::File) {
+        let scope = self.vm.enter_file_scope(self.file_id.into());
         visit::walk_file(self, f);
+        self.vm.leave_file_scope(scope);
         self.file_id += 1;
     }
 
@@ -519,7 +532,7 @@ mod tests {
         err(
             "class Foo { let a: Int; }",
             pos(1, 13),
-            SemError::LetMissingInitialization,
+            SemError::FieldNotInitialized("Foo".into(), "a".into()),
         );
     }
 
@@ -2,6 +2,8 @@ use dora_parser::ast::visit::Visitor;
 use dora_parser::ast::{Ast, Enum};
 
 use crate::error::msg::SemError;
+use crate::semck;
+use crate::ty::BuiltinType;
 use crate::vm::{EnumId, EnumVariant, NodeMap, VM};
 
 pub fn check<'ast>(vm: &mut VM<'ast>, ast: &'ast Ast, map_enum_defs: &NodeMap<EnumId>) {
@@ -35,13 +37,22 @@ impl<'x, 'ast> Visitor<'ast> for EnumCheck<'x, 'ast> {
         assert!(e.type_params.is_none());
 
         for value in &e.variants {
+            let mut types = Vec::new();
+
+            if let Some(ref variant_types) = value.types {
+                for ty in variant_types {
+                    let ty =
+                        semck::read_type(self.vm, xenum.file, ty).unwrap_or(BuiltinType::Error);
+                    types.push(ty);
+                }
+            }
+
             let variant = EnumVariant {
                 name: value.name,
-                types: Vec::new(),
+                types,
             };
             xenum.variants.push(variant);
             let result = xenum.name_to_value.insert(value.name, enum_value_int);
-            assert!(value.types.is_none());
 
             if result.is_some() {
                 let name = self.vm.interner.str(value.name).to_string();
@@ -77,5 +88,12 @@ mod tests {
             pos(1, 15),
             SemError::ShadowEnumValue("A".into()),
         );
+        ok("enum Foo { A(Int, Bool), B(String), C }");
+        ok("enum Foo { Nil, Cons(Int, Foo) }");
+        err(
+            "enum Foo { A(Bar) }",
+            pos(1, 14),
+            SemError::UnknownType("Bar".into()),
+        );
     }
 }
@@ -1,7 +1,7 @@
 use parking_lot::RwLock;
 use std::collections::HashMap;
 
-use crate::error::msg::SemError;
+use crate::error::msg::{SemError, SemNote};
 use crate::semck;
 use crate::ty::BuiltinType;
 use crate::vm::{EnumId, ExtensionId, Fct, FctId, FctKind, FctParent, FctSrc, FileId, NodeMap, VM};
@@ -107,7 +107,11 @@ impl<'x, 'ast> ExtensionCheck<'x, 'ast> {
             if method.name == f.name && method.is_static == f.is_static {
                 let method_name = self.vm.interner.str(method.name).to_string();
                 let msg = SemError::MethodExists(method_name, method.pos);
-                self.vm.diag.lock().report(self.file_id.into(), f.pos, msg);
+                let note = SemNote::new(method.file, method.pos, "previous definition here".into());
+                self.vm
+                    .diag
+                    .lock()
+                    .report_with_notes(self.file_id.into(), f.pos, msg, vec![note]);
                 return false;
             }
         }
@@ -139,7 +143,11 @@ impl<'x, 'ast> ExtensionCheck<'x, 'ast> {
             let method = method.read();
             let method_name = self.vm.interner.str(method.name).to_string();
             let msg = SemError::MethodExists(method_name, method.pos);
-            self.vm.diag.lock().report(self.file_id.into(), f.pos, msg);
+            let note = SemNote::new(method.file, method.pos, "previous definition here".into());
+            self.vm
+                .diag
+                .lock()
+                .report_with_notes(self.file_id.into(), f.pos, msg, vec![note]);
             false
         } else {
             true
@@ -202,6 +210,7 @@ impl<'x, 'ast> Visitor<'ast> for ExtensionCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: false,
             vtable_index: None,
@@ -1,9 +1,10 @@
 use std::collections::HashSet;
 
-use crate::error::msg::SemError;
+use crate::error::msg::{SemError, SemNote};
 use crate::semck;
+use crate::semck::specialize::{replace_trait_type_params, replace_type_param};
 use crate::sym::TypeSym;
-use crate::ty::BuiltinType;
+use crate::ty::{BuiltinType, TypeList};
 use crate::vm::{self, Fct, FctId, FctParent, FctSrc, VM};
 use dora_parser::ast::visit::*;
 use dora_parser::ast::*;
@@ -47,6 +48,21 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
                 let cls = vm.classes.idx(ximpl.cls_id(vm));
                 let cls = cls.read();
 
+                for (type_param_id, param) in ximpl.type_params.iter().enumerate() {
+                    let sym = TypeSym::SymClassTypeParam(cls.id, type_param_id.into());
+                    vm.sym.lock().insert_type(param.name, sym);
+                }
+
+                // the copy of a trait default method sees the trait's type params
+                if is_trait_default(&*fct) {
+                    let xtrait = vm.traits[ximpl.trait_id()].read();
+
+                    for (type_param_id, param) in xtrait.type_params.iter().enumerate() {
+                        let sym = TypeSym::SymTraitTypeParam(xtrait.id, type_param_id.into());
+                        vm.sym.lock().insert_type(param.name, sym);
+                    }
+                }
+
                 if fct.has_self() {
                     fct.param_types.push(cls.ty);
                 }
@@ -60,14 +76,30 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
                 }
             }
 
-            FctParent::Module(_) => {}
+            FctParent::Module(module_id) => {
+                if fct.has_self() {
+                    let module = vm.modules.idx(module_id);
+                    fct.param_types.push(module.read().ty);
+                }
+            }
+
+            FctParent::Trait(trait_id) => {
+                let xtrait = vm.traits[trait_id].read();
+
+                for (type_param_id, param) in xtrait.type_params.iter().enumerate() {
+                    let sym = TypeSym::SymTraitTypeParam(trait_id, type_param_id.into());
+                    vm.sym.lock().insert_type(param.name, sym);
+                }
 
-            FctParent::Trait(_) => {
                 if fct.has_self() {
                     fct.param_types.push(BuiltinType::This);
                 }
             }
 
+            FctParent::Function(_) => {
+                insert_outer_type_params(vm, &*fct);
+            }
+
             FctParent::None => {}
         }
 
@@ -125,6 +157,7 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
             }
 
             let ty = semck::read_type(vm, fct.file, &p.data_type).unwrap_or(BuiltinType::Unit);
+            let ty = specialize_trait_default(vm, &*fct, ty);
 
             if ty == BuiltinType::This && !fct.in_trait() {
                 vm.diag
@@ -157,6 +190,7 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
 
         if let Some(ret) = ast.return_type.as_ref() {
             let ty = semck::read_type(vm, fct.file, ret).unwrap_or(BuiltinType::Unit);
+            let ty = specialize_trait_default(vm, &*fct, ty);
 
             if ty == BuiltinType::This && !fct.in_trait() {
                 vm.diag
@@ -167,6 +201,20 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
             fct.return_type = ty;
         }
 
+        if fct.is_lambda() {
+            // the closure object is passed as hidden first parameter
+            let lambda_id = vm
+                .lambda_types
+                .lock()
+                .insert(fct.param_types.clone(), fct.return_type);
+            let ty = BuiltinType::Lambda(lambda_id);
+            fct.param_types.insert(0, ty);
+
+            let src = fct.src();
+            let mut src = src.write();
+            src.var_self_mut().ty = ty;
+        }
+
         fct.initialized = true;
 
         match fct.parent {
@@ -213,6 +261,77 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
     debug_assert!(vm.sym.lock().levels() == 1);
 }
 
+// lambdas can use the type params of all their enclosing functions and classes
+fn insert_outer_type_params<'ast>(vm: &VM<'ast>, fct: &Fct<'ast>) {
+    let mut outer_id = fct.parent.fct_id();
+
+    loop {
+        let outer = vm.fcts.idx(outer_id);
+        let outer = outer.read();
+
+        if let FctParent::Function(fct_id) = outer.parent {
+            outer_id = fct_id;
+            continue;
+        }
+
+        if let FctParent::Class(cls_id) = outer.parent {
+            let cls = vm.classes.idx(cls_id);
+            let cls = cls.read();
+
+            for (type_param_id, param) in cls.type_params.iter().enumerate() {
+                let sym = TypeSym::SymClassTypeParam(cls.id, type_param_id.into());
+                vm.sym.lock().insert_type(param.name, sym);
+            }
+        }
+
+        for (type_param_id, param) in outer.type_params.iter().enumerate() {
+            let sym = TypeSym::SymFctTypeParam(outer.id, type_param_id.into());
+            vm.sym.lock().insert_type(param.name, sym);
+        }
+
+        break;
+    }
+}
+
+// impl methods copied from a trait default method already know the trait
+// method they implement, the other impl methods only get it in implck
+fn is_trait_default(fct: &Fct) -> bool {
+    match fct.parent {
+        FctParent::Impl(_) => fct.impl_for.is_some(),
+        _ => false,
+    }
+}
+
+// in the copy of a trait default method `Self` and the trait's type params
+// stand for the implementing class and the type params given in the impl
+fn specialize_trait_default(vm: &VM, fct: &Fct, ty: BuiltinType) -> BuiltinType {
+    let impl_id = match fct.parent {
+        FctParent::Impl(impl_id) if is_trait_default(fct) => impl_id,
+        _ => return ty,
+    };
+
+    let ximpl = vm.impls[impl_id].read();
+    let cls = vm.classes.idx(ximpl.cls_id(vm));
+    let cls = cls.read();
+
+    let cls_tps = (0..cls.type_params.len())
+        .map(|idx| BuiltinType::ClassTypeParam(cls.id, idx.into()))
+        .collect::<Vec<_>>();
+    let fct_tps = (0..fct.type_params.len())
+        .map(|idx| BuiltinType::FctTypeParam(fct.id, idx.into()))
+        .collect::<Vec<_>>();
+
+    let ty = replace_trait_type_params(vm, ty, &ximpl.trait_type_params);
+
+    replace_type_param(
+        vm,
+        ty,
+        &TypeList::with(cls_tps),
+        &TypeList::with(fct_tps),
+        Some(cls.ty),
+    )
+}
+
 fn check_abstract<'ast>(vm: &VM<'ast>, fct: &Fct<'ast>) {
     if !fct.is_abstract {
         return;
@@ -268,7 +387,10 @@ fn check_against_methods(vm: &VM, fct: &Fct, methods: &[FctId]) {
             let method_name = vm.interner.str(method.name).to_string();
 
             let msg = SemError::MethodExists(method_name, method.pos);
-            vm.diag.lock().report(fct.file, fct.ast.pos, msg);
+            let note = SemNote::new(method.file, method.pos, "previous definition here".into());
+            vm.diag
+                .lock()
+                .report_with_notes(fct.file, fct.ast.pos, msg, vec![note]);
             return;
         }
     }
@@ -301,8 +423,8 @@ impl<'a, 'ast> Visitor<'ast> for FctDefCheck<'a, 'ast> {
     }
 
     fn visit_type(&mut self, t: &'ast Type) {
-        self.current_type =
-            semck::read_type(self.vm, self.fct.file, t).unwrap_or(BuiltinType::Unit);
+        let ty = semck::read_type(self.vm, self.fct.file, t).unwrap_or(BuiltinType::Unit);
+        self.current_type = specialize_trait_default(self.vm, self.fct, ty);
         self.src.set_ty(t.id(), self.current_type);
     }
 }
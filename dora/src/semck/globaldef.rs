@@ -2,19 +2,20 @@ use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::error::msg::SemError;
+use crate::error::msg::{SemError, SemNote};
 use crate::gc::Address;
 use crate::sym::TermSym::{
     SymClassConstructor, SymConst, SymFct, SymGlobal, SymModule, SymStructConstructor, SymVar,
 };
-use crate::sym::TypeSym::{SymClass, SymEnum, SymStruct, SymTrait};
+use crate::sym::TypeSym::{SymAlias, SymClass, SymEnum, SymStruct, SymTrait};
 use crate::sym::{SymLevel, TermSym, TypeSym};
-use crate::ty::BuiltinType;
+use crate::ty::{BuiltinType, TypeList};
 use crate::vm::module::ModuleId;
 use crate::vm::{
-    class, module, ClassId, ConstData, ConstId, ConstValue, EnumData, EnumId, ExtensionData,
-    ExtensionId, Fct, FctId, FctKind, FctParent, FctSrc, FileId, GlobalData, GlobalId, ImplData,
-    ImplId, NodeMap, StructData, StructId, TraitData, TraitId, TypeParam, VM,
+    class, module, AliasData, AliasId, ClassId, ConstData, ConstId, ConstValue, EnumData, EnumId,
+    ExtensionData, ExtensionId, Fct, FctId, FctKind, FctParent, FctSrc, FileId, GlobalData,
+    GlobalId, ImplData, ImplId, NamespaceData, NamespaceId, NodeMap, StructData, StructId,
+    TraitData, TraitId, TypeParam, Visibility, VM,
 };
 use dora_parser::ast::visit::*;
 use dora_parser::ast::*;
@@ -37,6 +38,7 @@ pub fn check<'ast>(
     let mut gdef = GlobalDef {
         vm,
         file_id: 0,
+        namespace: None,
         map_cls_defs,
         map_struct_defs,
         map_trait_defs,
@@ -54,6 +56,7 @@ pub fn check<'ast>(
 struct GlobalDef<'x, 'ast: 'x> {
     vm: &'x mut VM<'ast>,
     file_id: u32,
+    namespace: Option<NamespaceId>,
     map_cls_defs: &'x mut NodeMap<ClassId>,
     map_struct_defs: &'x mut NodeMap<StructId>,
     map_trait_defs: &'x mut NodeMap<TraitId>,
@@ -67,6 +70,13 @@ struct GlobalDef<'x, 'ast: 'x> {
 
 impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
     fn visit_file(&mut self, f: &'ast File) {
+        let namespace = f
+            .package
+            .as_ref()
+            .map(|package| self.ensure_namespace(&package.path));
+        self.namespace = namespace;
+        self.vm.file_namespaces.push(namespace);
+
         walk_file(self, f);
         self.file_id += 1;
     }
@@ -78,6 +88,12 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             file: self.file_id.into(),
             pos: t.pos,
             name: t.name,
+            type_params: t
+                .type_params
+                .iter()
+                .flatten()
+                .map(|param| TypeParam::new(param.name))
+                .collect(),
             methods: Vec::new(),
         };
 
@@ -86,7 +102,8 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         self.map_trait_defs.insert(t.id, id);
 
         let sym = SymTrait(id);
-        if let Some(sym) = self.vm.sym.lock().insert_type(t.name, sym) {
+        let visibility = Visibility::new(t.is_pub, false);
+        if let Some(sym) = self.insert_type(t.name, sym, visibility) {
             report_type_shadow(self.vm, t.name, self.file_id.into(), t.pos, sym);
         }
     }
@@ -115,7 +132,8 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         self.map_global_defs.insert(g.id, id);
 
         let sym = SymGlobal(id);
-        if let Some(sym) = self.vm.sym.lock().insert_term(g.name, sym) {
+        let visibility = Visibility::new(g.is_pub, false);
+        if let Some(sym) = self.insert_term(g.name, sym, visibility) {
             report_term_shadow(self.vm, g.name, self.file_id.into(), g.pos, sym);
         }
     }
@@ -127,7 +145,14 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
                 id,
                 file: self.file_id.into(),
                 pos: i.pos,
+                type_params: i
+                    .type_params
+                    .iter()
+                    .flatten()
+                    .map(|param| TypeParam::new(param.name))
+                    .collect(),
                 trait_id: None,
+                trait_type_params: TypeList::empty(),
                 class_ty: BuiltinType::Error,
                 methods: Vec::new(),
             };
@@ -151,6 +176,27 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
     }
 
     fn visit_module(&mut self, m: &'ast Module) {
+        // the module object is stored in a global that is not visible by name
+        let global_id = {
+            let mut globals = self.vm.globals.lock();
+            let global_id: GlobalId = (globals.len() as u32).into();
+            let global = GlobalData {
+                id: global_id,
+                file: self.file_id.into(),
+                pos: m.pos,
+                name: m.name,
+                ty: BuiltinType::Unit,
+                reassignable: false,
+                initializer: None,
+                address_init: Address::null(),
+                address_value: Address::null(),
+            };
+
+            globals.push(Arc::new(RwLock::new(global)));
+
+            global_id
+        };
+
         let id = {
             let mut modules = self.vm.modules.lock();
 
@@ -172,6 +218,9 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
                 virtual_fcts: Vec::new(),
 
                 traits: Vec::new(),
+
+                global_id,
+                class_def_id: None,
             };
 
             modules.push(Arc::new(RwLock::new(module)));
@@ -179,10 +228,13 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             id
         };
 
+        self.vm.globals.idx(global_id).write().ty = self.vm.modu(id);
+
         self.map_module_defs.insert(m.id, id);
 
         let sym = SymModule(id);
-        if let Some(sym) = self.vm.sym.lock().insert_term(m.name, sym) {
+        let visibility = Visibility::new(m.is_pub, m.internal);
+        if let Some(sym) = self.insert_term(m.name, sym, visibility) {
             report_term_shadow(self.vm, m.name, self.file_id.into(), m.pos, sym);
         }
     }
@@ -209,7 +261,8 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         self.map_const_defs.insert(c.id, id);
 
         let sym = SymConst(id);
-        if let Some(sym) = self.vm.sym.lock().insert_term(c.name, sym) {
+        let visibility = Visibility::new(c.is_pub, false);
+        if let Some(sym) = self.insert_term(c.name, sym, visibility) {
             report_term_shadow(self.vm, c.name, self.file_id.into(), c.pos, sym);
         }
     }
@@ -264,13 +317,14 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         self.map_cls_defs.insert(c.id, id);
 
         let sym = SymClass(id);
-        if let Some(sym) = self.vm.sym.lock().insert_type(c.name, sym) {
+        let visibility = Visibility::new(c.is_pub, c.internal);
+        if let Some(sym) = self.insert_type(c.name, sym, visibility) {
             report_type_shadow(self.vm, c.name, self.file_id.into(), c.pos, sym);
             return;
         }
 
         let sym = SymClassConstructor(id);
-        match self.vm.sym.lock().insert_term(c.name, sym) {
+        match self.insert_term(c.name, sym, visibility) {
             Some(SymModule(_)) | None => {}
             Some(sym) => {
                 report_term_shadow(self.vm, c.name, self.file_id.into(), c.pos, sym);
@@ -299,13 +353,14 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         self.map_struct_defs.insert(s.id, id);
 
         let sym = SymStruct(id);
-        if let Some(sym) = self.vm.sym.lock().insert_type(s.name, sym) {
+        let visibility = Visibility::new(s.is_pub, false);
+        if let Some(sym) = self.insert_type(s.name, sym, visibility) {
             report_type_shadow(self.vm, s.name, self.file_id.into(), s.pos, sym);
             return;
         }
 
         let sym = SymStructConstructor(id);
-        match self.vm.sym.lock().insert_term(s.name, sym) {
+        match self.insert_term(s.name, sym, visibility) {
             Some(SymModule(_)) | None => {}
             Some(sym) => {
                 report_term_shadow(self.vm, s.name, self.file_id.into(), s.pos, sym);
@@ -340,6 +395,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: false,
             vtable_index: None,
@@ -353,7 +409,9 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             specializations: RwLock::new(HashMap::new()),
         };
 
-        if let Err(sym) = self.vm.add_fct_to_sym(fct) {
+        let fct_id = self.vm.add_fct(fct);
+        let visibility = Visibility::new(f.is_pub, f.internal);
+        if let Some(sym) = self.insert_term(f.name, SymFct(fct_id), visibility) {
             report_term_shadow(self.vm, f.name, self.file_id.into(), f.pos, sym);
         }
     }
@@ -376,41 +434,180 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         self.map_enum_defs.insert(e.id, id);
 
         let sym = SymEnum(id);
-        if let Some(sym) = self.vm.sym.lock().insert_type(e.name, sym) {
+        let visibility = Visibility::new(e.is_pub, false);
+        if let Some(sym) = self.insert_type(e.name, sym, visibility) {
             report_type_shadow(self.vm, e.name, self.file_id.into(), e.pos, sym);
         }
     }
+
+    fn visit_alias(&mut self, a: &'ast Alias) {
+        let id: AliasId = (self.vm.aliases.len() as u32).into();
+        let alias = AliasData {
+            id,
+            file: self.file_id.into(),
+            pos: a.pos,
+            name: a.name,
+            type_params: a
+                .type_params
+                .iter()
+                .flatten()
+                .map(|param| param.name)
+                .collect(),
+            ast: a,
+        };
+
+        self.vm.aliases.push(alias);
+
+        let sym = SymAlias(id);
+        let visibility = Visibility::new(a.is_pub, false);
+        if let Some(sym) = self.insert_type(a.name, sym, visibility) {
+            report_type_shadow(self.vm, a.name, self.file_id.into(), a.pos, sym);
+        }
+    }
+}
+
+impl<'x, 'ast> GlobalDef<'x, 'ast> {
+    fn ensure_namespace(&mut self, path: &[Name]) -> NamespaceId {
+        if let Some(id) = self.vm.find_namespace(path) {
+            return id;
+        }
+
+        let id: NamespaceId = (self.vm.namespaces.len() as u32).into();
+        self.vm.namespaces.push(NamespaceData {
+            id,
+            path: path.to_vec(),
+            table: SymLevel::new(),
+            visibility: HashMap::new(),
+        });
+
+        id
+    }
+
+    // top-level declarations go into the table of the file's namespace, the
+    // root namespace is the outermost level of `vm.sym`
+    fn insert_type(&mut self, name: Name, sym: TypeSym, visibility: Visibility) -> Option<TypeSym> {
+        match self.namespace {
+            Some(id) => {
+                let namespace = &mut self.vm.namespaces[id];
+                namespace.visibility.insert(name, visibility);
+                namespace.table.insert_type(name, sym)
+            }
+
+            None => self.vm.sym.lock().insert_type(name, sym),
+        }
+    }
+
+    fn insert_term(&mut self, name: Name, sym: TermSym, visibility: Visibility) -> Option<TermSym> {
+        match self.namespace {
+            Some(id) => {
+                let namespace = &mut self.vm.namespaces[id];
+                namespace.visibility.insert(name, visibility);
+                namespace.table.insert_term(name, sym)
+            }
+
+            None => self.vm.sym.lock().insert_term(name, sym),
+        }
+    }
 }
 
 pub fn report_type_shadow(vm: &VM, name: Name, file: FileId, pos: Position, sym: TypeSym) {
     let name = vm.interner.str(name).to_string();
 
-    let msg = match sym {
-        SymClass(_) => SemError::ShadowClass(name),
-        SymStruct(_) => SemError::ShadowStruct(name),
-        SymTrait(_) => SemError::ShadowTrait(name),
-        SymEnum(_) => SemError::ShadowEnum(name),
+    let (msg, previous) = match sym {
+        SymClass(id) => {
+            let cls = vm.classes.idx(id);
+            let cls = cls.read();
+            (SemError::ShadowClass(name), (cls.file, cls.pos))
+        }
+        SymStruct(id) => {
+            let xstruct = vm.structs.idx(id);
+            let xstruct = xstruct.lock();
+            (SemError::ShadowStruct(name), (xstruct.file, xstruct.pos))
+        }
+        SymTrait(id) => {
+            let xtrait = vm.traits[id].read();
+            (SemError::ShadowTrait(name), (xtrait.file, xtrait.pos))
+        }
+        SymEnum(id) => {
+            let xenum = vm.enums[id].read();
+            (SemError::ShadowEnum(name), (xenum.file, xenum.pos))
+        }
+        SymAlias(id) => {
+            let alias = &vm.aliases[id];
+            (SemError::ShadowAlias(name), (alias.file, alias.pos))
+        }
         _ => unimplemented!(),
     };
 
-    vm.diag.lock().report(file, pos, msg);
+    report_shadow(vm, file, pos, msg, Some(previous));
 }
 
 pub fn report_term_shadow(vm: &VM, name: Name, file: FileId, pos: Position, sym: TermSym) {
     let name = vm.interner.str(name).to_string();
 
-    let msg = match sym {
-        SymFct(_) => SemError::ShadowFunction(name),
-        SymGlobal(_) => SemError::ShadowGlobal(name),
-        SymConst(_) => SemError::ShadowConst(name),
-        SymModule(_) => SemError::ShadowModule(name),
-        SymVar(_) => SemError::ShadowParam(name),
-        SymClassConstructor(_) => SemError::ShadowClassConstructor(name),
-        SymStructConstructor(_) => SemError::ShadowStructConstructor(name),
+    let (msg, previous) = match sym {
+        SymFct(id) => {
+            let fct = vm.fcts.idx(id);
+            let fct = fct.read();
+            (SemError::ShadowFunction(name), Some((fct.file, fct.pos)))
+        }
+        SymGlobal(id) => {
+            let global = vm.globals.idx(id);
+            let global = global.read();
+            (
+                SemError::ShadowGlobal(name),
+                Some((global.file, global.pos)),
+            )
+        }
+        SymConst(id) => {
+            let xconst = vm.consts.idx(id);
+            let xconst = xconst.lock();
+            (SemError::ShadowConst(name), Some((xconst.file, xconst.pos)))
+        }
+        SymModule(id) => {
+            let module = vm.modules.idx(id);
+            let module = module.read();
+            (
+                SemError::ShadowModule(name),
+                Some((module.file, module.pos)),
+            )
+        }
+        SymVar(_) => (SemError::ShadowParam(name), None),
+        SymClassConstructor(id) => {
+            let cls = vm.classes.idx(id);
+            let cls = cls.read();
+            (
+                SemError::ShadowClassConstructor(name),
+                Some((cls.file, cls.pos)),
+            )
+        }
+        SymStructConstructor(id) => {
+            let xstruct = vm.structs.idx(id);
+            let xstruct = xstruct.lock();
+            (
+                SemError::ShadowStructConstructor(name),
+                Some((xstruct.file, xstruct.pos)),
+            )
+        }
         _ => unimplemented!(),
     };
 
-    vm.diag.lock().report(file, pos, msg);
+    report_shadow(vm, file, pos, msg, previous);
+}
+
+fn report_shadow(
+    vm: &VM,
+    file: FileId,
+    pos: Position,
+    msg: SemError,
+    previous: Option<(FileId, Position)>,
+) {
+    let notes = previous
+        .into_iter()
+        .map(|(file, pos)| SemNote::new(file, pos, "previous definition here".into()))
+        .collect();
+
+    vm.diag.lock().report_with_notes(file, pos, msg, notes);
 }
 
 #[cfg(test)]
@@ -565,4 +762,20 @@ mod tests {
             SemError::ShadowEnum("Foo".into()),
         );
     }
+
+    #[test]
+    fn test_alias() {
+        ok("type Foo = Int;");
+
+        err(
+            "type Foo = Int; class Foo",
+            pos(1, 17),
+            SemError::ShadowAlias("Foo".into()),
+        );
+        err(
+            "class Foo type Foo = Int;",
+            pos(1, 11),
+            SemError::ShadowClass("Foo".into()),
+        );
+    }
 }
@@ -63,6 +63,7 @@ impl<'a, 'ast> Visitor<'ast> for GlobalDefCheck<'a, 'ast> {
                 use_cannon: initializer.use_cannon,
                 internal: initializer.internal,
                 internal_resolved: false,
+                capability: None,
                 overrides: None,
                 is_constructor: initializer.is_constructor,
                 vtable_index: None,
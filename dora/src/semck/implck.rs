@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::error::msg::SemError;
+use crate::semck::specialize::replace_trait_type_params;
 use crate::ty::BuiltinType;
 use crate::vm::{FileId, VM};
 
@@ -26,6 +27,7 @@ pub fn check<'ast>(vm: &mut VM<'ast>) {
                 method.is_static,
                 method.name,
                 Some(cls),
+                &ximpl.trait_type_params,
                 method.params_without_self(),
             ) {
                 method.impl_for = Some(fid);
@@ -34,16 +36,19 @@ pub fn check<'ast>(vm: &mut VM<'ast>) {
                 let trait_method = vm.fcts.idx(fid);
                 let trait_method = trait_method.read();
 
-                let return_type_valid = method.return_type
-                    == if trait_method.return_type == BuiltinType::This {
-                        cls
-                    } else {
-                        trait_method.return_type
-                    };
+                let trait_return_type = if trait_method.return_type == BuiltinType::This {
+                    cls
+                } else {
+                    replace_trait_type_params(
+                        vm,
+                        trait_method.return_type,
+                        &ximpl.trait_type_params,
+                    )
+                };
 
-                if !return_type_valid {
+                if method.return_type != trait_return_type {
                     let impl_return_type = method.return_type.name(vm);
-                    let trait_return_type = trait_method.return_type.name(vm);
+                    let trait_return_type = trait_return_type.name(vm);
 
                     let msg = SemError::ReturnTypeMismatch(impl_return_type, trait_return_type);
                     vm.diag.lock().report(ximpl.file, method.pos, msg);
@@ -186,4 +191,91 @@ mod tests {
             SemError::ReturnTypeMismatch("Int".into(), "Bool".into()),
         );
     }
+
+    #[test]
+    fn method_with_trait_type_param() {
+        ok("trait X[T] { fun get() -> T; fun set(value: T); }
+            class CX
+            impl X[Int] for CX {
+                fun get() -> Int = 0;
+                fun set(value: Int) {}
+            }");
+
+        err(
+            "trait X[T] { fun get() -> T; }
+            class CX
+            impl X[Int] for CX {
+                fun get() -> Bool = false;
+            }",
+            pos(4, 17),
+            SemError::ReturnTypeMismatch("Bool".into(), "Int".into()),
+        );
+
+        errors(
+            "trait X[T] { fun set(value: T); }
+            class CX
+            impl X[Int] for CX {
+                fun set(value: Bool) {}
+            }",
+            &[
+                (
+                    pos(4, 17),
+                    SemError::MethodNotInTrait("X".into(), "set".into(), vec!["Bool".into()]),
+                ),
+                (
+                    pos(3, 13),
+                    SemError::MethodMissingFromTrait("X".into(), "set".into(), vec!["T".into()]),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn method_with_default_body() {
+        ok("trait Foo { fun foo() -> Int = 1; }
+            class A
+            impl Foo for A {}
+            fun f() -> Int = A().foo();");
+
+        ok("trait Foo { fun foo() -> Int = 1; }
+            class A
+            impl Foo for A { fun foo() -> Int = 2; }
+            fun f() -> Int = A().foo();");
+
+        ok(
+            "trait Foo { fun foo() -> Int; fun bar() -> Int = self.foo() + 1; }
+            class A
+            impl Foo for A { fun foo() -> Int = 1; }
+            fun f() -> Int = A().bar();",
+        );
+
+        ok("trait Foo { @static fun foo() -> Int = 1; }
+            class A
+            impl Foo for A {}
+            fun f() -> Int = A::foo();");
+
+        ok("trait Foo { fun same(other: Self) -> Self = other; }
+            class A
+            impl Foo for A {}
+            fun f(a: A) -> A = a.same(A());");
+
+        ok("trait Foo[T] { fun id(x: T) -> T = x; }
+            class A
+            impl Foo[Int] for A {}
+            fun f() -> Int = A().id(1);");
+
+        ok("trait Foo { fun foo() -> Int = 1; }
+            class A
+            impl Foo for A {}
+            fun f[T: Foo](t: T) -> Int = t.foo();
+            fun g() -> Int = f[A](A());");
+
+        err(
+            "trait Foo { fun foo() -> Int = true; }
+            class A
+            impl Foo for A {}",
+            pos(1, 32),
+            SemError::ReturnType("Int".into(), "Bool".into()),
+        );
+    }
 }
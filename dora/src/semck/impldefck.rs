@@ -1,11 +1,11 @@
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::error::msg::SemError;
 use crate::semck;
 use crate::sym::TypeSym;
-use crate::ty::BuiltinType;
-use crate::vm::{Fct, FctId, FctKind, FctParent, FctSrc, FileId, ImplId, NodeMap, VM};
+use crate::ty::{BuiltinType, TypeList};
+use crate::vm::{Fct, FctId, FctKind, FctParent, FctSrc, FileId, ImplData, ImplId, NodeMap, VM};
 
 use dora_parser::ast::visit::{self, Visitor};
 use dora_parser::ast::{self, Ast};
@@ -44,32 +44,31 @@ impl<'x, 'ast> ImplCheck<'x, 'ast> {
 
         let mut ximpl = self.vm.impls[self.impl_id.unwrap()].write();
 
-        if i.type_params.is_some() {
-            // We don't support type parameters for impl-blocks yet.
-            report(self.vm, ximpl.file, i.pos, SemError::Unimplemented);
-            self.impl_id = None;
-            return;
-        }
-
-        if let Some(ref trait_type) = i.trait_type {
-            if let Some(trait_name) = trait_type.to_basic_without_type_params() {
-                if let Some(TypeSym::SymTrait(trait_id)) = self.vm.sym.lock().get_type(trait_name) {
-                    ximpl.trait_id = Some(trait_id);
-                } else {
-                    let name = self.vm.interner.str(trait_name).to_string();
-                    report(self.vm, ximpl.file, i.pos, SemError::ExpectedTrait(name));
-                }
-            } else {
-                // We don't support type parameters for traits yet.
+        let trait_type = match i.trait_type.as_ref().unwrap().to_basic() {
+            Some(trait_type) => trait_type,
+            None => {
+                // We only support traits given by name.
                 report(self.vm, ximpl.file, i.pos, SemError::Unimplemented);
                 self.impl_id = None;
                 return;
             }
+        };
+
+        if let Some(TypeSym::SymTrait(trait_id)) = self.vm.sym.lock().get_type(trait_type.name) {
+            ximpl.trait_id = Some(trait_id);
         } else {
-            // We don't support extension blocks yet.
-            report(self.vm, ximpl.file, i.pos, SemError::Unimplemented);
-            self.impl_id = None;
-            return;
+            let name = self.vm.interner.str(trait_type.name).to_string();
+            report(self.vm, ximpl.file, i.pos, SemError::ExpectedTrait(name));
+        }
+
+        self.vm.sym.lock().push_level();
+
+        if let Some(ref type_params) = i.type_params {
+            if !self.check_type_params(&ximpl, i, type_params) {
+                self.vm.sym.lock().pop_level();
+                self.impl_id = None;
+                return;
+            }
         }
 
         if let Some(class_ty) = semck::read_type(self.vm, self.file_id.into(), &i.class_type) {
@@ -85,6 +84,28 @@ impl<'x, 'ast> ImplCheck<'x, 'ast> {
             }
         }
 
+        if let Some(trait_id) = ximpl.trait_id {
+            let mut trait_type_params = Vec::new();
+
+            for param in &trait_type.params {
+                let ty = semck::read_type(self.vm, self.file_id.into(), param)
+                    .unwrap_or(BuiltinType::Error);
+                trait_type_params.push(ty);
+            }
+
+            let expected = self.vm.traits[trait_id].read().type_params.len();
+
+            if expected != trait_type_params.len() {
+                let msg = SemError::WrongNumberTypeParams(expected, trait_type_params.len());
+                report(self.vm, ximpl.file, trait_type.pos, msg);
+                ximpl.trait_id = None;
+            } else {
+                ximpl.trait_type_params = TypeList::with(trait_type_params);
+            }
+        }
+
+        self.vm.sym.lock().pop_level();
+
         if ximpl.trait_id.is_some() && !ximpl.class_ty.is_error() {
             let cls = self.vm.classes.idx(ximpl.cls_id(self.vm));
             let mut cls = cls.write();
@@ -94,11 +115,80 @@ impl<'x, 'ast> ImplCheck<'x, 'ast> {
 
         self.impl_id = None;
     }
+
+    fn check_type_params(
+        &self,
+        ximpl: &ImplData,
+        i: &'ast ast::Impl,
+        type_params: &'ast [ast::TypeParam],
+    ) -> bool {
+        if type_params.is_empty() {
+            report(self.vm, ximpl.file, i.pos, SemError::TypeParamsExpected);
+            return false;
+        }
+
+        // We only support impl-blocks for generic classes that pass their
+        // type parameters through unchanged: impl[T] Foo[T] for Bar[T].
+        let cls_id = i.class_type.to_basic().and_then(|basic| {
+            let forwards_params = basic.params.len() == type_params.len()
+                && basic
+                    .params
+                    .iter()
+                    .zip(type_params)
+                    .all(|(param, tp)| param.to_basic_without_type_params() == Some(tp.name));
+
+            match self.vm.sym.lock().get_type(basic.name) {
+                Some(TypeSym::SymClass(cls_id)) if forwards_params => Some(cls_id),
+                _ => None,
+            }
+        });
+
+        let cls_id = match cls_id {
+            Some(cls_id) => cls_id,
+            None => {
+                report(self.vm, ximpl.file, i.pos, SemError::Unimplemented);
+                return false;
+            }
+        };
+
+        let cls = self.vm.classes.idx(cls_id);
+        let cls = cls.read();
+
+        if cls.type_params.len() != type_params.len() {
+            let msg = SemError::WrongNumberTypeParams(cls.type_params.len(), type_params.len());
+            report(self.vm, ximpl.file, i.class_type.pos(), msg);
+            return false;
+        }
+
+        let mut names = HashSet::new();
+
+        for (type_param_id, type_param) in type_params.iter().enumerate() {
+            if !names.insert(type_param.name) {
+                let name = self.vm.interner.str(type_param.name).to_string();
+                let msg = SemError::TypeParamNameNotUnique(name);
+                report(self.vm, ximpl.file, type_param.pos, msg);
+                return false;
+            }
+
+            if !type_param.bounds.is_empty() {
+                // Bounds are inherited from the class.
+                report(self.vm, ximpl.file, type_param.pos, SemError::Unimplemented);
+                return false;
+            }
+
+            let sym = TypeSym::SymClassTypeParam(cls_id, type_param_id.into());
+            self.vm.sym.lock().insert_type(type_param.name, sym);
+        }
+
+        true
+    }
 }
 
 impl<'x, 'ast> Visitor<'ast> for ImplCheck<'x, 'ast> {
     fn visit_file(&mut self, f: &'ast ast::File) {
+        let scope = self.vm.enter_file_scope(self.file_id.into());
         visit::walk_file(self, f);
+        self.vm.leave_file_scope(scope);
         self.file_id += 1;
     }
 
@@ -151,6 +241,7 @@ impl<'x, 'ast> Visitor<'ast> for ImplCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: false,
             vtable_index: None,
@@ -248,4 +339,29 @@ mod tests {
     fn impl_class_type_params() {
         ok("trait MyTrait {} class Foo[T] impl MyTrait for Foo[String] {}");
     }
+
+    #[test]
+    fn impl_generic_trait() {
+        ok("trait Foo[T] {} class A impl Foo[Int] for A {}");
+        ok("trait Foo[T] {} class A[T] impl[T] Foo[T] for A[T] {}");
+        ok("trait Foo[T] {} class A[X, Y] impl[X, Y] Foo[(X, Y)] for A[X, Y] {}");
+
+        err(
+            "trait Foo[T] {} class A impl Foo for A {}",
+            pos(1, 30),
+            SemError::WrongNumberTypeParams(1, 0),
+        );
+
+        err(
+            "trait Foo {} class A impl Foo[Int] for A {}",
+            pos(1, 27),
+            SemError::WrongNumberTypeParams(0, 1),
+        );
+
+        err(
+            "trait Foo[T] {} class A[T] impl[T] Foo[T] for A[Int] {}",
+            pos(1, 28),
+            SemError::Unimplemented,
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use crate::error::msg::SemError;
+use crate::sym::SymLevel;
+use crate::vm::{namespace_name, FileId, Visibility, VM};
+use dora_parser::ast::Import;
+
+// builds the scope of every file: the top-level names of its namespace
+// together with all imported names
+pub fn check<'ast>(vm: &mut VM<'ast>) {
+    let ast = vm.ast;
+    let mut scopes = Vec::with_capacity(ast.files.len());
+
+    for (idx, file) in ast.files.iter().enumerate() {
+        let file_id: FileId = (idx as u32).into();
+
+        let mut scope = match vm.file_namespace(file_id) {
+            Some(namespace_id) => vm.namespaces[namespace_id].table.clone(),
+            None => SymLevel::new(),
+        };
+
+        for import in &file.imports {
+            check_import(vm, file_id, import, &mut scope);
+        }
+
+        scopes.push(Arc::new(scope));
+    }
+
+    vm.file_scopes = scopes;
+}
+
+fn check_import(vm: &VM, file: FileId, import: &Import, scope: &mut SymLevel) {
+    let namespace_id = match vm.find_namespace(&import.path) {
+        Some(namespace_id) => namespace_id,
+        None => {
+            let name = namespace_name(&vm.interner, &import.path);
+            let msg = SemError::UnknownNamespace(name);
+            vm.diag.lock().report(file, import.pos, msg);
+            return;
+        }
+    };
+
+    // all names of the own namespace are visible anyway
+    if vm.file_namespace(file) == Some(namespace_id) {
+        return;
+    }
+
+    let namespace = &vm.namespaces[namespace_id];
+
+    for import_name in &import.names {
+        let name = import_name.name;
+        let type_sym = namespace.table.get_type(name).cloned();
+        let term_sym = namespace.table.get_term(name).cloned();
+
+        let msg: Option<fn(String, String) -> SemError> =
+            if type_sym.is_none() && term_sym.is_none() {
+                Some(SemError::UnknownImport)
+            } else {
+                match namespace.visibility(name) {
+                    Visibility::Pub => None,
+                    Visibility::Internal if vm.is_trusted_file(file) => None,
+                    Visibility::Internal => Some(SemError::ImportInternal),
+                    Visibility::Private => Some(SemError::ImportNotPub),
+                }
+            };
+
+        if let Some(msg) = msg {
+            let namespace = namespace.name(&vm.interner);
+            let name = vm.interner.str(name).to_string();
+            vm.diag
+                .lock()
+                .report(file, import_name.pos, msg(namespace, name));
+            continue;
+        }
+
+        let conflict = (type_sym.is_some() && scope.contains_type(name))
+            || (term_sym.is_some() && scope.contains_term(name));
+
+        if conflict {
+            let name = vm.interner.str(name).to_string();
+            let msg = SemError::ImportConflict(name);
+            vm.diag.lock().report(file, import_name.pos, msg);
+            continue;
+        }
+
+        if let Some(sym) = type_sym {
+            scope.insert_type(name, sym);
+        }
+
+        if let Some(sym) = term_sym {
+            scope.insert_term(name, sym);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::msg::SemError;
+    use crate::semck::tests::*;
+
+    #[test]
+    fn import_from_namespace() {
+        ok_files(&[
+            "package geo; @pub class Point(let x: Int) @pub fun origin() -> Point = Point(0);",
+            "import geo::{Point, origin}; fun f() -> Int { let p: Point = origin(); p.x }",
+        ]);
+        ok_files(&[
+            "package geo; @pub class Point",
+            "package app; import geo::Point; fun f() -> Point = Point();",
+            "package app; fun g() { f(); }",
+        ]);
+    }
+
+    #[test]
+    fn namespace_does_not_collide_with_stdlib() {
+        ok_files(&["package app; fun println(a: Int) -> Int = a; fun f() -> Int = println(1);"]);
+        ok_files(&["package app; class String fun f() -> String = String();"]);
+    }
+
+    #[test]
+    fn namespace_not_visible_without_import() {
+        err_files(
+            &["package geo; @pub fun origin() {}", "fun f() { origin(); }"],
+            pos(1, 11),
+            SemError::UnknownIdentifier("origin".into()),
+        );
+        err_files(
+            &["package geo; @pub class Point", "fun f(p: Point) {}"],
+            pos(1, 10),
+            SemError::UnknownType("Point".into()),
+        );
+    }
+
+    #[test]
+    fn import_errors() {
+        err_files(
+            &["package geo; @pub class A", "import gem::A;"],
+            pos(1, 1),
+            SemError::UnknownNamespace("gem".into()),
+        );
+        err_files(
+            &["package geo; @pub class A", "import geo::B;"],
+            pos(1, 13),
+            SemError::UnknownImport("geo".into(), "B".into()),
+        );
+        err_files(
+            &["package geo; class A", "import geo::A;"],
+            pos(1, 13),
+            SemError::ImportNotPub("geo".into(), "A".into()),
+        );
+        err_files(
+            &[
+                "package geo; @pub class A",
+                "package app; import geo::A; class A",
+            ],
+            pos(1, 26),
+            SemError::ImportConflict("A".into()),
+        );
+    }
+}
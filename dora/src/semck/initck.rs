@@ -0,0 +1,542 @@
+use fixedbitset::FixedBitSet;
+
+use crate::error::msg::SemError;
+use crate::vm::{Fct, FctSrc, FieldId, IdentType, VM};
+
+use dora_parser::ast::visit::*;
+use dora_parser::ast::Expr::*;
+use dora_parser::ast::Stmt::*;
+use dora_parser::ast::*;
+use dora_parser::lexer::position::Position;
+
+// Definite assignment: a `var` declared without initializer needs to be
+// assigned on every path before it is read and the constructor of a class
+// needs to assign each of its `let` fields exactly once. With --null-safety
+// the constructor also needs to assign `var` fields of non-nullable class
+// type, they would be nil otherwise.
+pub fn check<'ast>(vm: &VM<'ast>) {
+    for fct in vm.fcts.iter() {
+        let fct = fct.read();
+
+        if !vm.needs_body_check(&fct) {
+            continue;
+        }
+
+        let src = fct.src();
+        let src = src.read();
+
+        let fields = if fct.is_constructor && fct.in_class() {
+            let cls = vm.classes.idx(fct.cls_id());
+            let cls = cls.read();
+
+            cls.fields
+                .iter()
+                .filter(|field| {
+                    !field.reassignable || (vm.args.flag_null_safety && field.ty.is_cls())
+                })
+                .map(|field| field.id)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let slots = src.vars.len() + fields.len();
+
+        let mut initck = InitCheck {
+            vm,
+            fct: &fct,
+            src: &src,
+            fields,
+            state: State::entry(slots, src.vars.len()),
+            loops: Vec::new(),
+            exit: State::unreachable(slots),
+        };
+
+        initck.check();
+    }
+}
+
+// The variables and fields that are assigned at some point of the function.
+// Variables occupy the first slots followed by the fields of the class that
+// need to be assigned when checking a constructor.
+#[derive(Clone)]
+struct State {
+    assigned: FixedBitSet, // assigned on every path
+    maybe: FixedBitSet,    // assigned on at least one path
+    reachable: bool,
+}
+
+impl State {
+    // all variables count as assigned until their declaration is reached,
+    // fields are unassigned when entering the constructor
+    fn entry(slots: usize, vars: usize) -> State {
+        let mut assigned = FixedBitSet::with_capacity(slots);
+        assigned.insert_range(..vars);
+
+        State {
+            assigned: assigned.clone(),
+            maybe: assigned,
+            reachable: true,
+        }
+    }
+
+    fn unreachable(slots: usize) -> State {
+        State {
+            assigned: FixedBitSet::with_capacity(slots),
+            maybe: FixedBitSet::with_capacity(slots),
+            reachable: false,
+        }
+    }
+
+    fn assign(&mut self, slot: usize) {
+        self.assigned.insert(slot);
+        self.maybe.insert(slot);
+    }
+
+    // merges the state of two paths, unreachable paths are ignored
+    fn join(&mut self, other: &State) {
+        if !other.reachable {
+            return;
+        }
+
+        if !self.reachable {
+            *self = other.clone();
+            return;
+        }
+
+        self.assigned.intersect_with(&other.assigned);
+        self.maybe.union_with(&other.maybe);
+    }
+}
+
+struct InitCheck<'a, 'ast: 'a> {
+    vm: &'a VM<'ast>,
+    fct: &'a Fct<'ast>,
+    src: &'a FctSrc,
+    fields: Vec<FieldId>,
+    state: State,
+
+    // joined states of all `break`s for each enclosing loop
+    loops: Vec<State>,
+
+    // joined states of all `return`s
+    exit: State,
+}
+
+impl<'a, 'ast> InitCheck<'a, 'ast> {
+    fn check(&mut self) {
+        let block = self.fct.ast.block();
+
+        for stmt in &block.stmts {
+            self.visit_stmt(stmt);
+        }
+
+        if let Some(ref expr) = block.expr {
+            self.visit_expr(expr);
+        }
+
+        let end = self.state.clone();
+        self.exit.join(&end);
+
+        if self.exit.reachable {
+            self.check_fields();
+        }
+    }
+
+    fn check_fields(&self) {
+        if self.fields.is_empty() {
+            return;
+        }
+
+        let cls = self.vm.classes.idx(self.fct.cls_id());
+        let cls = cls.read();
+
+        for (idx, &field_id) in self.fields.iter().enumerate() {
+            if self.exit.assigned.contains(self.src.vars.len() + idx) {
+                continue;
+            }
+
+            let field = &cls.fields[field_id];
+            let cls_name = self.vm.interner.str(cls.name).to_string();
+            let field_name = self.vm.interner.str(field.name).to_string();
+            let msg = SemError::FieldNotInitialized(cls_name, field_name);
+            self.vm.diag.lock().report(cls.file, field.pos, msg);
+        }
+    }
+
+    fn field_slot(&self, lhs: &'ast Expr) -> Option<usize> {
+        let dot = lhs.to_dot()?;
+
+        if !dot.lhs.is_this() {
+            return None;
+        }
+
+        match self.src.map_idents.get(lhs.id()) {
+            Some(&IdentType::Field(owner_ty, field_id))
+                if owner_ty.cls_id(self.vm) == Some(self.fct.cls_id()) =>
+            {
+                let idx = self.fields.iter().position(|&id| id == field_id)?;
+                Some(self.src.vars.len() + idx)
+            }
+
+            _ => None,
+        }
+    }
+
+    fn is_let_field(&self, slot: usize) -> bool {
+        let field_id = self.fields[slot - self.src.vars.len()];
+        let cls = self.vm.classes.idx(self.fct.cls_id());
+        let cls = cls.read();
+
+        !cls.fields[field_id].reassignable
+    }
+
+    fn check_assign(&mut self, e: &'ast ExprBinType) {
+        if let Some(ident) = e.lhs.to_ident() {
+            self.visit_expr(&e.rhs);
+
+            if let Some(&IdentType::Var(var_id)) = self.src.map_idents.get(ident.id) {
+                self.state.assign(var_id.0);
+            }
+
+            return;
+        }
+
+        let slot = if self.fields.is_empty() {
+            None
+        } else {
+            self.field_slot(&e.lhs)
+        };
+
+        match slot {
+            Some(slot) => {
+                self.visit_expr(&e.rhs);
+
+                // a field assigned in a loop would be assigned in every iteration
+                if self.state.reachable
+                    && self.is_let_field(slot)
+                    && (self.state.maybe.contains(slot) || !self.loops.is_empty())
+                {
+                    self.vm
+                        .diag
+                        .lock()
+                        .report(self.fct.file, e.pos, SemError::LetReassigned);
+                }
+
+                self.state.assign(slot);
+            }
+
+            None => {
+                self.visit_expr(&e.lhs);
+                self.visit_expr(&e.rhs);
+            }
+        }
+    }
+
+    fn check_ident(&mut self, e: &'ast ExprIdentType) {
+        if let Some(&IdentType::Var(var_id)) = self.src.map_idents.get(e.id) {
+            if self.state.reachable && !self.state.assigned.contains(var_id.0) {
+                self.report_uninitialized(e.pos, var_id.0);
+            }
+        }
+    }
+
+    // captured variables are copied into the closure when the lambda is created
+    fn check_lambda(&mut self, e: &'ast ExprLambdaType) {
+        if !self.state.reachable {
+            return;
+        }
+
+        let lambda_id = match self.src.map_lambdas.get(e.id) {
+            Some(&lambda_id) => lambda_id,
+            None => return,
+        };
+
+        let lambda = self.vm.fcts.idx(lambda_id);
+        let lambda = lambda.read();
+        let lambda_src = lambda.src();
+        let lambda_src = lambda_src.read();
+
+        for capture in &lambda_src.captures {
+            if !self.state.assigned.contains(capture.outer.0) {
+                self.report_uninitialized(e.pos, capture.outer.0);
+            }
+        }
+    }
+
+    fn report_uninitialized(&self, pos: Position, var: usize) {
+        let name = self.vm.interner.str(self.src.vars[var].name).to_string();
+        let msg = SemError::UninitializedVar(name);
+        self.vm.diag.lock().report(self.fct.file, pos, msg);
+    }
+
+    fn check_if(&mut self, e: &'ast ExprIfType) {
+        self.visit_expr(&e.cond);
+        let after_cond = self.state.clone();
+
+        self.visit_expr(&e.then_block);
+        let after_then = self.state.clone();
+
+        self.state = after_cond;
+
+        if let Some(ref else_block) = e.else_block {
+            self.visit_expr(else_block);
+        }
+
+        self.state.join(&after_then);
+    }
+
+    fn check_match(&mut self, e: &'ast ExprMatchType) {
+        self.visit_expr(&e.expr);
+
+        if e.cases.is_empty() {
+            return;
+        }
+
+        let before = self.state.clone();
+        let mut after = State::unreachable(before.assigned.len());
+
+        for case in &e.cases {
+            self.state = before.clone();
+
+            if let MatchPattern::Literal(ref lit) = case.pattern {
+                self.visit_expr(lit);
+            }
+
+            self.visit_expr(&case.value);
+            after.join(&self.state);
+        }
+
+        self.state = after;
+    }
+
+    // the right operand of `&&` and `||` is not always evaluated
+    fn check_short_circuit(&mut self, e: &'ast ExprBinType) {
+        self.visit_expr(&e.lhs);
+        let after_lhs = self.state.clone();
+
+        self.visit_expr(&e.rhs);
+        self.state.join(&after_lhs);
+    }
+
+    fn check_loop(&mut self, cond: &'ast Expr, block: &'ast Stmt) {
+        self.visit_expr(cond);
+
+        // `while true` can only be left through `break`
+        let infinite = match *cond {
+            ExprLitBool(ref lit) => lit.value,
+            _ => false,
+        };
+
+        let exit = if infinite {
+            State::unreachable(self.state.assigned.len())
+        } else {
+            self.state.clone()
+        };
+
+        self.check_loop_body(exit, block);
+    }
+
+    // variables assigned in the body are not assigned after the loop, since the
+    // body might not be executed at all
+    fn check_loop_body(&mut self, exit: State, block: &'ast Stmt) {
+        let slots = self.state.assigned.len();
+        self.loops.push(State::unreachable(slots));
+
+        self.visit_stmt(block);
+
+        let breaks = self.loops.pop().unwrap();
+        self.state = exit;
+        self.state.join(&breaks);
+    }
+
+    fn check_try(&mut self, s: &'ast StmtTryType) {
+        let before = self.state.clone();
+
+        self.visit_stmt(&s.try_block);
+        let mut after = self.state.clone();
+
+        // an exception can be thrown before any assignment in the try block
+        let mut on_throw = before.clone();
+        on_throw.maybe.union_with(&after.maybe);
+
+        for catch in &s.catch_blocks {
+            self.state = on_throw.clone();
+            self.visit_stmt(&catch.block);
+            after.join(&self.state);
+        }
+
+        if let Some(ref finally_block) = s.finally_block {
+            let mut start = on_throw;
+            start.maybe.union_with(&after.maybe);
+            self.state = start;
+
+            self.visit_stmt(&finally_block.block);
+
+            if self.state.reachable {
+                after.assigned.union_with(&self.state.assigned);
+                after.maybe.union_with(&self.state.maybe);
+            } else {
+                after = self.state.clone();
+            }
+        }
+
+        self.state = after;
+    }
+
+    fn unreachable(&mut self) {
+        self.state = State::unreachable(self.state.assigned.len());
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for InitCheck<'a, 'ast> {
+    fn visit_stmt(&mut self, s: &'ast Stmt) {
+        match *s {
+            StmtVar(ref stmt) => {
+                if let Some(ref expr) = stmt.expr {
+                    self.visit_expr(expr);
+                }
+
+                let var_id = *self.src.map_vars.get(stmt.id).unwrap();
+
+                if stmt.expr.is_some() {
+                    self.state.assign(var_id.0);
+                } else {
+                    self.state.assigned.set(var_id.0, false);
+                }
+            }
+
+            StmtWhile(ref stmt) => self.check_loop(&stmt.cond, &stmt.block),
+
+            StmtFor(ref stmt) => {
+                self.visit_expr(&stmt.expr);
+                let exit = self.state.clone();
+                self.check_loop_body(exit, &stmt.block);
+            }
+
+            StmtBreak(_) => {
+                let state = self.state.clone();
+
+                if let Some(breaks) = self.loops.last_mut() {
+                    breaks.join(&state);
+                }
+
+                self.unreachable();
+            }
+
+            StmtContinue(_) => self.unreachable(),
+
+            StmtReturn(ref stmt) => {
+                if let Some(ref expr) = stmt.expr {
+                    self.visit_expr(expr);
+                }
+
+                let state = self.state.clone();
+                self.exit.join(&state);
+                self.unreachable();
+            }
+
+            StmtThrow(ref stmt) => {
+                self.visit_expr(&stmt.expr);
+                self.unreachable();
+            }
+
+            StmtTry(ref stmt) => self.check_try(stmt),
+
+            StmtExpr(_) => visit::walk_stmt(self, s),
+        }
+    }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        match *e {
+            ExprIdent(ref ident) => self.check_ident(ident),
+            ExprBin(ref bin) if bin.op == BinOp::Assign => self.check_assign(bin),
+            ExprBin(ref bin) if bin.op == BinOp::And || bin.op == BinOp::Or => {
+                self.check_short_circuit(bin)
+            }
+            ExprIf(ref expr) => self.check_if(expr),
+            ExprMatch(ref expr) => self.check_match(expr),
+            ExprLambda(ref expr) => self.check_lambda(expr),
+
+            _ => visit::walk_expr(self, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::msg::SemError;
+    use crate::semck::tests::*;
+
+    #[test]
+    fn initck_var() {
+        ok("fun f() -> Int { var x: Int; x = 1; x }");
+        ok("fun f(a: Bool) -> Int { var x: Int; if a { x = 1; } else { x = 2; } x }");
+        ok("fun f() -> Int { var x: Int; while true { x = 1; break; } x }");
+        ok("fun f(a: Bool) -> Int { var x: Int; if a { x = 1; } else { return 0; } x }");
+        err(
+            "fun f() -> Int { var x: Int; x }",
+            pos(1, 30),
+            SemError::UninitializedVar("x".into()),
+        );
+        err(
+            "fun f(a: Bool) -> Int { var x: Int; if a { x = 1; } x }",
+            pos(1, 53),
+            SemError::UninitializedVar("x".into()),
+        );
+        err(
+            "fun f(a: Bool) -> Int { var x: Int; while a { x = 1; } x }",
+            pos(1, 56),
+            SemError::UninitializedVar("x".into()),
+        );
+        err(
+            "fun f(a: Bool) -> Bool { var x: Bool; a && { x = true; x } || x }",
+            pos(1, 63),
+            SemError::UninitializedVar("x".into()),
+        );
+    }
+
+    #[test]
+    fn initck_var_captured() {
+        err(
+            "fun f() { var x: Int; let g = || -> Int { x }; }",
+            pos(1, 31),
+            SemError::UninitializedVar("x".into()),
+        );
+    }
+
+    #[test]
+    fn initck_let_field() {
+        ok("class Foo(a: Bool) { let x: Int; if a { self.x = 1; } else { self.x = 2; } }");
+        ok("class Foo { let x: Int; self.x = 1; }");
+        err(
+            "class Foo(a: Bool) { let x: Int; if a { self.x = 1; } }",
+            pos(1, 22),
+            SemError::FieldNotInitialized("Foo".into(), "x".into()),
+        );
+        err(
+            "class Foo { let x: Int = 1; self.x = 2; }",
+            pos(1, 36),
+            SemError::LetReassigned,
+        );
+        err(
+            "class Foo { let x: Int; self.x = 1; fun f() { self.x = 2; } }",
+            pos(1, 54),
+            SemError::LetReassigned,
+        );
+    }
+
+    #[test]
+    fn initck_var_field_null_safety() {
+        ok("class A class B { var a: A; }");
+        ok_null_safety("class A class B { var a: A?; }");
+        ok_null_safety(
+            "class A class B(c: Bool) { var a: A; self.a = A(); if c { self.a = A(); } }",
+        );
+        err_null_safety(
+            "class A class B { var a: A; }",
+            pos(1, 19),
+            SemError::FieldNotInitialized("B".into(), "a".into()),
+        );
+    }
+}
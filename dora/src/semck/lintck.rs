@@ -0,0 +1,519 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error::lint::{Lint, LintLevel};
+use crate::error::msg::{SemError, Severity};
+use crate::semck::returnck::returns_value;
+use crate::vm::{Fct, FctId, FctParent, FctSrc, IdentType, VarId, VM};
+
+use dora_parser::ast::visit::*;
+use dora_parser::ast::Expr::*;
+use dora_parser::ast::Stmt::*;
+use dora_parser::ast::*;
+use dora_parser::lexer::position::{Position, Span};
+
+pub fn check<'ast>(vm: &VM<'ast>) {
+    let captured_vars = find_captured_vars(vm);
+    let used_fcts = find_used_fcts(vm);
+
+    for fct in vm.fcts.iter() {
+        let fct = fct.read();
+
+        if !fct.is_src() || vm.is_trusted_file(fct.file) {
+            continue;
+        }
+
+        if is_unused_fct(vm, &fct, &used_fcts) {
+            let name = fct.full_name(vm);
+            report(
+                vm,
+                &fct,
+                Lint::UnusedFunction,
+                fct.pos,
+                None,
+                SemError::UnusedFunction(name),
+            );
+        }
+
+        let src = fct.src();
+        let src = src.read();
+
+        let mut lintck = LintCheck {
+            vm,
+            fct: &fct,
+            src: &src,
+            captured_vars: &captured_vars,
+            decls: HashMap::new(),
+        };
+
+        lintck.check();
+    }
+}
+
+// variables of a function that are read by one of its lambdas
+fn find_captured_vars<'ast>(vm: &VM<'ast>) -> HashSet<(FctId, VarId)> {
+    let mut captured = HashSet::new();
+
+    for fct in vm.fcts.iter() {
+        let fct = fct.read();
+
+        if let FctParent::Function(outer_id) = fct.parent {
+            let src = fct.src();
+            let src = src.read();
+
+            for capture in &src.captures {
+                captured.insert((outer_id, capture.outer));
+            }
+        }
+    }
+
+    captured
+}
+
+fn find_used_fcts<'ast>(vm: &VM<'ast>) -> HashSet<FctId> {
+    let mut used = HashSet::new();
+    let reflective_call = vm.sym.lock().get_fct(vm.interner.intern("call"));
+
+    for fct in vm.fcts.iter() {
+        let fct = fct.read();
+
+        if !fct.is_src() {
+            continue;
+        }
+
+        let src = fct.src();
+        let src = src.read();
+
+        for (_, call_type) in src.map_calls.iter() {
+            if let Some(fct_id) = call_type.fct_id() {
+                used.insert(fct_id);
+            }
+        }
+
+        for (_, for_type) in src.map_fors.iter() {
+            used.insert(for_type.make_iterator);
+            used.insert(for_type.has_next);
+            used.insert(for_type.next);
+        }
+
+        for (_, ident_type) in src.map_idents.iter() {
+            match *ident_type {
+                IdentType::Fct(fct_id) | IdentType::FctType(fct_id, _) => {
+                    used.insert(fct_id);
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(reflective_call) = reflective_call {
+            let mut finder = ReflectiveCallFinder {
+                vm,
+                src: &src,
+                reflective_call,
+                used: &mut used,
+            };
+
+            finder.visit_fct(fct.ast);
+        }
+    }
+
+    // initializers of globals are invoked by the VM
+    for glob in vm.globals.iter() {
+        if let Some(fct_id) = glob.read().initializer {
+            used.insert(fct_id);
+        }
+    }
+
+    used
+}
+
+// `call("name")` invokes the function `name` by its name at runtime,
+// a string literal argument counts as use of that function
+struct ReflectiveCallFinder<'a, 'ast: 'a> {
+    vm: &'a VM<'ast>,
+    src: &'a FctSrc,
+    reflective_call: FctId,
+    used: &'a mut HashSet<FctId>,
+}
+
+impl<'a, 'ast> Visitor<'ast> for ReflectiveCallFinder<'a, 'ast> {
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        if let ExprCall(ref call) = *e {
+            let callee_id = self
+                .src
+                .map_calls
+                .get(call.id)
+                .and_then(|call_type| call_type.fct_id());
+
+            if callee_id == Some(self.reflective_call) {
+                if let Some(ExprLitStr(ref lit)) = call.args.first().map(|arg| &**arg) {
+                    let name = self.vm.interner.intern(&lit.value);
+
+                    if let Some(fct_id) = self.vm.sym.lock().get_fct(name) {
+                        self.used.insert(fct_id);
+                    }
+                }
+            }
+        }
+
+        visit::walk_expr(self, e);
+    }
+}
+
+// functions are visible to the whole program, methods only when marked `pub`
+fn is_unused_fct(vm: &VM, fct: &Fct, used_fcts: &HashSet<FctId>) -> bool {
+    let candidate = match fct.parent {
+        FctParent::None => &*vm.interner.str(fct.name) != "main",
+        FctParent::Class(_) | FctParent::Module(_) | FctParent::Extension(_) => !fct.is_pub,
+        FctParent::Trait(_) | FctParent::Impl(_) | FctParent::Function(_) => false,
+    };
+
+    candidate
+        && !used_fcts.contains(&fct.id)
+        && !fct.is_test
+        && !fct.is_constructor
+        && !fct.is_abstract
+        && !fct.has_open
+        && !fct.has_override
+        && !fct.internal
+        && fct.impl_for.is_none()
+        && !vm.interner.str(fct.name).starts_with('_')
+}
+
+fn report(vm: &VM, fct: &Fct, lint: Lint, pos: Position, span: Option<Span>, msg: SemError) {
+    let severity = match vm.args.lint_level(lint) {
+        LintLevel::Allow => return,
+        LintLevel::Warn => Severity::Warning,
+        LintLevel::Deny => Severity::Error,
+    };
+
+    vm.diag
+        .lock()
+        .report_lint(fct.file, pos, span, severity, msg);
+}
+
+#[derive(Copy, Clone)]
+struct Decl {
+    pos: Position,
+    is_param: bool,
+}
+
+struct LintCheck<'a, 'ast: 'a> {
+    vm: &'a VM<'ast>,
+    fct: &'a Fct<'ast>,
+    src: &'a FctSrc,
+    captured_vars: &'a HashSet<(FctId, VarId)>,
+    decls: HashMap<NodeId, Decl>,
+}
+
+impl<'a, 'ast> LintCheck<'a, 'ast> {
+    fn check(&mut self) {
+        let ast = self.fct.ast;
+
+        for param in &ast.params {
+            self.add_decl(param.id, param.pos, true);
+        }
+
+        self.check_block(ast.block());
+        self.check_unused_vars();
+    }
+
+    fn add_decl(&mut self, id: NodeId, pos: Position, is_param: bool) {
+        self.decls.insert(id, Decl { pos, is_param });
+    }
+
+    fn check_unused_vars(&mut self) {
+        let used_vars: HashSet<VarId> = self
+            .src
+            .map_idents
+            .iter()
+            .filter_map(|(_, ident_type)| match *ident_type {
+                IdentType::Var(var_id) => Some(var_id),
+                _ => None,
+            })
+            .collect();
+
+        // hidden and captured variables have no declaration in the function
+        for var in &self.src.vars {
+            let decl = match self.decls.get(&var.node_id) {
+                Some(&decl) => decl,
+                None => continue,
+            };
+
+            if used_vars.contains(&var.id) || self.captured_vars.contains(&(self.fct.id, var.id)) {
+                continue;
+            }
+
+            let name = self.vm.interner.str(var.name).to_string();
+
+            if name.starts_with('_') {
+                continue;
+            }
+
+            if !decl.is_param {
+                self.report(
+                    Lint::UnusedVariable,
+                    decl.pos,
+                    None,
+                    SemError::UnusedVariable(name),
+                );
+            } else if self.params_can_be_unused() {
+                self.report(
+                    Lint::UnusedVariable,
+                    decl.pos,
+                    None,
+                    SemError::UnusedParam(name),
+                );
+            }
+        }
+    }
+
+    // the signature of these functions is dictated by a lambda type,
+    // a super class or a trait
+    fn params_can_be_unused(&self) -> bool {
+        let fct = self.fct;
+
+        match fct.parent {
+            FctParent::Trait(_) | FctParent::Impl(_) | FctParent::Function(_) => false,
+            _ => {
+                !fct.has_open
+                    && !fct.has_override
+                    && !fct.is_abstract
+                    && !fct.is_constructor
+                    && fct.impl_for.is_none()
+            }
+        }
+    }
+
+    fn check_block(&mut self, block: &'ast ExprBlockType) {
+        if let Some(idx) = block.stmts.iter().position(|stmt| diverges(stmt)) {
+            let next = match block.stmts.get(idx + 1) {
+                Some(stmt) => Some((stmt.pos(), stmt.span())),
+                None => block.expr.as_ref().map(|expr| (expr.pos(), expr.span())),
+            };
+
+            if let Some((pos, span)) = next {
+                self.report(
+                    Lint::UnreachableCode,
+                    pos,
+                    Some(span),
+                    SemError::UnreachableCode,
+                );
+            }
+        }
+
+        for stmt in &block.stmts {
+            self.visit_stmt(stmt);
+        }
+
+        if let Some(ref expr) = block.expr {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn check_stmt_expr(&mut self, stmt: &'ast StmtExprType) {
+        let ty = match self.src.map_tys.get(stmt.expr.id()) {
+            Some(ty) => ty,
+            None => return,
+        };
+
+        if ty.is_unit() || ty.is_error() {
+            return;
+        }
+
+        let ty = ty.name(self.vm);
+        self.report(
+            Lint::UnusedResult,
+            stmt.pos,
+            Some(stmt.span),
+            SemError::UnusedResult(ty),
+        );
+    }
+
+    fn report(&self, lint: Lint, pos: Position, span: Option<Span>, msg: SemError) {
+        report(self.vm, self.fct, lint, pos, span, msg);
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for LintCheck<'a, 'ast> {
+    fn visit_stmt(&mut self, s: &'ast Stmt) {
+        match *s {
+            StmtVar(ref stmt) => self.add_decl(stmt.id, stmt.pos, false),
+            StmtFor(ref stmt) => self.add_decl(stmt.id, stmt.pos, false),
+            StmtExpr(ref stmt) => self.check_stmt_expr(stmt),
+
+            StmtTry(ref stmt) => {
+                for catch in &stmt.catch_blocks {
+                    self.add_decl(catch.id, catch.pos, false);
+                }
+            }
+
+            _ => {}
+        }
+
+        visit::walk_stmt(self, s);
+    }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        match *e {
+            ExprBlock(ref block) => {
+                self.check_block(block);
+                return;
+            }
+
+            ExprMatch(ref expr) => {
+                for case in &expr.cases {
+                    if let MatchPattern::Variant(ref pattern) = case.pattern {
+                        for param in pattern.params.iter().flatten() {
+                            self.add_decl(param.id, param.pos, false);
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        visit::walk_expr(self, e);
+    }
+}
+
+// statements after these are never executed
+fn diverges(stmt: &Stmt) -> bool {
+    match *stmt {
+        StmtBreak(_) | StmtContinue(_) => true,
+        _ => returns_value(stmt).is_ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::driver::cmd::{Args, DenyList};
+    use crate::error::lint::LintSet;
+    use crate::error::msg::SemError;
+    use crate::semck::tests::*;
+    use crate::test;
+    use dora_parser::lexer::position::Position;
+
+    fn warnings_with_args(code: &'static str, args: Args, expected: &[(Position, SemError)]) {
+        test::parse_with_args(code, args, |vm| {
+            let diag = vm.diag.lock();
+            let warnings = diag.warnings();
+
+            println!("warnings = {:?}", warnings);
+            assert!(!diag.has_errors());
+            assert_eq!(expected.len(), warnings.len());
+
+            for (warning, &(pos, ref msg)) in warnings.iter().zip(expected) {
+                assert_eq!(pos, warning.pos);
+                assert_eq!(*msg, warning.msg);
+            }
+        });
+    }
+
+    fn warnings(code: &'static str, expected: &[(Position, SemError)]) {
+        warnings_with_args(code, Default::default(), expected);
+    }
+
+    #[test]
+    fn lint_unused_variable() {
+        warnings(
+            "fun main() { let x = 1; let y = 2; y; }",
+            &[(pos(1, 14), SemError::UnusedVariable("x".into()))],
+        );
+        warnings("fun main() { var x = 1; x = 2; }", &[]);
+        warnings("fun main() { let _x = 1; }", &[]);
+        warnings(
+            "fun main() { let x = 1; let f = || -> Int { x }; f(); }",
+            &[],
+        );
+        warnings(
+            "fun main() { for i in range(0, 2) {} }",
+            &[(pos(1, 14), SemError::UnusedVariable("i".into()))],
+        );
+    }
+
+    #[test]
+    fn lint_unused_param() {
+        warnings(
+            "fun main() { f(1); } fun f(a: Int) {}",
+            &[(pos(1, 28), SemError::UnusedParam("a".into()))],
+        );
+        warnings("fun main() { f(1); } fun f(_a: Int) {}", &[]);
+        warnings(
+            "fun main() { B().f(1); }
+            @open class A { @open fun f(a: Int) {} }
+            class B: A { @override fun f(a: Int) {} }",
+            &[],
+        );
+        warnings("fun main() { let f = |a: Int| {}; f(1); }", &[]);
+    }
+
+    #[test]
+    fn lint_unused_function() {
+        warnings(
+            "fun main() {} fun f() {}",
+            &[(pos(1, 15), SemError::UnusedFunction("f()".into()))],
+        );
+        warnings("fun main() { f(); } fun f() {}", &[]);
+        warnings("fun main() {} @test fun f(x: Testing) { x; }", &[]);
+        warnings("fun main() {} fun _f() {}", &[]);
+        warnings(
+            "fun main() { A(); } class A { fun f() {} @pub fun g() {} }",
+            &[(pos(1, 31), SemError::UnusedFunction("A.f()".into()))],
+        );
+        warnings("fun main() { A().f(); } class A { fun f() {} }", &[]);
+        warnings("fun main() { call(\"f\"); } fun f() {}", &[]);
+    }
+
+    #[test]
+    fn lint_unreachable_code() {
+        warnings(
+            "fun main() { return; 1; }",
+            &[(pos(1, 22), SemError::UnreachableCode)],
+        );
+        warnings(
+            "fun main() { while true { break; let x = 1; x; } }",
+            &[(pos(1, 34), SemError::UnreachableCode)],
+        );
+        warnings(
+            "fun f(a: Bool) -> Int { if a { return 1; } else { return 2; } 3 }
+            fun main() { f(true); }",
+            &[(pos(1, 63), SemError::UnreachableCode)],
+        );
+        warnings("fun main() { if true { return; } 1; }", &[]);
+    }
+
+    #[test]
+    fn lint_unused_result() {
+        warnings("fun main() { 1 + 2; }", &[]);
+
+        let mut args: Args = Default::default();
+        args.flag_warn = Some(LintSet::parse("unused-result").unwrap());
+        warnings_with_args(
+            "fun main() { 1 + 2; f(); g(); } fun f() -> Int { 1 } fun g() {}",
+            args,
+            &[
+                (pos(1, 16), SemError::UnusedResult("Int".into())),
+                (pos(1, 22), SemError::UnusedResult("Int".into())),
+            ],
+        );
+    }
+
+    #[test]
+    fn lint_allow_and_deny() {
+        let mut args: Args = Default::default();
+        args.flag_allow = Some(LintSet::parse("unused-variable").unwrap());
+        warnings_with_args("fun main() { let x = 1; }", args, &[]);
+
+        let mut args: Args = Default::default();
+        args.flag_deny = Some(DenyList::parse("unused-variable").unwrap());
+        test::parse_with_args("fun main() { let x = 1; }", args, |vm| {
+            let diag = vm.diag.lock();
+            assert!(diag.warnings().is_empty());
+            assert_eq!(1, diag.errors().len());
+            assert_eq!(pos(1, 14), diag.errors()[0].pos);
+            assert_eq!(SemError::UnusedVariable("x".into()), diag.errors()[0].msg);
+        });
+    }
+}
@@ -2,6 +2,7 @@ use parking_lot::RwLock;
 use std::collections::HashMap;
 
 use crate::error::msg::SemError;
+use crate::error::suggest::did_you_mean;
 use crate::semck;
 use crate::sym::TypeSym;
 use crate::ty::{BuiltinType, TypeList};
@@ -56,6 +57,7 @@ impl<'x, 'ast> ModuleCheck<'x, 'ast> {
         let field = Field {
             id: module.fields.len().into(),
             name,
+            pos,
             ty,
             offset: 0,
             reassignable,
@@ -111,11 +113,22 @@ impl<'x, 'ast> ModuleCheck<'x, 'ast> {
             }
 
             _ => {
+                let candidates = {
+                    let sym = self.vm.sym.lock();
+                    sym.type_names()
+                        .into_iter()
+                        .filter(|&name| sym.get_class(name).is_some())
+                        .collect::<Vec<_>>()
+                };
+
+                let notes = did_you_mean(self.vm, parent_class.name, candidates);
                 let msg = SemError::UnknownClass(name);
-                self.vm
-                    .diag
-                    .lock()
-                    .report(self.file_id.into(), parent_class.pos, msg);
+                self.vm.diag.lock().report_with_notes(
+                    self.file_id.into(),
+                    parent_class.pos,
+                    msg,
+                    notes,
+                );
             }
         }
     }
@@ -135,7 +148,9 @@ impl<'x, 'ast> ModuleCheck<'x, 'ast> {
 
 impl<'x, 'ast> Visitor<'ast> for ModuleCheck<'x, 'ast> {
     fn visit_file(&mut self, f: &'ast ast::File) {
+        let scope = self.vm.enter_file_scope(self.file_id.into());
         visit::walk_file(self, f);
+        self.vm.leave_file_scope(scope);
         self.file_id += 1;
     }
 
@@ -200,6 +215,7 @@ impl<'x, 'ast> Visitor<'ast> for ModuleCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: f.is_constructor,
             vtable_index: None,
@@ -219,6 +235,10 @@ impl<'x, 'ast> Visitor<'ast> for ModuleCheck<'x, 'ast> {
         let module = self.vm.modules.idx(self.module_id.unwrap());
         let mut module = module.write();
         module.constructor = Some(fctid);
+
+        // the constructor is invoked like the initializer of a global
+        let glob = self.vm.globals.idx(module.global_id);
+        glob.write().initializer = Some(fctid);
     }
 
     fn visit_method(&mut self, f: &'ast ast::Function) {
@@ -255,6 +275,7 @@ impl<'x, 'ast> Visitor<'ast> for ModuleCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: false,
             vtable_index: None,
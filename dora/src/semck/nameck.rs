@@ -1,4 +1,8 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
 use crate::error::msg::SemError;
+use crate::error::suggest::did_you_mean;
 use crate::vm::*;
 
 use dora_parser::ast::visit::*;
@@ -19,7 +23,8 @@ pub fn check<'ast>(vm: &VM<'ast>) {
     for fct in vm.fcts.iter() {
         let fct = fct.read();
 
-        if !fct.is_src() {
+        // lambdas are checked together with their enclosing function
+        if !fct.is_src() || fct.is_lambda() {
             continue;
         }
 
@@ -32,9 +37,11 @@ pub fn check<'ast>(vm: &VM<'ast>) {
             fct: &fct,
             src: &mut src,
             ast,
+            level_start: vm.sym.lock().levels(),
+            captures: Vec::new(),
         };
 
-        nameck.check();
+        vm.with_file_scope(fct.file, || nameck.check());
     }
 }
 
@@ -43,6 +50,13 @@ struct NameCheck<'a, 'ast: 'a> {
     fct: &'a Fct<'ast>,
     src: &'a mut FctSrc,
     ast: &'ast Function,
+
+    // symbols in levels below level_start belong to enclosing functions
+    level_start: usize,
+
+    // variables captured by this lambda, the corresponding variable
+    // in the enclosing function is resolved after this lambda was checked
+    captures: Vec<(Name, VarId)>,
 }
 
 impl<'a, 'ast> NameCheck<'a, 'ast> {
@@ -116,11 +130,20 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
                 extension.class_ty
             }
 
+            FctParent::Module(module_id) => self.vm.modules.idx(module_id).read().ty,
+
+            // type of closure object is only known after fctdefck
+            FctParent::Function(_) => BuiltinType::Unit,
+
             _ => unreachable!(),
         };
 
         let ast_id = self.fct.ast.id;
-        let name = self.vm.interner.intern("self");
+        let name = if self.fct.is_lambda() {
+            self.vm.interner.intern("<closure>")
+        } else {
+            self.vm.interner.intern("self")
+        };
 
         let var = Var {
             id: VarId(0),
@@ -182,28 +205,57 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
         self.vm.sym.lock().pop_level();
     }
 
+    fn check_stmt_try(&mut self, s: &'ast StmtTryType) {
+        self.visit_stmt(&s.try_block);
+
+        for catch in &s.catch_blocks {
+            self.vm.sym.lock().push_level();
+
+            let var_ctxt = Var {
+                id: VarId(0),
+                name: catch.name,
+                reassignable: false,
+                ty: BuiltinType::Unit,
+                node_id: catch.id,
+            };
+
+            let var_id = self.add_var(var_ctxt);
+            self.src.map_vars.insert(catch.id, var_id);
+
+            self.visit_stmt(&catch.block);
+            self.vm.sym.lock().pop_level();
+        }
+
+        if let Some(ref finally_block) = s.finally_block {
+            self.visit_stmt(&finally_block.block);
+        }
+    }
+
     fn check_expr_ident(&mut self, ident: &'ast ExprIdentType) {
         let term_sym = self.vm.sym.lock().get_term(ident.name);
         let type_sym = self.vm.sym.lock().get_type(ident.name);
 
+        // terms win over types, the name of a namespace declaration or import
+        // can coincide with a type of the root namespace
         match (term_sym, type_sym) {
-            (Some(SymVar(id)), None) => {
+            (Some(SymVar(id)), _) => {
+                let id = self.capture_if_outer(ident.name, id);
                 self.src.map_idents.insert(ident.id, IdentType::Var(id));
             }
 
-            (Some(SymGlobal(id)), None) => {
+            (Some(SymGlobal(id)), _) => {
                 self.src.map_idents.insert(ident.id, IdentType::Global(id));
             }
 
-            (Some(SymConst(id)), None) => {
+            (Some(SymConst(id)), _) => {
                 self.src.map_idents.insert(ident.id, IdentType::Const(id));
             }
 
-            (Some(SymFct(id)), None) => {
+            (Some(SymFct(id)), _) => {
                 self.src.map_idents.insert(ident.id, IdentType::Fct(id));
             }
 
-            (Some(SymModule(id)), None) => {
+            (Some(SymModule(id)), _) => {
                 self.src.map_idents.insert(ident.id, IdentType::Module(id));
             }
 
@@ -242,12 +294,20 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
             }
 
             (None, None) => {
+                let candidates = {
+                    let sym = self.vm.sym.lock();
+                    let mut names = sym.term_names();
+                    names.extend(sym.type_names());
+                    names
+                };
+
+                let notes = did_you_mean(self.vm, ident.name, candidates);
                 let name = self.vm.interner.str(ident.name).to_string();
-                report(
-                    self.vm,
+                self.vm.diag.lock().report_with_notes(
                     self.fct.file,
                     ident.pos,
                     SemError::UnknownIdentifier(name),
+                    notes,
                 );
             }
 
@@ -255,6 +315,143 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
         }
     }
 
+    fn check_expr_self(&mut self, expr: &'ast ExprSelfType) {
+        // methods use their hidden self parameter directly
+        if !self.fct.is_lambda() {
+            return;
+        }
+
+        let name = self.vm.interner.intern("self");
+        let var_id = self.capture_by_name(name);
+        self.src.map_idents.insert(expr.id, IdentType::Var(var_id));
+    }
+
+    fn check_expr_lambda(&mut self, expr: &'ast ExprLambdaType) {
+        let lambda = Fct {
+            id: FctId(0),
+            file: self.fct.file,
+            pos: expr.pos,
+            ast: &expr.fct,
+            name: expr.fct.name,
+            param_types: Vec::new(),
+            return_type: BuiltinType::Unit,
+            parent: FctParent::Function(self.fct.id),
+            has_override: false,
+            has_open: false,
+            has_final: false,
+            has_optimize_immediately: false,
+            is_pub: false,
+            is_static: false,
+            is_abstract: false,
+            is_test: false,
+            use_cannon: self.fct.use_cannon,
+            internal: false,
+            internal_resolved: false,
+            capability: None,
+            overrides: None,
+            is_constructor: false,
+            vtable_index: None,
+            initialized: false,
+            impl_for: None,
+            variadic_arguments: false,
+
+            type_params: Vec::new(),
+            kind: FctKind::Source(RwLock::new(FctSrc::new())),
+
+            specializations: RwLock::new(HashMap::new()),
+        };
+
+        let lambda_id = self.vm.add_fct(lambda);
+        self.src.map_lambdas.insert(expr.id, lambda_id);
+
+        let lambda = self.vm.fcts.idx(lambda_id);
+        let lambda = lambda.read();
+        let lambda_src = lambda.src();
+        let mut lambda_src = lambda_src.write();
+
+        let captures = {
+            let mut nameck = NameCheck {
+                vm: self.vm,
+                fct: &lambda,
+                src: &mut lambda_src,
+                ast: &expr.fct,
+                level_start: self.vm.sym.lock().levels(),
+                captures: Vec::new(),
+            };
+
+            nameck.check();
+            nameck.captures
+        };
+
+        let self_name = self.vm.interner.intern("self");
+
+        for (name, inner) in captures {
+            let outer = if name == self_name {
+                self.resolve_self()
+            } else {
+                let (sym, _) = self.vm.sym.lock().get_term_with_level(name).unwrap();
+                Some(self.capture_if_outer(name, sym.to_var().unwrap()))
+            };
+
+            // usage of unavailable self is reported in typeck
+            if let Some(outer) = outer {
+                lambda_src.captures.push(Capture { name, inner, outer });
+
+                if !self.src.captured_vars.contains(&outer) {
+                    self.src.captured_vars.push(outer);
+                }
+            }
+        }
+    }
+
+    fn resolve_self(&mut self) -> Option<VarId> {
+        if self.fct.is_lambda() {
+            let name = self.vm.interner.intern("self");
+            Some(self.capture_by_name(name))
+        } else if self.fct.has_self() {
+            Some(self.src.var_self().id)
+        } else {
+            None
+        }
+    }
+
+    fn capture_if_outer(&mut self, name: Name, var_id: VarId) -> VarId {
+        let (_, level) = self.vm.sym.lock().get_term_with_level(name).unwrap();
+
+        if level >= self.level_start {
+            var_id
+        } else {
+            self.capture_by_name(name)
+        }
+    }
+
+    fn capture_by_name(&mut self, name: Name) -> VarId {
+        debug_assert!(self.fct.is_lambda());
+
+        if let Some(&(_, var_id)) = self.captures.iter().find(|&&(n, _)| n == name) {
+            return var_id;
+        }
+
+        // captured variables are copied into the lambda and
+        // therefore cannot be reassigned
+        let var_id = VarId(self.src.vars.len());
+        self.src.vars.push(Var {
+            id: var_id,
+            name,
+            ty: BuiltinType::Unit,
+            reassignable: false,
+            node_id: self.ast.id,
+        });
+
+        self.vm
+            .sym
+            .lock()
+            .insert_term_at(self.level_start, name, SymVar(var_id));
+        self.captures.push((name, var_id));
+
+        var_id
+    }
+
     fn check_expr_path(&mut self, path: &'ast ExprPathType) {
         self.visit_expr(&path.lhs);
         // do not check right hand site of path
@@ -265,6 +462,85 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
         // do not check right hand site of dot
     }
 
+    fn check_expr_lit_struct(&mut self, expr: &'ast ExprLitStructType) {
+        let type_sym = self.vm.sym.lock().get_type(expr.path);
+
+        if let Some(SymStruct(id)) = type_sym {
+            self.src.map_idents.insert(expr.id, IdentType::Struct(id));
+        } else {
+            let candidates = self.vm.sym.lock().type_names();
+            let notes = did_you_mean(self.vm, expr.path, candidates);
+            let name = str(self.vm, expr.path);
+            self.vm.diag.lock().report_with_notes(
+                self.fct.file,
+                expr.pos,
+                SemError::UnknownStruct(name),
+                notes,
+            );
+        }
+
+        for arg in &expr.args {
+            self.visit_expr(&arg.value);
+        }
+    }
+
+    fn check_expr_match(&mut self, expr: &'ast ExprMatchType) {
+        self.visit_expr(&expr.expr);
+
+        for case in &expr.cases {
+            self.vm.sym.lock().push_level();
+
+            match case.pattern {
+                MatchPattern::Underscore(_) => {}
+                MatchPattern::Literal(ref lit) => self.visit_expr(lit),
+                MatchPattern::Variant(ref pattern) => self.check_match_pattern_variant(pattern),
+            }
+
+            self.visit_expr(&case.value);
+            self.vm.sym.lock().pop_level();
+        }
+    }
+
+    fn check_match_pattern_variant(&mut self, pattern: &'ast MatchPatternVariantType) {
+        if pattern.path.len() > 1 {
+            let enum_name = pattern.path[0];
+            let type_sym = self.vm.sym.lock().get_type(enum_name);
+
+            match type_sym {
+                Some(SymEnum(id)) => {
+                    self.src.map_idents.insert(pattern.id, IdentType::Enum(id));
+                }
+
+                _ => {
+                    let name = str(self.vm, enum_name);
+                    report(
+                        self.vm,
+                        self.fct.file,
+                        pattern.pos,
+                        SemError::UnknownEnum(name),
+                    );
+                }
+            }
+        }
+
+        if let Some(ref params) = pattern.params {
+            for param in params {
+                if let Some(name) = param.name {
+                    let var_ctxt = Var {
+                        id: VarId(0),
+                        name,
+                        reassignable: false,
+                        ty: BuiltinType::Unit,
+                        node_id: param.id,
+                    };
+
+                    let var_id = self.add_var(var_ctxt);
+                    self.src.map_vars.insert(param.id, var_id);
+                }
+            }
+        }
+    }
+
     fn check_expr_block(&mut self, block: &'ast ExprBlockType) {
         self.vm.sym.lock().push_level();
 
@@ -307,6 +583,7 @@ impl<'a, 'ast> Visitor<'ast> for NameCheck<'a, 'ast> {
         match *s {
             StmtVar(ref stmt) => self.check_stmt_var(stmt),
             StmtFor(ref stmt) => self.check_stmt_for(stmt),
+            StmtTry(ref stmt) => self.check_stmt_try(stmt),
 
             // no need to handle rest of statements
             _ => visit::walk_stmt(self, s),
@@ -318,7 +595,11 @@ impl<'a, 'ast> Visitor<'ast> for NameCheck<'a, 'ast> {
             &ExprIdent(ref ident) => self.check_expr_ident(ident),
             &ExprPath(ref path) => self.check_expr_path(path),
             &ExprDot(ref dot) => self.check_expr_dot(dot),
+            &ExprLitStruct(ref expr) => self.check_expr_lit_struct(expr),
             &ExprBlock(ref block) => self.check_expr_block(block),
+            &ExprMatch(ref expr) => self.check_expr_match(expr),
+            &ExprSelf(ref expr) => self.check_expr_self(expr),
+            &ExprLambda(ref expr) => self.check_expr_lambda(expr),
 
             // no need to handle rest of expressions
             _ => visit::walk_expr(self, e),
@@ -420,6 +701,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn undefined_variable_suggestion() {
+        err_with_note(
+            "fun f() { let count = 1; let b = cout; }",
+            pos(1, 34),
+            SemError::UnknownIdentifier("cout".into()),
+            "did you mean `count`?",
+        );
+        err(
+            "fun f() { let count = 1; let b = xyz; }",
+            pos(1, 34),
+            SemError::UnknownIdentifier("xyz".into()),
+        );
+    }
+
     #[test]
     fn undefined_function() {
         err(
@@ -9,7 +9,9 @@ use crate::size::InstanceSize;
 use crate::stack;
 use crate::stdlib;
 use crate::ty::{BuiltinType, TypeList};
-use crate::vm::{ClassDef, ClassDefId, ClassId, FctId, FctKind, Intrinsic, TraitId, VM};
+use crate::vm::{
+    Capability, ClassDef, ClassDefId, ClassId, FctId, FctKind, Intrinsic, TraitId, VM,
+};
 use crate::vtable::VTableBox;
 
 pub fn internal_classes<'ast>(vm: &mut VM<'ast>) {
@@ -44,6 +46,14 @@ pub fn internal_classes<'ast>(vm: &mut VM<'ast>) {
     vm.vips.stacktrace_class = internal_class(vm, "Stacktrace", None);
     vm.vips.stacktrace_element_class = internal_class(vm, "StacktraceElement", None);
 
+    vm.vips.cls.exception = internal_class(vm, "Exception", None);
+    vm.vips.cls.division_by_zero_exception = internal_class(vm, "DivisionByZeroException", None);
+    vm.vips.cls.assertion_exception = internal_class(vm, "AssertionException", None);
+    vm.vips.cls.index_out_of_bounds_exception =
+        internal_class(vm, "IndexOutOfBoundsException", None);
+    vm.vips.cls.nil_pointer_exception = internal_class(vm, "NilPointerException", None);
+    vm.vips.cls.cast_exception = internal_class(vm, "CastException", None);
+
     vm.vips.stringable_trait = find_trait(vm, "Stringable");
     vm.vips.zero_trait = find_trait(vm, "Zero");
     *vm.vips.iterator_trait.lock() = Some(find_trait(vm, "Iterator"));
@@ -77,6 +87,8 @@ fn internal_free_classes<'ast>(vm: &mut VM<'ast>) {
             fields: Vec::new(),
             ref_fields: Vec::new(),
             vtable: None,
+            lambda: None,
+            module: None,
         })));
 
         class_defs.push(Arc::new(RwLock::new(ClassDef {
@@ -88,6 +100,8 @@ fn internal_free_classes<'ast>(vm: &mut VM<'ast>) {
             fields: Vec::new(),
             ref_fields: Vec::new(),
             vtable: None,
+            lambda: None,
+            module: None,
         })));
 
         {
@@ -403,6 +417,42 @@ pub fn internal_functions<'ast>(vm: &mut VM<'ast>) {
     if let Some(clsid) = clsid {
         native_method(vm, clsid, "start", stdlib::spawn_thread as *const u8);
     }
+
+    internal_capabilities(vm);
+}
+
+// natives that give access to the outside world are grouped into
+// capabilities, which can be denied for partially trusted programs
+fn internal_capabilities<'ast>(vm: &mut VM<'ast>) {
+    fct_capability(vm, "abort", Capability::Process);
+    fct_capability(vm, "exit", Capability::Process);
+    fct_capability(vm, "argc", Capability::Process);
+    fct_capability(vm, "argv", Capability::Process);
+
+    fct_capability(vm, "sleep", Capability::Threads);
+
+    let iname = vm.interner.intern("Thread");
+    let clsid = vm.sym.lock().get_class(iname);
+
+    if let Some(clsid) = clsid {
+        let fid = find_method(vm, clsid, "start");
+        vm.fcts.idx(fid).write().capability = Some(Capability::Threads);
+    }
+
+    fct_capability(vm, "call", Capability::Reflection);
+    fct_capability(vm, "encodedBytecode", Capability::Reflection);
+}
+
+fn fct_capability<'ast>(vm: &mut VM<'ast>, name: &str, capability: Capability) {
+    let name = vm.interner.intern(name);
+    let fctid = vm.sym.lock().get_fct(name);
+
+    if let Some(fctid) = fctid {
+        let fct = vm.fcts.idx(fctid);
+        let mut fct = fct.write();
+        assert!(fct.internal);
+        fct.capability = Some(capability);
+    }
 }
 
 fn native_method<'ast>(vm: &mut VM<'ast>, clsid: ClassId, name: &str, fctptr: *const u8) {
@@ -9,7 +9,7 @@ pub fn check<'ast>(vm: &VM<'ast>) {
     for fct in vm.fcts.iter() {
         let fct = fct.read();
 
-        if !fct.is_src() {
+        if !vm.needs_body_check(&fct) {
             continue;
         }
 
@@ -64,13 +64,32 @@ pub fn returns_value(s: &Stmt) -> Result<(), Position> {
         StmtContinue(ref stmt) => Err(stmt.pos),
         StmtVar(ref stmt) => Err(stmt.pos),
         StmtExpr(ref stmt) => expr_returns_value(&stmt.expr),
+        StmtThrow(_) => Ok(()),
+        StmtTry(ref stmt) => try_returns_value(stmt),
     }
 }
 
+fn try_returns_value(s: &StmtTryType) -> Result<(), Position> {
+    if let Some(ref finally_block) = s.finally_block {
+        if returns_value(&finally_block.block).is_ok() {
+            return Ok(());
+        }
+    }
+
+    returns_value(&s.try_block)?;
+
+    for catch in &s.catch_blocks {
+        returns_value(&catch.block)?;
+    }
+
+    Ok(())
+}
+
 pub fn expr_returns_value(e: &Expr) -> Result<(), Position> {
     match *e {
         Expr::ExprBlock(ref block) => expr_block_returns_value(block),
         Expr::ExprIf(ref expr) => expr_if_returns_value(expr),
+        Expr::ExprMatch(ref expr) => expr_match_returns_value(expr),
         _ => Err(e.pos()),
     }
 }
@@ -101,6 +120,18 @@ fn expr_if_returns_value(e: &ExprIfType) -> Result<(), Position> {
     }
 }
 
+fn expr_match_returns_value(e: &ExprMatchType) -> Result<(), Position> {
+    if e.cases.is_empty() {
+        return Err(e.pos);
+    }
+
+    for case in &e.cases {
+        expr_returns_value(&case.value)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::error::msg::SemError;
@@ -164,4 +195,27 @@ mod tests {
         ok("fun f() -> Int { if true { return 1; } else { return 2; } }");
         ok("fun f() -> Int { return 1; 1+2; }");
     }
+
+    #[test]
+    fn returns_throw_and_try() {
+        ok("fun f() -> Int { throw Exception(\"test\"); }");
+        ok("fun f() -> Int { try { return 1; } catch x: Exception { return 2; } }");
+        ok("fun f() -> Int { try { } finally { return 2; } }");
+        err(
+            "fun f() -> Int { try { return 1; } catch x: Exception { } }",
+            pos(1, 16),
+            SemError::ReturnType("Int".into(), "()".into()),
+        );
+        test_always_returns("fun f() { throw Exception(\"test\"); }", true);
+    }
+
+    #[test]
+    fn returns_match() {
+        ok("fun f(x: Int) -> Int { match x { 1 => { return 1; } _ => { return 2; } } }");
+        err(
+            "fun f(x: Int) -> Int { match x { 1 => { return 1; } _ => { } } }",
+            pos(1, 22),
+            SemError::ReturnType("Int".into(), "()".into()),
+        );
+    }
 }
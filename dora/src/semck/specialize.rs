@@ -1,5 +1,5 @@
 use parking_lot::{Mutex, RwLock};
-use std::cmp::max;
+use std::collections::HashMap;
 use std::ptr;
 use std::sync::Arc;
 
@@ -7,11 +7,13 @@ use crate::mem;
 use crate::object::Header;
 use crate::size::InstanceSize;
 use crate::ty::{BuiltinType, TypeList};
+use crate::vm::module::ModuleId;
 use crate::vm::{
     ensure_tuple, CallType, Class, ClassDef, ClassDefId, ClassId, EnumData, EnumDef, EnumDefId,
-    EnumId, EnumLayout, FieldDef, StructData, StructDef, StructDefId, StructFieldDef, StructId, VM,
+    EnumDefVariant, EnumId, EnumLayout, Fct, FctDef, FctId, FctKind, FctParent, FctSrc, FieldDef,
+    ImplData, StructData, StructDef, StructDefId, StructFieldDef, StructId, VM,
 };
-use crate::vtable::{VTableBox, DISPLAY_SIZE};
+use crate::vtable::{VTable, VTableBox, DISPLAY_SIZE};
 
 pub fn specialize_type(
     vm: &VM,
@@ -53,55 +55,44 @@ pub fn specialize_struct(vm: &VM, struc: &StructData, type_params: TypeList) ->
 }
 
 fn create_specialized_struct(vm: &VM, struc: &StructData, type_params: TypeList) -> StructDefId {
-    let id = {
-        let mut struct_defs = vm.struct_defs.lock();
-        let id: StructDefId = struct_defs.len().into();
-
-        let old = struc
-            .specializations
-            .write()
-            .insert(type_params.clone(), id);
-        assert!(old.is_none());
-
-        struct_defs.push(Arc::new(Mutex::new(StructDef {
-            size: 0,
-            align: 0,
-            fields: Vec::new(),
-            ref_fields: Vec::new(),
-        })));
-
-        id
+    let types: Vec<BuiltinType> = struc
+        .fields
+        .iter()
+        .map(|f| specialize_type(vm, f.ty, &type_params, &TypeList::empty()))
+        .collect();
+    debug_assert!(types.iter().all(|ty| !ty.contains_type_param(vm)));
+
+    let tuple_id = ensure_tuple(vm, types.clone());
+
+    let struct_def = {
+        let tuples = vm.tuples.lock();
+        let tuple = tuples.get_tuple(tuple_id);
+
+        let fields = types
+            .iter()
+            .zip(tuple.offsets())
+            .map(|(&ty, &offset)| StructFieldDef { offset, ty })
+            .collect();
+
+        StructDef {
+            tuple_id,
+            fields,
+            size: tuple.size(),
+            align: tuple.align(),
+            ref_fields: tuple.references().to_vec(),
+        }
     };
 
-    let mut size = 0;
-    let mut align = 0;
-    let mut fields = Vec::with_capacity(struc.fields.len());
-    let mut ref_fields = Vec::new();
-
-    for f in &struc.fields {
-        let ty = specialize_type(vm, f.ty, &type_params, &TypeList::empty());
-        debug_assert!(!ty.contains_type_param(vm));
-
-        let field_size = ty.size(vm);
-        let field_align = ty.align(vm);
-
-        let offset = mem::align_i32(size, field_align);
-        fields.push(StructFieldDef { offset, ty });
+    let mut struct_defs = vm.struct_defs.lock();
+    let mut specializations = struc.specializations.write();
 
-        size = offset + field_size;
-        align = max(align, field_align);
-
-        if ty.reference_type() {
-            ref_fields.push(offset);
-        }
+    if let Some(&id) = specializations.get(&type_params) {
+        return id;
     }
 
-    let struct_def = vm.struct_defs.idx(id);
-    let mut struct_def = struct_def.lock();
-    struct_def.size = size;
-    struct_def.align = align;
-    struct_def.fields = fields;
-    struct_def.ref_fields = ref_fields;
+    let id: StructDefId = struct_defs.len().into();
+    struct_defs.push(Arc::new(Mutex::new(struct_def)));
+    specializations.insert(type_params, id);
 
     id
 }
@@ -121,6 +112,19 @@ pub fn specialize_enum(vm: &VM, xenum: &EnumData, type_params: TypeList) -> Enum
 }
 
 fn create_specialized_enum(vm: &VM, xenum: &EnumData, type_params: TypeList) -> EnumDefId {
+    let has_payload = xenum
+        .variants
+        .iter()
+        .any(|variant| !variant.types.is_empty());
+
+    // the layout needs to be known before the variants are specialized,
+    // since a payload might refer to the enum itself.
+    let (layout, size, align) = if has_payload {
+        (EnumLayout::Tagged, mem::ptr_width(), mem::ptr_width())
+    } else {
+        (EnumLayout::Int, 4, 4)
+    };
+
     let id = {
         let mut enum_defs = vm.enum_defs.lock();
         let id: EnumDefId = enum_defs.len().into();
@@ -134,20 +138,198 @@ fn create_specialized_enum(vm: &VM, xenum: &EnumData, type_params: TypeList) ->
         enum_defs.push(Arc::new(RwLock::new(EnumDef {
             id,
             enum_id: xenum.id,
-            type_params,
-            size: 0,
-            align: 0,
-            layout: EnumLayout::Int,
+            type_params: type_params.clone(),
+            size,
+            align,
+            layout,
+            variants: Vec::new(),
         })));
 
         id
     };
 
+    let mut variants = Vec::with_capacity(xenum.variants.len());
+
+    for variant in &xenum.variants {
+        let types = variant
+            .types
+            .iter()
+            .map(|&ty| specialize_type(vm, ty, &type_params, &TypeList::empty()))
+            .collect::<Vec<_>>();
+
+        let cls_def_id = if layout == EnumLayout::Tagged {
+            Some(create_enum_variant_class(vm, &types))
+        } else {
+            None
+        };
+
+        variants.push(EnumDefVariant { types, cls_def_id });
+    }
+
     let enum_def = vm.enum_defs.idx(id);
     let mut enum_def = enum_def.write();
-    enum_def.size = 4;
-    enum_def.align = 4;
-    enum_def.layout = EnumLayout::Int;
+    enum_def.variants = variants;
+
+    id
+}
+
+// Values of tagged enums are heap objects: the variant tag is stored as
+// the first field and is followed by the payload of the variant.
+fn create_enum_variant_class(vm: &VM, types: &[BuiltinType]) -> ClassDefId {
+    let id = {
+        let mut class_defs = vm.class_defs.lock();
+        let id: ClassDefId = class_defs.len().into();
+
+        class_defs.push(Arc::new(RwLock::new(ClassDef {
+            id,
+            cls_id: None,
+            type_params: TypeList::empty(),
+            parent_id: None,
+            size: InstanceSize::Fixed(0),
+            fields: Vec::new(),
+            ref_fields: Vec::new(),
+            vtable: None,
+            lambda: None,
+            module: None,
+        })));
+
+        id
+    };
+
+    let mut fields = Vec::with_capacity(types.len() + 1);
+    let mut ref_fields = Vec::new();
+    let mut csize = Header::size();
+
+    fields.push(FieldDef {
+        offset: csize,
+        ty: BuiltinType::Int,
+    });
+    csize += BuiltinType::Int.size(vm);
+
+    for &ty in types {
+        debug_assert!(!ty.contains_type_param(vm));
+
+        let offset = mem::align_i32(csize, ty.align(vm));
+        fields.push(FieldDef { offset, ty });
+
+        csize = offset + ty.size(vm);
+
+        if let Some(tuple_id) = ty.layout_tuple_id(vm) {
+            let tuples = vm.tuples.lock();
+            let tuple = tuples.get_tuple(tuple_id);
+
+            for &ref_offset in tuple.references() {
+                ref_fields.push(offset + ref_offset);
+            }
+        } else if ty.reference_type(vm) {
+            ref_fields.push(offset);
+        }
+    }
+
+    let instance_size = mem::align_i32(csize, mem::ptr_width());
+
+    let cls_def = vm.class_defs.idx(id);
+    let mut cls_def = cls_def.write();
+    cls_def.size = InstanceSize::Fixed(instance_size);
+    cls_def.fields = fields;
+    cls_def.ref_fields = ref_fields;
+
+    let clsptr = (&*cls_def) as *const ClassDef as *mut ClassDef;
+    let vtable = VTableBox::new(clsptr, instance_size as usize, 0, &[]);
+    cls_def.vtable = Some(vtable);
+
+    ensure_display(vm, &mut cls_def);
+
+    id
+}
+
+// Closure objects store the captured variables as fields in the order of
+// `FctSrc::captures`. The only entry in the vtable is the lambda itself.
+pub fn specialize_lambda(
+    vm: &VM,
+    fct: &Fct,
+    cls_type_params: TypeList,
+    fct_type_params: TypeList,
+) -> ClassDefId {
+    let fct_def_id = FctDef::with(vm, fct, cls_type_params.clone(), fct_type_params.clone());
+    let fct_def = vm.fct_defs.idx(fct_def_id);
+    let mut fct_def = fct_def.write();
+
+    if let Some(id) = fct_def.closure_class {
+        return id;
+    }
+
+    let types = {
+        let src = fct.src();
+        let src = src.read();
+
+        src.captures
+            .iter()
+            .map(|capture| {
+                let ty = src.vars[capture.inner].ty;
+                specialize_type(vm, ty, &cls_type_params, &fct_type_params)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let id = {
+        let mut class_defs = vm.class_defs.lock();
+        let id: ClassDefId = class_defs.len().into();
+
+        class_defs.push(Arc::new(RwLock::new(ClassDef {
+            id,
+            cls_id: None,
+            type_params: TypeList::empty(),
+            parent_id: None,
+            size: InstanceSize::Fixed(0),
+            fields: Vec::new(),
+            ref_fields: Vec::new(),
+            vtable: None,
+            lambda: Some(fct_def_id),
+            module: None,
+        })));
+
+        id
+    };
+
+    let mut fields = Vec::with_capacity(types.len());
+    let mut ref_fields = Vec::new();
+    let mut csize = Header::size();
+
+    for ty in types {
+        let offset = mem::align_i32(csize, ty.align(vm));
+        fields.push(FieldDef { offset, ty });
+
+        csize = offset + ty.size(vm);
+
+        if let Some(tuple_id) = ty.layout_tuple_id(vm) {
+            let tuples = vm.tuples.lock();
+            let tuple = tuples.get_tuple(tuple_id);
+
+            for &ref_offset in tuple.references() {
+                ref_fields.push(offset + ref_offset);
+            }
+        } else if ty.reference_type(vm) {
+            ref_fields.push(offset);
+        }
+    }
+
+    let instance_size = mem::align_i32(csize, mem::ptr_width());
+
+    let cls_def = vm.class_defs.idx(id);
+    let mut cls_def = cls_def.write();
+    cls_def.size = InstanceSize::Fixed(instance_size);
+    cls_def.fields = fields;
+    cls_def.ref_fields = ref_fields;
+
+    let clsptr = (&*cls_def) as *const ClassDef as *mut ClassDef;
+    let vtable_entries = [vm.compile_stub().to_usize()];
+    let vtable = VTableBox::new(clsptr, instance_size as usize, 0, &vtable_entries);
+    cls_def.vtable = Some(vtable);
+
+    ensure_display(vm, &mut cls_def);
+
+    fct_def.closure_class = Some(id);
 
     id
 }
@@ -171,10 +353,104 @@ pub fn specialize_class_ty(vm: &VM, ty: BuiltinType) -> ClassDefId {
             specialize_class_id_params(vm, cls_id, &params)
         }
 
+        BuiltinType::Module(module_id) => specialize_module(vm, module_id),
+
         _ => unreachable!(),
     }
 }
 
+pub fn specialize_module(vm: &VM, module_id: ModuleId) -> ClassDefId {
+    let module = vm.modules.idx(module_id);
+    let mut module = module.write();
+
+    if let Some(id) = module.class_def_id {
+        return id;
+    }
+
+    let id = {
+        let mut class_defs = vm.class_defs.lock();
+        let id: ClassDefId = class_defs.len().into();
+
+        class_defs.push(Arc::new(RwLock::new(ClassDef {
+            id,
+            cls_id: None,
+            type_params: TypeList::empty(),
+            parent_id: None,
+            size: InstanceSize::Fixed(0),
+            fields: Vec::new(),
+            ref_fields: Vec::new(),
+            vtable: None,
+            lambda: None,
+            module: Some(module_id),
+        })));
+
+        id
+    };
+
+    module.class_def_id = Some(id);
+
+    let parent_class = module.parent_class.expect("module without super class");
+    let parent_id = specialize_class_ty(vm, parent_class);
+
+    let (mut ref_fields, mut csize, vtable_len) = {
+        let parent = vm.class_defs.idx(parent_id);
+        let parent = parent.read();
+
+        let csize = match parent.size {
+            InstanceSize::Fixed(size) => size,
+            _ => unreachable!(),
+        };
+
+        let vtable_len = parent.vtable.as_ref().unwrap().table().len();
+
+        (parent.ref_fields.clone(), csize, vtable_len)
+    };
+
+    let mut fields = Vec::with_capacity(module.fields.len());
+
+    for f in &module.fields {
+        let ty = f.ty;
+        let offset = mem::align_i32(csize, ty.align(vm));
+        fields.push(FieldDef { offset, ty });
+
+        csize = offset + ty.size(vm);
+
+        if let Some(tuple_id) = ty.layout_tuple_id(vm) {
+            let tuples = vm.tuples.lock();
+            let tuple = tuples.get_tuple(tuple_id);
+
+            for &ref_offset in tuple.references() {
+                ref_fields.push(offset + ref_offset);
+            }
+        } else if ty.reference_type(vm) {
+            ref_fields.push(offset);
+        }
+    }
+
+    let instance_size = mem::align_i32(csize, mem::ptr_width());
+
+    let cls_def = vm.class_defs.idx(id);
+    let mut cls_def = cls_def.write();
+    cls_def.size = InstanceSize::Fixed(instance_size);
+    cls_def.fields = fields;
+    cls_def.ref_fields = ref_fields;
+    cls_def.parent_id = Some(parent_id);
+
+    // module objects inherit the virtual methods of their super class
+    let stub = vm.compile_stub().to_usize();
+    let vtable_entries = vec![stub; vtable_len];
+
+    let clsptr = (&*cls_def) as *const ClassDef as *mut ClassDef;
+    let mut vtable = VTableBox::new(clsptr, instance_size as usize, 0, &vtable_entries);
+    let parent_cls_id = parent_class.cls_id(vm).expect("no class");
+    ensure_itable(vm, parent_cls_id, &mut vtable);
+    cls_def.vtable = Some(vtable);
+
+    ensure_display(vm, &mut cls_def);
+
+    id
+}
+
 pub fn specialize_class(vm: &VM, cls: &Class, type_params: &TypeList) -> ClassDefId {
     if let Some(&id) = cls.specializations.read().get(&type_params) {
         return id;
@@ -202,6 +478,8 @@ fn create_specialized_class(vm: &VM, cls: &Class, type_params: &TypeList) -> Cla
             fields: Vec::new(),
             ref_fields: Vec::new(),
             vtable: None,
+            lambda: None,
+            module: None,
         })));
 
         id
@@ -221,9 +499,9 @@ fn create_specialized_class(vm: &VM, cls: &Class, type_params: &TypeList) -> Cla
 
             if element_ty.is_unit() {
                 InstanceSize::UnitArray
-            } else if element_ty.reference_type() {
+            } else if element_ty.reference_type(vm) {
                 InstanceSize::ObjArray
-            } else if let Some(tuple_id) = element_ty.tuple_id() {
+            } else if let Some(tuple_id) = element_ty.layout_tuple_id(vm) {
                 let tuples = vm.tuples.lock();
                 let tuple = tuples.get_tuple(tuple_id);
 
@@ -283,14 +561,14 @@ fn create_specialized_class(vm: &VM, cls: &Class, type_params: &TypeList) -> Cla
 
             csize = offset + field_size;
 
-            if let Some(tuple_id) = ty.tuple_id() {
+            if let Some(tuple_id) = ty.layout_tuple_id(vm) {
                 let tuples = vm.tuples.lock();
                 let tuple = tuples.get_tuple(tuple_id);
 
                 for &ref_offset in tuple.references() {
                     ref_fields.push(offset + ref_offset);
                 }
-            } else if ty.reference_type() {
+            } else if ty.reference_type(vm) {
                 ref_fields.push(offset);
             }
         }
@@ -319,7 +597,8 @@ fn create_specialized_class(vm: &VM, cls: &Class, type_params: &TypeList) -> Cla
     };
 
     let clsptr = (&*cls_def) as *const ClassDef as *mut ClassDef;
-    let vtable = VTableBox::new(clsptr, instance_size, element_size, &vtable_entries);
+    let mut vtable = VTableBox::new(clsptr, instance_size, element_size, &vtable_entries);
+    ensure_itable(vm, cls.id, &mut vtable);
     cls_def.vtable = Some(vtable);
 
     ensure_display(vm, &mut cls_def);
@@ -327,6 +606,35 @@ fn create_specialized_class(vm: &VM, cls: &Class, type_params: &TypeList) -> Cla
     id
 }
 
+// Calls on trait objects load the method from the itable, it has a method
+// table for every trait implemented by the class or one of its super classes.
+// All entries start out as the compile stub and get patched on the first call.
+fn ensure_itable(vm: &VM, cls_id: ClassId, vtable: &mut VTable) {
+    let stub = vm.compile_stub().to_usize();
+    let mut method_tables: Vec<Option<Vec<usize>>> = vec![None; vm.traits.len()];
+    let mut implements_trait = false;
+    let mut cls_id = Some(cls_id);
+
+    while let Some(id) = cls_id {
+        let cls = vm.classes.idx(id);
+        let cls = cls.read();
+
+        for &trait_id in &cls.traits {
+            let xtrait = vm.traits[trait_id].read();
+            method_tables[trait_id.to_usize()] = Some(vec![stub; xtrait.methods.len()]);
+            implements_trait = true;
+        }
+
+        cls_id = cls
+            .parent_class
+            .map(|parent_class| parent_class.cls_id(vm).expect("no class"));
+    }
+
+    if implements_trait {
+        vtable.allocate_itable(&method_tables);
+    }
+}
+
 fn ensure_display<'ast>(vm: &VM<'ast>, cls_def: &mut ClassDef) -> usize {
     let vtable = cls_def.vtable.as_mut().unwrap();
 
@@ -383,6 +691,89 @@ fn ensure_display<'ast>(vm: &VM<'ast>, cls_def: &mut ClassDef) -> usize {
     }
 }
 
+// every impl that doesn't override a trait method with a default body gets its
+// own copy of that method: the copy shares the ast of the trait method but is
+// checked and compiled with `Self` being the implementing class
+pub fn specialize_trait_defaults(vm: &VM) {
+    for ximpl in &vm.impls {
+        let (impl_id, defaults) = {
+            let ximpl = ximpl.read();
+
+            if ximpl.trait_id.is_none() || ximpl.class_ty.is_error() {
+                continue;
+            }
+
+            (ximpl.id, missing_trait_defaults(vm, &ximpl))
+        };
+
+        for trait_fct_id in defaults {
+            let trait_fct = vm.fcts.idx(trait_fct_id);
+            let trait_fct = trait_fct.read();
+
+            let fct = Fct {
+                id: FctId(0),
+                ast: trait_fct.ast,
+                pos: trait_fct.pos,
+                name: trait_fct.name,
+                param_types: Vec::new(),
+                return_type: BuiltinType::Unit,
+                parent: FctParent::Impl(impl_id),
+                has_override: false,
+                has_open: false,
+                has_final: false,
+                has_optimize_immediately: trait_fct.has_optimize_immediately,
+                is_pub: trait_fct.is_pub,
+                is_static: trait_fct.is_static,
+                is_abstract: false,
+                is_test: false,
+                use_cannon: trait_fct.use_cannon,
+                internal: false,
+                internal_resolved: false,
+                capability: None,
+                overrides: None,
+                is_constructor: false,
+                vtable_index: None,
+                initialized: false,
+                impl_for: Some(trait_fct_id),
+                file: trait_fct.file,
+                variadic_arguments: false,
+
+                type_params: Vec::new(),
+                kind: FctKind::Source(RwLock::new(FctSrc::new())),
+
+                specializations: RwLock::new(HashMap::new()),
+            };
+
+            let fct_id = vm.add_fct(fct);
+            vm.impls[impl_id].write().methods.push(fct_id);
+        }
+    }
+}
+
+// trait methods with a default body the impl doesn't define itself, trait
+// methods are unique by name
+fn missing_trait_defaults(vm: &VM, ximpl: &ImplData) -> Vec<FctId> {
+    let xtrait = vm.traits[ximpl.trait_id()].read();
+
+    xtrait
+        .methods
+        .iter()
+        .cloned()
+        .filter(|&trait_fct_id| {
+            let trait_fct = vm.fcts.idx(trait_fct_id);
+            let trait_fct = trait_fct.read();
+
+            trait_fct.ast.block.is_some()
+                && !ximpl.methods.iter().any(|&method_id| {
+                    let method = vm.fcts.idx(method_id);
+                    let method = method.read();
+
+                    method.name == trait_fct.name && method.is_static == trait_fct.is_static
+                })
+        })
+        .collect()
+}
+
 pub fn replace_type_param(
     vm: &VM,
     ty: BuiltinType,
@@ -408,9 +799,30 @@ pub fn replace_type_param(
             BuiltinType::Class(cls_id, list_id)
         }
 
+        BuiltinType::Nullable(cls_id, list_id) => {
+            let cls_ty = BuiltinType::Class(cls_id, list_id);
+
+            match replace_type_param(vm, cls_ty, cls_tp, fct_tp, self_ty) {
+                BuiltinType::Class(cls_id, list_id) => BuiltinType::Nullable(cls_id, list_id),
+                _ => unreachable!(),
+            }
+        }
+
         BuiltinType::This => self_ty.expect("no type for Self given"),
 
-        BuiltinType::Lambda(_) => unimplemented!(),
+        BuiltinType::Lambda(id) => {
+            let lambda = vm.lambda_types.lock().get(id);
+
+            let params = lambda
+                .params()
+                .iter()
+                .map(|&p| replace_type_param(vm, p, cls_tp, fct_tp, self_ty))
+                .collect::<Vec<_>>();
+            let ret = replace_type_param(vm, lambda.ret(), cls_tp, fct_tp, self_ty);
+
+            let id = vm.lambda_types.lock().insert(params, ret);
+            BuiltinType::Lambda(id)
+        }
 
         BuiltinType::Tuple(tuple_id) => {
             let subtypes = {
@@ -437,6 +849,63 @@ pub fn replace_type_param(
     }
 }
 
+pub fn replace_trait_type_params(vm: &VM, ty: BuiltinType, trait_tp: &TypeList) -> BuiltinType {
+    match ty {
+        BuiltinType::TraitTypeParam(_, tpid) => trait_tp[tpid.idx()],
+
+        BuiltinType::Class(cls_id, list_id) => {
+            let params = vm.lists.lock().get(list_id);
+
+            let params = TypeList::with(
+                params
+                    .iter()
+                    .map(|p| replace_trait_type_params(vm, p, trait_tp))
+                    .collect::<Vec<_>>(),
+            );
+
+            let list_id = vm.lists.lock().insert(params);
+            BuiltinType::Class(cls_id, list_id)
+        }
+
+        BuiltinType::Nullable(cls_id, list_id) => {
+            let cls_ty = BuiltinType::Class(cls_id, list_id);
+
+            match replace_trait_type_params(vm, cls_ty, trait_tp) {
+                BuiltinType::Class(cls_id, list_id) => BuiltinType::Nullable(cls_id, list_id),
+                _ => unreachable!(),
+            }
+        }
+
+        BuiltinType::Lambda(id) => {
+            let lambda = vm.lambda_types.lock().get(id);
+
+            let params = lambda
+                .params()
+                .iter()
+                .map(|&p| replace_trait_type_params(vm, p, trait_tp))
+                .collect::<Vec<_>>();
+            let ret = replace_trait_type_params(vm, lambda.ret(), trait_tp);
+
+            let id = vm.lambda_types.lock().insert(params, ret);
+            BuiltinType::Lambda(id)
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let subtypes = vm.tuples.lock().get(tuple_id);
+
+            let new_subtypes = subtypes
+                .iter()
+                .map(|&t| replace_trait_type_params(vm, t, trait_tp))
+                .collect::<Vec<_>>();
+
+            let tuple_id = ensure_tuple(vm, new_subtypes);
+            BuiltinType::Tuple(tuple_id)
+        }
+
+        _ => ty,
+    }
+}
+
 pub fn specialize_for_call_type(call_type: &CallType, ty: BuiltinType, vm: &VM) -> BuiltinType {
     match *call_type {
         CallType::Fct(_, ref cls_type_params, ref fct_type_params) => {
@@ -462,7 +931,16 @@ pub fn specialize_for_call_type(call_type: &CallType, ty: BuiltinType, vm: &VM)
             specialize_type(vm, ty, &cls_type_params, &TypeList::empty())
         }
 
-        CallType::Trait(_, _) => unimplemented!(),
+        // `Self` is the trait object itself
+        CallType::Trait(trait_id, _, ref fct_type_params) => replace_type_param(
+            vm,
+            ty,
+            &TypeList::empty(),
+            fct_type_params,
+            Some(BuiltinType::Trait(trait_id)),
+        ),
+
+        CallType::Lambda(_) => ty,
 
         CallType::Intrinsic(_) => unimplemented!(),
 
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::error::msg::SemError;
 use crate::semck;
 use crate::ty::BuiltinType;
@@ -15,6 +17,58 @@ pub fn check<'ast>(vm: &mut VM<'ast>, ast: &'ast Ast, map_struct_defs: &NodeMap<
     };
 
     clsck.check();
+    check_recursion(vm);
+}
+
+// a struct containing itself by value would need infinite space
+fn check_recursion(vm: &VM) {
+    for struc in vm.structs.iter() {
+        let struc = struc.lock();
+
+        for field in &struc.fields {
+            if contains_struct(vm, field.ty, struc.id, &mut HashSet::new()) {
+                vm.diag
+                    .lock()
+                    .report(struc.file, field.pos, SemError::RecursiveStructure);
+            }
+        }
+    }
+}
+
+fn contains_struct(
+    vm: &VM,
+    ty: BuiltinType,
+    struct_id: StructId,
+    visited: &mut HashSet<StructId>,
+) -> bool {
+    match ty {
+        BuiltinType::Struct(id, _) => {
+            if id == struct_id {
+                return true;
+            }
+
+            if !visited.insert(id) {
+                return false;
+            }
+
+            let struc = vm.structs.idx(id);
+            let fields: Vec<BuiltinType> = struc.lock().fields.iter().map(|f| f.ty).collect();
+
+            fields
+                .into_iter()
+                .any(|ty| contains_struct(vm, ty, struct_id, visited))
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let subtypes = vm.tuples.lock().get(tuple_id);
+
+            subtypes
+                .iter()
+                .any(|&ty| contains_struct(vm, ty, struct_id, visited))
+        }
+
+        _ => false,
+    }
 }
 
 struct StructCheck<'x, 'ast: 'x> {
@@ -93,4 +147,26 @@ mod tests {
             SemError::ShadowField("a".into()),
         );
     }
+
+    #[test]
+    fn struct_recursive() {
+        ok("struct Foo { a: Array[Foo], b: Bar } class Bar(let foo: Foo)");
+        err(
+            "struct Foo { a: Int, b: Foo }",
+            pos(1, 22),
+            SemError::RecursiveStructure,
+        );
+        err(
+            "struct Foo { a: (Int, Foo) }",
+            pos(1, 14),
+            SemError::RecursiveStructure,
+        );
+        errors(
+            "struct Foo { a: Bar } struct Bar { b: Foo }",
+            &[
+                (pos(1, 14), SemError::RecursiveStructure),
+                (pos(1, 36), SemError::RecursiveStructure),
+            ],
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::error::msg::SemError;
 use crate::ty::BuiltinType;
@@ -33,6 +33,36 @@ impl<'x, 'ast> TraitCheck<'x, 'ast> {
     fn check(&mut self) {
         self.visit_ast(self.ast);
     }
+
+    fn check_type_params(&mut self, t: &'ast ast::Trait, type_params: &'ast [ast::TypeParam]) {
+        if type_params.is_empty() {
+            let msg = SemError::TypeParamsExpected;
+            self.vm.diag.lock().report(self.file_id.into(), t.pos, msg);
+            return;
+        }
+
+        let mut names = HashSet::new();
+
+        for type_param in type_params {
+            if !names.insert(type_param.name) {
+                let name = self.vm.interner.str(type_param.name).to_string();
+                let msg = SemError::TypeParamNameNotUnique(name);
+                self.vm
+                    .diag
+                    .lock()
+                    .report(self.file_id.into(), type_param.pos, msg);
+            }
+
+            if !type_param.bounds.is_empty() {
+                // We don't support bounds for trait type parameters yet.
+                self.vm.diag.lock().report(
+                    self.file_id.into(),
+                    type_param.pos,
+                    SemError::Unimplemented,
+                );
+            }
+        }
+    }
 }
 
 impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
@@ -44,6 +74,10 @@ impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
     fn visit_trait(&mut self, t: &'ast ast::Trait) {
         self.trait_id = Some(*self.map_trait_defs.get(t.id).unwrap());
 
+        if let Some(ref type_params) = t.type_params {
+            self.check_type_params(t, type_params);
+        }
+
         visit::walk_trait(self, t);
 
         self.trait_id = None;
@@ -54,13 +88,6 @@ impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
             return;
         }
 
-        if f.block.is_some() {
-            self.vm
-                .diag
-                .lock()
-                .report(self.file_id.into(), f.pos, SemError::TraitMethodWithBody);
-        }
-
         let fct = Fct {
             id: FctId(0),
             ast: f,
@@ -80,6 +107,7 @@ impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
             use_cannon: f.use_cannon,
             internal: f.internal,
             internal_resolved: false,
+            capability: None,
             overrides: None,
             is_constructor: false,
             vtable_index: None,
@@ -108,11 +136,9 @@ mod tests {
 
     #[test]
     fn trait_method_with_body() {
-        err(
-            "trait Foo { fun foo() -> Int { return 1; } }",
-            pos(1, 13),
-            SemError::TraitMethodWithBody,
-        );
+        ok("trait Foo { fun foo() -> Int { return 1; } }");
+        ok("trait Foo { fun foo() -> Int; fun bar() -> Int = self.foo() + 1; }");
+        ok("trait Foo { @static fun foo() -> Int { return 1; } }");
     }
 
     #[test]
@@ -153,4 +179,24 @@ mod tests {
             SemError::MethodExists("foo".into(), pos(2, 13)),
         );
     }
+
+    #[test]
+    fn trait_with_type_params() {
+        ok("trait Foo[T] { fun get() -> T; }");
+        ok("trait Foo[A, B] { fun f(a: A) -> B; }");
+
+        err(
+            "trait Foo[T, T] {}",
+            pos(1, 14),
+            SemError::TypeParamNameNotUnique("T".into()),
+        );
+
+        err("trait Foo[] {}", pos(1, 1), SemError::TypeParamsExpected);
+
+        err(
+            "trait Foo[T] {} fun f[X: Foo](x: X) {}",
+            pos(1, 26),
+            SemError::Unimplemented,
+        );
+    }
 }
@@ -1,11 +1,14 @@
 use std::ptr;
 
-use crate::compiler::fct::JitFctId;
+use crate::compiler::fct::{CatchType, JitFctId};
 use crate::compiler::map::CodeDescriptor;
 use crate::handle::{root, Handle};
-use crate::object::{alloc, Array, IntArray, Ref, Stacktrace, StacktraceElement, Str};
+use crate::object::{
+    alloc, Array, Exception, IntArray, Obj, Ref, Stacktrace, StacktraceElement, Str,
+};
+use crate::semck::specialize::specialize_class_id;
 use crate::threads::THREAD;
-use crate::vm::{get_vm, FctParent, VM};
+use crate::vm::{get_vm, ClassDefId, FctParent, Trap, VM};
 
 pub struct NativeStacktrace {
     elems: Vec<StackElem>,
@@ -171,6 +174,8 @@ fn determine_stack_entry(stacktrace: &mut NativeStacktrace, vm: &VM, pc: usize)
         }
 
         Some(CodeDescriptor::TrapStub) => true,
+        Some(CodeDescriptor::ThrowStub) => true,
+        Some(CodeDescriptor::CatchStub) => true,
         Some(CodeDescriptor::GuardCheckStub) => true,
         Some(CodeDescriptor::CompileStub) => true,
         Some(CodeDescriptor::AllocStub) => true,
@@ -185,9 +190,166 @@ fn determine_stack_entry(stacktrace: &mut NativeStacktrace, vm: &VM, pc: usize)
 }
 
 pub struct ThrowResume {
-    pc: usize,
-    sp: usize,
-    fp: usize,
+    pub pc: usize,
+    pub sp: usize,
+    pub fp: usize,
+}
+
+pub enum PendingException {
+    Object(Ref<Obj>),
+    Trap(Trap, NativeStacktrace),
+}
+
+impl PendingException {
+    pub fn object_address(&self) -> Option<*const Ref<Obj>> {
+        match self {
+            PendingException::Object(ref obj) => Some(obj as *const _),
+            PendingException::Trap(_, _) => None,
+        }
+    }
+}
+
+// returns the exception class a trap is converted into, traps without
+// such a class (e.g. out of memory or denied capabilities) can't be caught
+pub fn trap_exception_class(vm: &VM, trap: Trap) -> Option<ClassDefId> {
+    let cls_id = match trap {
+        Trap::DIV0 => vm.vips.cls.division_by_zero_exception,
+        Trap::ASSERT => vm.vips.cls.assertion_exception,
+        Trap::INDEX_OUT_OF_BOUNDS => vm.vips.cls.index_out_of_bounds_exception,
+        Trap::NIL => vm.vips.cls.nil_pointer_exception,
+        Trap::CAST => vm.vips.cls.cast_exception,
+        Trap::OOM | Trap::STACK_OVERFLOW | Trap::CAPABILITY => return None,
+    };
+
+    Some(specialize_class_id(vm, cls_id))
+}
+
+// walks all Dora frames of the current DTN and returns where execution
+// continues for an exception of the given class
+pub fn find_handler(vm: &VM, cls_def_id: ClassDefId) -> Option<ThrowResume> {
+    let dtn = THREAD.with(|thread| thread.borrow().dtn());
+    assert!(!dtn.is_null());
+    let mut fp = unsafe { (*dtn).fp };
+
+    while fp != 0 {
+        let ra = unsafe { *((fp + 8) as *const usize) };
+        let caller_fp = unsafe { *(fp as *const usize) };
+
+        let data = {
+            let code_map = vm.code_map.lock();
+            code_map.get(ra.into())
+        };
+
+        let fct_id = match data {
+            Some(CodeDescriptor::DoraFct(fct_id)) => fct_id,
+            _ => return None,
+        };
+
+        let jit_fct = vm.jit_fcts.idx(fct_id);
+        let instruction_start = jit_fct.instruction_start().to_usize();
+        let offset = (ra - instruction_start) as u32;
+
+        for handler in jit_fct.exception_handlers() {
+            if handler.covers(offset) && catches(vm, handler.catch_type, cls_def_id) {
+                return Some(ThrowResume {
+                    pc: instruction_start + handler.catch as usize,
+                    sp: caller_fp - jit_fct.framesize() as usize,
+                    fp: caller_fp,
+                });
+            }
+        }
+
+        fp = caller_fp;
+    }
+
+    None
+}
+
+fn catches(vm: &VM, catch_type: CatchType, cls_def_id: ClassDefId) -> bool {
+    let expected_id = match catch_type {
+        CatchType::Any => return true,
+        CatchType::Class(expected_id) => expected_id,
+    };
+
+    let mut current_id = Some(cls_def_id);
+
+    while let Some(cls_def_id) = current_id {
+        if cls_def_id == expected_id {
+            return true;
+        }
+
+        let cls_def = vm.class_defs.idx(cls_def_id);
+        let cls_def = cls_def.read();
+        current_id = cls_def.parent_id;
+    }
+
+    false
+}
+
+pub fn set_pending_exception(exception: PendingException, resume: &ThrowResume) {
+    THREAD.with(|thread| {
+        let thread = thread.borrow();
+        *thread.exception.lock() = Some(exception);
+        thread.tld.set_throw_resume(resume);
+    });
+}
+
+pub extern "C" fn throw(exception: Handle<Obj>) {
+    let vm = get_vm();
+    let cls_def_id = exception.header().vtbl().class().id;
+
+    if let Some(resume) = find_handler(vm, cls_def_id) {
+        set_pending_exception(PendingException::Object(exception.direct()), &resume);
+        return;
+    }
+
+    eprintln!("uncaught exception");
+
+    let exception_cls_def_id = specialize_class_id(vm, vm.vips.cls.exception);
+
+    if catches(vm, CatchType::Class(exception_cls_def_id), cls_def_id) {
+        let exception: Handle<Exception> = exception.cast();
+        eprintln!("{}", String::from_utf8_lossy(exception.message.content()));
+    }
+
+    let exception: Handle<Stacktrace> = exception.cast();
+    let mut stacktrace = NativeStacktrace::new();
+
+    if !exception.backtrace.raw().is_null() {
+        let backtrace = exception.backtrace;
+        let len = backtrace.len() / 2;
+
+        for ind in 0..len {
+            let lineno = backtrace.get_at(ind * 2);
+            let fct_id = backtrace.get_at(ind * 2 + 1);
+            stacktrace.push_entry(JitFctId::from(fct_id as usize), lineno);
+        }
+    }
+
+    stacktrace.dump_err(vm);
+
+    unsafe {
+        libc::_exit(108);
+    }
+}
+
+pub extern "C" fn catch() -> Ref<Obj> {
+    let vm = get_vm();
+    let exception = THREAD.with(|thread| thread.borrow().exception.lock().take());
+
+    match exception.expect("no pending exception") {
+        PendingException::Object(obj) => obj,
+        PendingException::Trap(trap, stacktrace) => {
+            let cls_def_id = trap_exception_class(vm, trap).expect("trap can't be caught");
+            let obj: Ref<Exception> = alloc(vm, cls_def_id).cast();
+            let mut obj = root(obj);
+
+            obj.backtrace = backtrace_array(vm, &stacktrace, 0);
+            obj.message = Str::from_buffer(vm, trap.message().as_bytes());
+
+            obj.direct().cast()
+        }
+    }
 }
 
 pub extern "C" fn retrieve_stack_trace(obj: Handle<Stacktrace>) {
@@ -286,6 +448,10 @@ fn set_backtrace(vm: &VM, mut obj: Handle<Stacktrace>, via_retrieve: bool) {
         assert!(skip_constructor);
     }
 
+    obj.backtrace = backtrace_array(vm, &stacktrace, skip);
+}
+
+fn backtrace_array(vm: &VM, stacktrace: &NativeStacktrace, skip: usize) -> Ref<IntArray> {
     let len = stacktrace.len() - skip;
 
     let cls_id = vm.vips.int_array(vm);
@@ -298,5 +464,6 @@ fn set_backtrace(vm: &VM, mut obj: Handle<Stacktrace>, via_retrieve: bool) {
         array.set_at(i + 1, elem.fct_id.idx() as i32);
         i += 2;
     }
-    obj.backtrace = array.direct();
+
+    array.direct()
 }
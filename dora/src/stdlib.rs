@@ -11,7 +11,7 @@ use crate::boots;
 use crate::gc::{Address, GcReason};
 use crate::handle::{scope as handle_scope, Handle};
 use crate::object::{ByteArray, Obj, Ref, Str};
-use crate::stack::stacktrace_from_last_dtn;
+use crate::stack::{self, stacktrace_from_last_dtn, PendingException};
 use crate::sym::TermSym::SymFct;
 use crate::threads::{DoraThread, STACK_SIZE, THREAD};
 use crate::ty::TypeList;
@@ -259,25 +259,27 @@ pub extern "C" fn str_to_long(val: Handle<Str>) -> i64 {
 pub extern "C" fn trap(trap_id: u32) {
     let vm = get_vm();
     let trap = Trap::from(trap_id).expect("invalid trap id!");
-
-    let msg = match trap {
-        Trap::DIV0 => "division by 0",
-        Trap::ASSERT => "assert failed",
-        Trap::INDEX_OUT_OF_BOUNDS => "array index out of bounds",
-        Trap::NIL => "nil check failed",
-        Trap::CAST => "cast failed",
-        Trap::OOM => "out of memory",
-        Trap::STACK_OVERFLOW => "stack overflow",
-    };
-
-    eprintln!("{}", msg);
     let stacktrace = stacktrace_from_last_dtn(vm);
+
+    if let Some(cls_def_id) = stack::trap_exception_class(vm, trap) {
+        if let Some(resume) = stack::find_handler(vm, cls_def_id) {
+            stack::set_pending_exception(PendingException::Trap(trap, stacktrace), &resume);
+            return;
+        }
+    }
+
+    eprintln!("{}", trap.message());
     stacktrace.dump_err(vm);
     unsafe {
         libc::_exit(100 + trap_id as i32);
     }
 }
 
+// invoked instead of natives whose capability was denied
+pub extern "C" fn capability_denied() {
+    trap(Trap::CAPABILITY.int());
+}
+
 pub extern "C" fn spawn_thread(obj: Handle<Obj>) {
     use crate::compiler;
     use crate::stack::DoraToNativeInfo;
@@ -1,23 +1,64 @@
 use std::collections::HashMap;
+use std::mem;
+use std::sync::Arc;
 
 use self::TypeSym::*;
 
 use crate::sym::TermSym::{SymConst, SymFct, SymGlobal, SymModule, SymVar};
 use crate::ty::TypeListId;
 use crate::vm::module::ModuleId;
-use crate::vm::{ClassId, ConstId, EnumId, FctId, FieldId, GlobalId, StructId, TraitId, VarId};
+use crate::vm::{
+    AliasId, ClassId, ConstId, EnumId, FctId, FieldId, GlobalId, StructId, TraitId, VarId,
+};
 use dora_parser::interner::Name;
 
 #[derive(Debug)]
 pub struct SymTable {
     levels: Vec<SymLevel>,
+
+    // top-level names of the current file's namespace and its imports, they
+    // shadow the root namespace in the outermost level
+    scope: Option<Arc<SymLevel>>,
 }
 
 impl SymTable {
     pub fn new() -> SymTable {
         SymTable {
             levels: vec![SymLevel::new()],
+            scope: None,
+        }
+    }
+
+    // replaces the file scope and returns the previous one
+    pub fn set_scope(&mut self, scope: Option<Arc<SymLevel>>) -> Option<Arc<SymLevel>> {
+        mem::replace(&mut self.scope, scope)
+    }
+
+    // looks up a symbol from the innermost to the outermost level and returns
+    // the index of the level, the file scope counts as the outermost level
+    fn lookup<T, F>(&self, f: F) -> Option<(T, usize)>
+    where
+        F: Fn(&SymLevel) -> Option<T>,
+    {
+        for (idx, level) in self.levels.iter().enumerate().skip(1).rev() {
+            if let Some(val) = f(level) {
+                return Some((val, idx));
+            }
         }
+
+        if let Some(ref scope) = self.scope {
+            if let Some(val) = f(scope) {
+                return Some((val, 0));
+            }
+        }
+
+        f(&self.levels[0]).map(|val| (val, 0))
+    }
+
+    fn visible_levels(&self) -> impl Iterator<Item = &SymLevel> {
+        self.levels
+            .iter()
+            .chain(self.scope.iter().map(|scope| &**scope))
     }
 
     pub fn push_level(&mut self) {
@@ -35,23 +76,43 @@ impl SymTable {
     }
 
     pub fn get_type(&self, name: Name) -> Option<TypeSym> {
-        for level in self.levels.iter().rev() {
-            if let Some(val) = level.get_type(name) {
-                return Some(val.clone());
+        self.lookup(|level| level.get_type(name).cloned())
+            .map(|(sym, _)| sym)
+    }
+
+    // looks only at the top-level declarations visible in the current file
+    pub fn get_global_type(&self, name: Name) -> Option<TypeSym> {
+        if let Some(ref scope) = self.scope {
+            if let Some(sym) = scope.get_type(name) {
+                return Some(sym.clone());
             }
         }
 
-        None
+        self.levels[0].get_type(name).cloned()
     }
 
     pub fn get_term(&self, name: Name) -> Option<TermSym> {
-        for level in self.levels.iter().rev() {
-            if let Some(val) = level.get_term(name) {
-                return Some(val.clone());
-            }
-        }
+        self.lookup(|level| level.get_term(name).cloned())
+            .map(|(sym, _)| sym)
+    }
+
+    // like get_term, but also returns the index of the level the symbol was found in
+    pub fn get_term_with_level(&self, name: Name) -> Option<(TermSym, usize)> {
+        self.lookup(|level| level.get_term(name).cloned())
+    }
 
-        None
+    // names of all visible terms, e.g. for suggestions on unknown identifiers
+    pub fn term_names(&self) -> Vec<Name> {
+        self.visible_levels()
+            .flat_map(|level| level.terms.keys().cloned())
+            .collect()
+    }
+
+    // names of all visible types
+    pub fn type_names(&self) -> Vec<Name> {
+        self.visible_levels()
+            .flat_map(|level| level.types.keys().cloned())
+            .collect()
     }
 
     pub fn get_class(&self, name: Name) -> Option<ClassId> {
@@ -97,9 +158,13 @@ impl SymTable {
     pub fn insert_term(&mut self, name: Name, sym: TermSym) -> Option<TermSym> {
         self.levels.last_mut().unwrap().insert_term(name, sym)
     }
+
+    pub fn insert_term_at(&mut self, level: usize, name: Name, sym: TermSym) -> Option<TermSym> {
+        self.levels[level].insert_term(name, sym)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SymLevel {
     types: HashMap<Name, TypeSym>,
     terms: HashMap<Name, TermSym>,
@@ -146,7 +211,9 @@ pub enum TypeSym {
     SymTrait(TraitId),
     SymClassTypeParam(ClassId, TypeListId),
     SymFctTypeParam(FctId, TypeListId),
+    SymTraitTypeParam(TraitId, TypeListId),
     SymEnum(EnumId),
+    SymAlias(AliasId),
 }
 
 #[derive(Debug, Clone)]
@@ -208,6 +275,7 @@ impl TypeSym {
         match *self {
             SymClassTypeParam(_, _) => true,
             SymFctTypeParam(_, _) => true,
+            SymTraitTypeParam(_, _) => true,
             _ => false,
         }
     }
@@ -1,9 +1,7 @@
 use dora_parser::ast::Ast;
-use dora_parser::lexer::reader::Reader;
-use dora_parser::parser::Parser;
 
 use crate::driver::cmd::Args;
-use crate::driver::start::parse_bundled_stdlib;
+use crate::driver::start::{parse_bundled_stdlib, parse_str};
 use crate::semck;
 use crate::vm::VM;
 
@@ -23,20 +21,40 @@ where
 }
 
 pub fn parse_with_errors<F, T>(code: &'static str, f: F) -> T
+where
+    F: FnOnce(&VM) -> T,
+{
+    parse_with_args(code, Default::default(), f)
+}
+
+pub fn parse_with_args<F, T>(code: &'static str, args: Args, f: F) -> T
+where
+    F: FnOnce(&VM) -> T,
+{
+    parse_files_with_args(&[code], args, f)
+}
+
+// every code snippet is parsed as a file of its own
+pub fn parse_files_with_errors<F, T>(codes: &[&'static str], f: F) -> T
+where
+    F: FnOnce(&VM) -> T,
+{
+    parse_files_with_args(codes, Default::default(), f)
+}
+
+fn parse_files_with_args<F, T>(codes: &[&'static str], args: Args, f: F) -> T
 where
     F: FnOnce(&VM) -> T,
 {
     let mut ast = Ast::new();
-    let args: Args = Default::default();
     let empty = Ast::new();
     let mut vm = VM::new(args, &empty);
 
     parse_bundled_stdlib(&mut vm, &mut ast).expect("failed parsing stdlib");
+    vm.trusted_files = vm.files.len();
 
-    {
-        let reader = Reader::from_string("<<code>>", code);
-        let parser = Parser::new(reader, &vm.id_generator, &mut ast, &mut vm.interner);
-        parser.parse().unwrap();
+    for code in codes {
+        parse_str(code, &mut vm, &mut ast).expect("failed parsing code");
     }
 
     vm.ast = &ast;
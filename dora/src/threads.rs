@@ -7,7 +7,7 @@ use std::sync::Arc;
 use crate::gc::{tlab, Address, Region, K};
 use crate::handle::HandleMemory;
 use crate::safepoint;
-use crate::stack::DoraToNativeInfo;
+use crate::stack::{DoraToNativeInfo, PendingException, ThrowResume};
 use crate::vm::{get_vm, VM};
 
 pub const STACK_SIZE: usize = 500 * K;
@@ -120,6 +120,7 @@ pub struct DoraThread {
     pub saved_pc: AtomicUsize,
     pub saved_fp: AtomicUsize,
     pub state: StateManager,
+    pub exception: Mutex<Option<PendingException>>,
 }
 
 unsafe impl Sync for DoraThread {}
@@ -142,6 +143,7 @@ impl DoraThread {
             saved_pc: AtomicUsize::new(0),
             saved_fp: AtomicUsize::new(0),
             state: StateManager::new(),
+            exception: Mutex::new(None),
         })
     }
 
@@ -325,6 +327,9 @@ pub struct ThreadLocalData {
     guard_stack_limit: AtomicUsize,
     real_stack_limit: AtomicUsize,
     dtn: AtomicUsize,
+    resume_pc: AtomicUsize,
+    resume_sp: AtomicUsize,
+    resume_fp: AtomicUsize,
 }
 
 impl ThreadLocalData {
@@ -336,6 +341,9 @@ impl ThreadLocalData {
             guard_stack_limit: AtomicUsize::new(0),
             real_stack_limit: AtomicUsize::new(0),
             dtn: AtomicUsize::new(0),
+            resume_pc: AtomicUsize::new(0),
+            resume_sp: AtomicUsize::new(0),
+            resume_fp: AtomicUsize::new(0),
         }
     }
 
@@ -395,6 +403,24 @@ impl ThreadLocalData {
         offset_of!(ThreadLocalData, dtn) as i32
     }
 
+    pub fn set_throw_resume(&self, resume: &ThrowResume) {
+        self.resume_pc.store(resume.pc, Ordering::Relaxed);
+        self.resume_sp.store(resume.sp, Ordering::Relaxed);
+        self.resume_fp.store(resume.fp, Ordering::Relaxed);
+    }
+
+    pub fn resume_pc_offset() -> i32 {
+        offset_of!(ThreadLocalData, resume_pc) as i32
+    }
+
+    pub fn resume_sp_offset() -> i32 {
+        offset_of!(ThreadLocalData, resume_sp) as i32
+    }
+
+    pub fn resume_fp_offset() -> i32 {
+        offset_of!(ThreadLocalData, resume_fp) as i32
+    }
+
     pub fn arm_stack_guard(&self) {
         self.guard_stack_limit.store(!0, Ordering::Release);
     }
@@ -1,3 +1,4 @@
+use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::ops::Index;
 use std::sync::Arc;
@@ -6,7 +7,7 @@ use crate::mem;
 use crate::semck;
 use crate::vm::module::ModuleId;
 use crate::vm::VM;
-use crate::vm::{ClassId, EnumId, FctId, StructId, TraitId, TupleId};
+use crate::vm::{ClassId, EnumDef, EnumId, EnumLayout, FctId, StructId, TraitId, TupleId};
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum BuiltinType {
@@ -43,6 +44,9 @@ pub enum BuiltinType {
     // some class
     Class(ClassId, TypeListId),
 
+    // some class that may also be nil: `T?`, only with --null-safety
+    Nullable(ClassId, TypeListId),
+
     // some struct
     Struct(StructId, TypeListId),
 
@@ -58,6 +62,7 @@ pub enum BuiltinType {
     // some type variable
     FctTypeParam(FctId, TypeListId),
     ClassTypeParam(ClassId, TypeListId),
+    TraitTypeParam(TraitId, TypeListId),
 
     // some lambda
     Lambda(LambdaId),
@@ -102,6 +107,28 @@ impl BuiltinType {
         }
     }
 
+    pub fn is_nullable(&self) -> bool {
+        match *self {
+            BuiltinType::Nullable(_, _) => true,
+            _ => false,
+        }
+    }
+
+    // the type without nil, e.g. after a nil check
+    pub fn non_nullable(&self) -> BuiltinType {
+        match *self {
+            BuiltinType::Nullable(cls_id, list_id) => BuiltinType::Class(cls_id, list_id),
+            _ => *self,
+        }
+    }
+
+    pub fn nullable(&self) -> BuiltinType {
+        match *self {
+            BuiltinType::Class(cls_id, list_id) => BuiltinType::Nullable(cls_id, list_id),
+            _ => *self,
+        }
+    }
+
     pub fn is_module(&self) -> bool {
         match *self {
             BuiltinType::Module(_) => true,
@@ -127,6 +154,7 @@ impl BuiltinType {
         match self {
             &BuiltinType::ClassTypeParam(_, _) => true,
             &BuiltinType::FctTypeParam(_, _) => true,
+            &BuiltinType::TraitTypeParam(_, _) => true,
             _ => false,
         }
     }
@@ -138,6 +166,20 @@ impl BuiltinType {
         }
     }
 
+    pub fn is_lambda(&self) -> bool {
+        match *self {
+            BuiltinType::Lambda(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn lambda_id(&self) -> Option<LambdaId> {
+        match *self {
+            BuiltinType::Lambda(id) => Some(id),
+            _ => None,
+        }
+    }
+
     pub fn cls_id(&self, vm: &VM) -> Option<ClassId> {
         match *self {
             BuiltinType::Class(cls_id, _) => Some(cls_id),
@@ -180,6 +222,23 @@ impl BuiltinType {
         }
     }
 
+    // tuples and structs are stored inline, a struct is laid out like the
+    // tuple of its field types
+    pub fn layout_tuple_id(&self, vm: &VM) -> Option<TupleId> {
+        match *self {
+            BuiltinType::Tuple(tuple_id) => Some(tuple_id),
+            BuiltinType::Struct(sid, list_id) => {
+                let params = vm.lists.lock().get(list_id);
+                let sid = semck::specialize::specialize_struct_id_params(vm, sid, params);
+                let struc = vm.struct_defs.idx(sid);
+                let struc = struc.lock();
+
+                Some(struc.tuple_id)
+            }
+            _ => None,
+        }
+    }
+
     pub fn implements_trait(&self, vm: &VM, trait_id: TraitId) -> bool {
         if let Some(cls_id) = self.cls_id(vm) {
             let cls = vm.classes.idx(cls_id);
@@ -203,23 +262,37 @@ impl BuiltinType {
         match self {
             &BuiltinType::ClassTypeParam(_, _) => true,
             &BuiltinType::FctTypeParam(_, _) => true,
+            &BuiltinType::TraitTypeParam(_, _) => true,
 
-            &BuiltinType::Class(_, list_id) => {
+            &BuiltinType::Class(_, list_id) | &BuiltinType::Nullable(_, list_id) => {
                 let params = vm.lists.lock().get(list_id);
                 params.iter().any(|t| t.contains_type_param(vm))
             }
 
-            &BuiltinType::Lambda(_) => unimplemented!(),
+            &BuiltinType::Lambda(id) => {
+                let lambda = vm.lambda_types.lock().get(id);
+                lambda.params.iter().any(|t| t.contains_type_param(vm))
+                    || lambda.ret.contains_type_param(vm)
+            }
 
             _ => false,
         }
     }
 
-    pub fn reference_type(&self) -> bool {
+    pub fn reference_type(&self, vm: &VM) -> bool {
         match *self {
             BuiltinType::Ptr => true,
             BuiltinType::Class(_, _) => true,
+            BuiltinType::Nullable(_, _) => true,
+            BuiltinType::Module(_) => true,
             BuiltinType::Trait(_) => true,
+            BuiltinType::Lambda(_) => true,
+            BuiltinType::Enum(enum_id, list_id) => {
+                let enum_def = enum_def(vm, enum_id, list_id);
+                let enum_def = enum_def.read();
+
+                enum_def.layout != EnumLayout::Int
+            }
             _ => false,
         }
     }
@@ -285,6 +358,7 @@ impl BuiltinType {
                     format!("{}[{}]", base, params)
                 }
             }
+            BuiltinType::Nullable(_, _) => format!("{}?", self.non_nullable().name(vm)),
             BuiltinType::Struct(sid, list_id) => {
                 let struc = vm.structs.idx(sid);
                 let struc = struc.lock();
@@ -344,6 +418,13 @@ impl BuiltinType {
                 vm.interner.str(fct.type_params[id.idx()].name).to_string()
             }
 
+            BuiltinType::TraitTypeParam(tid, id) => {
+                let xtrait = vm.traits[tid].read();
+                vm.interner
+                    .str(xtrait.type_params[id.idx()].name)
+                    .to_string()
+            }
+
             BuiltinType::Lambda(id) => {
                 let lambda = vm.lambda_types.lock().get(id);
                 let params = lambda
@@ -393,8 +474,14 @@ impl BuiltinType {
             BuiltinType::Nil => panic!("nil does not allow any other types"),
             BuiltinType::Ptr => panic!("ptr does not allow any other types"),
             BuiltinType::This => unreachable!(),
+            // with --null-safety only nullable types allow nil
             BuiltinType::Class(_, _) => {
-                *self == other || other.is_nil() || other.subclass_from(vm, *self)
+                *self == other
+                    || (other.is_nil() && !vm.args.flag_null_safety)
+                    || other.subclass_from(vm, *self)
+            }
+            BuiltinType::Nullable(_, _) => {
+                other.is_nil() || self.non_nullable().allows(vm, other.non_nullable())
             }
             BuiltinType::Tuple(tuple_id) => match other {
                 BuiltinType::Tuple(other_tuple_id) => {
@@ -425,12 +512,18 @@ impl BuiltinType {
 
                 _ => false,
             },
-            BuiltinType::Trait(_) => unimplemented!(),
+            // trait objects hold instances of classes implementing the trait
+            BuiltinType::Trait(trait_id) => {
+                *self == other
+                    || (other.is_nil() && !vm.args.flag_null_safety)
+                    || class_implements_trait(vm, other, trait_id)
+            }
             BuiltinType::Module(_) => *self == other,
             BuiltinType::Enum(_, _) => *self == other,
 
             BuiltinType::ClassTypeParam(_, _) => *self == other,
             BuiltinType::FctTypeParam(_, _) => *self == other,
+            BuiltinType::TraitTypeParam(_, _) => *self == other,
 
             BuiltinType::Lambda(_) => {
                 // for now expect the exact same params and return types
@@ -461,11 +554,17 @@ impl BuiltinType {
             BuiltinType::Int64 => 8,
             BuiltinType::Float => 4,
             BuiltinType::Double => 8,
-            BuiltinType::Enum(_, _) => 4,
+            BuiltinType::Enum(enum_id, list_id) => {
+                let enum_def = enum_def(vm, enum_id, list_id);
+                let enum_def = enum_def.read();
+
+                enum_def.size
+            }
             BuiltinType::Nil => panic!("no size for nil."),
             BuiltinType::This => panic!("no size for Self."),
             BuiltinType::Any => panic!("no size for Any."),
             BuiltinType::Class(_, _)
+            | BuiltinType::Nullable(_, _)
             | BuiltinType::Module(_)
             | BuiltinType::Lambda(_)
             | BuiltinType::Ptr => mem::ptr_width(),
@@ -478,9 +577,9 @@ impl BuiltinType {
                 struc.size
             }
             BuiltinType::Trait(_) => mem::ptr_width(),
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => {
-                panic!("no size for type variable.")
-            }
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => panic!("no size for type variable."),
             BuiltinType::Tuple(tuple_id) => vm.tuples.lock().get_tuple(tuple_id).size(),
         }
     }
@@ -500,8 +599,14 @@ impl BuiltinType {
             BuiltinType::Nil => panic!("no alignment for nil."),
             BuiltinType::This => panic!("no alignment for Self."),
             BuiltinType::Any => panic!("no alignment for Any."),
-            BuiltinType::Enum(_, _) => 4,
+            BuiltinType::Enum(enum_id, list_id) => {
+                let enum_def = enum_def(vm, enum_id, list_id);
+                let enum_def = enum_def.read();
+
+                enum_def.align
+            }
             BuiltinType::Class(_, _)
+            | BuiltinType::Nullable(_, _)
             | BuiltinType::Module(_)
             | BuiltinType::Lambda(_)
             | BuiltinType::Ptr => mem::ptr_width(),
@@ -514,14 +619,14 @@ impl BuiltinType {
                 struc.align
             }
             BuiltinType::Trait(_) => mem::ptr_width(),
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => {
-                panic!("no alignment for type variable.")
-            }
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => panic!("no alignment for type variable."),
             BuiltinType::Tuple(tuple_id) => vm.tuples.lock().get_tuple(tuple_id).align(),
         }
     }
 
-    pub fn mode(&self) -> MachineMode {
+    pub fn mode(&self, vm: &VM) -> MachineMode {
         match *self {
             BuiltinType::Error => panic!("no machine mode for error."),
             BuiltinType::Unit => panic!("no machine mode for ()."),
@@ -533,19 +638,28 @@ impl BuiltinType {
             BuiltinType::Int64 => MachineMode::Int64,
             BuiltinType::Float => MachineMode::Float32,
             BuiltinType::Double => MachineMode::Float64,
-            BuiltinType::Enum(_, _) => MachineMode::Int32,
+            BuiltinType::Enum(enum_id, list_id) => {
+                let enum_def = enum_def(vm, enum_id, list_id);
+                let enum_def = enum_def.read();
+
+                match enum_def.layout {
+                    EnumLayout::Int => MachineMode::Int32,
+                    EnumLayout::Ptr | EnumLayout::Tagged => MachineMode::Ptr,
+                }
+            }
             BuiltinType::Nil => panic!("no machine mode for nil."),
             BuiltinType::This => panic!("no machine mode for Self."),
             BuiltinType::Any => panic!("no machine mode for Any."),
             BuiltinType::Class(_, _)
+            | BuiltinType::Nullable(_, _)
             | BuiltinType::Module(_)
             | BuiltinType::Lambda(_)
             | BuiltinType::Ptr => MachineMode::Ptr,
             BuiltinType::Struct(_, _) => panic!("no machine mode for struct."),
             BuiltinType::Trait(_) => MachineMode::Ptr,
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => {
-                panic!("no machine mode for type variable.")
-            }
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => panic!("no machine mode for type variable."),
             BuiltinType::Tuple(_) => unimplemented!(),
         }
     }
@@ -571,8 +685,11 @@ impl BuiltinType {
             | BuiltinType::Trait(_)
             | BuiltinType::Lambda(_)
             | BuiltinType::ClassTypeParam(_, _)
-            | BuiltinType::FctTypeParam(_, _) => true,
-            BuiltinType::Class(_, list_id) | BuiltinType::Struct(_, list_id) => {
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => true,
+            BuiltinType::Class(_, list_id)
+            | BuiltinType::Nullable(_, list_id)
+            | BuiltinType::Struct(_, list_id) => {
                 let params = vm.lists.lock().get(list_id);
 
                 for param in params.iter() {
@@ -614,7 +731,7 @@ impl BuiltinType {
             | BuiltinType::Ptr
             | BuiltinType::Trait(_)
             | BuiltinType::Nil => true,
-            BuiltinType::Class(_, list_id) => {
+            BuiltinType::Class(_, list_id) | BuiltinType::Nullable(_, list_id) => {
                 let params = vm.lists.lock().get(list_id);
 
                 for param in params.iter() {
@@ -626,8 +743,53 @@ impl BuiltinType {
                 true
             }
             BuiltinType::Tuple(tuple_id) => vm.tuples.lock().get_tuple(tuple_id).is_concrete_type(),
-            BuiltinType::Lambda(_) | BuiltinType::Struct(_, _) => unimplemented!(),
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => false,
+            BuiltinType::Lambda(id) => {
+                let lambda = vm.lambda_types.lock().get(id);
+                lambda.params.iter().all(|t| t.is_concrete_type(vm))
+                    && lambda.ret.is_concrete_type(vm)
+            }
+            BuiltinType::Struct(_, list_id) => {
+                let params = vm.lists.lock().get(list_id);
+
+                for param in params.iter() {
+                    if !param.is_concrete_type(vm) {
+                        return false;
+                    }
+                }
+
+                true
+            }
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => false,
+        }
+    }
+}
+
+fn enum_def(vm: &VM, enum_id: EnumId, list_id: TypeListId) -> Arc<RwLock<EnumDef>> {
+    let params = vm.lists.lock().get(list_id);
+    let enum_def_id = semck::specialize::specialize_enum_id_params(vm, enum_id, params);
+    vm.enum_defs.idx(enum_def_id)
+}
+
+// subclasses inherit the traits implemented by their super classes
+fn class_implements_trait(vm: &VM, ty: BuiltinType, trait_id: TraitId) -> bool {
+    let mut cls_id = match ty {
+        BuiltinType::Class(cls_id, _) => cls_id,
+        _ => return false,
+    };
+
+    loop {
+        let cls = vm.classes.idx(cls_id);
+        let cls = cls.read();
+
+        if cls.traits.contains(&trait_id) {
+            return true;
+        }
+
+        match cls.parent_class {
+            Some(parent_class) => cls_id = parent_class.cls_id(vm).expect("no class"),
+            None => return false,
         }
     }
 }
@@ -853,6 +1015,16 @@ pub struct LambdaType {
     ret: BuiltinType,
 }
 
+impl LambdaType {
+    pub fn params(&self) -> &[BuiltinType] {
+        &self.params
+    }
+
+    pub fn ret(&self) -> BuiltinType {
+        self.ret
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum TypeParamId {
     Fct(TypeListId),
@@ -863,6 +1035,7 @@ pub enum TypeParamId {
 mod tests {
     use super::*;
     use crate::mem;
+    use crate::test;
 
     #[test]
     fn mode_size() {
@@ -873,20 +1046,26 @@ mod tests {
 
     #[test]
     fn mode_for_types() {
-        assert_eq!(MachineMode::Int8, BuiltinType::Bool.mode());
-        assert_eq!(MachineMode::Int32, BuiltinType::Int.mode());
-        assert_eq!(MachineMode::Ptr, BuiltinType::Ptr.mode());
+        test::parse("", |vm| {
+            assert_eq!(MachineMode::Int8, BuiltinType::Bool.mode(vm));
+            assert_eq!(MachineMode::Int32, BuiltinType::Int.mode(vm));
+            assert_eq!(MachineMode::Ptr, BuiltinType::Ptr.mode(vm));
+        });
     }
 
     #[test]
     #[should_panic]
     fn mode_for_nil() {
-        assert_eq!(MachineMode::Ptr, BuiltinType::Nil.mode());
+        test::parse("", |vm| {
+            assert_eq!(MachineMode::Ptr, BuiltinType::Nil.mode(vm));
+        });
     }
 
     #[test]
     #[should_panic]
     fn mode_for_unit() {
-        assert_eq!(MachineMode::Ptr, BuiltinType::Unit.mode());
+        test::parse("", |vm| {
+            assert_eq!(MachineMode::Ptr, BuiltinType::Unit.mode(vm));
+        });
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::hash_map::HashMap;
 use std::collections::hash_set::HashSet;
 
 use crate::typeck::constck::ConstCheck;
@@ -16,7 +17,7 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
     for fct in vm.fcts.iter() {
         let fct = fct.read();
 
-        if !fct.is_src() {
+        if !vm.needs_body_check(&fct) {
             continue;
         }
 
@@ -31,9 +32,10 @@ pub fn check<'a, 'ast>(vm: &VM<'ast>) {
             src: &mut src,
             ast,
             used_in_call: HashSet::new(),
+            smart_casts: HashMap::new(),
         };
 
-        typeck.check();
+        vm.with_file_scope(fct.file, || typeck.check());
     }
 
     for xconst in vm.consts.iter() {
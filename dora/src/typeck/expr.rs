@@ -1,18 +1,23 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
+use std::iter::once;
 use std::sync::Arc;
 use std::{f32, f64};
 
 use crate::error::msg::SemError;
+use crate::error::suggest::did_you_mean;
 use crate::semck::specialize::replace_type_param;
 use crate::semck::typeparamck;
 use crate::semck::{always_returns, expr_always_returns};
 use crate::sym::TypeSym::SymClass;
 use crate::ty::{BuiltinType, TypeList, TypeParamId};
-use crate::typeck::lookup::MethodLookup;
+use crate::typeck::lookup::{method_names, MethodLookup};
+use crate::vm::module::{field_names_in_module, find_field_in_module, find_methods_in_module};
 use crate::vm::{
-    self, ensure_tuple, find_field_in_class, find_methods_in_class, CallType, ClassId, ConvInfo,
-    Fct, FctId, FctParent, FctSrc, FileId, ForTypeInfo, IdentType, Intrinsic, VM,
+    self, ensure_tuple, field_names_in_class, field_names_in_struct, find_field_in_class,
+    find_field_in_struct, find_methods_in_class, find_trait_impl_in_class, CallType, ClassId,
+    ConvInfo, EnumId, Fct, FctId, FctParent, FctSrc, FieldId, FileId, ForTypeInfo, IdentType,
+    Intrinsic, VarId, VM,
 };
 
 use dora_parser::ast::visit::Visitor;
@@ -20,7 +25,7 @@ use dora_parser::ast::Expr::*;
 use dora_parser::ast::Stmt::*;
 use dora_parser::ast::*;
 use dora_parser::interner::Name;
-use dora_parser::lexer::position::Position;
+use dora_parser::lexer::position::{Position, Span};
 use dora_parser::lexer::token::{FloatSuffix, IntBase, IntSuffix};
 
 pub struct TypeCheck<'a, 'ast: 'a> {
@@ -30,10 +35,18 @@ pub struct TypeCheck<'a, 'ast: 'a> {
     pub src: &'a mut FctSrc,
     pub ast: &'ast Function,
     pub used_in_call: HashSet<NodeId>,
+
+    // narrowed types of immutable variables in the region guarded by the
+    // enclosing `is` checks and nil comparisons
+    pub smart_casts: HashMap<VarId, BuiltinType>,
 }
 
 impl<'a, 'ast> TypeCheck<'a, 'ast> {
     pub fn check(&mut self) {
+        if self.fct.is_lambda() {
+            self.init_captured_vars();
+        }
+
         let block = self.ast.block.as_ref().expect("missing block");
         let mut returns = false;
 
@@ -57,7 +70,20 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         };
 
         if !returns {
-            self.check_fct_return_type(block.pos, return_type);
+            self.check_fct_return_type(block.pos, block.span, return_type);
+        }
+    }
+
+    // enclosing functions are always checked before their lambdas,
+    // so types of all captured variables are already known
+    fn init_captured_vars(&mut self) {
+        let outer = self.vm.fcts.idx(self.fct.parent.fct_id());
+        let outer = outer.read();
+        let outer_src = outer.src();
+        let outer_src = outer_src.read();
+
+        for capture in &self.src.captures {
+            self.src.vars[capture.inner].ty = outer_src.vars[capture.outer].ty;
         }
     }
 
@@ -105,7 +131,10 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 let defined_type = defined_type.name(self.vm);
                 let expr_type = expr_type.name(self.vm);
                 let msg = SemError::AssignType(name, defined_type, expr_type);
-                self.vm.diag.lock().report(self.file, s.pos, msg);
+                self.vm
+                    .diag
+                    .lock()
+                    .report_span(self.file, s.pos, s.span, msg);
             }
 
         // let variable binding needs to be assigned
@@ -120,6 +149,15 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
     fn check_stmt_for(&mut self, s: &'ast StmtForType) {
         let object_type = self.check_expr(&s.expr, BuiltinType::Any);
 
+        if object_type.is_nullable() {
+            self.report_nullable_receiver(s.expr.pos(), object_type);
+
+            let var_id = *self.src.map_vars.get(s.id).unwrap();
+            self.src.vars[var_id].ty = BuiltinType::Error;
+            self.visit_stmt(&s.block);
+            return;
+        }
+
         if let Some(cls_id) = object_type.cls_id(self.vm) {
             if cls_id == self.vm.vips.array_class {
                 let var_id = *self.src.map_vars.get(s.id).unwrap();
@@ -140,29 +178,38 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
         if lookup.find() {
             let make_iterator_id = lookup.found_fct_id().unwrap();
+            let make_iterator_type = lookup.found_class_type().unwrap();
             let make_iterator_ret = lookup.found_ret().unwrap();
             let iterator_trait_id = self.vm.vips.iterator();
 
-            if make_iterator_ret.implements_trait(self.vm, iterator_trait_id) {
+            if let Some((iterator_type, impl_id)) =
+                find_trait_impl_in_class(self.vm, make_iterator_ret, iterator_trait_id)
+            {
                 // find fct next() & hasNext() in iterator-trait
                 let has_next_name = self.vm.interner.intern("hasNext");
                 let next_name = self.vm.interner.intern("next");
                 let trai = self.vm.traits[iterator_trait_id].read();
                 let next_id = trai
-                    .find_method_with_replace(self.vm, false, next_name, None, &[])
+                    .find_method_with_replace(
+                        self.vm,
+                        false,
+                        next_name,
+                        None,
+                        &TypeList::empty(),
+                        &[],
+                    )
                     .expect("next() not found");
                 let has_next_id = trai
-                    .find_method_with_replace(self.vm, false, has_next_name, None, &[])
+                    .find_method_with_replace(
+                        self.vm,
+                        false,
+                        has_next_name,
+                        None,
+                        &TypeList::empty(),
+                        &[],
+                    )
                     .expect("hasNext() not found");
 
-                // find impl for ret that implements Iterator
-                let cls_id = make_iterator_ret.cls_id(self.vm).unwrap();
-                let cls = self.vm.classes.idx(cls_id);
-                let cls = cls.read();
-                let impl_id = cls
-                    .find_impl_for_trait(self.vm, iterator_trait_id)
-                    .expect("impl not found for Iterator");
-
                 // find method in impl that implements next()
                 let ximpl = self.vm.impls[impl_id].read();
                 let impl_next_id = ximpl
@@ -174,23 +221,28 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     .find_implements(self.vm, has_next_id)
                     .expect("hasNext() impl not found");
 
-                // get return type of next() in impl
-                let fct = self.vm.fcts.idx(impl_next_id);
-                let fct = fct.read();
-                let ret = fct.return_type;
+                // element type is the type argument of Iterator in impl
+                let element_type = replace_type_param(
+                    self.vm,
+                    ximpl.trait_type_params[0],
+                    &iterator_type.type_params(self.vm),
+                    &TypeList::empty(),
+                    None,
+                );
 
-                // set variable type to return type of next
+                // set variable type to element type of iterator
                 let var_id = *self.src.map_vars.get(s.id).unwrap();
-                self.src.vars[var_id].ty = ret;
+                self.src.vars[var_id].ty = element_type;
 
                 // store fct ids for `for-in` loop
                 self.src.map_fors.insert(
                     s.id,
                     ForTypeInfo {
                         make_iterator: make_iterator_id,
+                        make_iterator_type,
                         has_next: impl_has_next_id,
                         next: impl_next_id,
-                        iterator_type: make_iterator_ret,
+                        iterator_type,
                     },
                 );
             } else {
@@ -220,20 +272,64 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.vm.diag.lock().report(self.file, s.pos, msg);
         }
 
-        self.visit_stmt(&s.block);
+        self.with_smart_casts(&s.cond, true, |ck| ck.visit_stmt(&s.block));
     }
 
     fn check_stmt_return(&mut self, s: &'ast StmtReturnType) {
         let expr_type = s
             .expr
             .as_ref()
-            .map(|expr| self.check_expr(&expr, BuiltinType::Any))
+            .map(|expr| {
+                let expected_ty = self.expected_call_type(expr, self.fct.return_type);
+                self.check_expr(&expr, expected_ty)
+            })
             .unwrap_or(BuiltinType::Unit);
 
-        self.check_fct_return_type(s.pos, expr_type);
+        self.check_fct_return_type(s.pos, s.span, expr_type);
+    }
+
+    fn check_stmt_throw(&mut self, s: &'ast StmtThrowType) {
+        let expr_type = self.check_expr(&s.expr, BuiltinType::Any);
+        self.check_exception_type(s.pos, expr_type);
+    }
+
+    fn check_stmt_try(&mut self, s: &'ast StmtTryType) {
+        self.visit_stmt(&s.try_block);
+
+        for catch in &s.catch_blocks {
+            let catch_type = self.src.ty(catch.data_type.id());
+            self.check_exception_type(catch.data_type.pos(), catch_type);
+
+            let var = *self.src.map_vars.get(catch.id).unwrap();
+            self.src.vars[var].ty = catch_type;
+
+            self.visit_stmt(&catch.block);
+        }
+
+        if let Some(ref finally_block) = s.finally_block {
+            self.visit_stmt(&finally_block.block);
+        }
     }
 
-    fn check_fct_return_type(&mut self, pos: Position, expr_type: BuiltinType) {
+    fn check_exception_type(&mut self, pos: Position, ty: BuiltinType) {
+        if ty.is_error() {
+            return;
+        }
+
+        let is_exception = ty.is_cls() && {
+            let cls = self.vm.classes.idx(ty.cls_id(self.vm).unwrap());
+            let cls = cls.read();
+            cls.subclass_from(self.vm, self.vm.vips.stacktrace_class)
+        };
+
+        if !is_exception {
+            let ty = ty.name(self.vm);
+            let msg = SemError::ExceptionTypeExpected(ty);
+            self.vm.diag.lock().report(self.file, pos, msg);
+        }
+    }
+
+    fn check_fct_return_type(&mut self, pos: Position, span: Span, expr_type: BuiltinType) {
         let fct_type = self.fct.return_type;
 
         if !expr_type.is_error() && !fct_type.allows(self.vm, expr_type) {
@@ -248,7 +344,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 SemError::ReturnType(fct_type, expr_type)
             };
 
-            self.vm.diag.lock().report(self.file, pos, msg);
+            self.vm.diag.lock().report_span(self.file, pos, span, msg);
         }
     }
 
@@ -297,6 +393,88 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         ty
     }
 
+    fn check_expr_lit_struct(
+        &mut self,
+        e: &'ast ExprLitStructType,
+        _expected_ty: BuiltinType,
+    ) -> BuiltinType {
+        let struct_id = match self.src.map_idents.get(e.id) {
+            Some(&IdentType::Struct(struct_id)) => struct_id,
+
+            // unknown struct was already reported
+            _ => {
+                for arg in &e.args {
+                    self.check_expr(&arg.value, BuiltinType::Any);
+                }
+
+                self.src.set_ty(e.id, BuiltinType::Error);
+                return BuiltinType::Error;
+            }
+        };
+
+        let list_id = self.vm.lists.lock().insert(TypeList::empty());
+        let ty = BuiltinType::Struct(struct_id, list_id);
+        let struct_name = ty.name(self.vm);
+
+        let fields: Vec<(Name, BuiltinType)> = {
+            let struc = self.vm.structs.idx(struct_id);
+            let struc = struc.lock();
+
+            struc.fields.iter().map(|f| (f.name, f.ty)).collect()
+        };
+
+        let mut initialized = vec![false; fields.len()];
+
+        for arg in &e.args {
+            let arg_type = self.check_expr(&arg.value, BuiltinType::Any);
+            let field_name = self.vm.interner.str(arg.name).to_string();
+
+            let idx = match fields.iter().position(|&(name, _)| name == arg.name) {
+                Some(idx) => idx,
+
+                None => {
+                    let names: Vec<Name> = fields.iter().map(|&(name, _)| name).collect();
+                    let notes = did_you_mean(self.vm, arg.name, names);
+                    let msg = SemError::UnknownStructField(struct_name.clone(), field_name);
+                    self.vm
+                        .diag
+                        .lock()
+                        .report_with_notes(self.file, arg.pos, msg, notes);
+                    continue;
+                }
+            };
+
+            if initialized[idx] {
+                let msg = SemError::StructFieldInitializedTwice(struct_name.clone(), field_name);
+                self.vm.diag.lock().report(self.file, arg.pos, msg);
+                continue;
+            }
+
+            initialized[idx] = true;
+            let field_type = fields[idx].1;
+
+            if !field_type.allows(self.vm, arg_type) && !arg_type.is_error() {
+                let field_type = field_type.name(self.vm);
+                let arg_type = arg_type.name(self.vm);
+                let msg =
+                    SemError::AssignField(field_name, struct_name.clone(), field_type, arg_type);
+                self.vm.diag.lock().report(self.file, arg.pos, msg);
+            }
+        }
+
+        for (&(name, _), &initialized) in fields.iter().zip(&initialized) {
+            if !initialized {
+                let field_name = self.vm.interner.str(name).to_string();
+                let msg = SemError::StructFieldNotInitialized(struct_name.clone(), field_name);
+                self.vm.diag.lock().report(self.file, e.pos, msg);
+            }
+        }
+
+        self.src.set_ty(e.id, ty);
+
+        ty
+    }
+
     fn check_expr_if(&mut self, expr: &'ast ExprIfType, _expected_ty: BuiltinType) -> BuiltinType {
         let expr_type = self.check_expr(&expr.cond, BuiltinType::Any);
 
@@ -306,10 +484,14 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.vm.diag.lock().report(self.file, expr.pos, msg);
         }
 
-        let then_type = self.check_expr(&expr.then_block, BuiltinType::Any);
+        let then_type = self.with_smart_casts(&expr.cond, true, |ck| {
+            ck.check_expr(&expr.then_block, BuiltinType::Any)
+        });
 
         let merged_type = if let Some(ref else_block) = expr.else_block {
-            let else_type = self.check_expr(else_block, BuiltinType::Any);
+            let else_type = self.with_smart_casts(&expr.cond, false, |ck| {
+                ck.check_expr(else_block, BuiltinType::Any)
+            });
 
             if expr_always_returns(&expr.then_block) {
                 else_type
@@ -319,6 +501,13 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 else_type
             } else if else_type.is_error() {
                 then_type
+            } else if self.vm.args.flag_null_safety
+                && then_type.is_cls()
+                && (else_type.is_nil() || else_type.is_nullable())
+                && then_type.nullable().allows(self.vm, else_type)
+            {
+                // e.g. `if c { Foo() } else { nil }` is of type `Foo?`
+                then_type.nullable()
             } else if !then_type.allows(self.vm, else_type) {
                 let then_type_name = then_type.name(self.vm);
                 let else_type_name = else_type.name(self.vm);
@@ -337,6 +526,218 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         merged_type
     }
 
+    fn check_expr_match(
+        &mut self,
+        expr: &'ast ExprMatchType,
+        _expected_ty: BuiltinType,
+    ) -> BuiltinType {
+        let expr_type = self.check_expr(&expr.expr, BuiltinType::Any);
+
+        let supported = match expr_type {
+            BuiltinType::Error
+            | BuiltinType::Enum(_, _)
+            | BuiltinType::Bool
+            | BuiltinType::Char
+            | BuiltinType::UInt8
+            | BuiltinType::Int
+            | BuiltinType::Int64 => true,
+            _ => false,
+        };
+
+        if !supported {
+            let expr_type = expr_type.name(self.vm);
+            let msg = SemError::MatchTypeUnsupported(expr_type);
+            self.vm.diag.lock().report(self.file, expr.expr.pos(), msg);
+        }
+
+        let mut merged_type: Option<BuiltinType> = None;
+        let mut has_underscore = false;
+        let mut used_values = HashSet::new();
+
+        for case in &expr.cases {
+            match case.pattern {
+                MatchPattern::Underscore(_) => {
+                    has_underscore = true;
+                }
+
+                MatchPattern::Literal(ref lit) => {
+                    let lit_type = self.check_expr(lit, BuiltinType::Any);
+
+                    let compatible = expr_type.is_error()
+                        || lit_type.is_error()
+                        || (!expr_type.is_enum() && expr_type.allows(self.vm, lit_type));
+
+                    if !compatible {
+                        let expr_type = expr_type.name(self.vm);
+                        let lit_type = lit_type.name(self.vm);
+                        let msg = SemError::MatchPatternTypeMismatch(expr_type, lit_type);
+                        self.vm.diag.lock().report(self.file, lit.pos(), msg);
+                    } else if let Some(value) = lit_value(lit) {
+                        used_values.insert(value);
+                    }
+                }
+
+                MatchPattern::Variant(ref pattern) => {
+                    if let Some(value) = self.check_match_pattern_variant(pattern, expr_type) {
+                        used_values.insert(value as i64);
+                    }
+                }
+            }
+
+            let case_type = self.check_expr(&case.value, BuiltinType::Any);
+
+            if expr_always_returns(&case.value) || case_type.is_error() {
+                continue;
+            }
+
+            if let Some(merged) = merged_type {
+                if !merged.allows(self.vm, case_type) {
+                    let merged = merged.name(self.vm);
+                    let case_type = case_type.name(self.vm);
+                    let msg = SemError::MatchBranchTypesIncompatible(merged, case_type);
+                    self.vm.diag.lock().report(self.file, case.value.pos(), msg);
+                }
+            } else {
+                merged_type = Some(case_type);
+            }
+        }
+
+        if supported && !has_underscore && !expr_type.is_error() {
+            let missing = if let Some(enum_id) = expr_type.enum_id() {
+                let xenum = self.vm.enums[enum_id].read();
+
+                xenum
+                    .variants
+                    .iter()
+                    .enumerate()
+                    .filter(|&(value, _)| !used_values.contains(&(value as i64)))
+                    .map(|(_, variant)| self.vm.interner.str(variant.name).to_string())
+                    .collect::<Vec<_>>()
+            } else if expr_type.is_bool() && used_values.len() == 2 {
+                Vec::new()
+            } else {
+                vec!["_".into()]
+            };
+
+            if !missing.is_empty() {
+                let msg = SemError::MatchNotExhaustive(missing);
+                self.vm.diag.lock().report(self.file, expr.pos, msg);
+            }
+        }
+
+        let ty = merged_type.unwrap_or(BuiltinType::Unit);
+        self.src.set_ty(expr.id, ty);
+
+        ty
+    }
+
+    fn check_match_pattern_variant(
+        &mut self,
+        pattern: &'ast MatchPatternVariantType,
+        expr_type: BuiltinType,
+    ) -> Option<u32> {
+        let value = self.read_match_pattern_variant(pattern, expr_type);
+
+        let types = value.and_then(|value| {
+            let enum_id = expr_type.enum_id().unwrap();
+            let xenum = self.vm.enums[enum_id].read();
+            let variant = &xenum.variants[value as usize];
+            let params = pattern.params.as_ref().map(|p| p.len()).unwrap_or(0);
+
+            if variant.types.len() == params {
+                Some(variant.types.clone())
+            } else {
+                let name = self.vm.interner.str(variant.name).to_string();
+                let msg =
+                    SemError::MatchPatternWrongNumberOfParams(name, variant.types.len(), params);
+                self.vm.diag.lock().report(self.file, pattern.pos, msg);
+                None
+            }
+        });
+
+        if let Some(ref params) = pattern.params {
+            for (idx, param) in params.iter().enumerate() {
+                if param.name.is_none() {
+                    continue;
+                }
+
+                let ty = types
+                    .as_ref()
+                    .map(|types| types[idx])
+                    .unwrap_or(BuiltinType::Error);
+
+                let var = *self.src.map_vars.get(param.id).unwrap();
+                self.src.vars[var].ty = ty;
+            }
+        }
+
+        value
+    }
+
+    fn read_match_pattern_variant(
+        &mut self,
+        pattern: &'ast MatchPatternVariantType,
+        expr_type: BuiltinType,
+    ) -> Option<u32> {
+        if expr_type.is_error() {
+            return None;
+        }
+
+        let enum_id = if let Some(enum_id) = expr_type.enum_id() {
+            enum_id
+        } else {
+            let expr_type = expr_type.name(self.vm);
+            let name = self.vm.interner.str(pattern.path[0]).to_string();
+            let msg = SemError::MatchPatternTypeMismatch(expr_type, name);
+            self.vm.diag.lock().report(self.file, pattern.pos, msg);
+            return None;
+        };
+
+        let xenum = self.vm.enums[enum_id].read();
+
+        if pattern.path.len() > 1 {
+            match self.src.map_idents.get(pattern.id) {
+                Some(&IdentType::Enum(id)) if id == enum_id => {}
+
+                Some(&IdentType::Enum(id)) => {
+                    let expr_type = expr_type.name(self.vm);
+                    let name = self.vm.interner.str(self.vm.enums[id].read().name);
+                    let msg = SemError::MatchPatternTypeMismatch(expr_type, name.to_string());
+                    self.vm.diag.lock().report(self.file, pattern.pos, msg);
+                    return None;
+                }
+
+                _ => return None,
+            }
+        }
+
+        let name = *pattern.path.last().unwrap();
+
+        if pattern.path.len() > 2 {
+            let name = self.vm.interner.str(name).to_string();
+            let msg = SemError::UnknownEnumValue(name);
+            self.vm.diag.lock().report(self.file, pattern.pos, msg);
+            return None;
+        }
+
+        if let Some(&value) = xenum.name_to_value.get(&name) {
+            self.src
+                .map_idents
+                .insert_or_replace(pattern.id, IdentType::EnumValue(enum_id, value));
+            Some(value)
+        } else {
+            let candidates = xenum.name_to_value.keys().cloned();
+            let notes = did_you_mean(self.vm, name, candidates);
+            let name = self.vm.interner.str(name).to_string();
+            let msg = SemError::UnknownEnumValue(name);
+            self.vm
+                .diag
+                .lock()
+                .report_with_notes(self.file, pattern.pos, msg, notes);
+            None
+        }
+    }
+
     fn check_expr_ident(
         &mut self,
         e: &'ast ExprIdentType,
@@ -346,7 +747,15 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
         match ident_type {
             &IdentType::Var(varid) => {
-                let ty = self.src.vars[varid].ty;
+                let ty = match self.smart_casts.get(&varid) {
+                    Some(&ty) => {
+                        self.src.map_smart_casts.insert_or_replace(e.id, ty);
+                        ty
+                    }
+
+                    None => self.src.vars[varid].ty,
+                };
+
                 self.src.set_ty(e.id, ty);
 
                 ty
@@ -371,12 +780,12 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 field.ty
             }
 
-            &IdentType::Struct(sid) => {
-                let list_id = self.vm.lists.lock().insert(TypeList::empty());
-                let ty = BuiltinType::Struct(sid, list_id);
-                self.src.set_ty(e.id, ty);
+            &IdentType::Struct(_) => {
+                let msg = SemError::StructUsedAsIdentifier;
+                self.vm.diag.lock().report(self.file, e.pos, msg);
+                self.src.set_ty(e.id, BuiltinType::Error);
 
-                ty
+                BuiltinType::Error
             }
 
             &IdentType::Const(const_id) => {
@@ -443,7 +852,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 BuiltinType::Error
             }
 
-            &IdentType::EnumValue(_, _) => unreachable!(),
+            &IdentType::EnumValue(_, _) | &IdentType::StructField(_, _) => unreachable!(),
             &IdentType::FctType(_, _) | &IdentType::ClassType(_, _) => unreachable!(),
             &IdentType::TypeParamStaticMethod(_, _) => unreachable!(),
             &IdentType::Method(_, _) | &IdentType::MethodType(_, _, _) => unreachable!(),
@@ -456,19 +865,30 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
     fn check_expr_assign(&mut self, e: &'ast ExprBinType) {
         if e.lhs.is_call() {
             self.check_expr_assign_call(e);
-        } else if e.lhs.is_dot() {
+        } else if e.lhs.is_dot() || e.lhs.is_path() {
             self.check_expr_assign_field(e);
         } else if e.lhs.is_ident() {
             let lhs_type;
 
-            let rhs_type = self.check_expr(&e.rhs, BuiltinType::Any);
+            let lhs_ty = match self.src.map_idents.get(e.lhs.id()) {
+                Some(&IdentType::Var(varid)) => self.src.vars[varid].ty,
+                Some(&IdentType::Global(gid)) => self.vm.globals.idx(gid).read().ty,
+                _ => BuiltinType::Any,
+            };
+
+            let expected_ty = self.expected_call_type(&e.rhs, lhs_ty);
+            let rhs_type = self.check_expr(&e.rhs, expected_ty);
 
             self.src.set_ty(e.id, BuiltinType::Unit);
 
             if let Some(ident_type) = self.src.map_idents.get(e.lhs.id()) {
                 match ident_type {
                     &IdentType::Var(varid) => {
-                        if !self.src.vars[varid].reassignable {
+                        if self.src.is_captured(varid) {
+                            let name = self.vm.interner.str(self.src.vars[varid].name).to_string();
+                            let msg = SemError::CapturedVarReassigned(name);
+                            self.vm.diag.lock().report(self.file, e.pos, msg);
+                        } else if !self.src.vars[varid].reassignable {
                             self.vm
                                 .diag
                                 .lock()
@@ -492,14 +912,10 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                         lhs_type = glob.ty;
                     }
 
-                    &IdentType::Field(_, _) => {
+                    &IdentType::Field(_, _) | &IdentType::StructField(_, _) => {
                         unreachable!();
                     }
 
-                    &IdentType::Struct(_) => {
-                        unimplemented!();
-                    }
-
                     &IdentType::Const(_) => {
                         self.vm
                             .diag
@@ -538,7 +954,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                         return;
                     }
 
-                    &IdentType::Enum(_) | &IdentType::EnumValue(_, _) => {
+                    &IdentType::Enum(_) | &IdentType::EnumValue(_, _) | &IdentType::Struct(_) => {
                         self.vm.diag.lock().report(
                             self.file,
                             e.pos,
@@ -609,9 +1025,14 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
     }
 
     fn check_expr_assign_field(&mut self, e: &'ast ExprBinType) {
-        let field_expr = e.lhs.to_dot().unwrap();
+        // fields of modules can also be assigned through a path, e.g. `Foo::x = 1`
+        let (object, field, field_pos) = match *e.lhs {
+            ExprDot(ref dot) => (&dot.lhs, &dot.rhs, dot.pos),
+            ExprPath(ref path) => (&path.lhs, &path.rhs, path.pos),
+            _ => unreachable!(),
+        };
 
-        let name = match field_expr.rhs.to_ident() {
+        let name = match field.to_ident() {
             Some(ident) => ident.name,
 
             None => {
@@ -623,39 +1044,32 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             }
         };
 
-        let object_type = self.check_expr(&field_expr.lhs, BuiltinType::Any);
-        let rhs_type = self.check_expr(&e.rhs, BuiltinType::Any);
+        let object_type = self.check_expr(object, BuiltinType::Any);
 
-        if object_type.cls_id(self.vm).is_some() {
-            if let Some((cls_ty, field_id)) = find_field_in_class(self.vm, object_type, name) {
-                let ident_type = IdentType::Field(cls_ty, field_id);
-                self.src
-                    .map_idents
-                    .insert_or_replace(e.lhs.id(), ident_type);
+        let fty = match find_field(self.vm, object_type, name) {
+            Some((owner_ty, field_id)) if !object_type.is_nullable() => {
+                field_type(self.vm, owner_ty, field_id).0
+            }
+            _ => BuiltinType::Any,
+        };
 
-                let cls = self
-                    .vm
-                    .classes
-                    .idx(cls_ty.cls_id(self.vm).expect("no class"));
-                let cls = cls.read();
-                let field = &cls.fields[field_id];
+        let expected_ty = self.expected_call_type(&e.rhs, fty);
+        let rhs_type = self.check_expr(&e.rhs, expected_ty);
 
-                let class_type_params = cls_ty.type_params(self.vm);
+        if object_type.is_nullable() {
+            self.report_nullable_receiver(field_pos, object_type);
+            self.src.set_ty(e.id, BuiltinType::Error);
+            return;
+        }
 
-                let fty = replace_type_param(
-                    self.vm,
-                    field.ty,
-                    &class_type_params,
-                    &TypeList::empty(),
-                    None,
-                );
+        if let BuiltinType::Struct(struct_id, _) = object_type {
+            if let Some((field_id, fty)) = find_field_in_struct(self.vm, struct_id, name) {
+                let ident_type = IdentType::StructField(object_type, field_id);
+                self.src
+                    .map_idents
+                    .insert_or_replace(e.lhs.id(), ident_type);
 
-                if !e.initializer && !field.reassignable {
-                    self.vm
-                        .diag
-                        .lock()
-                        .report(self.file, e.pos, SemError::LetReassigned);
-                }
+                self.check_struct_place(object, e.pos);
 
                 if !fty.allows(self.vm, rhs_type) && !rhs_type.is_error() {
                     let name = self.vm.interner.str(name).to_string();
@@ -673,13 +1087,126 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             }
         }
 
+        if let Some((owner_ty, field_id)) = find_field(self.vm, object_type, name) {
+            let ident_type = IdentType::Field(owner_ty, field_id);
+            self.src
+                .map_idents
+                .insert_or_replace(e.lhs.id(), ident_type);
+
+            let (fty, reassignable) = field_type(self.vm, owner_ty, field_id);
+
+            if !e.initializer && !reassignable && !self.is_ctor_field(object, owner_ty) {
+                self.vm
+                    .diag
+                    .lock()
+                    .report(self.file, e.pos, SemError::LetReassigned);
+            }
+
+            if !fty.allows(self.vm, rhs_type) && !rhs_type.is_error() {
+                let name = self.vm.interner.str(name).to_string();
+
+                let object_type = object_type.name(self.vm);
+                let lhs_type = fty.name(self.vm);
+                let rhs_type = rhs_type.name(self.vm);
+
+                let msg = SemError::AssignField(name, object_type, lhs_type, rhs_type);
+                self.vm.diag.lock().report(self.file, e.pos, msg);
+            }
+
+            self.src.set_ty(e.id, BuiltinType::Unit);
+            return;
+        }
+
         // field not found, report error
+        let notes = did_you_mean(self.vm, name, field_names(self.vm, object_type));
         let field_name = self.vm.interner.str(name).to_string();
         let expr_name = object_type.name(self.vm);
         let msg = SemError::UnknownField(field_name, expr_name);
-        self.vm.diag.lock().report(self.file, field_expr.pos, msg);
+        self.vm
+            .diag
+            .lock()
+            .report_with_notes(self.file, field_pos, msg, notes);
+
+        self.src.set_ty(e.id, BuiltinType::Unit);
+    }
+
+    // the constructor may assign `let` fields of its own class through `self`,
+    // initck checks that every such field is assigned exactly once
+    fn is_ctor_field(&self, object: &'ast Expr, owner_ty: BuiltinType) -> bool {
+        self.fct.is_constructor
+            && self.fct.in_class()
+            && object.is_this()
+            && owner_ty.cls_id(self.vm) == Some(self.fct.cls_id())
+    }
+
+    // assigning a field of a struct value modifies the variable or field the
+    // struct is stored in, which therefore needs to be reassignable
+    fn check_struct_place(&mut self, e: &'ast Expr, pos: Position) {
+        let msg = match *e {
+            ExprIdent(ref ident) => match self.src.map_idents.get(ident.id) {
+                Some(&IdentType::Var(var_id)) => {
+                    if self.src.is_captured(var_id) {
+                        let name = self.vm.interner.str(self.src.vars[var_id].name);
+                        Some(SemError::CapturedVarReassigned(name.to_string()))
+                    } else if !self.src.vars[var_id].reassignable {
+                        Some(SemError::LetReassigned)
+                    } else {
+                        None
+                    }
+                }
+
+                Some(&IdentType::Global(global_id)) => {
+                    let glob = self.vm.globals.idx(global_id);
+
+                    if !glob.read().reassignable {
+                        Some(SemError::LetReassigned)
+                    } else {
+                        None
+                    }
+                }
+
+                _ => Some(SemError::LvalueExpected),
+            },
+
+            ExprDot(ref dot) => match self.src.map_idents.get(dot.id) {
+                Some(&IdentType::StructField(_, _)) => {
+                    self.check_struct_place(&dot.lhs, pos);
+                    None
+                }
+
+                Some(&IdentType::Field(owner_ty, field_id)) => {
+                    let (_, reassignable) = field_type(self.vm, owner_ty, field_id);
+
+                    if !reassignable {
+                        Some(SemError::LetReassigned)
+                    } else {
+                        None
+                    }
+                }
+
+                _ => Some(SemError::LvalueExpected),
+            },
+
+            ExprPath(ref path) => match self.src.map_idents.get(path.id) {
+                Some(&IdentType::Field(owner_ty, field_id)) => {
+                    let (_, reassignable) = field_type(self.vm, owner_ty, field_id);
+
+                    if !reassignable {
+                        Some(SemError::LetReassigned)
+                    } else {
+                        None
+                    }
+                }
+
+                _ => Some(SemError::LvalueExpected),
+            },
+
+            _ => Some(SemError::LvalueExpected),
+        };
 
-        self.src.set_ty(e.id, BuiltinType::Unit);
+        if let Some(msg) = msg {
+            self.vm.diag.lock().report(self.file, pos, msg);
+        }
     }
 
     fn find_method(
@@ -691,6 +1218,11 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         args: &[BuiltinType],
         fct_type_params: &TypeList,
     ) -> Option<(ClassId, FctId, BuiltinType)> {
+        if object_type.is_nullable() {
+            self.report_nullable_receiver(pos, object_type);
+            return None;
+        }
+
         let result = lookup_method(
             self.vm,
             object_type,
@@ -702,6 +1234,9 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         );
 
         if result.is_none() {
+            let candidates = method_names(self.vm, object_type, is_static);
+            let notes = did_you_mean(self.vm, name, candidates);
+
             let type_name = object_type.name(self.vm);
             let name = self.vm.interner.str(name).to_string();
             let param_names = args
@@ -714,12 +1249,32 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 SemError::UnknownMethod(type_name, name, param_names)
             };
 
-            self.vm.diag.lock().report(self.file, pos, msg);
+            self.vm
+                .diag
+                .lock()
+                .report_with_notes(self.file, pos, msg, notes);
         }
 
         result
     }
 
+    // the type of the assigned variable, field or return value is only used to
+    // infer type params of calls, literals keep their own type there
+    fn expected_call_type(&self, e: &'ast Expr, ty: BuiltinType) -> BuiltinType {
+        if e.is_call() {
+            ty
+        } else {
+            BuiltinType::Any
+        }
+    }
+
+    // with --null-safety fields and methods of a nullable value can only be
+    // used after a nil check
+    fn report_nullable_receiver(&mut self, pos: Position, object_type: BuiltinType) {
+        let msg = SemError::NullableReceiver(object_type.name(self.vm));
+        self.vm.diag.lock().report(self.file, pos, msg);
+    }
+
     fn check_expr_un(&mut self, e: &'ast ExprUnType, _expected_ty: BuiltinType) -> BuiltinType {
         if e.op == UnOp::Neg && e.opnd.is_lit_int() {
             let expr_type =
@@ -782,7 +1337,17 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
 
         let lhs_type = self.check_expr(&e.lhs, BuiltinType::Any);
-        let rhs_type = self.check_expr(&e.rhs, BuiltinType::Any);
+
+        // the right operand is only evaluated if the left one is true for `&&`
+        // and false for `||`
+        let rhs_type =
+            match e.op {
+                BinOp::And => self
+                    .with_smart_casts(&e.lhs, true, |ck| ck.check_expr(&e.rhs, BuiltinType::Any)),
+                BinOp::Or => self
+                    .with_smart_casts(&e.lhs, false, |ck| ck.check_expr(&e.rhs, BuiltinType::Any)),
+                _ => self.check_expr(&e.rhs, BuiltinType::Any),
+            };
 
         if lhs_type.is_error() || rhs_type.is_error() {
             self.src.set_ty(e.id, BuiltinType::Error);
@@ -811,6 +1376,107 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
     }
 
+    // checks `f` with the variables narrowed that are known to be non-nil or of
+    // a more specific type when `cond` evaluates to `value`
+    fn with_smart_casts<F, R>(&mut self, cond: &'ast Expr, value: bool, f: F) -> R
+    where
+        F: FnOnce(&mut TypeCheck<'a, 'ast>) -> R,
+    {
+        let mut casts = Vec::new();
+        self.find_smart_casts(cond, value, &mut casts);
+
+        if casts.is_empty() {
+            return f(self);
+        }
+
+        let saved = self.smart_casts.clone();
+        self.smart_casts.extend(casts);
+
+        let result = f(self);
+        self.smart_casts = saved;
+
+        result
+    }
+
+    fn find_smart_casts(
+        &self,
+        cond: &'ast Expr,
+        value: bool,
+        casts: &mut Vec<(VarId, BuiltinType)>,
+    ) {
+        match *cond {
+            ExprBin(ref e) if (e.op == BinOp::And && value) || (e.op == BinOp::Or && !value) => {
+                self.find_smart_casts(&e.lhs, value, casts);
+                self.find_smart_casts(&e.rhs, value, casts);
+            }
+
+            ExprUn(ref e) if e.op == UnOp::Not => {
+                self.find_smart_casts(&e.opnd, !value, casts);
+            }
+
+            ExprConv(ref e) if e.is && value => {
+                let var_id = match self.immutable_var(&e.object) {
+                    Some(var_id) => var_id,
+                    None => return,
+                };
+
+                // `x is T` for a supertype T of x only tells that x is not nil
+                let ty = match self.src.map_convs.get(e.id) {
+                    Some(conv) if !conv.valid => conv.check_type,
+                    Some(_) => self.var_type(var_id).non_nullable(),
+                    None => return,
+                };
+
+                casts.push((var_id, ty));
+            }
+
+            ExprBin(ref e)
+                if (e.op == BinOp::Cmp(CmpOp::IsNot) && value)
+                    || (e.op == BinOp::Cmp(CmpOp::Is) && !value) =>
+            {
+                let var_id = if e.rhs.is_nil() {
+                    self.immutable_var(&e.lhs)
+                } else if e.lhs.is_nil() {
+                    self.immutable_var(&e.rhs)
+                } else {
+                    None
+                };
+
+                if let Some(var_id) = var_id {
+                    casts.push((var_id, self.var_type(var_id).non_nullable()));
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    // only variables that cannot be reassigned keep the narrowed type
+    fn immutable_var(&self, e: &'ast Expr) -> Option<VarId> {
+        let ident = e.to_ident()?;
+
+        match self.src.map_idents.get(ident.id) {
+            Some(&IdentType::Var(var_id)) if !self.src.vars[var_id].reassignable => {
+                let ty = self.src.vars[var_id].ty;
+
+                if ty.reference_type(self.vm) {
+                    Some(var_id)
+                } else {
+                    None
+                }
+            }
+
+            _ => None,
+        }
+    }
+
+    fn var_type(&self, var_id: VarId) -> BuiltinType {
+        match self.smart_casts.get(&var_id) {
+            Some(&ty) => ty,
+            None => self.src.vars[var_id].ty,
+        }
+    }
+
     fn check_expr_bin_bool(
         &mut self,
         e: &'ast ExprBinType,
@@ -915,7 +1581,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         lhs_type: BuiltinType,
         rhs_type: BuiltinType,
     ) {
-        if lhs_type.allows(self.vm, rhs_type) {
+        if lhs_type.allows(self.vm, rhs_type) && !lhs_type.reference_type(self.vm) {
             let intrinsic = match op {
                 CmpOp::Eq => Intrinsic::EnumEq,
                 CmpOp::Ne => Intrinsic::EnumNe,
@@ -956,7 +1622,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
     }
 
-    fn check_expr_call(&mut self, e: &'ast ExprCallType, _expected_ty: BuiltinType) -> BuiltinType {
+    fn check_expr_call(&mut self, e: &'ast ExprCallType, expected_ty: BuiltinType) -> BuiltinType {
         self.used_in_call.insert(e.callee.id());
 
         let expr_type = self.check_expr(&e.callee, BuiltinType::Any);
@@ -970,19 +1636,19 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
         match ident_type {
             Some(IdentType::Fct(fct_id)) => {
-                self.check_expr_call_ident(e, fct_id, TypeList::empty(), &arg_types)
+                self.check_expr_call_ident(e, fct_id, TypeList::empty(), &arg_types, expected_ty)
             }
 
             Some(IdentType::FctType(fct_id, type_params)) => {
-                self.check_expr_call_ident(e, fct_id, type_params, &arg_types)
+                self.check_expr_call_ident(e, fct_id, type_params, &arg_types, expected_ty)
             }
 
             Some(IdentType::Class(cls_id)) => {
-                self.check_expr_call_ctor(e, cls_id, TypeList::empty(), &arg_types)
+                self.check_expr_call_ctor(e, cls_id, TypeList::empty(), &arg_types, expected_ty)
             }
 
             Some(IdentType::ClassType(cls_id, type_params)) => {
-                self.check_expr_call_ctor(e, cls_id, type_params, &arg_types)
+                self.check_expr_call_ctor(e, cls_id, type_params, &arg_types, expected_ty)
             }
 
             Some(IdentType::Method(object_type, method_name)) => self.check_expr_call_method(
@@ -991,11 +1657,18 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 method_name,
                 TypeList::empty(),
                 &arg_types,
+                expected_ty,
             ),
 
-            Some(IdentType::MethodType(object_type, method_name, type_params)) => {
-                self.check_expr_call_method(e, object_type, method_name, type_params, &arg_types)
-            }
+            Some(IdentType::MethodType(object_type, method_name, type_params)) => self
+                .check_expr_call_method(
+                    e,
+                    object_type,
+                    method_name,
+                    type_params,
+                    &arg_types,
+                    expected_ty,
+                ),
 
             Some(IdentType::StaticMethod(object_type, method_name)) => self
                 .check_expr_call_static_method(
@@ -1004,6 +1677,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     method_name,
                     TypeList::empty(),
                     &arg_types,
+                    expected_ty,
                 ),
 
             Some(IdentType::StaticMethodType(object_type, method_name, type_params)) => self
@@ -1013,6 +1687,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     method_name,
                     type_params,
                     &arg_types,
+                    expected_ty,
                 ),
 
             Some(IdentType::TypeParamStaticMethod(ty, name)) => {
@@ -1031,17 +1706,85 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 BuiltinType::Error
             }
 
+            Some(IdentType::EnumValue(enum_id, value)) => {
+                self.check_expr_call_enum(e, enum_id, value, &arg_types)
+            }
+
             _ => {
                 if expr_type.is_error() {
                     self.src.set_ty(e.id, expr_type);
                     return expr_type;
                 }
 
+                if expr_type.is_lambda() {
+                    return self.check_expr_call_lambda(e, expr_type, &arg_types);
+                }
+
                 self.check_expr_call_expr(e, expr_type, &arg_types)
             }
         }
     }
 
+    fn check_expr_call_enum(
+        &mut self,
+        e: &'ast ExprCallType,
+        enum_id: EnumId,
+        value: u32,
+        arg_types: &[BuiltinType],
+    ) -> BuiltinType {
+        let list_id = self.vm.lists.lock().insert(TypeList::empty());
+        let ty = BuiltinType::Enum(enum_id, list_id);
+        self.src.set_ty(e.id, ty);
+
+        if arg_types.iter().any(|ty| ty.is_error()) {
+            return ty;
+        }
+
+        let compatible = {
+            let xenum = self.vm.enums[enum_id].read();
+            let variant = &xenum.variants[value as usize];
+
+            !variant.types.is_empty()
+                && variant.types.len() == arg_types.len()
+                && variant
+                    .types
+                    .iter()
+                    .zip(arg_types)
+                    .all(|(def, &arg)| def.allows(self.vm, arg))
+        };
+
+        if !compatible {
+            let msg = self.enum_args_incompatible(enum_id, value, arg_types);
+            self.vm.diag.lock().report(self.file, e.pos, msg);
+        }
+
+        ty
+    }
+
+    fn enum_args_incompatible(
+        &self,
+        enum_id: EnumId,
+        value: u32,
+        arg_types: &[BuiltinType],
+    ) -> SemError {
+        let xenum = self.vm.enums[enum_id].read();
+        let variant = &xenum.variants[value as usize];
+
+        let enum_name = self.vm.interner.str(xenum.name).to_string();
+        let variant_name = self.vm.interner.str(variant.name).to_string();
+        let def = variant
+            .types
+            .iter()
+            .map(|ty| ty.name(self.vm))
+            .collect::<Vec<_>>();
+        let args = arg_types
+            .iter()
+            .map(|ty| ty.name(self.vm))
+            .collect::<Vec<_>>();
+
+        SemError::EnumArgsIncompatible(enum_name, variant_name, def, args)
+    }
+
     fn check_expr_call_generic_static_method(
         &mut self,
         e: &'ast ExprCallType,
@@ -1162,21 +1905,67 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
     }
 
+    fn check_expr_call_lambda(
+        &mut self,
+        e: &'ast ExprCallType,
+        expr_type: BuiltinType,
+        arg_types: &[BuiltinType],
+    ) -> BuiltinType {
+        let lambda = self
+            .vm
+            .lambda_types
+            .lock()
+            .get(expr_type.lambda_id().unwrap());
+
+        if arg_types.iter().any(|ty| ty.is_error()) {
+            self.src.set_ty(e.id, BuiltinType::Error);
+            return BuiltinType::Error;
+        }
+
+        let compatible = lambda.params().len() == arg_types.len()
+            && lambda
+                .params()
+                .iter()
+                .zip(arg_types)
+                .all(|(def, &arg)| def.allows(self.vm, arg));
+
+        if !compatible {
+            let lambda_name = expr_type.name(self.vm);
+            let arg_types = arg_types
+                .iter()
+                .map(|a| a.name(self.vm))
+                .collect::<Vec<_>>();
+            let msg = SemError::LambdaArgsIncompatible(lambda_name, arg_types);
+            self.vm.diag.lock().report(self.file, e.pos, msg);
+        }
+
+        let call_type = CallType::Lambda(expr_type);
+        self.src.map_calls.insert(e.id, Arc::new(call_type));
+
+        let return_type = lambda.ret();
+        self.src.set_ty(e.id, return_type);
+
+        return_type
+    }
+
     fn check_expr_call_ident(
         &mut self,
         e: &'ast ExprCallType,
         fct_id: FctId,
         type_params: TypeList,
         arg_types: &[BuiltinType],
+        expected_ty: BuiltinType,
     ) -> BuiltinType {
         let mut lookup = MethodLookup::new(self.vm, self.file)
             .pos(e.pos)
             .callee(fct_id)
             .args(&arg_types)
-            .fct_type_params(&type_params);
+            .fct_type_params(&type_params)
+            .expected_type(expected_ty);
 
         let ty = if lookup.find() {
-            let call_type = CallType::Fct(fct_id, TypeList::empty(), type_params.clone());
+            let type_params = lookup.found_fct_tps().unwrap();
+            let call_type = CallType::Fct(fct_id, TypeList::empty(), type_params);
             self.src.map_calls.insert(e.id, Arc::new(call_type));
 
             lookup.found_ret().unwrap()
@@ -1196,6 +1985,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         method_name: Name,
         type_params: TypeList,
         arg_types: &[BuiltinType],
+        expected_ty: BuiltinType,
     ) -> BuiltinType {
         let cls_id = object_type.cls_id(self.vm).unwrap();
         let cls_type_params = object_type.type_params(self.vm);
@@ -1206,16 +1996,14 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             .static_method(cls_id)
             .name(method_name)
             .args(arg_types)
-            .fct_type_params(&type_params);
+            .fct_type_params(&type_params)
+            .expected_type(expected_ty);
 
         if lookup.find() {
             let fct_id = lookup.found_fct_id().unwrap();
             let return_type = lookup.found_ret().unwrap();
-            let call_type = Arc::new(CallType::Fct(
-                fct_id,
-                TypeList::empty(),
-                type_params.clone(),
-            ));
+            let type_params = lookup.found_fct_tps().unwrap();
+            let call_type = Arc::new(CallType::Fct(fct_id, TypeList::empty(), type_params));
             self.src.map_calls.insert(e.id, call_type.clone());
 
             self.src.set_ty(e.id, return_type);
@@ -1235,6 +2023,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         method_name: Name,
         type_params: TypeList,
         arg_types: &[BuiltinType],
+        expected_ty: BuiltinType,
     ) -> BuiltinType {
         if object_type.is_type_param() {
             assert_eq!(type_params.len(), 0);
@@ -1252,17 +2041,20 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             .pos(e.pos)
             .name(method_name)
             .fct_type_params(&type_params)
-            .args(arg_types);
+            .args(arg_types)
+            .expected_type(expected_ty);
 
         if lookup.find() {
             let fct_id = lookup.found_fct_id().unwrap();
             let return_type = lookup.found_ret().unwrap();
 
+            let type_params = lookup.found_fct_tps().unwrap();
+
             let call_type = if let BuiltinType::Trait(trait_id) = object_type {
-                CallType::Trait(trait_id, fct_id)
+                CallType::Trait(trait_id, fct_id, type_params)
             } else {
                 let method_type = lookup.found_class_type().unwrap();
-                CallType::Method(method_type, fct_id, type_params.clone())
+                CallType::Method(method_type, fct_id, type_params)
             };
 
             self.src
@@ -1284,19 +2076,22 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         cls_id: ClassId,
         type_params: TypeList,
         arg_types: &[BuiltinType],
+        expected_ty: BuiltinType,
     ) -> BuiltinType {
         let mut lookup = MethodLookup::new(self.vm, self.file)
             .pos(e.pos)
             .ctor(cls_id)
             .args(arg_types)
-            .cls_type_params(&type_params);
+            .cls_type_params(&type_params)
+            .expected_type(expected_ty);
 
         let ty = if lookup.find() {
             let fct_id = lookup.found_fct_id().unwrap();
             let cls = self.vm.classes.idx(cls_id);
             let cls = cls.read();
 
-            let cls_ty = self.vm.cls_with_type_list(cls_id, type_params.clone());
+            let type_params = lookup.found_cls_tps().unwrap();
+            let cls_ty = self.vm.cls_with_type_list(cls_id, type_params);
             let call_type = CallType::CtorNew(cls_ty, fct_id);
             self.src.map_calls.insert(e.id, Arc::new(call_type));
 
@@ -1305,6 +2100,20 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 self.vm.diag.lock().report(self.file, e.pos, msg);
             }
 
+            // with --null-safety the elements of a new array would be nil,
+            // type params could be instantiated with a class as well
+            if self.vm.args.flag_null_safety
+                && cls_id == self.vm.vips.array_class
+                && !self.vm.is_trusted_file(self.file)
+            {
+                let element_ty = type_params[0];
+
+                if element_ty.is_cls() || element_ty.is_type_param() {
+                    let msg = SemError::ArrayWithoutElements(element_ty.name(self.vm));
+                    self.vm.diag.lock().report(self.file, e.pos, msg);
+                }
+            }
+
             lookup.found_ret().unwrap()
         } else {
             BuiltinType::Error
@@ -1352,7 +2161,9 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         for &trait_id in &tp.trait_bounds {
             let trai = self.vm.traits[trait_id].read();
 
-            if let Some(fid) = trai.find_method_with_replace(self.vm, false, name, None, args) {
+            if let Some(fid) =
+                trai.find_method_with_replace(self.vm, false, name, None, &TypeList::empty(), args)
+            {
                 found_fcts.push(fid);
             }
         }
@@ -1437,11 +2248,8 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
                 let ty = if lookup.find() {
                     let fct_id = lookup.found_fct_id().unwrap();
-                    let call_type = Arc::new(CallType::Fct(
-                        fct_id,
-                        TypeList::empty(),
-                        type_params.clone(),
-                    ));
+                    let type_params = lookup.found_fct_tps().unwrap();
+                    let call_type = Arc::new(CallType::Fct(fct_id, TypeList::empty(), type_params));
                     self.src.map_calls.insert(e.id, call_type.clone());
                     let ty = lookup.found_ret().unwrap();
                     self.src.set_ty(e.id, ty);
@@ -1478,10 +2286,12 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             .map(|arg| self.check_expr(arg, BuiltinType::Any))
             .collect();
 
-        let owner = self.vm.classes.idx(self.fct.cls_id());
-        let owner = owner.read();
+        let parent_class = match self.fct.parent {
+            FctParent::Module(module_id) => self.vm.modules.idx(module_id).read().parent_class,
+            _ => self.vm.classes.idx(self.fct.cls_id()).read().parent_class,
+        };
 
-        let parent_class = owner.parent_class.unwrap();
+        let parent_class = parent_class.unwrap();
         let cls_id = parent_class.cls_id(self.vm).expect("no class");
         let cls = self.vm.classes.idx(cls_id);
         let cls = cls.read();
@@ -1555,7 +2365,35 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             }
 
             Some(&IdentType::Module(module_id)) => {
-                let module_ty = BuiltinType::Module(module_id);
+                // the module object is the receiver of the call or the owner of the field
+                let module_ty = self.check_expr(&e.lhs, BuiltinType::Any);
+                debug_assert_eq!(module_ty, BuiltinType::Module(module_id));
+
+                if !self.used_in_call.contains(&e.id) {
+                    if let Some((owner_ty, field_id)) = find_field(self.vm, module_ty, name) {
+                        let ident_type = IdentType::Field(owner_ty, field_id);
+                        self.src.map_idents.insert_or_replace(e.id, ident_type);
+
+                        let (fty, _) = field_type(self.vm, owner_ty, field_id);
+
+                        self.src.set_ty(e.id, fty);
+                        return fty;
+                    }
+
+                    if find_methods_in_module(self.vm, module_ty, name).is_empty() {
+                        let notes = did_you_mean(self.vm, name, field_names(self.vm, module_ty));
+                        let field_name = self.vm.interner.str(name).to_string();
+                        let module_name = module_ty.name(self.vm);
+                        let msg = SemError::UnknownField(field_name, module_name);
+                        self.vm
+                            .diag
+                            .lock()
+                            .report_with_notes(self.file, e.pos, msg, notes);
+
+                        self.src.set_ty(e.id, BuiltinType::Error);
+                        return BuiltinType::Error;
+                    }
+                }
 
                 IdentType::Method(module_ty, name)
             }
@@ -1576,12 +2414,23 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     self.src
                         .map_idents
                         .insert(e.id, IdentType::EnumValue(id, value));
+
+                    let variant = &xenum.variants[value as usize];
+
+                    if !variant.types.is_empty() && !self.used_in_call.contains(&e.id) {
+                        let msg = self.enum_args_incompatible(id, value, &[]);
+                        self.vm.diag.lock().report(self.file, e.pos, msg);
+                    }
                 } else {
+                    let candidates = xenum.name_to_value.keys().cloned();
+                    let notes = did_you_mean(self.vm, name, candidates);
                     let name = self.vm.interner.str(name).to_string();
-                    self.vm
-                        .diag
-                        .lock()
-                        .report(self.file, e.pos, SemError::UnknownEnumValue(name));
+                    self.vm.diag.lock().report_with_notes(
+                        self.file,
+                        e.pos,
+                        SemError::UnknownEnumValue(name),
+                        notes,
+                    );
                 }
 
                 let list_id = self.vm.lists.lock().insert(TypeList::empty());
@@ -1669,6 +2518,13 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.check_expr(&e.lhs, BuiltinType::Any)
         };
 
+        let object_type = if object_type.is_nullable() {
+            self.report_nullable_receiver(e.pos, object_type);
+            BuiltinType::Error
+        } else {
+            object_type
+        };
+
         if object_type.is_tuple() {
             return self.check_expr_dot_tuple(e, object_type);
         }
@@ -1692,38 +2548,36 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             return BuiltinType::Error;
         }
 
-        if object_type.cls_id(self.vm).is_some() {
-            if let Some((cls_ty, field_id)) = find_field_in_class(self.vm, object_type, name) {
-                let ident_type = IdentType::Field(cls_ty, field_id);
+        if let BuiltinType::Struct(struct_id, _) = object_type {
+            if let Some((field_id, fty)) = find_field_in_struct(self.vm, struct_id, name) {
+                let ident_type = IdentType::StructField(object_type, field_id);
                 self.src.map_idents.insert_or_replace(e.id, ident_type);
 
-                let cls = self
-                    .vm
-                    .classes
-                    .idx(cls_ty.cls_id(self.vm).expect("no class"));
-                let cls = cls.read();
-
-                let field = &cls.fields[field_id];
-                let class_type_params = cls_ty.type_params(self.vm);
-                let fty = replace_type_param(
-                    self.vm,
-                    field.ty,
-                    &class_type_params,
-                    &TypeList::empty(),
-                    None,
-                );
-
                 self.src.set_ty(e.id, fty);
                 return fty;
             }
         }
 
+        if let Some((owner_ty, field_id)) = find_field(self.vm, object_type, name) {
+            let ident_type = IdentType::Field(owner_ty, field_id);
+            self.src.map_idents.insert_or_replace(e.id, ident_type);
+
+            let (fty, _) = field_type(self.vm, owner_ty, field_id);
+
+            self.src.set_ty(e.id, fty);
+            return fty;
+        }
+
         // field not found, report error
         if !object_type.is_error() {
+            let notes = did_you_mean(self.vm, name, field_names(self.vm, object_type));
             let field_name = self.vm.interner.str(name).to_string();
             let expr_name = object_type.name(self.vm);
             let msg = SemError::UnknownField(field_name, expr_name);
-            self.vm.diag.lock().report(self.file, e.pos, msg);
+            self.vm
+                .diag
+                .lock()
+                .report_with_notes(self.file, e.pos, msg, notes);
         }
 
         self.src.set_ty(e.id, BuiltinType::Error);
@@ -1798,6 +2652,22 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 ty
             }
 
+            FctParent::Module(module_id) if self.fct.has_self() => {
+                let ty = self.vm.modules.idx(module_id).read().ty;
+                self.src.set_ty(e.id, ty);
+
+                ty
+            }
+
+            // self was captured from the enclosing method
+            FctParent::Function(_) if self.src.map_idents.get(e.id).is_some() => {
+                let var_id = self.src.map_idents.get(e.id).unwrap().var_id();
+                let ty = self.src.vars[var_id].ty;
+                self.src.set_ty(e.id, ty);
+
+                ty
+            }
+
             _ => {
                 let msg = SemError::ThisUnavailable;
                 self.vm.diag.lock().report(self.file, e.pos, msg);
@@ -1831,21 +2701,12 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         e: &'ast ExprLambdaType,
         _expected_ty: BuiltinType,
     ) -> BuiltinType {
-        let ret = if let Some(ref ty) = e.ret {
-            self.src.ty(ty.id())
-        } else {
-            BuiltinType::Unit
-        };
-
-        let params = e
-            .params
-            .iter()
-            .map(|p| self.src.ty(p.data_type.id()))
-            .collect::<Vec<_>>();
-
-        let ty = self.vm.lambda_types.lock().insert(params, ret);
-        let ty = BuiltinType::Lambda(ty);
+        let lambda_id = *self.src.map_lambdas.get(e.id).unwrap();
+        let lambda = self.vm.fcts.idx(lambda_id);
+        let lambda = lambda.read();
 
+        // type of the closure object is the type of the hidden self parameter
+        let ty = lambda.param_types[0];
         self.src.set_ty(e.id, ty);
 
         ty
@@ -1882,6 +2743,9 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             return ty;
         }
 
+        // a nil value passes `as` unchanged
+        let nullable = object_type.is_nullable();
+        let object_type = object_type.non_nullable();
         let mut valid = false;
 
         if object_type.subclass_from(self.vm, check_type) {
@@ -1906,7 +2770,13 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             },
         );
 
-        let ty = if e.is { BuiltinType::Bool } else { check_type };
+        let ty = if e.is {
+            BuiltinType::Bool
+        } else if nullable {
+            check_type.nullable()
+        } else {
+            check_type
+        };
 
         self.src.set_ty(e.id, ty);
 
@@ -2043,7 +2913,9 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             ExprLambda(ref expr) => self.check_expr_lambda(expr, expected_ty),
             ExprBlock(ref expr) => self.check_expr_block(expr, expected_ty),
             ExprIf(ref expr) => self.check_expr_if(expr, expected_ty),
+            ExprMatch(ref expr) => self.check_expr_match(expr, expected_ty),
             ExprTuple(ref expr) => self.check_expr_tuple(expr, expected_ty),
+            ExprLitStruct(ref expr) => self.check_expr_lit_struct(expr, expected_ty),
         }
     }
 }
@@ -2059,6 +2931,8 @@ impl<'a, 'ast> Visitor<'ast> for TypeCheck<'a, 'ast> {
             StmtWhile(ref stmt) => self.check_stmt_while(stmt),
             StmtFor(ref stmt) => self.check_stmt_for(stmt),
             StmtReturn(ref stmt) => self.check_stmt_return(stmt),
+            StmtThrow(ref stmt) => self.check_stmt_throw(stmt),
+            StmtTry(ref stmt) => self.check_stmt_try(stmt),
 
             // for the rest of the statements, no special handling is necessary
             StmtBreak(_) => visit::walk_stmt(self, s),
@@ -2162,7 +3036,7 @@ fn arg_allows(
                 self_ty,
             )
         }
-        BuiltinType::Trait(_) => panic!("trait should not occur in fct definition."),
+        BuiltinType::Trait(_) => def.allows(vm, arg),
 
         BuiltinType::ClassTypeParam(cls_id, tpid) => {
             if def == arg {
@@ -2205,8 +3079,10 @@ fn arg_allows(
             )
         }
 
+        BuiltinType::TraitTypeParam(_, _) => def == arg,
+
         BuiltinType::Class(cls_id, list_id) => {
-            if def == arg || arg.is_nil() {
+            if def == arg || (arg.is_nil() && !vm.args.flag_null_safety) {
                 return true;
             }
 
@@ -2253,6 +3129,20 @@ fn arg_allows(
             true
         }
 
+        BuiltinType::Nullable(_, _) => {
+            arg.is_nil()
+                || arg_allows(
+                    vm,
+                    def.non_nullable(),
+                    arg.non_nullable(),
+                    global_cls_id,
+                    global_fct_id,
+                    cls_tps,
+                    fct_tps,
+                    self_ty,
+                )
+        }
+
         BuiltinType::Tuple(tuple_id) => match arg {
             BuiltinType::Tuple(other_tuple_id) => {
                 if tuple_id == other_tuple_id {
@@ -2294,12 +3184,47 @@ fn arg_allows(
 
         BuiltinType::Module(_) => def == arg,
 
-        BuiltinType::Lambda(_) => {
-            // for now expect the exact same params and return types
-            // possible improvement: allow super classes for params,
-            //                             sub class for return type
-            def == arg
-        }
+        BuiltinType::Lambda(lambda_id) => match arg {
+            BuiltinType::Lambda(other_lambda_id) => {
+                if lambda_id == other_lambda_id {
+                    return true;
+                }
+
+                // params and return type may contain type params of the callee
+                let lambda = vm.lambda_types.lock().get(lambda_id);
+                let other_lambda = vm.lambda_types.lock().get(other_lambda_id);
+
+                if lambda.params().len() != other_lambda.params().len() {
+                    return false;
+                }
+
+                let types = lambda.params().iter().cloned().chain(once(lambda.ret()));
+                let other_types = other_lambda
+                    .params()
+                    .iter()
+                    .cloned()
+                    .chain(once(other_lambda.ret()));
+
+                for (ty, other_ty) in types.zip(other_types) {
+                    if !arg_allows(
+                        vm,
+                        ty,
+                        other_ty,
+                        global_cls_id,
+                        global_fct_id,
+                        cls_tps,
+                        fct_tps,
+                        self_ty,
+                    ) {
+                        return false;
+                    }
+                }
+
+                true
+            }
+
+            _ => false,
+        },
     }
 }
 
@@ -2452,3 +3377,54 @@ pub fn lookup_method<'ast>(
 
     None
 }
+
+// names of all fields accessible on a value of this type
+fn field_names(vm: &VM, ty: BuiltinType) -> Vec<Name> {
+    match ty {
+        BuiltinType::Struct(struct_id, _) => field_names_in_struct(vm, struct_id),
+        BuiltinType::Module(_) => field_names_in_module(vm, ty),
+        _ => field_names_in_class(vm, ty),
+    }
+}
+
+// finds the field in a class or module object
+fn find_field(vm: &VM, object_type: BuiltinType, name: Name) -> Option<(BuiltinType, FieldId)> {
+    if object_type.is_module() {
+        find_field_in_module(vm, object_type, name)
+    } else if object_type.cls_id(vm).is_some() {
+        find_field_in_class(vm, object_type, name)
+    } else {
+        None
+    }
+}
+
+// type of the field and whether it is reassignable, the owner is either a
+// class or a module
+fn field_type(vm: &VM, owner_ty: BuiltinType, field_id: FieldId) -> (BuiltinType, bool) {
+    if let Some(module_id) = owner_ty.module_id() {
+        let module = vm.modules.idx(module_id);
+        let module = module.read();
+        let field = &module.fields[field_id];
+
+        return (field.ty, field.reassignable);
+    }
+
+    let cls = vm.classes.idx(owner_ty.cls_id(vm).expect("no class"));
+    let cls = cls.read();
+    let field = &cls.fields[field_id];
+
+    let class_type_params = owner_ty.type_params(vm);
+    let fty = replace_type_param(vm, field.ty, &class_type_params, &TypeList::empty(), None);
+
+    (fty, field.reassignable)
+}
+
+fn lit_value(lit: &Expr) -> Option<i64> {
+    match *lit {
+        ExprLitBool(ref lit) => Some(lit.value as i64),
+        ExprLitChar(ref lit) => Some(lit.value as i64),
+        ExprLitInt(ref lit) => Some(lit.value as i64),
+        ExprUn(ref un) if un.op == UnOp::Neg => lit_value(&un.opnd).map(|value| -value),
+        _ => None,
+    }
+}
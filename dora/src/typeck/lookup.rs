@@ -1,17 +1,29 @@
 use crate::error::msg::SemError;
+use crate::error::suggest::did_you_mean;
 use crate::semck::specialize::replace_type_param;
 use crate::semck::typeparamck;
 use crate::ty::{BuiltinType, TypeList};
 use crate::typeck::expr::args_compatible;
 use crate::vm::{
-    find_methods_in_class, find_methods_in_enum, ClassId, FctId, FctParent, FileId, TraitId,
-    TypeParam, VM,
+    find_methods_in_class, find_methods_in_enum, method_names_in_class, ClassId, Fct, FctId,
+    FctParent, FileId, TraitId, TypeParam, VM,
 };
 
-use crate::vm::module::find_methods_in_module;
+use crate::vm::module::{find_methods_in_module, method_names_in_module};
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
 
+// names of the methods callable on the given type, e.g. for suggestions
+pub fn method_names(vm: &VM, object_type: BuiltinType, is_static: bool) -> Vec<Name> {
+    if object_type.is_module() {
+        method_names_in_module(vm, object_type)
+    } else if object_type.cls_id(vm).is_some() {
+        method_names_in_class(vm, object_type, is_static)
+    } else {
+        Vec::new()
+    }
+}
+
 #[derive(Copy, Clone)]
 enum LookupKind {
     Fct,
@@ -31,11 +43,14 @@ pub struct MethodLookup<'a, 'ast: 'a> {
     cls_tps: Option<&'a TypeList>,
     fct_tps: Option<&'a TypeList>,
     ret: Option<BuiltinType>,
+    expected: Option<BuiltinType>,
     pos: Option<Position>,
 
     found_fct_id: Option<FctId>,
     found_class_type: Option<BuiltinType>,
     found_ret: Option<BuiltinType>,
+    found_cls_tps: Option<TypeList>,
+    found_fct_tps: Option<TypeList>,
 
     found_multiple_functions: bool,
 }
@@ -51,11 +66,14 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             cls_tps: None,
             fct_tps: None,
             ret: None,
+            expected: None,
             pos: None,
 
             found_fct_id: None,
             found_class_type: None,
             found_ret: None,
+            found_cls_tps: None,
+            found_fct_tps: None,
 
             found_multiple_functions: false,
         }
@@ -129,6 +147,12 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         self
     }
 
+    // type the result is assigned to, used to infer missing type params
+    pub fn expected_type(mut self, expected: BuiltinType) -> MethodLookup<'a, 'ast> {
+        self.expected = Some(expected);
+        self
+    }
+
     pub fn find(&mut self) -> bool {
         let kind = self.kind.expect("kind not set");
         let args = self.args.expect("args not set");
@@ -169,6 +193,30 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         let fct_id = if let Some(fct_id) = fct_id {
             fct_id
         } else {
+            let notes = match kind {
+                LookupKind::Method(obj) if !self.found_multiple_functions => {
+                    let name = self.name.expect("name not set");
+                    did_you_mean(self.vm, name, method_names(self.vm, obj, false))
+                }
+
+                LookupKind::Trait(trait_id) => {
+                    let name = self.name.expect("name not set");
+                    let methods = self.vm.traits[trait_id].read().methods.clone();
+                    let candidates = methods
+                        .into_iter()
+                        .map(|method| self.vm.fcts.idx(method).read().name);
+                    did_you_mean(self.vm, name, candidates)
+                }
+
+                LookupKind::Static(cls_id) => {
+                    let name = self.name.expect("name not set");
+                    let candidates = method_names_in_class(self.vm, self.vm.cls(cls_id), true);
+                    did_you_mean(self.vm, name, candidates)
+                }
+
+                _ => Vec::new(),
+            };
+
             let name = match kind {
                 LookupKind::Ctor(cls_id) => {
                     let cls = self.vm.classes.idx(cls_id);
@@ -217,16 +265,38 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
                 }
             };
 
-            self.vm
-                .diag
-                .lock()
-                .report(self.file, self.pos.expect("pos not set"), msg);
+            self.vm.diag.lock().report_with_notes(
+                self.file,
+                self.pos.expect("pos not set"),
+                msg,
+                notes,
+            );
             return false;
         };
 
         let fct = self.vm.fcts.idx(fct_id);
         let fct = fct.read();
 
+        // the class of a trait object is only known at runtime, so there is
+        // no type to check arguments passed for `Self` against
+        if let LookupKind::Trait(trait_id) = kind {
+            if fct
+                .params_without_self()
+                .iter()
+                .any(|&ty| mentions_self(self.vm, ty))
+            {
+                let xtrait = self.vm.traits[trait_id].read();
+                let trait_name = self.vm.interner.str(xtrait.name).to_string();
+                let name = self.vm.interner.str(fct.name).to_string();
+                let msg = SemError::TraitObjectSelfParam(trait_name, name);
+                self.vm
+                    .diag
+                    .lock()
+                    .report(self.file, self.pos.expect("pos not set"), msg);
+                return false;
+            }
+        }
+
         let cls_id = match fct.parent {
             FctParent::Class(cls_id) => Some(cls_id),
             FctParent::Impl(impl_id) => {
@@ -236,27 +306,58 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             _ => None,
         };
 
-        let cls_tps: TypeList = if let Some(cls_tps) = self.cls_tps {
+        let mut cls_tps: TypeList = if let Some(cls_tps) = self.cls_tps {
             cls_tps.clone()
         } else if let LookupKind::Method(obj) = kind {
-            obj.type_params(self.vm)
+            // the method might be defined in a super class with different type params
+            self.found_class_type.unwrap_or(obj).type_params(self.vm)
         } else {
             TypeList::empty()
         };
 
-        if cls_id.is_some() && !self.check_cls_tps(cls_id.unwrap(), &cls_tps) {
-            return false;
-        }
+        let mut fct_tps: TypeList = self.fct_tps.cloned().unwrap_or_else(TypeList::empty);
+
+        // type params left out by the caller are inferred, only constructors
+        // infer the class type params
+        let infer_cls_tps = match kind {
+            LookupKind::Ctor(cls_id) => {
+                cls_tps.len() == 0 && !self.vm.classes.idx(cls_id).read().type_params.is_empty()
+            }
+            _ => false,
+        };
+        let infer_fct_tps = fct_tps.len() == 0 && !fct.type_params.is_empty();
 
-        let fct_tps: TypeList = if let Some(fct_tps) = self.fct_tps {
-            if !self.check_fct_tps(fct_tps) {
+        if infer_cls_tps || infer_fct_tps {
+            if args.contains(&BuiltinType::Error) {
                 return false;
             }
 
-            fct_tps.clone()
-        } else {
-            TypeList::empty()
-        };
+            let inferred = self.infer_type_params(
+                &*fct,
+                kind,
+                if infer_cls_tps { cls_id } else { None },
+                infer_fct_tps,
+                &cls_tps,
+                &fct_tps,
+            );
+
+            match inferred {
+                Some((inferred_cls_tps, inferred_fct_tps)) => {
+                    cls_tps = inferred_cls_tps;
+                    fct_tps = inferred_fct_tps;
+                }
+
+                None => return false,
+            }
+        }
+
+        if cls_id.is_some() && !self.check_cls_tps(cls_id.unwrap(), &cls_tps) {
+            return false;
+        }
+
+        if (self.fct_tps.is_some() || infer_fct_tps) && !self.check_fct_tps(&fct_tps) {
+            return false;
+        }
 
         if args.contains(&BuiltinType::Error) {
             return false;
@@ -279,7 +380,16 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
                 .map(|a| a.name(self.vm))
                 .collect::<Vec<_>>();
             let call_types = args.iter().map(|a| a.name(self.vm)).collect::<Vec<_>>();
-            let msg = SemError::ParamTypesIncompatible(fct_name, fct_params, call_types);
+            let msg = match kind {
+                LookupKind::Ctor(cls_id) => {
+                    let cls = self.vm.classes.idx(cls_id);
+                    let cls = cls.read();
+                    let name = self.vm.interner.str(cls.name).to_string();
+                    SemError::UnknownCtor(name, call_types)
+                }
+
+                _ => SemError::ParamTypesIncompatible(fct_name, fct_params, call_types),
+            };
             self.vm
                 .diag
                 .lock()
@@ -289,13 +399,24 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
 
         let cmp_type = match kind {
             LookupKind::Ctor(cls_id) => {
-                let list_id = self.vm.lists.lock().insert(cls_tps);
+                let list_id = self.vm.lists.lock().insert(cls_tps.clone());
                 BuiltinType::Class(cls_id, list_id)
             }
 
+            LookupKind::Trait(trait_id) => replace_type_param(
+                self.vm,
+                fct.return_type,
+                &cls_tps,
+                &fct_tps,
+                Some(BuiltinType::Trait(trait_id)),
+            ),
+
             _ => replace_type_param(self.vm, fct.return_type, &cls_tps, &fct_tps, None),
         };
 
+        self.found_cls_tps = Some(cls_tps);
+        self.found_fct_tps = Some(fct_tps);
+
         if self.ret.is_none() || self.ret.unwrap() == cmp_type {
             self.found_ret = Some(cmp_type);
             true
@@ -308,32 +429,12 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         unimplemented!()
     }
 
+    // argument types are checked in `find` after inferring the type params
     fn find_ctor(&self, cls_id: ClassId) -> Option<FctId> {
         let cls = self.vm.classes.idx(cls_id);
         let cls = cls.read();
 
-        let type_params = self.cls_tps.as_ref().unwrap();
-        let args = self.args.unwrap();
-
-        if let Some(ctor_id) = cls.constructor {
-            let ctor = self.vm.fcts.idx(ctor_id);
-            let ctor = ctor.read();
-
-            if args_compatible(
-                self.vm,
-                &*ctor,
-                &args,
-                Some(cls_id),
-                None,
-                type_params,
-                &TypeList::empty(),
-                None,
-            ) {
-                return Some(ctor_id);
-            }
-        }
-
-        None
+        cls.constructor
     }
 
     fn find_method(
@@ -377,6 +478,112 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         xtrait.find_method(self.vm, name, is_static)
     }
 
+    // unifies the parameter types with the argument types and the return type
+    // with the expected type, reports the first type param that could not be
+    // determined that way
+    fn infer_type_params(
+        &self,
+        fct: &Fct,
+        kind: LookupKind,
+        infer_cls_id: Option<ClassId>,
+        infer_fct_tps: bool,
+        cls_tps: &TypeList,
+        fct_tps: &TypeList,
+    ) -> Option<(TypeList, TypeList)> {
+        let cls_type_params = match infer_cls_id {
+            Some(cls_id) => self.vm.classes.idx(cls_id).read().type_params.clone(),
+            None => Vec::new(),
+        };
+
+        let fct_type_params = if infer_fct_tps {
+            fct.type_params.len()
+        } else {
+            0
+        };
+
+        let mut inference = Inference {
+            vm: self.vm,
+            cls_id: infer_cls_id,
+            fct_id: if infer_fct_tps { Some(fct.id) } else { None },
+            cls_tps: vec![None; cls_type_params.len()],
+            fct_tps: vec![None; fct_type_params],
+        };
+
+        let args = self.args.expect("args not set");
+        let def = fct.params_without_self();
+
+        for (idx, &arg) in args.iter().enumerate() {
+            let def = if idx < def.len() {
+                def[idx]
+            } else if fct.variadic_arguments && !def.is_empty() {
+                def[def.len() - 1]
+            } else {
+                break;
+            };
+
+            inference.unify(def, arg);
+        }
+
+        // the expected type is unified last, it may only generalize what the
+        // arguments determined: `let x: Foo[Object] = Foo("a")` is a `Foo[Object]`
+        if let Some(expected) = self.expected {
+            if !expected.is_error() && expected != BuiltinType::Any {
+                let ret = match infer_cls_id {
+                    Some(cls_id) => {
+                        let params = (0..cls_type_params.len())
+                            .map(|idx| BuiltinType::ClassTypeParam(cls_id, idx.into()))
+                            .collect::<Vec<_>>();
+                        let list_id = self.vm.lists.lock().insert(TypeList::with(params));
+                        BuiltinType::Class(cls_id, list_id)
+                    }
+
+                    None => fct.return_type,
+                };
+
+                inference.unify(ret, expected);
+            }
+        }
+
+        let missing = if let Some(idx) = inference.cls_tps.iter().position(|tp| tp.is_none()) {
+            Some(cls_type_params[idx].name)
+        } else if let Some(idx) = inference.fct_tps.iter().position(|tp| tp.is_none()) {
+            Some(fct.type_params[idx].name)
+        } else {
+            None
+        };
+
+        if let Some(missing) = missing {
+            let name = match kind {
+                LookupKind::Ctor(cls_id) => self.vm.classes.idx(cls_id).read().name,
+                _ => fct.name,
+            };
+
+            let tp = self.vm.interner.str(missing).to_string();
+            let name = self.vm.interner.str(name).to_string();
+            let msg = SemError::TypeParamNotInferred(tp, name);
+            self.vm
+                .diag
+                .lock()
+                .report(self.file, self.pos.expect("pos not set"), msg);
+
+            return None;
+        }
+
+        let cls_tps = if infer_cls_id.is_some() {
+            inferred_list(inference.cls_tps)
+        } else {
+            cls_tps.clone()
+        };
+
+        let fct_tps = if infer_fct_tps {
+            inferred_list(inference.fct_tps)
+        } else {
+            fct_tps.clone()
+        };
+
+        Some((cls_tps, fct_tps))
+    }
+
     fn check_cls_tps(&self, cls_id: ClassId, tps: &TypeList) -> bool {
         let cls_tps = {
             let cls = self.vm.classes.idx(cls_id);
@@ -420,4 +627,130 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
     pub fn found_ret(&self) -> Option<BuiltinType> {
         self.found_ret
     }
+
+    // type params of the found function, including the inferred ones
+    pub fn found_cls_tps(&self) -> Option<TypeList> {
+        self.found_cls_tps.clone()
+    }
+
+    pub fn found_fct_tps(&self) -> Option<TypeList> {
+        self.found_fct_tps.clone()
+    }
+}
+
+struct Inference<'a, 'ast: 'a> {
+    vm: &'a VM<'ast>,
+    cls_id: Option<ClassId>,
+    fct_id: Option<FctId>,
+    cls_tps: Vec<Option<BuiltinType>>,
+    fct_tps: Vec<Option<BuiltinType>>,
+}
+
+impl<'a, 'ast> Inference<'a, 'ast> {
+    // binds the type params of `def` to the corresponding parts of `ty`,
+    // parts that don't match are left for `args_compatible` to report
+    fn unify(&mut self, def: BuiltinType, ty: BuiltinType) {
+        match def {
+            BuiltinType::ClassTypeParam(cls_id, tp_id) if Some(cls_id) == self.cls_id => {
+                bind(self.vm, &mut self.cls_tps[tp_id.idx()], ty);
+            }
+
+            BuiltinType::FctTypeParam(fct_id, tp_id) if Some(fct_id) == self.fct_id => {
+                bind(self.vm, &mut self.fct_tps[tp_id.idx()], ty);
+            }
+
+            BuiltinType::Class(cls_id, list_id) | BuiltinType::Nullable(cls_id, list_id) => {
+                match ty {
+                    BuiltinType::Class(ty_cls_id, ty_list_id)
+                    | BuiltinType::Nullable(ty_cls_id, ty_list_id)
+                        if cls_id == ty_cls_id =>
+                    {
+                        let params = self.vm.lists.lock().get(list_id);
+                        let ty_params = self.vm.lists.lock().get(ty_list_id);
+
+                        for (param, ty_param) in params.iter().zip(ty_params.iter()) {
+                            self.unify(param, ty_param);
+                        }
+                    }
+
+                    _ => {}
+                }
+            }
+
+            BuiltinType::Lambda(lambda_id) => {
+                if let BuiltinType::Lambda(ty_lambda_id) = ty {
+                    let lambda = self.vm.lambda_types.lock().get(lambda_id);
+                    let ty_lambda = self.vm.lambda_types.lock().get(ty_lambda_id);
+
+                    if lambda.params().len() == ty_lambda.params().len() {
+                        for (&param, &ty_param) in lambda.params().iter().zip(ty_lambda.params()) {
+                            self.unify(param, ty_param);
+                        }
+
+                        self.unify(lambda.ret(), ty_lambda.ret());
+                    }
+                }
+            }
+
+            BuiltinType::Tuple(tuple_id) => {
+                if let BuiltinType::Tuple(ty_tuple_id) = ty {
+                    let subtypes = self.vm.tuples.lock().get_tuple(tuple_id).args();
+                    let ty_subtypes = self.vm.tuples.lock().get_tuple(ty_tuple_id).args();
+
+                    if subtypes.len() == ty_subtypes.len() {
+                        for (&subtype, &ty_subtype) in subtypes.iter().zip(ty_subtypes.iter()) {
+                            self.unify(subtype, ty_subtype);
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+fn inferred_list(tps: Vec<Option<BuiltinType>>) -> TypeList {
+    TypeList::with(tps.into_iter().map(|tp| tp.unwrap()).collect())
+}
+
+// nil doesn't determine a type, of two class types the more general one is kept
+// when it allows the other one
+fn bind(vm: &VM, tp: &mut Option<BuiltinType>, ty: BuiltinType) {
+    if ty.is_nil() || ty.is_error() || ty == BuiltinType::Any {
+        return;
+    }
+
+    match *tp {
+        None => *tp = Some(ty),
+        Some(current) => {
+            if !current.allows(vm, ty) && ty.allows(vm, current) {
+                *tp = Some(ty);
+            }
+        }
+    }
+}
+
+fn mentions_self(vm: &VM, ty: BuiltinType) -> bool {
+    match ty {
+        BuiltinType::This => true,
+
+        BuiltinType::Class(_, list_id) | BuiltinType::Nullable(_, list_id) => {
+            let params = vm.lists.lock().get(list_id);
+            params.iter().any(|ty| mentions_self(vm, ty))
+        }
+
+        BuiltinType::Lambda(id) => {
+            let lambda = vm.lambda_types.lock().get(id);
+            lambda.params().iter().any(|&ty| mentions_self(vm, ty))
+                || mentions_self(vm, lambda.ret())
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let subtypes = vm.tuples.lock().get(tuple_id);
+            subtypes.iter().any(|&ty| mentions_self(vm, ty))
+        }
+
+        _ => false,
+    }
 }
@@ -1,6 +1,7 @@
 use crate::error::msg::SemError;
 use crate::semck::tests::*;
 use crate::vm::ConstValue;
+use dora_parser::error::ParseError;
 
 #[test]
 fn type_method_len() {
@@ -91,6 +92,33 @@ fn type_module_method_call_1() {
     );
 }
 
+#[test]
+fn type_module_field() {
+    ok("module Foo { let bar: Int = 1; } fun f() -> Int { return Foo::bar; }");
+    ok("module Foo { let bar: Int = 1; } fun f() -> Int { return Foo.bar; }");
+    ok("module Foo { var bar: Int = 1; } fun f() { Foo::bar = 2; Foo.bar = 3; }");
+    ok("module Foo { var bar: Int = 1; fun baz() -> Int { self.bar = 2; return self.bar; } }");
+    ok("open class Bar { var x: Int = 1; } module Foo : Bar {} fun f() -> Int { return Foo::x; }");
+
+    err(
+        "module Foo { let bar: Int = 1; } fun f() { Foo::bar = 2; }",
+        pos(1, 53),
+        SemError::LetReassigned,
+    );
+
+    err(
+        "module Foo { let bar: Int = 1; } fun f() -> String { return Foo::bar; }",
+        pos(1, 54),
+        SemError::ReturnType("String".into(), "Int".into()),
+    );
+
+    err(
+        "module Foo {} fun f() -> Int { return Foo::bar; }",
+        pos(1, 42),
+        SemError::UnknownField("bar".into(), "Foo".into()),
+    );
+}
+
 #[test]
 fn type_method_defined_twice() {
     err(
@@ -639,6 +667,92 @@ fn check_is() {
     ok("@open class A() class B(): A() fun f() { let a: A = B(); }");
 }
 
+#[test]
+fn check_smart_cast() {
+    ok("@open class A class B: A { fun g() -> Int { 1 } }
+        fun f(a: A) -> Int { if a is B { a.g() } else { 0 } }");
+    ok("@open class A class B: A { fun g() -> Int { 1 } }
+        fun f(a: A) -> Int { if !(a is B) { 0 } else { a.g() } }");
+    ok("@open class A class B: A { fun g() -> Int { 1 } }
+        fun f(a: A) -> Bool { a is B && a.g() == 1 }");
+    ok("@open class A class B: A { fun g() -> Int { 1 } }
+        fun f(a: A) -> Int { let b = a; while b is B { return b.g(); } 0 }");
+    ok("@open class A class B: A { fun g() -> Int { 1 } }
+        fun f(a: A) -> Bool { a !== nil && a is B && (a as B).g() == 1 }");
+    err(
+        "@open class A class B: A { fun g() -> Int { 1 } } fun f(var a: A) -> Int { if a is B { a.g() } else { 0 } }",
+        pos(1, 91),
+        SemError::UnknownMethod("A".into(), "g".into(), Vec::new()),
+    );
+}
+
+#[test]
+fn check_nullable_without_null_safety() {
+    ok("class A fun f(a: A?) -> A { a }");
+    ok("class A fun f() -> A { nil }");
+    err(
+        "fun f(a: Int?) {}",
+        pos(1, 10),
+        SemError::NullableNotClass("Int".into()),
+    );
+}
+
+#[test]
+fn check_null_safety() {
+    ok_null_safety("class A fun f() -> A? { nil }");
+    ok_null_safety("class A fun f(a: A) -> A? { a }");
+    ok_null_safety("class A fun f(a: A?) -> Bool { a === nil }");
+    ok_null_safety("class A fun f(c: Bool) -> A? { if c { A() } else { nil } }");
+    ok_null_safety("class A fun f() -> Option[A?] { some[A?](nil) }");
+    ok_null_safety("class A(let x: Int) fun f(a: A?) -> Int { if a !== nil { a.x } else { 0 } }");
+    ok_null_safety("class A(let x: Int) fun f(a: A?) -> Int { if a === nil { 0 } else { a.x } }");
+    ok_null_safety(
+        "@open class A class B: A { fun g() -> Int { 1 } }
+        fun f(a: A?) -> Int { if a is B { a.g() } else { 0 } }",
+    );
+    ok_null_safety("@open class A class B: A fun f(a: A?) -> B? { a as B }");
+    ok_null_safety("class A fun f() -> Array[A?] { Array[A?](2) }");
+    ok_null_safety("class A fun f() -> Array[A] { arrayFill[A](2, A()) }");
+    ok_null_safety("fun f() -> Array[Int] { Array[Int](2) }");
+
+    err_null_safety(
+        "class A fun f() -> A { return nil; }",
+        pos(1, 24),
+        SemError::IncompatibleWithNil("A".into()),
+    );
+    err_null_safety(
+        "class A fun f() { let a: A = nil; }",
+        pos(1, 19),
+        SemError::AssignType("a".into(), "A".into(), "nil".into()),
+    );
+    err_null_safety(
+        "class A fun f(a: A?) { let b: A = a; }",
+        pos(1, 24),
+        SemError::AssignType("b".into(), "A".into(), "A?".into()),
+    );
+    err_null_safety(
+        "class A(let x: Int) fun f(a: A?) -> Int { a.x }",
+        pos(1, 44),
+        SemError::NullableReceiver("A?".into()),
+    );
+    err_null_safety(
+        "class A { fun g() {} } fun f(a: A?) { a.g(); }",
+        pos(1, 40),
+        SemError::NullableReceiver("A?".into()),
+    );
+    err_null_safety(
+        "class A fun f() -> Array[A] { Array[A](2) }",
+        pos(1, 39),
+        SemError::ArrayWithoutElements("A".into()),
+    );
+    err_null_safety(
+        "fun f[T]() -> Array[T] { Array[T](2) }",
+        pos(1, 34),
+        SemError::ArrayWithoutElements("T".into()),
+    );
+    ok_null_safety("fun f[T](x: T) -> Array[T] { arrayFill[T](2, x) }");
+}
+
 #[test]
 fn check_as() {
     ok("@open class A class B: A
@@ -710,19 +824,93 @@ fn super_as_normal_expression() {
 
 #[test]
 fn struct_lit() {
-    ok("struct Foo {} fun foo() -> Foo { return Foo; }");
-    ok("struct Foo {} fun foo() { let x = Foo; }");
-    ok("struct Foo {} fun foo() { let x: Foo = Foo; }");
+    ok("struct Foo {} fun foo() -> Foo { return Foo {}; }");
+    ok("struct Foo { a: Int } fun foo() { let x = Foo { a: 1 }; }");
+    ok("struct Foo { a: Int, b: Bool } fun foo() { let x: Foo = Foo { b: true, a: 1 }; }");
     err(
-        "struct Foo {} fun foo() { let x: Int = Foo; }",
+        "struct Foo {} fun foo() { let x: Int = Foo {}; }",
         pos(1, 27),
         SemError::AssignType("x".into(), "Int".into(), "Foo".into()),
     );
     err(
-        "struct Foo {} fun foo() -> Int { return Foo; }",
+        "struct Foo {} fun foo() -> Int { return Foo {}; }",
         pos(1, 34),
         SemError::ReturnType("Int".into(), "Foo".into()),
     );
+    err(
+        "struct Foo {} fun foo() { let x = Foo; }",
+        pos(1, 35),
+        SemError::StructUsedAsIdentifier,
+    );
+    err(
+        "struct Foo { a: Int } fun foo() { Foo { a: 1, b: 2 }; }",
+        pos(1, 47),
+        SemError::UnknownStructField("Foo".into(), "b".into()),
+    );
+    err(
+        "struct Foo { a: Int } fun foo() { Foo { a: 1, a: 2 }; }",
+        pos(1, 47),
+        SemError::StructFieldInitializedTwice("Foo".into(), "a".into()),
+    );
+    err(
+        "struct Foo { a: Int } fun foo() { Foo { a: true }; }",
+        pos(1, 41),
+        SemError::AssignField("a".into(), "Foo".into(), "Int".into(), "Bool".into()),
+    );
+    err(
+        "struct Foo { a: Int, b: Int } fun foo() { Foo { a: 1 }; }",
+        pos(1, 43),
+        SemError::StructFieldNotInitialized("Foo".into(), "b".into()),
+    );
+    err(
+        "fun foo() { Bar { a: 1 }; }",
+        pos(1, 13),
+        SemError::UnknownStruct("Bar".into()),
+    );
+}
+
+#[test]
+fn struct_field() {
+    ok("struct Foo { a: Int } fun foo(x: Foo) -> Int { x.a }");
+    ok("struct Foo { a: Int } struct Bar { foo: Foo } fun foo(x: Bar) -> Int { x.foo.a }");
+    ok("struct Foo { a: Int } fun foo() { var x = Foo { a: 1 }; x.a = 2; }");
+    ok("struct Foo { a: Int } struct Bar { foo: Foo }
+        fun foo() { var x = Bar { foo: Foo { a: 1 } }; x.foo.a = 2; }");
+    ok(
+        "struct Foo { a: Int } class Bar { var foo: Foo = Foo { a: 1 }; }
+        fun foo(x: Bar) { x.foo.a = 2; }",
+    );
+    ok("struct Foo { a: Int } var x: Foo = Foo { a: 1 }; fun foo() { x.a = 2; }");
+    err(
+        "struct Foo { a: Int } fun foo(x: Foo) -> Int { x.b }",
+        pos(1, 49),
+        SemError::UnknownField("b".into(), "Foo".into()),
+    );
+    err(
+        "struct Foo { a: Int } fun foo(x: Foo) -> Bool { x.a }",
+        pos(1, 47),
+        SemError::ReturnType("Bool".into(), "Int".into()),
+    );
+    err(
+        "struct Foo { a: Int } fun foo() { let x = Foo { a: 1 }; x.a = 2; }",
+        pos(1, 61),
+        SemError::LetReassigned,
+    );
+    err(
+        "struct Foo { a: Int } fun foo(x: Foo) { x.a = 2; }",
+        pos(1, 45),
+        SemError::LetReassigned,
+    );
+    err(
+        "struct Foo { a: Int } class Bar(let foo: Foo) fun foo(x: Bar) { x.foo.a = 2; }",
+        pos(1, 73),
+        SemError::LetReassigned,
+    );
+    err(
+        "struct Foo { a: Int } fun bar() -> Foo { Foo { a: 1 } } fun foo() { bar().a = 2; }",
+        pos(1, 77),
+        SemError::LvalueExpected,
+    );
 }
 
 #[test]
@@ -948,7 +1136,7 @@ fn test_generic_arguments_mismatch() {
                 let a = A();
             }",
         pos(3, 26),
-        SemError::WrongNumberTypeParams(1, 0),
+        SemError::TypeParamNotInferred("T".into(), "A".into()),
     );
 
     err(
@@ -995,7 +1183,7 @@ fn test_fct_with_type_params() {
     err(
         "fun f[T]() {} fun g() { f(); }",
         pos(1, 26),
-        SemError::WrongNumberTypeParams(1, 0),
+        SemError::TypeParamNotInferred("T".into(), "f".into()),
     );
     ok("fun f[T]() {} fun g() { f[Int](); }");
     ok("fun f[T1, T2]() {} fun g() { f[Int, String](); }");
@@ -1200,6 +1388,51 @@ fn lambda_assignment() {
     );
 }
 
+#[test]
+fn lambda_captures() {
+    ok("fun f(a: Int) -> () -> Int { || -> Int { a } }");
+    ok("fun f() { let a = 1; let x = |b: Int| -> Int { a + b }; }");
+    ok("fun f() { let a = 1; let x = || -> () -> Int { || -> Int { a } }; }");
+    ok("class Foo(let a: Int) { fun f() -> () -> Int { || -> Int { self.a } } }");
+    ok("fun f[T](a: T) -> () -> T { || -> T { a } }");
+    err(
+        "fun f() { var a = 1; let x = || { a = 2; }; }",
+        pos(1, 37),
+        SemError::CapturedVarReassigned("a".into()),
+    );
+    err(
+        "fun f() { var a = 1; let x = || -> Int { a }; a = 2; }",
+        pos(1, 49),
+        SemError::CapturedVarReassigned("a".into()),
+    );
+    err(
+        "fun f() { let x = || { y }; }",
+        pos(1, 24),
+        SemError::UnknownIdentifier("y".into()),
+    );
+}
+
+#[test]
+fn lambda_call() {
+    ok("fun f(x: (Int) -> Int) -> Int { x(1) }");
+    ok("fun f() { let x = |a: Int, b: Bool| {}; x(1, true); }");
+    err(
+        "fun f(x: (Int) -> Int) -> Int { x(true) }",
+        pos(1, 34),
+        SemError::LambdaArgsIncompatible("(Int) -> Int".into(), vec!["Bool".into()]),
+    );
+    err(
+        "fun f(x: (Int) -> Int) -> Int { x() }",
+        pos(1, 34),
+        SemError::LambdaArgsIncompatible("(Int) -> Int".into(), Vec::new()),
+    );
+    err(
+        "fun f(x: (Int) -> Int) -> Bool { x(1) }",
+        pos(1, 32),
+        SemError::ReturnType("Bool".into(), "Int".into()),
+    );
+}
+
 #[test]
 fn method_call_with_multiple_matching_traits() {
     err(
@@ -1232,7 +1465,55 @@ fn test_generic_ctor_without_type_params() {
         "class Foo[A, B]()
             fun test() { Foo(); }",
         pos(2, 29),
-        SemError::WrongNumberTypeParams(2, 0),
+        SemError::TypeParamNotInferred("A".into(), "Foo".into()),
+    );
+}
+
+#[test]
+fn test_infer_type_params() {
+    ok("fun id[T](x: T) -> T = x; fun f() -> Int = id(1);");
+    ok("fun id[T](x: T) -> T = x; fun f() -> String { let x = id(\"a\"); return x; }");
+    ok("fun first[T](x: Array[T]) -> T = x(0); fun f(a: Array[Int]) -> Int = first(a);");
+    ok("fun f() -> Array[Int] = arrayEmpty();");
+    ok("fun f() { let x: Array[String] = arrayEmpty(); }");
+    ok("fun f() { var x = arrayEmpty[Int](); x = arrayEmpty(); }");
+    ok("class Foo[T](let x: T) fun f() -> Int = Foo(1).x;");
+    ok("class Foo[T](let x: T) fun f() -> Foo[Object] = Foo(\"a\");");
+    ok("class Foo[T] fun f() { let x: Foo[Int] = Foo(); }");
+    ok("class Foo[T] class Bar { var foo: Foo[Int] = Foo(); }");
+    ok("class Foo[A, B](let a: A, let b: B) fun f() -> Foo[Int, Bool] = Foo(1, true);");
+    ok("fun apply[T](x: T, f: (T) -> T) -> T = f(x); fun f() -> Int = apply(1, |x: Int| -> Int { x });");
+    ok("class A { fun map[T](x: T) -> T = x; } fun f(a: A) -> Int = a.map(1);");
+    ok("fun f[T](x: T) -> Array[T] { return arrayEmpty(); }");
+
+    err(
+        "fun id[T](x: T) -> T = x; fun f() { id(nil); }",
+        pos(1, 39),
+        SemError::TypeParamNotInferred("T".into(), "id".into()),
+    );
+    err(
+        "fun f() { let x = arrayEmpty(); }",
+        pos(1, 29),
+        SemError::TypeParamNotInferred("T".into(), "arrayEmpty".into()),
+    );
+    err(
+        "fun pair[A, B](a: A) {} fun f() { pair(1); }",
+        pos(1, 39),
+        SemError::TypeParamNotInferred("B".into(), "pair".into()),
+    );
+    err(
+        "class Foo[T](let x: T) fun f() { Foo(true, 1); }",
+        pos(1, 37),
+        SemError::UnknownCtor("Foo".into(), vec!["Bool".into(), "Int".into()]),
+    );
+    err(
+        "fun same[T](a: T, b: T) {} fun f() { same(1, true); }",
+        pos(1, 42),
+        SemError::ParamTypesIncompatible(
+            "same".into(),
+            vec!["T".into(), "T".into()],
+            vec!["Int".into(), "Bool".into()],
+        ),
     );
 }
 
@@ -1265,7 +1546,7 @@ fn test_for_supports_make_iterator() {
     ok(
         "class Foo { fun makeIterator() -> FooIter { return FooIter(); } }
             class FooIter
-            impl Iterator for FooIter {
+            impl Iterator[Int] for FooIter {
                 fun hasNext() -> Bool { return false; }
                 fun next() -> Int { return 0; }
             }
@@ -1273,6 +1554,33 @@ fn test_for_supports_make_iterator() {
     );
 }
 
+#[test]
+fn test_for_var_type_from_iterator_impl() {
+    ok("fun f(x: Vec[String]) -> String { for s in x { return s; } \"\" }");
+    ok("fun f(x: HashMap[Int, String]) -> Int { for e in x { return e.0; } 0 }");
+
+    ok(
+        "class Foo[T](let value: T) { fun makeIterator() -> FooIter[T] = FooIter[T](self.value); }
+        class FooIter[T](let value: T)
+        impl[T] Iterator[T] for FooIter[T] {
+            fun hasNext() -> Bool = false;
+            fun next() -> T = self.value;
+        }
+        fun f(x: Foo[Bool]) -> Bool { for b in x { return b; } false }",
+    );
+
+    ok("fun f(x: Vec[Int]) -> String {
+            for s in x.iter().map[String](|i: Int| -> String { i.toString() }) { return s; }
+            \"\"
+        }");
+
+    err(
+        "fun f(x: Vec[String]) -> Int { for s in x { return s; } 0 }",
+        pos(1, 45),
+        SemError::ReturnType("Int".into(), "String".into()),
+    );
+}
+
 #[test]
 fn test_ctor_with_type_param() {
     err(
@@ -1510,6 +1818,49 @@ fn test_trait_object_as_argument() {
     );
 }
 
+#[test]
+fn test_trait_object_from_class() {
+    ok("trait Foo { fun bar() -> Int; }
+        class A impl Foo for A { fun bar() -> Int = 1; }
+        fun f() -> Int { let x: Foo = A(); return x.bar(); }");
+    ok("trait Foo { fun bar() -> Int; }
+        @open class A impl Foo for A { fun bar() -> Int = 1; }
+        class B: A
+        fun f(b: B) -> Foo = b;");
+    ok("trait Foo { fun bar() -> Int; }
+        class A impl Foo for A { fun bar() -> Int = 1; }
+        class B impl Foo for B { fun bar() -> Int = 2; }
+        fun f() -> Int {
+            let v = Vec[Foo]();
+            v.push(A());
+            v.push(B());
+            return v.get(0).bar() + v.get(1).bar();
+        }");
+    ok("trait Foo { fun dup() -> Self; }
+        fun f(x: Foo) -> Foo = x.dup();");
+
+    err(
+        "trait Foo {}
+        class C
+        fun f() { let x: Foo = C(); }",
+        pos(3, 19),
+        SemError::AssignType("x".into(), "Foo".into(), "C".into()),
+    );
+    err(
+        "trait Foo {}
+        impl Foo for Int {}
+        fun f() { let x: Foo = 1; }",
+        pos(3, 19),
+        SemError::AssignType("x".into(), "Foo".into(), "Int".into()),
+    );
+    err(
+        "trait Foo { fun eq(other: Self) -> Bool; }
+        fun f(x: Foo, y: Foo) -> Bool { return x.eq(y); }",
+        pos(2, 52),
+        SemError::TraitObjectSelfParam("Foo".into(), "eq".into()),
+    );
+}
+
 #[test]
 fn test_type_param_used_as_value() {
     err(
@@ -2065,3 +2416,174 @@ fn for_with_array() {
         result
     }");
 }
+
+#[test]
+fn throw_and_catch() {
+    ok("fun f() { throw Exception(\"test\"); }");
+    ok("class MyException(): Exception(\"my\")
+        fun f() {
+            try {
+                throw MyException();
+            } catch e: MyException {
+                let x: String = e.message;
+            } catch e: Stacktrace {
+                e.printStacktrace();
+            } finally {
+                println(\"finally\");
+            }
+        }");
+
+    err(
+        "fun f() { throw 1; }",
+        pos(1, 11),
+        SemError::ExceptionTypeExpected("Int".into()),
+    );
+    err(
+        "fun f() { try {} catch e: String {} }",
+        pos(1, 27),
+        SemError::ExceptionTypeExpected("String".into()),
+    );
+    err(
+        "fun f() { try {} catch e: Exception { e = Exception(\"x\"); } }",
+        pos(1, 41),
+        SemError::LetReassigned,
+    );
+}
+
+#[test]
+fn enum_with_payload() {
+    ok("enum A { V1(Int, String), V2 } fun f() -> A { A::V1(1, \"a\") }");
+    ok("enum A { V1(Int), V2 } fun f() -> A { A::V2 }");
+
+    err(
+        "enum A { V1(Int), V2 } fun f() -> A { A::V1 }",
+        pos(1, 40),
+        SemError::EnumArgsIncompatible("A".into(), "V1".into(), vec!["Int".into()], Vec::new()),
+    );
+    err(
+        "enum A { V1(Int), V2 } fun f() -> A { A::V1(true) }",
+        pos(1, 44),
+        SemError::EnumArgsIncompatible(
+            "A".into(),
+            "V1".into(),
+            vec!["Int".into()],
+            vec!["Bool".into()],
+        ),
+    );
+    err(
+        "enum A { V1(Int), V2 } fun f() -> Bool { A::V2 == A::V2 }",
+        pos(1, 48),
+        SemError::BinOpType("equals".into(), "A".into(), "A".into()),
+    );
+}
+
+#[test]
+fn match_expr() {
+    ok("enum A { V1(Int, Bool), V2 }
+        fun f(a: A) -> Int {
+            match a {
+                A::V1(x, _) => x,
+                V2 => 0,
+            }
+        }");
+    ok("fun f(x: Int) -> Int { match x { 1 => 2, -1 => 3, _ => 4 } }");
+    ok("fun f(x: Bool) -> Int { match x { true => 1, false => 0 } }");
+    ok("fun f(x: Int) { match x { 1 => { return; } _ => {} } }");
+
+    err(
+        "fun f(x: String) -> Int { match x { _ => 1 } }",
+        pos(1, 33),
+        SemError::MatchTypeUnsupported("String".into()),
+    );
+    err(
+        "fun f(x: Int) -> Int { match x { true => 1, _ => 2 } }",
+        pos(1, 34),
+        SemError::MatchPatternTypeMismatch("Int".into(), "Bool".into()),
+    );
+    err(
+        "enum A { V1(Int), V2 } fun f(a: A) -> Int { match a { A::V1 => 1, _ => 2 } }",
+        pos(1, 55),
+        SemError::MatchPatternWrongNumberOfParams("V1".into(), 1, 0),
+    );
+    err(
+        "enum A { V1, V2 } enum B { V1 } fun f(a: A) -> Int { match a { B::V1 => 1, _ => 2 } }",
+        pos(1, 64),
+        SemError::MatchPatternTypeMismatch("A".into(), "B".into()),
+    );
+    err(
+        "enum A { V1, V2 } fun f(a: A) -> Int { match a { A::V3 => 1, _ => 2 } }",
+        pos(1, 50),
+        SemError::UnknownEnumValue("V3".into()),
+    );
+    err(
+        "enum A { V1, V2 } fun f(a: A) -> Int { match a { A::V1 => 1 } }",
+        pos(1, 40),
+        SemError::MatchNotExhaustive(vec!["V2".into()]),
+    );
+    err(
+        "fun f(x: Int) -> Int { match x { 1 => 2 } }",
+        pos(1, 24),
+        SemError::MatchNotExhaustive(vec!["_".into()]),
+    );
+    err(
+        "fun f(x: Int) -> Int { match x { 1 => 2, _ => true } }",
+        pos(1, 47),
+        SemError::MatchBranchTypesIncompatible("Int".into(), "Bool".into()),
+    );
+    err(
+        "fun f(x: Int) -> Int { match x { _ => 1 } y }",
+        pos(1, 43),
+        SemError::UnknownIdentifier("y".into()),
+    );
+    err(
+        "enum A { V1(Int) } fun f(a: A) { match a { A::V1(x) => { x = 1; } } }",
+        pos(1, 60),
+        SemError::LetReassigned,
+    );
+}
+
+#[test]
+fn parse_errors_with_sem_errors() {
+    errors(
+        "fun f() { let x = ; } fun g() -> Int { return false; }",
+        &[
+            (
+                pos(1, 19),
+                SemError::ParseError(ParseError::ExpectedFactor(";".into())),
+            ),
+            (
+                pos(1, 40),
+                SemError::ReturnType("Int".into(), "Bool".into()),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn type_unknown_names_suggestion() {
+    err_with_note(
+        "class Foo(let count: Int) fun f(x: Foo) -> Int { return x.cuont; }",
+        pos(1, 58),
+        SemError::UnknownField("cuont".into(), "Foo".into()),
+        "did you mean `count`?",
+    );
+    err_with_note(
+        "class Foo { fun length() -> Int { return 0; } }
+         fun f(x: Foo) -> Int { return x.lenght(); }",
+        pos(2, 48),
+        SemError::UnknownMethod("Foo".into(), "lenght".into(), Vec::new()),
+        "did you mean `length`?",
+    );
+    err_with_note(
+        "fun f(x: Strng) {}",
+        pos(1, 10),
+        SemError::UnknownType("Strng".into()),
+        "did you mean `String`?",
+    );
+    err_with_note(
+        "enum Color { Red, Green } fun f() -> Color { return Color::Gren; }",
+        pos(1, 58),
+        SemError::UnknownEnumValue("Gren".into()),
+        "did you mean `Green`?",
+    );
+}
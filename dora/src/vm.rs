@@ -14,41 +14,50 @@ use crate::error::diag::Diagnostic;
 use crate::gc::{Address, Gc};
 use crate::object::{Ref, Testing};
 use crate::safepoint;
-use crate::stack::DoraToNativeInfo;
+use crate::stack::{self, DoraToNativeInfo};
 use crate::stdlib;
-use crate::sym::TermSym::SymFct;
-use crate::sym::{SymTable, TermSym};
+use crate::sym::{SymLevel, SymTable};
 use crate::threads::{Threads, STACK_SIZE, THREAD};
 use crate::ty::{BuiltinType, LambdaTypes, TypeList, TypeLists, TypeParamId};
 use crate::utils::GrowableVec;
-use crate::vm::module::{Module, ModuleDef, ModuleId};
+use crate::vm::module::{Module, ModuleId};
 
 use dora_parser::ast;
 use dora_parser::interner::*;
 use dora_parser::lexer::File;
 use dora_parser::parser::NodeIdGenerator;
 
+pub use self::alias::{AliasData, AliasId};
+pub use self::capability::{Capability, CapabilitySet};
 pub use self::class::{
-    find_field_in_class, find_method_in_class, find_methods_in_class, Class, ClassDef, ClassDefId,
-    ClassId, TypeParam,
+    field_names_in_class, find_field_in_class, find_method_in_class, find_methods_in_class,
+    find_trait_impl_in_class, method_names_in_class, Class, ClassDef, ClassDefId, ClassId,
+    TypeParam,
 };
 pub use self::cnst::{ConstData, ConstId, ConstValue};
 pub use self::enums::{
-    find_methods_in_enum, EnumData, EnumDef, EnumDefId, EnumId, EnumLayout, EnumVariant,
+    find_methods_in_enum, EnumData, EnumDef, EnumDefId, EnumDefVariant, EnumId, EnumLayout,
+    EnumVariant,
 };
 pub use self::extension::{ExtensionData, ExtensionId};
 pub use self::fct::{Fct, FctDef, FctDefId, FctId, FctKind, FctParent, Intrinsic};
 pub use self::field::{Field, FieldDef, FieldId};
 pub use self::global::{GlobalData, GlobalId};
 pub use self::impls::{ImplData, ImplId};
-pub use self::src::{CallType, ConvInfo, FctSrc, ForTypeInfo, IdentType, NodeMap, Var, VarId};
+pub use self::namespace::{namespace_name, NamespaceData, NamespaceId, Visibility};
+pub use self::src::{
+    CallType, Capture, ConvInfo, FctSrc, ForTypeInfo, IdentType, NodeMap, Var, VarId,
+};
 pub use self::strct::{
-    StructData, StructDef, StructDefId, StructFieldData, StructFieldDef, StructId,
+    field_names_in_struct, find_field_in_struct, StructData, StructDef, StructDefId,
+    StructFieldData, StructFieldDef, StructFieldId, StructId,
 };
 pub use self::traits::{TraitData, TraitId};
-pub use self::tuple::{ensure_tuple, TupleId, Tuples};
+pub use self::tuple::{ensure_tuple, layout_deferred_tuples, TupleId, Tuples};
 pub use self::vip::{KnownClasses, KnownElements, KnownFunctions};
 
+mod alias;
+mod capability;
 pub mod class;
 mod cnst;
 mod enums;
@@ -58,6 +67,7 @@ mod field;
 mod global;
 mod impls;
 pub mod module;
+mod namespace;
 mod src;
 mod strct;
 mod traits;
@@ -90,8 +100,13 @@ pub struct VM<'ast> {
     pub ast: &'ast ast::Ast,
     pub id_generator: NodeIdGenerator,
     pub files: Vec<File>,
+    pub trusted_files: usize, // stdlib & boots files, not subject to capability checks
+    pub check_trusted_bodies: bool, // the language server skips the bodies of trusted files
     pub diag: Mutex<Diagnostic>,
     pub sym: Mutex<SymTable>,
+    pub namespaces: Vec<NamespaceData>, // stores all namespaces declared with `package`
+    pub file_namespaces: Vec<Option<NamespaceId>>, // namespace of each file, None for the root namespace
+    pub file_scopes: Vec<Arc<SymLevel>>, // top-level names of each file's namespace and imports
     pub vips: KnownElements,
     pub consts: GrowableVec<Mutex<ConstData>>, // stores all const definitions
     pub structs: GrowableVec<Mutex<StructData>>, // stores all struct source definitions
@@ -101,7 +116,6 @@ pub struct VM<'ast> {
     pub extensions: Vec<RwLock<ExtensionData>>, // stores all extension definitions
     pub tuples: Mutex<Tuples>,                 // stores all tuple definitions
     pub modules: GrowableVec<RwLock<Module>>,  // stores all module source definitions
-    pub module_defs: GrowableVec<RwLock<ModuleDef>>, // stores all module definitions
     pub fcts: GrowableVec<RwLock<Fct<'ast>>>,  // stores all function source definitions
     pub jit_fcts: GrowableVec<JitFct>,         // stores all function implementations
     pub fct_defs: GrowableVec<RwLock<FctDef>>, // stores all function definitions
@@ -109,6 +123,7 @@ pub struct VM<'ast> {
     pub enum_defs: GrowableVec<RwLock<EnumDef>>, // stores all enum definitions
     pub traits: Vec<RwLock<TraitData>>,        // stores all trait definitions
     pub impls: Vec<RwLock<ImplData>>,          // stores all impl definitions
+    pub aliases: Vec<AliasData<'ast>>,         // stores all type alias definitions
     pub code_map: Mutex<CodeMap>,              // stores all compiled functions
     pub globals: GrowableVec<RwLock<GlobalData>>, // stores all global variables
     pub gc: Gc,                                // garbage collector
@@ -118,6 +133,8 @@ pub struct VM<'ast> {
     pub compile_stub: Mutex<Address>,
     pub dora_stub: Mutex<Address>,
     pub trap_stub: Mutex<Address>,
+    pub throw_stub: Mutex<Address>,
+    pub catch_stub: Mutex<Address>,
     pub guard_check_stub: Mutex<Address>,
     pub threads: Threads,
 }
@@ -133,6 +150,8 @@ impl<'ast> VM<'ast> {
         let vm = Box::new(VM {
             args,
             files: Vec::new(),
+            trusted_files: 0,
+            check_trusted_bodies: true,
             consts: GrowableVec::new(),
             structs: GrowableVec::new(),
             struct_defs: GrowableVec::new(),
@@ -141,11 +160,11 @@ impl<'ast> VM<'ast> {
             extensions: Vec::new(),
             tuples: Mutex::new(Tuples::new()),
             modules: GrowableVec::new(),
-            module_defs: GrowableVec::new(),
             enums: Vec::new(),
             enum_defs: GrowableVec::new(),
             traits: Vec::new(),
             impls: Vec::new(),
+            aliases: Vec::new(),
             globals: GrowableVec::new(),
             interner: Interner::new(),
             vips: KnownElements {
@@ -165,6 +184,12 @@ impl<'ast> VM<'ast> {
 
                 cls: KnownClasses {
                     string_buffer: empty_class_id,
+                    exception: empty_class_id,
+                    division_by_zero_exception: empty_class_id,
+                    assertion_exception: empty_class_id,
+                    index_out_of_bounds_exception: empty_class_id,
+                    nil_pointer_exception: empty_class_id,
+                    cast_exception: empty_class_id,
                 },
 
                 fct: KnownFunctions {
@@ -198,6 +223,9 @@ impl<'ast> VM<'ast> {
             id_generator: NodeIdGenerator::new(),
             diag: Mutex::new(Diagnostic::new()),
             sym: Mutex::new(SymTable::new()),
+            namespaces: Vec::new(),
+            file_namespaces: Vec::new(),
+            file_scopes: Vec::new(),
             fcts: GrowableVec::new(),
             jit_fcts: GrowableVec::new(),
             fct_defs: GrowableVec::new(),
@@ -208,6 +236,8 @@ impl<'ast> VM<'ast> {
             compile_stub: Mutex::new(Address::null()),
             dora_stub: Mutex::new(Address::null()),
             trap_stub: Mutex::new(Address::null()),
+            throw_stub: Mutex::new(Address::null()),
+            catch_stub: Mutex::new(Address::null()),
             guard_check_stub: Mutex::new(Address::null()),
             threads: Threads::new(),
         });
@@ -272,7 +302,7 @@ impl<'ast> VM<'ast> {
         code_map.insert(start, end, desc);
     }
 
-    pub fn add_fct(&mut self, mut fct: Fct<'ast>) -> FctId {
+    pub fn add_fct(&self, mut fct: Fct<'ast>) -> FctId {
         let mut fcts = self.fcts.lock();
         let fctid = FctId(fcts.len());
 
@@ -283,22 +313,6 @@ impl<'ast> VM<'ast> {
         fctid
     }
 
-    pub fn add_fct_to_sym(&mut self, fct: Fct<'ast>) -> Result<FctId, TermSym> {
-        let name = fct.name;
-        let fctid = self.add_fct(fct);
-
-        let mut sym = self.sym.lock();
-
-        match sym.get_term(name) {
-            Some(sym) => Err(sym),
-            None => {
-                assert!(sym.insert_term(name, SymFct(fctid)).is_none());
-
-                Ok(fctid)
-            }
-        }
-    }
-
     pub fn add_fct_def(&self, mut fct_def: FctDef) -> FctDefId {
         let mut fct_defs = self.fct_defs.lock();
         let fid = FctDefId(fct_defs.len());
@@ -381,6 +395,34 @@ impl<'ast> VM<'ast> {
         }
     }
 
+    #[cfg(test)]
+    pub fn trait_method_def_by_name(
+        &self,
+        trait_name: &'static str,
+        function_name: &'static str,
+    ) -> Option<FctDefId> {
+        let trait_name = self.interner.intern(trait_name);
+        let function_name = self.interner.intern(function_name);
+
+        let trait_id = self
+            .sym
+            .lock()
+            .get_trait(trait_name)
+            .expect("trait not found");
+        let xtrait = self.traits[trait_id].read();
+
+        let fct_id = xtrait.find_method(self, function_name, false)?;
+        let fct = self.fcts.idx(fct_id);
+        let fct = fct.read();
+        let fct_def = fct
+            .specializations
+            .read()
+            .get(&(TypeList::Empty, TypeList::Empty))
+            .cloned();
+
+        fct_def
+    }
+
     pub fn cls_def_by_name(&self, name: &'static str) -> ClassDefId {
         use crate::semck::specialize::specialize_class_id;
 
@@ -581,6 +623,44 @@ impl<'ast> VM<'ast> {
         *trap_stub_address
     }
 
+    pub fn throw_stub(&self) -> Address {
+        let mut throw_stub_address = self.throw_stub.lock();
+
+        if throw_stub_address.is_null() {
+            let ifct = NativeFct {
+                ptr: Address::from_ptr(stack::throw as *const u8),
+                args: &[BuiltinType::Ptr],
+                return_type: BuiltinType::Unit,
+                desc: NativeFctDescriptor::ThrowStub,
+            };
+            let jit_fct_id = native_stub::generate(self, ifct, false);
+            let jit_fct = self.jit_fcts.idx(jit_fct_id);
+            let fct_ptr = jit_fct.instruction_start();
+            *throw_stub_address = fct_ptr;
+        }
+
+        *throw_stub_address
+    }
+
+    pub fn catch_stub(&self) -> Address {
+        let mut catch_stub_address = self.catch_stub.lock();
+
+        if catch_stub_address.is_null() {
+            let ifct = NativeFct {
+                ptr: Address::from_ptr(stack::catch as *const u8),
+                args: &[],
+                return_type: BuiltinType::Ptr,
+                desc: NativeFctDescriptor::CatchStub,
+            };
+            let jit_fct_id = native_stub::generate(self, ifct, false);
+            let jit_fct = self.jit_fcts.idx(jit_fct_id);
+            let fct_ptr = jit_fct.instruction_start();
+            *catch_stub_address = fct_ptr;
+        }
+
+        *catch_stub_address
+    }
+
     pub fn guard_check_stub(&self) -> Address {
         let mut guard_check_stub_address = self.guard_check_stub.lock();
 
@@ -603,6 +683,59 @@ impl<'ast> VM<'ast> {
     pub fn file(&self, idx: FileId) -> &File {
         &self.files[idx.0 as usize]
     }
+
+    pub fn is_trusted_file(&self, idx: FileId) -> bool {
+        (idx.0 as usize) < self.trusted_files
+    }
+
+    // bodies of functions are type checked unless they belong to a trusted
+    // file and the VM is only used for analyzing other files
+    pub fn needs_body_check(&self, fct: &Fct) -> bool {
+        fct.is_src() && (self.check_trusted_bodies || !self.is_trusted_file(fct.file))
+    }
+
+    pub fn file_namespace(&self, idx: FileId) -> Option<NamespaceId> {
+        self.file_namespaces
+            .get(idx.0 as usize)
+            .cloned()
+            .unwrap_or(None)
+    }
+
+    pub fn find_namespace(&self, path: &[Name]) -> Option<NamespaceId> {
+        self.namespaces
+            .iter()
+            .find(|namespace| namespace.path == path)
+            .map(|namespace| namespace.id)
+    }
+
+    // resolves top-level names the way the given file sees them: the names of
+    // its namespace and its imports shadow the root namespace
+    pub fn with_file_scope<F, R>(&self, idx: FileId, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let previous = self.enter_file_scope(idx);
+        let result = f();
+        self.leave_file_scope(previous);
+
+        result
+    }
+
+    // like with_file_scope, returns the previous scope for leave_file_scope
+    pub fn enter_file_scope(&self, idx: FileId) -> Option<Arc<SymLevel>> {
+        let scope = self.file_scopes.get(idx.0 as usize).cloned();
+        self.sym.lock().set_scope(scope)
+    }
+
+    pub fn leave_file_scope(&self, previous: Option<Arc<SymLevel>>) {
+        self.sym.lock().set_scope(previous);
+    }
+
+    // returns the capability of the given function if it was denied
+    pub fn denied_capability(&self, fct: &Fct) -> Option<Capability> {
+        let denied = self.args.denied_capabilities();
+        fct.capability.filter(|&cap| denied.contains(cap))
+    }
 }
 
 unsafe impl<'ast> Sync for VM<'ast> {}
@@ -625,6 +758,7 @@ pub enum Trap {
     CAST,
     OOM,
     STACK_OVERFLOW,
+    CAPABILITY,
 }
 
 impl Trap {
@@ -637,6 +771,20 @@ impl Trap {
             Trap::CAST => 5,
             Trap::OOM => 6,
             Trap::STACK_OVERFLOW => 7,
+            Trap::CAPABILITY => 9,
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            Trap::DIV0 => "division by 0",
+            Trap::ASSERT => "assert failed",
+            Trap::INDEX_OUT_OF_BOUNDS => "array index out of bounds",
+            Trap::NIL => "nil check failed",
+            Trap::CAST => "cast failed",
+            Trap::OOM => "out of memory",
+            Trap::STACK_OVERFLOW => "stack overflow",
+            Trap::CAPABILITY => "capability denied",
         }
     }
 
@@ -649,6 +797,7 @@ impl Trap {
             5 => Some(Trap::CAST),
             6 => Some(Trap::OOM),
             7 => Some(Trap::STACK_OVERFLOW),
+            9 => Some(Trap::CAPABILITY),
             _ => None,
         }
     }
@@ -0,0 +1,36 @@
+use std::ops::Index;
+
+use dora_parser::ast;
+use dora_parser::interner::Name;
+use dora_parser::lexer::position::Position;
+
+use crate::vm::FileId;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AliasId(u32);
+
+impl From<u32> for AliasId {
+    fn from(data: u32) -> AliasId {
+        AliasId(data)
+    }
+}
+
+impl<'ast> Index<AliasId> for Vec<AliasData<'ast>> {
+    type Output = AliasData<'ast>;
+
+    fn index(&self, index: AliasId) -> &AliasData<'ast> {
+        &self[index.0 as usize]
+    }
+}
+
+// Type aliases are not resolved on their own, the aliased type is read again
+// with the type arguments substituted wherever the alias is used.
+#[derive(Debug)]
+pub struct AliasData<'ast> {
+    pub id: AliasId,
+    pub file: FileId,
+    pub pos: Position,
+    pub name: Name,
+    pub type_params: Vec<Name>,
+    pub ast: &'ast ast::Alias,
+}
@@ -0,0 +1,109 @@
+// groups native functions by the kind of access they grant to a program,
+// capabilities can be denied with `--deny=<cap,...>`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Capability {
+    Process,
+    Threads,
+    Filesystem,
+    Network,
+    Reflection,
+}
+
+impl Capability {
+    pub fn all() -> &'static [Capability] {
+        &[
+            Capability::Process,
+            Capability::Threads,
+            Capability::Filesystem,
+            Capability::Network,
+            Capability::Reflection,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Process => "process",
+            Capability::Threads => "threads",
+            Capability::Filesystem => "filesystem",
+            Capability::Network => "network",
+            Capability::Reflection => "reflection",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Capability> {
+        Capability::all()
+            .iter()
+            .find(|cap| cap.name() == name)
+            .cloned()
+    }
+
+    fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CapabilitySet(u8);
+
+impl CapabilitySet {
+    pub fn empty() -> CapabilitySet {
+        CapabilitySet(0)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, cap: Capability) {
+        self.0 |= cap.bit();
+    }
+
+    pub fn contains(self, cap: Capability) -> bool {
+        self.0 & cap.bit() != 0
+    }
+
+    pub fn parse(value: &str) -> Result<CapabilitySet, String> {
+        let mut set = CapabilitySet::empty();
+
+        for name in value.split(',').map(|name| name.trim()) {
+            if name.is_empty() {
+                continue;
+            }
+
+            match Capability::from_name(name) {
+                Some(cap) => set.insert(cap),
+                None => return Err(format!("unknown capability `{}`", name)),
+            }
+        }
+
+        Ok(set)
+    }
+}
+
+impl From<&[Capability]> for CapabilitySet {
+    fn from(caps: &[Capability]) -> CapabilitySet {
+        let mut set = CapabilitySet::empty();
+
+        for &cap in caps {
+            set.insert(cap);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_capabilities() {
+        let set = CapabilitySet::parse("process,threads").unwrap();
+        assert!(set.contains(Capability::Process));
+        assert!(set.contains(Capability::Threads));
+        assert!(!set.contains(Capability::Reflection));
+
+        assert!(CapabilitySet::parse("").unwrap().is_empty());
+        assert!(CapabilitySet::parse("process,foo").is_err());
+    }
+}
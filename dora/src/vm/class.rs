@@ -10,8 +10,9 @@ use crate::size::InstanceSize;
 use crate::sym::SymLevel;
 use crate::ty::{BuiltinType, TypeList};
 use crate::utils::GrowableVec;
+use crate::vm::module::ModuleId;
 use crate::vm::VM;
-use crate::vm::{ExtensionId, FctId, Field, FieldDef, FieldId, FileId, ImplId, TraitId};
+use crate::vm::{ExtensionId, FctDefId, FctId, Field, FieldDef, FieldId, FileId, ImplId, TraitId};
 use crate::vtable::VTableBox;
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
@@ -256,6 +257,29 @@ pub fn find_method_in_class(
     }
 }
 
+pub fn find_trait_impl_in_class(
+    vm: &VM,
+    mut class: BuiltinType,
+    trait_id: TraitId,
+) -> Option<(BuiltinType, ImplId)> {
+    loop {
+        let cls_id = class.cls_id(vm)?;
+        let cls = vm.classes.idx(cls_id);
+        let cls = cls.read();
+
+        if let Some(impl_id) = cls.find_impl_for_trait(vm, trait_id) {
+            return Some((class, impl_id));
+        }
+
+        if let Some(parent_class) = cls.parent_class {
+            let type_list = class.type_params(vm);
+            class = replace_type_param(vm, parent_class, &type_list, &TypeList::empty(), None);
+        } else {
+            return None;
+        }
+    }
+}
+
 pub fn find_methods_in_class(
     vm: &VM,
     object_type: BuiltinType,
@@ -330,7 +354,9 @@ pub fn find_methods_in_class(
         for &impl_id in &cls.impls {
             let ximpl = vm.impls[impl_id].read();
 
-            if ximpl.class_ty.type_params(vm) != class_type.type_params(vm) {
+            if ximpl.type_params.is_empty()
+                && ximpl.class_ty.type_params(vm) != class_type.type_params(vm)
+            {
                 continue;
             }
 
@@ -355,6 +381,70 @@ pub fn find_methods_in_class(
     candidates
 }
 
+// names of all fields of the class and its super classes, e.g. for suggestions
+pub fn field_names_in_class(vm: &VM, class: BuiltinType) -> Vec<Name> {
+    let mut names = Vec::new();
+    let mut cls_id = class.cls_id(vm);
+
+    while let Some(id) = cls_id {
+        let cls = vm.classes.idx(id);
+        let cls = cls.read();
+
+        names.extend(cls.fields.iter().map(|field| field.name));
+        cls_id = cls.parent_class.and_then(|parent| parent.cls_id(vm));
+    }
+
+    names
+}
+
+// names of all methods callable on the class, including methods of super classes,
+// extensions and trait implementations
+pub fn method_names_in_class(vm: &VM, class: BuiltinType, is_static: bool) -> Vec<Name> {
+    let mut names = Vec::new();
+    let mut cls_id = class.cls_id(vm);
+
+    while let Some(id) = cls_id {
+        let cls = vm.classes.idx(id);
+        let cls = cls.read();
+
+        let mut methods = cls.methods.clone();
+
+        for &impl_id in &cls.impls {
+            methods.extend(vm.impls[impl_id].read().methods.iter().cloned());
+        }
+
+        for method in methods {
+            let method = vm.fcts.idx(method);
+            let method = method.read();
+
+            if method.is_static == is_static {
+                names.push(method.name);
+            }
+        }
+
+        cls_id = cls.parent_class.and_then(|parent| parent.cls_id(vm));
+    }
+
+    if let Some(cls_id) = class.cls_id(vm) {
+        let cls = vm.classes.idx(cls_id);
+        let cls = cls.read();
+
+        for &extension_id in &cls.extensions {
+            let extension = vm.extensions[extension_id].read();
+
+            let table = if is_static {
+                &extension.static_names
+            } else {
+                &extension.instance_names
+            };
+
+            names.extend(table.keys().cloned());
+        }
+    }
+
+    names
+}
+
 #[derive(Clone, Debug)]
 pub struct TypeParam {
     pub name: Name,
@@ -401,6 +491,8 @@ pub struct ClassDef {
     pub size: InstanceSize,
     pub ref_fields: Vec<i32>,
     pub vtable: Option<VTableBox>,
+    pub lambda: Option<FctDefId>, // set for closure objects
+    pub module: Option<ModuleId>, // set for module objects
 }
 
 impl ClassDef {
@@ -421,6 +513,13 @@ impl ClassDef {
             };
 
             format!("{}<{}>", name, params)
+        } else if self.lambda.is_some() {
+            "<closure>".into()
+        } else if let Some(module_id) = self.module {
+            let module = vm.modules.idx(module_id);
+            let module = module.read();
+
+            vm.interner.str(module.name).to_string()
         } else {
             "<Unknown>".into()
         }
@@ -10,7 +10,7 @@ use dora_parser::lexer::position::Position;
 
 use crate::ty::{BuiltinType, TypeList};
 use crate::utils::GrowableVec;
-use crate::vm::{ExtensionId, FctId, FileId, TypeParam, VM};
+use crate::vm::{ClassDefId, ExtensionId, FctId, FileId, TypeParam, VM};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct EnumId(u32);
@@ -71,9 +71,10 @@ pub struct EnumDef {
     pub size: i32,
     pub align: i32,
     pub layout: EnumLayout,
+    pub variants: Vec<EnumDefVariant>,
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum EnumLayout {
     Int,
     Ptr,
@@ -83,6 +84,7 @@ pub enum EnumLayout {
 #[derive(Debug)]
 pub struct EnumDefVariant {
     pub types: Vec<BuiltinType>,
+    pub cls_def_id: Option<ClassDefId>,
 }
 
 pub fn find_methods_in_enum(
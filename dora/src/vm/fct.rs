@@ -13,7 +13,9 @@ use crate::gc::Address;
 use crate::ty::BuiltinType;
 use crate::utils::GrowableVec;
 use crate::vm::module::ModuleId;
-use crate::vm::{ClassId, ExtensionId, FctSrc, FileId, ImplId, TraitId, TypeParam, VM};
+use crate::vm::{
+    Capability, ClassDefId, ClassId, ExtensionId, FctSrc, FileId, ImplId, TraitId, TypeParam, VM,
+};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
 pub struct FctId(pub usize);
@@ -54,6 +56,7 @@ pub struct Fct<'ast> {
     pub use_cannon: bool,
     pub internal: bool,
     pub internal_resolved: bool,
+    pub capability: Option<Capability>,
     pub overrides: Option<FctId>,
     pub param_types: Vec<BuiltinType>,
     pub return_type: BuiltinType,
@@ -97,6 +100,21 @@ impl<'ast> Fct<'ast> {
         }
     }
 
+    // the constructor of a module allocates the module object itself
+    pub fn is_module_constructor(&self) -> bool {
+        match self.parent {
+            FctParent::Module(_) => self.is_constructor,
+            _ => false,
+        }
+    }
+
+    pub fn is_lambda(&self) -> bool {
+        match self.parent {
+            FctParent::Function(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn trait_id(&self) -> TraitId {
         match self.parent {
             FctParent::Trait(traitid) => traitid,
@@ -193,10 +211,14 @@ impl<'ast> Fct<'ast> {
     pub fn has_self(&self) -> bool {
         match self.parent {
             FctParent::Class(_)
+            | FctParent::Module(_)
             | FctParent::Trait(_)
             | FctParent::Impl(_)
             | FctParent::Extension(_) => !self.is_static,
 
+            // the closure object is passed as hidden first parameter
+            FctParent::Function(_) => true,
+
             _ => false,
         }
     }
@@ -221,6 +243,7 @@ pub enum FctParent {
     Module(ModuleId),
     Impl(ImplId),
     Extension(ExtensionId),
+    Function(FctId),
     None,
 }
 
@@ -245,6 +268,13 @@ impl FctParent {
             _ => unreachable!(),
         }
     }
+
+    pub fn fct_id(&self) -> FctId {
+        match *self {
+            FctParent::Function(id) => id,
+            _ => unreachable!(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -568,6 +598,7 @@ pub struct FctDef {
     pub fct_id: FctId,
     pub cls_type_params: TypeList,
     pub fct_type_params: TypeList,
+    pub closure_class: Option<ClassDefId>, // class of closure objects for lambdas
 }
 
 impl FctDef {
@@ -624,6 +655,7 @@ impl FctDef {
             fct_id: fct.id,
             cls_type_params: cls_type_params.clone(),
             fct_type_params: fct_type_params.clone(),
+            closure_class: None,
         });
 
         let old = specializations.insert(
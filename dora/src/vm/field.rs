@@ -2,6 +2,7 @@ use std::ops::{Index, IndexMut};
 
 use crate::ty::BuiltinType;
 use dora_parser::interner::Name;
+use dora_parser::lexer::position::Position;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FieldId(usize);
@@ -26,6 +27,7 @@ impl From<usize> for FieldId {
 pub struct Field {
     pub id: FieldId,
     pub name: Name,
+    pub pos: Position,
     pub ty: BuiltinType,
     pub offset: i32,
     pub reassignable: bool,
@@ -4,8 +4,8 @@ use std::ops::Index;
 
 use dora_parser::lexer::position::Position;
 
-use crate::ty::BuiltinType;
-use crate::vm::{ClassId, FctId, FileId, TraitId, VM};
+use crate::ty::{BuiltinType, TypeList};
+use crate::vm::{ClassId, FctId, FileId, TraitId, TypeParam, VM};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ImplId(u32);
@@ -21,7 +21,9 @@ pub struct ImplData {
     pub id: ImplId,
     pub file: FileId,
     pub pos: Position,
+    pub type_params: Vec<TypeParam>,
     pub trait_id: Option<TraitId>,
+    pub trait_type_params: TypeList,
     pub class_ty: BuiltinType,
     pub methods: Vec<FctId>,
 }
@@ -1,16 +1,15 @@
 use parking_lot::RwLock;
 use std::sync::Arc;
 
-use crate::semck::specialize::replace_type_param;
-use crate::size::InstanceSize;
-use crate::ty::{BuiltinType, TypeList};
+use crate::ty::BuiltinType;
 use crate::utils::GrowableVec;
-use crate::vm::{FctId, Field, FieldDef, FileId, TraitId, VM};
+use crate::vm::{
+    field_names_in_class, find_field_in_class, find_methods_in_class, method_names_in_class,
+    ClassDefId, FctId, Field, FieldId, FileId, GlobalId, TraitId, VM,
+};
 
-use crate::vtable::VTableBox;
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
-use std::collections::HashSet;
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub struct ModuleId(usize);
@@ -39,8 +38,6 @@ impl GrowableVec<RwLock<Module>> {
     }
 }
 
-pub static DISPLAY_SIZE: usize = 6;
-
 #[derive(Debug)]
 pub struct Module {
     pub id: ModuleId,
@@ -59,6 +56,10 @@ pub struct Module {
     pub virtual_fcts: Vec<FctId>,
 
     pub traits: Vec<TraitId>,
+
+    // the module object is created by the constructor on first use
+    pub global_id: GlobalId,
+    pub class_def_id: Option<ClassDefId>,
 }
 
 pub fn find_methods_in_module(
@@ -66,84 +67,78 @@ pub fn find_methods_in_module(
     object_type: BuiltinType,
     name: Name,
 ) -> Vec<(BuiltinType, FctId)> {
-    let mut ignores = HashSet::new();
-
-    let mut module_type = object_type;
+    let module_id = object_type.module_id().expect("no module");
+    let module = vm.modules.idx(module_id);
+    let module = module.read();
 
-    loop {
-        let module_id = module_type.module_id().expect("no class");
-        let module = vm.modules.idx(module_id);
-        let module = module.read();
+    for &method in &module.methods {
+        let method = vm.fcts.idx(method);
+        let method = method.read();
 
-        for &method in &module.methods {
-            let method = vm.fcts.idx(method);
-            let method = method.read();
-
-            if method.name == name {
-                if let Some(overrides) = method.overrides {
-                    ignores.insert(overrides);
-                }
-
-                if !ignores.contains(&method.id) {
-                    return vec![(module_type, method.id)];
-                }
-            }
-        }
-
-        if let Some(parent_class) = module.parent_class {
-            let type_list = module_type.type_params(vm);
-            module_type =
-                replace_type_param(vm, parent_class, &type_list, &TypeList::empty(), None);
-        } else {
-            break;
+        if method.name == name && !method.is_static {
+            return vec![(object_type, method.id)];
         }
     }
 
-    Vec::new()
+    // methods of the super class are called on the module object
+    if let Some(parent_class) = module.parent_class {
+        find_methods_in_class(vm, parent_class, name, false)
+    } else {
+        Vec::new()
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct ModuleDefId(usize);
+// names of all methods of the module and its super classes, e.g. for suggestions
+pub fn method_names_in_module(vm: &VM, object_type: BuiltinType) -> Vec<Name> {
+    let module_id = object_type.module_id().expect("no module");
+    let module = vm.modules.idx(module_id);
+    let module = module.read();
 
-impl ModuleDefId {
-    pub fn to_usize(self) -> usize {
-        self.0
-    }
-}
+    let mut names: Vec<Name> = module
+        .methods
+        .iter()
+        .map(|&method| vm.fcts.idx(method).read().name)
+        .collect();
 
-impl From<usize> for ModuleDefId {
-    fn from(data: usize) -> ModuleDefId {
-        ModuleDefId(data)
+    if let Some(parent_class) = module.parent_class {
+        names.extend(method_names_in_class(vm, parent_class, false));
     }
+
+    names
 }
 
-impl GrowableVec<RwLock<ModuleDef>> {
-    pub fn idx(&self, index: ModuleDefId) -> Arc<RwLock<ModuleDef>> {
-        self.idx_usize(index.0)
+// finds the field in the module or its super classes
+pub fn find_field_in_module(
+    vm: &VM,
+    object_type: BuiltinType,
+    name: Name,
+) -> Option<(BuiltinType, FieldId)> {
+    let module_id = object_type.module_id().expect("no module");
+    let module = vm.modules.idx(module_id);
+    let module = module.read();
+
+    for field in &module.fields {
+        if field.name == name {
+            return Some((object_type, field.id));
+        }
     }
-}
 
-#[derive(Debug)]
-pub struct ModuleDef {
-    pub id: ModuleDefId,
-    pub mod_id: Option<ModuleId>,
-    pub parent_id: Option<ModuleDefId>,
-    pub fields: Vec<FieldDef>,
-    pub size: InstanceSize,
-    pub ref_fields: Vec<i32>,
-    pub vtable: Option<VTableBox>,
+    module
+        .parent_class
+        .and_then(|parent_class| find_field_in_class(vm, parent_class, name))
 }
 
-impl ModuleDef {
-    pub fn name(&self, vm: &VM) -> String {
-        if let Some(module_id) = self.mod_id {
-            let module = vm.modules.idx(module_id);
-            let module = module.read();
-            let name = vm.interner.str(module.name);
+// names of all fields of the module and its super classes, e.g. for suggestions
+pub fn field_names_in_module(vm: &VM, object_type: BuiltinType) -> Vec<Name> {
+    let module_id = object_type.module_id().expect("no module");
+    let module = vm.modules.idx(module_id);
+    let module = module.read();
 
-            format!("{}", name)
-        } else {
-            "<Unknown>".into()
-        }
+    let mut names: Vec<Name> = module.fields.iter().map(|field| field.name).collect();
+
+    if let Some(parent_class) = module.parent_class {
+        names.extend(field_names_in_class(vm, parent_class));
     }
+
+    names
 }
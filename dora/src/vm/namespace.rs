@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+use dora_parser::interner::{Interner, Name};
+
+use crate::sym::SymLevel;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NamespaceId(u32);
+
+impl From<u32> for NamespaceId {
+    fn from(data: u32) -> NamespaceId {
+        NamespaceId(data)
+    }
+}
+
+impl Index<NamespaceId> for Vec<NamespaceData> {
+    type Output = NamespaceData;
+
+    fn index(&self, index: NamespaceId) -> &NamespaceData {
+        &self[index.0 as usize]
+    }
+}
+
+impl IndexMut<NamespaceId> for Vec<NamespaceData> {
+    fn index_mut(&mut self, index: NamespaceId) -> &mut NamespaceData {
+        &mut self[index.0 as usize]
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Private,  // only visible in files of the same namespace
+    Pub,      // can be imported from every file
+    Internal, // implemented by the VM, only stdlib and boots files may import it
+}
+
+impl Visibility {
+    pub fn new(is_pub: bool, internal: bool) -> Visibility {
+        if internal {
+            Visibility::Internal
+        } else if is_pub {
+            Visibility::Pub
+        } else {
+            Visibility::Private
+        }
+    }
+}
+
+// A namespace declared with `package a::b;`, all files with the same package
+// declaration share its table. Declarations of the root namespace (the stdlib
+// and files without a package) are stored in the outermost level of `vm.sym`.
+#[derive(Debug)]
+pub struct NamespaceData {
+    pub id: NamespaceId,
+    pub path: Vec<Name>,
+    pub table: SymLevel,
+    pub visibility: HashMap<Name, Visibility>,
+}
+
+impl NamespaceData {
+    pub fn visibility(&self, name: Name) -> Visibility {
+        self.visibility
+            .get(&name)
+            .cloned()
+            .unwrap_or(Visibility::Private)
+    }
+
+    pub fn name(&self, interner: &Interner) -> String {
+        namespace_name(interner, &self.path)
+    }
+}
+
+pub fn namespace_name(interner: &Interner, path: &[Name]) -> String {
+    path.iter()
+        .map(|&name| interner.str(name).to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
@@ -10,7 +10,8 @@ use crate::compiler::fct::JitFctId;
 use crate::ty::{BuiltinType, TypeList};
 use crate::vm::module::ModuleId;
 use crate::vm::{
-    ClassId, ConstId, EnumId, FctId, FieldId, GlobalId, Intrinsic, StructId, TraitId, TypeParamId,
+    ClassId, ConstId, EnumId, FctId, FieldId, GlobalId, Intrinsic, StructFieldId, StructId,
+    TraitId, TypeParamId,
 };
 
 #[derive(Debug)]
@@ -22,11 +23,15 @@ pub struct FctSrc {
     pub map_convs: NodeMap<ConvInfo>,
     pub map_cls: NodeMap<ClassId>,
     pub map_fors: NodeMap<ForTypeInfo>,
+    pub map_lambdas: NodeMap<FctId>, // maps lambda expression to its Fct
+    pub map_smart_casts: NodeMap<BuiltinType>, // non-nil identifiers and their narrowed type
 
     pub always_returns: bool, // true if function is always exited via return statement
     // false if function execution could reach the closing } of this function
     pub specializations: RwLock<HashMap<(TypeList, TypeList), JitFctId>>,
-    pub vars: Vec<Var>, // variables in functions
+    pub vars: Vec<Var>,            // variables in functions
+    pub captures: Vec<Capture>,    // variables captured by lambdas
+    pub captured_vars: Vec<VarId>, // variables of this function captured by a lambda
 }
 
 impl Clone for FctSrc {
@@ -39,8 +44,12 @@ impl Clone for FctSrc {
             map_convs: self.map_convs.clone(),
             map_cls: self.map_cls.clone(),
             map_fors: self.map_fors.clone(),
+            map_lambdas: self.map_lambdas.clone(),
+            map_smart_casts: self.map_smart_casts.clone(),
 
             vars: self.vars.clone(),
+            captures: self.captures.clone(),
+            captured_vars: self.captured_vars.clone(),
             always_returns: self.always_returns,
             specializations: RwLock::new(HashMap::new()),
         }
@@ -57,8 +66,12 @@ impl FctSrc {
             map_convs: NodeMap::new(),
             map_cls: NodeMap::new(),
             map_fors: NodeMap::new(),
+            map_lambdas: NodeMap::new(),
+            map_smart_casts: NodeMap::new(),
 
             vars: Vec::new(),
+            captures: Vec::new(),
+            captured_vars: Vec::new(),
             always_returns: false,
             specializations: RwLock::new(HashMap::new()),
         }
@@ -79,6 +92,13 @@ impl FctSrc {
     pub fn var_self_mut(&mut self) -> &mut Var {
         &mut self.vars[0]
     }
+
+    // captured variables are copied into the closure object, neither the
+    // lambda nor the enclosing function may therefore reassign them
+    pub fn is_captured(&self, var_id: VarId) -> bool {
+        self.captures.iter().any(|capture| capture.inner == var_id)
+            || self.captured_vars.contains(&var_id)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -149,6 +169,9 @@ pub enum IdentType {
     /// name of structure
     Struct(StructId),
 
+    /// field expression on struct value: <expr>.<field_name>
+    StructField(BuiltinType, StructFieldId),
+
     // name of constant
     Const(ConstId),
 
@@ -241,6 +264,7 @@ impl IdentType {
 #[derive(Debug, Clone)]
 pub struct ForTypeInfo {
     pub make_iterator: FctId,
+    pub make_iterator_type: BuiltinType,
     pub next: FctId,
     pub has_next: FctId,
     pub iterator_type: BuiltinType,
@@ -262,11 +286,14 @@ pub enum CallType {
     Expr(BuiltinType, FctId),
 
     // Invoke method on trait object
-    Trait(TraitId, FctId),
+    Trait(TraitId, FctId, TypeList),
 
     // Invoke static trait method on type param, e.g. T::method()
     TraitStatic(TypeParamId, TraitId, FctId),
 
+    // Invoke lambda, e.g. <expr>(<args>) where <expr> has lambda type
+    Lambda(BuiltinType),
+
     // Used for *internal* functions (those are not exposed to Dora as Fct)
     Intrinsic(Intrinsic),
 }
@@ -300,6 +327,13 @@ impl CallType {
         }
     }
 
+    pub fn is_lambda(&self) -> bool {
+        match *self {
+            CallType::Lambda(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn to_intrinsic(&self) -> Option<Intrinsic> {
         match *self {
             CallType::Intrinsic(intrinsic) => Some(intrinsic),
@@ -314,8 +348,9 @@ impl CallType {
             CallType::CtorNew(_, fctid) => Some(fctid),
             CallType::Ctor(_, fctid) => Some(fctid),
             CallType::Expr(_, fctid) => Some(fctid),
-            CallType::Trait(_, fctid) => Some(fctid),
+            CallType::Trait(_, fctid, _) => Some(fctid),
             CallType::TraitStatic(_, _, fctid) => Some(fctid),
+            CallType::Lambda(_) => None,
             CallType::Intrinsic(_) => None,
         }
     }
@@ -333,6 +368,15 @@ pub struct Var {
     pub node_id: ast::NodeId,
 }
 
+// A variable of an enclosing function that is copied into the closure
+// object when the lambda is created.
+#[derive(Clone, Debug)]
+pub struct Capture {
+    pub name: Name,
+    pub inner: VarId, // variable in the lambda
+    pub outer: VarId, // variable in the enclosing function
+}
+
 impl Index<VarId> for Vec<Var> {
     type Output = Var;
 
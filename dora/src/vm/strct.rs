@@ -7,7 +7,7 @@ use dora_parser::lexer::position::Position;
 
 use crate::ty::BuiltinType;
 use crate::utils::GrowableVec;
-use crate::vm::{FileId, TypeList};
+use crate::vm::{FileId, TupleId, TypeList, VM};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StructId(u32);
@@ -37,6 +37,12 @@ pub struct StructData {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct StructFieldId(u32);
 
+impl StructFieldId {
+    pub fn idx(self) -> usize {
+        self.0 as usize
+    }
+}
+
 impl From<u32> for StructFieldId {
     fn from(data: u32) -> StructFieldId {
         StructFieldId(data)
@@ -51,6 +57,28 @@ pub struct StructFieldData {
     pub ty: BuiltinType,
 }
 
+pub fn find_field_in_struct(
+    vm: &VM,
+    struct_id: StructId,
+    name: Name,
+) -> Option<(StructFieldId, BuiltinType)> {
+    let struc = vm.structs.idx(struct_id);
+    let struc = struc.lock();
+
+    struc
+        .fields
+        .iter()
+        .find(|field| field.name == name)
+        .map(|field| (field.id, field.ty))
+}
+
+pub fn field_names_in_struct(vm: &VM, struct_id: StructId) -> Vec<Name> {
+    let struc = vm.structs.idx(struct_id);
+    let struc = struc.lock();
+
+    struc.fields.iter().map(|field| field.name).collect()
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StructDefId(usize);
 
@@ -67,6 +95,8 @@ impl GrowableVec<Mutex<StructDef>> {
 }
 
 pub struct StructDef {
+    // the layout is shared with the tuple of all field types
+    pub tuple_id: TupleId,
     pub fields: Vec<StructFieldDef>,
     pub size: i32,
     pub align: i32,
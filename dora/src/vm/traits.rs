@@ -4,12 +4,19 @@ use std::ops::Index;
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
 
-use crate::ty::BuiltinType;
-use crate::vm::{FctId, FileId, VM};
+use crate::semck::specialize::replace_trait_type_params;
+use crate::ty::{BuiltinType, TypeList};
+use crate::vm::{FctId, FileId, TypeParam, VM};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TraitId(u32);
 
+impl TraitId {
+    pub fn to_usize(self) -> usize {
+        self.0 as usize
+    }
+}
+
 impl From<u32> for TraitId {
     fn from(data: u32) -> TraitId {
         TraitId(data)
@@ -22,10 +29,19 @@ pub struct TraitData {
     pub file: FileId,
     pub pos: Position,
     pub name: Name,
+    pub type_params: Vec<TypeParam>,
     pub methods: Vec<FctId>,
 }
 
 impl TraitData {
+    // position of the method in the trait's itable method table
+    pub fn method_index(&self, fct_id: FctId) -> usize {
+        self.methods
+            .iter()
+            .position(|&method| method == fct_id)
+            .expect("method not in trait")
+    }
+
     pub fn find_method(&self, vm: &VM, name: Name, is_static: bool) -> Option<FctId> {
         for &method in &self.methods {
             let method = vm.fcts.idx(method);
@@ -45,6 +61,7 @@ impl TraitData {
         is_static: bool,
         name: Name,
         replace: Option<BuiltinType>,
+        trait_type_params: &TypeList,
         args: &[BuiltinType],
     ) -> Option<FctId> {
         for &method in &self.methods {
@@ -53,7 +70,13 @@ impl TraitData {
 
             if method.name == name
                 && method.is_static == is_static
-                && params_match(replace, method.params_without_self(), args)
+                && params_match(
+                    vm,
+                    replace,
+                    trait_type_params,
+                    method.params_without_self(),
+                    args,
+                )
             {
                 return Some(method.id);
             }
@@ -64,7 +87,9 @@ impl TraitData {
 }
 
 fn params_match(
+    vm: &VM,
     replace: Option<BuiltinType>,
+    trait_type_params: &TypeList,
     trait_args: &[BuiltinType],
     args: &[BuiltinType],
 ) -> bool {
@@ -77,6 +102,8 @@ fn params_match(
 
         let found = if ty == BuiltinType::This {
             replace.is_none() || replace.unwrap() == other
+        } else if trait_type_params.len() > 0 {
+            replace_trait_type_params(vm, ty, trait_type_params) == other
         } else {
             ty == other
         };
@@ -31,12 +31,13 @@ pub struct ConcreteTuple {
 
 pub struct Tuple {
     args: Arc<Vec<BuiltinType>>,
+    is_concrete: bool,
     concrete: Option<ConcreteTuple>,
 }
 
 impl Tuple {
     pub fn is_concrete_type(&self) -> bool {
-        self.concrete.is_some()
+        self.is_concrete
     }
 
     pub fn is_defined_type(&self, vm: &VM) -> bool {
@@ -77,6 +78,10 @@ impl Tuple {
 pub struct Tuples {
     all: Vec<Tuple>,
     map: HashMap<Arc<Vec<BuiltinType>>, TupleId>,
+
+    // the size of a struct is only known after the fields of all structs were
+    // read, until then tuples are created without determining their layout
+    defer_layout: bool,
 }
 
 impl Tuples {
@@ -84,6 +89,7 @@ impl Tuples {
         Tuples {
             all: Vec::new(),
             map: HashMap::new(),
+            defer_layout: true,
         }
     }
 
@@ -107,11 +113,20 @@ impl Tuples {
 pub fn ensure_tuple(vm: &VM, args: Vec<BuiltinType>) -> TupleId {
     let args = Arc::new(args);
 
-    if let Some(&tuple_id) = vm.tuples.lock().map.get(&args) {
+    let tuple_id = vm.tuples.lock().map.get(&args).cloned();
+
+    if let Some(tuple_id) = tuple_id {
+        ensure_tuple_layout(vm, tuple_id);
         return tuple_id;
     }
 
-    let concrete = determine_tuple_size(vm, &*args);
+    let is_concrete = args.iter().all(|ty| ty.is_concrete_type(vm));
+
+    let concrete = if is_concrete && !vm.tuples.lock().defer_layout {
+        Some(determine_tuple_size(vm, &*args))
+    } else {
+        None
+    };
 
     let mut tuples = vm.tuples.lock();
 
@@ -121,6 +136,7 @@ pub fn ensure_tuple(vm: &VM, args: Vec<BuiltinType>) -> TupleId {
 
     tuples.all.push(Tuple {
         args: args.clone(),
+        is_concrete,
         concrete,
     });
 
@@ -130,15 +146,51 @@ pub fn ensure_tuple(vm: &VM, args: Vec<BuiltinType>) -> TupleId {
     id
 }
 
-fn determine_tuple_size<'ast>(vm: &VM, subtypes: &[BuiltinType]) -> Option<ConcreteTuple> {
+// determines the layout of all tuples created so far, called once all struct
+// fields are known
+pub fn layout_deferred_tuples(vm: &VM) {
+    let len = {
+        let mut tuples = vm.tuples.lock();
+        tuples.defer_layout = false;
+        tuples.all.len()
+    };
+
+    for idx in 0..len {
+        ensure_tuple_layout(vm, TupleId(idx.try_into().unwrap()));
+    }
+}
+
+fn ensure_tuple_layout(vm: &VM, id: TupleId) {
+    let args = {
+        let tuples = vm.tuples.lock();
+        let tuple = tuples.get_tuple(id);
+
+        if tuples.defer_layout || !tuple.is_concrete || tuple.concrete.is_some() {
+            return;
+        }
+
+        tuple.args.clone()
+    };
+
+    let concrete = determine_tuple_size(vm, &*args);
+
+    let mut tuples = vm.tuples.lock();
+    let tuple = &mut tuples.all[id.to_usize()];
+
+    if tuple.concrete.is_none() {
+        tuple.concrete = Some(concrete);
+    }
+}
+
+fn determine_tuple_size<'ast>(vm: &VM, subtypes: &[BuiltinType]) -> ConcreteTuple {
     let mut size = 0;
     let mut offsets = Vec::new();
     let mut references = Vec::new();
     let mut align = 0;
 
     for ty in subtypes {
-        if !ty.is_concrete_type(vm) {
-            return None;
+        if let BuiltinType::Tuple(tuple_id) = *ty {
+            ensure_tuple_layout(vm, tuple_id);
         }
 
         let element_size = ty.size(vm);
@@ -147,8 +199,14 @@ fn determine_tuple_size<'ast>(vm: &VM, subtypes: &[BuiltinType]) -> Option<Concr
         let element_offset = mem::align_i32(size, element_align);
         offsets.push(element_offset);
 
-        if ty.reference_type() {
+        if ty.reference_type(vm) {
             references.push(element_offset);
+        } else if let Some(tuple_id) = ty.layout_tuple_id(vm) {
+            let tuples = vm.tuples.lock();
+
+            for &offset in tuples.get_tuple(tuple_id).references() {
+                references.push(element_offset + offset);
+            }
         }
 
         size = element_offset + element_size;
@@ -156,10 +214,10 @@ fn determine_tuple_size<'ast>(vm: &VM, subtypes: &[BuiltinType]) -> Option<Concr
     }
 
     size = mem::align_i32(size, align);
-    Some(ConcreteTuple {
+    ConcreteTuple {
         offsets,
         references,
         size,
         align,
-    })
+    }
 }
@@ -46,6 +46,12 @@ pub struct KnownElements {
 #[derive(Debug)]
 pub struct KnownClasses {
     pub string_buffer: ClassId,
+    pub exception: ClassId,
+    pub division_by_zero_exception: ClassId,
+    pub assertion_exception: ClassId,
+    pub index_out_of_bounds_exception: ClassId,
+    pub nil_pointer_exception: ClassId,
+    pub cast_exception: ClassId,
 }
 
 #[derive(Debug)]
@@ -28,6 +28,8 @@ impl VTableBox {
             subtype_depth: 0,
             subtype_display: [ptr::null(); DISPLAY_SIZE],
             subtype_overflow: ptr::null(),
+            itable: ptr::null_mut(),
+            itable_length: 0,
             table_length: entries.len(),
             table: [0],
         };
@@ -92,6 +94,8 @@ pub struct VTable {
     pub subtype_depth: usize,
     pub subtype_display: [*const VTable; DISPLAY_SIZE],
     pub subtype_overflow: *const *const VTable,
+    pub itable: *mut usize,
+    pub itable_length: usize,
     pub table_length: usize,
     pub table: [usize; 1],
 }
@@ -145,6 +149,10 @@ impl VTable {
         offset_of!(VTable, subtype_overflow) as i32
     }
 
+    pub fn offset_of_itable() -> i32 {
+        offset_of!(VTable, itable) as i32
+    }
+
     pub fn get_subtype_overflow(&self, ind: usize) -> *const VTable {
         assert!(
             self.subtype_depth as usize >= DISPLAY_SIZE
@@ -188,6 +196,66 @@ impl VTable {
         }
     }
 
+    // The itable starts with one entry per trait: the address of the method
+    // table for this trait or null if the class doesn't implement it. The
+    // method tables follow in the same allocation.
+    pub fn allocate_itable(&mut self, method_tables: &[Option<Vec<usize>>]) {
+        assert!(self.itable.is_null() && !method_tables.is_empty());
+
+        let length = method_tables.len()
+            + method_tables
+                .iter()
+                .map(|table| table.as_ref().map_or(0, |table| table.len()))
+                .sum::<usize>();
+
+        let mut heap: Global = Default::default();
+        let lay =
+            Layout::from_size_align(length * size_of::<usize>(), align_of::<usize>()).unwrap();
+
+        unsafe {
+            let itable = heap.alloc(lay).expect("could not allocate").as_ptr() as *mut usize;
+            let mut offset = method_tables.len();
+
+            for (trait_idx, table) in method_tables.iter().enumerate() {
+                if let Some(table) = table {
+                    let start = itable.add(offset);
+                    ptr::copy(table.as_ptr(), start, table.len());
+                    *itable.add(trait_idx) = start as usize;
+                    offset += table.len();
+                } else {
+                    *itable.add(trait_idx) = 0;
+                }
+            }
+
+            self.itable = itable;
+            self.itable_length = length;
+        }
+    }
+
+    pub fn set_itable_entry(&self, trait_idx: usize, method_idx: usize, value: usize) {
+        assert!(!self.itable.is_null());
+
+        unsafe {
+            let table = *self.itable.add(trait_idx) as *mut usize;
+            assert!(!table.is_null());
+
+            *table.add(method_idx) = value;
+        }
+    }
+
+    pub fn deallocate_itable(&mut self) {
+        assert!(!self.itable.is_null());
+        let mut heap: Global = Default::default();
+        let lay =
+            Layout::from_size_align(self.itable_length * size_of::<usize>(), align_of::<usize>())
+                .unwrap();
+
+        unsafe {
+            let ptr = NonNull::new_unchecked(self.itable as *mut u8);
+            heap.dealloc(ptr, lay);
+        }
+    }
+
     pub fn is_array_ref(&self) -> bool {
         let cls = self.class();
 
@@ -204,5 +272,9 @@ impl Drop for VTable {
             let elems = self.subtype_depth as usize - DISPLAY_SIZE + 1;
             self.deallocate_overflow(elems);
         }
+
+        if !self.itable.is_null() {
+            self.deallocate_itable();
+        }
     }
 }
@@ -333,6 +333,7 @@ end
 def read_error_message(content)
   position = nil
   message = nil
+  in_error = false
 
   content.each_line do |line|
     line = line.strip
@@ -340,6 +341,14 @@ def read_error_message(content)
     if line == "1 error found." || line == "error during parsing."
       return position, message
 
+    elsif (m = line.match(/^error\[E\d+\]: (.+)$/)) != nil
+      message = m[1].to_s
+      in_error = true
+
+    elsif in_error && (m = line.match(/^--> .+:(\d+:\d+)$/)) != nil
+      position = m[1].to_s
+      in_error = false
+
     elsif (m = line.match(/^error in (.+) at (\d+:\d+): (.+)$/)) != nil
       position = m[2].to_s
       message = m[3].to_s
@@ -439,6 +448,8 @@ def parse_test_file(file)
         when "cast" then test_case.expectation.code = 105
         when "oom" then test_case.expectation.code = 106
         when "stack-overflow" then test_case.expectation.code = 107
+        when "exception" then test_case.expectation.code = 108
+        when "capability" then test_case.expectation.code = 109
         when "fail"
           # do nothing
         else